@@ -26,6 +26,41 @@ fn setup_cubes(app: &mut App, size: u32) {
     });
 }
 
+/// Spawns `piles * piles` piles of touching cubes, each `pile_size` cubes wide and `layers` cubes high.
+///
+/// A single large pile forms one big island whose contacts are solved in batches,
+/// while many small piles form small islands whose contacts are solved one by one.
+fn setup_piles(app: &mut App, piles: u32, pile_size: u32, layers: u32) {
+    app.add_systems(Startup, move |mut commands: Commands| {
+        commands.spawn((
+            RigidBody::Static,
+            Position(-2.0 * Vector::Z),
+            Collider::cuboid(100.0, 1.0, 100.0),
+        ));
+
+        let pile_spacing = (pile_size + 2) as Scalar;
+        for pile_x in 0..piles {
+            for pile_z in 0..piles {
+                for x in 0..pile_size {
+                    for y in 0..layers {
+                        for z in 0..pile_size {
+                            commands.spawn((
+                                RigidBody::Dynamic,
+                                Position(Vector::new(
+                                    pile_x as Scalar * pile_spacing + x as Scalar,
+                                    y as Scalar + 1.0,
+                                    pile_z as Scalar * pile_spacing + z as Scalar,
+                                )),
+                                Collider::cuboid(1.0, 1.0, 1.0),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("cubes 3x3, 30 steps", |b| {
         bench_app(b, 30, |app| setup_cubes(app, 3))
@@ -38,6 +73,16 @@ fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("cubes 10x10, 30 steps", |b| {
         bench_app(b, 30, |app| setup_cubes(app, 10))
     });
+
+    // The same number of bodies in one large island and in many small islands,
+    // for comparing the batched contact solver against the scalar one
+    c.bench_function("pile 12x12x2, 30 steps", |b| {
+        bench_app(b, 30, |app| setup_piles(app, 1, 12, 2))
+    });
+
+    c.bench_function("piles 6x6 of 2x2x2, 30 steps", |b| {
+        bench_app(b, 30, |app| setup_piles(app, 6, 2, 2))
+    });
 }
 
 criterion_group!(
@@ -42,9 +42,9 @@ fn pause_button(
     }
 }
 
-fn step_button(mut physics_loop: ResMut<PhysicsLoop>, keys: Res<Input<KeyCode>>) {
+fn step_button(mut physics_time: ResMut<PhysicsTime>, keys: Res<Input<KeyCode>>) {
     if keys.just_pressed(KeyCode::Return) {
-        physics_loop.step();
+        physics_time.step();
     }
 }
 
@@ -0,0 +1,197 @@
+use bevy::prelude::*;
+
+use crate::prelude::*;
+
+/// A torque-based attitude controller that rotates a [rigid body](RigidBody) towards a target orientation,
+/// like the reaction wheels of a spacecraft or the flight controller of a drone.
+///
+/// The controller is a PD controller that is solved every substep. The proportional term accelerates the body
+/// towards the [`target_rotation`](#structfield.target_rotation) and the derivative term slows down its angular velocity.
+/// The gains are scaled by the inertia of the body, so the same gains work for bodies of different sizes,
+/// but the resulting torque is limited to the [`max_torque`](#structfield.max_torque).
+///
+/// Real reaction wheels store the angular momentum that they transfer from the body. If a
+/// [`max_momentum`](#structfield.max_momentum) is set, the stored momentum is tracked, and once the wheels
+/// are saturated, they can no longer apply torque that would spin them up further.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     commands.spawn((
+///         RigidBody::Dynamic,
+///         Collider::ball(0.5),
+///         AttitudeControl::new(5.0).with_gains(20.0, 8.0).with_max_momentum(10.0),
+///     ));
+/// }
+///
+/// // Point the spacecraft to the right
+/// # #[cfg(feature = "2d")]
+/// # fn point_right(mut controllers: Query<&mut AttitudeControl>) {
+/// #     for mut controller in &mut controllers {
+/// #         controller.target_rotation = Rotation::from_degrees(-90.0);
+/// #     }
+/// # }
+/// # #[cfg(feature = "3d")]
+/// fn point_right(mut controllers: Query<&mut AttitudeControl>) {
+///     for mut controller in &mut controllers {
+///         controller.target_rotation = Rotation::from(Quat::from_rotation_y(-std::f32::consts::FRAC_PI_2));
+///     }
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Component, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct AttitudeControl {
+    /// The orientation that the controller rotates the body towards.
+    pub target_rotation: Rotation,
+    /// The maximum torque that the controller can apply.
+    pub max_torque: Scalar,
+    /// The proportional gain, which determines how strongly the body is accelerated towards the target orientation.
+    pub proportional_gain: Scalar,
+    /// The derivative gain, which determines how strongly the angular velocity of the body is damped.
+    pub derivative_gain: Scalar,
+    /// The maximum angular momentum that the reaction wheels can store. `None` means that the wheels never saturate.
+    pub max_momentum: Option<Scalar>,
+    /// The angular momentum currently stored by the reaction wheels.
+    pub(crate) momentum: Torque,
+}
+
+impl Default for AttitudeControl {
+    fn default() -> Self {
+        Self::new(0.0)
+    }
+}
+
+impl AttitudeControl {
+    /// Creates a new attitude controller with the given maximum torque.
+    ///
+    /// The target orientation is the identity rotation by default.
+    pub fn new(max_torque: Scalar) -> Self {
+        Self {
+            target_rotation: Rotation::default(),
+            max_torque,
+            proportional_gain: 10.0,
+            derivative_gain: 5.0,
+            max_momentum: None,
+            #[cfg(feature = "2d")]
+            momentum: 0.0,
+            #[cfg(feature = "3d")]
+            momentum: Vector::ZERO,
+        }
+    }
+
+    /// Sets the orientation that the controller rotates the body towards.
+    pub fn with_target_rotation(self, target_rotation: Rotation) -> Self {
+        Self {
+            target_rotation,
+            ..self
+        }
+    }
+
+    /// Sets the proportional and derivative gains of the controller.
+    pub fn with_gains(self, proportional_gain: Scalar, derivative_gain: Scalar) -> Self {
+        Self {
+            proportional_gain,
+            derivative_gain,
+            ..self
+        }
+    }
+
+    /// Sets the maximum angular momentum that the reaction wheels can store.
+    pub fn with_max_momentum(self, max_momentum: Scalar) -> Self {
+        Self {
+            max_momentum: Some(max_momentum),
+            ..self
+        }
+    }
+
+    /// Returns the angular momentum currently stored by the reaction wheels.
+    pub fn momentum(&self) -> Torque {
+        self.momentum
+    }
+
+    /// Returns true if the reaction wheels store the maximum angular momentum.
+    pub fn is_saturated(&self) -> bool {
+        #[cfg(feature = "2d")]
+        let momentum = self.momentum.abs();
+        #[cfg(feature = "3d")]
+        let momentum = self.momentum.length();
+        self.max_momentum
+            .is_some_and(|max_momentum| momentum >= max_momentum - Scalar::EPSILON)
+    }
+
+    /// Clears the angular momentum stored by the reaction wheels, for example when it is dumped using thrusters.
+    pub fn desaturate(&mut self) {
+        #[cfg(feature = "2d")]
+        {
+            self.momentum = 0.0;
+        }
+        #[cfg(feature = "3d")]
+        {
+            self.momentum = Vector::ZERO;
+        }
+    }
+
+    /// Returns the rotation from the given orientation to the target orientation as a rotation vector.
+    #[cfg(feature = "2d")]
+    pub fn rotation_error(&self, rotation: &Rotation) -> Scalar {
+        self.target_rotation.mul(rotation.inverse()).as_radians()
+    }
+
+    /// Returns the rotation from the given orientation to the target orientation as a rotation vector
+    /// whose direction is the axis of rotation and whose length is the angle in radians.
+    #[cfg(feature = "3d")]
+    pub fn rotation_error(&self, rotation: &Rotation) -> Vector {
+        let mut error = self.target_rotation.0 * rotation.0.inverse();
+        // Take the shortest path
+        if error.w < 0.0 {
+            error = -error;
+        }
+        let (axis, angle) = error.to_axis_angle();
+        axis * angle
+    }
+
+    /// Computes the torque applied by the controller for a body with the given orientation,
+    /// angular velocity and world-space inertia over the given time step, and stores the
+    /// transferred angular momentum in the reaction wheels.
+    pub(crate) fn compute_torque(
+        &mut self,
+        rotation: &Rotation,
+        angular_velocity: Torque,
+        #[cfg(feature = "2d")] inertia: Scalar,
+        #[cfg(feature = "3d")] inertia: Matrix3,
+        delta_seconds: Scalar,
+    ) -> Torque {
+        let angular_acceleration = self.proportional_gain * self.rotation_error(rotation)
+            - self.derivative_gain * angular_velocity;
+
+        #[cfg(feature = "2d")]
+        let mut torque = (inertia * angular_acceleration).clamp(-self.max_torque, self.max_torque);
+        #[cfg(feature = "3d")]
+        let mut torque = (inertia * angular_acceleration).clamp_length_max(self.max_torque);
+
+        // The wheels spin up in the opposite direction of the body and can only store a limited momentum
+        if let Some(max_momentum) = self.max_momentum {
+            if delta_seconds > Scalar::EPSILON {
+                let momentum = self.momentum - torque * delta_seconds;
+                #[cfg(feature = "2d")]
+                let momentum = momentum.clamp(-max_momentum, max_momentum);
+                #[cfg(feature = "3d")]
+                let momentum = momentum.clamp_length_max(max_momentum);
+                torque = (self.momentum - momentum) / delta_seconds;
+                self.momentum = momentum;
+            }
+        } else {
+            self.momentum -= torque * delta_seconds;
+        }
+
+        torque
+    }
+}
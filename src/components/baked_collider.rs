@@ -0,0 +1,211 @@
+//! Colliders that are baked into binary data offline, for example by build pipelines and asset processors,
+//! and loaded at runtime without computing their shapes or bounding volume hierarchies again.
+//!
+//! See [`Collider::bake`] and [`BakedCollider`].
+
+use crate::prelude::*;
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::BoxedFuture,
+};
+use parry::shape::SharedShape;
+
+/// The bytes at the start of baked collider data.
+const MAGIC: &[u8; 8] = b"XPBDCOL\0";
+/// The version of the baked data format, increased when the format changes.
+const VERSION: u8 = 2;
+
+/// The header identifying baked data that is compatible with the current dimension and precision.
+fn header() -> [u8; 11] {
+    let mut header = [0; 11];
+    header[..8].copy_from_slice(MAGIC);
+    header[8] = VERSION;
+    header[9] = if cfg!(feature = "2d") { 2 } else { 3 };
+    header[10] = std::mem::size_of::<Scalar>() as u8;
+    header
+}
+
+impl Collider {
+    /// Serializes the shape of the collider into binary data that can be loaded with [`Collider::from_baked`]
+    /// or as a [`BakedCollider`] asset.
+    ///
+    /// Computing convex decompositions and building the bounding volume hierarchies of triangle meshes
+    /// can take seconds for large levels. Baking the colliders when exporting the level, for example in
+    /// a build script, an editor tool or an asset processor, skips that work at runtime.
+    /// The shape is baked without the [scale](Collider::scale) of the collider.
+    ///
+    /// The baked data can only be loaded by a build with the same dimension and precision.
+    ///
+    /// Requires the `baked-colliders` feature.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "2d")]
+    /// # use bevy_xpbd_2d::{math::*, prelude::*};
+    /// # #[cfg(feature = "3d")]
+    /// use bevy_xpbd_3d::{math::*, prelude::*};
+    ///
+    /// fn export_level(vertices: Vec<Vector>, indices: Vec<[u32; 3]>) -> std::io::Result<()> {
+    ///     let collider = Collider::trimesh_with_flags(vertices, indices, TriMeshFlags::MERGE_DUPLICATE_VERTICES);
+    ///     std::fs::write("assets/level.collider", collider.bake())
+    /// }
+    /// ```
+    pub fn bake(&self) -> Vec<u8> {
+        let mut bytes = header().to_vec();
+        bincode::serialize_into(&mut bytes, self.get_unscaled_shape())
+            .expect("serializing a collider shape into memory shouldn't fail");
+        bytes
+    }
+
+    /// Builds a triangle mesh and bakes it together with its bounding volume hierarchy.
+    /// See [`Collider::bake`].
+    ///
+    /// Requires the `baked-colliders` feature.
+    pub fn bake_trimesh(
+        vertices: Vec<Vector>,
+        indices: Vec<[u32; 3]>,
+        flags: TriMeshFlags,
+    ) -> Vec<u8> {
+        Self::trimesh_with_flags(vertices, indices, flags).bake()
+    }
+
+    /// Computes a collider from a Bevy `Mesh` like an [`AsyncSceneCollider`] would, and bakes it.
+    /// See [`Collider::bake`].
+    ///
+    /// Returns `None` if the mesh doesn't have vertex positions and indices, or if the collider can't be computed.
+    ///
+    /// Requires the `baked-colliders` and `collider-from-mesh` features.
+    #[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
+    pub fn bake_bevy_mesh(mesh: &Mesh, shape: ComputedCollider) -> Option<Vec<u8>> {
+        let collider = match shape {
+            ComputedCollider::TriMesh => Self::trimesh_from_bevy_mesh(mesh),
+            ComputedCollider::ConvexHull => Self::convex_hull_from_bevy_mesh(mesh),
+            ComputedCollider::ConvexDecomposition => {
+                Self::convex_decomposition_from_bevy_mesh(mesh)
+            }
+        };
+        collider.map(|collider| collider.bake())
+    }
+
+    /// Creates a collider from data baked with [`Collider::bake`], without computing its shape
+    /// or bounding volume hierarchies again.
+    ///
+    /// Returns an error if the data is invalid, or if it was baked by a build with a different
+    /// dimension or precision.
+    ///
+    /// Requires the `baked-colliders` feature.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// use bevy::prelude::*;
+    /// # #[cfg(feature = "2d")]
+    /// # use bevy_xpbd_2d::prelude::*;
+    /// # #[cfg(feature = "3d")]
+    /// use bevy_xpbd_3d::prelude::*;
+    ///
+    /// fn setup(mut commands: Commands) {
+    ///     let baked = std::fs::read("assets/level.collider").unwrap();
+    ///     let collider = Collider::from_baked(&baked).unwrap();
+    ///     commands.spawn((RigidBody::Static, collider));
+    /// }
+    /// ```
+    pub fn from_baked(bytes: &[u8]) -> Result<Self, BakedColliderError> {
+        let header = header();
+        if bytes.len() < header.len() || &bytes[..MAGIC.len()] != MAGIC {
+            return Err(BakedColliderError::InvalidHeader);
+        }
+        if bytes[..header.len()] != header {
+            return Err(BakedColliderError::Incompatible);
+        }
+
+        let shape: SharedShape = bincode::deserialize(&bytes[header.len()..])?;
+        Ok(shape.into())
+    }
+}
+
+/// An asset containing a [`Collider`] loaded from data baked with [`Collider::bake`].
+///
+/// Files with the `.collider` extension are loaded as baked colliders by the asset server.
+/// Adding a `Handle<BakedCollider>` to an entity without a [`Collider`] inserts the collider
+/// once the asset has been loaded.
+///
+/// Requires the `baked-colliders` feature and Bevy's `AssetPlugin`.
+///
+/// ## Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn setup(mut commands: Commands, assets: Res<AssetServer>) {
+///     commands.spawn((
+///         RigidBody::Static,
+///         assets.load::<BakedCollider, _>("level.collider"),
+///     ));
+/// }
+/// ```
+#[derive(TypeUuid, TypePath, Clone, Debug)]
+#[uuid = "6f3c1d0a-54a7-4bd5-9b8e-2f0e7d0c9a41"]
+pub struct BakedCollider(pub Collider);
+
+/// Loads [`BakedCollider`] assets from `.collider` files.
+#[derive(Default)]
+pub(crate) struct BakedColliderLoader;
+
+impl AssetLoader for BakedColliderLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let collider = Collider::from_baked(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(BakedCollider(collider)));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["collider"]
+    }
+}
+
+/// An error that can occur when loading a collider baked with [`Collider::bake`].
+#[derive(Debug)]
+pub enum BakedColliderError {
+    /// The data doesn't start with the header of baked collider data.
+    InvalidHeader,
+    /// The data was baked by a different version of the format, or by a build with a different
+    /// dimension or precision.
+    Incompatible,
+    /// The collider shape could not be deserialized.
+    Bincode(bincode::Error),
+}
+
+impl std::fmt::Display for BakedColliderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidHeader => write!(f, "not baked collider data"),
+            Self::Incompatible => write!(
+                f,
+                "baked collider data is from an incompatible version, dimension or precision"
+            ),
+            Self::Bincode(err) => write!(f, "invalid baked collider data: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for BakedColliderError {}
+
+impl From<bincode::Error> for BakedColliderError {
+    fn from(value: bincode::Error) -> Self {
+        Self::Bincode(value)
+    }
+}
@@ -0,0 +1,321 @@
+use bevy::prelude::*;
+
+use crate::prelude::*;
+
+/// A component that makes a [kinematic](crate::prelude::RigidBody::Kinematic) body follow a joint (bone)
+/// of a `SkinnedMesh`.
+///
+/// Before each physics frame, the [`Position`](crate::prelude::Position) and [`Rotation`](crate::prelude::Rotation)
+/// of the body are set to the `GlobalTransform` of the joint, so the [colliders](crate::prelude::Collider)
+/// of the body match the animated pose of the character. This allows accurate hit detection on animated
+/// characters without simulating full ragdolls. Use a [`ColliderOffset`](crate::prelude::ColliderOffset)
+/// to position the collider relative to the joint.
+///
+/// The body should not be a child of the joint, because the pose is read from the joint instead of
+/// being propagated through the hierarchy. The pose is computed from the `Transform` of the joint
+/// and the `GlobalTransform` of its parent from the latest transform propagation.
+///
+/// Requires the `bone-collider` feature.
+///
+/// ## Partial ragdolls
+///
+/// The [mode](BoneColliderMode) of each bone collider can be switched separately, so only some limbs
+/// can be taken over by physics while the rest of the character stays animated:
+///
+/// - In [`BoneColliderMode::Animated`], the body is [kinematic](crate::prelude::RigidBody::Kinematic)
+/// and follows the joint.
+/// - In [`BoneColliderMode::Simulated`], the body is [dynamic](crate::prelude::RigidBody::Dynamic),
+/// and the `Transform` of the joint follows the body instead.
+///
+/// When a body is taken over by physics, it starts with the velocity of the animated joint. When it is given
+/// back to animation, it keeps moving with its physics velocity while blending to the animated pose over
+/// the [blend duration](#structfield.blend_duration). The [`RigidBody`](crate::prelude::RigidBody) is switched
+/// automatically, so joints connecting the limbs stay intact across the change.
+///
+/// The animated pose is read from the `Transform` of the joint, so it must be written each frame,
+/// typically by an `AnimationPlayer`, while the joint follows a simulated body.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::{prelude::*, render::mesh::skinning::SkinnedMesh};
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn add_hitboxes(mut commands: Commands, skinned_meshes: Query<Entity, Added<SkinnedMesh>>) {
+///     for skinned_mesh in &skinned_meshes {
+///         // Follow the first joint, like the hips of the character
+///         commands.spawn((
+///             RigidBody::Kinematic,
+///             Collider::capsule(0.5, 0.2),
+///             BoneCollider::new(skinned_mesh, 0),
+///         ));
+///     }
+/// }
+///
+/// fn go_limp(mut bone_colliders: Query<&mut BoneCollider>) {
+///     for mut bone_collider in &mut bone_colliders {
+///         bone_collider.mode = BoneColliderMode::Simulated;
+///     }
+/// }
+/// ```
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct BoneCollider {
+    /// The entity with the `SkinnedMesh`.
+    pub skinned_mesh: Entity,
+    /// The index of the followed joint in the `joints` of the `SkinnedMesh`.
+    pub joint: usize,
+    /// Whether the body follows the animation or is simulated by physics.
+    pub mode: BoneColliderMode,
+    /// The time in seconds that it takes to blend from the simulated pose back to the animated pose.
+    pub blend_duration: Scalar,
+    /// The pose of the joint during the previous frame, used for computing the velocity of the animation.
+    pub(crate) previous_joint_pose: Option<(Vector, Quaternion)>,
+    /// The linear and angular velocity of the animated joint.
+    pub(crate) joint_velocity: (Vector, Vector),
+    /// The active blend from the simulated pose back to the animated pose.
+    pub(crate) blend: Option<BoneColliderBlend>,
+    /// The local transform of the joint with the offset of the [`HitReaction`] blended into it,
+    /// and the animated transform without the offset.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) joint_offset: Option<(Transform, Transform)>,
+}
+
+impl Default for BoneCollider {
+    /// Creates a [`BoneCollider`] with a placeholder `skinned_mesh` entity that should be replaced before it is used.
+    fn default() -> Self {
+        Self::new(Entity::PLACEHOLDER, 0)
+    }
+}
+
+impl BoneCollider {
+    /// Creates a new [`BoneCollider`] that follows the joint at the given index of the given `SkinnedMesh`.
+    pub fn new(skinned_mesh: Entity, joint: usize) -> Self {
+        Self {
+            skinned_mesh,
+            joint,
+            mode: BoneColliderMode::Animated,
+            blend_duration: 0.2,
+            previous_joint_pose: None,
+            joint_velocity: (Vector::ZERO, Vector::ZERO),
+            blend: None,
+            joint_offset: None,
+        }
+    }
+
+    /// Sets the [mode](BoneColliderMode) of the bone collider.
+    pub fn with_mode(self, mode: BoneColliderMode) -> Self {
+        Self { mode, ..self }
+    }
+
+    /// Sets the time in seconds that it takes to blend from the simulated pose back to the animated pose.
+    pub fn with_blend_duration(self, blend_duration: Scalar) -> Self {
+        Self {
+            blend_duration,
+            ..self
+        }
+    }
+
+    /// Returns the linear velocity of the animated joint.
+    pub fn joint_linear_velocity(&self) -> Vector {
+        self.joint_velocity.0
+    }
+
+    /// Returns the angular velocity of the animated joint.
+    pub fn joint_angular_velocity(&self) -> Vector {
+        self.joint_velocity.1
+    }
+
+    /// Returns true if the body is blending from the simulated pose back to the animated pose.
+    pub fn is_blending(&self) -> bool {
+        self.blend.is_some()
+    }
+}
+
+/// Determines whether a [`BoneCollider`] follows the animation or is simulated by physics.
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BoneColliderMode {
+    /// The body is [kinematic](crate::prelude::RigidBody::Kinematic) and follows the animated joint.
+    #[default]
+    Animated,
+    /// The body is [dynamic](crate::prelude::RigidBody::Dynamic), and the joint follows the body.
+    Simulated,
+}
+
+/// A blend of a [`BoneCollider`] from the simulated pose back to the animated pose.
+#[derive(Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct BoneColliderBlend {
+    /// The position of the body when it was given back to animation.
+    pub(crate) position: Vector,
+    /// The rotation of the body when it was given back to animation.
+    pub(crate) rotation: Quaternion,
+    /// The linear velocity of the body when it was given back to animation.
+    pub(crate) linear_velocity: Vector,
+    /// The angular velocity of the body when it was given back to animation.
+    pub(crate) angular_velocity: Vector,
+    /// The time in seconds since the blend started.
+    pub(crate) elapsed: Scalar,
+}
+
+impl BoneColliderBlend {
+    /// Returns the blended pose and advances the blend. Returns `None` once the blend is finished.
+    pub(crate) fn advance(
+        &mut self,
+        target_position: Vector,
+        target_rotation: Quaternion,
+        duration: Scalar,
+        delta_seconds: Scalar,
+    ) -> Option<(Vector, Quaternion)> {
+        self.elapsed += delta_seconds;
+        if duration <= 0.0 || self.elapsed >= duration {
+            return None;
+        }
+
+        // The simulated pose keeps moving with the velocity that the body had at the handoff
+        let position = self.position + self.linear_velocity * self.elapsed;
+        let rotation = (Quaternion::from_scaled_axis(self.angular_velocity * self.elapsed)
+            * self.rotation)
+            .normalize();
+
+        // Smoothstep for a smooth start and end
+        let t = self.elapsed / duration;
+        let t = t * t * (3.0 - 2.0 * t);
+        Some((
+            position.lerp(target_position, t),
+            rotation.slerp(target_rotation, t),
+        ))
+    }
+}
+
+/// A component for [bone colliders](BoneCollider) that turns impacts into a decaying procedural offset
+/// from the pose of the joint, for hit reactions without switching to a full ragdoll.
+///
+/// Impulses applied with [`apply_impulse`](Self::apply_impulse) or [`apply_impulse_at_point`](Self::apply_impulse_at_point)
+/// push the body away from the joint. The offset is pulled back to zero by a damped spring
+/// with the given [stiffness](#structfield.stiffness) and [damping](#structfield.damping).
+///
+/// The offset is added to the pose of the bone collider, and if [`blend_into_joint`](#structfield.blend_into_joint)
+/// is true, also to the `Transform` of the joint so that the skinned mesh reacts to the hit.
+/// This expects the `Transform` of the joint to be written each frame, typically by an `AnimationPlayer`,
+/// because the offset is added on top of the animated pose.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn shoot(
+///     mut hitboxes: Query<(&Position, &mut HitReaction)>,
+///     spatial_query: SpatialQuery,
+/// ) {
+///     let direction = Vec3::X;
+///     if let Some(hit) = spatial_query.cast_ray(Vec3::ZERO, direction, 100.0, true, SpatialQueryFilter::default()) {
+///         if let Ok((position, mut reaction)) = hitboxes.get_mut(hit.entity) {
+///             let point = direction * hit.time_of_impact;
+///             reaction.apply_impulse_at_point(direction * 2.0, point, position.0);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct HitReaction {
+    /// The current offset of the position of the body from the joint in world space.
+    pub offset: Vector,
+    /// The current offset of the rotation of the body from the joint as a scaled axis in world space.
+    pub angular_offset: Vector,
+    /// The rate of change of the [offset](#structfield.offset).
+    pub velocity: Vector,
+    /// The rate of change of the [angular offset](#structfield.angular_offset).
+    pub angular_velocity: Vector,
+    /// The mass that impulses are divided by.
+    pub mass: Scalar,
+    /// The moment of inertia that angular impulses are divided by.
+    pub inertia: Scalar,
+    /// The stiffness of the spring pulling the offset back to zero, independent of the mass.
+    pub stiffness: Scalar,
+    /// The damping of the spring pulling the offset back to zero, independent of the mass.
+    /// The spring is critically damped when this is `2.0 * stiffness.sqrt()`.
+    pub damping: Scalar,
+    /// If true, the offset is also added to the `Transform` of the joint.
+    pub blend_into_joint: bool,
+}
+
+impl Default for HitReaction {
+    fn default() -> Self {
+        Self {
+            offset: Vector::ZERO,
+            angular_offset: Vector::ZERO,
+            velocity: Vector::ZERO,
+            angular_velocity: Vector::ZERO,
+            mass: 1.0,
+            inertia: 0.1,
+            stiffness: 200.0,
+            damping: 25.0,
+            blend_into_joint: true,
+        }
+    }
+}
+
+impl HitReaction {
+    /// Creates a new [`HitReaction`] with the given mass and moment of inertia.
+    pub fn new(mass: Scalar, inertia: Scalar) -> Self {
+        Self {
+            mass,
+            inertia,
+            ..default()
+        }
+    }
+
+    /// Sets the stiffness of the spring pulling the offset back to zero.
+    pub fn with_stiffness(self, stiffness: Scalar) -> Self {
+        Self { stiffness, ..self }
+    }
+
+    /// Sets the damping of the spring pulling the offset back to zero.
+    pub fn with_damping(self, damping: Scalar) -> Self {
+        Self { damping, ..self }
+    }
+
+    /// Sets whether the offset is also added to the `Transform` of the joint.
+    pub fn with_blend_into_joint(self, blend_into_joint: bool) -> Self {
+        Self {
+            blend_into_joint,
+            ..self
+        }
+    }
+
+    /// Applies a linear impulse to the offset.
+    pub fn apply_impulse(&mut self, impulse: Vector) {
+        self.velocity += impulse / self.mass;
+    }
+
+    /// Applies an impulse at the given point in world space. `center` is the current
+    /// [`Position`](crate::prelude::Position) of the body.
+    pub fn apply_impulse_at_point(&mut self, impulse: Vector, point: Vector, center: Vector) {
+        self.apply_impulse(impulse);
+        self.angular_velocity += (point - center).cross(impulse) / self.inertia;
+    }
+
+    /// Returns the rotation corresponding to the [angular offset](#structfield.angular_offset).
+    pub fn rotation_offset(&self) -> Quaternion {
+        Quaternion::from_scaled_axis(self.angular_offset)
+    }
+
+    /// Advances the spring by the given time step.
+    pub fn advance(&mut self, delta_seconds: Scalar) {
+        let acceleration = -self.stiffness * self.offset - self.damping * self.velocity;
+        let angular_acceleration =
+            -self.stiffness * self.angular_offset - self.damping * self.angular_velocity;
+
+        self.velocity += acceleration * delta_seconds;
+        self.angular_velocity += angular_acceleration * delta_seconds;
+        self.offset += self.velocity * delta_seconds;
+        self.angular_offset += self.angular_velocity * delta_seconds;
+    }
+}
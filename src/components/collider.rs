@@ -1,18 +1,204 @@
-use std::fmt;
+use std::{
+    fmt,
+    ops::RangeInclusive,
+    sync::{Arc, Mutex},
+};
 
 use crate::prelude::*;
+#[cfg(all(feature = "3d", feature = "async-collider"))]
+use bevy::utils::HashMap;
+use bevy::{prelude::*, tasks::AsyncComputeTaskPool, utils::HashSet};
 #[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
-use bevy::render::mesh::{Indices, VertexAttributeValues};
-use bevy::{prelude::*, utils::HashSet};
-use derive_more::From;
+use bevy::{
+    render::mesh::{Indices, VertexAttributeValues},
+    tasks::Task,
+};
+#[cfg(feature = "3d")]
+use parry::shape::HeightFieldCellStatus;
 use parry::{
-    bounding_volume::Aabb,
-    shape::{SharedShape, TypedShape},
+    bounding_volume::{Aabb, BoundingVolume},
+    either::Either,
+    shape::{HeightField, SharedShape, TypedShape},
 };
 
 /// Flags used for the preprocessing of a triangle mesh collider.
 pub type TriMeshFlags = parry::shape::TriMeshFlags;
 
+/// Flags that control which sides of the triangles of a [triangle mesh](Collider::trimesh) collider
+/// generate contacts and are hit by ray casts. Set them using [`Collider::with_trimesh_collision_flags`].
+///
+/// The front face of a triangle is the side from which its vertices appear in counterclockwise order.
+/// Note that a negative scale along an odd number of axes flips the winding, and with it the front faces.
+///
+/// By default, both sides of the triangles are solid. Single-sided meshes like level geometry that only
+/// looks closed can trap bodies that end up behind a triangle, because contacts push them further
+/// inside. Making the mesh one-sided lets bodies pass through the back faces instead.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// # #[cfg(all(feature = "3d", feature = "f32"))]
+/// fn setup(mut commands: Commands) {
+///     let floor = Collider::trimesh(
+///         vec![Vec3::new(-10.0, 0.0, -10.0), Vec3::new(-10.0, 0.0, 10.0), Vec3::new(10.0, 0.0, 0.0)],
+///         vec![[0, 1, 2]],
+///     )
+///     .with_trimesh_collision_flags(TriMeshCollisionFlags {
+///         one_sided: true,
+///         cull_backfaces: true,
+///         ..default()
+///     });
+///
+///     commands.spawn((RigidBody::Static, floor));
+/// }
+/// ```
+#[cfg(feature = "3d")]
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TriMeshCollisionFlags {
+    /// If true, contacts are only generated against the front faces of the triangles.
+    pub one_sided: bool,
+    /// If true, ray casts and [spatial queries](crate::plugins::spatial_query) using rays ignore hits
+    /// against the back faces of the triangles.
+    pub cull_backfaces: bool,
+    /// If true, contact normals against internal edges and vertices are replaced by the face normal of the triangle.
+    ///
+    /// Internal edges are edges shared by two triangles that are concave or nearly flat. Bodies sliding across
+    /// a flat triangle mesh can otherwise catch on them and get kicked upward, which are known as ghost collisions.
+    /// The triangles must have a consistent winding for the edges to be detected correctly.
+    ///
+    /// Unlike the other flags, this also applies to [heightfield](Collider::heightfield) colliders,
+    /// whose triangles are always wound with their front faces pointing up.
+    pub fix_internal_edges: bool,
+}
+
+/// Flags that control which sides of the segments of a [segment](Collider::segment) or [polyline](Collider::polyline)
+/// collider generate contacts. Set them using [`Collider::with_polyline_collision_flags`].
+///
+/// The front side of a segment is on the right when looking from its first endpoint towards its second one,
+/// so a floor goes from right to left, and a polyline boundary that keeps bodies inside lists its vertices
+/// in clockwise order. Note that a negative scale along one axis flips the front sides.
+///
+/// By default, both sides of the segments are solid, and bodies that end up behind a segment are pushed
+/// further out of the front side. Making the collider one-sided lets bodies pass through the back sides
+/// instead, for example for level boundaries and thin walls that bodies can tunnel into.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// use bevy_xpbd_2d::prelude::*;
+///
+/// # #[cfg(all(feature = "2d", feature = "f32"))]
+/// fn setup(mut commands: Commands) {
+///     // A floor whose front side points up
+///     let floor = Collider::segment(Vec2::new(10.0, 0.0), Vec2::new(-10.0, 0.0))
+///         .with_polyline_collision_flags(PolylineCollisionFlags { one_sided: true });
+///
+///     commands.spawn((RigidBody::Static, floor));
+/// }
+/// ```
+#[cfg(feature = "2d")]
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PolylineCollisionFlags {
+    /// If true, contacts are only generated against the front sides of the segments.
+    pub one_sided: bool,
+}
+
+/// The maximum angle between the normals of two triangles sharing a convex edge
+/// for the edge to be considered an internal edge.
+#[cfg(feature = "3d")]
+const INTERNAL_EDGE_MAX_ANGLE: Scalar = 0.1;
+
+/// For each triangle of a triangle mesh or heightfield, a bit mask of its edges that are internal edges.
+/// See [`TriMeshCollisionFlags::fix_internal_edges`].
+#[cfg(feature = "3d")]
+#[derive(Clone, Debug, Default)]
+pub(crate) struct InternalEdges(Vec<u8>);
+
+#[cfg(feature = "3d")]
+impl InternalEdges {
+    /// Computes the internal edges of the triangles of a triangle mesh or heightfield.
+    ///
+    /// Returns `None` if the shape has no triangles.
+    fn from_shape(shape: &SharedShape) -> Option<Self> {
+        if let Some(trimesh) = shape.as_trimesh() {
+            let indices = trimesh.indices();
+            Some(Self::from_triangles(indices.len(), |id| {
+                Some((indices[id as usize], trimesh.triangle(id)))
+            }))
+        } else {
+            let heightfield = shape.as_heightfield()?;
+            // Each cell of a heightfield is split into two triangles, some of which can be removed
+            Some(Self::from_triangles(
+                heightfield.nrows() * heightfield.ncols() * 2,
+                |id| {
+                    Some((
+                        heightfield.triangle_vids_at_id(id)?,
+                        heightfield.triangle_at_id(id)?,
+                    ))
+                },
+            ))
+        }
+    }
+
+    /// Computes the internal edges of `count` triangles. `triangle` returns the vertex indices
+    /// and the shape of the triangle with the given index, or `None` if it doesn't exist.
+    fn from_triangles(
+        count: usize,
+        triangle: impl Fn(u32) -> Option<([u32; 3], parry::shape::Triangle)>,
+    ) -> Self {
+        let mut masks = vec![0; count];
+        let mut unmatched_edges = bevy::utils::HashMap::<(u32, u32), (u32, u8)>::default();
+
+        for triangle_index in 0..count as u32 {
+            let Some((vertices, shape)) = triangle(triangle_index) else {
+                continue;
+            };
+            for edge_index in 0..3 {
+                let (a, b) = (vertices[edge_index], vertices[(edge_index + 1) % 3]);
+                let key = (a.min(b), a.max(b));
+
+                if let Some((other_index, other_edge_index)) = unmatched_edges.remove(&key) {
+                    let Some((_, other_shape)) = triangle(other_index) else {
+                        continue;
+                    };
+                    if is_internal_edge(&shape, &other_shape) {
+                        masks[triangle_index as usize] |= 1 << edge_index;
+                        masks[other_index as usize] |= 1 << other_edge_index;
+                    }
+                } else {
+                    unmatched_edges.insert(key, (triangle_index, edge_index as u8));
+                }
+            }
+        }
+
+        Self(masks)
+    }
+}
+
+/// Returns true if the edge shared by the given triangles is concave or nearly flat.
+#[cfg(feature = "3d")]
+fn is_internal_edge(
+    triangle1: &parry::shape::Triangle,
+    triangle2: &parry::shape::Triangle,
+) -> bool {
+    let (Some(normal1), Some(normal2)) = (triangle1.normal(), triangle2.normal()) else {
+        return false;
+    };
+    let center = |triangle: &parry::shape::Triangle| {
+        (triangle.a.coords + triangle.b.coords + triangle.c.coords) / 3.0
+    };
+    let is_concave = (center(triangle2) - center(triangle1)).dot(&normal1) > 0.0;
+    is_concave || normal1.dot(&normal2) >= math::ops::cos(INTERNAL_EDGE_MAX_ANGLE)
+}
+
 /// A collider used for collision detection.
 ///
 /// By default, colliders generate [collision events](#collision-events) and cause a collision response for
@@ -41,6 +227,39 @@ pub type TriMeshFlags = parry::shape::TriMeshFlags;
 /// - [`CollidingEntities`]
 /// - [`ColliderMassProperties`]
 ///
+/// ## Multiple colliders
+///
+/// A [rigid body](RigidBody) can have any number of colliders attached to it by adding colliders
+/// to its child entities. The colliders will move with the body, contribute to its mass properties
+/// and cause a collision response for it. Colliders can also be nested deeper in the hierarchy;
+/// they are attached to the closest ancestor that has a [`RigidBody`].
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     // Spawn a dynamic body with two ball colliders
+///     commands
+///         .spawn((RigidBody::Dynamic, Collider::ball(0.5)))
+///         .with_children(|children| {
+///             children.spawn((
+///                 Collider::ball(0.5),
+///                 TransformBundle::from_transform(Transform::from_xyz(2.0, 0.0, 0.0)),
+///             ));
+///         });
+/// }
+/// ```
+///
+/// To offset a collider from its entity without the entity hierarchy, you can use [`ColliderOffset`].
+///
+/// Each collider gets a [`ColliderParent`] component that stores the rigid body it is attached to.
+/// [Collision events](#collision-events) are sent for the collider entities that are colliding,
+/// and the [`Contacts`] in [`Collision`] events also contain the entities of the corresponding bodies.
+///
 /// ## Collision layers
 ///
 /// You can use collsion layers to configure which entities can collide with each other.
@@ -78,18 +297,85 @@ pub type TriMeshFlags = parry::shape::TriMeshFlags;
 /// using these shapes, you can simply use `Collider::from(SharedShape::some_method())`.
 ///
 /// To get a reference to the internal [`SharedShape`], you can use the [`get_shape`](#method.get_shape) method.
-#[derive(Clone, Component, Deref, DerefMut, From)]
-pub struct Collider(SharedShape);
+/// To replace it, use [`set_shape`](#method.set_shape).
+///
+/// ## Scale
+///
+/// The shape of a collider is scaled by the scale of the entity's `GlobalTransform`, so scaling an entity
+/// or one of its ancestors also scales its collider. The scaled shape is cached and only recomputed
+/// when the scale changes.
+///
+/// Balls, capsules, cylinders and cones can't be scaled non-uniformly, so they are approximated
+/// by convex hulls in that case. Custom and rounded shapes are not scaled.
+#[derive(Reflect, Clone, Component)]
+#[reflect_value(Component, Debug)]
+pub struct Collider {
+    /// The shape of the collider without scale.
+    shape: SharedShape,
+    /// The shape of the collider with `scale` applied.
+    scaled_shape: SharedShape,
+    /// The scale applied to the shape.
+    scale: Vector,
+    /// Controls which sides of the triangles of a triangle mesh collide and are hit by rays.
+    #[cfg(feature = "3d")]
+    trimesh_collision_flags: TriMeshCollisionFlags,
+    /// The internal edges of a triangle mesh, computed if [`TriMeshCollisionFlags::fix_internal_edges`] is true.
+    #[cfg(feature = "3d")]
+    internal_edges: Option<std::sync::Arc<InternalEdges>>,
+    /// Controls which sides of the segments of a segment or polyline collide.
+    #[cfg(feature = "2d")]
+    polyline_collision_flags: PolylineCollisionFlags,
+    /// The region of a heightfield or replaced shape in the local space of the unscaled shape that has been edited
+    /// since the sleeping bodies near it were last woken up.
+    shape_edits: Option<Aabb>,
+    /// True if the unscaled shape has been borrowed mutably, so the scaled shape needs to be recomputed.
+    unscaled_shape_borrowed: bool,
+}
+
+impl From<SharedShape> for Collider {
+    fn from(value: SharedShape) -> Self {
+        Self {
+            shape: value.clone(),
+            scaled_shape: value,
+            scale: Vector::ONE,
+            #[cfg(feature = "3d")]
+            trimesh_collision_flags: TriMeshCollisionFlags::default(),
+            #[cfg(feature = "3d")]
+            internal_edges: None,
+            #[cfg(feature = "2d")]
+            polyline_collision_flags: PolylineCollisionFlags::default(),
+            shape_edits: None,
+            unscaled_shape_borrowed: false,
+        }
+    }
+}
+
+impl std::ops::Deref for Collider {
+    type Target = SharedShape;
+
+    fn deref(&self) -> &Self::Target {
+        &self.scaled_shape
+    }
+}
+
+/// Mutably dereferences to the unscaled shape like the deprecated [`Collider::get_shape_mut`].
+/// Prefer [`Collider::set_shape`], which keeps the scaled shape up to date immediately.
+impl std::ops::DerefMut for Collider {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.unscaled_shape_borrowed = true;
+        &mut self.shape
+    }
+}
 
 impl Default for Collider {
     fn default() -> Self {
         #[cfg(feature = "2d")]
         {
-            Self(SharedShape::cuboid(0.5, 0.5))
+            Self::from(SharedShape::cuboid(0.5, 0.5))
         }
         #[cfg(feature = "3d")]
         {
-            Self(SharedShape::cuboid(0.5, 0.5, 0.5))
+            Self::from(SharedShape::cuboid(0.5, 0.5, 0.5))
         }
     }
 }
@@ -130,15 +416,220 @@ impl fmt::Debug for Collider {
     }
 }
 
+/// The serialized representation of a [`Collider`].
+///
+/// Only the unscaled shape is stored, because the scale is applied again from the `GlobalTransform`
+/// of the entity. Custom shapes can't be serialized.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SerializedCollider {
+    shape: SharedShape,
+    #[cfg(feature = "3d")]
+    #[serde(default)]
+    trimesh_collision_flags: TriMeshCollisionFlags,
+    #[cfg(feature = "2d")]
+    #[serde(default)]
+    polyline_collision_flags: PolylineCollisionFlags,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Collider {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedCollider {
+            shape: self.shape.clone(),
+            #[cfg(feature = "3d")]
+            trimesh_collision_flags: self.trimesh_collision_flags,
+            #[cfg(feature = "2d")]
+            polyline_collision_flags: self.polyline_collision_flags,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Collider {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let serialized = SerializedCollider::deserialize(deserializer)?;
+        let collider = Collider::from(serialized.shape);
+        #[cfg(feature = "3d")]
+        let collider = collider.with_trimesh_collision_flags(serialized.trimesh_collision_flags);
+        #[cfg(feature = "2d")]
+        let collider = collider.with_polyline_collision_flags(serialized.polyline_collision_flags);
+        Ok(collider)
+    }
+}
+
 impl Collider {
-    /// Returns the raw shape of the collider. The shapes are provided by [`parry`].
+    /// Returns the raw shape of the collider with its [scale](#method.scale) applied.
+    /// The shapes are provided by [`parry`].
     pub fn get_shape(&self) -> &SharedShape {
-        &self.0
+        &self.scaled_shape
+    }
+
+    /// Returns the raw shape of the collider without its [scale](#method.scale).
+    /// The shapes are provided by [`parry`].
+    pub fn get_unscaled_shape(&self) -> &SharedShape {
+        &self.shape
+    }
+
+    /// Sets the unscaled shape of the collider. The collider's current [scale](#method.scale)
+    /// is applied to the new shape.
+    pub fn set_shape(&mut self, shape: SharedShape) {
+        self.shape = shape;
+        self.scaled_shape = scale_shape(&self.shape, self.scale, DEFAULT_SCALE_SUBDIVISIONS)
+            .unwrap_or_else(|| self.shape.clone());
+        self.unscaled_shape_borrowed = false;
+        #[cfg(feature = "3d")]
+        self.update_internal_edges();
     }
 
-    /// Returns a mutable reference to the raw shape of the collider. The shapes are provided by [`parry`].
+    /// Returns a mutable reference to the unscaled shape of the collider. The shapes are provided by [`parry`].
+    ///
+    /// The scaled shape returned by [`get_shape`](#method.get_shape) is only updated
+    /// when the scale of the collider is updated in [`PhysicsSet::Prepare`].
+    #[deprecated(
+        since = "0.3.0",
+        note = "use `Collider::set_shape`, which keeps the scaled shape up to date"
+    )]
     pub fn get_shape_mut(&mut self) -> &mut SharedShape {
-        &mut self.0
+        self.unscaled_shape_borrowed = true;
+        &mut self.shape
+    }
+
+    /// Recomputes the scaled shape if the unscaled shape has been borrowed mutably
+    /// using [`get_shape_mut`](#method.get_shape_mut) or `DerefMut`.
+    pub(crate) fn update_borrowed_shape(&mut self) {
+        if self.unscaled_shape_borrowed {
+            self.set_shape(self.shape.clone());
+        }
+    }
+
+    /// Returns the scale of the collider. This is typically the scale of the entity's `GlobalTransform`.
+    pub fn scale(&self) -> Vector {
+        self.scale
+    }
+
+    /// Sets the scale of the collider and recomputes the scaled shape if the scale changed.
+    ///
+    /// `num_subdivisions` is used when a shape like a ball has to be approximated by a convex hull
+    /// because of non-uniform scaling. If the scaled shape can't be computed, for example because
+    /// of a zero scale, the previous scaled shape is kept.
+    pub fn set_scale(&mut self, scale: Vector, num_subdivisions: u32) {
+        if scale == self.scale {
+            return;
+        }
+
+        if let Some(scaled_shape) = scale_shape(&self.shape, scale, num_subdivisions) {
+            self.scaled_shape = scaled_shape;
+            self.scale = scale;
+        }
+    }
+
+    /// Returns the [`TriMeshCollisionFlags`] of the collider.
+    #[cfg(feature = "3d")]
+    pub fn trimesh_collision_flags(&self) -> TriMeshCollisionFlags {
+        self.trimesh_collision_flags
+    }
+
+    /// Sets the [`TriMeshCollisionFlags`] of the collider.
+    /// They only have an effect if the collider is a [triangle mesh](#method.trimesh), except for
+    /// [`fix_internal_edges`](TriMeshCollisionFlags::fix_internal_edges), which also applies to heightfields.
+    #[cfg(feature = "3d")]
+    pub fn set_trimesh_collision_flags(&mut self, flags: TriMeshCollisionFlags) {
+        self.trimesh_collision_flags = flags;
+        self.update_internal_edges();
+    }
+
+    /// Returns the collider with the given [`TriMeshCollisionFlags`].
+    /// They only have an effect if the collider is a [triangle mesh](#method.trimesh), except for
+    /// [`fix_internal_edges`](TriMeshCollisionFlags::fix_internal_edges), which also applies to heightfields.
+    #[cfg(feature = "3d")]
+    pub fn with_trimesh_collision_flags(mut self, flags: TriMeshCollisionFlags) -> Self {
+        self.set_trimesh_collision_flags(flags);
+        self
+    }
+
+    /// Returns the [`PolylineCollisionFlags`] of the collider.
+    #[cfg(feature = "2d")]
+    pub fn polyline_collision_flags(&self) -> PolylineCollisionFlags {
+        self.polyline_collision_flags
+    }
+
+    /// Sets the [`PolylineCollisionFlags`] of the collider.
+    /// They only have an effect if the collider is a [segment](#method.segment) or a [polyline](#method.polyline).
+    #[cfg(feature = "2d")]
+    pub fn set_polyline_collision_flags(&mut self, flags: PolylineCollisionFlags) {
+        self.polyline_collision_flags = flags;
+    }
+
+    /// Returns the collider with the given [`PolylineCollisionFlags`].
+    /// They only have an effect if the collider is a [segment](#method.segment) or a [polyline](#method.polyline).
+    #[cfg(feature = "2d")]
+    pub fn with_polyline_collision_flags(mut self, flags: PolylineCollisionFlags) -> Self {
+        self.set_polyline_collision_flags(flags);
+        self
+    }
+
+    /// Computes the internal edges of the triangle mesh or heightfield
+    /// if [`TriMeshCollisionFlags::fix_internal_edges`] is true.
+    #[cfg(feature = "3d")]
+    fn update_internal_edges(&mut self) {
+        self.internal_edges = if self.trimesh_collision_flags.fix_internal_edges {
+            InternalEdges::from_shape(&self.shape).map(std::sync::Arc::new)
+        } else {
+            None
+        };
+    }
+
+    /// Returns the face normal of the triangle at `triangle_index` if the contact normal should be replaced by it
+    /// because all of the given contact points are on [internal edges](TriMeshCollisionFlags::fix_internal_edges).
+    ///
+    /// The normal points out of the triangle mesh, and the normal and points are in the local space of the collider.
+    #[cfg(feature = "3d")]
+    pub(crate) fn internal_edge_face_normal(
+        &self,
+        triangle_index: u32,
+        normal: &parry::math::Vector<Scalar>,
+        mut points: impl Iterator<Item = parry::math::Point<Scalar>>,
+    ) -> Option<parry::math::Vector<Scalar>> {
+        let internal_edges = self.internal_edges.as_ref()?;
+        let edge_mask = *internal_edges.0.get(triangle_index as usize)?;
+        let shape = self.get_shape();
+        let triangle = match shape.as_trimesh() {
+            Some(trimesh) => trimesh.triangle(triangle_index),
+            None => shape.as_heightfield()?.triangle_at_id(triangle_index)?,
+        };
+        let face_normal = triangle.normal()?.into_inner();
+
+        // Face contacts and contacts against the back face are left as is
+        let alignment = face_normal.dot(normal);
+        if !(0.0..1.0 - 1.0e-4).contains(&alignment) {
+            return None;
+        }
+
+        let edges = [
+            (triangle.a, triangle.b),
+            (triangle.b, triangle.c),
+            (triangle.c, triangle.a),
+        ];
+        let all_on_internal_edges = points.all(|point| {
+            let mut on_edge = false;
+            for (i, &(a, b)) in edges.iter().enumerate() {
+                let edge = b - a;
+                let t = ((point - a).dot(&edge) / edge.norm_squared()).clamp(0.0, 1.0);
+                let distance_squared = (a + edge * t - point).norm_squared();
+                if distance_squared <= 1.0e-6 * edge.norm_squared() {
+                    // Contacts on vertices must be on two internal edges
+                    if edge_mask & (1 << i) == 0 {
+                        return false;
+                    }
+                    on_edge = true;
+                }
+            }
+            on_edge
+        });
+
+        all_on_internal_edges.then_some(face_normal)
     }
 
     /// Computes the [Axis-Aligned Bounding Box](ColliderAabb) of the collider.
@@ -202,13 +693,57 @@ impl Collider {
         SharedShape::cuboid(x_length * 0.5, y_length * 0.5, z_length * 0.5).into()
     }
 
+    /// Creates a collider with a cuboid shape defined by its extents and rounded corners.
+    ///
+    /// The corners are rounded by inflating the cuboid by `border_radius`, so the total size of the shape
+    /// is the extents plus `2.0 * border_radius` along each axis. Rounded shapes produce smoother contacts
+    /// when sliding over edges.
+    #[cfg(feature = "2d")]
+    pub fn round_cuboid(x_length: Scalar, y_length: Scalar, border_radius: Scalar) -> Self {
+        SharedShape::round_cuboid(x_length * 0.5, y_length * 0.5, border_radius).into()
+    }
+
+    /// Creates a collider with a cuboid shape defined by its extents and rounded corners and edges.
+    ///
+    /// The corners and edges are rounded by inflating the cuboid by `border_radius`, so the total size of
+    /// the shape is the extents plus `2.0 * border_radius` along each axis. Rounded shapes produce smoother
+    /// contacts when sliding over edges.
+    #[cfg(feature = "3d")]
+    pub fn round_cuboid(
+        x_length: Scalar,
+        y_length: Scalar,
+        z_length: Scalar,
+        border_radius: Scalar,
+    ) -> Self {
+        SharedShape::round_cuboid(
+            x_length * 0.5,
+            y_length * 0.5,
+            z_length * 0.5,
+            border_radius,
+        )
+        .into()
+    }
+
     /// Creates a collider with a cylinder shape defined by its height along the `Y` axis and its radius on the `XZ` plane.
     #[cfg(feature = "3d")]
     pub fn cylinder(height: Scalar, radius: Scalar) -> Self {
         SharedShape::cylinder(height * 0.5, radius).into()
     }
 
+    /// Creates a collider with a cylinder shape defined by its height along the `Y` axis and its radius on the `XZ` plane,
+    /// with edges rounded by `border_radius`.
+    ///
+    /// Like [`round_cuboid`](#method.round_cuboid), the cylinder is inflated by `border_radius`,
+    /// so the total height is `height + 2.0 * border_radius` and the total radius is `radius + border_radius`.
+    #[cfg(feature = "3d")]
+    pub fn round_cylinder(height: Scalar, radius: Scalar, border_radius: Scalar) -> Self {
+        SharedShape::round_cylinder(height * 0.5, radius, border_radius).into()
+    }
+
     /// Creates a collider with a cone shape defined by its height along the `Y` axis and the radius of its base on the `XZ` plane.
+    ///
+    /// The cone is centered at half of its height, with the apex pointing towards the positive `Y` axis.
+    /// Its center of mass is at a quarter of the height above the base, at `-height / 4.0` on the `Y` axis.
     #[cfg(feature = "3d")]
     pub fn cone(height: Scalar, radius: Scalar) -> Self {
         SharedShape::cone(height * 0.5, radius).into()
@@ -235,6 +770,10 @@ impl Collider {
     }
 
     /// Creates a collider with a segment shape defined by its endpoints `a` and `b`.
+    ///
+    /// Segments have no interior, so they are well suited for static thin walls and level boundaries,
+    /// especially in 2D. Contacts are two-sided by default: bodies are pushed out of a segment on the side
+    /// they approach it from. In 2D, segments can be made one-sided using `PolylineCollisionFlags`.
     pub fn segment(a: Vector, b: Vector) -> Self {
         SharedShape::segment(a.into(), b.into()).into()
     }
@@ -245,12 +784,23 @@ impl Collider {
     }
 
     /// Creates a collider with a polyline shape defined by its vertices and optionally an index buffer.
+    ///
+    /// If no indices are given, consecutive vertices are connected, so the first and last vertices
+    /// are only connected if the last vertex is equal to the first one.
+    ///
+    /// Like [segments](#method.segment), polylines have no interior and their contacts are two-sided by default.
+    /// In 2D, they can be made one-sided using `PolylineCollisionFlags`.
+    /// They are a good fit for static level boundaries and terrain built from connected segments,
+    /// because there are no gaps between the segments that bodies could slip through.
     pub fn polyline(vertices: Vec<Vector>, indices: Option<Vec<[u32; 2]>>) -> Self {
         let vertices = vertices.into_iter().map(|v| v.into()).collect();
         SharedShape::polyline(vertices, indices).into()
     }
 
     /// Creates a collider with a triangle mesh shape defined by its vertex and index buffers.
+    ///
+    /// In 3D, contacts are generated on both sides of the triangles by default. For single-sided meshes
+    /// like level geometry, [`TriMeshCollisionFlags`] can be used to only collide with the front faces.
     pub fn trimesh(vertices: Vec<Vector>, indices: Vec<[u32; 3]>) -> Self {
         let vertices = vertices.into_iter().map(|v| v.into()).collect();
         SharedShape::trimesh(vertices, indices).into()
@@ -286,6 +836,18 @@ impl Collider {
         vertices_indices.map(|(v, i)| SharedShape::trimesh_with_flags(v, i, flags).into())
     }
 
+    /// Creates a collider with a convex hull shape built from the vertex positions of a given Bevy `Mesh`.
+    ///
+    /// For dynamic bodies, this is usually faster and more robust than a [triangle mesh](#method.trimesh_from_bevy_mesh).
+    ///
+    /// Returns `None` if the mesh doesn't have vertex positions or if the convex hull can't be computed,
+    /// for example if there are too few vertices or they are all collinear.
+    #[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
+    pub fn convex_hull_from_bevy_mesh(mesh: &Mesh) -> Option<Self> {
+        let vertices = extract_mesh_vertices(mesh)?;
+        SharedShape::convex_hull(&vertices).map(Self::from)
+    }
+
     /// Creates a collider with a compound shape obtained from the decomposition of a triangle mesh
     /// built from a given Bevy `Mesh`.
     #[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
@@ -312,6 +874,10 @@ impl Collider {
 
     /// Creates a collider with a [convex polygon](https://en.wikipedia.org/wiki/Convex_polygon) shape obtained after computing
     /// the [convex hull](https://en.wikipedia.org/wiki/Convex_hull) of the given points.
+    ///
+    /// The hull is computed when the collider is created, and only its vertices are stored.
+    /// Returns `None` if the hull can't be computed, for example if there are too few points
+    /// or they are all collinear.
     #[cfg(feature = "2d")]
     pub fn convex_hull(points: Vec<Vector>) -> Option<Self> {
         let points = points.iter().map(|v| (*v).into()).collect::<Vec<_>>();
@@ -320,6 +886,10 @@ impl Collider {
 
     /// Creates a collider with a [convex polyhedron](https://en.wikipedia.org/wiki/Convex_polytope) shape obtained after computing
     /// the [convex hull](https://en.wikipedia.org/wiki/Convex_hull) of the given points.
+    ///
+    /// The hull is computed when the collider is created, and only its vertices are stored.
+    /// Returns `None` if the hull can't be computed, for example if there are too few points
+    /// or they are all collinear.
     #[cfg(feature = "3d")]
     pub fn convex_hull(points: Vec<Vector>) -> Option<Self> {
         let points = points.iter().map(|v| (*v).into()).collect::<Vec<_>>();
@@ -361,90 +931,914 @@ impl Collider {
         let heights = nalgebra::DMatrix::from_vec(row_count, column_count, data);
         SharedShape::heightfield(heights, scale.into()).into()
     }
-}
 
-#[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
-type VerticesIndices = (Vec<nalgebra::Point3<Scalar>>, Vec<[u32; 3]>);
+    /// Creates a collider with a heightfield shape built from the first row of pixels of a grayscale
+    /// heightmap `Image`.
+    ///
+    /// The height of each point is the brightness of the pixel between 0 and 1 multiplied by `height_scale`.
+    /// `scale` indicates the length of each subdivided segment along the `X` axis. Only every `sub_sampling`-th
+    /// pixel is used, which reduces the resolution of the heightfield.
+    ///
+    /// Returns `None` if the image format is not supported or if the heightfield would have less than two points.
+    /// The supported formats are `R8Unorm`, `Rgba8Unorm`, `Rgba8UnormSrgb`, `R16Unorm` and `R32Float`.
+    /// For multi-channel formats, the red channel is used.
+    #[cfg(all(feature = "2d", feature = "collider-from-image"))]
+    pub fn heightfield_from_image(
+        image: &Image,
+        scale: Scalar,
+        height_scale: Scalar,
+        sub_sampling: usize,
+    ) -> Option<Self> {
+        let sub_sampling = sub_sampling.max(1);
+        let width = image.texture_descriptor.size.width as usize;
+        let column_count = width.saturating_sub(1) / sub_sampling + 1;
 
-#[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
-fn extract_mesh_vertices_indices(mesh: &Mesh) -> Option<VerticesIndices> {
-    let vertices = mesh.attribute(Mesh::ATTRIBUTE_POSITION)?;
-    let indices = mesh.indices()?;
+        if column_count < 2 {
+            return None;
+        }
 
-    let vtx: Vec<_> = match vertices {
-        VertexAttributeValues::Float32(vtx) => Some(
-            vtx.chunks(3)
-                .map(|v| [v[0] as Scalar, v[1] as Scalar, v[2] as Scalar].into())
-                .collect(),
-        ),
-        VertexAttributeValues::Float32x3(vtx) => Some(
-            vtx.iter()
-                .map(|v| [v[0] as Scalar, v[1] as Scalar, v[2] as Scalar].into())
-                .collect(),
-        ),
-        _ => None,
-    }?;
+        let heights = (0..column_count)
+            .map(|x| Some(image_height(image, x * sub_sampling, 0)? * height_scale))
+            .collect::<Option<Vec<Scalar>>>()?;
 
-    let idx = match indices {
-        Indices::U16(idx) => idx
-            .chunks_exact(3)
-            .map(|i| [i[0] as u32, i[1] as u32, i[2] as u32])
-            .collect(),
-        Indices::U32(idx) => idx.chunks_exact(3).map(|i| [i[0], i[1], i[2]]).collect(),
-    };
+        Some(Self::heightfield(heights, scale))
+    }
 
-    Some((vtx, idx))
-}
+    /// Creates a collider with a heightfield shape built from a grayscale heightmap `Image`.
+    ///
+    /// Pixels along the width of the image correspond to points along the `X` axis, and pixels along
+    /// the height of the image correspond to points along the `Z` axis. The height of each point is
+    /// the brightness of the pixel between 0 and 1 multiplied by `scale.y`, and `scale.x` and `scale.z`
+    /// indicate the size of the heightfield on the `XZ` plane. Only every `sub_sampling`-th pixel
+    /// along each axis is used, which reduces the resolution of the heightfield.
+    ///
+    /// Returns `None` if the image format is not supported or if the heightfield would have less than
+    /// two points along an axis. The supported formats are `R8Unorm`, `Rgba8Unorm`, `Rgba8UnormSrgb`,
+    /// `R16Unorm` and `R32Float`. For multi-channel formats, the red channel is used.
+    #[cfg(all(feature = "3d", feature = "collider-from-image"))]
+    pub fn heightfield_from_image(
+        image: &Image,
+        scale: Vector,
+        sub_sampling: usize,
+    ) -> Option<Self> {
+        let sub_sampling = sub_sampling.max(1);
+        let width = image.texture_descriptor.size.width as usize;
+        let height = image.texture_descriptor.size.height as usize;
+        let column_count = width.saturating_sub(1) / sub_sampling + 1;
+        let row_count = height.saturating_sub(1) / sub_sampling + 1;
 
-/// A component that marks a [`Collider`] as a sensor, also known as a trigger.
-///
-/// Sensor colliders send [collision events](Collider#collision-events) and register intersections,
-/// but allow other bodies to pass through them. This is often used to detect when something enters
-/// or leaves an area or is intersecting some shape.
-///
-/// ## Example
-///
-/// ```
-/// use bevy::prelude::*;
-/// # #[cfg(feature = "2d")]
-/// # use bevy_xpbd_2d::prelude::*;
-/// # #[cfg(feature = "3d")]
-/// use bevy_xpbd_3d::prelude::*;
-///
-/// fn setup(mut commands: Commands) {
-///     // Spawn a static body with a sensor collider.
-///     // Other bodies will pass through, but it will still send collision events.
-///     commands.spawn((RigidBody::Static, Collider::ball(0.5), Sensor));
-/// }
-/// ```
-#[doc(alias = "Trigger")]
-#[derive(Reflect, Clone, Component, Debug, Default, PartialEq, Eq)]
-#[reflect(Component)]
-pub struct Sensor;
+        if column_count < 2 || row_count < 2 {
+            return None;
+        }
 
-/// The Axis-Aligned Bounding Box of a collider.
-#[derive(Clone, Copy, Component, Debug, Deref, DerefMut, PartialEq)]
-pub struct ColliderAabb(pub Aabb);
+        // Rows are along the Z axis and columns are along the X axis
+        let mut heights = nalgebra::DMatrix::zeros(row_count, column_count);
+        for row in 0..row_count {
+            for column in 0..column_count {
+                heights[(row, column)] =
+                    image_height(image, column * sub_sampling, row * sub_sampling)?;
+            }
+        }
 
-impl ColliderAabb {
-    /// Creates a new collider from a given [`SharedShape`] with a default density of 1.0.
-    pub fn from_shape(shape: &SharedShape) -> Self {
-        Self(shape.compute_local_aabb())
+        Some(SharedShape::heightfield(heights, scale.into()).into())
     }
-}
 
-impl Default for ColliderAabb {
-    fn default() -> Self {
-        ColliderAabb(Aabb::new_invalid())
+    /// Marks a segment of a heightfield collider as a hole that has no collision, or fills the hole
+    /// if `hole` is false. Segment `i` is between the points `i` and `i + 1`.
+    ///
+    /// Sleeping bodies near the segment are woken up on the next physics step.
+    /// Returns false if the collider isn't a heightfield or the segment doesn't exist.
+    #[cfg(feature = "2d")]
+    pub fn set_heightfield_hole(&mut self, segment: usize, hole: bool) -> bool {
+        let Some(heightfield) = self.shape.as_heightfield() else {
+            return false;
+        };
+        if segment + 1 >= heightfield.heights().len() {
+            return false;
+        }
+
+        let mut heightfield = heightfield.clone();
+        heightfield.set_segment_removed(segment, hole);
+
+        self.add_shape_edit(heightfield_points_aabb(&heightfield, segment..=segment + 1));
+        self.set_shape(SharedShape::new(heightfield));
+        true
     }
-}
 
-/// Contains the entities that are colliding with an entity.
-///
-/// This component is automatically added for all entities with a [`Collider`].
-///
-/// ## Example
-///
+    /// Marks a cell of a heightfield collider as a hole that has no collision, or fills the hole
+    /// if `hole` is false. Rows are along the `Z` axis and columns are along the `X` axis.
+    ///
+    /// Sleeping bodies near the cell are woken up on the next physics step.
+    /// Returns false if the collider isn't a heightfield or the cell doesn't exist.
+    #[cfg(feature = "3d")]
+    pub fn set_heightfield_hole(&mut self, row: usize, column: usize, hole: bool) -> bool {
+        let Some(heightfield) = self.shape.as_heightfield() else {
+            return false;
+        };
+        if row + 1 >= heightfield.nrows() || column + 1 >= heightfield.ncols() {
+            return false;
+        }
+
+        let mut heightfield = heightfield.clone();
+        let mut status = heightfield.cell_status(row, column);
+        status.set(HeightFieldCellStatus::CELL_REMOVED, hole);
+        heightfield.set_cell_status(row, column, status);
+
+        self.add_shape_edit(heightfield_points_aabb(
+            &heightfield,
+            row..=row + 1,
+            column..=column + 1,
+        ));
+        self.set_shape(SharedShape::new(heightfield));
+        true
+    }
+
+    /// Edits the heights of a heightfield collider at runtime, for things like craters and digging.
+    ///
+    /// `edit` is called with the index and a mutable reference to the height of each point.
+    /// Holes are kept, and sleeping bodies near the changed points are woken up on the next physics step,
+    /// while bodies elsewhere on the heightfield keep sleeping.
+    ///
+    /// Returns false if the collider isn't a heightfield.
+    #[cfg(feature = "2d")]
+    pub fn edit_heightfield_heights(&mut self, mut edit: impl FnMut(usize, &mut Scalar)) -> bool {
+        let Some(old) = self.shape.as_heightfield() else {
+            return false;
+        };
+
+        // The range of points that have changed
+        let mut changed: Option<(usize, usize)> = None;
+        let mut heights = old.heights().clone();
+        for (index, height) in heights.iter_mut().enumerate() {
+            let previous = *height;
+            edit(index, height);
+            if *height != previous {
+                changed = Some(changed.map_or((index, index), |(first, _)| (first, index)));
+            }
+        }
+        let Some((first, last)) = changed else {
+            return true;
+        };
+
+        let mut heightfield = HeightField::new(heights, *old.scale());
+        for segment in 0..heightfield.heights().len() - 1 {
+            heightfield.set_segment_removed(segment, old.is_segment_removed(segment));
+        }
+
+        // The segments next to the changed points move too
+        let points = first.saturating_sub(1)..=last + 1;
+        let region = heightfield_points_aabb(old, points.clone())
+            .merged(&heightfield_points_aabb(&heightfield, points));
+
+        self.add_shape_edit(region);
+        self.set_shape(SharedShape::new(heightfield));
+        true
+    }
+
+    /// Edits the heights of a heightfield collider at runtime, for things like craters and digging.
+    ///
+    /// `edit` is called with the row, the column and a mutable reference to the height of each point.
+    /// Rows are along the `Z` axis and columns are along the `X` axis. Holes are kept, and sleeping bodies
+    /// near the changed points are woken up on the next physics step, while bodies elsewhere
+    /// on the heightfield keep sleeping.
+    ///
+    /// Returns false if the collider isn't a heightfield.
+    #[cfg(feature = "3d")]
+    pub fn edit_heightfield_heights(
+        &mut self,
+        mut edit: impl FnMut(usize, usize, &mut Scalar),
+    ) -> bool {
+        let Some(old) = self.shape.as_heightfield() else {
+            return false;
+        };
+
+        // The ranges of rows and columns that have changed
+        let mut changed: Option<(usize, usize, usize, usize)> = None;
+        let mut heights = old.heights().clone();
+        for row in 0..heights.nrows() {
+            for column in 0..heights.ncols() {
+                let height = &mut heights[(row, column)];
+                let previous = *height;
+                edit(row, column, height);
+                if *height != previous {
+                    changed = Some(changed.map_or(
+                        (row, row, column, column),
+                        |(first_row, _, first_column, last_column)| {
+                            (
+                                first_row,
+                                row,
+                                first_column.min(column),
+                                last_column.max(column),
+                            )
+                        },
+                    ));
+                }
+            }
+        }
+        let Some((first_row, last_row, first_column, last_column)) = changed else {
+            return true;
+        };
+
+        let mut heightfield = HeightField::new(heights, *old.scale());
+        for row in 0..heightfield.nrows() - 1 {
+            for column in 0..heightfield.ncols() - 1 {
+                heightfield.set_cell_status(row, column, old.cell_status(row, column));
+            }
+        }
+
+        // The cells next to the changed points move too
+        let rows = first_row.saturating_sub(1)..=last_row + 1;
+        let columns = first_column.saturating_sub(1)..=last_column + 1;
+        let region = heightfield_points_aabb(old, rows.clone(), columns.clone())
+            .merged(&heightfield_points_aabb(&heightfield, rows, columns));
+
+        self.add_shape_edit(region);
+        self.set_shape(SharedShape::new(heightfield));
+        true
+    }
+
+    /// Replaces the unscaled shape of the collider like [`set_shape`](Self::set_shape), and marks the regions
+    /// covered by the old and new shapes as edited so that the sleeping bodies near them are woken up.
+    pub(crate) fn replace_shape(&mut self, shape: SharedShape) {
+        let region = self
+            .shape
+            .compute_local_aabb()
+            .merged(&shape.compute_local_aabb());
+        self.add_shape_edit(region);
+        self.set_shape(shape);
+    }
+
+    /// Adds a region in the local space of the unscaled shape to the edited region of the collider.
+    fn add_shape_edit(&mut self, region: Aabb) {
+        self.shape_edits = Some(
+            self.shape_edits
+                .map_or(region, |edits| edits.merged(&region)),
+        );
+    }
+
+    /// Returns the region of the shape that has been edited since the last call in the local space
+    /// of the collider, including its scale, and clears it.
+    pub(crate) fn take_shape_edits(&mut self) -> Option<Aabb> {
+        let scale = self.scale;
+        self.shape_edits
+            .take()
+            .map(|region| region.scaled(&scale.into()))
+    }
+}
+
+/// Computes the AABB of the given points of a heightfield in its local space.
+/// Points outside of the heightfield are ignored.
+#[cfg(feature = "2d")]
+fn heightfield_points_aabb(heightfield: &HeightField, points: RangeInclusive<usize>) -> Aabb {
+    let heights = heightfield.heights();
+    let scale = heightfield.scale();
+    let last = heights.len() - 1;
+
+    let points = points
+        .filter(|index| *index <= last)
+        .map(|index| {
+            parry::math::Point::new(
+                (-0.5 + index as Scalar / last as Scalar) * scale.x,
+                heights[index] * scale.y,
+            )
+        })
+        .collect::<Vec<_>>();
+    Aabb::from_points(&points)
+}
+
+/// Computes the AABB of the points in the given rows and columns of a heightfield in its local space.
+/// Points outside of the heightfield are ignored.
+#[cfg(feature = "3d")]
+fn heightfield_points_aabb(
+    heightfield: &HeightField,
+    rows: RangeInclusive<usize>,
+    columns: RangeInclusive<usize>,
+) -> Aabb {
+    let heights = heightfield.heights();
+    let scale = heightfield.scale();
+    let last_row = heights.nrows() - 1;
+    let last_column = heights.ncols() - 1;
+
+    let points = rows
+        .filter(|row| *row <= last_row)
+        .flat_map(|row| {
+            columns
+                .clone()
+                .filter(|column| *column <= last_column)
+                .map(move |column| (row, column))
+        })
+        .map(|(row, column)| {
+            parry::math::Point::new(
+                (-0.5 + column as Scalar / last_column as Scalar) * scale.x,
+                heights[(row, column)] * scale.y,
+                (-0.5 + row as Scalar / last_row as Scalar) * scale.z,
+            )
+        })
+        .collect::<Vec<_>>();
+    Aabb::from_points(&points)
+}
+
+/// Returns the brightness of the pixel at the given coordinates of a grayscale heightmap between 0 and 1,
+/// or `None` if the image format is not supported.
+#[cfg(feature = "collider-from-image")]
+fn image_height(image: &Image, x: usize, y: usize) -> Option<Scalar> {
+    use bevy::render::render_resource::TextureFormat;
+
+    let index = y * image.texture_descriptor.size.width as usize + x;
+    let data = &image.data;
+
+    match image.texture_descriptor.format {
+        TextureFormat::R8Unorm => data.get(index).map(|v| *v as Scalar / u8::MAX as Scalar),
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => data
+            .get(index * 4)
+            .map(|v| *v as Scalar / u8::MAX as Scalar),
+        TextureFormat::R16Unorm => {
+            let bytes = data.get(index * 2..index * 2 + 2)?;
+            Some(u16::from_le_bytes([bytes[0], bytes[1]]) as Scalar / u16::MAX as Scalar)
+        }
+        TextureFormat::R32Float => {
+            let bytes = data.get(index * 4..index * 4 + 4)?;
+            Some(f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as Scalar)
+        }
+        _ => None,
+    }
+}
+
+/// A component that creates a [`Collider`] from the convex decomposition of a `Mesh` without blocking
+/// the main thread.
+///
+/// Convex decomposition can take several seconds for large meshes, so it is computed in the background
+/// on the `AsyncComputeTaskPool` once the mesh has been loaded. When the decomposition is ready,
+/// the resulting [`Collider`] is inserted and the `AsyncCollider` is removed.
+///
+/// A placeholder collider can be used in the meantime with [`AsyncCollider::with_placeholder`].
+/// It is replaced by the decomposed collider when the computation finishes.
+///
+/// If the mesh doesn't have vertex positions and indices, the `AsyncCollider` is removed
+/// without creating a collider.
+///
+/// ## Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn setup(mut commands: Commands, assets: Res<AssetServer>) {
+///     commands.spawn((
+///         RigidBody::Dynamic,
+///         AsyncCollider::new(assets.load("statue.glb#Mesh0/Primitive0"))
+///             .with_placeholder(Collider::cuboid(1.0, 2.0, 1.0)),
+///     ));
+/// }
+/// ```
+#[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
+#[derive(Component, Reflect, Clone, Debug, Default)]
+#[reflect(Component)]
+pub struct AsyncCollider {
+    /// The handle of the mesh that is decomposed.
+    pub mesh: Handle<Mesh>,
+    /// The collider that is used until the decomposition is ready.
+    pub placeholder: Option<Collider>,
+}
+
+#[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
+impl AsyncCollider {
+    /// Creates an [`AsyncCollider`] that decomposes the given mesh.
+    pub fn new(mesh: Handle<Mesh>) -> Self {
+        Self {
+            mesh,
+            placeholder: None,
+        }
+    }
+
+    /// Sets the collider that is used until the decomposition is ready.
+    pub fn with_placeholder(self, placeholder: Collider) -> Self {
+        Self {
+            placeholder: Some(placeholder),
+            ..self
+        }
+    }
+}
+
+/// Determines how a [`Collider`] is computed from a `Mesh`, for example by an [`AsyncSceneCollider`].
+#[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ComputedCollider {
+    /// A triangle mesh. See [`Collider::trimesh_from_bevy_mesh`].
+    #[default]
+    TriMesh,
+    /// A convex hull. See [`Collider::convex_hull_from_bevy_mesh`].
+    ConvexHull,
+    /// A convex decomposition, computed in the background with an [`AsyncCollider`].
+    ConvexDecomposition,
+}
+
+/// A component that creates [colliders](Collider) for the meshes of a scene, like a glTF scene,
+/// once the scene has been spawned.
+///
+/// The component should be added to the entity that has the `SceneBundle`. When the scene is ready,
+/// a collider is added to each descendant entity with a `Handle<Mesh>`, and the `AsyncSceneCollider`
+/// is removed. The colliders are scaled by the transforms of the entities, and they are attached
+/// to the closest [rigid body](RigidBody) in the hierarchy if there is one.
+///
+/// The [`default_shape`](#structfield.default_shape) is used for all meshes unless the mesh entity
+/// or its parent has a `Name` that is found in [`named_shapes`](#structfield.named_shapes).
+/// Convex decompositions are computed in the background using [`AsyncCollider`].
+///
+/// Requires the `async-collider` feature.
+///
+/// ## Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn setup(mut commands: Commands, assets: Res<AssetServer>) {
+///     commands.spawn((
+///         SceneBundle {
+///             scene: assets.load("level.glb#Scene0"),
+///             ..default()
+///         },
+///         RigidBody::Static,
+///         // Use triangle meshes for the level, convex hulls for crates and no colliders for foliage
+///         AsyncSceneCollider::new(Some(ComputedCollider::TriMesh))
+///             .with_shape_for_name("Crate", Some(ComputedCollider::ConvexHull))
+///             .with_shape_for_name("Foliage", None),
+///     ));
+/// }
+/// ```
+#[cfg(all(feature = "3d", feature = "async-collider"))]
+#[derive(Component, Reflect, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct AsyncSceneCollider {
+    /// The type of the colliders created for meshes without a named shape.
+    /// If `None`, colliders are only created for the meshes in [`named_shapes`](#structfield.named_shapes).
+    pub default_shape: Option<ComputedCollider>,
+    /// The types of the colliders created for meshes with specific names.
+    /// If the value is `None`, no collider is created for the mesh.
+    pub named_shapes: HashMap<String, Option<ComputedCollider>>,
+}
+
+#[cfg(all(feature = "3d", feature = "async-collider"))]
+impl AsyncSceneCollider {
+    /// Creates an [`AsyncSceneCollider`] that creates colliders of the given type for all meshes.
+    /// If `None`, colliders are only created for meshes with [named shapes](#method.with_shape_for_name).
+    pub fn new(default_shape: Option<ComputedCollider>) -> Self {
+        Self {
+            default_shape,
+            named_shapes: HashMap::default(),
+        }
+    }
+
+    /// Sets the type of the collider created for meshes with the given name.
+    /// If `None`, no collider is created for the meshes.
+    pub fn with_shape_for_name(mut self, name: &str, shape: Option<ComputedCollider>) -> Self {
+        self.named_shapes.insert(name.to_string(), shape);
+        self
+    }
+}
+
+/// The background task computing the convex decomposition of an [`AsyncCollider`].
+#[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
+#[derive(Component, Debug)]
+pub(crate) struct AsyncColliderTask(pub(crate) Task<Collider>);
+
+#[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
+impl AsyncColliderTask {
+    /// Starts computing the convex decomposition of the given mesh on the `AsyncComputeTaskPool`.
+    ///
+    /// Returns `None` if the mesh doesn't have vertex positions and indices.
+    pub(crate) fn new(mesh: &Mesh) -> Option<Self> {
+        let (vertices, indices) = extract_mesh_vertices_indices(mesh)?;
+        let task = AsyncComputeTaskPool::get().spawn(async move {
+            Collider::from(SharedShape::convex_decomposition(&vertices, &indices))
+        });
+        Some(Self(task))
+    }
+}
+
+/// A component that recomputes the shape of a [`Collider`] in the background, for shapes that change
+/// every now and then, like a growing blob or a shape that is edited by the player.
+///
+/// Each [request](Self::request) computes a new collider on the `AsyncComputeTaskPool` without blocking
+/// the physics step. When the computation finishes, the shape of the entity's [`Collider`] is replaced
+/// at the start of the next physics frame, and the mass properties and the AABB of the collider
+/// are updated like for any other change of the shape. Sleeping bodies near the old and new shapes are woken up.
+///
+/// If a new update is requested before the previous one has finished, the result of the older request
+/// is discarded, so the newest shape always wins.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::{math::*, prelude::*};
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::{math::*, prelude::*};
+///
+/// #[derive(Component)]
+/// struct Blob {
+///     points: Vec<Vector>,
+/// }
+///
+/// fn setup(mut commands: Commands) {
+///     commands.spawn((
+///         RigidBody::Dynamic,
+///         Collider::ball(0.5),
+///         ColliderShapeUpdates::default(),
+///     ));
+/// }
+///
+/// fn grow_blobs(mut blobs: Query<(&mut Blob, &mut ColliderShapeUpdates)>) {
+///     for (mut blob, mut shape_updates) in &mut blobs {
+///         for point in blob.points.iter_mut() {
+///             *point *= 1.01;
+///         }
+///         // Computing the convex hull of many points can be slow, so do it in the background
+///         shape_updates.request_convex_hull(blob.points.clone());
+///     }
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct ColliderShapeUpdates {
+    /// The number of the latest finished request and the collider that it computed, if any.
+    #[reflect(ignore)]
+    finished: Arc<Mutex<Option<(u64, Option<Collider>)>>>,
+    /// The number of requested updates.
+    requested: u64,
+    /// The number of the request whose result was applied last.
+    applied: u64,
+}
+
+impl ColliderShapeUpdates {
+    /// Computes a new collider with the given function on the `AsyncComputeTaskPool`.
+    /// If the function returns `None`, the current shape is kept.
+    ///
+    /// Only the shape of the new collider is used. The scale and other settings of the current collider are kept.
+    pub fn request(&mut self, compute: impl FnOnce() -> Option<Collider> + Send + 'static) {
+        self.requested += 1;
+        let request = self.requested;
+        let finished = self.finished.clone();
+
+        AsyncComputeTaskPool::get()
+            .spawn(async move {
+                // Failed requests are recorded too, so that they are no longer pending
+                let collider = compute();
+                let mut finished = finished.lock().unwrap();
+                // Results of older requests can finish later, so only keep the newest one
+                if finished
+                    .as_ref()
+                    .map_or(true, |(other, _)| *other < request)
+                {
+                    *finished = Some((request, collider));
+                }
+            })
+            .detach();
+    }
+
+    /// Computes the [convex hull](Collider::convex_hull) of the given points in the background.
+    pub fn request_convex_hull(&mut self, points: Vec<Vector>) {
+        self.request(move || Collider::convex_hull(points));
+    }
+
+    /// Computes the [convex decomposition](Collider::convex_decomposition) of the given polyline in the background.
+    #[cfg(feature = "2d")]
+    pub fn request_convex_decomposition(&mut self, vertices: Vec<Vector>, indices: Vec<[u32; 2]>) {
+        self.request(move || Some(Collider::convex_decomposition(vertices, indices)));
+    }
+
+    /// Computes the [convex decomposition](Collider::convex_decomposition) of the given triangle mesh
+    /// in the background.
+    #[cfg(feature = "3d")]
+    pub fn request_convex_decomposition(&mut self, vertices: Vec<Vector>, indices: Vec<[u32; 3]>) {
+        self.request(move || Some(Collider::convex_decomposition(vertices, indices)));
+    }
+
+    /// Returns true if an update has been requested but its result hasn't been applied yet.
+    ///
+    /// Requests that don't compute a new collider stop being pending once they have finished.
+    pub fn is_pending(&self) -> bool {
+        self.applied < self.requested
+    }
+
+    /// Returns the collider computed by the newest request that has finished since the last call, if any.
+    ///
+    /// If the newest request didn't compute a collider, it is still marked as applied and `None` is returned.
+    pub(crate) fn take_finished(&mut self) -> Option<Collider> {
+        let (request, collider) = self.finished.lock().unwrap().take()?;
+        if request <= self.applied {
+            return None;
+        }
+        self.applied = request;
+        collider
+    }
+}
+
+#[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
+type VerticesIndices = (Vec<nalgebra::Point3<Scalar>>, Vec<[u32; 3]>);
+
+#[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
+fn extract_mesh_vertices(mesh: &Mesh) -> Option<Vec<nalgebra::Point3<Scalar>>> {
+    match mesh.attribute(Mesh::ATTRIBUTE_POSITION)? {
+        VertexAttributeValues::Float32(vtx) => Some(
+            vtx.chunks(3)
+                .map(|v| [v[0] as Scalar, v[1] as Scalar, v[2] as Scalar].into())
+                .collect(),
+        ),
+        VertexAttributeValues::Float32x3(vtx) => Some(
+            vtx.iter()
+                .map(|v| [v[0] as Scalar, v[1] as Scalar, v[2] as Scalar].into())
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+#[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
+pub(crate) fn extract_mesh_vertices_indices(mesh: &Mesh) -> Option<VerticesIndices> {
+    let vtx = extract_mesh_vertices(mesh)?;
+    let idx = match mesh.indices()? {
+        Indices::U16(idx) => idx
+            .chunks_exact(3)
+            .map(|i| [i[0] as u32, i[1] as u32, i[2] as u32])
+            .collect(),
+        Indices::U32(idx) => idx.chunks_exact(3).map(|i| [i[0], i[1], i[2]]).collect(),
+    };
+
+    Some((vtx, idx))
+}
+
+/// The number of subdivisions used when a scaled shape has to be approximated by a convex hull.
+pub(crate) const DEFAULT_SCALE_SUBDIVISIONS: u32 = 10;
+
+/// Scales a shape by the given scale. Shapes that can't be represented with a non-uniform scale,
+/// like balls, are approximated by convex hulls with `num_subdivisions` subdivisions.
+///
+/// Returns `None` if the scaled shape is degenerate or if the shape can't be scaled.
+fn scale_shape(shape: &SharedShape, scale: Vector, num_subdivisions: u32) -> Option<SharedShape> {
+    let scale: parry::math::Vector<Scalar> = scale.into();
+
+    match shape.as_typed_shape() {
+        TypedShape::Ball(s) => match s.scaled(&scale, num_subdivisions)? {
+            Either::Left(ball) => Some(SharedShape::new(ball)),
+            Either::Right(hull) => Some(SharedShape::new(hull)),
+        },
+        TypedShape::Cuboid(s) => Some(SharedShape::new(s.scaled(&scale))),
+        TypedShape::Capsule(s) => match s.scaled(&scale, num_subdivisions)? {
+            Either::Left(capsule) => Some(SharedShape::new(capsule)),
+            Either::Right(hull) => Some(SharedShape::new(hull)),
+        },
+        TypedShape::Segment(s) => Some(SharedShape::new(s.scaled(&scale))),
+        TypedShape::Triangle(s) => Some(SharedShape::new(s.scaled(&scale))),
+        TypedShape::TriMesh(s) => Some(SharedShape::new(s.clone().scaled(&scale))),
+        TypedShape::Polyline(s) => Some(SharedShape::new(s.clone().scaled(&scale))),
+        TypedShape::HalfSpace(s) => Some(SharedShape::new(s.scaled(&scale)?)),
+        TypedShape::HeightField(s) => Some(SharedShape::new(s.clone().scaled(&scale))),
+        TypedShape::Compound(s) => {
+            let mut shapes = Vec::with_capacity(s.shapes().len());
+            for (isometry, sub_shape) in s.shapes() {
+                let mut isometry = *isometry;
+                isometry.translation.vector.component_mul_assign(&scale);
+                // Sub-shape rotations are kept, so non-uniform scaling of rotated sub-shapes is approximate
+                let sub_shape = scale_shape(sub_shape, scale.into(), num_subdivisions)?;
+                shapes.push((isometry, sub_shape));
+            }
+            Some(SharedShape::compound(shapes))
+        }
+        #[cfg(feature = "2d")]
+        TypedShape::ConvexPolygon(s) => Some(SharedShape::new(s.clone().scaled(&scale)?)),
+        #[cfg(feature = "3d")]
+        TypedShape::ConvexPolyhedron(s) => Some(SharedShape::new(s.clone().scaled(&scale)?)),
+        #[cfg(feature = "3d")]
+        TypedShape::Cylinder(s) => match s.scaled(&scale, num_subdivisions)? {
+            Either::Left(cylinder) => Some(SharedShape::new(cylinder)),
+            Either::Right(hull) => Some(SharedShape::new(hull)),
+        },
+        #[cfg(feature = "3d")]
+        TypedShape::Cone(s) => match s.scaled(&scale, num_subdivisions)? {
+            Either::Left(cone) => Some(SharedShape::new(cone)),
+            Either::Right(hull) => Some(SharedShape::new(hull)),
+        },
+        // Rounded and custom shapes are not scaled
+        _ => None,
+    }
+}
+
+/// A component that marks a [`Collider`] as a sensor, also known as a trigger.
+///
+/// Sensor colliders send [collision events](Collider#collision-events) and register intersections,
+/// but allow other bodies to pass through them. This is often used to detect when something enters
+/// or leaves an area or is intersecting some shape.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     // Spawn a static body with a sensor collider.
+///     // Other bodies will pass through, but it will still send collision events.
+///     commands.spawn((RigidBody::Static, Collider::ball(0.5), Sensor));
+/// }
+/// ```
+#[doc(alias = "Trigger")]
+#[derive(Reflect, Clone, Component, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct Sensor;
+
+/// A collision margin, or contact skin, that keeps colliders separated by a small distance when they are in contact.
+///
+/// Contacts are generated and resolved as if the collider was inflated by the margin, so resting contacts
+/// stay persistent instead of flickering on and off at exactly zero penetration. This improves the stability
+/// of stacks and reduces jitter.
+///
+/// Colliders without this component use the [global collision margin](NarrowPhaseConfig::collision_margin).
+/// For a pair of colliders, the larger of their margins is used.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     commands.spawn((
+///         RigidBody::Dynamic,
+///         Collider::ball(0.5),
+///         CollisionMargin(0.01),
+///     ));
+/// }
+/// ```
+#[doc(alias = "ContactSkin")]
+#[derive(
+    Reflect, Clone, Copy, Component, Debug, Default, Deref, DerefMut, PartialEq, PartialOrd,
+)]
+#[reflect(Component)]
+pub struct CollisionMargin(pub Scalar);
+
+/// Overrides or blends the normals of the contacts of a collider towards a world-space direction,
+/// for stylized behavior that doesn't follow the real geometry of the contacts.
+///
+/// The normals point in the direction that the other collider pushes this collider. Only normals that
+/// are within [`max_angle`](#structfield.max_angle) of the [`direction`](#structfield.direction)
+/// are changed, and the [`blend`](#structfield.blend) factor controls how far they are rotated towards it.
+/// For example, a hover vehicle can always be pushed straight up by the ground, even on bumpy terrain,
+/// while walls still push it sideways.
+///
+/// The normals are changed before the [`PenetrationConstraint`](crate::constraints::penetration::PenetrationConstraint)s
+/// are created, so the contact is resolved, and friction and restitution are applied, along the new normal.
+/// The component can be added to a collider or to the [rigid body](RigidBody) that the collider is attached to.
+/// If both colliders of a contact have an override, both overrides are applied.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// # #[cfg(all(feature = "3d", feature = "f32"))]
+/// fn setup(mut commands: Commands) {
+///     // Contacts that push the vehicle less than 45 degrees away from up push it straight up
+///     commands.spawn((
+///         RigidBody::Dynamic,
+///         Collider::ball(0.5),
+///         ContactNormalOverride::new(Vec3::Y).with_max_angle(std::f32::consts::FRAC_PI_4),
+///     ));
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Component, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct ContactNormalOverride {
+    /// The world-space direction that the normals are rotated towards.
+    pub direction: Vector,
+    /// The largest angle in radians between a normal and the [`direction`](#structfield.direction)
+    /// at which the normal is still changed.
+    pub max_angle: Scalar,
+    /// How far the normals are rotated towards the [`direction`](#structfield.direction) in the `[0, 1]` range.
+    /// A value of `1.0` replaces the normals, and `0.0` keeps them as is.
+    pub blend: Scalar,
+}
+
+impl Default for ContactNormalOverride {
+    /// Creates an override that points up but has a [`blend`](#structfield.blend) of zero,
+    /// so the normals are kept as is.
+    fn default() -> Self {
+        Self::new(Vector::Y).with_blend(0.0)
+    }
+}
+
+impl ContactNormalOverride {
+    /// Creates a new override that replaces all contact normals with the given world-space direction.
+    pub fn new(direction: Vector) -> Self {
+        Self {
+            direction,
+            max_angle: PI,
+            blend: 1.0,
+        }
+    }
+
+    /// Sets the largest angle in radians between a normal and the direction at which the normal is still changed.
+    pub fn with_max_angle(self, max_angle: Scalar) -> Self {
+        Self { max_angle, ..self }
+    }
+
+    /// Sets how far the normals are rotated towards the direction in the `[0, 1]` range.
+    pub fn with_blend(self, blend: Scalar) -> Self {
+        Self { blend, ..self }
+    }
+
+    /// Returns the given world-space normal changed by the override.
+    /// The normal must point in the direction that the other collider pushes this collider.
+    pub fn apply_to(&self, normal: Vector) -> Vector {
+        let direction = self.direction.normalize_or_zero();
+        if direction == Vector::ZERO
+            || normal.dot(direction) < math::ops::cos(self.max_angle.min(PI))
+        {
+            return normal;
+        }
+
+        let blended = normal
+            .lerp(direction, self.blend.clamp(0.0, 1.0))
+            .normalize_or_zero();
+        if blended == Vector::ZERO {
+            normal
+        } else {
+            blended
+        }
+    }
+}
+
+/// Makes the contacts of a collider roll without slipping by matching the velocities of the surfaces
+/// at the contact points, instead of using the generic static and dynamic friction.
+///
+/// Balls and capsules rolling with the generic friction slip a tiny bit on every substep, which slowly
+/// drains their energy. With a rolling contact, the velocity solve removes the relative tangential velocity
+/// of the contact points, limited by the static [`Friction`] coefficient, so rolling bodies keep their speed
+/// unless they have [rolling friction](Friction::rolling_coefficient).
+///
+/// The component can be added to a collider or to the [rigid body](RigidBody) that the collider is attached to,
+/// and a contact is a rolling contact if either collider is a rolling contact.
+/// [`TireFriction`] takes priority over rolling contacts.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     commands.spawn((RigidBody::Dynamic, Collider::ball(0.5), RollingContact));
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Component, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct RollingContact;
+
+/// The Axis-Aligned Bounding Box of a collider.
+#[derive(Reflect, Clone, Copy, Component, Debug, Deref, DerefMut, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect_value(Component, Debug, PartialEq)]
+pub struct ColliderAabb(pub Aabb);
+
+impl ColliderAabb {
+    /// Creates a new collider from a given [`SharedShape`] with a default density of 1.0.
+    pub fn from_shape(shape: &SharedShape) -> Self {
+        Self(shape.compute_local_aabb())
+    }
+}
+
+impl Default for ColliderAabb {
+    fn default() -> Self {
+        ColliderAabb(Aabb::new_invalid())
+    }
+}
+
+/// Contains the entities that are colliding with an entity.
+///
+/// This component is automatically added for all entities with a [`Collider`].
+///
+/// ## Example
+///
 /// ```
 /// use bevy::prelude::*;
 /// # #[cfg(feature = "2d")]
@@ -463,5 +1857,181 @@ impl Default for ColliderAabb {
 /// }
 /// ```
 #[derive(Reflect, Clone, Component, Debug, Default, Deref, DerefMut, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct CollidingEntities(pub HashSet<Entity>);
+
+/// The [rigid body](RigidBody) that a [`Collider`] is attached to.
+///
+/// This component is automatically added for colliders that are on a rigid body entity
+/// or on one of its descendants. For colliders on the body entity itself, the parent is the entity itself.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn print_collider_bodies(colliders: Query<(Entity, &ColliderParent)>) {
+///     for (entity, parent) in &colliders {
+///         println!("{:?} is attached to {:?}", entity, parent.get());
+///     }
+/// }
+/// ```
+///
+/// [Collision events](Collider#collision-events) and spatial query hits also contain the entities
+/// of the rigid bodies, so there is no need to look them up manually.
+#[derive(Reflect, Clone, Copy, Component, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct ColliderParent(pub(crate) Entity);
+
+impl ColliderParent {
+    /// Gets the entity of the [rigid body](RigidBody) that the collider is attached to.
+    pub const fn get(&self) -> Entity {
+        self.0
+    }
+}
+
+impl FromWorld for ColliderParent {
+    fn from_world(_world: &mut World) -> Self {
+        Self(Entity::PLACEHOLDER)
+    }
+}
+
+/// The transform of a [`Collider`] relative to the [rigid body](RigidBody) it is attached to,
+/// determined by the entity hierarchy between the collider and its [`ColliderParent`].
+///
+/// This component is automatically added and updated for colliders attached to rigid bodies.
+/// For colliders on the body entity itself, the transform is the identity.
+#[derive(Reflect, Clone, Copy, Component, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct ColliderTransform {
+    /// The translation of the collider relative to the body.
+    pub translation: Vector,
+    /// The rotation of the collider relative to the body.
+    pub rotation: Rotation,
+}
+
+impl ColliderTransform {
+    /// Transforms a point from the collider's local space to the local space of the body.
+    pub fn transform_point(&self, point: Vector) -> Vector {
+        self.translation + self.rotation.rotate(point)
+    }
+
+    /// Transforms a direction from the collider's local space to the local space of the body.
+    pub fn transform_direction(&self, direction: Vector) -> Vector {
+        self.rotation.rotate(direction)
+    }
+
+    /// Combines the transform with a [`ColliderOffset`] that is applied first.
+    pub fn offset_by(&self, offset: &ColliderOffset) -> Self {
+        Self {
+            translation: self.transform_point(offset.translation),
+            rotation: self.rotation.mul(offset.rotation),
+        }
+    }
+}
+
+impl Default for ColliderTransform {
+    fn default() -> Self {
+        Self {
+            translation: Vector::ZERO,
+            rotation: Rotation::default(),
+        }
+    }
+}
+
+impl From<ColliderOffset> for ColliderTransform {
+    fn from(value: ColliderOffset) -> Self {
+        Self {
+            translation: value.translation,
+            rotation: value.rotation,
+        }
+    }
+}
+
+impl From<Transform> for ColliderTransform {
+    fn from(value: Transform) -> Self {
+        Self {
+            #[cfg(feature = "2d")]
+            translation: value.translation.truncate().adjust_precision(),
+            #[cfg(feature = "3d")]
+            translation: value.translation.adjust_precision(),
+            rotation: Rotation::from(value.rotation.adjust_precision()),
+        }
+    }
+}
+
+/// A local offset of a [`Collider`] relative to the [`Position`] and [`Rotation`] of its entity.
+///
+/// This can be used for off-center colliders without having to create a compound shape
+/// or a separate child entity. The offset is taken into account in collision detection,
+/// spatial queries and mass property computation.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     // Spawn a dynamic body with a ball collider that is one unit above the body's origin
+///     commands.spawn((
+///         RigidBody::Dynamic,
+///         Collider::ball(0.5),
+///         # #[cfg(feature = "2d")]
+///         # ColliderOffset::from_translation(Vec2::Y),
+///         # #[cfg(feature = "3d")]
+///         ColliderOffset::from_translation(Vec3::Y),
+///     ));
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Component, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct ColliderOffset {
+    /// The local translation of the collider.
+    pub translation: Vector,
+    /// The local rotation of the collider.
+    pub rotation: Rotation,
+}
+
+impl ColliderOffset {
+    /// Creates a [`ColliderOffset`] with the given translation and rotation.
+    pub fn new(translation: Vector, rotation: Rotation) -> Self {
+        Self {
+            translation,
+            rotation,
+        }
+    }
+
+    /// Creates a [`ColliderOffset`] with the given translation and no rotation.
+    pub fn from_translation(translation: Vector) -> Self {
+        Self {
+            translation,
+            ..default()
+        }
+    }
+
+    /// Sets the local rotation of the collider.
+    pub fn with_rotation(self, rotation: Rotation) -> Self {
+        Self { rotation, ..self }
+    }
+
+    /// Computes the global position and rotation of the collider using the given
+    /// [`Position`] and [`Rotation`] of its entity.
+    pub fn transform_pose(&self, position: Vector, rotation: Rotation) -> (Vector, Rotation) {
+        (
+            position + rotation.rotate(self.translation),
+            rotation.mul(self.rotation),
+        )
+    }
+}
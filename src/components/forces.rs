@@ -88,6 +88,7 @@ impl FloatZero for Scalar {
 /// If you want to apply a force in the same local direction every frame,
 /// consider setting `persistent` to `false` and running [`apply_force`](#method.apply_force) in a system.
 #[derive(Reflect, Clone, Copy, Component, Debug, PartialEq, From)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct ExternalForce {
     /// The total external force that will be applied.
@@ -226,6 +227,7 @@ impl ExternalForce {
 /// }
 /// ```
 #[derive(Reflect, Clone, Copy, Component, Debug, PartialEq, From)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct ExternalTorque {
     /// The total external torque that will be applied.
@@ -369,6 +371,7 @@ impl ExternalTorque {
 /// }
 /// ```
 #[derive(Reflect, Clone, Copy, Component, Debug, PartialEq, From)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct ExternalImpulse {
     /// The total external impulse that will be applied.
@@ -510,6 +513,7 @@ impl ExternalImpulse {
 /// }
 /// ```
 #[derive(Reflect, Clone, Copy, Component, Debug, PartialEq, From)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 #[doc(alias = "ExternalTorqueImpulse")]
 pub struct ExternalAngularImpulse {
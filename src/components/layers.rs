@@ -72,6 +72,7 @@ impl<L: PhysicsLayer> PhysicsLayer for &L {
 /// }
 /// ```
 #[derive(Reflect, Clone, Copy, Component, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct CollisionLayers {
     groups: u32,
@@ -211,3 +212,135 @@ impl Default for CollisionLayers {
         }
     }
 }
+
+/// A global resource that defines which [physics layers](PhysicsLayer) can interact with each other,
+/// like the layer collision matrix in many game engines.
+///
+/// When the resource exists, two colliders can only interact if they are allowed to interact by their
+/// [`CollisionLayers`] *and* the matrix allows interaction between one of the groups of the first collider
+/// and one of the groups of the second collider. Colliders without [`CollisionLayers`] belong to all layers.
+///
+/// This makes it possible to configure the interactions of layers in one central place instead of
+/// setting the masks of each collider. By default, all layers can interact with each other.
+///
+/// The matrix is consulted by the [broad phase](BroadPhasePlugin).
+///
+/// ## Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// #[derive(PhysicsLayer)]
+/// enum Layer {
+///     Player,
+///     Enemy,
+///     Ground,
+/// }
+///
+/// fn main() {
+///     App::new()
+///         .add_plugins((DefaultPlugins, PhysicsPlugins::default()))
+///         // Players and enemies can only collide with the ground
+///         .insert_resource(
+///             CollisionMatrix::none()
+///                 .with_interaction(Layer::Player, Layer::Ground, true)
+///                 .with_interaction(Layer::Enemy, Layer::Ground, true),
+///         )
+///         .add_systems(Startup, setup)
+///         .run();
+/// }
+///
+/// fn setup(mut commands: Commands) {
+///     commands.spawn((
+///         Collider::ball(0.5),
+///         CollisionLayers::new([Layer::Player], [Layer::Player, Layer::Enemy, Layer::Ground]),
+///     ));
+/// }
+/// ```
+#[derive(Resource, Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Resource)]
+pub struct CollisionMatrix {
+    /// The layers that each of the 32 layers can interact with as bitmasks.
+    rows: [u32; 32],
+}
+
+impl Default for CollisionMatrix {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl CollisionMatrix {
+    /// Creates a [`CollisionMatrix`] where all layers can interact with each other.
+    pub const fn all() -> Self {
+        Self {
+            rows: [0xffff_ffff; 32],
+        }
+    }
+
+    /// Creates a [`CollisionMatrix`] where no layers can interact with each other.
+    pub const fn none() -> Self {
+        Self { rows: [0; 32] }
+    }
+
+    /// Sets whether the given layers can interact with each other.
+    pub fn set_interaction(
+        &mut self,
+        layer1: impl PhysicsLayer,
+        layer2: impl PhysicsLayer,
+        interacts: bool,
+    ) {
+        let (bits1, bits2) = (layer1.to_bits(), layer2.to_bits());
+
+        for i in 0..32 {
+            if bits1 & (1 << i) != 0 {
+                self.set_row_bits(i, bits2, interacts);
+            }
+            if bits2 & (1 << i) != 0 {
+                self.set_row_bits(i, bits1, interacts);
+            }
+        }
+    }
+
+    /// Sets whether the given layers can interact with each other and returns the matrix.
+    pub fn with_interaction(
+        mut self,
+        layer1: impl PhysicsLayer,
+        layer2: impl PhysicsLayer,
+        interacts: bool,
+    ) -> Self {
+        self.set_interaction(layer1, layer2, interacts);
+        self
+    }
+
+    /// Returns true if the given layers can interact with each other.
+    pub fn interacts(&self, layer1: impl PhysicsLayer, layer2: impl PhysicsLayer) -> bool {
+        self.groups_interact(layer1.to_bits(), layer2.to_bits())
+    }
+
+    /// Returns true if any layer in the `groups1` bitmask can interact with any layer in the `groups2` bitmask.
+    pub fn groups_interact(&self, groups1: u32, groups2: u32) -> bool {
+        (0..32).any(|i| groups1 & (1 << i) != 0 && self.rows[i] & groups2 != 0)
+    }
+
+    /// Returns true if the groups of the given [`CollisionLayers`] can interact according to the matrix.
+    ///
+    /// Note that this doesn't take the masks of the layers into account.
+    /// For that, use [`CollisionLayers::interacts_with`].
+    pub fn layers_interact(&self, layers1: CollisionLayers, layers2: CollisionLayers) -> bool {
+        self.groups_interact(layers1.groups_bits(), layers2.groups_bits())
+    }
+
+    fn set_row_bits(&mut self, row: usize, bits: u32, value: bool) {
+        if value {
+            self.rows[row] |= bits;
+        } else {
+            self.rows[row] &= !bits;
+        }
+    }
+}
@@ -30,6 +30,7 @@ use crate::prelude::*;
 /// }
 /// ```
 #[derive(Component, Reflect, Clone, Copy, Debug, Default, From)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct LockedAxes(u8);
 
@@ -1,11 +1,14 @@
 use crate::prelude::*;
 use bevy::prelude::*;
 
+#[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
+use crate::components::collider::extract_mesh_vertices_indices;
 #[cfg(feature = "3d")]
 use crate::utils::get_rotated_inertia_tensor;
 
 /// The mass of a body.
 #[derive(Reflect, Clone, Copy, Component, Debug, Default, Deref, DerefMut, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct Mass(pub Scalar);
 
@@ -16,6 +19,7 @@ impl Mass {
 
 /// The inverse mass of a body.
 #[derive(Reflect, Clone, Copy, Component, Debug, Default, Deref, DerefMut, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct InverseMass(pub Scalar);
 
@@ -27,6 +31,7 @@ impl InverseMass {
 /// The moment of inertia of a body. This represents the torque needed for a desired angular acceleration.
 #[cfg(feature = "2d")]
 #[derive(Reflect, Clone, Copy, Component, Debug, Default, Deref, DerefMut, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct Inertia(pub Scalar);
 
@@ -38,6 +43,7 @@ pub struct Inertia(pub Scalar);
 /// To get the world-space version that takes the body's rotation into account, use the associated `rotated` method. Note that this operation is quite expensive, so use it sparingly.
 #[cfg(feature = "3d")]
 #[derive(Reflect, Clone, Copy, Component, Debug, Deref, DerefMut, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct Inertia(pub Matrix3);
 
@@ -112,6 +118,7 @@ impl Inertia {
 /// The inverse moment of inertia of the body. This represents the inverse of the torque needed for a desired angular acceleration.
 #[cfg(feature = "2d")]
 #[derive(Reflect, Clone, Copy, Component, Debug, Default, Deref, DerefMut, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct InverseInertia(pub Scalar);
 
@@ -123,6 +130,7 @@ pub struct InverseInertia(pub Scalar);
 /// To get the world-space version that takes the body's rotation into account, use the associated `rotated` method. Note that this operation is quite expensive, so use it sparingly.
 #[cfg(feature = "3d")]
 #[derive(Reflect, Clone, Copy, Component, Debug, Deref, DerefMut, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct InverseInertia(pub Matrix3);
 
@@ -174,6 +182,7 @@ impl From<Inertia> for InverseInertia {
 
 /// The local center of mass of a body.
 #[derive(Reflect, Clone, Copy, Component, Debug, Default, Deref, DerefMut, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct CenterOfMass(pub Vector);
 
@@ -182,6 +191,62 @@ impl CenterOfMass {
     pub const ZERO: Self = Self(Vector::ZERO);
 }
 
+/// Overrides or offsets the [`CenterOfMass`] of a body computed from its colliders.
+///
+/// The [`CenterOfMass`] component keeps the computed value, but the solver uses the adjusted center of mass
+/// for contacts and joints. This can be used for things like lowering the center of mass of a car
+/// to make it harder to flip over, or for making a self-righting toy.
+///
+/// Note that the [`Inertia`] of the body isn't adjusted, so it's still the inertia around the computed
+/// center of mass. For small adjustments, this is usually not noticeable, but if the center of mass is moved far
+/// from the colliders, consider defining all of the mass properties of the body explicitly instead,
+/// as described in the [`RigidBody`] documentation.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::{math::*, prelude::*};
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::{math::*, prelude::*};
+///
+/// fn setup(mut commands: Commands) {
+///     // A toy that stands back up when tipped over
+///     commands.spawn((
+///         RigidBody::Dynamic,
+///         Collider::capsule(1.0, 0.5),
+///         CenterOfMassOverride::Local(Vector::NEG_Y * 0.8),
+///     ));
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Component, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub enum CenterOfMassOverride {
+    /// Replaces the computed center of mass with the given local point.
+    Local(Vector),
+    /// Offsets the computed center of mass by the given local vector.
+    Offset(Vector),
+}
+
+impl Default for CenterOfMassOverride {
+    /// An offset of zero that keeps the computed center of mass.
+    fn default() -> Self {
+        Self::Offset(Vector::ZERO)
+    }
+}
+
+impl CenterOfMassOverride {
+    /// Returns the local center of mass adjusted from the given computed center of mass.
+    pub fn apply_to(&self, center_of_mass: Vector) -> Vector {
+        match self {
+            Self::Local(point) => *point,
+            Self::Offset(offset) => center_of_mass + *offset,
+        }
+    }
+}
+
 /// A bundle containing mass properties.
 ///
 /// ## Example
@@ -235,12 +300,180 @@ impl MassPropertiesBundle {
     }
 }
 
+/// The density of a [`Collider`], used for computing the [`Mass`], [`Inertia`] and [`CenterOfMass`]
+/// of the collider from its shape. The default density is 1.0.
+///
+/// The mass properties are recomputed automatically when the density or the shape of the collider changes,
+/// and they are added to the mass properties of the [rigid body](RigidBody) that the collider is attached to.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     // A heavy ball with a mass of about 4.2 in 3D
+///     commands.spawn((RigidBody::Dynamic, Collider::ball(0.5), ColliderDensity(8.0)));
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Component, Debug, Deref, DerefMut, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct ColliderDensity(pub Scalar);
+
+impl Default for ColliderDensity {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+impl From<Scalar> for ColliderDensity {
+    fn from(density: Scalar) -> Self {
+        Self(density)
+    }
+}
+
+/// The volume, center of mass and inertia tensor of a solid described by a closed triangle mesh
+/// with a density of 1.0.
+///
+/// The properties are computed by integrating over the tetrahedra formed by the origin and each triangle,
+/// so the mesh should be closed. The winding of the triangles can be either clockwise or counterclockwise.
+///
+/// This is used for computing the [`ColliderMassProperties`] of triangle mesh and convex colliders,
+/// like the ones created from Bevy meshes.
+#[cfg(feature = "3d")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MeshMassProperties {
+    /// The volume of the mesh.
+    pub volume: Scalar,
+    /// The center of mass of the mesh.
+    pub center_of_mass: Vector,
+    /// The inertia tensor of the mesh around its center of mass with a density of 1.0.
+    pub inertia: Matrix3,
+}
+
+#[cfg(feature = "3d")]
+impl MeshMassProperties {
+    /// Computes the mass properties of the closed triangle mesh with the given vertices and indices.
+    ///
+    /// Returns `None` if the mesh has no volume, for example if it is flat or open.
+    pub fn from_trimesh(vertices: &[Vector], indices: &[[u32; 3]]) -> Option<Self> {
+        let mut volume = 0.0;
+        let mut first_moment = Vector::ZERO;
+        // The integral of x * x^T over the volume
+        let mut covariance = Matrix3::ZERO;
+
+        for triangle in indices {
+            let [a, b, c] = triangle.map(|i| vertices.get(i as usize).copied());
+            let (Some(a), Some(b), Some(c)) = (a, b, c) else {
+                return None;
+            };
+
+            // Signed volume of the tetrahedron formed by the origin and the triangle
+            let tetrahedron_volume = a.dot(b.cross(c)) / 6.0;
+            let sum = a + b + c;
+
+            volume += tetrahedron_volume;
+            first_moment += tetrahedron_volume * sum / 4.0;
+            covariance += (outer_product(a, a)
+                + outer_product(b, b)
+                + outer_product(c, c)
+                + outer_product(sum, sum))
+                * (tetrahedron_volume / 20.0);
+        }
+
+        // Inverted triangle winding results in a negative volume
+        if volume < 0.0 {
+            volume = -volume;
+            first_moment = -first_moment;
+            covariance = -covariance;
+        }
+        if volume <= Scalar::EPSILON {
+            return None;
+        }
+
+        let center_of_mass = first_moment / volume;
+
+        // Move the covariance to the center of mass and convert it to an inertia tensor
+        let covariance = covariance - outer_product(center_of_mass, center_of_mass) * volume;
+        let trace = covariance.x_axis.x + covariance.y_axis.y + covariance.z_axis.z;
+        let inertia = Matrix3::from_diagonal(Vector::splat(trace)) - covariance;
+
+        Some(Self {
+            volume,
+            center_of_mass,
+            inertia,
+        })
+    }
+
+    /// Computes the mass properties of a Bevy [`Mesh`] with a triangle list topology.
+    ///
+    /// Returns `None` if the mesh has no vertex positions or indices, or if it has no volume.
+    #[cfg(feature = "collider-from-mesh")]
+    pub fn from_bevy_mesh(mesh: &Mesh) -> Option<Self> {
+        let (vertices, indices) = extract_mesh_vertices_indices(mesh)?;
+        let vertices: Vec<Vector> = vertices.into_iter().map(Vector::from).collect();
+        Self::from_trimesh(&vertices, &indices)
+    }
+
+    /// Computes the mass properties of a triangle mesh or convex polyhedron [`Collider`].
+    ///
+    /// Returns `None` for other shapes or if the shape has no volume.
+    pub fn from_collider(collider: &Collider) -> Option<Self> {
+        if let Some(trimesh) = collider.as_trimesh() {
+            let vertices: Vec<Vector> = trimesh
+                .vertices()
+                .iter()
+                .map(|v| Vector::from(*v))
+                .collect();
+            Self::from_trimesh(&vertices, trimesh.indices())
+        } else if let Some(convex) = collider.as_convex_polyhedron() {
+            let (vertices, indices) = convex.to_trimesh();
+            let vertices: Vec<Vector> = vertices.into_iter().map(Vector::from).collect();
+            Self::from_trimesh(&vertices, &indices)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the [`ColliderMassProperties`] of the mesh with the given density.
+    pub fn to_collider_mass_properties(&self, density: Scalar) -> ColliderMassProperties {
+        let mass = self.volume * density;
+        let inertia = Inertia(self.inertia * density);
+
+        ColliderMassProperties {
+            mass: Mass(mass),
+            inverse_mass: InverseMass(if mass > Scalar::EPSILON {
+                1.0 / mass
+            } else {
+                0.0
+            }),
+            inertia,
+            inverse_inertia: inertia.inverse(),
+            center_of_mass: CenterOfMass(self.center_of_mass),
+            density,
+        }
+    }
+}
+
+/// Computes the outer product `a * b^T` of two vectors.
+#[cfg(feature = "3d")]
+fn outer_product(a: Vector, b: Vector) -> Matrix3 {
+    Matrix3::from_cols(a * b.x, a * b.y, a * b.z)
+}
+
 /// The mass properties derived from a given collider shape and density.
 ///
 /// These will be added to the body's actual [`Mass`], [`InverseMass`], [`Inertia`], [`InverseInertia`] and [`CenterOfMass`] components.
 ///
-/// You should generally not create or modify this directly. Instead, you can generate this automatically using a given collider shape and density with the associated `from_shape_and_density` method.
-#[derive(Reflect, Clone, Copy, Component, PartialEq)]
+/// You should generally not create or modify this directly. Instead, it is computed automatically from the shape
+/// of the [`Collider`] and its [`ColliderDensity`].
+#[derive(Reflect, Clone, Copy, Component, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct ColliderMassProperties {
     /// Mass given by collider.
@@ -272,6 +505,12 @@ impl ColliderMassProperties {
 impl ColliderMassProperties {
     /// Computes mass properties from a given [`Collider`] and density.
     pub fn new_computed(collider: &Collider, density: Scalar) -> Self {
+        // Triangle meshes and convex polyhedra are integrated as solids
+        #[cfg(feature = "3d")]
+        if let Some(mesh_props) = MeshMassProperties::from_collider(collider) {
+            return mesh_props.to_collider_mass_properties(density);
+        }
+
         let props = collider.mass_properties(density);
 
         Self {
@@ -293,6 +532,17 @@ impl ColliderMassProperties {
             density,
         }
     }
+
+    /// Transforms the center of mass and inertia of the mass properties from the local space of a collider
+    /// to the local space of the body that the collider is attached to.
+    pub fn transformed_by(&self, transform: &ColliderTransform) -> Self {
+        Self {
+            inertia: self.inertia.rotated(&transform.rotation),
+            inverse_inertia: self.inverse_inertia.rotated(&transform.rotation),
+            center_of_mass: CenterOfMass(transform.transform_point(self.center_of_mass.0)),
+            ..*self
+        }
+    }
 }
 
 impl Default for ColliderMassProperties {
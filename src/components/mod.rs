@@ -1,19 +1,45 @@
 //! Components used for rigid bodies, colliders and mass properties.
 
+mod attitude_control;
+#[cfg(feature = "baked-colliders")]
+mod baked_collider;
+#[cfg(all(feature = "3d", feature = "bone-collider"))]
+mod bone_collider;
 mod collider;
 mod forces;
 mod layers;
 mod locked_axes;
 mod mass_properties;
+#[cfg(feature = "physics-material")]
+mod physics_material;
+mod quantization;
+mod remote_body;
 mod rotation;
+mod thruster;
+mod time_dilation;
+mod tire_friction;
+mod velocity_provider;
 mod world_queries;
 
+pub use attitude_control::*;
+#[cfg(feature = "baked-colliders")]
+pub use baked_collider::*;
+#[cfg(all(feature = "3d", feature = "bone-collider"))]
+pub use bone_collider::*;
 pub use collider::*;
 pub use forces::*;
 pub use layers::*;
 pub use locked_axes::*;
 pub use mass_properties::*;
+#[cfg(feature = "physics-material")]
+pub use physics_material::*;
+pub use quantization::*;
+pub use remote_body::*;
 pub use rotation::*;
+pub use thruster::*;
+pub use time_dilation::*;
+pub use tire_friction::*;
+pub use velocity_provider::*;
 pub use world_queries::*;
 
 use crate::prelude::*;
@@ -85,7 +111,10 @@ use derive_more::From;
 /// Note that the mass properties of colliders are added on top of the existing mass properties, so if you
 /// want to define the body's mass properties explicitly, you might want to add
 /// [`ColliderMassProperties::ZERO`](ColliderMassProperties#associatedconstant.ZERO) to the colliders.
+///
+/// To only move the center of mass computed from the colliders, add a [`CenterOfMassOverride`].
 #[derive(Reflect, Clone, Copy, Component, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub enum RigidBody {
     /// Dynamic bodies are bodies that are affected by forces, velocity and collisions.
@@ -140,6 +169,7 @@ impl RigidBody {
 /// Sleeping can be disabled for specific entities with the [`SleepingDisabled`] component,
 /// or for all entities by setting the [`SleepingThreshold`] to a negative value.
 #[derive(Reflect, Clone, Copy, Component, Debug, Default, PartialEq, Eq, From)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct Sleeping;
 
@@ -148,21 +178,75 @@ pub struct Sleeping;
 ///
 /// See [`Sleeping`] for further information.
 #[derive(Reflect, Clone, Copy, Component, Debug, Default, PartialEq, From)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct TimeSleeping(pub Scalar);
 
 /// Indicates that the body can not be deactivated by the physics engine. See [`Sleeping`] for information about sleeping.
 #[derive(Reflect, Clone, Copy, Component, Debug, Default, PartialEq, Eq, From)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct SleepingDisabled;
 
+/// The sleep state of an assembly of [dynamic](RigidBody::Dynamic) bodies that are connected by [joints],
+/// like the chassis and wheels of a vehicle.
+///
+/// Bodies in an assembly sleep and wake up together: the assembly only falls asleep once all of its bodies
+/// have been still for long enough, and waking up any of them, for example by touching a wheel,
+/// wakes up the whole assembly. See [`Sleeping`] for more information about sleeping.
+///
+/// The component is added automatically to a representative body of each assembly, which is the body with
+/// the smallest [`Entity`], usually the one that was spawned first. It is removed when the body
+/// is no longer the representative of an assembly.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn print_parked_vehicles(assemblies: Query<(Entity, &AssemblySleeping)>) {
+///     for (entity, assembly) in &assemblies {
+///         if assembly.is_sleeping() {
+///             println!("{entity:?} is parked with {} bodies", assembly.bodies().len());
+///         }
+///     }
+/// }
+/// ```
+#[derive(Reflect, Clone, Component, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct AssemblySleeping {
+    /// True if all bodies in the assembly are sleeping.
+    pub(crate) sleeping: bool,
+    /// The bodies in the assembly, ordered by their entities.
+    pub(crate) bodies: Vec<Entity>,
+}
+
+impl AssemblySleeping {
+    /// Returns true if all bodies in the assembly are [`Sleeping`].
+    pub fn is_sleeping(&self) -> bool {
+        self.sleeping
+    }
+
+    /// Returns the bodies in the assembly, including the representative body, ordered by their entities.
+    pub fn bodies(&self) -> &[Entity] {
+        &self.bodies
+    }
+}
+
 /// The position of a body.
 #[derive(Reflect, Clone, Copy, Component, Debug, Default, Deref, DerefMut, PartialEq, From)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct Position(pub Vector);
 
 /// The previous position of a body.
 #[derive(Reflect, Clone, Copy, Component, Debug, Default, Deref, DerefMut, PartialEq, From)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct PreviousPosition(pub Vector);
 
@@ -174,11 +258,13 @@ pub struct PreviousPosition(pub Vector);
 ///
 /// After each substep, actual [`Position`] is updated during [`SubstepSet::ApplyTranslation`].
 #[derive(Reflect, Clone, Copy, Component, Debug, Default, Deref, DerefMut, PartialEq, From)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct AccumulatedTranslation(pub Vector);
 
 /// The linear velocity of a body.
 #[derive(Reflect, Clone, Copy, Component, Debug, Default, Deref, DerefMut, PartialEq, From)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct LinearVelocity(pub Vector);
 
@@ -195,12 +281,14 @@ pub(crate) struct PreSolveLinearVelocity(pub Vector);
 /// The angular velocity of a body in radians. Positive values will result in counterclockwise rotation.
 #[cfg(feature = "2d")]
 #[derive(Reflect, Clone, Copy, Component, Debug, Default, PartialEq, From)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct AngularVelocity(pub Scalar);
 
 /// The angular velocity of a body in radians.
 #[cfg(feature = "3d")]
 #[derive(Reflect, Clone, Copy, Component, Debug, Default, Deref, DerefMut, PartialEq, From)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct AngularVelocity(pub Vector);
 
@@ -232,6 +320,7 @@ pub(crate) struct PreSolveAngularVelocity(pub Vector);
 #[derive(
     Component, Reflect, Debug, Clone, Copy, PartialEq, PartialOrd, Default, Deref, DerefMut, From,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct GravityScale(pub Scalar);
 
@@ -239,6 +328,7 @@ pub struct GravityScale(pub Scalar);
 ///
 /// When combine rules clash with each other, the following priority order is used: `Max > Multiply > Min > Average`.
 #[derive(Reflect, Clone, Copy, Component, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CoefficientCombine {
     // The discriminants allow priority ordering to work automatically via comparison methods
     /// Coefficients are combined by computing their average.
@@ -256,11 +346,14 @@ pub enum CoefficientCombine {
 /// This controls how bouncy a body is.
 ///
 /// The coefficient is between 0 and 1, where 0 corresponds to a **perfectly inelastic collision**, and 1 corresponds
-/// to a **perfectly elastic collision** that preserves all kinetic energy. The default coefficient is 0.3, and it currently
-/// can not be configured at a global level.
+/// to a **perfectly elastic collision** that preserves all kinetic energy. The default coefficient is 0.3, and it
+/// can be configured at a global level using the [`DefaultRestitution`] resource.
 ///
 /// When two bodies collide, their restitution coefficients are combined using the specified [`CoefficientCombine`] rule.
 ///
+/// Restitution is ignored when the bodies approach each other slower than the
+/// [restitution threshold](SolverConfig::restitution_threshold), so that bodies can come to rest.
+///
 /// ## Example
 ///
 /// Create a new [`Restitution`] component with a restitution coefficient of 0.4:
@@ -295,6 +388,7 @@ pub enum CoefficientCombine {
 #[doc(alias = "Bounciness")]
 #[doc(alias = "Elasticity")]
 #[derive(Reflect, Clone, Copy, Component, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct Restitution {
     /// The [coefficient of restitution](https://en.wikipedia.org/wiki/Coefficient_of_restitution).
@@ -386,10 +480,16 @@ impl From<Scalar> for Restitution {
 ///
 /// For surfaces that are at rest relative to each other, static friction is used.
 /// Once the static friction is overcome, the bodies will start sliding relative to each other, and dynamic friction is applied instead.
+/// Rolling friction resists the relative rolling of the bodies, and it is zero by default.
+/// For friction that depends on the direction of sliding, use [`AnisotropicFriction`],
+/// and for wheels, use [`TireFriction`].
 ///
 /// 0.0: No friction at all, the body slides indefinitely\
 /// 1.0: High friction\
 ///
+/// When two bodies are in contact, their friction coefficients are combined using the specified [`CoefficientCombine`] rule.
+/// Bodies without a [`Friction`] component use the [`DefaultFriction`] resource.
+///
 /// ## Example
 ///
 /// Create a new [`Friction`] component with dynamic and static friction coefficients of 0.4:
@@ -407,6 +507,12 @@ impl From<Scalar> for Restitution {
 /// Friction::new(0.4).with_static_coefficient(0.6)
 /// ```
 ///
+/// Add rolling friction so that rolling bodies like spheres eventually stop:
+///
+/// ```ignore
+/// Friction::new(0.4).with_rolling_coefficient(0.01)
+/// ```
+///
 /// Configure how the friction coefficients of two [`Friction`] components are combined with [`CoefficientCombine`]:
 ///
 /// ```ignore
@@ -430,13 +536,22 @@ impl From<Scalar> for Restitution {
 ///     Friction::new(0.4).with_combine_rule(CoefficientCombine::Multiply)
 /// );
 /// ```
+///
+/// New coefficients may be added in the future, so [`Friction`] can't be constructed with a struct literal
+/// outside of this crate. Use [`Friction::new`], [`Friction::ZERO`] or [`Friction::default`]
+/// with the `with_*` methods instead.
 #[derive(Reflect, Clone, Copy, Component, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
+#[non_exhaustive]
 pub struct Friction {
     /// Coefficient of dynamic friction.
     pub dynamic_coefficient: Scalar,
     /// Coefficient of static friction.
     pub static_coefficient: Scalar,
+    /// Coefficient of rolling friction, the distance from the contact point at which the contact force resists rolling.
+    /// It has the unit of length, and small values like 0.01 are usually enough to stop rolling spheres and capsules.
+    pub rolling_coefficient: Scalar,
     /// The coefficient combine rule used when two bodies collide.
     pub combine_rule: CoefficientCombine,
 }
@@ -446,6 +561,7 @@ impl Friction {
     pub const ZERO: Self = Self {
         dynamic_coefficient: 0.0,
         static_coefficient: 0.0,
+        rolling_coefficient: 0.0,
         combine_rule: CoefficientCombine::Average,
     };
 
@@ -482,12 +598,21 @@ impl Friction {
         }
     }
 
+    /// Sets the coefficient of rolling friction.
+    pub fn with_rolling_coefficient(&self, coefficient: Scalar) -> Self {
+        Self {
+            rolling_coefficient: coefficient,
+            ..*self
+        }
+    }
+
     /// Combines the properties of two `Friction` components.
     pub fn combine(&self, other: Self) -> Self {
         // Choose rule with higher priority
         let rule = self.combine_rule.max(other.combine_rule);
         let (dynamic1, dynamic2) = (self.dynamic_coefficient, other.dynamic_coefficient);
         let (static1, static2) = (self.static_coefficient, other.static_coefficient);
+        let (rolling1, rolling2) = (self.rolling_coefficient, other.rolling_coefficient);
 
         Self {
             dynamic_coefficient: match rule {
@@ -502,6 +627,12 @@ impl Friction {
                 CoefficientCombine::Multiply => static1 * static2,
                 CoefficientCombine::Max => static1.max(static2),
             },
+            rolling_coefficient: match rule {
+                CoefficientCombine::Average => (rolling1 + rolling2) * 0.5,
+                CoefficientCombine::Min => rolling1.min(rolling2),
+                CoefficientCombine::Multiply => rolling1 * rolling2,
+                CoefficientCombine::Max => rolling1.max(rolling2),
+            },
             combine_rule: rule,
         }
     }
@@ -512,6 +643,7 @@ impl Default for Friction {
         Self {
             dynamic_coefficient: 0.3,
             static_coefficient: 0.3,
+            rolling_coefficient: 0.0,
             combine_rule: CoefficientCombine::default(),
         }
     }
@@ -527,6 +659,89 @@ impl From<Scalar> for Friction {
     }
 }
 
+/// Makes the static and dynamic [friction](Friction) of a [collider](Collider) or a [rigid body](RigidBody)
+/// depend on the direction of sliding, for example for skis, sleds and treads.
+///
+/// On a collider, the friction direction is given in the local space of the collider, and only the contacts
+/// of that collider are affected. On a rigid body, the direction is given in the local space of the body,
+/// and the contacts of all of its colliders that don't have their own [`AnisotropicFriction`] are affected.
+///
+/// When the collider slides along the direction, the [along coefficient](#structfield.along_coefficient) is used,
+/// and when it slides perpendicular to it, the [across coefficient](#structfield.across_coefficient) is used.
+/// Other directions interpolate between the two elliptically. The coefficients replace the static and dynamic
+/// coefficients of the [`Friction`] of the body before they are combined with the friction of the other body using the [`CoefficientCombine`] rule of the [`Friction`].
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::{math::*, prelude::*};
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::{math::*, prelude::*};
+///
+/// fn setup(mut commands: Commands) {
+///     // A ski that slides easily forwards but grips sideways
+///     commands.spawn((
+///         RigidBody::Dynamic,
+///         AnisotropicFriction::new(Vector::X, 0.02, 0.8),
+///     ));
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Component, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct AnisotropicFriction {
+    /// The friction direction in the local space of the collider or body. This should be normalized.
+    pub direction: Vector,
+    /// The friction coefficient used when sliding along the [direction](#structfield.direction).
+    pub along_coefficient: Scalar,
+    /// The friction coefficient used when sliding perpendicular to the [direction](#structfield.direction).
+    pub across_coefficient: Scalar,
+}
+
+impl Default for AnisotropicFriction {
+    /// Isotropic friction with the default friction coefficient along and across the x axis.
+    fn default() -> Self {
+        let coefficient = Friction::default().dynamic_coefficient;
+        Self::new(Vector::X, coefficient, coefficient)
+    }
+}
+
+impl AnisotropicFriction {
+    /// Creates a new [`AnisotropicFriction`] component with the given local direction
+    /// and the coefficients used along and across it.
+    pub fn new(direction: Vector, along_coefficient: Scalar, across_coefficient: Scalar) -> Self {
+        Self {
+            direction: direction.normalize_or_zero(),
+            along_coefficient,
+            across_coefficient,
+        }
+    }
+
+    /// Returns the friction coefficient for sliding in the given local direction.
+    pub fn coefficient(&self, local_sliding_direction: Vector) -> Scalar {
+        let along = self
+            .direction
+            .dot(local_sliding_direction.normalize_or_zero());
+        let across_squared = (1.0 - along * along).max(0.0);
+        (self.along_coefficient.powi(2) * along * along
+            + self.across_coefficient.powi(2) * across_squared)
+            .sqrt()
+    }
+
+    /// Returns the given [`Friction`] with its static and dynamic coefficients replaced by
+    /// the coefficient for sliding in the given local direction.
+    pub fn apply_to(&self, friction: Friction, local_sliding_direction: Vector) -> Friction {
+        let coefficient = self.coefficient(local_sliding_direction);
+        Friction {
+            dynamic_coefficient: coefficient,
+            static_coefficient: coefficient,
+            ..friction
+        }
+    }
+}
+
 /// Automatically slows down a dynamic [rigid body](RigidBody), decreasing it's [linear velocity](LinearVelocity)
 /// each frame. This can be used to simulate air resistance.
 ///
@@ -0,0 +1,94 @@
+use bevy::{prelude::*, reflect::TypeUuid};
+
+use crate::prelude::*;
+
+/// An asset that bundles the [`Friction`], [`Restitution`] and density of a collider so that they can be shared
+/// between entities and edited in one place.
+///
+/// Add a `Handle<PhysicsMaterial>` to an entity to apply the material to it. The [`Friction`] and [`Restitution`]
+/// of the entity are replaced with the ones of the material, and the [`ColliderDensity`] is set if the entity
+/// has a [`Collider`]. The material is applied again when the handle changes or when the asset
+/// is modified, for example by hot reloading.
+///
+/// Friction and restitution are used from the [rigid body](RigidBody), so the material should be added to the body
+/// when its [`Collider`] is on the same entity.
+///
+/// Requires the `physics-material` feature and Bevy's `AssetPlugin`.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn setup(mut commands: Commands, mut materials: ResMut<Assets<PhysicsMaterial>>) {
+///     let ice = materials.add(PhysicsMaterial::new(
+///         Friction::new(0.02).with_combine_rule(CoefficientCombine::Min),
+///         Restitution::new(0.1),
+///         0.9,
+///     ));
+///
+///     // Both bodies share the same material
+///     for x in [-2.0, 2.0] {
+///         commands.spawn((
+///             RigidBody::Dynamic,
+///             Collider::ball(0.5),
+///             TransformBundle::from_transform(Transform::from_xyz(x, 0.0, 0.0)),
+///             ice.clone(),
+///         ));
+///     }
+/// }
+/// ```
+#[derive(TypeUuid, Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[uuid = "2b8efa71-b6b2-4890-a6c8-20c84f490281"]
+pub struct PhysicsMaterial {
+    /// The friction of the material, including its combine rule.
+    pub friction: Friction,
+    /// The restitution of the material, including its combine rule.
+    pub restitution: Restitution,
+    /// The density of the material that the mass properties of colliders are computed from.
+    pub density: Scalar,
+}
+
+impl PhysicsMaterial {
+    /// Creates a new [`PhysicsMaterial`] with the given friction, restitution and density.
+    pub fn new(friction: Friction, restitution: Restitution, density: Scalar) -> Self {
+        Self {
+            friction,
+            restitution,
+            density,
+        }
+    }
+
+    /// Sets the [`Friction`] of the material.
+    pub fn with_friction(self, friction: Friction) -> Self {
+        Self { friction, ..self }
+    }
+
+    /// Sets the [`Restitution`] of the material.
+    pub fn with_restitution(self, restitution: Restitution) -> Self {
+        Self {
+            restitution,
+            ..self
+        }
+    }
+
+    /// Sets the density of the material.
+    pub fn with_density(self, density: Scalar) -> Self {
+        Self { density, ..self }
+    }
+}
+
+impl Default for PhysicsMaterial {
+    fn default() -> Self {
+        Self {
+            friction: Friction::default(),
+            restitution: Restitution::default(),
+            density: 1.0,
+        }
+    }
+}
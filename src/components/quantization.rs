@@ -0,0 +1,354 @@
+//! Quantization of body state into compact byte payloads for replication.
+
+use crate::prelude::*;
+
+/// The number of components in a [`Vector`].
+#[cfg(feature = "2d")]
+const VECTOR_DIM: usize = 2;
+/// The number of components in a [`Vector`].
+#[cfg(feature = "3d")]
+const VECTOR_DIM: usize = 3;
+
+/// The number of components in an [`AngularVelocity`].
+#[cfg(feature = "2d")]
+const ANGULAR_DIM: usize = 1;
+/// The number of components in an [`AngularVelocity`].
+#[cfg(feature = "3d")]
+const ANGULAR_DIM: usize = 3;
+
+/// A quantized [`Rotation`]. The angle is mapped to the full range of a `u16`.
+#[cfg(feature = "2d")]
+pub type QuantizedRotation = u16;
+/// A quantized [`Rotation`] in the "smallest three" encoding.
+///
+/// The two highest bits store the index of the quaternion component with the largest magnitude,
+/// and the three other components are stored with 10 bits each.
+#[cfg(feature = "3d")]
+pub type QuantizedRotation = u32;
+
+/// The state of a body that can be [quantized](StateQuantizer) for replication.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BodyState {
+    /// The position of the body.
+    pub position: Position,
+    /// The rotation of the body.
+    pub rotation: Rotation,
+    /// The linear velocity of the body.
+    pub linear_velocity: LinearVelocity,
+    /// The angular velocity of the body.
+    pub angular_velocity: AngularVelocity,
+}
+
+impl BodyState {
+    /// Creates a new [`BodyState`] from the given components.
+    pub fn new(
+        position: Position,
+        rotation: Rotation,
+        linear_velocity: LinearVelocity,
+        angular_velocity: AngularVelocity,
+    ) -> Self {
+        Self {
+            position,
+            rotation,
+            linear_velocity,
+            angular_velocity,
+        }
+    }
+
+    /// Computes the [`StateError`] between this state and the `other` state.
+    ///
+    /// This can be used to measure the precision lost by quantization,
+    /// or to decide whether the replicated state of a body should be corrected.
+    pub fn error(&self, other: &Self) -> StateError {
+        #[cfg(feature = "2d")]
+        let (rotation, angular_velocity) = (
+            self.rotation
+                .mul(other.rotation.inverse())
+                .as_radians()
+                .abs(),
+            (self.angular_velocity.0 - other.angular_velocity.0).abs(),
+        );
+        #[cfg(feature = "3d")]
+        let (rotation, angular_velocity) = (
+            self.rotation.angle_between(other.rotation.0),
+            self.angular_velocity.distance(other.angular_velocity.0),
+        );
+
+        StateError {
+            position: self.position.distance(other.position.0),
+            rotation,
+            linear_velocity: self.linear_velocity.distance(other.linear_velocity.0),
+            angular_velocity,
+        }
+    }
+}
+
+/// The difference between two [body states](BodyState). See [`BodyState::error`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StateError {
+    /// The distance between the positions.
+    pub position: Scalar,
+    /// The angle between the rotations in radians.
+    pub rotation: Scalar,
+    /// The magnitude of the difference between the linear velocities.
+    pub linear_velocity: Scalar,
+    /// The magnitude of the difference between the angular velocities.
+    pub angular_velocity: Scalar,
+}
+
+/// A [`BodyState`] quantized by a [`StateQuantizer`].
+///
+/// It can be converted into a compact byte payload with [`to_bytes`](#method.to_bytes)
+/// and back with [`from_bytes`](#method.from_bytes).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct QuantizedBodyState {
+    /// The position in multiples of the [position precision](StateQuantizer::position_precision).
+    pub position: [i32; VECTOR_DIM],
+    /// The quantized rotation.
+    pub rotation: QuantizedRotation,
+    /// The linear velocity relative to the [maximum linear speed](StateQuantizer::max_linear_speed).
+    pub linear_velocity: [i16; VECTOR_DIM],
+    /// The angular velocity relative to the [maximum angular speed](StateQuantizer::max_angular_speed).
+    pub angular_velocity: [i16; ANGULAR_DIM],
+}
+
+impl QuantizedBodyState {
+    /// The number of bytes in the payload created by [`to_bytes`](#method.to_bytes).
+    pub const BYTE_LEN: usize = VECTOR_DIM * 4
+        + std::mem::size_of::<QuantizedRotation>()
+        + VECTOR_DIM * 2
+        + ANGULAR_DIM * 2;
+
+    /// Encodes the state into a little-endian byte payload of [`BYTE_LEN`](Self::BYTE_LEN) bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::BYTE_LEN);
+        for value in self.position {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&self.rotation.to_le_bytes());
+        for value in self.linear_velocity.iter().chain(&self.angular_velocity) {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Decodes a state from a byte payload created by [`to_bytes`](#method.to_bytes).
+    ///
+    /// Returns `None` if the payload doesn't have exactly [`BYTE_LEN`](Self::BYTE_LEN) bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::BYTE_LEN {
+            return None;
+        }
+
+        let mut state = Self::default();
+        let (position_bytes, rest) = bytes.split_at(VECTOR_DIM * 4);
+        let (rotation_bytes, velocity_bytes) =
+            rest.split_at(std::mem::size_of::<QuantizedRotation>());
+
+        for (value, chunk) in state
+            .position
+            .iter_mut()
+            .zip(position_bytes.chunks_exact(4))
+        {
+            *value = i32::from_le_bytes(chunk.try_into().ok()?);
+        }
+        state.rotation = QuantizedRotation::from_le_bytes(rotation_bytes.try_into().ok()?);
+        for (value, chunk) in state
+            .linear_velocity
+            .iter_mut()
+            .chain(state.angular_velocity.iter_mut())
+            .zip(velocity_bytes.chunks_exact(2))
+        {
+            *value = i16::from_le_bytes(chunk.try_into().ok()?);
+        }
+
+        Some(state)
+    }
+}
+
+/// Quantizes [body states](BodyState) into [`QuantizedBodyState`]s with a fixed precision,
+/// for example for replicating bodies over the network.
+///
+/// - Positions are stored in multiples of the [`position_precision`](#structfield.position_precision).
+/// - Rotations are stored as angles with 16 bits in 2D, and with the "smallest three" quaternion
+/// encoding with 32 bits in 3D.
+/// - Velocities are stored with 16 bits per component, and they are clamped to the
+/// [`max_linear_speed`](#structfield.max_linear_speed) and [`max_angular_speed`](#structfield.max_angular_speed).
+///
+/// ## Example
+///
+/// ```
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn replicate(position: &Position, rotation: &Rotation, lin_vel: &LinearVelocity, ang_vel: &AngularVelocity) {
+///     let quantizer = StateQuantizer::default();
+///     let state = BodyState::new(*position, *rotation, *lin_vel, *ang_vel);
+///
+///     let payload = quantizer.quantize(&state).to_bytes();
+///
+///     // On the receiving side
+///     let received = QuantizedBodyState::from_bytes(&payload).unwrap();
+///     let replicated_state = quantizer.dequantize(&received);
+///     println!("Quantization error: {:?}", state.error(&replicated_state));
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StateQuantizer {
+    /// The precision of positions. Positions are rounded to the closest multiple of this value.
+    ///
+    /// The default is `1.0 / 1024.0`, which covers positions up to about 2 million units from the origin.
+    pub position_precision: Scalar,
+    /// The maximum linear speed that can be represented. Faster velocities are clamped per component.
+    ///
+    /// The default is `100.0`.
+    pub max_linear_speed: Scalar,
+    /// The maximum angular speed in radians per second that can be represented.
+    /// Faster angular velocities are clamped per component.
+    ///
+    /// The default is `50.0`.
+    pub max_angular_speed: Scalar,
+}
+
+impl Default for StateQuantizer {
+    fn default() -> Self {
+        Self {
+            position_precision: 1.0 / 1024.0,
+            max_linear_speed: 100.0,
+            max_angular_speed: 50.0,
+        }
+    }
+}
+
+impl StateQuantizer {
+    /// Quantizes the given [`BodyState`].
+    pub fn quantize(&self, state: &BodyState) -> QuantizedBodyState {
+        let mut quantized = QuantizedBodyState {
+            rotation: quantize_rotation(state.rotation),
+            ..Default::default()
+        };
+
+        for (value, component) in quantized.position.iter_mut().zip(state.position.to_array()) {
+            *value = (component / self.position_precision)
+                .round()
+                .clamp(i32::MIN as Scalar, i32::MAX as Scalar) as i32;
+        }
+        for (value, component) in quantized
+            .linear_velocity
+            .iter_mut()
+            .zip(state.linear_velocity.to_array())
+        {
+            *value = quantize_unit(component / self.max_linear_speed);
+        }
+
+        #[cfg(feature = "2d")]
+        let angular_velocity = [state.angular_velocity.0];
+        #[cfg(feature = "3d")]
+        let angular_velocity = state.angular_velocity.to_array();
+        for (value, component) in quantized.angular_velocity.iter_mut().zip(angular_velocity) {
+            *value = quantize_unit(component / self.max_angular_speed);
+        }
+
+        quantized
+    }
+
+    /// Restores a [`BodyState`] from the given [`QuantizedBodyState`].
+    pub fn dequantize(&self, state: &QuantizedBodyState) -> BodyState {
+        let position = state
+            .position
+            .map(|value| value as Scalar * self.position_precision);
+        let linear_velocity = state
+            .linear_velocity
+            .map(|value| dequantize_unit(value) * self.max_linear_speed);
+        let angular_velocity = state
+            .angular_velocity
+            .map(|value| dequantize_unit(value) * self.max_angular_speed);
+
+        BodyState {
+            position: Position(Vector::from_array(position)),
+            rotation: dequantize_rotation(state.rotation),
+            linear_velocity: LinearVelocity(Vector::from_array(linear_velocity)),
+            #[cfg(feature = "2d")]
+            angular_velocity: AngularVelocity(angular_velocity[0]),
+            #[cfg(feature = "3d")]
+            angular_velocity: AngularVelocity(Vector::from_array(angular_velocity)),
+        }
+    }
+}
+
+/// Maps a value in the `[-1.0, 1.0]` range to an `i16`. Values outside of the range are clamped.
+fn quantize_unit(value: Scalar) -> i16 {
+    (value.clamp(-1.0, 1.0) * i16::MAX as Scalar).round() as i16
+}
+
+/// Maps an `i16` created by [`quantize_unit`] back to the `[-1.0, 1.0]` range.
+fn dequantize_unit(value: i16) -> Scalar {
+    (value as Scalar / i16::MAX as Scalar).max(-1.0)
+}
+
+#[cfg(feature = "2d")]
+fn quantize_rotation(rotation: Rotation) -> QuantizedRotation {
+    let normalized = (rotation.as_radians() + PI) / (2.0 * PI);
+    (normalized * u16::MAX as Scalar).round() as u16
+}
+
+#[cfg(feature = "2d")]
+fn dequantize_rotation(rotation: QuantizedRotation) -> Rotation {
+    Rotation::from_radians(rotation as Scalar / u16::MAX as Scalar * 2.0 * PI - PI)
+}
+
+/// The largest possible magnitude of the three smallest components of a unit quaternion.
+#[cfg(feature = "3d")]
+const SMALLEST_THREE_MAX: Scalar = std::f64::consts::FRAC_1_SQRT_2 as Scalar;
+
+/// The largest value that fits in the 10 bits used for each of the smallest three components.
+#[cfg(feature = "3d")]
+const SMALLEST_THREE_COMPONENT_MAX: u32 = (1 << 10) - 1;
+
+#[cfg(feature = "3d")]
+fn quantize_rotation(rotation: Rotation) -> QuantizedRotation {
+    let components = rotation.normalize().to_array();
+    let largest_index = (0..4)
+        .max_by(|&i, &j| components[i].abs().total_cmp(&components[j].abs()))
+        .unwrap_or(3);
+
+    // `q` and `-q` represent the same rotation, so the largest component can always be made positive
+    let sign = components[largest_index].signum();
+
+    let mut bits = (largest_index as u32) << 30;
+    let mut shift = 20;
+    for (i, component) in components.into_iter().enumerate() {
+        if i == largest_index {
+            continue;
+        }
+        let normalized = (component * sign / SMALLEST_THREE_MAX).clamp(-1.0, 1.0) * 0.5 + 0.5;
+        let value = (normalized * SMALLEST_THREE_COMPONENT_MAX as Scalar).round() as u32;
+        bits |= value << shift;
+        shift -= 10;
+    }
+    bits
+}
+
+#[cfg(feature = "3d")]
+fn dequantize_rotation(rotation: QuantizedRotation) -> Rotation {
+    let largest_index = (rotation >> 30) as usize;
+
+    let mut components = [0.0; 4];
+    let mut shift = 20;
+    let mut length_squared = 0.0;
+    for (i, component) in components.iter_mut().enumerate() {
+        if i == largest_index {
+            continue;
+        }
+        let value = (rotation >> shift) & SMALLEST_THREE_COMPONENT_MAX;
+        *component = (value as Scalar / SMALLEST_THREE_COMPONENT_MAX as Scalar * 2.0 - 1.0)
+            * SMALLEST_THREE_MAX;
+        length_squared += *component * *component;
+        shift -= 10;
+    }
+    components[largest_index] = (1.0 - length_squared).max(0.0).sqrt();
+
+    Rotation(Quaternion::from_array(components).normalize())
+}
@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+
+use crate::prelude::*;
+
+/// A component for [rigid bodies](RigidBody) whose pose is driven by an authoritative remote source,
+/// like a network server.
+///
+/// Instead of snapping the body to the received pose or making it [kinematic](RigidBody::Kinematic),
+/// the body is pulled towards the [target pose](#structfield.target_position) by a compliant constraint
+/// in the solver. This corrects the body smoothly while it still collides with other bodies locally.
+/// The correction moves the body without adding velocity to it, so the body doesn't overshoot the target.
+///
+/// The [compliance](#structfield.compliance) is the inverse of the stiffness of the constraint.
+/// A compliance of zero snaps the body to the target on each substep, while larger values
+/// make the correction softer. Heavier bodies are corrected more slowly for a given compliance.
+///
+/// Only dynamic bodies are affected, and [sleeping](Sleeping) bodies are skipped,
+/// so consider adding [`SleepingDisabled`] to remote bodies.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// /// A pose received from the server.
+/// #[derive(Event)]
+/// struct ServerUpdate {
+///     entity: Entity,
+///     position: Position,
+///     rotation: Rotation,
+/// }
+///
+/// fn apply_server_updates(
+///     mut updates: EventReader<ServerUpdate>,
+///     mut remote_bodies: Query<&mut RemoteBody>,
+/// ) {
+///     for update in updates.iter() {
+///         if let Ok(mut remote_body) = remote_bodies.get_mut(update.entity) {
+///             remote_body.set_target(update.position.0, update.rotation);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct RemoteBody {
+    /// The authoritative position that the body is pulled towards.
+    pub target_position: Vector,
+    /// The authoritative rotation that the body is pulled towards.
+    pub target_rotation: Rotation,
+    /// The compliance of the positional correction, the inverse of stiffness, has the unit meters / Newton.
+    pub compliance: Scalar,
+    /// The compliance of the rotational correction, the inverse of stiffness, has the unit radians / (Newton * meter).
+    pub angular_compliance: Scalar,
+}
+
+impl Default for RemoteBody {
+    fn default() -> Self {
+        Self::new(Vector::ZERO, Rotation::default())
+    }
+}
+
+impl RemoteBody {
+    /// The default [compliance](#structfield.compliance) and [angular compliance](#structfield.angular_compliance).
+    pub const DEFAULT_COMPLIANCE: Scalar = 0.0001;
+
+    /// Creates a new [`RemoteBody`] with the given target pose and the
+    /// [default compliance](Self::DEFAULT_COMPLIANCE).
+    pub fn new(target_position: Vector, target_rotation: Rotation) -> Self {
+        Self {
+            target_position,
+            target_rotation,
+            compliance: Self::DEFAULT_COMPLIANCE,
+            angular_compliance: Self::DEFAULT_COMPLIANCE,
+        }
+    }
+
+    /// Sets the [compliance](#structfield.compliance) of the positional correction.
+    pub fn with_compliance(self, compliance: Scalar) -> Self {
+        Self { compliance, ..self }
+    }
+
+    /// Sets the [compliance](#structfield.angular_compliance) of the rotational correction.
+    pub fn with_angular_compliance(self, angular_compliance: Scalar) -> Self {
+        Self {
+            angular_compliance,
+            ..self
+        }
+    }
+
+    /// Sets the authoritative pose that the body is pulled towards.
+    pub fn set_target(&mut self, position: Vector, rotation: Rotation) {
+        self.target_position = position;
+        self.target_rotation = rotation;
+    }
+}
@@ -34,6 +34,7 @@ pub(crate) type RotationValue = Quaternion;
 /// ```
 #[cfg(feature = "2d")]
 #[derive(Reflect, Clone, Copy, Component, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct Rotation {
     /// The cosine of the rotation angle in radians.
@@ -58,6 +59,7 @@ pub struct Rotation {
 /// ```
 #[cfg(feature = "3d")]
 #[derive(Reflect, Clone, Copy, Component, Debug, Default, Deref, DerefMut, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct Rotation(pub Quaternion);
 
@@ -96,8 +98,8 @@ impl Rotation {
     /// Creates a [`Rotation`] from radians.
     pub fn from_radians(radians: Scalar) -> Self {
         Self {
-            cos: radians.cos(),
-            sin: radians.sin(),
+            cos: math::ops::cos(radians),
+            sin: math::ops::sin(radians),
         }
     }
 
@@ -113,7 +115,7 @@ impl Rotation {
 
     /// Returns the rotation in radians.
     pub fn as_radians(&self) -> Scalar {
-        Scalar::atan2(self.sin(), self.cos())
+        math::ops::atan2(self.sin(), self.cos())
     }
 
     /// Returns the rotation in degrees.
@@ -157,6 +159,11 @@ impl Rotation {
     pub fn inverse(&self) -> Self {
         Self(self.0.inverse())
     }
+
+    /// Multiplies the rotation by another rotation, applying `rhs` first.
+    pub fn mul(&self, rhs: Self) -> Self {
+        Self((self.0 * rhs.0).normalize())
+    }
 }
 
 #[cfg(feature = "2d")]
@@ -294,5 +301,6 @@ impl From<Rotation> for Matrix3x1<Scalar> {
 
 /// The previous rotation of a body. See [`Rotation`].
 #[derive(Reflect, Clone, Copy, Component, Debug, Default, Deref, DerefMut, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct PreviousRotation(pub Rotation);
@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+
+use crate::prelude::*;
+
+/// A propeller, jet engine or rocket thruster that pushes a [rigid body](RigidBody) from a mount point.
+///
+/// The thrust is applied every substep at the [`local_position`](#structfield.local_position) of the thruster
+/// along its [`direction`](#structfield.direction), so thrusters mounted away from the center of mass also cause torque.
+/// The thrust follows the [`throttle`](#structfield.throttle) input, taking [`spool_up_time`](#structfield.spool_up_time)
+/// to go from zero to the maximum thrust.
+///
+/// A thruster can be added to the rigid body itself, to one of its [colliders](Collider), or to another descendant entity
+/// of the body, which allows bodies like drones to have several thrusters. The position and direction are always
+/// in the local space of the body. Thrust wakes up [sleeping](Sleeping) bodies.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::{math::*, prelude::*};
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::{math::*, prelude::*};
+///
+/// fn setup(mut commands: Commands) {
+///     // A rocket with its engine at the bottom
+///     commands.spawn((
+///         RigidBody::Dynamic,
+///         Collider::ball(0.5),
+///         Thruster::new(Vector::NEG_Y * 0.5, Vector::Y, 50.0)
+///             .with_spool_up_time(0.5)
+///             .with_throttle(1.0),
+///     ));
+/// }
+///
+/// fn control_throttle(keyboard: Res<Input<KeyCode>>, mut thrusters: Query<&mut Thruster>) {
+///     for mut thruster in &mut thrusters {
+///         thruster.throttle = if keyboard.pressed(KeyCode::Space) { 1.0 } else { 0.0 };
+///     }
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Component, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct Thruster {
+    /// The mount point of the thruster in the local space of the body.
+    pub local_position: Vector,
+    /// The direction that the thruster pushes the body in the local space of the body.
+    pub direction: Vector,
+    /// The thrust force at full throttle.
+    pub max_thrust: Scalar,
+    /// The throttle input in the `[-1, 1]` range. Negative values reverse the thrust, for example for boat propellers.
+    pub throttle: Scalar,
+    /// The time in seconds that it takes for the thrust to go from zero to the maximum thrust.
+    /// Zero means that the thrust follows the throttle instantly.
+    pub spool_up_time: Scalar,
+    /// The current thrust force along the `direction`.
+    pub(crate) thrust: Scalar,
+}
+
+impl Default for Thruster {
+    fn default() -> Self {
+        Self::new(Vector::ZERO, Vector::Y, 0.0)
+    }
+}
+
+impl Thruster {
+    /// Creates a new thruster at the given local mount point, pushing the body in the given local direction
+    /// with the given thrust force at full throttle.
+    ///
+    /// The throttle is zero by default.
+    pub fn new(local_position: Vector, direction: Vector, max_thrust: Scalar) -> Self {
+        Self {
+            local_position,
+            direction: direction.normalize_or_zero(),
+            max_thrust,
+            throttle: 0.0,
+            spool_up_time: 0.0,
+            thrust: 0.0,
+        }
+    }
+
+    /// Sets the throttle input in the `[-1, 1]` range.
+    pub fn with_throttle(self, throttle: Scalar) -> Self {
+        Self { throttle, ..self }
+    }
+
+    /// Sets the time in seconds that it takes for the thrust to go from zero to the maximum thrust.
+    pub fn with_spool_up_time(self, spool_up_time: Scalar) -> Self {
+        Self {
+            spool_up_time,
+            ..self
+        }
+    }
+
+    /// Returns the current thrust force along the `direction`.
+    pub fn thrust(&self) -> Scalar {
+        self.thrust
+    }
+
+    /// Returns the current thrust force in the local space of the body.
+    pub fn local_force(&self) -> Vector {
+        self.direction * self.thrust
+    }
+
+    /// Moves the current thrust towards the thrust requested by the `throttle` over the given time step.
+    pub(crate) fn spool(&mut self, delta_seconds: Scalar) {
+        let target = self.throttle.clamp(-1.0, 1.0) * self.max_thrust;
+
+        if self.spool_up_time <= Scalar::EPSILON {
+            self.thrust = target;
+        } else {
+            let max_change = self.max_thrust.abs() / self.spool_up_time * delta_seconds;
+            self.thrust += (target - self.thrust).clamp(-max_change, max_change);
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+
+use crate::prelude::*;
+
+/// The smallest time scale that [`TimeDilationVolume`]s can slow bodies down to.
+///
+/// Velocities are computed by dividing the motion of a body by its scaled time step,
+/// so scales very close to zero would make them explode.
+pub const MIN_TIME_SCALE: Scalar = 0.01;
+
+/// A volume that slows down time for the [rigid bodies](RigidBody) inside of it, like a slow-motion bubble.
+///
+/// The volume is the [`Collider`] of the entity, which is usually a [`Sensor`]. Bodies whose [`Position`]
+/// is inside of the collider have their [`TimeDilation`] set to the [`scale`](#structfield.scale) of the volume.
+/// If a body is inside of several volumes, the smallest scale is used.
+///
+/// The velocities of a body stay the same when it enters a volume, but the body moves, accelerates and
+/// rotates slower, so a projectile flying into a volume slows down and speeds up again when it leaves.
+/// The [solver] takes the time scales into account when bodies interact, so contacts and joints
+/// between bodies with different time scales stay stable.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     // A bubble where time passes at a fifth of the normal speed
+///     commands.spawn((
+///         RigidBody::Static,
+///         Collider::ball(5.0),
+///         Sensor,
+///         TimeDilationVolume::new(0.2),
+///     ));
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Component, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct TimeDilationVolume {
+    /// The time scale of the bodies inside of the volume in the `[MIN_TIME_SCALE, 1]` range.
+    /// Smaller values slow bodies down more.
+    pub scale: Scalar,
+}
+
+impl Default for TimeDilationVolume {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl TimeDilationVolume {
+    /// Creates a new time dilation volume with the given time scale.
+    /// The scale is clamped to the `[MIN_TIME_SCALE, 1]` range.
+    pub fn new(scale: Scalar) -> Self {
+        Self {
+            scale: scale.clamp(MIN_TIME_SCALE, 1.0),
+        }
+    }
+}
+
+/// The time scale of a [rigid body](RigidBody), which is 1.0 by default.
+///
+/// A body with a time scale of `0.5` is integrated and solved as if only half of the time step
+/// had passed for it. This is set automatically for the bodies inside of [`TimeDilationVolume`]s.
+#[derive(Reflect, Clone, Copy, Component, Debug, Deref, DerefMut, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct TimeDilation(pub Scalar);
+
+impl Default for TimeDilation {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
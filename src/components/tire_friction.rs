@@ -0,0 +1,124 @@
+use bevy::prelude::*;
+
+use crate::prelude::*;
+
+/// A tire friction model for wheels that replaces the static and dynamic [`Friction`] of the contacts
+/// of a [rigid body](RigidBody) with forces computed from the slip of the wheel.
+///
+/// The relative velocity at each contact is decomposed into a longitudinal part along the rolling direction
+/// of the wheel and a lateral part along its [axle](#structfield.axle). The slip ratio and the slip angle are
+/// then mapped to friction coefficients using the [longitudinal](#structfield.longitudinal) and
+/// [lateral](#structfield.lateral) [slip curves](SlipCurve), and the resulting forces are limited to a friction ellipse.
+///
+/// In 2D, the wheel rotates around the `z` axis, so only the longitudinal slip curve is used.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     // A wheel with less lateral grip, for example for drifting
+///     commands.spawn((
+///         RigidBody::Dynamic,
+///         Collider::ball(0.4),
+///         TireFriction::default().with_lateral(SlipCurve::new(8.0, 1.3, 0.7, 0.9)),
+///     ));
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Component, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct TireFriction {
+    /// The axis that the wheel rotates around in the local space of the body.
+    #[cfg(feature = "3d")]
+    pub axle: Vector,
+    /// The slip curve used for the slip ratio along the rolling direction.
+    pub longitudinal: SlipCurve,
+    /// The slip curve used for the slip angle in radians. Only used in 3D.
+    pub lateral: SlipCurve,
+    /// The minimum speed used for normalizing the slip, which avoids huge slip values
+    /// when the wheel is almost at rest.
+    pub min_speed: Scalar,
+}
+
+impl Default for TireFriction {
+    fn default() -> Self {
+        Self {
+            #[cfg(feature = "3d")]
+            axle: Vector::X,
+            longitudinal: SlipCurve::new(10.0, 1.9, 1.0, 0.97),
+            lateral: SlipCurve::new(10.0, 1.3, 1.0, 0.97),
+            min_speed: 0.5,
+        }
+    }
+}
+
+impl TireFriction {
+    /// Sets the axis that the wheel rotates around in the local space of the body.
+    #[cfg(feature = "3d")]
+    pub fn with_axle(self, axle: Vector) -> Self {
+        Self {
+            axle: axle.normalize_or_zero(),
+            ..self
+        }
+    }
+
+    /// Sets the slip curve used for the slip ratio along the rolling direction.
+    pub fn with_longitudinal(self, longitudinal: SlipCurve) -> Self {
+        Self {
+            longitudinal,
+            ..self
+        }
+    }
+
+    /// Sets the slip curve used for the slip angle.
+    pub fn with_lateral(self, lateral: SlipCurve) -> Self {
+        Self { lateral, ..self }
+    }
+
+    /// Sets the minimum speed used for normalizing the slip.
+    pub fn with_min_speed(self, min_speed: Scalar) -> Self {
+        Self { min_speed, ..self }
+    }
+}
+
+/// A simplified Pacejka "magic formula" curve that maps the slip of a [tire](TireFriction)
+/// to a friction coefficient:
+///
+/// `coefficient = peak * sin(shape * atan(stiffness * slip - curvature * (stiffness * slip - atan(stiffness * slip))))`
+#[derive(Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SlipCurve {
+    /// The stiffness factor (B), which controls how quickly the friction grows with small slip.
+    pub stiffness: Scalar,
+    /// The shape factor (C), which controls how much the friction drops after the peak.
+    pub shape: Scalar,
+    /// The peak friction coefficient (D).
+    pub peak: Scalar,
+    /// The curvature factor (E), which controls the sharpness of the peak.
+    pub curvature: Scalar,
+}
+
+impl SlipCurve {
+    /// Creates a new [`SlipCurve`] with the given stiffness, shape, peak and curvature factors.
+    pub fn new(stiffness: Scalar, shape: Scalar, peak: Scalar, curvature: Scalar) -> Self {
+        Self {
+            stiffness,
+            shape,
+            peak,
+            curvature,
+        }
+    }
+
+    /// Returns the friction coefficient for the given slip. The sign of the coefficient matches the sign of the slip.
+    pub fn evaluate(&self, slip: Scalar) -> Scalar {
+        let x = self.stiffness * slip;
+        let curved = x - self.curvature * (x - math::ops::atan(x));
+        self.peak * math::ops::sin(self.shape * math::ops::atan(curved))
+    }
+}
@@ -0,0 +1,120 @@
+use crate::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+type VelocityProviderComponents = (
+    &'static Position,
+    &'static Rotation,
+    &'static LinearVelocity,
+    &'static AngularVelocity,
+    Option<&'static CenterOfMass>,
+    Option<&'static TimeDilation>,
+);
+
+/// A system parameter for getting the world-space velocities of points on [rigid bodies](RigidBody).
+///
+/// The velocity of a point includes the contribution of the body's [`AngularVelocity`], so points far away
+/// from the center of mass of a spinning body move faster than the body itself. This is useful for things like
+/// computing the doppler effect for audio sources and inheriting the velocity of particle emitters.
+///
+/// The entity doesn't have to be a rigid body. If it isn't, the velocity of the closest ancestor that is
+/// a rigid body is used, so audio sources and emitters can be children of the body they are attached to.
+///
+/// Velocities are returned as seen from outside of any [`TimeDilationVolume`], so bodies that are slowed down
+/// by time dilation also sound and look slowed down. For the velocity of a point without any [`Query`],
+/// see [`point_velocity`].
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// #[derive(Component)]
+/// struct Engine;
+///
+/// # #[cfg(all(feature = "3d", feature = "f32"))]
+/// fn print_engine_velocities(
+///     engines: Query<(Entity, &GlobalTransform), With<Engine>>,
+///     velocities: VelocityProvider,
+/// ) {
+///     for (entity, transform) in &engines {
+///         // The velocity of the engine, including the rotation of the ship it's attached to
+///         if let Some(velocity) = velocities.velocity_at_point(entity, transform.translation()) {
+///             println!("Engine velocity: {velocity}");
+///         }
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct VelocityProvider<'w, 's> {
+    bodies: Query<'w, 's, VelocityProviderComponents, With<RigidBody>>,
+    parents: Query<'w, 's, &'static Parent>,
+}
+
+impl<'w, 's> VelocityProvider<'w, 's> {
+    /// Returns the world-space velocity of the given world-space `point`, moving with the body that
+    /// the given entity belongs to.
+    ///
+    /// Returns `None` if neither the entity nor any of its ancestors is a rigid body.
+    pub fn velocity_at_point(&self, entity: Entity, point: Vector) -> Option<Vector> {
+        let (position, rotation, lin_vel, ang_vel, center_of_mass, time_dilation) =
+            self.bodies.get(self.body_entity(entity)?).ok()?;
+
+        let center_of_mass =
+            position.0 + rotation.rotate(center_of_mass.map_or(Vector::ZERO, |center| center.0));
+        let velocity = point_velocity(lin_vel.0, ang_vel.0, center_of_mass, point);
+
+        Some(velocity * time_dilation.map_or(1.0, |dilation| dilation.0))
+    }
+
+    /// Returns the world-space velocity of the center of mass of the body that the given entity belongs to.
+    ///
+    /// Returns `None` if neither the entity nor any of its ancestors is a rigid body.
+    pub fn linear_velocity(&self, entity: Entity) -> Option<Vector> {
+        let (_, _, lin_vel, _, _, time_dilation) =
+            self.bodies.get(self.body_entity(entity)?).ok()?;
+
+        Some(lin_vel.0 * time_dilation.map_or(1.0, |dilation| dilation.0))
+    }
+
+    /// Returns the entity itself if it is a rigid body, or its closest ancestor that is a rigid body.
+    fn body_entity(&self, mut entity: Entity) -> Option<Entity> {
+        while !self.bodies.contains(entity) {
+            entity = self.parents.get(entity).ok()?.get();
+        }
+        Some(entity)
+    }
+}
+
+/// Computes the world-space velocity of a world-space `point` on a body with the given velocities
+/// and world-space `center_of_mass`.
+///
+/// The linear velocity is the velocity of the center of mass, and the angular velocity adds
+/// the tangential velocity of the point rotating around the center of mass.
+#[cfg(feature = "2d")]
+pub fn point_velocity(
+    linear_velocity: Vector,
+    angular_velocity: Scalar,
+    center_of_mass: Vector,
+    point: Vector,
+) -> Vector {
+    linear_velocity + angular_velocity * (point - center_of_mass).perp()
+}
+
+/// Computes the world-space velocity of a world-space `point` on a body with the given velocities
+/// and world-space `center_of_mass`.
+///
+/// The linear velocity is the velocity of the center of mass, and the angular velocity adds
+/// the tangential velocity of the point rotating around the center of mass.
+#[cfg(feature = "3d")]
+pub fn point_velocity(
+    linear_velocity: Vector,
+    angular_velocity: Vector,
+    center_of_mass: Vector,
+    point: Vector,
+) -> Vector {
+    linear_velocity + angular_velocity.cross(point - center_of_mass)
+}
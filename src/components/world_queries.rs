@@ -5,6 +5,11 @@ use bevy::ecs::query::WorldQuery;
 use std::ops::{AddAssign, SubAssign};
 
 /// A [`WorldQuery`] to make querying and modifying rigid bodies more convenient.
+///
+/// This only contains the data that the solver reads and writes while solving constraints, like the poses,
+/// velocities and inverse masses of the bodies, so that iterating over the bodies stays cache friendly.
+/// The surface properties used for contacts are queried separately using [`ContactMaterialQuery`],
+/// and the mass properties using [`Mass`] and [`Inertia`] directly.
 #[derive(WorldQuery)]
 #[world_query(mutable)]
 pub struct RigidBodyQuery {
@@ -19,17 +24,40 @@ pub struct RigidBodyQuery {
     pub(crate) pre_solve_linear_velocity: &'static mut PreSolveLinearVelocity,
     pub angular_velocity: &'static mut AngularVelocity,
     pub(crate) pre_solve_angular_velocity: &'static mut PreSolveAngularVelocity,
-    pub mass: &'static mut Mass,
     pub inverse_mass: &'static mut InverseMass,
-    pub inertia: &'static mut Inertia,
     pub inverse_inertia: &'static mut InverseInertia,
     pub center_of_mass: &'static mut CenterOfMass,
-    pub friction: &'static mut Friction,
-    pub restitution: &'static mut Restitution,
+    pub center_of_mass_override: Option<&'static CenterOfMassOverride>,
     pub locked_axes: Option<&'static LockedAxes>,
+    pub time_dilation: Option<&'static TimeDilation>,
 }
 
 impl<'w> RigidBodyQueryItem<'w> {
+    /// Returns the time scale of the body from its [`TimeDilation`], or 1.0 if it has none.
+    pub fn time_scale(&self) -> Scalar {
+        self.time_dilation.map_or(1.0, |dilation| dilation.0)
+    }
+
+    /// Returns the linear velocity of the body as seen from outside of any [`TimeDilationVolume`].
+    ///
+    /// The [`LinearVelocity`] of a body is relative to its own time scale, so a slowed down body
+    /// moves slower than its velocity would suggest.
+    pub fn dilated_linear_velocity(&self) -> Vector {
+        self.linear_velocity.0 * self.time_scale()
+    }
+
+    /// Returns the angular velocity of the body as seen from outside of any [`TimeDilationVolume`].
+    #[cfg(feature = "2d")]
+    pub fn dilated_angular_velocity(&self) -> Scalar {
+        self.angular_velocity.0 * self.time_scale()
+    }
+
+    /// Returns the angular velocity of the body as seen from outside of any [`TimeDilationVolume`].
+    #[cfg(feature = "3d")]
+    pub fn dilated_angular_velocity(&self) -> Vector {
+        self.angular_velocity.0 * self.time_scale()
+    }
+
     /// Computes the effective inverse mass, taking into account any translation locking.
     pub fn effective_inv_mass(&self) -> Vector {
         let mut inv_mass = Vector::splat(self.inverse_mass.0);
@@ -65,6 +93,27 @@ impl<'w> RigidBodyQueryItem<'w> {
         inv_inertia
     }
 
+    /// Returns the local center of mass used by the solver, taking into account any [`CenterOfMassOverride`].
+    pub fn effective_center_of_mass(&self) -> Vector {
+        self.center_of_mass_override
+            .map_or(self.center_of_mass.0, |com_override| {
+                com_override.apply_to(self.center_of_mass.0)
+            })
+    }
+
+    /// Returns the world-space lever arm of a joint anchor given in the local space of the body.
+    ///
+    /// Joints use lever arms from the origin of the body, so the lever arm is only measured from
+    /// the center of mass if the body has a [`CenterOfMassOverride`].
+    pub fn joint_lever_arm(&self, local_anchor: Vector) -> Vector {
+        match self.center_of_mass_override {
+            Some(_) => self
+                .rotation
+                .rotate(local_anchor - self.effective_center_of_mass()),
+            None => self.rotation.rotate(local_anchor),
+        }
+    }
+
     /// Returns the current position of the body. This is a sum of the [`Position`] and
     /// [`AccumulatedTranslation`] components.
     pub fn current_position(&self) -> Vector {
@@ -72,6 +121,72 @@ impl<'w> RigidBodyQueryItem<'w> {
     }
 }
 
+/// A read-only [`WorldQuery`] for the surface properties of rigid bodies that are used for solving contacts.
+///
+/// See [`ContactMaterial`].
+#[derive(WorldQuery)]
+pub struct ContactMaterialQuery {
+    pub friction: &'static Friction,
+    pub anisotropic_friction: Option<&'static AnisotropicFriction>,
+    pub restitution: &'static Restitution,
+    pub tire_friction: Option<&'static TireFriction>,
+}
+
+impl<'w> ContactMaterialQueryItem<'w> {
+    /// Copies the surface properties into a [`ContactMaterial`].
+    pub fn material(&self) -> ContactMaterial {
+        ContactMaterial {
+            friction: *self.friction,
+            anisotropic_friction: self.anisotropic_friction.copied(),
+            restitution: *self.restitution,
+            tire_friction: self.tire_friction.copied(),
+        }
+    }
+}
+
+/// The surface properties of a rigid body that are used for solving its contacts.
+///
+/// They are gathered once for each pair of colliding bodies every substep and stored in the [`PenetrationConstraint`]s
+/// of the contacts, so that the solver doesn't need to fetch them from the bodies while solving.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ContactMaterial {
+    pub friction: Friction,
+    pub anisotropic_friction: Option<AnisotropicFriction>,
+    pub restitution: Restitution,
+    pub tire_friction: Option<TireFriction>,
+}
+
+impl ContactMaterial {
+    /// Replaces the [`AnisotropicFriction`] of the body with that of one of its colliders, if it has one.
+    /// The direction of the collider's anisotropic friction is transformed into the local space of the body.
+    pub fn with_collider_anisotropic_friction(
+        mut self,
+        anisotropic_friction: Option<&AnisotropicFriction>,
+        collider_transform: &ColliderTransform,
+    ) -> Self {
+        if let Some(anisotropic_friction) = anisotropic_friction {
+            self.anisotropic_friction = Some(AnisotropicFriction {
+                direction: collider_transform
+                    .rotation
+                    .rotate(anisotropic_friction.direction),
+                ..*anisotropic_friction
+            });
+        }
+        self
+    }
+
+    /// Returns the [`Friction`] for sliding in the given world-space direction, taking into account
+    /// any [`AnisotropicFriction`]. `rotation` is the rotation of the body.
+    pub fn friction_along(&self, rotation: &Rotation, direction: Vector) -> Friction {
+        match self.anisotropic_friction {
+            Some(anisotropic_friction) => {
+                anisotropic_friction.apply_to(self.friction, rotation.inverse().rotate(direction))
+            }
+            None => self.friction,
+        }
+    }
+}
+
 #[derive(WorldQuery)]
 #[world_query(mutable)]
 pub(crate) struct MassPropertiesQuery {
@@ -1,12 +1,20 @@
 //! [`DistanceJoint`] component.
 
 use crate::prelude::*;
-use bevy::prelude::*;
+use bevy::{
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+    prelude::*,
+};
 
 /// A distance joint keeps the attached bodies at a certain distance from each other while while allowing rotation around all axes.
 ///
 /// Distance joints can be useful for things like springs, muscles, and mass-spring networks.
-#[derive(Component, Clone, Copy, Debug, PartialEq)]
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component, MapEntities)]
 pub struct DistanceJoint {
     /// First entity constrained by the joint.
     pub entity1: Entity,
@@ -32,6 +40,20 @@ pub struct DistanceJoint {
     pub force: Vector,
 }
 
+impl Default for DistanceJoint {
+    /// Creates a joint between placeholder entities. This is mainly used by reflection.
+    fn default() -> Self {
+        Self::new(Entity::PLACEHOLDER, Entity::PLACEHOLDER)
+    }
+}
+
+impl MapEntities for DistanceJoint {
+    fn map_entities(&mut self, entity_mapper: &mut EntityMapper) {
+        self.entity1 = entity_mapper.get_or_reserve(self.entity1);
+        self.entity2 = entity_mapper.get_or_reserve(self.entity2);
+    }
+}
+
 impl XpbdConstraint<2> for DistanceJoint {
     fn entities(&self) -> [Entity; 2] {
         [self.entity1, self.entity2]
@@ -110,6 +132,16 @@ impl Joint for DistanceJoint {
     fn damping_angular(&self) -> Scalar {
         self.damping_angular
     }
+
+    fn lagrange_multipliers(&self) -> Vec<Scalar> {
+        vec![self.lagrange]
+    }
+
+    fn set_lagrange_multipliers(&mut self, multipliers: &[Scalar]) {
+        for (lagrange, value) in [&mut self.lagrange].into_iter().zip(multipliers) {
+            *lagrange = *value;
+        }
+    }
 }
 
 impl DistanceJoint {
@@ -121,6 +153,10 @@ impl DistanceJoint {
         let world_r1 = body1.rotation.rotate(self.local_anchor1);
         let world_r2 = body2.rotation.rotate(self.local_anchor2);
 
+        // Lever arms used for the generalized inverse masses and corrections
+        let com_r1 = body1.joint_lever_arm(self.local_anchor1);
+        let com_r2 = body2.joint_lever_arm(self.local_anchor2);
+
         // // Compute the positional difference
         let mut delta_x =
             (body1.current_position() + world_r1) - (body2.current_position() + world_r2);
@@ -153,8 +189,8 @@ impl DistanceJoint {
         let n = delta_x / length;
 
         // Compute generalized inverse masses (method from PositionConstraint)
-        let w1 = PositionConstraint::compute_generalized_inverse_mass(self, body1, world_r1, n);
-        let w2 = PositionConstraint::compute_generalized_inverse_mass(self, body2, world_r2, n);
+        let w1 = PositionConstraint::compute_generalized_inverse_mass(self, body1, com_r1, n);
+        let w2 = PositionConstraint::compute_generalized_inverse_mass(self, body2, com_r2, n);
         let w = [w1, w2];
 
         // Constraint gradients, i.e. how the bodies should be moved
@@ -167,7 +203,7 @@ impl DistanceJoint {
         self.lagrange += delta_lagrange;
 
         // Apply positional correction (method from PositionConstraint)
-        self.apply_positional_correction(body1, body2, delta_lagrange, n, world_r1, world_r2);
+        self.apply_positional_correction(body1, body2, delta_lagrange, n, com_r1, com_r2);
 
         // Return constraint force
         self.compute_force(self.lagrange, n, dt)
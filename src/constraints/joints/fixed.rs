@@ -1,13 +1,24 @@
 //! [`FixedJoint`] component.
 
 use crate::prelude::*;
-use bevy::prelude::*;
+use bevy::{
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+    prelude::*,
+};
 
 /// A fixed joint prevents any relative movement of the attached bodies.
 ///
 /// You should generally prefer using a single body instead of multiple bodies fixed together,
 /// but fixed joints can be useful for things like rigid structures where a force can dynamically break the joints connecting individual bodies.
-#[derive(Component, Clone, Copy, Debug, PartialEq)]
+///
+/// Bodies welded together by fixed joints with zero compliance can be merged into single bodies
+/// while the joints exist by inserting the [`WeldMerging`] resource.
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component, MapEntities)]
 pub struct FixedJoint {
     /// First entity constrained by the joint.
     pub entity1: Entity,
@@ -17,6 +28,8 @@ pub struct FixedJoint {
     pub local_anchor1: Vector,
     /// Attachment point on the second body.
     pub local_anchor2: Vector,
+    /// The rotation of the second body relative to the first body that the joint maintains.
+    pub rest_rotation: Rotation,
     /// Linear damping applied by the joint.
     pub damping_linear: Scalar,
     /// Angular damping applied by the joint.
@@ -33,6 +46,20 @@ pub struct FixedJoint {
     pub align_torque: Torque,
 }
 
+impl Default for FixedJoint {
+    /// Creates a joint between placeholder entities. This is mainly used by reflection.
+    fn default() -> Self {
+        Self::new(Entity::PLACEHOLDER, Entity::PLACEHOLDER)
+    }
+}
+
+impl MapEntities for FixedJoint {
+    fn map_entities(&mut self, entity_mapper: &mut EntityMapper) {
+        self.entity1 = entity_mapper.get_or_reserve(self.entity1);
+        self.entity2 = entity_mapper.get_or_reserve(self.entity2);
+    }
+}
+
 impl XpbdConstraint<2> for FixedJoint {
     fn entities(&self) -> [Entity; 2] {
         [self.entity1, self.entity2]
@@ -75,6 +102,7 @@ impl Joint for FixedJoint {
             entity2,
             local_anchor1: Vector::ZERO,
             local_anchor2: Vector::ZERO,
+            rest_rotation: Rotation::default(),
             damping_linear: 1.0,
             damping_angular: 1.0,
             position_lagrange: 0.0,
@@ -135,17 +163,40 @@ impl Joint for FixedJoint {
     fn damping_angular(&self) -> Scalar {
         self.damping_angular
     }
+
+    fn lagrange_multipliers(&self) -> Vec<Scalar> {
+        vec![self.position_lagrange, self.align_lagrange]
+    }
+
+    fn set_lagrange_multipliers(&mut self, multipliers: &[Scalar]) {
+        for (lagrange, value) in [&mut self.position_lagrange, &mut self.align_lagrange]
+            .into_iter()
+            .zip(multipliers)
+        {
+            *lagrange = *value;
+        }
+    }
 }
 
 impl FixedJoint {
+    /// Sets the rotation of the second body relative to the first body that the joint maintains.
+    ///
+    /// By default, the joint aligns the bodies so that they have the same rotation.
+    pub fn with_rest_rotation(self, rest_rotation: Rotation) -> Self {
+        Self {
+            rest_rotation,
+            ..self
+        }
+    }
+
     #[cfg(feature = "2d")]
     fn get_delta_q(&self, rot1: &Rotation, rot2: &Rotation) -> Vector3 {
-        (*rot2 - *rot1).as_radians() * Vector3::Z
+        (*rot2 - rot1.mul(self.rest_rotation)).as_radians() * Vector3::Z
     }
 
     #[cfg(feature = "3d")]
     fn get_delta_q(&self, rot1: &Rotation, rot2: &Rotation) -> Vector {
-        2.0 * (rot1.0 * rot2.inverse().0).xyz()
+        2.0 * (rot1.0 * self.rest_rotation.0 * rot2.inverse().0).xyz()
     }
 }
 
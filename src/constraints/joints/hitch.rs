@@ -0,0 +1,251 @@
+//! [`TrailerHitch`] component and a [`SphericalJoint`] preset for connecting trailers to vehicles.
+
+use crate::prelude::*;
+use bevy::{ecs::system::Command, prelude::*};
+
+/// Stabilizes a [`SphericalJoint`] that connects a trailer to a towing vehicle.
+///
+/// Trailers attached with plain spherical joints tend to swing and oscillate,
+/// and they can fold into the towing vehicle when reversing or braking. A trailer hitch
+/// damps the relative yaw rotation of the bodies and applies a restoring torque
+/// when the yaw angle exceeds the `jackknife_angle`.
+///
+/// When a trailer hitch is added, the velocity of the trailer is matched with the velocity
+/// of the towing vehicle at the hitch so that connecting vehicles at runtime doesn't cause jolts.
+///
+/// The hitch should be added to the same entity as the joint. [`SphericalJoint::hitch`] can be used
+/// to create a joint with limits suitable for hitches, and [`ConnectHitch`] adds both at runtime.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// # #[cfg(feature = "3d")]
+/// fn setup(mut commands: Commands) {
+///     let truck = commands.spawn(RigidBody::Dynamic).id();
+///     let trailer = commands.spawn(RigidBody::Dynamic).id();
+///
+///     // Connect the rear of the truck to the front of the trailer
+///     commands.spawn((
+///         SphericalJoint::hitch(truck, trailer)
+///             .with_local_anchor_1(Vec3::Z * 2.0)
+///             .with_local_anchor_2(Vec3::NEG_Z * 3.0),
+///         TrailerHitch::default(),
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct TrailerHitch {
+    /// The up axis of the towing vehicle in its local space. The yaw angle of the trailer
+    /// is measured around this axis. This is normally the y-axis.
+    pub up_axis: Vector,
+    /// The yaw angle in radians after which the restoring torque is applied.
+    pub jackknife_angle: Scalar,
+    /// The angular acceleration applied per radian that the yaw angle exceeds the `jackknife_angle`.
+    pub stiffness: Scalar,
+    /// The damping of the relative yaw rotation of the bodies.
+    pub yaw_damping: Scalar,
+    /// If true, the velocity of the trailer is matched with the velocity of the towing vehicle
+    /// at the hitch when the hitch is added. True by default.
+    pub match_velocity: bool,
+}
+
+impl Default for TrailerHitch {
+    fn default() -> Self {
+        Self {
+            up_axis: Vector::Y,
+            jackknife_angle: PI / 3.0,
+            stiffness: 20.0,
+            yaw_damping: 2.0,
+            match_velocity: true,
+        }
+    }
+}
+
+impl TrailerHitch {
+    /// Sets the up axis of the towing vehicle in its local space.
+    pub fn with_up_axis(self, up_axis: Vector) -> Self {
+        Self {
+            up_axis: up_axis.normalize(),
+            ..self
+        }
+    }
+
+    /// Sets the yaw angle in radians after which the restoring torque is applied.
+    pub fn with_jackknife_angle(self, angle: Scalar) -> Self {
+        Self {
+            jackknife_angle: angle,
+            ..self
+        }
+    }
+
+    /// Sets the angular acceleration applied per radian that the yaw angle exceeds the `jackknife_angle`.
+    pub fn with_stiffness(self, stiffness: Scalar) -> Self {
+        Self { stiffness, ..self }
+    }
+
+    /// Sets the damping of the relative yaw rotation of the bodies.
+    pub fn with_yaw_damping(self, damping: Scalar) -> Self {
+        Self {
+            yaw_damping: damping,
+            ..self
+        }
+    }
+
+    /// Sets whether the velocity of the trailer is matched with the velocity of the towing vehicle
+    /// when the hitch is added.
+    pub fn with_velocity_matching(self, match_velocity: bool) -> Self {
+        Self {
+            match_velocity,
+            ..self
+        }
+    }
+
+    /// Returns the signed yaw angle of the second body relative to the first body around the `up_axis`.
+    pub fn yaw_angle(&self, rotation1: &Rotation, rotation2: &Rotation) -> Scalar {
+        let relative = rotation1.0.inverse() * rotation2.0;
+        let twist = self
+            .up_axis
+            .dot(Vector::new(relative.x, relative.y, relative.z));
+        let angle = 2.0 * math::ops::atan2(twist, relative.w);
+
+        // Wrap the angle to the [-PI, PI] range
+        if angle > PI {
+            angle - 2.0 * PI
+        } else if angle < -PI {
+            angle + 2.0 * PI
+        } else {
+            angle
+        }
+    }
+}
+
+impl SphericalJoint {
+    /// Creates a spherical joint that is suitable for trailer hitches, connecting
+    /// a towing vehicle (`entity1`) to a trailer (`entity2`).
+    ///
+    /// The joint allows the trailer to pitch and roll within a 20 degree cone and to yaw up to 80 degrees
+    /// relative to the towing vehicle, assuming that the y-axis points up and the z-axis points forward or backward.
+    /// Add a [`TrailerHitch`] to the same entity to damp oscillation and to prevent jackknifing.
+    pub fn hitch(entity1: Entity, entity2: Entity) -> Self {
+        Self {
+            swing_axis: Vector::Y,
+            twist_axis: Vector::Z,
+            ..Self::new(entity1, entity2)
+        }
+        .with_swing_limits(-PI / 9.0, PI / 9.0)
+        .with_twist_limits(-PI * 4.0 / 9.0, PI * 4.0 / 9.0)
+        .with_angular_velocity_damping(2.0)
+    }
+}
+
+/// A [`Command`] that connects a trailer to a towing vehicle at runtime by adding a
+/// [`SphericalJoint::hitch`] joint and a [`TrailerHitch`] to the `joint` entity.
+///
+/// The velocity of the trailer is matched with the velocity of the towing vehicle at the hitch,
+/// unless disabled in the `hitch`. To disconnect the vehicles, despawn the `joint` entity or use
+/// [`DisconnectHitch`].
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// # #[cfg(feature = "3d")]
+/// fn connect_trailer(mut commands: Commands, truck: Entity, trailer: Entity) {
+///     let joint = commands.spawn_empty().id();
+///     commands.add(
+///         ConnectHitch::new(joint, truck, trailer)
+///             .with_local_anchors(Vec3::Z * 2.0, Vec3::NEG_Z * 3.0),
+///     );
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConnectHitch {
+    /// The entity that the joint and hitch are added to.
+    pub joint: Entity,
+    /// The towing vehicle.
+    pub tractor: Entity,
+    /// The trailer.
+    pub trailer: Entity,
+    /// The attachment point on the towing vehicle.
+    pub tractor_anchor: Vector,
+    /// The attachment point on the trailer.
+    pub trailer_anchor: Vector,
+    /// The trailer hitch configuration.
+    pub hitch: TrailerHitch,
+}
+
+impl ConnectHitch {
+    /// Creates a command that connects the `trailer` to the `tractor` using the `joint` entity.
+    pub fn new(joint: Entity, tractor: Entity, trailer: Entity) -> Self {
+        Self {
+            joint,
+            tractor,
+            trailer,
+            tractor_anchor: Vector::ZERO,
+            trailer_anchor: Vector::ZERO,
+            hitch: TrailerHitch::default(),
+        }
+    }
+
+    /// Sets the attachment points on the towing vehicle and the trailer.
+    pub fn with_local_anchors(self, tractor_anchor: Vector, trailer_anchor: Vector) -> Self {
+        Self {
+            tractor_anchor,
+            trailer_anchor,
+            ..self
+        }
+    }
+
+    /// Sets the trailer hitch configuration.
+    pub fn with_hitch(self, hitch: TrailerHitch) -> Self {
+        Self { hitch, ..self }
+    }
+}
+
+impl Command for ConnectHitch {
+    fn apply(self, world: &mut World) {
+        let joint = SphericalJoint::hitch(self.tractor, self.trailer)
+            .with_local_anchor_1(self.tractor_anchor)
+            .with_local_anchor_2(self.trailer_anchor);
+
+        if let Some(mut entity) = world.get_entity_mut(self.joint) {
+            entity.insert((joint, self.hitch));
+        }
+    }
+}
+
+/// A [`Command`] that disconnects a trailer from a towing vehicle at runtime by removing
+/// the [`SphericalJoint`] and [`TrailerHitch`] from the `joint` entity.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DisconnectHitch {
+    /// The entity that the joint and hitch were added to.
+    pub joint: Entity,
+}
+
+impl DisconnectHitch {
+    /// Creates a command that disconnects the vehicles connected by the `joint` entity.
+    pub fn new(joint: Entity) -> Self {
+        Self { joint }
+    }
+}
+
+impl Command for DisconnectHitch {
+    fn apply(self, world: &mut World) {
+        if let Some(mut entity) = world.get_entity_mut(self.joint) {
+            entity.remove::<(SphericalJoint, TrailerHitch)>();
+        }
+    }
+}
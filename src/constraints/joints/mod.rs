@@ -69,6 +69,9 @@
 //!
 //! Take a look at the documentation and methods of each joint to see all of the configuration options.
 //!
+//! In 3D, [`SphericalJoint::hitch`] creates a spherical joint preset for connecting trailers to vehicles.
+//! It can be combined with a [`TrailerHitch`] to prevent oscillation and jackknifing.
+//!
 //! ## Custom joints
 //!
 //! Joints are [constraints] that implement [`Joint`] and [`XpbdConstraint`].
@@ -85,12 +88,16 @@
 
 mod distance;
 mod fixed;
+#[cfg(feature = "3d")]
+mod hitch;
 mod prismatic;
 mod revolute;
 mod spherical;
 
 pub use distance::*;
 pub use fixed::*;
+#[cfg(feature = "3d")]
+pub use hitch::*;
 pub use prismatic::*;
 pub use revolute::*;
 pub use spherical::*;
@@ -130,6 +137,28 @@ pub trait Joint: Component + PositionConstraint + AngularConstraint {
     /// Returns the angular velocity damping of the joint.
     fn damping_angular(&self) -> Scalar;
 
+    /// Returns the largest angle in radians by which the relative rotation of the bodies exceeded
+    /// the angle limits of the joint during the latest substep, measured before the limits were applied.
+    ///
+    /// Returns zero by default. See [`JointLimitMonitor`].
+    fn angle_limit_violation(&self) -> Scalar {
+        0.0
+    }
+
+    /// Returns the Lagrange multipliers of the joint from the latest substep.
+    /// This is useful for inspecting the solver when debugging joints.
+    ///
+    /// Returns an empty list by default.
+    fn lagrange_multipliers(&self) -> Vec<Scalar> {
+        vec![]
+    }
+
+    /// Sets the Lagrange multipliers of the joint in the order returned by
+    /// [`lagrange_multipliers`](Joint::lagrange_multipliers). Missing values are left unchanged.
+    ///
+    /// This is used for restoring a [`PhysicsSnapshot`]. Does nothing by default.
+    fn set_lagrange_multipliers(&mut self, _multipliers: &[Scalar]) {}
+
     /// Applies a positional correction that aligns the positions of the local attachment points `r1` and `r2`.
     ///
     /// Returns the force exerted by the alignment.
@@ -147,6 +176,10 @@ pub trait Joint: Component + PositionConstraint + AngularConstraint {
         let world_r1 = body1.rotation.rotate(r1);
         let world_r2 = body2.rotation.rotate(r2);
 
+        // Lever arms used for the generalized inverse masses and corrections
+        let com_r1 = body1.joint_lever_arm(r1);
+        let com_r2 = body2.joint_lever_arm(r2);
+
         let delta_x = DistanceLimit::new(0.0, 0.0).compute_correction(
             body1.current_position() + world_r1,
             body2.current_position() + world_r2,
@@ -160,8 +193,8 @@ pub trait Joint: Component + PositionConstraint + AngularConstraint {
         let dir = delta_x / magnitude;
 
         // Compute generalized inverse masses
-        let w1 = PositionConstraint::compute_generalized_inverse_mass(self, body1, world_r1, dir);
-        let w2 = PositionConstraint::compute_generalized_inverse_mass(self, body2, world_r2, dir);
+        let w1 = PositionConstraint::compute_generalized_inverse_mass(self, body1, com_r1, dir);
+        let w2 = PositionConstraint::compute_generalized_inverse_mass(self, body2, com_r2, dir);
 
         // Constraint gradients and inverse masses
         let gradients = [dir, -dir];
@@ -173,7 +206,7 @@ pub trait Joint: Component + PositionConstraint + AngularConstraint {
         *lagrange += delta_lagrange;
 
         // Apply positional correction to align the positions of the bodies
-        self.apply_positional_correction(body1, body2, delta_lagrange, dir, world_r1, world_r2);
+        self.apply_positional_correction(body1, body2, delta_lagrange, dir, com_r1, com_r2);
 
         // Return constraint force
         self.compute_force(*lagrange, dir, dt)
@@ -233,7 +266,8 @@ pub trait Joint: Component + PositionConstraint + AngularConstraint {
 }
 
 /// A limit that indicates that the distance between two points should be between `min` and `max`.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DistanceLimit {
     /// The minimum distance between two points.
     pub min: Scalar,
@@ -292,7 +326,8 @@ impl DistanceLimit {
 }
 
 /// A limit that indicates that angles should be between `alpha` and `beta`.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AngleLimit {
     /// The minimum angle.
     pub alpha: Scalar,
@@ -312,16 +347,16 @@ impl AngleLimit {
         Self { alpha, beta }
     }
 
-    /// Returns the angular correction required to limit the angle between the axes `n1` and `n2`
-    /// to be inside the angle limits.
-    fn compute_correction(
-        &self,
-        n: Vector3,
-        n1: Vector3,
-        n2: Vector3,
-        max_correction: Scalar,
-    ) -> Option<Vector3> {
-        let mut phi = n1.cross(n2).dot(n).asin();
+    /// Returns the angle by which the angle between the axes `n1` and `n2` around the axis `n`
+    /// exceeds the angle limits, or zero if the angle is inside the limits.
+    pub fn compute_violation(&self, n: Vector3, n1: Vector3, n2: Vector3) -> Scalar {
+        let phi = Self::signed_angle(n, n1, n2);
+        (self.alpha - phi).max(phi - self.beta).max(0.0)
+    }
+
+    /// Returns the signed angle between the axes `n1` and `n2` around the axis `n` in the `[-PI, PI]` range.
+    fn signed_angle(n: Vector3, n1: Vector3, n2: Vector3) -> Scalar {
+        let mut phi = math::ops::asin(n1.cross(n2).dot(n));
 
         if n1.dot(n2) < 0.0 {
             phi = PI - phi;
@@ -335,6 +370,20 @@ impl AngleLimit {
             phi += 2.0 * PI;
         }
 
+        phi
+    }
+
+    /// Returns the angular correction required to limit the angle between the axes `n1` and `n2`
+    /// to be inside the angle limits.
+    fn compute_correction(
+        &self,
+        n: Vector3,
+        n1: Vector3,
+        n2: Vector3,
+        max_correction: Scalar,
+    ) -> Option<Vector3> {
+        let mut phi = Self::signed_angle(n, n1, n2);
+
         if phi < self.alpha || phi > self.beta {
             phi = phi.clamp(self.alpha, self.beta);
 
@@ -353,3 +402,101 @@ impl AngleLimit {
         None
     }
 }
+
+/// A component that monitors the angle limits of the [joint](joints) on the same entity.
+///
+/// When the relative rotation of the bodies exceeds the limits by more than the [tolerance](#structfield.tolerance)
+/// for [`steps`](#structfield.steps) consecutive substeps, a [`JointLimitViolated`] event is sent,
+/// and the joint is highlighted by the debug renderer until the violation ends.
+///
+/// This is useful for tuning the limits and compliances of joints in setups like ragdolls,
+/// where unstable limits can be hard to spot.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     let entity1 = commands.spawn(RigidBody::Dynamic).id();
+///     let entity2 = commands.spawn(RigidBody::Dynamic).id();
+///
+///     // Report violations of more than 0.1 radians that last for at least 10 substeps
+///     commands.spawn((
+///         RevoluteJoint::new(entity1, entity2).with_angle_limits(-0.5, 0.5),
+///         JointLimitMonitor::new(0.1, 10),
+///     ));
+/// }
+///
+/// fn print_violations(mut events: EventReader<JointLimitViolated>) {
+///     for event in events.iter() {
+///         println!("{:?} exceeds its limits by {} radians", event.joint, event.violation);
+///     }
+/// }
+/// ```
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct JointLimitMonitor {
+    /// The angle in radians that the limits can be exceeded by without counting as a violation.
+    pub tolerance: Scalar,
+    /// The number of consecutive substeps that the limits must be violated for
+    /// before the joint is considered to be violating them.
+    pub steps: u32,
+    /// The number of consecutive substeps that the limits have been violated for.
+    pub violating_steps: u32,
+    /// The largest [violation](Joint::angle_limit_violation) during the current consecutive violating substeps.
+    pub max_violation: Scalar,
+}
+
+impl Default for JointLimitMonitor {
+    fn default() -> Self {
+        Self::new(0.05, 5)
+    }
+}
+
+impl JointLimitMonitor {
+    /// Creates a new [`JointLimitMonitor`] with the given tolerance in radians
+    /// and number of consecutive substeps.
+    pub fn new(tolerance: Scalar, steps: u32) -> Self {
+        Self {
+            tolerance,
+            steps: steps.max(1),
+            violating_steps: 0,
+            max_violation: 0.0,
+        }
+    }
+
+    /// Returns true if the limits have been violated for at least [`steps`](#structfield.steps) consecutive substeps.
+    pub fn is_violating(&self) -> bool {
+        self.violating_steps >= self.steps
+    }
+
+    /// Updates the monitor with the violation of the latest substep.
+    /// Returns true if the joint just started violating its limits.
+    pub(crate) fn update(&mut self, violation: Scalar) -> bool {
+        if violation <= self.tolerance {
+            self.violating_steps = 0;
+            self.max_violation = 0.0;
+            return false;
+        }
+        self.violating_steps = self.violating_steps.saturating_add(1);
+        self.max_violation = self.max_violation.max(violation);
+        self.violating_steps == self.steps
+    }
+}
+
+/// An event that is sent when a joint with a [`JointLimitMonitor`] starts violating its angle limits.
+#[derive(Event, Reflect, Clone, Copy, Debug, PartialEq)]
+pub struct JointLimitViolated {
+    /// The entity of the joint.
+    pub joint: Entity,
+    /// The bodies constrained by the joint.
+    pub bodies: [Entity; 2],
+    /// The largest angle in radians by which the limits were exceeded during the violating substeps.
+    pub violation: Scalar,
+}
@@ -1,12 +1,20 @@
 //! [`PrismaticJoint`] component.
 
 use crate::prelude::*;
-use bevy::prelude::*;
+use bevy::{
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+    prelude::*,
+};
 
 /// A prismatic joint prevents relative movement of the attached bodies, except for translation along one `free_axis`.
 ///
 /// Prismatic joints can be useful for things like elevators, pistons, sliding doors and moving platforms.
-#[derive(Component, Clone, Copy, Debug, PartialEq)]
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component, MapEntities)]
 pub struct PrismaticJoint {
     /// First entity constrained by the joint.
     pub entity1: Entity,
@@ -36,6 +44,20 @@ pub struct PrismaticJoint {
     pub align_torque: Torque,
 }
 
+impl Default for PrismaticJoint {
+    /// Creates a joint between placeholder entities. This is mainly used by reflection.
+    fn default() -> Self {
+        Self::new(Entity::PLACEHOLDER, Entity::PLACEHOLDER)
+    }
+}
+
+impl MapEntities for PrismaticJoint {
+    fn map_entities(&mut self, entity_mapper: &mut EntityMapper) {
+        self.entity1 = entity_mapper.get_or_reserve(self.entity1);
+        self.entity2 = entity_mapper.get_or_reserve(self.entity2);
+    }
+}
+
 impl XpbdConstraint<2> for PrismaticJoint {
     fn entities(&self) -> [Entity; 2] {
         [self.entity1, self.entity2]
@@ -130,6 +152,19 @@ impl Joint for PrismaticJoint {
     fn damping_angular(&self) -> Scalar {
         self.damping_angular
     }
+
+    fn lagrange_multipliers(&self) -> Vec<Scalar> {
+        vec![self.position_lagrange, self.align_lagrange]
+    }
+
+    fn set_lagrange_multipliers(&mut self, multipliers: &[Scalar]) {
+        for (lagrange, value) in [&mut self.position_lagrange, &mut self.align_lagrange]
+            .into_iter()
+            .zip(multipliers)
+        {
+            *lagrange = *value;
+        }
+    }
 }
 
 impl PrismaticJoint {
@@ -145,6 +180,10 @@ impl PrismaticJoint {
         let world_r1 = body1.rotation.rotate(self.local_anchor1);
         let world_r2 = body2.rotation.rotate(self.local_anchor2);
 
+        // Lever arms used for the generalized inverse masses and corrections
+        let com_r1 = body1.joint_lever_arm(self.local_anchor1);
+        let com_r2 = body2.joint_lever_arm(self.local_anchor2);
+
         let mut delta_x = Vector::ZERO;
 
         let axis1 = body1.rotation.rotate(self.free_axis);
@@ -193,8 +232,8 @@ impl PrismaticJoint {
         let dir = delta_x / magnitude;
 
         // Compute generalized inverse masses
-        let w1 = PositionConstraint::compute_generalized_inverse_mass(self, body1, world_r1, dir);
-        let w2 = PositionConstraint::compute_generalized_inverse_mass(self, body2, world_r2, dir);
+        let w1 = PositionConstraint::compute_generalized_inverse_mass(self, body1, com_r1, dir);
+        let w2 = PositionConstraint::compute_generalized_inverse_mass(self, body2, com_r2, dir);
 
         // Constraint gradients and inverse masses
         let gradients = [dir, -dir];
@@ -212,7 +251,7 @@ impl PrismaticJoint {
         self.position_lagrange += delta_lagrange;
 
         // Apply positional correction to align the positions of the bodies
-        self.apply_positional_correction(body1, body2, delta_lagrange, dir, world_r1, world_r2);
+        self.apply_positional_correction(body1, body2, delta_lagrange, dir, com_r1, com_r2);
 
         // Return constraint force
         self.compute_force(self.position_lagrange, dir, dt)
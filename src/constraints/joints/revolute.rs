@@ -1,12 +1,20 @@
 //! [`RevoluteJoint`] component.
 
 use crate::prelude::*;
-use bevy::prelude::*;
+use bevy::{
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+    prelude::*,
+};
 
 /// A revolute joint prevents relative movement of the attached bodies, except for rotation around one `aligned_axis`.
 ///
 /// Revolute joints can be useful for things like wheels, fans, revolving doors etc.
-#[derive(Component, Clone, Copy, Debug, PartialEq)]
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component, MapEntities)]
 pub struct RevoluteJoint {
     /// First entity constrained by the joint.
     pub entity1: Entity,
@@ -44,6 +52,23 @@ pub struct RevoluteJoint {
     pub align_torque: Torque,
     /// The torque exerted by the joint when limiting the relative rotation of the bodies around the `aligned_axis`.
     pub angle_limit_torque: Torque,
+    /// The angle in radians by which the relative rotation of the bodies exceeded the `angle_limit`
+    /// during the latest substep, measured before the limit was applied.
+    pub angle_limit_violation: Scalar,
+}
+
+impl Default for RevoluteJoint {
+    /// Creates a joint between placeholder entities. This is mainly used by reflection.
+    fn default() -> Self {
+        Self::new(Entity::PLACEHOLDER, Entity::PLACEHOLDER)
+    }
+}
+
+impl MapEntities for RevoluteJoint {
+    fn map_entities(&mut self, entity_mapper: &mut EntityMapper) {
+        self.entity1 = entity_mapper.get_or_reserve(self.entity1);
+        self.entity2 = entity_mapper.get_or_reserve(self.entity2);
+    }
 }
 
 impl XpbdConstraint<2> for RevoluteJoint {
@@ -109,6 +134,7 @@ impl Joint for RevoluteJoint {
             angle_limit_torque: 0.0,
             #[cfg(feature = "3d")]
             angle_limit_torque: Vector::ZERO,
+            angle_limit_violation: 0.0,
         }
     }
 
@@ -159,6 +185,31 @@ impl Joint for RevoluteJoint {
     fn damping_angular(&self) -> Scalar {
         self.damping_angular
     }
+
+    fn angle_limit_violation(&self) -> Scalar {
+        self.angle_limit_violation
+    }
+
+    fn lagrange_multipliers(&self) -> Vec<Scalar> {
+        vec![
+            self.position_lagrange,
+            self.align_lagrange,
+            self.angle_limit_lagrange,
+        ]
+    }
+
+    fn set_lagrange_multipliers(&mut self, multipliers: &[Scalar]) {
+        for (lagrange, value) in [
+            &mut self.position_lagrange,
+            &mut self.align_lagrange,
+            &mut self.angle_limit_lagrange,
+        ]
+        .into_iter()
+        .zip(multipliers)
+        {
+            *lagrange = *value;
+        }
+    }
 }
 
 impl RevoluteJoint {
@@ -193,6 +244,8 @@ impl RevoluteJoint {
         body2: &mut RigidBodyQueryItem,
         dt: Scalar,
     ) -> Torque {
+        self.angle_limit_violation = 0.0;
+
         if let Some(angle_limit) = self.angle_limit {
             let limit_axis = Vector3::new(
                 self.aligned_axis.z,
@@ -203,6 +256,8 @@ impl RevoluteJoint {
             let a2 = body2.rotation.rotate_vec3(limit_axis);
             let n = a1.cross(a2).normalize();
 
+            self.angle_limit_violation = angle_limit.compute_violation(n, a1, a2);
+
             if let Some(dq) = angle_limit.compute_correction(n, a1, a2, PI) {
                 let mut lagrange = self.angle_limit_lagrange;
                 let torque =
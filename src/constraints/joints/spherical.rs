@@ -1,12 +1,20 @@
 //! [`SphericalJoint`] component.
 
 use crate::prelude::*;
-use bevy::prelude::*;
+use bevy::{
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+    prelude::*,
+};
 
 /// A spherical joint prevents relative translation of the attached bodies while allowing rotation around all axes.
 ///
 /// Spherical joints can be useful for things like pendula, chains, ragdolls etc.
-#[derive(Component, Clone, Copy, Debug, PartialEq)]
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component, MapEntities)]
 pub struct SphericalJoint {
     /// First entity constrained by the joint.
     pub entity1: Entity,
@@ -42,6 +50,23 @@ pub struct SphericalJoint {
     pub swing_torque: Torque,
     /// The torque exerted by the joint when limiting the relative rotation of the bodies around the `twist_axis`.
     pub twist_torque: Torque,
+    /// The largest angle in radians by which the relative rotation of the bodies exceeded the swing or twist limits
+    /// during the latest substep, measured before the limits were applied.
+    pub angle_limit_violation: Scalar,
+}
+
+impl Default for SphericalJoint {
+    /// Creates a joint between placeholder entities. This is mainly used by reflection.
+    fn default() -> Self {
+        Self::new(Entity::PLACEHOLDER, Entity::PLACEHOLDER)
+    }
+}
+
+impl MapEntities for SphericalJoint {
+    fn map_entities(&mut self, entity_mapper: &mut EntityMapper) {
+        self.entity1 = entity_mapper.get_or_reserve(self.entity1);
+        self.entity2 = entity_mapper.get_or_reserve(self.entity2);
+    }
 }
 
 impl XpbdConstraint<2> for SphericalJoint {
@@ -73,6 +98,7 @@ impl XpbdConstraint<2> for SphericalJoint {
         self.position_lagrange = lagrange;
 
         // Apply swing limits
+        self.angle_limit_violation = 0.0;
         self.swing_torque = self.apply_swing_limits(body1, body2, dt);
 
         // Apply twist limits
@@ -106,6 +132,7 @@ impl Joint for SphericalJoint {
             twist_torque: 0.0,
             #[cfg(feature = "3d")]
             twist_torque: Vector::ZERO,
+            angle_limit_violation: 0.0,
         }
     }
 
@@ -156,6 +183,31 @@ impl Joint for SphericalJoint {
     fn damping_angular(&self) -> Scalar {
         self.damping_angular
     }
+
+    fn angle_limit_violation(&self) -> Scalar {
+        self.angle_limit_violation
+    }
+
+    fn lagrange_multipliers(&self) -> Vec<Scalar> {
+        vec![
+            self.position_lagrange,
+            self.swing_lagrange,
+            self.twist_lagrange,
+        ]
+    }
+
+    fn set_lagrange_multipliers(&mut self, multipliers: &[Scalar]) {
+        for (lagrange, value) in [
+            &mut self.position_lagrange,
+            &mut self.swing_lagrange,
+            &mut self.twist_lagrange,
+        ]
+        .into_iter()
+        .zip(multipliers)
+        {
+            *lagrange = *value;
+        }
+    }
 }
 
 impl SphericalJoint {
@@ -196,6 +248,10 @@ impl SphericalJoint {
 
             let n = n / n_magnitude;
 
+            self.angle_limit_violation = self
+                .angle_limit_violation
+                .max(joint_limit.compute_violation(n, a1, a2));
+
             if let Some(dq) = joint_limit.compute_correction(n, a1, a2, PI) {
                 let mut lagrange = self.swing_lagrange;
                 let torque =
@@ -244,6 +300,10 @@ impl SphericalJoint {
 
             let max_correction = if a1.dot(a2) > -0.5 { 2.0 * PI } else { dt };
 
+            self.angle_limit_violation = self
+                .angle_limit_violation
+                .max(joint_limit.compute_violation(n, n1, n2));
+
             if let Some(dq) = joint_limit.compute_correction(n, n1, n2, max_correction) {
                 let mut lagrange = self.twist_lagrange;
                 let torque =
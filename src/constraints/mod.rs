@@ -272,3 +272,51 @@ pub trait XpbdConstraint<const ENTITY_COUNT: usize> {
     /// Sets the constraint's [Lagrange multipliers](constraints#lagrange-multipliers) to 0.
     fn clear_lagrange_multipliers(&mut self);
 }
+
+/// Overrides the number of times that a constraint is solved per substep, configured globally by
+/// [`SolverConfig::constraint_iterations`].
+///
+/// Solving a constraint several times per substep makes it stiffer and helps it converge, which is useful
+/// for constraints that are hard to satisfy with the global settings, like long chains of joints
+/// or joints between bodies with very different masses. It is cheaper than increasing the [`SubstepCount`]
+/// for the whole simulation, but each iteration costs as much as solving the constraint once.
+///
+/// The constraints of the same type are iterated together, so a constraint with more iterations is solved
+/// again after the other constraints of its type have been solved.
+///
+/// This is respected by all constraints that are solved with [`solve_constraint`], including the built-in [joints].
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     let mut previous_link = commands.spawn(RigidBody::Static).id();
+///
+///     for _ in 0..20 {
+///         let link = commands.spawn(RigidBody::Dynamic).id();
+///
+///         // Solve the joints of the long chain four times per substep so that it doesn't stretch
+///         commands.spawn((
+///             SphericalJoint::new(previous_link, link),
+///             ConstraintIterations(4),
+///         ));
+///         previous_link = link;
+///     }
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Component, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct ConstraintIterations(pub u32);
+
+impl Default for ConstraintIterations {
+    fn default() -> Self {
+        Self(1)
+    }
+}
@@ -28,6 +28,13 @@ pub struct PenetrationConstraint {
     pub normal_force: Vector,
     /// Static friction force acting along this constraint.
     pub static_friction_force: Vector,
+    /// True if either collider is a [`RollingContact`]. Rolling contacts match the velocities
+    /// of the contact points in the velocity solve instead of applying static friction.
+    pub rolling: bool,
+    /// The surface properties of the first body.
+    pub material1: ContactMaterial,
+    /// The surface properties of the second body.
+    pub material2: ContactMaterial,
 }
 
 impl XpbdConstraint<2> for PenetrationConstraint {
@@ -44,17 +51,12 @@ impl XpbdConstraint<2> for PenetrationConstraint {
     fn solve(&mut self, bodies: [&mut RigidBodyQueryItem; 2], dt: Scalar) {
         let [body1, body2] = bodies;
 
-        let p1 = body1.current_position() + body1.rotation.rotate(self.contact.point1);
-        let p2 = body2.current_position() + body2.rotation.rotate(self.contact.point2);
-        self.contact.penetration = (p1 - p2).dot(self.contact.global_normal1(&body1.rotation));
-
-        // If penetration depth is under 0, skip the collision
-        if self.contact.penetration <= Scalar::EPSILON {
+        if !self.update_penetration(body1, body2) {
             return;
         }
 
         self.solve_contact(body1, body2, dt);
-        self.solve_friction(body1, body2, dt);
+        self.solve_static_friction(body1, body2, dt);
     }
 }
 
@@ -65,8 +67,8 @@ impl PenetrationConstraint {
         body2: &RigidBodyQueryItem,
         contact: ContactData,
     ) -> Self {
-        let r1 = contact.point1 - body1.center_of_mass.0;
-        let r2 = contact.point2 - body2.center_of_mass.0;
+        let r1 = contact.point1 - body1.effective_center_of_mass();
+        let r2 = contact.point2 - body2.effective_center_of_mass();
 
         Self {
             entity1: body1.entity,
@@ -79,9 +81,132 @@ impl PenetrationConstraint {
             compliance: 0.0,
             normal_force: Vector::ZERO,
             static_friction_force: Vector::ZERO,
+            rolling: false,
+            material1: ContactMaterial::default(),
+            material2: ContactMaterial::default(),
         }
     }
 
+    /// Returns the combined [`Friction`] of the bodies for sliding in the given world-space direction,
+    /// taking into account any [`AnisotropicFriction`].
+    pub fn friction_along(
+        &self,
+        rotation1: &Rotation,
+        rotation2: &Rotation,
+        direction: Vector,
+    ) -> Friction {
+        self.material1
+            .friction_along(rotation1, direction)
+            .combine(self.material2.friction_along(rotation2, direction))
+    }
+
+    /// Applies the Lagrange multipliers that the contact had during the previous substep,
+    /// scaled by the given coefficient, as an initial guess for solving the constraint.
+    ///
+    /// The normal correction never pushes the bodies further apart than the current penetration depth,
+    /// and the static friction correction is limited by the static friction coefficient, so that
+    /// stale multipliers of contacts that have just been resolved or started sliding aren't reapplied.
+    ///
+    /// See [`SolverConfig::warm_start_coefficient`].
+    pub fn warm_start(
+        &mut self,
+        body1: &mut RigidBodyQueryItem,
+        body2: &mut RigidBodyQueryItem,
+        coefficient: Scalar,
+        dt: Scalar,
+    ) {
+        if coefficient == 0.0 || !self.update_penetration(body1, body2) {
+            return;
+        }
+
+        let normal = self.contact.global_normal1(&body1.rotation);
+        let r1 = body1.rotation.rotate(self.r1);
+        let r2 = body2.rotation.rotate(self.r2);
+        let w_sum = self.compute_generalized_inverse_mass(body1, r1, normal)
+            + self.compute_generalized_inverse_mass(body2, r2, normal);
+        if w_sum <= Scalar::EPSILON {
+            return;
+        }
+
+        // The multipliers are negative, so the larger one is the smaller correction
+        let normal_lagrange =
+            (self.contact.normal_lagrange * coefficient).max(-self.contact.penetration / w_sum);
+        if normal_lagrange < 0.0 {
+            self.apply_positional_correction(body1, body2, normal_lagrange, normal, r1, r2);
+            self.normal_lagrange = normal_lagrange;
+            self.normal_force = normal_lagrange * normal / dt.powi(2);
+        }
+
+        if self.rolling
+            || self.material1.tire_friction.is_some()
+            || self.material2.tire_friction.is_some()
+        {
+            return;
+        }
+
+        let tangent = body1.rotation.rotate(self.contact.tangent1);
+        let static_coefficient = self
+            .friction_along(&body1.rotation, &body2.rotation, tangent)
+            .static_coefficient;
+        let tangent_lagrange = (self.contact.tangent_lagrange * coefficient)
+            .max(static_coefficient * self.normal_lagrange);
+        if tangent_lagrange < 0.0 {
+            self.apply_positional_correction(body1, body2, tangent_lagrange, tangent, r1, r2);
+            self.tangent_lagrange = tangent_lagrange;
+            self.static_friction_force = tangent_lagrange * tangent / dt.powi(2);
+        }
+    }
+
+    /// Returns the accumulated Lagrange multiplier of static friction and the direction of the correction
+    /// in the local space of the first body, so that they can be used for warm starting on the next substep.
+    pub(crate) fn static_friction_lagrange(
+        &self,
+        rotation1: &Rotation,
+        dt: Scalar,
+    ) -> (Scalar, Vector) {
+        // The friction force is accumulated as a vector, because the tangent can change during a substep
+        let lagrange = self.static_friction_force * dt.powi(2);
+        let magnitude = lagrange.length();
+        if magnitude <= Scalar::EPSILON {
+            return (0.0, Vector::ZERO);
+        }
+        // The multipliers of static friction are negative, so the direction is flipped
+        (
+            -magnitude,
+            rotation1.inverse().rotate(-lagrange / magnitude),
+        )
+    }
+
+    /// Applies a normal Lagrange multiplier update computed by [`PenetrationBatch::compute_lagrange_updates`]
+    /// and solves static friction, which finishes solving the constraint like [`XpbdConstraint::solve`].
+    pub(crate) fn apply_batch_update(
+        &mut self,
+        body1: &mut RigidBodyQueryItem,
+        body2: &mut RigidBodyQueryItem,
+        delta_lagrange: Scalar,
+        dt: Scalar,
+    ) {
+        self.apply_normal_correction(body1, body2, delta_lagrange, dt);
+        self.solve_static_friction(body1, body2, dt);
+    }
+
+    /// Updates the penetration depth of the contact from the current positions of the bodies.
+    ///
+    /// Returns false if the constraint doesn't need to be solved.
+    fn update_penetration(
+        &mut self,
+        body1: &RigidBodyQueryItem,
+        body2: &RigidBodyQueryItem,
+    ) -> bool {
+        let p1 = body1.current_position() + body1.rotation.rotate(self.contact.point1);
+        let p2 = body2.current_position() + body2.rotation.rotate(self.contact.point2);
+        self.contact.penetration = (p1 - p2).dot(self.contact.global_normal1(&body1.rotation));
+
+        // If penetration depth is under 0, skip the collision,
+        // unless warm starting applied a correction that might need to be undone
+        self.contact.penetration > Scalar::EPSILON || self.normal_lagrange != 0.0
+    }
+
     /// Solves a non-penetration constraint between two bodies.
     fn solve_contact(
         &mut self,
@@ -108,6 +233,26 @@ impl PenetrationConstraint {
         // Compute Lagrange multiplier update
         let delta_lagrange =
             self.compute_lagrange_update(lagrange, penetration, &gradients, &w, compliance, dt);
+
+        // The accumulated correction can only push the bodies apart.
+        // An overshooting warm start correction is undone, but the bodies are never pulled together.
+        let delta_lagrange = (lagrange + delta_lagrange).min(0.0) - lagrange;
+
+        self.apply_normal_correction(body1, body2, delta_lagrange, dt);
+    }
+
+    /// Applies a positional correction along the contact normal to solve overlap.
+    fn apply_normal_correction(
+        &mut self,
+        body1: &mut RigidBodyQueryItem,
+        body2: &mut RigidBodyQueryItem,
+        delta_lagrange: Scalar,
+        dt: Scalar,
+    ) {
+        let normal = self.contact.global_normal1(&body1.rotation);
+        let r1 = body1.rotation.rotate(self.r1);
+        let r2 = body2.rotation.rotate(self.r2);
+
         self.normal_lagrange += delta_lagrange;
 
         // Apply positional correction to solve overlap
@@ -117,6 +262,23 @@ impl PenetrationConstraint {
         self.normal_force = self.normal_lagrange * normal / dt.powi(2);
     }
 
+    /// Solves static friction, unless the velocity solve handles friction for the contact.
+    fn solve_static_friction(
+        &mut self,
+        body1: &mut RigidBodyQueryItem,
+        body2: &mut RigidBodyQueryItem,
+        dt: Scalar,
+    ) {
+        // Tires use slip-based friction and rolling contacts match the velocities of the contact points
+        // in the velocity solve instead of static friction
+        if !self.rolling
+            && self.material1.tire_friction.is_none()
+            && self.material2.tire_friction.is_none()
+        {
+            self.solve_friction(body1, body2, dt);
+        }
+    }
+
     fn solve_friction(
         &mut self,
         body1: &mut RigidBodyQueryItem,
@@ -157,7 +319,14 @@ impl PenetrationConstraint {
         let w = [w1, w2];
 
         // Compute combined friction coefficients
-        let static_coefficient = body1.friction.combine(*body2.friction).static_coefficient;
+        let static_coefficient = self
+            .friction_along(&body1.rotation, &body2.rotation, tangent)
+            .static_coefficient;
+
+        // The normal correction of a warm started contact can be larger than its remaining penetration
+        let normal_w = self.compute_generalized_inverse_mass(body1, r1, normal)
+            + self.compute_generalized_inverse_mass(body2, r2, normal);
+        let penetration = penetration.max(-self.normal_lagrange * normal_w);
 
         // Apply static friction if |delta_x_perp| < mu_s * d
         if sliding_len < static_coefficient * penetration {
@@ -170,9 +339,175 @@ impl PenetrationConstraint {
             self.apply_positional_correction(body1, body2, delta_lagrange, tangent, r1, r2);
 
             // Update static friction force using the equation f = lambda * n / h^2
-            self.static_friction_force = self.tangent_lagrange * tangent / dt.powi(2);
+            self.static_friction_force += delta_lagrange * tangent / dt.powi(2);
+        } else {
+            // The contact is sliding, so undo the static friction correction applied by warm starting
+            let lagrange = self.static_friction_force * dt.powi(2);
+            let magnitude = lagrange.length();
+            if magnitude > Scalar::EPSILON {
+                let direction = lagrange / magnitude;
+                self.apply_positional_correction(body1, body2, -magnitude, direction, r1, r2);
+                self.tangent_lagrange = 0.0;
+                self.static_friction_force = Vector::ZERO;
+            }
         }
     }
 }
 
 impl PositionConstraint for PenetrationConstraint {}
+
+/// The contacts and bodies of [`LANES`] [`PenetrationConstraint`]s in a structure of arrays layout,
+/// used for solving the non-penetration constraints of several contacts at once with wide math.
+///
+/// The contacts in a batch must not share any dynamic bodies, since the bodies are only updated
+/// after the Lagrange multipliers of all contacts have been computed.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PenetrationBatch {
+    /// True for the lanes that have a contact that needs to be solved.
+    active: [bool; LANES],
+    penetration: [Scalar; LANES],
+    normal: [Vector; LANES],
+    r1: [Vector; LANES],
+    r2: [Vector; LANES],
+    inv_mass1: [Scalar; LANES],
+    inv_mass2: [Scalar; LANES],
+    #[cfg(feature = "2d")]
+    inv_inertia1: [Scalar; LANES],
+    #[cfg(feature = "2d")]
+    inv_inertia2: [Scalar; LANES],
+    #[cfg(feature = "3d")]
+    inv_inertia1: [Matrix3; LANES],
+    #[cfg(feature = "3d")]
+    inv_inertia2: [Matrix3; LANES],
+    lagrange: [Scalar; LANES],
+    compliance: [Scalar; LANES],
+}
+
+impl Default for PenetrationBatch {
+    fn default() -> Self {
+        Self {
+            active: [false; LANES],
+            penetration: [0.0; LANES],
+            normal: [Vector::ZERO; LANES],
+            r1: [Vector::ZERO; LANES],
+            r2: [Vector::ZERO; LANES],
+            inv_mass1: [0.0; LANES],
+            inv_mass2: [0.0; LANES],
+            #[cfg(feature = "2d")]
+            inv_inertia1: [0.0; LANES],
+            #[cfg(feature = "2d")]
+            inv_inertia2: [0.0; LANES],
+            #[cfg(feature = "3d")]
+            inv_inertia1: [Matrix3::ZERO; LANES],
+            #[cfg(feature = "3d")]
+            inv_inertia2: [Matrix3::ZERO; LANES],
+            lagrange: [0.0; LANES],
+            compliance: [0.0; LANES],
+        }
+    }
+}
+
+impl PenetrationBatch {
+    /// Updates the penetration depth of the given constraint and stores its contact in the given lane.
+    ///
+    /// If the constraint doesn't need to be solved, the lane is left [inactive](Self::is_active).
+    pub fn set_lane(
+        &mut self,
+        lane: usize,
+        constraint: &mut PenetrationConstraint,
+        body1: &RigidBodyQueryItem,
+        body2: &RigidBodyQueryItem,
+    ) {
+        self.active[lane] = constraint.update_penetration(body1, body2);
+        if !self.active[lane] {
+            return;
+        }
+
+        self.penetration[lane] = constraint.contact.penetration;
+        self.normal[lane] = constraint.contact.global_normal1(&body1.rotation);
+        self.r1[lane] = body1.rotation.rotate(constraint.r1);
+        self.r2[lane] = body2.rotation.rotate(constraint.r2);
+        self.lagrange[lane] = constraint.normal_lagrange;
+        self.compliance[lane] = constraint.compliance;
+
+        // Static and kinematic bodies have infinite mass, which is represented by zero inverse mass
+        // like in `PositionConstraint::compute_generalized_inverse_mass`
+        if body1.rb.is_dynamic() {
+            self.inv_mass1[lane] = body1.inverse_mass.0;
+            #[cfg(feature = "2d")]
+            {
+                self.inv_inertia1[lane] = body1.inverse_inertia.0;
+            }
+            #[cfg(feature = "3d")]
+            {
+                self.inv_inertia1[lane] = body1.effective_world_inv_inertia();
+            }
+        }
+        if body2.rb.is_dynamic() {
+            self.inv_mass2[lane] = body2.inverse_mass.0;
+            #[cfg(feature = "2d")]
+            {
+                self.inv_inertia2[lane] = body2.inverse_inertia.0;
+            }
+            #[cfg(feature = "3d")]
+            {
+                self.inv_inertia2[lane] = body2.effective_world_inv_inertia();
+            }
+        }
+    }
+
+    /// Returns true if the given lane has a contact that needs to be solved.
+    pub fn is_active(&self, lane: usize) -> bool {
+        self.active[lane]
+    }
+
+    /// Computes the updates of the normal Lagrange multipliers of all lanes at once,
+    /// like [`XpbdConstraint::solve`] does for a single contact. Inactive lanes get no update.
+    pub fn compute_lagrange_updates(&self, dt: Scalar) -> [Scalar; LANES] {
+        let normal = WideVector::from_vectors(self.normal);
+        let r1 = WideVector::from_vectors(self.r1);
+        let r2 = WideVector::from_vectors(self.r2);
+        let lagrange = WideScalar(self.lagrange);
+
+        // Compute generalized inverse masses
+        #[cfg(feature = "2d")]
+        let (w1, w2) = {
+            let r1_perp_dot_n = r1.perp_dot(normal);
+            let r2_perp_dot_n = r2.perp_dot(normal);
+            (
+                WideScalar(self.inv_mass1)
+                    + WideScalar(self.inv_inertia1) * r1_perp_dot_n * r1_perp_dot_n,
+                WideScalar(self.inv_mass2)
+                    + WideScalar(self.inv_inertia2) * r2_perp_dot_n * r2_perp_dot_n,
+            )
+        };
+        #[cfg(feature = "3d")]
+        let (w1, w2) = {
+            let r1_cross_n = r1.cross(normal);
+            let r2_cross_n = r2.cross(normal);
+            (
+                WideScalar(self.inv_mass1)
+                    + r1_cross_n.dot(WideMatrix3::from_matrices(self.inv_inertia1) * r1_cross_n),
+                WideScalar(self.inv_mass2)
+                    + r2_cross_n.dot(WideMatrix3::from_matrices(self.inv_inertia2) * r2_cross_n),
+            )
+        };
+
+        // Compute Lagrange multiplier updates like `XpbdConstraint::compute_lagrange_update`
+        let w_sum = (w1 + w2) * normal.dot(normal);
+        let tilde_compliance = WideScalar(self.compliance) / WideScalar::splat(dt.powi(2));
+        let delta_lagrange = (-WideScalar(self.penetration) - tilde_compliance * lagrange)
+            / (w_sum + tilde_compliance);
+
+        // Avoid division by zero
+        let delta_lagrange = delta_lagrange.select(
+            w_sum.gt(WideScalar::splat(Scalar::EPSILON)),
+            WideScalar::ZERO,
+        );
+
+        // The accumulated correction can only push the bodies apart
+        let delta_lagrange = (lagrange + delta_lagrange).min(WideScalar::ZERO) - lagrange;
+
+        delta_lagrange.select(self.active, WideScalar::ZERO).0
+    }
+}
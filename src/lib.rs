@@ -34,15 +34,28 @@
 //! - [Locking](LockedAxes) translational and rotational axes
 //! - [Joints](joints)
 //! - Built-in [constraints] and support for [custom constraints](constraints#custom-constraints)
+//! - [Sticky contacts](StickyContact) that weld resting stacks together until they are overloaded
+//! - Lightweight [crowd pushback](CrowdAgent) between kinematic characters
 //! - [Spatial queries](spatial_query)
 //!     - [Ray casting](spatial_query#ray-casting)
 //!     - [Shape casting](spatial_query#shape-casting)
 //!     - [Point projection](spatial_query#point-projection)
 //!     - [Intersection tests](spatial_query#intersection-tests)
+//!     - [Spring arms](SpringArm) for third-person cameras
 //! - Debug rendering [colliders](Collider), [AABBs](ColliderAabb), [contacts](Contact), [joints] and axes
 //! (with `debug-plugin` feature)
 //! - Automatically deactivating bodies with [sleeping](Sleeping)
-//! - Configurable [timesteps](PhysicsTimestep), [time scale](PhysicsTimescale) and [substepping](SubstepCount)
+//! - [Time dilation volumes](TimeDilationVolume) for local slow-motion effects
+//! - [Velocities of points on bodies](VelocityProvider) for things like doppler effects and particle emitters
+//! - Optional [world bounds](WorldBounds) for handling bodies that leave the simulation area
+//! - [Proximity](ProximityFuze) and [contact](ContactFuze) fuzes that send [`Detonation`] events
+//! - [Activity regions](ActivityRegions) for interest management of bodies that moved
+//! - Suspending the simulation of bodies in inactive [rooms](PhysicsRooms), like off-screen rooms in 2D games
+//! - Wrapping bodies around the edges of the world like in Asteroids with `ScreenWrap` (only in 2D)
+//! - [Re-simulating](Resimulation) bodies from a snapshot for client-side prediction and reconciliation
+//! - Saving and restoring [snapshots](PhysicsSnapshot) of the physics world for save games and rollback
+//! - [Rollback](PhysicsRollback) support for deterministic netcode, with `bevy_ggrs` integration (with `ggrs` feature)
+//! - Configurable [timesteps](PhysicsTimestep), [time scale](PhysicsTime) and [substepping](SubstepCount)
 //! - `f32`/`f64` precision (`f32` by default)
 //!
 //! ## Getting started
@@ -79,7 +92,7 @@
 //!
 //! ### Feature flags
 //!
-//! Default features: `2d`/`3d`, `f32` and `collider-from-mesh`
+//! Default features: `2d`/`3d`, `f32`, `collider-from-mesh`, `parallel` and `spatial-query`
 //!
 //! - `2d` enables simulation on the `x` and `y` axes. Enabled by default for `bevy_xpbd_2d`. Incompatible with `3d`.
 //! - `3d` enables simulation on the `x`, `y` and `z` axes. Enabled by default for `bevy_xpbd_3d`. Incompatible with `2d`.
@@ -87,13 +100,54 @@
 //! - `f64` enables using `f64` numbers. Recommended when encountering stability problems, especially with
 //! small timesteps. Incompatible with `f32`.
 //! - `debug-plugin` enables the `PhysicsDebugPlugin` used for rendering physics objects and properties, like
-//! [colliders](Collider), [AABBs](ColliderAabb) and [contacts](Contact).
-//! - `collider-from-mesh` allows you to create [colliders](Collider) from Bevy meshes. Enables `bevy_render`.
+//! [colliders](Collider), [AABBs](ColliderAabb), [contacts](Contact) and [velocities](LinearVelocity).
+//! - `spatial-query` enables the `SpatialQueryPlugin` used for [spatial queries](spatial_query) like ray casting.
+//! - `frame-capture` enables the `FrameCapturePlugin` used for exporting physics frames into JSON files. Enables `serde`.
+//! - `serde` implements `Serialize` and `Deserialize` for the components and configuration resources,
+//! including [colliders](Collider), so that they can be stored in scenes and sent in network messages.
+//! - `ggrs` adds `register_physics_rollback` for registering the physics state for rollback with `bevy_ggrs`.
+//! See [`PhysicsRollback`].
+//! - `collider-from-mesh` allows you to create [colliders](Collider) from Bevy meshes, also asynchronously with `AsyncCollider`. Enables `bevy_render` and `bevy_asset`.
+//! - `async-collider` enables `AsyncSceneCollider` for creating colliders for the meshes of scenes. Only for 3D. Enables `collider-from-mesh` and `bevy_scene`.
+//! - `bone-collider` enables `BoneCollider` for making kinematic colliders follow the joints of skinned meshes. Only for 3D. Enables `bevy_render`.
+//! - `collider-from-image` allows you to create heightfield [colliders](Collider) from heightmap images. Enables `bevy_render`.
+//! - `baked-colliders` allows you to bake [colliders](Collider) like triangle meshes and convex decompositions into
+//! binary data offline and load them without computing them at runtime, also as `BakedCollider` assets.
+//! See [`Collider::bake`]. Enables `bevy_asset`.
+//! - `physics-material` enables the `PhysicsMaterial` asset for sharing friction, restitution and density between colliders. Enables `bevy_asset`.
 //! - `simd` enables [SIMD](https://en.wikipedia.org/wiki/Single_instruction,_multiple_data) optimizations.
 //! - `parallel` enables multithreading. This improves performance for larger simulations but can add unnecessary
 //! overhead for smaller ones.
-//! - `enhanced-determinism` enables increased determinism. (Note: cross-platform determinism doesn't work yet, even
-//! with this feature enabled)
+//! - `enhanced-determinism` makes simulations deterministic across platforms like x86_64, ARM and WASM,
+//! which is needed for things like rollback netcode. Transcendental functions like `sin` and `atan2` use `libm`
+//! instead of platform-dependent implementations, also in [`math::ops`], `glam` and `parry`. Enables `libm`.
+//!
+//! Subsystems that aren't needed can be left out to reduce compile times and binary size. For example,
+//! a game that only needs collision detection and dynamics could use:
+//!
+//! ```toml
+//! [dependencies]
+//! # Add 3D Bevy XPBD without spatial queries
+//! bevy_xpbd_3d = { version = "0.2", default-features = false, features = ["3d", "f32", "parallel"] }
+//! ```
+//!
+//! #### Headless builds
+//!
+//! Dedicated game servers usually don't need rendering, windows or assets. Only `collider-from-mesh`,
+//! `collider-from-image`, `async-collider`, `bone-collider`, `physics-material`, `baked-colliders` and
+//! `debug-plugin` depend on Bevy's rendering and asset crates, so without them, Bevy XPBD only uses core crates
+//! like `bevy_ecs`, `bevy_math`, `bevy_transform` and `bevy_time`. Colliders can still be created from vertices and indices
+//! with constructors like [`Collider::trimesh`] and [`Collider::convex_hull`].
+//!
+//! ```toml
+//! [dependencies]
+//! # Add 3D Bevy XPBD for a dedicated server
+//! bevy = { version = "0.11", default-features = false }
+//! bevy_xpbd_3d = { version = "0.2", default-features = false, features = ["3d", "f32", "parallel", "spatial-query"] }
+//! ```
+//!
+//! Bevy's `MinimalPlugins` together with the `TransformPlugin` are enough for running
+//! the [`PhysicsPlugins`] in a headless app.
 //!
 //! ### Install the plugin
 //!
@@ -181,7 +235,8 @@
 //!     - [Point projection](spatial_query#point-projection)
 //!     - [Intersection tests](spatial_query#intersection-tests)
 //! - [Configure the physics timestep](PhysicsTimestep)
-//! - [Configure the time scale](PhysicsTimescale)
+//! - [Pause, step and configure the time scale](PhysicsTime)
+//! - [Smooth out motion with transform interpolation](SyncPlugin#transform-interpolation)
 //! - [Configure the substep count](SubstepCount)
 //! - [Configure the schedule for running physics](PhysicsPlugins#custom-schedule)
 //! - [Usage on servers](#can-the-engine-be-used-on-servers)
@@ -270,11 +325,16 @@
 //! [set the schedule that runs physics](PhysicsPlugins#custom-schedule) and [configure the timestep](PhysicsTimestep)
 //! to whatever you want.
 //!
-//! One configuration is to run the client in `FixedUpdate`, and to use [`PhysicsTimestep::FixedOnce`] on both the
-//! server and the client to make sure the physics simulation is only advanced by one step each time the schedule runs.
+//! One configuration is to run physics in `FixedUpdate` on both the server and the client. By default, the simulation
+//! is then advanced by one step of the `FixedTime` period each time the schedule runs (see [`PhysicsTimestep::Schedule`]),
+//! so it stays in sync with game logic in `FixedUpdate`. In other schedules, [`PhysicsTimestep::FixedOnce`] can be
+//! used to make sure the physics simulation is only advanced by one step each time the schedule runs.
 //!
 //! Note that while Bevy XPBD should be locally deterministic, it can produce slightly different results on different
-//! machines.
+//! machines unless the `enhanced-determinism` feature is enabled. For lockstep and rollback networking, enable
+//! the feature and [`DeterministicMode`] so that the results don't depend on the platform or on the order
+//! in which entities are stored and iterated over. Use the functions in [`math::ops`] instead of methods like
+//! `f32::sin` in your own gameplay code that affects the simulation.
 //!
 //! ### Something else?
 //!
@@ -1,5 +1,9 @@
 //! Math types and traits used in the crate. Most of the math types are feature-dependent, so they will
 //! be different for `2d`/`3d` and `f32`/`f64`.
+//!
+//! There is no fixed-point backend, because the vector types come from `glam` and collision detection
+//! is done by `parry`, which only support `f32` and `f64`. For simulations that match across platforms,
+//! enable the `enhanced-determinism` feature instead and use the functions in [`ops`].
 
 #[cfg(feature = "f32")]
 mod single;
@@ -11,6 +15,11 @@ mod double;
 #[cfg(feature = "f64")]
 pub use double::*;
 
+mod wide;
+pub use wide::*;
+
+pub mod ops;
+
 use glam::*;
 
 /// Adjust the precision of the math construct to the precision chosen for compilation.
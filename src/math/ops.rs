@@ -0,0 +1,69 @@
+//! Transcendental functions for [`Scalar`] values.
+//!
+//! The standard library implementations of functions like `sin` and `atan2` can return slightly different
+//! results on different platforms. With the `enhanced-determinism` feature, these functions use `libm` instead,
+//! which gives the same results everywhere. Without the feature, they use the faster standard library
+//! implementations.
+//!
+//! The physics engine uses these functions internally, and code that needs to stay deterministic,
+//! for example for rollback netcode, should use them too.
+
+use super::Scalar;
+
+macro_rules! scalar_fn {
+    ($(#[$doc:meta] $name:ident($($arg:ident),+) => $f32:ident, $f64:ident;)*) => {
+        $(
+            #[$doc]
+            #[inline]
+            pub fn $name($($arg: Scalar),+) -> Scalar {
+                #[cfg(all(feature = "enhanced-determinism", feature = "f32"))]
+                {
+                    libm::$f32($($arg),+)
+                }
+                #[cfg(all(feature = "enhanced-determinism", feature = "f64"))]
+                {
+                    libm::$f64($($arg),+)
+                }
+                #[cfg(not(feature = "enhanced-determinism"))]
+                {
+                    scalar_fn!(@std $($arg),+; $name)
+                }
+            }
+        )*
+    };
+    (@std $x:ident; $name:ident) => {
+        $x.$name()
+    };
+    (@std $x:ident, $y:ident; $name:ident) => {
+        $x.$name($y)
+    };
+}
+
+scalar_fn! {
+    /// Computes the sine of `x` in radians.
+    sin(x) => sinf, sin;
+    /// Computes the cosine of `x` in radians.
+    cos(x) => cosf, cos;
+    /// Computes the tangent of `x` in radians.
+    tan(x) => tanf, tan;
+    /// Computes the arcsine of `x` in radians.
+    asin(x) => asinf, asin;
+    /// Computes the arccosine of `x` in radians.
+    acos(x) => acosf, acos;
+    /// Computes the arctangent of `x` in radians.
+    atan(x) => atanf, atan;
+    /// Computes the four quadrant arctangent of `y` and `x` in radians.
+    atan2(y, x) => atan2f, atan2;
+    /// Computes `e^x`.
+    exp(x) => expf, exp;
+    /// Computes the natural logarithm of `x`.
+    ln(x) => logf, log;
+    /// Computes `x` raised to the power of `y`.
+    powf(x, y) => powf, pow;
+}
+
+/// Computes the sine and cosine of `x` in radians.
+#[inline]
+pub fn sin_cos(x: Scalar) -> (Scalar, Scalar) {
+    (sin(x), cos(x))
+}
@@ -0,0 +1,242 @@
+//! Wide math types that store several values in a structure of arrays layout, so that the same operation
+//! can be applied to all of the values at once. The operations are simple loops over the lanes,
+//! which the compiler turns into SIMD instructions.
+
+#[cfg(feature = "3d")]
+use super::Matrix3;
+use super::{Scalar, Vector};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// The number of values in the wide math types, chosen so that they fill a 256-bit SIMD register.
+#[cfg(feature = "f32")]
+pub const LANES: usize = 8;
+/// The number of values in the wide math types, chosen so that they fill a 256-bit SIMD register.
+#[cfg(feature = "f64")]
+pub const LANES: usize = 4;
+
+/// [`LANES`] scalars that are operated on at once.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WideScalar(pub [Scalar; LANES]);
+
+impl WideScalar {
+    /// All lanes set to zero.
+    pub const ZERO: Self = Self([0.0; LANES]);
+
+    /// Creates a wide scalar with all lanes set to the given value.
+    pub fn splat(value: Scalar) -> Self {
+        Self([value; LANES])
+    }
+
+    /// Creates a wide scalar by calling the given function with the index of each lane.
+    pub fn from_fn(f: impl FnMut(usize) -> Scalar) -> Self {
+        Self(std::array::from_fn(f))
+    }
+
+    /// Applies the given function to each pair of lanes of `self` and `other`.
+    #[inline]
+    fn zip_map(self, other: Self, f: impl Fn(Scalar, Scalar) -> Scalar) -> Self {
+        Self::from_fn(|i| f(self.0[i], other.0[i]))
+    }
+
+    /// Returns the lane-wise minimum of `self` and `other`.
+    pub fn min(self, other: Self) -> Self {
+        self.zip_map(other, Scalar::min)
+    }
+
+    /// Returns the lane-wise maximum of `self` and `other`.
+    pub fn max(self, other: Self) -> Self {
+        self.zip_map(other, Scalar::max)
+    }
+
+    /// Returns the lanes of `self` where `mask` is true, and the lanes of `other` elsewhere.
+    pub fn select(self, mask: [bool; LANES], other: Self) -> Self {
+        Self::from_fn(|i| if mask[i] { self.0[i] } else { other.0[i] })
+    }
+
+    /// Returns a mask that is true for the lanes of `self` that are greater than the lanes of `other`.
+    pub fn gt(self, other: Self) -> [bool; LANES] {
+        std::array::from_fn(|i| self.0[i] > other.0[i])
+    }
+}
+
+impl Default for WideScalar {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl Add for WideScalar {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        self.zip_map(rhs, |a, b| a + b)
+    }
+}
+
+impl Sub for WideScalar {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        self.zip_map(rhs, |a, b| a - b)
+    }
+}
+
+impl Mul for WideScalar {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        self.zip_map(rhs, |a, b| a * b)
+    }
+}
+
+impl Div for WideScalar {
+    type Output = Self;
+    #[inline]
+    fn div(self, rhs: Self) -> Self {
+        self.zip_map(rhs, |a, b| a / b)
+    }
+}
+
+impl Neg for WideScalar {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self {
+        Self::from_fn(|i| -self.0[i])
+    }
+}
+
+/// [`LANES`] vectors that are operated on at once, stored as one [`WideScalar`] per axis.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WideVector {
+    /// The x coordinates of the vectors.
+    pub x: WideScalar,
+    /// The y coordinates of the vectors.
+    pub y: WideScalar,
+    /// The z coordinates of the vectors.
+    #[cfg(feature = "3d")]
+    pub z: WideScalar,
+}
+
+impl WideVector {
+    /// Creates a wide vector from the vectors of each lane.
+    pub fn from_vectors(vectors: [Vector; LANES]) -> Self {
+        Self {
+            x: WideScalar::from_fn(|i| vectors[i].x),
+            y: WideScalar::from_fn(|i| vectors[i].y),
+            #[cfg(feature = "3d")]
+            z: WideScalar::from_fn(|i| vectors[i].z),
+        }
+    }
+
+    /// Returns the vector of the given lane.
+    pub fn lane(&self, index: usize) -> Vector {
+        #[cfg(feature = "2d")]
+        {
+            Vector::new(self.x.0[index], self.y.0[index])
+        }
+        #[cfg(feature = "3d")]
+        {
+            Vector::new(self.x.0[index], self.y.0[index], self.z.0[index])
+        }
+    }
+
+    /// Computes the dot products of the vectors of each lane.
+    pub fn dot(self, rhs: Self) -> WideScalar {
+        #[cfg(feature = "2d")]
+        {
+            self.x * rhs.x + self.y * rhs.y
+        }
+        #[cfg(feature = "3d")]
+        {
+            self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+        }
+    }
+
+    /// Computes the 2D cross products, or perpendicular dot products, of the vectors of each lane.
+    #[cfg(feature = "2d")]
+    pub fn perp_dot(self, rhs: Self) -> WideScalar {
+        self.x * rhs.y - self.y * rhs.x
+    }
+
+    /// Computes the cross products of the vectors of each lane.
+    #[cfg(feature = "3d")]
+    pub fn cross(self, rhs: Self) -> Self {
+        Self {
+            x: self.y * rhs.z - self.z * rhs.y,
+            y: self.z * rhs.x - self.x * rhs.z,
+            z: self.x * rhs.y - self.y * rhs.x,
+        }
+    }
+}
+
+impl Add for WideVector {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+            #[cfg(feature = "3d")]
+            z: self.z + rhs.z,
+        }
+    }
+}
+
+impl Sub for WideVector {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            #[cfg(feature = "3d")]
+            z: self.z - rhs.z,
+        }
+    }
+}
+
+impl Mul<WideScalar> for WideVector {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: WideScalar) -> Self {
+        Self {
+            x: self.x * rhs,
+            y: self.y * rhs,
+            #[cfg(feature = "3d")]
+            z: self.z * rhs,
+        }
+    }
+}
+
+/// [`LANES`] 3x3 matrices that are operated on at once, stored as one [`WideVector`] per column.
+#[cfg(feature = "3d")]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WideMatrix3 {
+    /// The first columns of the matrices.
+    pub x_axis: WideVector,
+    /// The second columns of the matrices.
+    pub y_axis: WideVector,
+    /// The third columns of the matrices.
+    pub z_axis: WideVector,
+}
+
+#[cfg(feature = "3d")]
+impl WideMatrix3 {
+    /// Creates a wide matrix from the matrices of each lane.
+    pub fn from_matrices(matrices: [Matrix3; LANES]) -> Self {
+        Self {
+            x_axis: WideVector::from_vectors(matrices.map(|matrix| matrix.x_axis)),
+            y_axis: WideVector::from_vectors(matrices.map(|matrix| matrix.y_axis)),
+            z_axis: WideVector::from_vectors(matrices.map(|matrix| matrix.z_axis)),
+        }
+    }
+}
+
+#[cfg(feature = "3d")]
+impl Mul<WideVector> for WideMatrix3 {
+    type Output = WideVector;
+    #[inline]
+    fn mul(self, rhs: WideVector) -> WideVector {
+        self.x_axis * rhs.x + self.y_axis * rhs.y + self.z_axis * rhs.z
+    }
+}
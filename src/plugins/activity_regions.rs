@@ -0,0 +1,244 @@
+//! Collects the bodies that moved during a physics frame into spatial regions for interest management.
+//!
+//! See [`ActivityRegionsPlugin`].
+
+use crate::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
+
+/// Collects the [rigid bodies](RigidBody) whose [`Position`] or [`Rotation`] changed during a physics frame
+/// into the grid cells and user-defined regions of the [`ActivityRegions`] resource.
+///
+/// This can be used for interest management, for example by network servers that build delta updates
+/// for the area of each client without scanning every body.
+///
+/// Nothing is collected if the [`ActivityRegions`] resource doesn't exist.
+pub struct ActivityRegionsPlugin {
+    schedule: Box<dyn ScheduleLabel>,
+}
+
+impl ActivityRegionsPlugin {
+    /// Creates an [`ActivityRegionsPlugin`] with the schedule that is used for running the [`PhysicsSchedule`].
+    ///
+    /// The default schedule is `PostUpdate`.
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: Box::new(schedule),
+        }
+    }
+}
+
+impl Default for ActivityRegionsPlugin {
+    fn default() -> Self {
+        Self::new(PostUpdate)
+    }
+}
+
+impl Plugin for ActivityRegionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ActivityRegions>()
+            .register_type::<ActivityRegion>()
+            .add_systems(
+                self.schedule.dyn_clone(),
+                collect_active_bodies
+                    .after(PhysicsSet::StepSimulation)
+                    .before(PhysicsSet::Sync)
+                    .run_if(resource_exists::<ActivityRegions>()),
+            );
+    }
+}
+
+/// The coordinates of a cell in the grid of [`ActivityRegions`].
+#[cfg(feature = "2d")]
+pub type GridCell = IVec2;
+/// The coordinates of a cell in the grid of [`ActivityRegions`].
+#[cfg(feature = "3d")]
+pub type GridCell = IVec3;
+
+/// An axis-aligned box that [active bodies](ActivityRegions) are collected into.
+#[derive(Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActivityRegion {
+    /// The minimum corner of the region.
+    pub min: Vector,
+    /// The maximum corner of the region.
+    pub max: Vector,
+}
+
+impl ActivityRegion {
+    /// Creates a new [`ActivityRegion`] with the given minimum and maximum corners.
+    pub fn new(min: Vector, max: Vector) -> Self {
+        Self { min, max }
+    }
+
+    /// Returns true if the given point is inside of the region.
+    pub fn contains(&self, point: Vector) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+}
+
+/// An optional resource containing the [rigid bodies](RigidBody) whose [`Position`] or [`Rotation`]
+/// changed during the latest physics frame, grouped by grid cells and user-defined [regions](ActivityRegion).
+///
+/// Bodies are assigned to cells and regions based on their [`Position`]. Sleeping and static bodies
+/// don't move, so they are never active. The lists are updated by the [`ActivityRegionsPlugin`]
+/// after each physics frame.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     // Collect active bodies into cells of 50x50 units
+///     commands.insert_resource(ActivityRegions::new(Some(50.0)));
+/// }
+///
+/// fn send_updates(regions: Res<ActivityRegions>, bodies: Query<(&Position, &Rotation)>) {
+///     for (_cell, entities) in regions.cells() {
+///         for (_position, _rotation) in bodies.iter_many(entities) {
+///             // Send the state to the clients interested in the cell
+///         }
+///     }
+/// }
+/// ```
+#[derive(Resource, Reflect, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Resource)]
+pub struct ActivityRegions {
+    /// The size of the grid cells. If `None`, bodies are only collected into the [regions](#structfield.regions).
+    pub cell_size: Option<Scalar>,
+    /// User-defined regions that bodies are collected into in addition to the grid cells.
+    pub regions: Vec<ActivityRegion>,
+    /// The active bodies in each grid cell that contains active bodies.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[reflect(ignore)]
+    cell_bodies: HashMap<GridCell, Vec<Entity>>,
+    /// The active bodies in each region, in the same order as `regions`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    region_bodies: Vec<Vec<Entity>>,
+    /// The pose of each body when it was last collected, used for ignoring bodies
+    /// whose pose was written to without actually changing.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[reflect(ignore)]
+    previous_poses: HashMap<Entity, (Vector, Rotation)>,
+}
+
+impl ActivityRegions {
+    /// Creates new [`ActivityRegions`] with the given grid cell size and no user-defined regions.
+    pub fn new(cell_size: Option<Scalar>) -> Self {
+        Self {
+            cell_size,
+            ..default()
+        }
+    }
+
+    /// Adds a user-defined [region](ActivityRegion) and returns its index.
+    pub fn add_region(&mut self, region: ActivityRegion) -> usize {
+        self.regions.push(region);
+        self.regions.len() - 1
+    }
+
+    /// Returns the grid cell containing the given point, or `None` if there is no grid.
+    pub fn cell_at(&self, point: Vector) -> Option<GridCell> {
+        let cell_size = self.cell_size?;
+        let cell = (point / cell_size).floor();
+        #[cfg(feature = "2d")]
+        {
+            Some(GridCell::new(cell.x as i32, cell.y as i32))
+        }
+        #[cfg(feature = "3d")]
+        {
+            Some(GridCell::new(cell.x as i32, cell.y as i32, cell.z as i32))
+        }
+    }
+
+    /// Returns the active bodies in the given grid cell.
+    pub fn bodies_in_cell(&self, cell: GridCell) -> &[Entity] {
+        self.cell_bodies
+            .get(&cell)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns the active bodies in the user-defined region at the given index.
+    pub fn bodies_in_region(&self, index: usize) -> &[Entity] {
+        self.region_bodies
+            .get(index)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns an iterator over the grid cells containing active bodies and the bodies in them.
+    pub fn cells(&self) -> impl Iterator<Item = (GridCell, &[Entity])> {
+        self.cell_bodies
+            .iter()
+            .map(|(cell, bodies)| (*cell, bodies.as_slice()))
+    }
+
+    /// Clears the active bodies of all cells and regions.
+    fn clear(&mut self) {
+        self.cell_bodies.clear();
+        self.region_bodies.resize(self.regions.len(), vec![]);
+        for bodies in self.region_bodies.iter_mut() {
+            bodies.clear();
+        }
+    }
+}
+
+/// Collects the bodies that moved during the physics frame into the [`ActivityRegions`].
+///
+/// The solver writes to the [`Position`] and [`Rotation`] of every awake body, so change detection
+/// alone would also match bodies that are at rest. Their poses are compared against the poses
+/// from when they were last collected instead.
+#[allow(clippy::type_complexity)]
+fn collect_active_bodies(
+    bodies: Query<
+        (Entity, &RigidBody, &Position, &Rotation),
+        (
+            Or<(Changed<Position>, Changed<Rotation>)>,
+            Without<Sleeping>,
+        ),
+    >,
+    mut removed_bodies: RemovedComponents<RigidBody>,
+    mut activity_regions: ResMut<ActivityRegions>,
+) {
+    let activity_regions = &mut *activity_regions;
+    activity_regions.clear();
+
+    for entity in removed_bodies.iter() {
+        activity_regions.previous_poses.remove(&entity);
+    }
+
+    for (entity, rb, position, rotation) in &bodies {
+        if rb.is_static() {
+            continue;
+        }
+
+        let pose = (position.0, *rotation);
+        if activity_regions.previous_poses.insert(entity, pose) == Some(pose) {
+            continue;
+        }
+
+        if let Some(cell) = activity_regions.cell_at(position.0) {
+            activity_regions
+                .cell_bodies
+                .entry(cell)
+                .or_default()
+                .push(entity);
+        }
+
+        for (region, bodies) in activity_regions
+            .regions
+            .iter()
+            .zip(activity_regions.region_bodies.iter_mut())
+        {
+            if region.contains(position.0) {
+                bodies.push(entity);
+            }
+        }
+    }
+}
@@ -0,0 +1,422 @@
+//! A dynamic bounding volume hierarchy used by the [broad phase](super::BroadPhasePlugin).
+
+use super::{collect_pairs, AabbProxy, BatchPairs};
+use crate::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
+use parry::bounding_volume::{Aabb, BoundingVolume};
+
+/// A node of the [`DynamicBvh`]. Leaves store an [`AabbProxy`], and other nodes have two children.
+#[derive(Clone, Debug)]
+struct BvhNode {
+    /// The enlarged AABB of a leaf, or the AABB containing both children.
+    aabb: Aabb,
+    parent: Option<usize>,
+    children: Option<[usize; 2]>,
+    /// The height of the node in the tree. Leaves have a height of zero.
+    height: usize,
+    proxy: Option<AabbProxy>,
+}
+
+/// A dynamic bounding volume hierarchy of the [`ColliderAabb`]s of colliders.
+///
+/// Each collider is stored in a leaf with an enlarged AABB, so colliders can move slightly without the tree
+/// having to change. When a collider moves outside of its enlarged AABB, its leaf is removed and reinserted.
+/// Leaves are inserted next to the sibling that increases the surface area of the tree the least,
+/// and the tree is kept balanced using tree rotations.
+#[derive(Resource, Debug, Default)]
+pub(super) struct DynamicBvh {
+    nodes: Vec<BvhNode>,
+    free_nodes: Vec<usize>,
+    root: Option<usize>,
+    leaves: HashMap<Entity, usize>,
+}
+
+impl DynamicBvh {
+    /// Inserts or updates the leaf of the given collider. The AABB stored in the tree is enlarged
+    /// by the given margin, and the leaf is only moved in the tree if the collider's AABB
+    /// no longer fits in the enlarged AABB.
+    pub fn insert_or_update(&mut self, proxy: AabbProxy, margin: Scalar) {
+        if let Some(&leaf) = self.leaves.get(&proxy.entity) {
+            self.nodes[leaf].proxy = Some(proxy);
+
+            if self.nodes[leaf].aabb.contains(&proxy.aabb) {
+                return;
+            }
+
+            self.remove_leaf(leaf);
+            self.nodes[leaf].aabb = proxy.aabb.loosened(margin);
+            self.insert_leaf(leaf);
+        } else {
+            let leaf = self.allocate_node(BvhNode {
+                aabb: proxy.aabb.loosened(margin),
+                parent: None,
+                children: None,
+                height: 0,
+                proxy: Some(proxy),
+            });
+            self.leaves.insert(proxy.entity, leaf);
+            self.insert_leaf(leaf);
+        }
+    }
+
+    /// Removes the leaf of the given collider from the tree.
+    pub fn remove(&mut self, entity: Entity) {
+        if let Some(leaf) = self.leaves.remove(&entity) {
+            self.remove_leaf(leaf);
+            self.free_node(leaf);
+        }
+    }
+
+    /// Removes the leaves of all colliders for which the given predicate returns false.
+    pub fn retain(&mut self, mut predicate: impl FnMut(Entity) -> bool) {
+        let removed = self
+            .leaves
+            .keys()
+            .copied()
+            .filter(|entity| !predicate(*entity))
+            .collect::<Vec<_>>();
+        for entity in removed {
+            self.remove(entity);
+        }
+    }
+
+    /// Returns the number of colliders in the tree.
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// Returns the height of the tree.
+    pub fn height(&self) -> usize {
+        self.root.map_or(0, |root| self.nodes[root].height)
+    }
+
+    /// Adds the entities of each pair of colliders whose tight AABBs intersect and that pass the given filter to `pairs`.
+    ///
+    /// With the `parallel` feature, the tree is traversed from several leaves in parallel.
+    pub fn collect_intersecting_pairs(
+        &self,
+        filter: impl Fn(&AabbProxy, &AabbProxy) -> bool + Sync,
+        pairs: &mut Vec<(Entity, Entity)>,
+        batch_pairs: &mut BatchPairs,
+    ) {
+        let leaves = self.leaves.values().copied().collect::<Vec<_>>();
+
+        collect_pairs(
+            &leaves,
+            |_, leaves, pairs| {
+                let mut stack = Vec::new();
+
+                for &leaf in leaves {
+                    let Some(proxy1) = &self.nodes[leaf].proxy else {
+                        continue;
+                    };
+
+                    stack.clear();
+                    stack.extend(self.root);
+
+                    while let Some(index) = stack.pop() {
+                        let node = &self.nodes[index];
+
+                        if !node.aabb.intersects(&proxy1.aabb) {
+                            continue;
+                        }
+
+                        if let Some(children) = node.children {
+                            stack.extend(children);
+                        } else if leaf < index {
+                            // Each pair is found from both leaves, so only report it once
+                            if let Some(proxy2) = &node.proxy {
+                                if proxy1.aabb.intersects(&proxy2.aabb) && filter(proxy1, proxy2) {
+                                    pairs.push((proxy1.entity, proxy2.entity));
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            pairs,
+            batch_pairs,
+        );
+    }
+
+    fn allocate_node(&mut self, node: BvhNode) -> usize {
+        if let Some(index) = self.free_nodes.pop() {
+            self.nodes[index] = node;
+            index
+        } else {
+            self.nodes.push(node);
+            self.nodes.len() - 1
+        }
+    }
+
+    fn free_node(&mut self, index: usize) {
+        self.nodes[index].proxy = None;
+        self.nodes[index].children = None;
+        self.nodes[index].parent = None;
+        self.free_nodes.push(index);
+    }
+
+    /// Inserts a leaf next to the sibling that results in the smallest increase in surface area.
+    fn insert_leaf(&mut self, leaf: usize) {
+        let Some(root) = self.root else {
+            self.root = Some(leaf);
+            self.nodes[leaf].parent = None;
+            return;
+        };
+
+        let leaf_aabb = self.nodes[leaf].aabb;
+
+        // Find the best sibling by descending the tree
+        let mut index = root;
+        while let Some([child1, child2]) = self.nodes[index].children {
+            let area = surface_area(&self.nodes[index].aabb);
+            let combined_area = surface_area(&self.nodes[index].aabb.merged(&leaf_aabb));
+
+            // The cost of creating a new parent for this node and the leaf
+            let cost = 2.0 * combined_area;
+            // The minimum cost of pushing the leaf further down the tree
+            let inheritance_cost = 2.0 * (combined_area - area);
+
+            let child_cost = |child: usize| {
+                let child_aabb = &self.nodes[child].aabb;
+                let merged_area = surface_area(&child_aabb.merged(&leaf_aabb));
+                if self.nodes[child].children.is_none() {
+                    merged_area + inheritance_cost
+                } else {
+                    merged_area - surface_area(child_aabb) + inheritance_cost
+                }
+            };
+            let cost1 = child_cost(child1);
+            let cost2 = child_cost(child2);
+
+            if cost < cost1 && cost < cost2 {
+                break;
+            }
+
+            index = if cost1 < cost2 { child1 } else { child2 };
+        }
+        let sibling = index;
+
+        // Create a new parent for the sibling and the leaf
+        let old_parent = self.nodes[sibling].parent;
+        let new_parent = self.allocate_node(BvhNode {
+            aabb: self.nodes[sibling].aabb.merged(&leaf_aabb),
+            parent: old_parent,
+            children: Some([sibling, leaf]),
+            height: self.nodes[sibling].height + 1,
+            proxy: None,
+        });
+
+        if let Some(old_parent) = old_parent {
+            self.replace_child(old_parent, sibling, new_parent);
+        } else {
+            self.root = Some(new_parent);
+        }
+        self.nodes[sibling].parent = Some(new_parent);
+        self.nodes[leaf].parent = Some(new_parent);
+
+        self.refit_ancestors(Some(new_parent));
+    }
+
+    /// Removes a leaf from the tree without freeing it. The leaf's sibling takes the place of their parent.
+    fn remove_leaf(&mut self, leaf: usize) {
+        if self.root == Some(leaf) {
+            self.root = None;
+            return;
+        }
+
+        let Some(parent) = self.nodes[leaf].parent else {
+            return;
+        };
+        let Some([child1, child2]) = self.nodes[parent].children else {
+            return;
+        };
+        let sibling = if child1 == leaf { child2 } else { child1 };
+        let grandparent = self.nodes[parent].parent;
+
+        self.nodes[sibling].parent = grandparent;
+        if let Some(grandparent) = grandparent {
+            self.replace_child(grandparent, parent, sibling);
+        } else {
+            self.root = Some(sibling);
+        }
+        self.free_node(parent);
+        self.nodes[leaf].parent = None;
+
+        self.refit_ancestors(grandparent);
+    }
+
+    fn replace_child(&mut self, parent: usize, old_child: usize, new_child: usize) {
+        if let Some(children) = &mut self.nodes[parent].children {
+            for child in children.iter_mut() {
+                if *child == old_child {
+                    *child = new_child;
+                }
+            }
+        }
+    }
+
+    /// Walks up the tree from the given node, balancing the nodes and updating their heights and AABBs.
+    fn refit_ancestors(&mut self, mut index: Option<usize>) {
+        while let Some(node) = index {
+            let node = self.balance(node);
+
+            if let Some([child1, child2]) = self.nodes[node].children {
+                self.nodes[node].height =
+                    1 + self.nodes[child1].height.max(self.nodes[child2].height);
+                self.nodes[node].aabb = self.nodes[child1].aabb.merged(&self.nodes[child2].aabb);
+            }
+
+            index = self.nodes[node].parent;
+        }
+    }
+
+    /// Performs a tree rotation if the heights of the children of the given node differ by more than one.
+    ///
+    /// Returns the index of the node that took the place of the given node.
+    fn balance(&mut self, node: usize) -> usize {
+        let Some([child1, child2]) = self.nodes[node].children else {
+            return node;
+        };
+        if self.nodes[node].height < 2 {
+            return node;
+        }
+
+        let height1 = self.nodes[child1].height;
+        let height2 = self.nodes[child2].height;
+
+        if height2 > height1 + 1 {
+            self.rotate_up(node, 1)
+        } else if height1 > height2 + 1 {
+            self.rotate_up(node, 0)
+        } else {
+            node
+        }
+    }
+
+    /// Moves the child in the given slot of `node` up to take the place of `node`.
+    /// The taller grandchild stays under the lifted child, and the shorter one is moved under `node`.
+    ///
+    /// Returns the index of the lifted child.
+    fn rotate_up(&mut self, node: usize, slot: usize) -> usize {
+        let children = self.nodes[node].children.unwrap();
+        let lifted = children[slot];
+        let other = children[1 - slot];
+
+        let Some([grandchild1, grandchild2]) = self.nodes[lifted].children else {
+            return node;
+        };
+        let (taller, shorter) = if self.nodes[grandchild1].height > self.nodes[grandchild2].height {
+            (grandchild1, grandchild2)
+        } else {
+            (grandchild2, grandchild1)
+        };
+
+        // The lifted child takes the place of the node
+        let parent = self.nodes[node].parent;
+        self.nodes[lifted].parent = parent;
+        if let Some(parent) = parent {
+            self.replace_child(parent, node, lifted);
+        } else {
+            self.root = Some(lifted);
+        }
+
+        // The node becomes a child of the lifted node, and the shorter grandchild moves under the node
+        self.nodes[lifted].children = Some([node, taller]);
+        self.nodes[node].parent = Some(lifted);
+
+        let mut node_children = children;
+        node_children[slot] = shorter;
+        self.nodes[node].children = Some(node_children);
+        self.nodes[shorter].parent = Some(node);
+
+        self.nodes[node].aabb = self.nodes[other].aabb.merged(&self.nodes[shorter].aabb);
+        self.nodes[node].height = 1 + self.nodes[other].height.max(self.nodes[shorter].height);
+        self.nodes[lifted].aabb = self.nodes[node].aabb.merged(&self.nodes[taller].aabb);
+        self.nodes[lifted].height = 1 + self.nodes[node].height.max(self.nodes[taller].height);
+
+        lifted
+    }
+}
+
+/// Returns the perimeter of the AABB in 2D.
+#[cfg(feature = "2d")]
+fn surface_area(aabb: &Aabb) -> Scalar {
+    let extents = aabb.extents();
+    2.0 * (extents.x + extents.y)
+}
+
+/// Returns the surface area of the AABB in 3D.
+#[cfg(feature = "3d")]
+fn surface_area(aabb: &Aabb) -> Scalar {
+    let extents = aabb.extents();
+    2.0 * (extents.x * extents.y + extents.y * extents.z + extents.z * extents.x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::tasks::{ComputeTaskPool, TaskPool};
+
+    fn proxy(index: u32, center: Vector) -> AabbProxy {
+        let mut aabb = ColliderAabb::default();
+        aabb.mins.coords = (center - Vector::splat(0.5)).into();
+        aabb.maxs.coords = (center + Vector::splat(0.5)).into();
+        AabbProxy {
+            entity: Entity::from_raw(index),
+            parent: Entity::from_raw(index),
+            aabb,
+            rb: RigidBody::Dynamic,
+            layers: CollisionLayers::default(),
+        }
+    }
+
+    fn intersecting_pairs(bvh: &DynamicBvh) -> Vec<(u32, u32)> {
+        ComputeTaskPool::init(TaskPool::default);
+
+        let mut pairs = vec![];
+        bvh.collect_intersecting_pairs(|_, _| true, &mut pairs, &mut BatchPairs::default());
+        let mut pairs = pairs
+            .into_iter()
+            .map(|(entity1, entity2)| {
+                let (a, b) = (entity1.index(), entity2.index());
+                (a.min(b), a.max(b))
+            })
+            .collect::<Vec<_>>();
+        pairs.sort();
+        pairs
+    }
+
+    #[test]
+    fn dynamic_bvh_finds_intersecting_pairs() {
+        let mut bvh = DynamicBvh::default();
+
+        // A row of AABBs where each one overlaps the next one
+        for i in 0..100 {
+            bvh.insert_or_update(proxy(i, Vector::X * 0.9 * i as Scalar), 0.1);
+        }
+        assert_eq!(bvh.len(), 100);
+        let expected = (0..99).map(|i| (i, i + 1)).collect::<Vec<_>>();
+        assert_eq!(intersecting_pairs(&bvh), expected);
+
+        // The tree stays balanced
+        assert!(bvh.height() <= 14);
+
+        // Teleporting an AABB reinserts it
+        bvh.insert_or_update(proxy(50, Vector::Y * 100.0), 0.1);
+        bvh.insert_or_update(proxy(100, Vector::Y * 100.5), 0.1);
+        let pairs = intersecting_pairs(&bvh);
+        assert!(pairs.contains(&(50, 100)));
+        assert!(!pairs.contains(&(49, 50)));
+        assert!(!pairs.contains(&(50, 51)));
+
+        // Removed AABBs no longer intersect anything
+        bvh.retain(|entity| entity.index() != 100);
+        bvh.remove(Entity::from_raw(0));
+        assert_eq!(bvh.len(), 99);
+        let pairs = intersecting_pairs(&bvh);
+        assert!(!pairs.contains(&(50, 100)));
+        assert!(!pairs.contains(&(0, 1)));
+        assert!(pairs.contains(&(1, 2)));
+    }
+}
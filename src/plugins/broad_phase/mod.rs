@@ -0,0 +1,692 @@
+//! Collects pairs of potentially colliding entities into [`BroadCollisionPairs`] using
+//! [AABB](ColliderAabb) intersection checks.
+//!
+//! See [`BroadPhasePlugin`].
+
+mod dynamic_bvh;
+mod spatial_hash;
+
+use crate::{prelude::*, utils::entity_sort_key};
+#[cfg(feature = "parallel")]
+use bevy::tasks::ComputeTaskPool;
+use bevy::{ecs::query::QueryItem, prelude::*, utils::HashSet};
+use dynamic_bvh::DynamicBvh;
+use spatial_hash::SpatialHash;
+
+/// Collects pairs of potentially colliding entities into [`BroadCollisionPairs`] using
+/// [AABB](ColliderAabb) intersection checks. This speeds up narrow phase collision detection,
+/// as the number of precise collision checks required is greatly reduced.
+///
+/// The algorithm is chosen using the [`BroadPhaseConfig`] resource:
+///
+/// - [`BroadPhaseAlgorithm::SweepAndPrune`] uses the [sweep and prune](https://en.wikipedia.org/wiki/Sweep_and_prune) algorithm.
+/// The AABBs are kept sorted across frames along the axis that they are the most spread out on,
+/// so that as few AABBs as possible overlap along the sweep axis.
+/// - [`BroadPhaseAlgorithm::DynamicBvh`] stores enlarged AABBs in a dynamic bounding volume hierarchy
+/// that is refit incrementally. This performs better than sweep and prune for clustered scenes
+/// and for bodies that move far or teleport.
+/// - [`BroadPhaseAlgorithm::SpatialHash`] inserts the AABBs into the cells of a uniform grid every frame.
+/// This performs best for large numbers of similarly sized bodies, like bullets, particles and crowds.
+///
+/// Pairs are filtered using [`CollisionLayers`] and the [`CollisionMatrix`] resource if it exists.
+/// Colliders that are [frozen](FrozenInRoom) in inactive [rooms](PhysicsRooms) are ignored.
+///
+/// With the `parallel` feature, the AABBs are computed and the pairs are collected on the `ComputeTaskPool`.
+/// The pairs are collected in the same order as on a single thread, so the results stay deterministic.
+///
+/// The broad phase systems run in [`PhysicsStepSet::BroadPhase`].
+pub struct BroadPhasePlugin;
+
+impl Plugin for BroadPhasePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BroadPhaseConfig>()
+            .init_resource::<AabbIntervals>()
+            .init_resource::<DynamicBvh>()
+            .init_resource::<SpatialHash>()
+            .register_type::<BroadPhaseConfig>()
+            .register_type::<BroadPhaseAlgorithm>();
+
+        let physics_schedule = app
+            .get_schedule_mut(PhysicsSchedule)
+            .expect("add PhysicsSchedule first");
+
+        physics_schedule.add_systems(
+            (
+                update_aabb,
+                (
+                    update_aabb_intervals,
+                    add_new_aabb_intervals,
+                    collect_collision_pairs,
+                )
+                    .chain()
+                    .run_if(|config: Res<BroadPhaseConfig>| {
+                        config.algorithm == BroadPhaseAlgorithm::SweepAndPrune
+                    }),
+                (update_dynamic_bvh, collect_bvh_collision_pairs)
+                    .chain()
+                    .run_if(|config: Res<BroadPhaseConfig>| {
+                        config.algorithm == BroadPhaseAlgorithm::DynamicBvh
+                    }),
+                collect_spatial_hash_collision_pairs.run_if(|config: Res<BroadPhaseConfig>| {
+                    config.algorithm == BroadPhaseAlgorithm::SpatialHash
+                }),
+                sort_collision_pairs.run_if(|mode: Res<DeterministicMode>| mode.0),
+            )
+                .chain()
+                .in_set(PhysicsStepSet::BroadPhase),
+        );
+    }
+}
+
+/// A resource for configuring the [broad phase](BroadPhasePlugin).
+///
+/// ## Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn main() {
+///     App::new()
+///         .add_plugins((DefaultPlugins, PhysicsPlugins::default()))
+///         // Use a spatial hash for a scene with lots of small bullets
+///         .insert_resource(BroadPhaseConfig {
+///             algorithm: BroadPhaseAlgorithm::SpatialHash,
+///             cell_size: 0.5,
+///             ..default()
+///         })
+///         .run();
+/// }
+/// ```
+#[derive(Resource, Reflect, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Resource)]
+pub struct BroadPhaseConfig {
+    /// The algorithm used for finding pairs of intersecting AABBs.
+    pub algorithm: BroadPhaseAlgorithm,
+    /// The distance that the AABBs stored in the [dynamic BVH](BroadPhaseAlgorithm::DynamicBvh) are enlarged by.
+    ///
+    /// Colliders can move this far before their leaves have to be reinserted into the tree,
+    /// but larger margins also produce more pairs for the narrow phase to reject.
+    pub aabb_margin: Scalar,
+    /// The size of the grid cells used by the [spatial hash](BroadPhaseAlgorithm::SpatialHash).
+    ///
+    /// The cells should be about as large as the typical collider. Colliders that cover many cells,
+    /// like the ground, are tested against all other colliders instead.
+    pub cell_size: Scalar,
+}
+
+impl Default for BroadPhaseConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: BroadPhaseAlgorithm::default(),
+            #[cfg(feature = "2d")]
+            aabb_margin: 5.0,
+            #[cfg(feature = "3d")]
+            aabb_margin: 0.1,
+            #[cfg(feature = "2d")]
+            cell_size: 100.0,
+            #[cfg(feature = "3d")]
+            cell_size: 2.0,
+        }
+    }
+}
+
+/// The algorithm used by the [broad phase](BroadPhasePlugin).
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BroadPhaseAlgorithm {
+    /// Sweep and prune along the axis with the largest AABB spread.
+    ///
+    /// Works well when bodies are spread out and move smoothly.
+    #[default]
+    SweepAndPrune,
+    /// A dynamic bounding volume hierarchy with enlarged AABBs and incremental refitting.
+    ///
+    /// Works well for clustered scenes and for bodies that move fast or teleport.
+    DynamicBvh,
+    /// A uniform grid with a configurable [cell size](BroadPhaseConfig::cell_size) that is rebuilt every frame.
+    ///
+    /// Works well for many similarly sized bodies, like bullets, particles and crowds.
+    SpatialHash,
+}
+
+type AABBChanged = Or<(
+    Changed<Position>,
+    Changed<Rotation>,
+    Changed<LinearVelocity>,
+    Changed<AngularVelocity>,
+    Changed<ColliderOffset>,
+    Changed<ProximityFuze>,
+    Changed<CollisionMargin>,
+    Changed<Collider>,
+)>;
+
+type AabbComponents = (
+    &'static Collider,
+    &'static mut ColliderAabb,
+    &'static Position,
+    &'static Rotation,
+    Option<&'static ColliderOffset>,
+    Option<&'static ColliderParent>,
+    Option<&'static LinearVelocity>,
+    Option<&'static AngularVelocity>,
+    Option<&'static ProximityFuze>,
+    Option<&'static CollisionMargin>,
+);
+
+/// Updates the Axis-Aligned Bounding Boxes of all colliders. A safety margin will be added to account for sudden accelerations.
+///
+/// Colliders attached to the children of rigid bodies use the velocity of the [`ColliderParent`].
+/// The AABBs of colliders with a [`ProximityFuze`] are enlarged by the radius of the fuze,
+/// and all AABBs are enlarged by the [collision margin](CollisionMargin) of the collider.
+///
+/// Only the AABBs of changed colliders are updated, unless the [`DeltaTime`] or the default
+/// [collision margin](NarrowPhaseConfig::collision_margin) has changed, in which case all AABBs are updated.
+///
+/// With the `parallel` feature, the AABBs are computed in parallel.
+#[allow(clippy::type_complexity)]
+fn update_aabb(
+    mut colliders: ParamSet<(
+        Query<AabbComponents, (AABBChanged, Without<FrozenInRoom>)>,
+        Query<AabbComponents, Without<FrozenInRoom>>,
+    )>,
+    parent_velocity: Query<(&LinearVelocity, &AngularVelocity)>,
+    narrow_phase_config: Option<Res<NarrowPhaseConfig>>,
+    dt: Res<DeltaTime>,
+) {
+    // Safety margin multiplier bigger than DELTA_TIME to account for sudden accelerations
+    let safety_margin_factor = 2.0 * dt.0;
+    let default_collision_margin = narrow_phase_config
+        .as_ref()
+        .map_or(0.0, |config| config.collision_margin);
+
+    // The AABBs of all colliders depend on these, so every AABB has to be updated when they change
+    let update_all = dt.is_changed()
+        || narrow_phase_config
+            .as_ref()
+            .is_some_and(|config| config.is_changed());
+
+    let update = |item: QueryItem<AabbComponents>| {
+        let (collider, mut aabb, pos, rot, offset, collider_parent, lin_vel, ang_vel, fuze, margin) =
+            item;
+
+        let (lin_vel, ang_vel) = if let Some(Ok((parent_lin_vel, parent_ang_vel))) =
+            collider_parent.map(|p| parent_velocity.get(p.get()))
+        {
+            (Some(parent_lin_vel), Some(parent_ang_vel))
+        } else {
+            (lin_vel, ang_vel)
+        };
+
+        let lin_vel = lin_vel.map_or(Vector::ZERO, |v| v.0);
+
+        #[cfg(feature = "2d")]
+        let ang_vel_magnitude = ang_vel.map_or(0.0, |v| v.0.abs());
+        #[cfg(feature = "3d")]
+        let ang_vel_magnitude = ang_vel.map_or(0.0, |v| v.0.length());
+
+        // Apply the collider's local offset
+        let (pos, rot) = offset.map_or((pos.0, *rot), |offset| offset.transform_pose(pos.0, *rot));
+
+        // Compute AABB half extents and center
+        let computed_aabb = collider
+            .get_shape()
+            .compute_aabb(&utils::make_isometry(pos, rot));
+        let half_extents = Vector::from(computed_aabb.half_extents());
+        let center = Vector::from(computed_aabb.center());
+
+        // Todo: Somehow consider the shape of the object for the safety margin
+        // caused by angular velocity. For example, balls shouldn't get any safety margin.
+        let ang_vel_safety_margin = safety_margin_factor * ang_vel_magnitude;
+
+        // Proximity fuzes need the colliders within their radius to be collected as pairs
+        let fuze_margin = fuze.map_or(0.0, |fuze| fuze.radius.max(0.0));
+
+        // Colliders are kept separated by the collision margin, so they need to be collected as pairs
+        // while they are within the margin of each other
+        let collision_margin = margin
+            .map_or(default_collision_margin, |margin| margin.0)
+            .max(0.0);
+
+        // Compute AABB mins and maxs, extending them by a safety margin that depends on the velocity
+        // of the body. Linear velocity only extends the AABB in the movement direction.
+        let mut mins =
+            center - half_extents - ang_vel_safety_margin - fuze_margin - collision_margin;
+        mins += safety_margin_factor * lin_vel.min(Vector::ZERO);
+        let mut maxs =
+            center + half_extents + ang_vel_safety_margin + fuze_margin + collision_margin;
+        maxs += safety_margin_factor * lin_vel.max(Vector::ZERO);
+
+        aabb.mins.coords = mins.into();
+        aabb.maxs.coords = maxs.into();
+    };
+
+    if update_all {
+        #[cfg(feature = "parallel")]
+        colliders.p1().par_iter_mut().for_each_mut(update);
+        #[cfg(not(feature = "parallel"))]
+        colliders.p1().for_each_mut(update);
+    } else {
+        #[cfg(feature = "parallel")]
+        colliders.p0().par_iter_mut().for_each_mut(update);
+        #[cfg(not(feature = "parallel"))]
+        colliders.p0().for_each_mut(update);
+    }
+}
+
+/// Entities with [`ColliderAabb`]s sorted along an axis by their extents.
+///
+/// Each interval also stores the entity of the rigid body that the collider is attached to
+/// and the type of that rigid body.
+#[derive(Resource, Default)]
+struct AabbIntervals {
+    intervals: Vec<(Entity, Entity, ColliderAabb, RigidBody, CollisionLayers)>,
+    /// The index of the axis that the intervals are sorted along.
+    axis: usize,
+}
+
+/// Updates [`AabbIntervals`] to keep them in sync with the [`ColliderAabb`]s.
+fn update_aabb_intervals(
+    aabbs: Query<(&ColliderAabb, Option<&ColliderParent>), Without<FrozenInRoom>>,
+    rbs: Query<&RigidBody>,
+    mut intervals: ResMut<AabbIntervals>,
+) {
+    intervals
+        .intervals
+        .retain_mut(|(entity, parent, aabb, rb, _)| {
+            if let Ok((new_aabb, new_parent)) = aabbs.get(*entity) {
+                *aabb = *new_aabb;
+                *parent = new_parent.map_or(*entity, |p| p.get());
+                if let Ok(new_rb) = rbs.get(*parent) {
+                    *rb = *new_rb;
+                }
+                true
+            } else {
+                false
+            }
+        });
+}
+
+type AabbIntervalComponents = (
+    Entity,
+    Option<&'static ColliderParent>,
+    &'static ColliderAabb,
+    Option<&'static CollisionLayers>,
+);
+
+/// Adds new [`ColliderAabb`]s to [`AabbIntervals`].
+///
+/// Colliders that are thawed from [rooms](PhysicsRooms) get their AABBs added again,
+/// and they are skipped if they weren't removed from the intervals yet.
+fn add_new_aabb_intervals(
+    aabbs: Query<AabbIntervalComponents, (Added<ColliderAabb>, Without<FrozenInRoom>)>,
+    rbs: Query<&RigidBody>,
+    mut intervals: ResMut<AabbIntervals>,
+) {
+    if aabbs.is_empty() {
+        return;
+    }
+
+    let existing: HashSet<Entity> = intervals
+        .intervals
+        .iter()
+        .map(|(entity, ..)| *entity)
+        .collect();
+    let aabbs = aabbs
+        .iter()
+        .filter(|(entity, ..)| !existing.contains(entity));
+    let aabbs = aabbs.map(|(ent, parent, aabb, layers)| {
+        let parent = parent.map_or(ent, |p| p.get());
+        (
+            ent,
+            parent,
+            *aabb,
+            // Default to treating collider as immovable/static for filtering unnecessary collision checks
+            rbs.get(parent).map_or(RigidBody::Static, |rb| *rb),
+            layers.map_or(CollisionLayers::default(), |layers| *layers),
+        )
+    });
+    intervals.intervals.extend(aabbs);
+}
+
+/// Collects bodies that are potentially colliding.
+fn collect_collision_pairs(
+    intervals: ResMut<AabbIntervals>,
+    collision_matrix: Option<Res<CollisionMatrix>>,
+    mut broad_collision_pairs: ResMut<BroadCollisionPairs>,
+    mut batch_pairs: Local<BatchPairs>,
+) {
+    sweep_and_prune(
+        intervals,
+        collision_matrix.as_deref(),
+        &mut broad_collision_pairs.0,
+        &mut batch_pairs,
+    );
+}
+
+/// Sorts the [`BroadCollisionPairs`] by their entities in [`DeterministicMode`].
+///
+/// The entities of each pair are ordered too, so that the pairs are the same regardless of which
+/// collider was found first.
+fn sort_collision_pairs(mut broad_collision_pairs: ResMut<BroadCollisionPairs>) {
+    for (entity1, entity2) in broad_collision_pairs.0.iter_mut() {
+        if entity_sort_key(*entity1) > entity_sort_key(*entity2) {
+            std::mem::swap(entity1, entity2);
+        }
+    }
+    broad_collision_pairs
+        .0
+        .sort_unstable_by_key(|(entity1, entity2)| {
+            (entity_sort_key(*entity1), entity_sort_key(*entity2))
+        });
+}
+
+/// Sorts the entities by their minimum extents along an axis and collects the entity pairs that have intersecting AABBs.
+///
+/// Sweep and prune exploits temporal coherence, as bodies are unlikely to move significantly between two simulation steps. Insertion sort is used, as it is good at sorting nearly sorted lists efficiently.
+///
+/// The sweep axis is the axis along which the centers of the AABBs have the largest variance.
+/// When the axis changes, the intervals are fully sorted again.
+fn sweep_and_prune(
+    mut intervals: ResMut<AabbIntervals>,
+    collision_matrix: Option<&CollisionMatrix>,
+    broad_collision_pairs: &mut Vec<(Entity, Entity)>,
+    batch_pairs: &mut BatchPairs,
+) {
+    let AabbIntervals { intervals, axis } = &mut *intervals;
+
+    let new_axis = dominant_axis(intervals.iter().map(|(_, _, aabb, _, _)| aabb), *axis);
+    if new_axis != *axis {
+        *axis = new_axis;
+        intervals.sort_unstable_by(|a, b| a.2.mins[new_axis].total_cmp(&b.2.mins[new_axis]));
+    } else {
+        // Sort bodies along the axis using insertion sort, a sorting algorithm great for sorting nearly sorted lists.
+        let axis = *axis;
+        insertion_sort(intervals, |a, b| a.2.mins[axis] > b.2.mins[axis]);
+    }
+    let axis = *axis;
+
+    let intervals = &*intervals;
+
+    // Clear broad phase collisions from previous iteration.
+    broad_collision_pairs.clear();
+
+    // Find potential collisions by checking for AABB intersections along all axes.
+    collect_pairs(
+        intervals,
+        |start, batch, pairs| {
+            for (i, (ent1, parent1, aabb1, rb1, layers1)) in batch.iter().enumerate() {
+                for (ent2, parent2, aabb2, rb2, layers2) in &intervals[start + i + 1..] {
+                    // The intervals are sorted, so no later AABB can intersect along the sweep axis
+                    if aabb2.mins[axis] > aabb1.maxs[axis] {
+                        break;
+                    }
+
+                    if !can_collide(
+                        (*parent1, *rb1, *layers1),
+                        (*parent2, *rb2, *layers2),
+                        collision_matrix,
+                    ) {
+                        continue;
+                    }
+
+                    // The other axes don't intersect
+                    if (0..DIM).any(|other_axis| {
+                        aabb1.mins[other_axis] > aabb2.maxs[other_axis]
+                            || aabb1.maxs[other_axis] < aabb2.mins[other_axis]
+                    }) {
+                        continue;
+                    }
+
+                    pairs.push((*ent1, *ent2));
+                }
+            }
+        },
+        broad_collision_pairs,
+        batch_pairs,
+    );
+}
+
+/// The data of a collider stored by the [`DynamicBvh`] and the [`SpatialHash`].
+#[derive(Clone, Copy, Debug)]
+struct AabbProxy {
+    /// The entity of the collider.
+    entity: Entity,
+    /// The entity of the rigid body that the collider is attached to.
+    parent: Entity,
+    /// The tight AABB of the collider.
+    aabb: ColliderAabb,
+    /// The type of the rigid body that the collider is attached to.
+    rb: RigidBody,
+    /// The collision layers of the collider.
+    layers: CollisionLayers,
+}
+
+impl AabbProxy {
+    fn new(
+        entity: Entity,
+        parent: Option<&ColliderParent>,
+        aabb: &ColliderAabb,
+        layers: Option<&CollisionLayers>,
+        rbs: &Query<&RigidBody>,
+    ) -> Self {
+        let parent = parent.map_or(entity, |p| p.get());
+        Self {
+            entity,
+            parent,
+            aabb: *aabb,
+            // Default to treating collider as immovable/static for filtering unnecessary collision checks
+            rb: rbs.get(parent).map_or(RigidBody::Static, |rb| *rb),
+            layers: layers.map_or(CollisionLayers::default(), |layers| *layers),
+        }
+    }
+}
+
+/// Colliders whose [`AabbProxy`] needs to be updated.
+type AabbProxyChanged = Or<(
+    Changed<ColliderAabb>,
+    Changed<ColliderParent>,
+    Changed<CollisionLayers>,
+)>;
+
+/// Updates the [`DynamicBvh`] to keep it in sync with the [`ColliderAabb`]s.
+///
+/// Only the leaves of colliders that have changed are updated, so colliders of static and sleeping bodies
+/// aren't visited. When the type of any rigid body changes, all leaves are updated.
+fn update_dynamic_bvh(
+    aabbs: Query<AabbIntervalComponents, Without<FrozenInRoom>>,
+    changed_aabbs: Query<AabbIntervalComponents, (AabbProxyChanged, Without<FrozenInRoom>)>,
+    changed_rbs: Query<(), Changed<RigidBody>>,
+    rbs: Query<&RigidBody>,
+    config: Res<BroadPhaseConfig>,
+    mut bvh: ResMut<DynamicBvh>,
+) {
+    bvh.retain(|entity| aabbs.contains(entity));
+
+    let mut update = |(entity, parent, aabb, layers): QueryItem<AabbIntervalComponents>| {
+        let proxy = AabbProxy::new(entity, parent, aabb, layers, &rbs);
+        bvh.insert_or_update(proxy, config.aabb_margin);
+    };
+
+    if changed_rbs.is_empty() {
+        changed_aabbs.for_each(&mut update);
+    } else {
+        aabbs.for_each(&mut update);
+    }
+}
+
+/// Collects bodies that are potentially colliding using the [`DynamicBvh`].
+fn collect_bvh_collision_pairs(
+    bvh: Res<DynamicBvh>,
+    collision_matrix: Option<Res<CollisionMatrix>>,
+    mut broad_collision_pairs: ResMut<BroadCollisionPairs>,
+    mut batch_pairs: Local<BatchPairs>,
+) {
+    broad_collision_pairs.0.clear();
+
+    bvh.collect_intersecting_pairs(
+        |proxy1, proxy2| {
+            can_collide(
+                (proxy1.parent, proxy1.rb, proxy1.layers),
+                (proxy2.parent, proxy2.rb, proxy2.layers),
+                collision_matrix.as_deref(),
+            )
+        },
+        &mut broad_collision_pairs.0,
+        &mut batch_pairs,
+    );
+}
+
+/// Rebuilds the [`SpatialHash`] and collects bodies that are potentially colliding using it.
+fn collect_spatial_hash_collision_pairs(
+    aabbs: Query<AabbIntervalComponents, Without<FrozenInRoom>>,
+    rbs: Query<&RigidBody>,
+    config: Res<BroadPhaseConfig>,
+    mut spatial_hash: ResMut<SpatialHash>,
+    collision_matrix: Option<Res<CollisionMatrix>>,
+    mut broad_collision_pairs: ResMut<BroadCollisionPairs>,
+    mut batch_pairs: Local<BatchPairs>,
+) {
+    spatial_hash.clear(config.cell_size);
+    for (entity, parent, aabb, layers) in &aabbs {
+        spatial_hash.insert(AabbProxy::new(entity, parent, aabb, layers, &rbs));
+    }
+
+    broad_collision_pairs.0.clear();
+
+    spatial_hash.collect_intersecting_pairs(
+        |proxy1, proxy2| {
+            can_collide(
+                (proxy1.parent, proxy1.rb, proxy1.layers),
+                (proxy2.parent, proxy2.rb, proxy2.layers),
+                collision_matrix.as_deref(),
+            )
+        },
+        &mut broad_collision_pairs.0,
+        &mut batch_pairs,
+    );
+}
+
+/// The smallest number of items that are processed by a single task when collecting pairs in parallel.
+/// Smaller batches aren't worth the overhead of spawning a task.
+#[cfg(feature = "parallel")]
+const MIN_BATCH_SIZE: usize = 64;
+
+/// The lists that the pairs of each batch are collected into when collecting pairs in parallel.
+/// They are kept in a `Local` of each broad phase system so that their memory can be reused.
+#[derive(Default)]
+pub(super) struct BatchPairs(#[cfg(feature = "parallel")] Vec<Vec<(Entity, Entity)>>);
+
+/// Splits the given items into batches and appends the pairs that `find_pairs` finds for each batch to `pairs`.
+///
+/// `find_pairs` is given the index of the first item of the batch, the batch and the list to push the pairs to.
+/// With the `parallel` feature, the batches are processed on the [`ComputeTaskPool`]. The pairs of the batches
+/// are appended in the order of the items, so the result is the same as when processing the items on a single thread.
+#[cfg_attr(not(feature = "parallel"), allow(unused_variables))]
+fn collect_pairs<T: Sync>(
+    items: &[T],
+    find_pairs: impl Fn(usize, &[T], &mut Vec<(Entity, Entity)>) + Sync,
+    pairs: &mut Vec<(Entity, Entity)>,
+    batch_pairs: &mut BatchPairs,
+) {
+    #[cfg(feature = "parallel")]
+    {
+        let pool = ComputeTaskPool::get();
+        let batch_size = (items.len() / pool.thread_num().max(1) + 1).max(MIN_BATCH_SIZE);
+        let find_pairs = &find_pairs;
+
+        let batch_pairs = &mut batch_pairs.0;
+        batch_pairs.resize_with(items.chunks(batch_size).len(), Vec::new);
+
+        pool.scope(|scope| {
+            for ((i, batch), batch_pairs) in items
+                .chunks(batch_size)
+                .enumerate()
+                .zip(batch_pairs.iter_mut())
+            {
+                scope.spawn(async move {
+                    find_pairs(i * batch_size, batch, batch_pairs);
+                });
+            }
+        });
+        pairs.extend(batch_pairs.iter_mut().flat_map(|pairs| pairs.drain(..)));
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        find_pairs(0, items, pairs);
+    }
+}
+
+/// Returns true if colliders attached to the given bodies with the given layers can collide.
+///
+/// There are no collisions between colliders attached to the same body,
+/// no static-static collisions and no collisions with incompatible layers.
+fn can_collide(
+    (parent1, rb1, layers1): (Entity, RigidBody, CollisionLayers),
+    (parent2, rb2, layers2): (Entity, RigidBody, CollisionLayers),
+    collision_matrix: Option<&CollisionMatrix>,
+) -> bool {
+    parent1 != parent2
+        && !(rb1.is_static() && rb2.is_static())
+        && layers1.interacts_with(layers2)
+        && !collision_matrix.is_some_and(|matrix| !matrix.layers_interact(layers1, layers2))
+}
+
+/// The number of spatial dimensions.
+#[cfg(feature = "2d")]
+const DIM: usize = 2;
+/// The number of spatial dimensions.
+#[cfg(feature = "3d")]
+const DIM: usize = 3;
+
+/// Returns the index of the axis along which the centers of the given AABBs have the largest variance.
+///
+/// The current axis is only changed if the variance along another axis is clearly larger,
+/// so that the intervals aren't resorted back and forth when the variances are similar.
+pub(crate) fn dominant_axis<'a>(
+    aabbs: impl Iterator<Item = &'a ColliderAabb>,
+    current_axis: usize,
+) -> usize {
+    let mut count = 0.0;
+    let mut sum = Vector::ZERO;
+    let mut sum_squared = Vector::ZERO;
+
+    for aabb in aabbs {
+        let center = Vector::from(aabb.center());
+        count += 1.0;
+        sum += center;
+        sum_squared += center * center;
+    }
+
+    if count < 2.0 {
+        return current_axis;
+    }
+
+    let variance = sum_squared / count - (sum / count) * (sum / count);
+    let best_axis = (0..DIM)
+        .max_by(|a, b| variance[*a].total_cmp(&variance[*b]))
+        .unwrap_or(current_axis);
+
+    if variance[best_axis] > 1.2 * variance[current_axis] {
+        best_axis
+    } else {
+        current_axis
+    }
+}
+
+/// Sorts a list iteratively using comparisons. In an ascending sort order, when a smaller value is encountered, it is moved lower in the list until it is larger than the item before it.
+///
+/// This is relatively slow for large lists, but very efficient in cases where the list is already mostly sorted.
+fn insertion_sort<T>(items: &mut Vec<T>, comparison: impl Fn(&T, &T) -> bool) {
+    for i in 1..items.len() {
+        let mut j = i;
+        while j > 0 && comparison(&items[j - 1], &items[j]) {
+            items.swap(j - 1, j);
+            j -= 1;
+        }
+    }
+}
@@ -0,0 +1,235 @@
+//! A uniform grid of hashed cells used by the [broad phase](super::BroadPhasePlugin).
+
+use super::{collect_pairs, AabbProxy, BatchPairs};
+use crate::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
+use parry::bounding_volume::BoundingVolume;
+
+/// The maximum number of cells that a collider is inserted into. Larger colliders are tested against all other colliders,
+/// so that a few huge colliders like the ground don't fill the whole grid.
+const MAX_CELLS_PER_PROXY: usize = 64;
+
+/// A spatial hash that stores the [`ColliderAabb`]s of colliders in the cells of a uniform grid.
+///
+/// The grid is rebuilt every frame, which is cheap when the colliders are of similar size and
+/// the cells are about as large as the colliders. Only the colliders in the same cells are tested against each other.
+#[derive(Resource, Debug, Default)]
+pub(super) struct SpatialHash {
+    cell_size: Scalar,
+    cells: HashMap<GridCell, Vec<usize>>,
+    proxies: Vec<AabbProxy>,
+    /// The indices of the proxies that cover too many cells to be inserted into the grid, in ascending order.
+    oversized: Vec<usize>,
+}
+
+impl SpatialHash {
+    /// Removes all colliders from the grid and sets the size of the cells.
+    ///
+    /// The cells that were used during the previous frame keep their allocations.
+    pub fn clear(&mut self, cell_size: Scalar) {
+        self.cell_size = cell_size.max(Scalar::EPSILON);
+        self.proxies.clear();
+        self.oversized.clear();
+        self.cells.retain(|_, indices| {
+            let used = !indices.is_empty();
+            indices.clear();
+            used
+        });
+    }
+
+    /// Inserts a collider into all of the cells that its AABB overlaps.
+    pub fn insert(&mut self, proxy: AabbProxy) {
+        let index = self.proxies.len();
+        self.proxies.push(proxy);
+
+        // Count the cells using floats, as the cell coordinates of huge AABBs don't fit in integers
+        let mins = (Vector::from(proxy.aabb.mins) / self.cell_size).floor();
+        let maxs = (Vector::from(proxy.aabb.maxs) / self.cell_size).floor();
+        let extents = (maxs - mins + 1.0).max(Vector::ZERO);
+        #[cfg(feature = "2d")]
+        let cell_count = extents.x * extents.y;
+        #[cfg(feature = "3d")]
+        let cell_count = extents.x * extents.y * extents.z;
+
+        // Non-finite AABBs are oversized too
+        if cell_count.is_nan() || cell_count > MAX_CELLS_PER_PROXY as Scalar {
+            self.oversized.push(index);
+            return;
+        }
+
+        let min = self.cell_at(Vector::from(proxy.aabb.mins));
+        let max = self.cell_at(Vector::from(proxy.aabb.maxs));
+
+        for x in min.x..=max.x {
+            for y in min.y..=max.y {
+                #[cfg(feature = "2d")]
+                self.cells
+                    .entry(GridCell::new(x, y))
+                    .or_default()
+                    .push(index);
+                #[cfg(feature = "3d")]
+                for z in min.z..=max.z {
+                    self.cells
+                        .entry(GridCell::new(x, y, z))
+                        .or_default()
+                        .push(index);
+                }
+            }
+        }
+    }
+
+    /// Returns the number of colliders in the grid.
+    pub fn len(&self) -> usize {
+        self.proxies.len()
+    }
+
+    /// Adds the entities of each pair of colliders whose AABBs intersect and that pass the given filter to `pairs`.
+    ///
+    /// With the `parallel` feature, the cells are processed in parallel.
+    pub fn collect_intersecting_pairs(
+        &self,
+        filter: impl Fn(&AabbProxy, &AabbProxy) -> bool + Sync,
+        pairs: &mut Vec<(Entity, Entity)>,
+        batch_pairs: &mut BatchPairs,
+    ) {
+        let cells = self.cells.iter().collect::<Vec<_>>();
+
+        collect_pairs(
+            &cells,
+            |_, cells, pairs| {
+                for &(cell, indices) in cells {
+                    for (i, &index1) in indices.iter().enumerate() {
+                        let proxy1 = &self.proxies[index1];
+
+                        for &index2 in &indices[i + 1..] {
+                            let proxy2 = &self.proxies[index2];
+
+                            if !proxy1.aabb.intersects(&proxy2.aabb) {
+                                continue;
+                            }
+
+                            // Colliders can share several cells, so each pair is only reported in the cell
+                            // containing the minimum corner of the intersection of the AABBs
+                            let intersection_min =
+                                Vector::from(proxy1.aabb.mins).max(Vector::from(proxy2.aabb.mins));
+                            if self.cell_at(intersection_min) != *cell {
+                                continue;
+                            }
+
+                            if filter(proxy1, proxy2) {
+                                pairs.push((proxy1.entity, proxy2.entity));
+                            }
+                        }
+                    }
+                }
+            },
+            pairs,
+            batch_pairs,
+        );
+
+        // Test oversized colliders against all other colliders
+        collect_pairs(
+            &self.oversized,
+            |_, oversized, pairs| {
+                for &index1 in oversized {
+                    let proxy1 = &self.proxies[index1];
+
+                    for (index2, proxy2) in self.proxies.iter().enumerate() {
+                        // Pairs of two oversized colliders are only reported once
+                        if index2 == index1
+                            || (index2 < index1 && self.oversized.binary_search(&index2).is_ok())
+                        {
+                            continue;
+                        }
+
+                        if proxy1.aabb.intersects(&proxy2.aabb) && filter(proxy1, proxy2) {
+                            pairs.push((proxy1.entity, proxy2.entity));
+                        }
+                    }
+                }
+            },
+            pairs,
+            batch_pairs,
+        );
+    }
+
+    /// Returns the grid cell containing the given point.
+    fn cell_at(&self, point: Vector) -> GridCell {
+        #[cfg(feature = "2d")]
+        {
+            (point / self.cell_size).floor().as_ivec2()
+        }
+        #[cfg(feature = "3d")]
+        {
+            (point / self.cell_size).floor().as_ivec3()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::tasks::{ComputeTaskPool, TaskPool};
+
+    fn proxy(index: u32, center: Vector, half_extent: Scalar) -> AabbProxy {
+        let mut aabb = ColliderAabb::default();
+        aabb.mins.coords = (center - Vector::splat(half_extent)).into();
+        aabb.maxs.coords = (center + Vector::splat(half_extent)).into();
+        AabbProxy {
+            entity: Entity::from_raw(index),
+            parent: Entity::from_raw(index),
+            aabb,
+            rb: RigidBody::Dynamic,
+            layers: CollisionLayers::default(),
+        }
+    }
+
+    fn intersecting_pairs(hash: &SpatialHash) -> Vec<(u32, u32)> {
+        ComputeTaskPool::init(TaskPool::default);
+
+        let mut pairs = vec![];
+        hash.collect_intersecting_pairs(|_, _| true, &mut pairs, &mut BatchPairs::default());
+        let mut pairs = pairs
+            .into_iter()
+            .map(|(entity1, entity2)| {
+                let (a, b) = (entity1.index(), entity2.index());
+                (a.min(b), a.max(b))
+            })
+            .collect::<Vec<_>>();
+        pairs.sort();
+        pairs
+    }
+
+    #[test]
+    fn spatial_hash_finds_intersecting_pairs_once() {
+        let mut hash = SpatialHash::default();
+        hash.clear(1.0);
+
+        // A row of AABBs that span several cells, where each one overlaps the next one
+        for i in 0..50 {
+            hash.insert(proxy(i, Vector::X * 0.9 * i as Scalar, 0.5));
+        }
+        // Two huge AABBs that overlap each other and the first AABBs
+        hash.insert(proxy(50, Vector::NEG_X * 100.0, 101.0));
+        hash.insert(proxy(51, Vector::NEG_X * 100.0, 100.0));
+        assert_eq!(hash.len(), 52);
+
+        let mut expected = (0..49).map(|i| (i, i + 1)).collect::<Vec<_>>();
+        expected.extend([(0, 50), (0, 51), (1, 50), (50, 51)]);
+        expected.sort();
+        assert_eq!(intersecting_pairs(&hash), expected);
+
+        // AABBs whose cell coordinates don't fit in integers are oversized instead of overflowing
+        hash.insert(proxy(52, Vector::ZERO, Scalar::MAX / 2.0));
+        hash.insert(proxy(53, Vector::X * 1e30, 0.5));
+        expected.extend((0..52).map(|i| (i, 52)));
+        expected.push((52, 53));
+        expected.sort();
+        assert_eq!(intersecting_pairs(&hash), expected);
+
+        // Clearing removes all colliders
+        hash.clear(1.0);
+        assert_eq!(hash.len(), 0);
+        assert!(intersecting_pairs(&hash).is_empty());
+    }
+}
@@ -0,0 +1,263 @@
+//! Lightweight pushback between crowds of character agents.
+//!
+//! See [`CrowdPlugin`].
+
+use crate::prelude::*;
+use bevy::prelude::*;
+
+/// Keeps [`CrowdAgent`]s from overlapping each other using soft pushback constraints.
+///
+/// Crowd agents are typically [kinematic](RigidBody::Kinematic) character bodies, like NPCs, that are moved
+/// by game logic instead of the full rigid body dynamics. Kinematic bodies aren't affected by contacts,
+/// so without the crowd layer, they would pass through each other freely.
+///
+/// Instead of full contact constraints, each agent is represented as a cheap particle: a circle in 2D or
+/// an upright capsule in 3D with a position and a [weight](CrowdAgent::weight), but no rotation or inertia.
+/// Overlapping pairs are found using a sort and sweep along the x axis, and each pair is pushed apart with an XPBD
+/// distance constraint with the [compliance](CrowdConfig::compliance) of the [`CrowdConfig`] resource.
+///
+/// The pushback only moves the [`Position`] of the agents, so the velocities of kinematic agents aren't changed
+/// and they keep moving the way game logic tells them to. In 3D, agents are only pushed apart perpendicular to
+/// the [`Gravity`], so they aren't pushed into the ground.
+///
+/// The constraints are solved in [`SubstepSet::SolveUserConstraints`].
+pub struct CrowdPlugin;
+
+impl Plugin for CrowdPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CrowdConfig>()
+            .register_type::<CrowdConfig>()
+            .register_type::<CrowdAgent>();
+
+        let substeps = app
+            .get_schedule_mut(SubstepSchedule)
+            .expect("add SubstepSchedule first");
+
+        substeps.add_systems(solve_crowd_agents.in_set(SubstepSet::SolveUserConstraints));
+    }
+}
+
+/// Configures the pushback between [`CrowdAgent`]s. See [`CrowdPlugin`].
+#[derive(Resource, Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Resource)]
+pub struct CrowdConfig {
+    /// The compliance of the pushback constraints, the inverse of their stiffness.
+    /// Zero separates agents completely in each substep, and larger values let them overlap more
+    /// and separate more gently.
+    pub compliance: Scalar,
+}
+
+impl Default for CrowdConfig {
+    fn default() -> Self {
+        Self {
+            compliance: 0.000_01,
+        }
+    }
+}
+
+/// A component that makes a body a member of the crowd layer, so that it is pushed apart from other agents
+/// that it overlaps. See [`CrowdPlugin`].
+///
+/// The agent is a circle in 2D and an upright capsule in 3D, centered at the [`Position`] of the body.
+/// Static bodies are ignored.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// # #[cfg(all(feature = "3d", feature = "f32"))]
+/// fn spawn_npcs(mut commands: Commands) {
+///     for i in 0..50 {
+///         // Capsules with a radius of 0.4 and a total height of 1.8
+///         commands.spawn((
+///             RigidBody::Kinematic,
+///             Position(Vec3::X * i as f32 * 0.5),
+///             CrowdAgent::new(0.4).with_half_height(0.5),
+///         ));
+///     }
+/// }
+/// ```
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct CrowdAgent {
+    /// The radius of the agent.
+    pub radius: Scalar,
+    /// Half of the height of the cylindrical part of the agent's capsule along the up direction.
+    #[cfg(feature = "3d")]
+    pub half_height: Scalar,
+    /// The relative weight of the agent. When two agents are pushed apart, the heavier one moves less.
+    /// An infinite weight makes the agent immovable by other agents. 1.0 by default.
+    pub weight: Scalar,
+}
+
+impl Default for CrowdAgent {
+    fn default() -> Self {
+        Self::new(0.5)
+    }
+}
+
+impl CrowdAgent {
+    /// Creates a [`CrowdAgent`] with the given radius and a weight of 1.0.
+    pub fn new(radius: Scalar) -> Self {
+        Self {
+            radius,
+            #[cfg(feature = "3d")]
+            half_height: 0.0,
+            weight: 1.0,
+        }
+    }
+
+    /// Sets half of the height of the cylindrical part of the agent's capsule along the up direction.
+    #[cfg(feature = "3d")]
+    pub fn with_half_height(mut self, half_height: Scalar) -> Self {
+        self.half_height = half_height;
+        self
+    }
+
+    /// Sets the relative weight of the agent.
+    pub fn with_weight(mut self, weight: Scalar) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Returns the inverse of the weight, or zero if the weight isn't positive and finite.
+    fn inverse_weight(&self) -> Scalar {
+        if self.weight > 0.0 && self.weight.is_finite() {
+            self.weight.recip()
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the distance from the center of the agent to its furthest point.
+    fn bounding_radius(&self) -> Scalar {
+        #[cfg(feature = "2d")]
+        {
+            self.radius
+        }
+        #[cfg(feature = "3d")]
+        {
+            self.radius + self.half_height
+        }
+    }
+}
+
+/// The cheap representation of an agent used by the solver.
+struct CrowdParticle<'a> {
+    agent: CrowdAgent,
+    inverse_weight: Scalar,
+    /// The position of the agent before the pushback, including its accumulated translation.
+    start_position: Vector,
+    /// The current position of the agent, including its accumulated translation.
+    position: Vector,
+    translation: Mut<'a, AccumulatedTranslation>,
+}
+
+fn solve_crowd_agents(
+    mut agents: Query<
+        (
+            &RigidBody,
+            &CrowdAgent,
+            &Position,
+            &mut AccumulatedTranslation,
+        ),
+        Without<Sleeping>,
+    >,
+    config: Res<CrowdConfig>,
+    #[cfg(feature = "3d")] gravity: Res<Gravity>,
+    sub_dt: Res<SubDeltaTime>,
+) {
+    let mut particles = agents
+        .iter_mut()
+        .filter(|(rb, ..)| !rb.is_static())
+        .map(|(_, agent, position, translation)| CrowdParticle {
+            agent: *agent,
+            inverse_weight: agent.inverse_weight(),
+            start_position: position.0 + translation.0,
+            position: position.0 + translation.0,
+            translation,
+        })
+        .collect::<Vec<_>>();
+
+    if particles.len() < 2 {
+        return;
+    }
+
+    // The agents are only pushed apart perpendicular to the up direction
+    #[cfg(feature = "3d")]
+    let up = -gravity.0.normalize_or_zero();
+
+    // Sort and sweep along the x axis to find the pairs of agents that can overlap
+    let min_x = |particle: &CrowdParticle| particle.position.x - particle.agent.bounding_radius();
+    particles.sort_by(|a, b| min_x(a).total_cmp(&min_x(b)));
+
+    let compliance = config.compliance / sub_dt.0.powi(2);
+
+    for i in 0..particles.len() {
+        let max_x = particles[i].position.x + particles[i].agent.bounding_radius();
+
+        for j in i + 1..particles.len() {
+            if min_x(&particles[j]) > max_x {
+                break;
+            }
+
+            let (left, right) = particles.split_at_mut(j);
+            let (p1, p2) = (&mut left[i], &mut right[0]);
+
+            let inverse_weight_sum = p1.inverse_weight + p2.inverse_weight;
+            if inverse_weight_sum <= Scalar::EPSILON {
+                continue;
+            }
+
+            #[allow(unused_mut)]
+            let mut delta = p2.position - p1.position;
+
+            #[cfg(feature = "3d")]
+            {
+                // Skip agents that are above or below each other
+                let vertical = delta.dot(up);
+                let max_vertical =
+                    p1.agent.half_height + p1.agent.radius + p2.agent.half_height + p2.agent.radius;
+                if vertical.abs() >= max_vertical {
+                    continue;
+                }
+                delta -= up * vertical;
+            }
+
+            let distance = delta.length();
+            let min_distance = p1.agent.radius + p2.agent.radius;
+            if distance >= min_distance {
+                continue;
+            }
+
+            // Agents at the same position are pushed apart along the x axis
+            let normal = if distance > Scalar::EPSILON {
+                delta / distance
+            } else {
+                Vector::X
+            };
+
+            // Compute the Lagrange multiplier update of the soft distance constraint.
+            // The multiplier starts from zero, as each constraint is only solved once per substep.
+            let c = distance - min_distance;
+            let delta_lagrange = -c / (inverse_weight_sum + compliance);
+
+            p1.position -= normal * delta_lagrange * p1.inverse_weight;
+            p2.position += normal * delta_lagrange * p2.inverse_weight;
+        }
+    }
+
+    for particle in particles.iter_mut() {
+        // Avoid triggering change detection unnecessarily
+        if particle.position != particle.start_position {
+            particle.translation.0 += particle.position - particle.start_position;
+        }
+    }
+}
@@ -5,6 +5,7 @@ use bevy::prelude::*;
 ///
 /// To configure the debug rendering of specific entities, use the [`DebugRender`] component.
 #[derive(Reflect, Resource)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Resource)]
 pub struct PhysicsDebugConfig {
     /// Determines if debug rendering is enabled.
@@ -15,12 +16,38 @@ pub struct PhysicsDebugConfig {
     pub aabb_color: Option<Color>,
     /// The color of the [collider](Collider) wireframes. If `None`, the colliders will not be rendered.
     pub collider_color: Option<Color>,
+    /// The color that the colliders of [sleeping](Sleeping) bodies are drawn with instead of the collider color.
+    /// If `None`, sleeping bodies are drawn like other bodies.
+    pub sleeping_color: Option<Color>,
+    /// Determines if the colliders of the bodies in each [simulation island](SimulationIslands) should be drawn
+    /// with a distinct color instead of the collider color. This helps to see which bodies are connected,
+    /// for example when islands keep merging or a pile doesn't fall asleep.
+    pub color_islands: bool,
     /// The color of the contact points. If `None`, the contact points will not be rendered.
     pub contact_color: Option<Color>,
+    /// The color of the contact normals of the current step's penetration constraints.
+    /// If `None`, the contact normals will not be rendered.
+    pub contact_normal_color: Option<Color>,
+    /// The color that contact points and normals are blended towards based on their penetration depth.
+    /// If `Some`, the penetration depths are also rendered as lines between the contact points on the two bodies.
+    /// If `None`, contacts aren't color-coded and the penetration depths will not be rendered.
+    pub penetration_color: Option<Color>,
+    /// The penetration depth at which contacts are drawn entirely with the
+    /// [`penetration_color`](#structfield.penetration_color).
+    pub severe_penetration_depth: Scalar,
+    /// The color of the arrows drawn for the [linear velocities](LinearVelocity) of bodies.
+    /// If `None`, the linear velocities will not be rendered.
+    pub linear_velocity_color: Option<Color>,
+    /// The color of the [angular velocities](AngularVelocity) of bodies. If `None`, the angular velocities
+    /// will not be rendered.
+    pub angular_velocity_color: Option<Color>,
     /// The color of the lines drawn from the centers of bodies to their joint anchors.
     pub joint_anchor_color: Option<Color>,
     /// The color of the lines drawn between joint anchors, indicating the separation.
     pub joint_separation_color: Option<Color>,
+    /// The color that the lines of joints violating their angle limits are drawn with instead of the other joint colors.
+    /// Only joints with a [`JointLimitMonitor`] are highlighted.
+    pub joint_limit_violation_color: Option<Color>,
     /// Determines if the visibility of entities with [colliders](Collider) should be set to `Visibility::Hidden`,
     /// which will only show the debug renders.
     pub hide_meshes: bool,
@@ -36,9 +63,20 @@ impl Default for PhysicsDebugConfig {
             axis_lengths: Some(Vector::new(0.5, 0.5, 0.5)),
             aabb_color: None,
             collider_color: Some(Color::ORANGE),
+            sleeping_color: None,
+            color_islands: false,
             contact_color: None,
+            contact_normal_color: None,
+            penetration_color: None,
+            #[cfg(feature = "2d")]
+            severe_penetration_depth: 2.0,
+            #[cfg(feature = "3d")]
+            severe_penetration_depth: 0.05,
+            linear_velocity_color: None,
+            angular_velocity_color: None,
             joint_anchor_color: Some(Color::PINK),
             joint_separation_color: Some(Color::RED),
+            joint_limit_violation_color: Some(Color::RED),
             hide_meshes: false,
         }
     }
@@ -55,9 +93,20 @@ impl PhysicsDebugConfig {
             axis_lengths: Some(Vector::new(0.5, 0.5, 0.5)),
             aabb_color: Some(Color::rgb(0.8, 0.8, 0.8)),
             collider_color: Some(Color::ORANGE),
+            sleeping_color: Some(Color::GRAY),
+            color_islands: true,
             contact_color: Some(Color::CYAN),
+            contact_normal_color: Some(Color::CYAN),
+            penetration_color: Some(Color::RED),
+            #[cfg(feature = "2d")]
+            severe_penetration_depth: 2.0,
+            #[cfg(feature = "3d")]
+            severe_penetration_depth: 0.05,
+            linear_velocity_color: Some(Color::YELLOW),
+            angular_velocity_color: Some(Color::PURPLE),
             joint_anchor_color: Some(Color::PINK),
             joint_separation_color: Some(Color::RED),
+            joint_limit_violation_color: Some(Color::RED),
             hide_meshes: true,
         }
     }
@@ -71,9 +120,20 @@ impl PhysicsDebugConfig {
             axis_lengths: None,
             aabb_color: None,
             collider_color: None,
+            sleeping_color: None,
+            color_islands: false,
             contact_color: None,
+            contact_normal_color: None,
+            penetration_color: None,
+            #[cfg(feature = "2d")]
+            severe_penetration_depth: 2.0,
+            #[cfg(feature = "3d")]
+            severe_penetration_depth: 0.05,
+            linear_velocity_color: None,
+            angular_velocity_color: None,
             joint_anchor_color: None,
             joint_separation_color: None,
+            joint_limit_violation_color: None,
             hide_meshes: false,
         }
     }
@@ -114,6 +174,16 @@ impl PhysicsDebugConfig {
         }
     }
 
+    /// Creates a [`PhysicsDebugConfig`] configuration with given colors for
+    /// linear and angular velocities. Other debug rendering options will be disabled.
+    pub fn velocities(linear_color: Color, angular_color: Color) -> Self {
+        Self {
+            linear_velocity_color: Some(linear_color),
+            angular_velocity_color: Some(angular_color),
+            ..Self::none()
+        }
+    }
+
     /// Creates a [`PhysicsDebugConfig`] configuration with given colors for
     /// joint anchors and separation distances. Other debug rendering options will be disabled.
     pub fn joints(anchor_color: Color, separation_color: Color) -> Self {
@@ -148,6 +218,54 @@ impl PhysicsDebugConfig {
         self
     }
 
+    /// Sets the color that the colliders of sleeping bodies are drawn with.
+    pub fn with_sleeping_color(mut self, color: Color) -> Self {
+        self.sleeping_color = Some(color);
+        self
+    }
+
+    /// Sets whether the colliders of the bodies in each simulation island are drawn with a distinct color.
+    pub fn with_island_colors(mut self, color_islands: bool) -> Self {
+        self.color_islands = color_islands;
+        self
+    }
+
+    /// Sets the contact normal color.
+    pub fn with_contact_normal_color(mut self, color: Color) -> Self {
+        self.contact_normal_color = Some(color);
+        self
+    }
+
+    /// Sets the color that contacts are blended towards based on their penetration depth, and the depth
+    /// at which contacts are drawn entirely with that color.
+    pub fn with_penetration_color(
+        mut self,
+        color: Color,
+        severe_penetration_depth: Scalar,
+    ) -> Self {
+        self.penetration_color = Some(color);
+        self.severe_penetration_depth = severe_penetration_depth;
+        self
+    }
+
+    /// Sets the linear velocity color.
+    pub fn with_linear_velocity_color(mut self, color: Color) -> Self {
+        self.linear_velocity_color = Some(color);
+        self
+    }
+
+    /// Sets the angular velocity color.
+    pub fn with_angular_velocity_color(mut self, color: Color) -> Self {
+        self.angular_velocity_color = Some(color);
+        self
+    }
+
+    /// Sets the color of joints that violate their angle limits.
+    pub fn with_joint_limit_violation_color(mut self, color: Color) -> Self {
+        self.joint_limit_violation_color = Some(color);
+        self
+    }
+
     /// Sets the visibility of the entity's visual mesh.
     pub fn with_mesh_visibility(mut self, is_visible: bool) -> Self {
         self.hide_meshes = !is_visible;
@@ -172,9 +290,18 @@ impl PhysicsDebugConfig {
         self
     }
 
-    /// Disables contact debug rendering.
+    /// Disables contact debug rendering, including contact normals and penetration depths.
     pub fn without_contacts(mut self) -> Self {
         self.contact_color = None;
+        self.contact_normal_color = None;
+        self.penetration_color = None;
+        self
+    }
+
+    /// Disables linear and angular velocity debug rendering.
+    pub fn without_velocities(mut self) -> Self {
+        self.linear_velocity_color = None;
+        self.angular_velocity_color = None;
         self
     }
 
@@ -182,6 +309,7 @@ impl PhysicsDebugConfig {
     pub fn without_joints(mut self) -> Self {
         self.joint_anchor_color = None;
         self.joint_separation_color = None;
+        self.joint_limit_violation_color = None;
         self
     }
 }
@@ -190,6 +318,7 @@ impl PhysicsDebugConfig {
 ///
 /// This overwrites the global [`PhysicsDebugConfig`] for this specific entity.
 #[derive(Component, Reflect, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Component)]
 pub struct DebugRender {
     /// The lengths of the axes drawn for the entity at the center of mass.
@@ -198,6 +327,11 @@ pub struct DebugRender {
     pub aabb_color: Option<Color>,
     /// The color of the [collider](Collider) wireframe. If `None`, the collider will not be rendered.
     pub collider_color: Option<Color>,
+    /// The color of the arrow drawn for the [linear velocity](LinearVelocity).
+    /// If `None`, the linear velocity will not be rendered.
+    pub linear_velocity_color: Option<Color>,
+    /// The color of the [angular velocity](AngularVelocity). If `None`, the angular velocity will not be rendered.
+    pub angular_velocity_color: Option<Color>,
     /// Determines if the entity's visibility should be set to `Visibility::Hidden`, which will only show the debug render.
     pub hide_mesh: bool,
 }
@@ -211,6 +345,8 @@ impl Default for DebugRender {
             axis_lengths: Some(Vector::new(0.5, 0.5, 0.5)),
             aabb_color: None,
             collider_color: Some(Color::ORANGE),
+            linear_velocity_color: None,
+            angular_velocity_color: None,
             hide_mesh: false,
         }
     }
@@ -226,6 +362,8 @@ impl DebugRender {
             axis_lengths: Some(Vector::new(0.5, 0.5, 0.5)),
             aabb_color: Some(Color::rgb(0.8, 0.8, 0.8)),
             collider_color: Some(Color::ORANGE),
+            linear_velocity_color: Some(Color::YELLOW),
+            angular_velocity_color: Some(Color::PURPLE),
             hide_mesh: true,
         }
     }
@@ -236,6 +374,8 @@ impl DebugRender {
             axis_lengths: None,
             aabb_color: None,
             collider_color: None,
+            linear_velocity_color: None,
+            angular_velocity_color: None,
             hide_mesh: false,
         }
     }
@@ -267,6 +407,16 @@ impl DebugRender {
         }
     }
 
+    /// Creates a [`DebugRender`] configuration with given colors for the linear and angular velocity.
+    /// Other debug rendering options will be disabled.
+    pub fn velocity(linear_color: Color, angular_color: Color) -> Self {
+        Self {
+            linear_velocity_color: Some(linear_color),
+            angular_velocity_color: Some(angular_color),
+            ..Self::none()
+        }
+    }
+
     /// Sets the lengths of the axes drawn for the entity at the center of mass.
     pub fn with_axes(mut self, axis_lengths: Vector) -> Self {
         self.axis_lengths = Some(axis_lengths);
@@ -285,6 +435,18 @@ impl DebugRender {
         self
     }
 
+    /// Sets the linear velocity color.
+    pub fn with_linear_velocity_color(mut self, color: Color) -> Self {
+        self.linear_velocity_color = Some(color);
+        self
+    }
+
+    /// Sets the angular velocity color.
+    pub fn with_angular_velocity_color(mut self, color: Color) -> Self {
+        self.angular_velocity_color = Some(color);
+        self
+    }
+
     /// Sets the visibility of the entity's visual mesh.
     pub fn with_mesh_visibility(mut self, is_visible: bool) -> Self {
         self.hide_mesh = !is_visible;
@@ -308,4 +470,11 @@ impl DebugRender {
         self.collider_color = None;
         self
     }
+
+    /// Disables linear and angular velocity debug rendering.
+    pub fn without_velocity(mut self) -> Self {
+        self.linear_velocity_color = None;
+        self.angular_velocity_color = None;
+        self
+    }
 }
@@ -0,0 +1,274 @@
+use std::collections::VecDeque;
+
+use crate::prelude::*;
+use bevy::prelude::*;
+
+/// A marker component for [joints] whose solver state should be recorded by the [`ConstraintDebugger`].
+#[derive(Reflect, Clone, Copy, Component, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct DebugConstraint;
+
+/// The state of a [joint](joints) marked with [`DebugConstraint`] during a single substep.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConstraintRecord {
+    /// The entity of the joint.
+    pub constraint: Entity,
+    /// The index of the substep since recording was started.
+    pub substep: usize,
+    /// The bodies constrained by the joint.
+    pub bodies: [Entity; 2],
+    /// The Lagrange multipliers of the joint after the constraints were solved.
+    /// See [`Joint::lagrange_multipliers`].
+    pub lagrange_multipliers: Vec<Scalar>,
+    /// The positions of the bodies before the constraints were solved.
+    pub positions_before: [Vector; 2],
+    /// The positions of the bodies after the constraints were solved.
+    pub positions_after: [Vector; 2],
+    /// The rotations of the bodies before the constraints were solved.
+    pub rotations_before: [Rotation; 2],
+    /// The rotations of the bodies after the constraints were solved.
+    pub rotations_after: [Rotation; 2],
+}
+
+impl ConstraintRecord {
+    /// Returns the positional corrections applied to the bodies during the substep.
+    ///
+    /// Note that this includes the corrections of all constraints that affect the bodies,
+    /// like contacts and other joints.
+    pub fn corrections(&self) -> [Vector; 2] {
+        [
+            self.positions_after[0] - self.positions_before[0],
+            self.positions_after[1] - self.positions_before[1],
+        ]
+    }
+}
+
+/// Records the solver state of [joints] marked with [`DebugConstraint`] for each substep,
+/// and allows stepping through the recorded substeps one by one.
+///
+/// Recording is disabled by default. When [`replay_index`](#structfield.replay_index) is set,
+/// the corresponding record is debug rendered, showing how the bodies were moved during that substep.
+/// The simulation can be paused with [`PhysicsTime::pause`] while inspecting the records.
+///
+/// ## Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn setup(mut commands: Commands, mut debugger: ResMut<ConstraintDebugger>) {
+///     let entity1 = commands.spawn(RigidBody::Static).id();
+///     let entity2 = commands.spawn(RigidBody::Dynamic).id();
+///
+///     // Record the state of the joint
+///     commands.spawn((FixedJoint::new(entity1, entity2), DebugConstraint));
+///     debugger.recording = true;
+/// }
+///
+/// fn step_through(
+///     keys: Res<Input<KeyCode>>,
+///     mut debugger: ResMut<ConstraintDebugger>,
+///     mut physics_time: ResMut<PhysicsTime>,
+/// ) {
+///     if keys.just_pressed(KeyCode::Space) {
+///         // Stop the simulation and start inspecting from the latest record
+///         physics_time.pause();
+///         debugger.recording = false;
+///         debugger.replay_index = debugger.records().len().checked_sub(1);
+///     }
+///     if keys.just_pressed(KeyCode::Left) {
+///         debugger.step_backward();
+///     }
+///     if keys.just_pressed(KeyCode::Right) {
+///         debugger.step_forward();
+///     }
+///     if let Some(record) = debugger.current() {
+///         println!("{:?}", record);
+///     }
+/// }
+/// ```
+#[derive(Resource, Reflect, Clone, Debug)]
+#[reflect(Resource)]
+pub struct ConstraintDebugger {
+    /// Determines if the states of the constraints are recorded.
+    pub recording: bool,
+    /// The maximum number of stored records. The oldest records are removed first.
+    pub max_records: usize,
+    /// The index of the record that is being inspected and debug rendered.
+    pub replay_index: Option<usize>,
+    /// The color of the lines drawn from the positions of the bodies before the constraints
+    /// were solved to the positions after.
+    pub correction_color: Color,
+    /// The color of the rotation axes drawn for the bodies before the constraints were solved.
+    pub before_color: Color,
+    /// The color of the rotation axes drawn for the bodies after the constraints were solved.
+    pub after_color: Color,
+    #[reflect(ignore)]
+    records: VecDeque<ConstraintRecord>,
+    #[reflect(ignore)]
+    pending: Vec<ConstraintRecord>,
+    substep: usize,
+}
+
+impl Default for ConstraintDebugger {
+    fn default() -> Self {
+        Self {
+            recording: false,
+            max_records: 1000,
+            replay_index: None,
+            correction_color: Color::GREEN,
+            before_color: Color::GRAY,
+            after_color: Color::WHITE,
+            records: VecDeque::new(),
+            pending: vec![],
+            substep: 0,
+        }
+    }
+}
+
+impl ConstraintDebugger {
+    /// Returns the recorded constraint states, oldest first.
+    pub fn records(&self) -> &VecDeque<ConstraintRecord> {
+        &self.records
+    }
+
+    /// Returns the record at the [replay index](#structfield.replay_index).
+    pub fn current(&self) -> Option<&ConstraintRecord> {
+        self.replay_index.and_then(|i| self.records.get(i))
+    }
+
+    /// Moves the replay index to the next record, starting from the first one.
+    pub fn step_forward(&mut self) {
+        let last = self.records.len().saturating_sub(1);
+        self.replay_index = Some(self.replay_index.map_or(0, |i| (i + 1).min(last)));
+    }
+
+    /// Moves the replay index to the previous record, starting from the latest one.
+    pub fn step_backward(&mut self) {
+        let last = self.records.len().saturating_sub(1);
+        self.replay_index = Some(self.replay_index.map_or(last, |i| i.saturating_sub(1)));
+    }
+
+    /// Removes all records and stops replaying.
+    pub fn clear(&mut self) {
+        self.records.clear();
+        self.pending.clear();
+        self.replay_index = None;
+        self.substep = 0;
+    }
+}
+
+type ConstraintBodyComponents = (
+    &'static Position,
+    Option<&'static AccumulatedTranslation>,
+    &'static Rotation,
+);
+
+/// Returns the current position and rotation of the given body during a substep.
+fn body_pose(bodies: &Query<ConstraintBodyComponents>, entity: Entity) -> (Vector, Rotation) {
+    bodies.get(entity).map_or(
+        (Vector::ZERO, Rotation::default()),
+        |(pos, translation, rot)| (pos.0 + translation.map_or(Vector::ZERO, |t| t.0), *rot),
+    )
+}
+
+/// Advances the substep counter of the [`ConstraintDebugger`] and discards unfinished records.
+pub(super) fn advance_constraint_debugger(mut debugger: ResMut<ConstraintDebugger>) {
+    debugger.substep += 1;
+    debugger.pending.clear();
+}
+
+/// Stores the poses of the bodies constrained by joints marked with [`DebugConstraint`]
+/// before the constraints are solved.
+pub(super) fn begin_constraint_records<T: Joint>(
+    joints: Query<(Entity, &T), With<DebugConstraint>>,
+    bodies: Query<ConstraintBodyComponents>,
+    mut debugger: ResMut<ConstraintDebugger>,
+) {
+    let substep = debugger.substep;
+    for (entity, joint) in &joints {
+        let [entity1, entity2] = joint.entities();
+        let (pos1, rot1) = body_pose(&bodies, entity1);
+        let (pos2, rot2) = body_pose(&bodies, entity2);
+
+        debugger.pending.push(ConstraintRecord {
+            constraint: entity,
+            substep,
+            bodies: [entity1, entity2],
+            lagrange_multipliers: vec![],
+            positions_before: [pos1, pos2],
+            positions_after: [pos1, pos2],
+            rotations_before: [rot1, rot2],
+            rotations_after: [rot1, rot2],
+        });
+    }
+}
+
+/// Completes the pending records of joints marked with [`DebugConstraint`]
+/// after the constraints have been solved.
+pub(super) fn finish_constraint_records<T: Joint>(
+    joints: Query<(Entity, &T), With<DebugConstraint>>,
+    bodies: Query<ConstraintBodyComponents>,
+    mut debugger: ResMut<ConstraintDebugger>,
+) {
+    for (entity, joint) in &joints {
+        let Some(index) = debugger
+            .pending
+            .iter()
+            .position(|record| record.constraint == entity)
+        else {
+            continue;
+        };
+        let mut record = debugger.pending.swap_remove(index);
+
+        let (pos1, rot1) = body_pose(&bodies, record.bodies[0]);
+        let (pos2, rot2) = body_pose(&bodies, record.bodies[1]);
+        record.lagrange_multipliers = joint.lagrange_multipliers();
+        record.positions_after = [pos1, pos2];
+        record.rotations_after = [rot1, rot2];
+
+        debugger.records.push_back(record);
+        while debugger.records.len() > debugger.max_records {
+            debugger.records.pop_front();
+        }
+    }
+}
+
+/// Renders the record at the [replay index](ConstraintDebugger::replay_index).
+pub(super) fn debug_render_constraint_record(
+    debugger: Res<ConstraintDebugger>,
+    mut debug_renderer: PhysicsDebugRenderer,
+) {
+    let Some(record) = debugger.current() else {
+        return;
+    };
+
+    #[cfg(feature = "2d")]
+    let len = 5.0;
+    #[cfg(feature = "3d")]
+    let len = 0.3;
+
+    for i in 0..2 {
+        let (pos_before, pos_after) = (record.positions_before[i], record.positions_after[i]);
+        let (rot_before, rot_after) = (record.rotations_before[i], record.rotations_after[i]);
+
+        debug_renderer.draw_line(pos_before, pos_after, debugger.correction_color);
+
+        for axis in [Vector::X, Vector::Y] {
+            debug_renderer.draw_line(
+                pos_before,
+                pos_before + rot_before.rotate(axis * len),
+                debugger.before_color,
+            );
+            debug_renderer.draw_line(
+                pos_after,
+                pos_after + rot_after.rotate(axis * len),
+                debugger.after_color,
+            );
+        }
+    }
+}
@@ -3,12 +3,14 @@
 //! See [`PhysicsDebugPlugin`].
 
 mod configuration;
+mod constraint_debugger;
 mod renderer;
 
 pub use configuration::*;
+pub use constraint_debugger::*;
 pub use renderer::*;
 
-use crate::prelude::*;
+use crate::{plugins::solver::PenetrationConstraints, prelude::*, utils::entity_sort_key};
 use bevy::prelude::*;
 
 /// Renders physics objects and properties for debugging purposes.
@@ -17,14 +19,19 @@ use bevy::prelude::*;
 ///
 /// - Entity axes
 /// - [AABBs](ColliderAabb)
-/// - [Collider] wireframes
-/// - [Contact] points
-/// - [Joints](joints)
+/// - [Collider] wireframes, optionally colored by [sleep state](Sleeping) and [simulation island](SimulationIslands)
+/// - [Contact] points, normals and penetration depths, color-coded by penetration severity
+/// - [Linear](LinearVelocity) and [angular](AngularVelocity) velocities
+/// - [Joints](joints), highlighting joints that violate their angle limits (see [`JointLimitMonitor`])
 /// - Changing the visibility of entities to only show debug rendering
+/// - Step-through inspection of [joint](joints) solver states using the [`ConstraintDebugger`]
 ///
 /// By default, only axes, colliders and joints are debug rendered. You can use the [`PhysicsDebugConfig`]
 /// resource for the global configuration and the [`DebugRender`] component
-/// for entity-level configuration.
+/// for entity-level configuration. Debug rendering can be toggled at runtime using [`PhysicsDebugConfig::enabled`].
+///
+/// The plugin adds systems to the [`SubstepSchedule`] for the [`ConstraintDebugger`], so it must be added
+/// after [`PhysicsPlugins`]. With the `debug-plugin` feature, [`PhysicsPlugins`] already includes it.
 pub struct PhysicsDebugPlugin {
     schedule: Box<dyn ScheduleLabel>,
 }
@@ -65,6 +72,8 @@ impl Plugin for PhysicsDebugPlugin {
                     debug_render_aabbs,
                     debug_render_colliders,
                     debug_render_contacts,
+                    debug_render_penetration_constraints,
+                    debug_render_velocities,
                     // Todo: Refactor joints to allow iterating over all of them without generics
                     debug_render_joints::<FixedJoint>,
                     debug_render_joints::<PrismaticJoint>,
@@ -76,17 +85,67 @@ impl Plugin for PhysicsDebugPlugin {
                     .after(PhysicsSet::StepSimulation)
                     .run_if(|config: Res<PhysicsDebugConfig>| config.enabled),
             );
+
+        app.init_resource::<ConstraintDebugger>()
+            .register_type::<ConstraintDebugger>()
+            .register_type::<DebugConstraint>()
+            .add_systems(
+                self.schedule.dyn_clone(),
+                debug_render_constraint_record
+                    .after(PhysicsSet::StepSimulation)
+                    .run_if(|config: Res<PhysicsDebugConfig>| config.enabled),
+            );
+
+        let substeps = app
+            .get_schedule_mut(SubstepSchedule)
+            .expect("add PhysicsDebugPlugin after PhysicsPlugins, which add the SubstepSchedule");
+
+        substeps.add_systems(
+            (
+                advance_constraint_debugger,
+                begin_constraint_records::<FixedJoint>,
+                begin_constraint_records::<PrismaticJoint>,
+                begin_constraint_records::<DistanceJoint>,
+                begin_constraint_records::<RevoluteJoint>,
+                begin_constraint_records::<SphericalJoint>,
+            )
+                .chain()
+                .after(SubstepSet::PostProcessCollisions)
+                .before(SubstepSet::SolveConstraints)
+                .run_if(|debugger: Res<ConstraintDebugger>| debugger.recording),
+        );
+
+        substeps.add_systems(
+            (
+                finish_constraint_records::<FixedJoint>,
+                finish_constraint_records::<PrismaticJoint>,
+                finish_constraint_records::<DistanceJoint>,
+                finish_constraint_records::<RevoluteJoint>,
+                finish_constraint_records::<SphericalJoint>,
+            )
+                .chain()
+                .after(SubstepSet::SolveUserConstraints)
+                .before(SubstepSet::UpdateVelocities)
+                .run_if(|debugger: Res<ConstraintDebugger>| debugger.recording),
+        );
     }
 }
 
 fn debug_render_axes(
-    bodies: Query<(&Position, &Rotation, &CenterOfMass, Option<&DebugRender>)>,
+    bodies: Query<(
+        &Position,
+        &Rotation,
+        &CenterOfMass,
+        Option<&CenterOfMassOverride>,
+        Option<&DebugRender>,
+    )>,
     mut debug_renderer: PhysicsDebugRenderer,
     config: Res<PhysicsDebugConfig>,
 ) {
-    for (pos, rot, local_com, render_config) in &bodies {
+    for (pos, rot, local_com, com_override, render_config) in &bodies {
         if let Some(lengths) = render_config.map_or(config.axis_lengths, |c| c.axis_lengths) {
-            let global_com = pos.0 + rot.rotate(local_com.0);
+            let local_com = com_override.map_or(local_com.0, |o| o.apply_to(local_com.0));
+            let global_com = pos.0 + rot.rotate(local_com);
             let x = rot.rotate(Vector::X * lengths.x);
             debug_renderer.draw_line(global_com - x, global_com + x, Color::hsl(0.0, 1.0, 0.5));
 
@@ -145,7 +204,7 @@ fn debug_render_aabbs(
 }
 
 fn debug_render_contacts(
-    colliders: Query<(&Position, &Rotation), With<Collider>>,
+    colliders: Query<(&Position, &Rotation, Option<&ColliderOffset>), With<Collider>>,
     mut collisions: EventReader<Collision>,
     mut debug_renderer: PhysicsDebugRenderer,
     config: Res<PhysicsDebugConfig>,
@@ -154,17 +213,28 @@ fn debug_render_contacts(
         return;
     };
     for Collision(contacts) in collisions.iter() {
-        let Ok((position1, rotation1)) = colliders.get(contacts.entity1) else {
+        let Ok((position1, rotation1, offset1)) = colliders.get(contacts.entity1) else {
             continue;
         };
-        let Ok((position2, rotation2)) = colliders.get(contacts.entity2) else {
+        let Ok((position2, rotation2, offset2)) = colliders.get(contacts.entity2) else {
             continue;
         };
 
+        // Contacts are expressed relative to the offset colliders
+        let (position1, rotation1) = offset1.map_or((*position1, *rotation1), |offset| {
+            let (pos, rot) = offset.transform_pose(position1.0, *rotation1);
+            (Position(pos), rot)
+        });
+        let (position2, rotation2) = offset2.map_or((*position2, *rotation2), |offset| {
+            let (pos, rot) = offset.transform_pose(position2.0, *rotation2);
+            (Position(pos), rot)
+        });
+
         for manifold in contacts.manifolds.iter() {
             for contact in manifold.contacts.iter() {
-                let p1 = contact.global_point1(position1, rotation1);
-                let p2 = contact.global_point2(position2, rotation2);
+                let p1 = contact.global_point1(&position1, &rotation1);
+                let p2 = contact.global_point2(&position2, &rotation2);
+                let color = penetration_severity_color(color, contact.penetration, &config);
                 #[cfg(feature = "2d")]
                 let len = 5.0;
                 #[cfg(feature = "3d")]
@@ -184,26 +254,218 @@ fn debug_render_contacts(
     }
 }
 
+/// Draws the contact normals and penetration depths of the penetration constraints of the current step.
+fn debug_render_penetration_constraints(
+    bodies: Query<(&Position, &Rotation)>,
+    penetration_constraints: Res<PenetrationConstraints>,
+    mut debug_renderer: PhysicsDebugRenderer,
+    config: Res<PhysicsDebugConfig>,
+) {
+    if config.contact_normal_color.is_none() && config.penetration_color.is_none() {
+        return;
+    }
+
+    #[cfg(feature = "2d")]
+    let (normal_length, head_length) = (20.0, 4.0);
+    #[cfg(feature = "3d")]
+    let (normal_length, head_length) = (0.5, 0.1);
+
+    for constraint in penetration_constraints.0.iter() {
+        let Ok([(position1, rotation1), (position2, rotation2)]) =
+            bodies.get_many([constraint.entity1, constraint.entity2])
+        else {
+            continue;
+        };
+
+        // The contact data of the constraints is in the local space of the bodies
+        let contact = &constraint.contact;
+        let p1 = contact.global_point1(position1, rotation1);
+        let p2 = contact.global_point2(position2, rotation2);
+
+        if let Some(color) = config.contact_normal_color {
+            let color = penetration_severity_color(color, contact.penetration, &config);
+            let normal = contact.global_normal1(rotation1);
+            debug_renderer.draw_arrow(p1, p1 + normal * normal_length, head_length, color);
+        }
+
+        if let (Some(color), true) = (config.penetration_color, contact.penetration > 0.0) {
+            let color = penetration_severity_color(color, contact.penetration, &config);
+            debug_renderer.draw_line(p1, p2, color);
+        }
+    }
+}
+
+/// Blends the given color towards the [`PhysicsDebugConfig::penetration_color`] based on the penetration depth.
+#[allow(clippy::unnecessary_cast)]
+fn penetration_severity_color(
+    color: Color,
+    penetration: Scalar,
+    config: &PhysicsDebugConfig,
+) -> Color {
+    let Some(penetration_color) = config.penetration_color else {
+        return color;
+    };
+
+    let t = if config.severe_penetration_depth > 0.0 {
+        (penetration / config.severe_penetration_depth).clamp(0.0, 1.0) as f32
+    } else {
+        1.0
+    };
+    let [r1, g1, b1, a1] = color.as_rgba_f32();
+    let [r2, g2, b2, a2] = penetration_color.as_rgba_f32();
+    Color::rgba(
+        r1 + (r2 - r1) * t,
+        g1 + (g2 - g1) * t,
+        b1 + (b2 - b1) * t,
+        a1 + (a2 - a1) * t,
+    )
+}
+
+type VelocityDebugComponents = (
+    &'static Position,
+    &'static Rotation,
+    &'static CenterOfMass,
+    Option<&'static CenterOfMassOverride>,
+    &'static LinearVelocity,
+    &'static AngularVelocity,
+    Option<&'static DebugRender>,
+);
+
+fn debug_render_velocities(
+    bodies: Query<VelocityDebugComponents, With<RigidBody>>,
+    mut debug_renderer: PhysicsDebugRenderer,
+    config: Res<PhysicsDebugConfig>,
+) {
+    #[cfg(feature = "2d")]
+    let head_length = 5.0;
+    #[cfg(feature = "3d")]
+    let head_length = 0.1;
+
+    for (pos, rot, local_com, com_override, lin_vel, ang_vel, render_config) in &bodies {
+        let local_com = com_override.map_or(local_com.0, |o| o.apply_to(local_com.0));
+        let global_com = pos.0 + rot.rotate(local_com);
+
+        // The velocities are drawn as the distance and angle travelled in one second
+        if let Some(color) =
+            render_config.map_or(config.linear_velocity_color, |c| c.linear_velocity_color)
+        {
+            debug_renderer.draw_arrow(global_com, global_com + lin_vel.0, head_length, color);
+        }
+
+        let Some(color) =
+            render_config.map_or(config.angular_velocity_color, |c| c.angular_velocity_color)
+        else {
+            continue;
+        };
+
+        // Draw an arc around the center of mass, starting from the body's local x axis
+        #[cfg(feature = "2d")]
+        if ang_vel.0 != 0.0 {
+            let radius = 2.0 * head_length;
+            let angle = ang_vel.0.clamp(-2.0 * PI, 2.0 * PI);
+            let segments = (angle.abs() / PI * 16.0).ceil().max(1.0) as usize;
+            let points = (0..=segments)
+                .map(|i| {
+                    let rotation = Rotation::from_radians(angle * i as Scalar / segments as Scalar);
+                    global_com + rot.mul(rotation).rotate(Vector::X * radius)
+                })
+                .collect::<Vec<_>>();
+            for segment in points.windows(2) {
+                debug_renderer.draw_line(segment[0], segment[1], color);
+            }
+
+            // Draw an arrow head tangent to the end of the arc
+            let end = points[segments];
+            let tangent = (end - global_com).perp().normalize_or_zero() * angle.signum();
+            let base = end - tangent * head_length;
+            let side = tangent.perp() * head_length * 0.5;
+            debug_renderer.draw_line(end, base + side, color);
+            debug_renderer.draw_line(end, base - side, color);
+        }
+
+        // Draw the axis of rotation, with a length equal to the angular speed
+        #[cfg(feature = "3d")]
+        debug_renderer.draw_arrow(global_com, global_com + ang_vel.0, head_length, color);
+    }
+}
+
+#[allow(clippy::type_complexity)]
 fn debug_render_colliders(
-    mut colliders: Query<(&Collider, &Position, &Rotation, Option<&DebugRender>)>,
+    mut colliders: Query<(
+        Entity,
+        &Collider,
+        &Position,
+        &Rotation,
+        Option<&ColliderOffset>,
+        Option<&ColliderParent>,
+        Option<&DebugRender>,
+    )>,
+    sleeping: Query<(), With<Sleeping>>,
+    islands: Res<SimulationIslands>,
     mut debug_renderer: PhysicsDebugRenderer,
     config: Res<PhysicsDebugConfig>,
 ) {
-    for (collider, position, rotation, render_config) in &mut colliders {
-        if let Some(color) = render_config.map_or(config.collider_color, |c| c.collider_color) {
-            debug_renderer.draw_collider(collider, position, rotation, color);
+    // Give each island a hue based on its first body, so that the colors don't change
+    // when other islands are created or removed
+    let island_hues = if config.color_islands {
+        islands
+            .iter()
+            .map(|bodies| {
+                let index = bodies
+                    .iter()
+                    .map(|entity| entity_sort_key(*entity))
+                    .min()
+                    .map_or(0, |(index, _)| index);
+                // Spread the hues of consecutive indices using the golden angle
+                (index as f32 * 137.508) % 360.0
+            })
+            .collect::<Vec<_>>()
+    } else {
+        vec![]
+    };
+
+    for (entity, collider, position, rotation, offset, parent, render_config) in &mut colliders {
+        if let Some(mut color) = render_config.map_or(config.collider_color, |c| c.collider_color) {
+            let body = parent.map_or(entity, |parent| parent.get());
+            if let Some(hue) = islands
+                .island_of(body)
+                .and_then(|island| island_hues.get(island))
+            {
+                color = Color::hsl(*hue, 0.8, 0.6);
+            }
+            if let (Some(sleeping_color), true) = (config.sleeping_color, sleeping.contains(body)) {
+                color = sleeping_color;
+            }
+
+            let (position, rotation) = offset.map_or((position.0, *rotation), |offset| {
+                offset.transform_pose(position.0, *rotation)
+            });
+            debug_renderer.draw_collider(collider, &Position(position), &rotation, color);
         }
     }
 }
 
 fn debug_render_joints<T: Joint>(
     bodies: Query<(&Position, &Rotation)>,
-    joints: Query<&T>,
+    joints: Query<(&T, Option<&JointLimitMonitor>)>,
     mut debug_renderer: PhysicsDebugRenderer,
     config: Res<PhysicsDebugConfig>,
 ) {
-    for joint in &joints {
+    for (joint, monitor) in &joints {
         if let Ok([(pos1, rot1), (pos2, rot2)]) = bodies.get_many(joint.entities()) {
+            // Highlight joints that violate their angle limits
+            if let (Some(violation_color), Some(true)) = (
+                config.joint_limit_violation_color,
+                monitor.map(|monitor| monitor.is_violating()),
+            ) {
+                let anchor1 = pos1.0 + rot1.rotate(joint.local_anchor_1());
+                let anchor2 = pos2.0 + rot2.rotate(joint.local_anchor_2());
+                debug_renderer.draw_line(pos1.0, anchor1, violation_color);
+                debug_renderer.draw_line(pos2.0, anchor2, violation_color);
+                debug_renderer.draw_line(anchor1, anchor2, violation_color);
+                continue;
+            }
+
             if let Some(anchor_color) = config.joint_anchor_color {
                 debug_renderer.draw_line(
                     pos1.0,
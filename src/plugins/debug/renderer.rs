@@ -22,6 +22,32 @@ impl<'w, 's> PhysicsDebugRenderer<'w, 's> {
         self.gizmos.line(a.as_f32(), b.as_f32(), color);
     }
 
+    /// Draws an arrow from `a` to `b` with a head of the given length.
+    pub fn draw_arrow(&mut self, a: Vector, b: Vector, head_length: Scalar, color: Color) {
+        self.draw_line(a, b, color);
+
+        let dir = (b - a).normalize_or_zero();
+        if dir == Vector::ZERO {
+            return;
+        }
+        let head_length = head_length.min(a.distance(b));
+        let base = b - dir * head_length;
+
+        #[cfg(feature = "2d")]
+        {
+            let side = dir.perp() * head_length * 0.5;
+            self.draw_line(b, base + side, color);
+            self.draw_line(b, base - side, color);
+        }
+        #[cfg(feature = "3d")]
+        {
+            let (side1, side2) = dir.any_orthonormal_pair();
+            for side in [side1, -side1, side2, -side2] {
+                self.draw_line(b, base + side * head_length * 0.5, color);
+            }
+        }
+    }
+
     /// Draws lines between a list of points.
     pub fn draw_line_strip(
         &mut self,
@@ -0,0 +1,887 @@
+//! Exports the state of the physics world into a file for external analysis.
+//!
+//! See [`FrameCapturePlugin`].
+
+use std::path::{Path, PathBuf};
+
+use crate::prelude::*;
+use bevy::{ecs::event::ManualEventReader, prelude::*, utils::HashMap};
+use parry::shape::TypedShape;
+use serde::{Deserialize, Serialize};
+
+/// Exports the state of the physics world into a JSON file when an [`ExportPhysicsFrame`] event is sent.
+///
+/// A capture contains the [rigid bodies](RigidBody), [collider](Collider) shapes, [contacts](Contacts)
+/// and [joints] with their Lagrange multipliers at the end of a physics frame. Captures can be attached
+/// to bug reports or loaded by external tools for visualization.
+///
+/// Captures can also be created with [`PhysicsCapture::capture`] without this plugin.
+/// They can be loaded back into a headless app with [`PhysicsCapture::create_app`],
+/// for example to turn a captured bug into a regression test.
+///
+/// This plugin requires the `frame-capture` feature.
+///
+/// ## Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn capture_on_key_press(
+///     keys: Res<Input<KeyCode>>,
+///     mut exports: EventWriter<ExportPhysicsFrame>,
+/// ) {
+///     if keys.just_pressed(KeyCode::F12) {
+///         exports.send(ExportPhysicsFrame::new("physics_capture.json"));
+///     }
+/// }
+/// ```
+pub struct FrameCapturePlugin {
+    schedule: Box<dyn ScheduleLabel>,
+}
+
+impl FrameCapturePlugin {
+    /// Creates a [`FrameCapturePlugin`] with the schedule that is used for running the [`PhysicsSchedule`].
+    ///
+    /// The default schedule is `PostUpdate`.
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: Box::new(schedule),
+        }
+    }
+}
+
+impl Default for FrameCapturePlugin {
+    fn default() -> Self {
+        Self::new(PostUpdate)
+    }
+}
+
+impl Plugin for FrameCapturePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ExportPhysicsFrame>()
+            .register_type::<ExportPhysicsFrame>()
+            .add_systems(
+                self.schedule.dyn_clone(),
+                export_physics_frames
+                    .after(PhysicsSet::StepSimulation)
+                    .before(PhysicsSet::Sync),
+            );
+    }
+}
+
+/// An event that requests the [`FrameCapturePlugin`] to write a [`PhysicsCapture`]
+/// of the current physics frame into the given file.
+#[derive(Event, Reflect, Clone, Debug, PartialEq, Eq)]
+pub struct ExportPhysicsFrame {
+    /// The path of the file that the capture is written into.
+    pub path: PathBuf,
+}
+
+impl ExportPhysicsFrame {
+    /// Creates a new [`ExportPhysicsFrame`] event that writes the capture into the given file.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+/// The rotation stored in captures as an angle in radians.
+#[cfg(feature = "2d")]
+pub type CapturedRotation = Scalar;
+/// The rotation stored in captures as a quaternion.
+#[cfg(feature = "3d")]
+pub type CapturedRotation = Quaternion;
+
+/// The angular velocity stored in captures.
+#[cfg(feature = "2d")]
+pub type CapturedAngularVelocity = Scalar;
+/// The angular velocity stored in captures.
+#[cfg(feature = "3d")]
+pub type CapturedAngularVelocity = Vector;
+
+/// A snapshot of the physics world at the end of a physics frame.
+///
+/// Entities are stored using [`Entity::to_bits`].
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct PhysicsCapture {
+    /// The [`DeltaTime`] of the captured frame.
+    pub delta_time: Scalar,
+    /// The [`SubstepCount`] of the captured frame.
+    pub substep_count: u32,
+    /// The [`Gravity`] of the captured frame.
+    pub gravity: Vector,
+    /// The captured rigid bodies.
+    pub bodies: Vec<CapturedBody>,
+    /// The captured colliders.
+    pub colliders: Vec<CapturedCollider>,
+    /// The captured contacts.
+    pub contacts: Vec<CapturedContact>,
+    /// The captured joints.
+    pub joints: Vec<CapturedJoint>,
+}
+
+/// The type of a captured [`RigidBody`].
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CapturedRigidBody {
+    /// A [`RigidBody::Dynamic`] body.
+    #[default]
+    Dynamic,
+    /// A [`RigidBody::Static`] body.
+    Static,
+    /// A [`RigidBody::Kinematic`] body.
+    Kinematic,
+}
+
+impl From<RigidBody> for CapturedRigidBody {
+    fn from(value: RigidBody) -> Self {
+        match value {
+            RigidBody::Dynamic => Self::Dynamic,
+            RigidBody::Static => Self::Static,
+            RigidBody::Kinematic => Self::Kinematic,
+        }
+    }
+}
+
+impl From<CapturedRigidBody> for RigidBody {
+    fn from(value: CapturedRigidBody) -> Self {
+        match value {
+            CapturedRigidBody::Dynamic => Self::Dynamic,
+            CapturedRigidBody::Static => Self::Static,
+            CapturedRigidBody::Kinematic => Self::Kinematic,
+        }
+    }
+}
+
+/// The state of a captured [rigid body](RigidBody).
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct CapturedBody {
+    /// The entity of the body.
+    pub entity: u64,
+    /// The type of the body.
+    pub rigid_body: CapturedRigidBody,
+    /// The [`Position`] of the body.
+    pub position: Vector,
+    /// The [`Rotation`] of the body.
+    pub rotation: CapturedRotation,
+    /// The [`LinearVelocity`] of the body.
+    pub linear_velocity: Vector,
+    /// The [`AngularVelocity`] of the body.
+    pub angular_velocity: CapturedAngularVelocity,
+    /// The [`Mass`] of the body.
+    pub mass: Scalar,
+    /// The local [`CenterOfMass`] of the body.
+    pub center_of_mass: Vector,
+    /// The dynamic [`Friction`] coefficient of the body.
+    pub dynamic_friction: Scalar,
+    /// The static [`Friction`] coefficient of the body.
+    pub static_friction: Scalar,
+    /// The rolling [`Friction`] coefficient of the body.
+    #[serde(default)]
+    pub rolling_friction: Scalar,
+    /// The [`Restitution`] coefficient of the body.
+    pub restitution: Scalar,
+    /// True if the body is [`Sleeping`].
+    pub sleeping: bool,
+}
+
+/// The state of a captured [`Collider`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CapturedCollider {
+    /// The entity of the collider.
+    pub entity: u64,
+    /// The entity of the rigid body that the collider is attached to, if any.
+    pub parent: Option<u64>,
+    /// The global position of the collider, including its [`ColliderOffset`].
+    pub position: Vector,
+    /// The global rotation of the collider, including its [`ColliderOffset`].
+    pub rotation: CapturedRotation,
+    /// The scaled shape of the collider.
+    pub shape: CapturedShape,
+    /// True if the collider is a [`Sensor`].
+    pub sensor: bool,
+}
+
+/// A captured [`Collider`] shape.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum CapturedShape {
+    /// A ball with the given radius.
+    Ball {
+        /// The radius of the ball.
+        radius: Scalar,
+    },
+    /// A cuboid with the given half extents.
+    Cuboid {
+        /// The half extents of the cuboid.
+        half_extents: Vector,
+    },
+    /// A capsule between two points.
+    Capsule {
+        /// The first endpoint of the capsule's segment.
+        a: Vector,
+        /// The second endpoint of the capsule's segment.
+        b: Vector,
+        /// The radius of the capsule.
+        radius: Scalar,
+    },
+    /// A cylinder along the `Y` axis.
+    Cylinder {
+        /// The half height of the cylinder.
+        half_height: Scalar,
+        /// The radius of the cylinder.
+        radius: Scalar,
+    },
+    /// A cone along the `Y` axis.
+    Cone {
+        /// The half height of the cone.
+        half_height: Scalar,
+        /// The radius of the cone's base.
+        radius: Scalar,
+    },
+    /// A segment between two points.
+    Segment {
+        /// The first endpoint of the segment.
+        a: Vector,
+        /// The second endpoint of the segment.
+        b: Vector,
+    },
+    /// A triangle.
+    Triangle {
+        /// The first vertex of the triangle.
+        a: Vector,
+        /// The second vertex of the triangle.
+        b: Vector,
+        /// The third vertex of the triangle.
+        c: Vector,
+    },
+    /// A convex hull of the given points.
+    ConvexHull {
+        /// The vertices of the hull.
+        points: Vec<Vector>,
+    },
+    /// A triangle mesh.
+    TriMesh {
+        /// The vertices of the mesh.
+        vertices: Vec<Vector>,
+        /// The triangles of the mesh as indices into `vertices`.
+        indices: Vec<[u32; 3]>,
+    },
+    /// A polyline.
+    Polyline {
+        /// The vertices of the polyline.
+        vertices: Vec<Vector>,
+        /// The segments of the polyline as indices into `vertices`.
+        indices: Vec<[u32; 2]>,
+    },
+    /// A half-space with the given outward normal.
+    HalfSpace {
+        /// The outward normal of the half-space.
+        normal: Vector,
+    },
+    /// A compound shape made of other shapes.
+    Compound {
+        /// The local positions, rotations and shapes of the sub-shapes.
+        shapes: Vec<(Vector, CapturedRotation, CapturedShape)>,
+    },
+    /// A shape that can't be captured, like a heightfield or a custom shape.
+    /// The AABB of the shape is stored instead.
+    Unsupported {
+        /// The minimum point of the shape's local AABB.
+        mins: Vector,
+        /// The maximum point of the shape's local AABB.
+        maxs: Vector,
+    },
+}
+
+impl From<&Collider> for CapturedShape {
+    fn from(value: &Collider) -> Self {
+        capture_shape(value.get_shape())
+    }
+}
+
+impl CapturedShape {
+    /// Creates a [`Collider`] with the captured shape.
+    ///
+    /// Returns `None` for [unsupported](CapturedShape::Unsupported) shapes
+    /// and for shapes that don't exist in the current dimension.
+    pub fn to_collider(&self) -> Option<Collider> {
+        match self {
+            Self::Ball { radius } => Some(Collider::ball(*radius)),
+            #[cfg(feature = "2d")]
+            Self::Cuboid { half_extents } => {
+                Some(Collider::cuboid(half_extents.x * 2.0, half_extents.y * 2.0))
+            }
+            #[cfg(feature = "3d")]
+            Self::Cuboid { half_extents } => Some(Collider::cuboid(
+                half_extents.x * 2.0,
+                half_extents.y * 2.0,
+                half_extents.z * 2.0,
+            )),
+            Self::Capsule { a, b, radius } => Some(Collider::capsule_endpoints(*a, *b, *radius)),
+            #[cfg(feature = "3d")]
+            Self::Cylinder {
+                half_height,
+                radius,
+            } => Some(Collider::cylinder(half_height * 2.0, *radius)),
+            #[cfg(feature = "3d")]
+            Self::Cone {
+                half_height,
+                radius,
+            } => Some(Collider::cone(half_height * 2.0, *radius)),
+            Self::Segment { a, b } => Some(Collider::segment(*a, *b)),
+            Self::Triangle { a, b, c } => Some(Collider::triangle(*a, *b, *c)),
+            Self::ConvexHull { points } => Collider::convex_hull(points.clone()),
+            Self::TriMesh { vertices, indices } => {
+                Some(Collider::trimesh(vertices.clone(), indices.clone()))
+            }
+            Self::Polyline { vertices, indices } => {
+                Some(Collider::polyline(vertices.clone(), Some(indices.clone())))
+            }
+            Self::HalfSpace { normal } => Some(Collider::halfspace(*normal)),
+            Self::Compound { shapes } => {
+                let shapes = shapes
+                    .iter()
+                    .map(|(position, rotation, shape)| {
+                        Some((
+                            Position(*position),
+                            restore_rotation(*rotation),
+                            shape.to_collider()?,
+                        ))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some(Collider::compound(shapes))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn capture_shape(shape: &parry::shape::SharedShape) -> CapturedShape {
+    match shape.as_typed_shape() {
+        TypedShape::Ball(s) => CapturedShape::Ball { radius: s.radius },
+        TypedShape::Cuboid(s) => CapturedShape::Cuboid {
+            half_extents: s.half_extents.into(),
+        },
+        TypedShape::Capsule(s) => CapturedShape::Capsule {
+            a: s.segment.a.into(),
+            b: s.segment.b.into(),
+            radius: s.radius,
+        },
+        TypedShape::Segment(s) => CapturedShape::Segment {
+            a: s.a.into(),
+            b: s.b.into(),
+        },
+        TypedShape::Triangle(s) => CapturedShape::Triangle {
+            a: s.a.into(),
+            b: s.b.into(),
+            c: s.c.into(),
+        },
+        TypedShape::TriMesh(s) => CapturedShape::TriMesh {
+            vertices: s.vertices().iter().map(|v| (*v).into()).collect(),
+            indices: s.indices().to_vec(),
+        },
+        TypedShape::Polyline(s) => CapturedShape::Polyline {
+            vertices: s.vertices().iter().map(|v| (*v).into()).collect(),
+            indices: s.indices().to_vec(),
+        },
+        TypedShape::HalfSpace(s) => CapturedShape::HalfSpace {
+            normal: s.normal.into_inner().into(),
+        },
+        TypedShape::Compound(s) => CapturedShape::Compound {
+            shapes: s
+                .shapes()
+                .iter()
+                .map(|(isometry, shape)| {
+                    #[cfg(feature = "2d")]
+                    let rotation = isometry.rotation.angle();
+                    #[cfg(feature = "3d")]
+                    let rotation = Quaternion::from(isometry.rotation);
+                    (
+                        isometry.translation.vector.into(),
+                        rotation,
+                        capture_shape(shape),
+                    )
+                })
+                .collect(),
+        },
+        #[cfg(feature = "2d")]
+        TypedShape::ConvexPolygon(s) => CapturedShape::ConvexHull {
+            points: s.points().iter().map(|p| (*p).into()).collect(),
+        },
+        #[cfg(feature = "3d")]
+        TypedShape::ConvexPolyhedron(s) => CapturedShape::ConvexHull {
+            points: s.points().iter().map(|p| (*p).into()).collect(),
+        },
+        #[cfg(feature = "3d")]
+        TypedShape::Cylinder(s) => CapturedShape::Cylinder {
+            half_height: s.half_height,
+            radius: s.radius,
+        },
+        #[cfg(feature = "3d")]
+        TypedShape::Cone(s) => CapturedShape::Cone {
+            half_height: s.half_height,
+            radius: s.radius,
+        },
+        _ => {
+            let aabb = shape.compute_local_aabb();
+            CapturedShape::Unsupported {
+                mins: aabb.mins.into(),
+                maxs: aabb.maxs.into(),
+            }
+        }
+    }
+}
+
+/// A captured contact point between two colliders.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct CapturedContact {
+    /// The first collider entity.
+    pub entity1: u64,
+    /// The second collider entity.
+    pub entity2: u64,
+    /// The contact point on the first collider in global coordinates.
+    pub point1: Vector,
+    /// The contact point on the second collider in global coordinates.
+    pub point2: Vector,
+    /// The contact normal of the first collider in global coordinates.
+    pub normal: Vector,
+    /// The penetration depth.
+    pub penetration: Scalar,
+}
+
+/// The state of a captured [joint](joints).
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct CapturedJoint {
+    /// The entity of the joint.
+    pub entity: u64,
+    /// The name of the joint type, like `FixedJoint`.
+    pub kind: String,
+    /// The first body constrained by the joint.
+    pub entity1: u64,
+    /// The second body constrained by the joint.
+    pub entity2: u64,
+    /// The attachment point on the first body.
+    pub local_anchor1: Vector,
+    /// The attachment point on the second body.
+    pub local_anchor2: Vector,
+    /// The linear velocity damping of the joint.
+    pub damping_linear: Scalar,
+    /// The angular velocity damping of the joint.
+    pub damping_angular: Scalar,
+    /// The Lagrange multipliers of the joint from the last substep.
+    /// See [`Joint::lagrange_multipliers`].
+    pub lagrange_multipliers: Vec<Scalar>,
+}
+
+/// An error that can occur when saving or loading a [`PhysicsCapture`].
+#[derive(Debug)]
+pub enum PhysicsCaptureError {
+    /// The capture could not be read or written.
+    Io(std::io::Error),
+    /// The capture could not be serialized or deserialized.
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for PhysicsCaptureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to access physics capture: {err}"),
+            Self::Json(err) => write!(f, "invalid physics capture: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for PhysicsCaptureError {}
+
+impl From<std::io::Error> for PhysicsCaptureError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for PhysicsCaptureError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
+
+impl PhysicsCapture {
+    /// Captures the current state of the physics world.
+    pub fn capture(world: &mut World) -> Self {
+        let mut capture = Self {
+            delta_time: world.get_resource::<DeltaTime>().map_or(0.0, |dt| dt.0),
+            substep_count: world.get_resource::<SubstepCount>().map_or(1, |s| s.0),
+            gravity: world
+                .get_resource::<Gravity>()
+                .map_or(Vector::ZERO, |g| g.0),
+            ..default()
+        };
+
+        let mut bodies = world.query::<(
+            Entity,
+            &RigidBody,
+            &Position,
+            &Rotation,
+            Option<&LinearVelocity>,
+            Option<&AngularVelocity>,
+            Option<&Mass>,
+            Option<&CenterOfMass>,
+            Option<&Friction>,
+            Option<&Restitution>,
+            Option<&Sleeping>,
+        )>();
+        for (entity, rb, pos, rot, lin_vel, ang_vel, mass, com, friction, restitution, sleeping) in
+            bodies.iter(world)
+        {
+            let friction = friction.copied().unwrap_or_default();
+            capture.bodies.push(CapturedBody {
+                entity: entity.to_bits(),
+                rigid_body: (*rb).into(),
+                position: pos.0,
+                rotation: capture_rotation(rot),
+                linear_velocity: lin_vel.map_or(Vector::ZERO, |v| v.0),
+                angular_velocity: ang_vel.map_or(default(), |v| v.0),
+                mass: mass.map_or(0.0, |m| m.0),
+                center_of_mass: com.map_or(Vector::ZERO, |c| c.0),
+                dynamic_friction: friction.dynamic_coefficient,
+                static_friction: friction.static_coefficient,
+                rolling_friction: friction.rolling_coefficient,
+                restitution: restitution.copied().unwrap_or_default().coefficient,
+                sleeping: sleeping.is_some(),
+            });
+        }
+
+        let mut colliders = world.query::<(
+            Entity,
+            &Collider,
+            &Position,
+            &Rotation,
+            Option<&ColliderOffset>,
+            Option<&ColliderParent>,
+            Option<&Sensor>,
+        )>();
+        for (entity, collider, pos, rot, offset, parent, sensor) in colliders.iter(world) {
+            let (pos, rot) =
+                offset.map_or((pos.0, *rot), |offset| offset.transform_pose(pos.0, *rot));
+            capture.colliders.push(CapturedCollider {
+                entity: entity.to_bits(),
+                parent: parent.map(|p| p.get().to_bits()),
+                position: pos,
+                rotation: capture_rotation(&rot),
+                shape: collider.into(),
+                sensor: sensor.is_some(),
+            });
+        }
+
+        let mut poses = world.query::<(&Position, &Rotation, Option<&ColliderOffset>)>();
+        if let Some(collisions) = world.get_resource::<Collisions>() {
+            for contacts in collisions.iter() {
+                let (Ok((pos1, rot1, offset1)), Ok((pos2, rot2, offset2))) = (
+                    poses.get(world, contacts.entity1),
+                    poses.get(world, contacts.entity2),
+                ) else {
+                    continue;
+                };
+
+                // Contacts are expressed relative to the offset colliders
+                let (pos1, rot1) = offset1.map_or((pos1.0, *rot1), |offset| {
+                    offset.transform_pose(pos1.0, *rot1)
+                });
+                let (pos2, rot2) = offset2.map_or((pos2.0, *rot2), |offset| {
+                    offset.transform_pose(pos2.0, *rot2)
+                });
+
+                for manifold in contacts.manifolds.iter() {
+                    for contact in manifold.contacts.iter() {
+                        capture.contacts.push(CapturedContact {
+                            entity1: contacts.entity1.to_bits(),
+                            entity2: contacts.entity2.to_bits(),
+                            point1: contact.global_point1(&Position(pos1), &rot1),
+                            point2: contact.global_point2(&Position(pos2), &rot2),
+                            normal: contact.global_normal1(&rot1),
+                            penetration: contact.penetration,
+                        });
+                    }
+                }
+            }
+        }
+
+        capture_joints::<FixedJoint>(world, "FixedJoint", &mut capture.joints);
+        capture_joints::<PrismaticJoint>(world, "PrismaticJoint", &mut capture.joints);
+        capture_joints::<DistanceJoint>(world, "DistanceJoint", &mut capture.joints);
+        capture_joints::<RevoluteJoint>(world, "RevoluteJoint", &mut capture.joints);
+        capture_joints::<SphericalJoint>(world, "SphericalJoint", &mut capture.joints);
+
+        capture
+    }
+
+    /// Serializes the capture into a JSON string.
+    pub fn to_json(&self) -> Result<String, PhysicsCaptureError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Deserializes a capture from a JSON string.
+    pub fn from_json(json: &str) -> Result<Self, PhysicsCaptureError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Writes the capture into a JSON file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), PhysicsCaptureError> {
+        Ok(std::fs::write(path, self.to_json()?)?)
+    }
+
+    /// Reads a capture from a JSON file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, PhysicsCaptureError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Spawns the captured bodies, colliders and joints into the given world and inserts
+    /// the captured [`Gravity`] and [`SubstepCount`].
+    ///
+    /// Returns a map from the captured entities to the spawned entities.
+    ///
+    /// Mass properties are recomputed from the colliders using the default density,
+    /// and contacts are recomputed by the simulation. Colliders with
+    /// [unsupported](CapturedShape::Unsupported) shapes and joints of unknown types are skipped.
+    pub fn spawn(&self, world: &mut World) -> HashMap<u64, Entity> {
+        world.insert_resource(Gravity(self.gravity));
+        world.insert_resource(SubstepCount(self.substep_count));
+
+        let mut entities = HashMap::default();
+        let mut bodies = HashMap::default();
+
+        for body in self.bodies.iter() {
+            let rotation = restore_rotation(body.rotation);
+            let mut entity = world.spawn((
+                restore_transform(body.position, rotation),
+                RigidBody::from(body.rigid_body),
+                Position(body.position),
+                rotation,
+                LinearVelocity(body.linear_velocity),
+                AngularVelocity(body.angular_velocity),
+                Friction::new(body.dynamic_friction)
+                    .with_static_coefficient(body.static_friction)
+                    .with_rolling_coefficient(body.rolling_friction),
+                Restitution::new(body.restitution),
+            ));
+            if body.sleeping {
+                entity.insert(Sleeping);
+            }
+            entities.insert(body.entity, entity.id());
+            bodies.insert(body.entity, body);
+        }
+
+        for collider in self.colliders.iter() {
+            let Some(shape) = collider.shape.to_collider() else {
+                continue;
+            };
+            let rotation = restore_rotation(collider.rotation);
+
+            // The captured pose of a collider includes its offset, so it's restored as an offset
+            // relative to the entity of the collider or as a child of the rigid body.
+            if let Some(body) = collider.parent.and_then(|parent| bodies.get(&parent)) {
+                let body_entity = entities[&body.entity];
+                let body_rotation = restore_rotation(body.rotation);
+                let offset = ColliderOffset::new(
+                    body_rotation
+                        .inverse()
+                        .rotate(collider.position - body.position),
+                    body_rotation.inverse().mul(rotation),
+                );
+
+                if body.entity == collider.entity {
+                    world.entity_mut(body_entity).insert((shape, offset));
+                } else {
+                    let child = world
+                        .spawn((
+                            restore_transform(offset.translation, offset.rotation),
+                            shape,
+                        ))
+                        .set_parent(body_entity)
+                        .id();
+                    entities.insert(collider.entity, child);
+                }
+            } else {
+                let entity = world
+                    .spawn((
+                        restore_transform(collider.position, rotation),
+                        Position(collider.position),
+                        rotation,
+                        shape,
+                    ))
+                    .id();
+                entities.insert(collider.entity, entity);
+            }
+
+            if collider.sensor {
+                if let Some(entity) = entities.get(&collider.entity) {
+                    world.entity_mut(*entity).insert(Sensor);
+                }
+            }
+        }
+
+        for joint in self.joints.iter() {
+            let (Some(entity1), Some(entity2)) =
+                (entities.get(&joint.entity1), entities.get(&joint.entity2))
+            else {
+                continue;
+            };
+            let (entity1, entity2) = (*entity1, *entity2);
+            let entity = match joint.kind.as_str() {
+                "FixedJoint" => world.spawn(restore_joint::<FixedJoint>(joint, entity1, entity2)),
+                "PrismaticJoint" => {
+                    world.spawn(restore_joint::<PrismaticJoint>(joint, entity1, entity2))
+                }
+                "DistanceJoint" => {
+                    world.spawn(restore_joint::<DistanceJoint>(joint, entity1, entity2))
+                }
+                "RevoluteJoint" => {
+                    world.spawn(restore_joint::<RevoluteJoint>(joint, entity1, entity2))
+                }
+                "SphericalJoint" => {
+                    world.spawn(restore_joint::<SphericalJoint>(joint, entity1, entity2))
+                }
+                _ => continue,
+            }
+            .id();
+            entities.insert(joint.entity, entity);
+        }
+
+        entities
+    }
+
+    /// Creates a headless [`App`] with the [`PhysicsPlugins`] and spawns the captured world into it
+    /// using [`PhysicsCapture::spawn`].
+    ///
+    /// The app advances the simulation by the captured [`DeltaTime`] on each update, which makes
+    /// it suitable for reproducing captured problems in tests.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "2d")]
+    /// # use bevy_xpbd_2d::prelude::*;
+    /// # #[cfg(feature = "3d")]
+    /// use bevy_xpbd_3d::prelude::*;
+    ///
+    /// #[test]
+    /// fn captured_stack_stays_upright() {
+    ///     let capture = PhysicsCapture::load("tests/captures/stack.json").unwrap();
+    ///     let mut app = capture.create_app();
+    ///
+    ///     for _ in 0..60 {
+    ///         app.update();
+    ///     }
+    ///
+    ///     // Assert something about the state of the world
+    /// }
+    /// ```
+    pub fn create_app(&self) -> App {
+        let mut app = App::new();
+
+        #[allow(unused_mut)]
+        let mut physics_plugins = PhysicsPlugins::default().build();
+        #[cfg(feature = "debug-plugin")]
+        {
+            physics_plugins = physics_plugins.disable::<PhysicsDebugPlugin>();
+        }
+
+        app.add_plugins((MinimalPlugins, TransformPlugin, physics_plugins))
+            .insert_resource(PhysicsTimestep::FixedOnce(self.delta_time));
+
+        self.spawn(&mut app.world);
+
+        app
+    }
+}
+
+fn restore_rotation(rotation: CapturedRotation) -> Rotation {
+    #[cfg(feature = "2d")]
+    {
+        Rotation::from_radians(rotation)
+    }
+    #[cfg(feature = "3d")]
+    {
+        Rotation(rotation)
+    }
+}
+
+/// Returns a transform matching the given pose, so that the transform isn't
+/// mistaken for a change made by the user when it's synchronized.
+fn restore_transform(translation: Vector, rotation: Rotation) -> TransformBundle {
+    #[cfg(feature = "2d")]
+    let transform = Transform::from_translation(translation.extend(0.0).as_f32())
+        .with_rotation(Quaternion::from(rotation).as_f32());
+    #[cfg(feature = "3d")]
+    let transform =
+        Transform::from_translation(translation.as_f32()).with_rotation(rotation.as_f32());
+
+    TransformBundle {
+        local: transform,
+        global: GlobalTransform::from(transform),
+    }
+}
+
+fn restore_joint<T: Joint>(joint: &CapturedJoint, entity1: Entity, entity2: Entity) -> T {
+    T::new(entity1, entity2)
+        .with_local_anchor_1(joint.local_anchor1)
+        .with_local_anchor_2(joint.local_anchor2)
+        .with_linear_velocity_damping(joint.damping_linear)
+        .with_angular_velocity_damping(joint.damping_angular)
+}
+
+fn capture_rotation(rotation: &Rotation) -> CapturedRotation {
+    #[cfg(feature = "2d")]
+    {
+        rotation.as_radians()
+    }
+    #[cfg(feature = "3d")]
+    {
+        rotation.0
+    }
+}
+
+fn capture_joints<T: Joint>(world: &mut World, kind: &str, joints: &mut Vec<CapturedJoint>) {
+    let mut query = world.query::<(Entity, &T)>();
+    for (entity, joint) in query.iter(world) {
+        let [entity1, entity2] = joint.entities();
+        joints.push(CapturedJoint {
+            entity: entity.to_bits(),
+            kind: kind.to_string(),
+            entity1: entity1.to_bits(),
+            entity2: entity2.to_bits(),
+            local_anchor1: joint.local_anchor_1(),
+            local_anchor2: joint.local_anchor_2(),
+            damping_linear: joint.damping_linear(),
+            damping_angular: joint.damping_angular(),
+            lagrange_multipliers: joint.lagrange_multipliers(),
+        });
+    }
+}
+
+/// Writes a [`PhysicsCapture`] for each [`ExportPhysicsFrame`] event.
+fn export_physics_frames(
+    world: &mut World,
+    mut reader: Local<ManualEventReader<ExportPhysicsFrame>>,
+) {
+    let paths: Vec<PathBuf> = reader
+        .iter(world.resource::<Events<ExportPhysicsFrame>>())
+        .map(|event| event.path.clone())
+        .collect();
+
+    if paths.is_empty() {
+        return;
+    }
+
+    let capture = PhysicsCapture::capture(world);
+    for path in paths {
+        if let Err(err) = capture.save(&path) {
+            error!(
+                "failed to export physics frame to {}: {err}",
+                path.display()
+            );
+        }
+    }
+}
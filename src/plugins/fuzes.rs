@@ -0,0 +1,284 @@
+//! Proximity and contact fuzes that send [`Detonation`] events, for things like mines, grenades and missiles.
+//!
+//! See [`FuzePlugin`].
+
+use crate::prelude::*;
+use bevy::{prelude::*, utils::HashSet};
+
+/// Triggers [`ProximityFuze`]s and [`ContactFuze`]s and sends [`Detonation`] events for them.
+///
+/// Proximity fuzes use the pairs collected by the [broad phase](BroadPhasePlugin), so the bodies
+/// around each fuze don't have to be found using spatial queries. Contact fuzes use the [`Collisions`]
+/// computed by the [narrow phase](NarrowPhasePlugin).
+///
+/// A fuze only detonates once. It is removed from its entity when it detonates, and the entity is left as is,
+/// so that explosions and other effects can be handled by reading the [`Detonation`] events.
+///
+/// The fuzes are triggered at the end of each physics step in the [`PhysicsSchedule`].
+pub struct FuzePlugin;
+
+impl Plugin for FuzePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Detonation>()
+            .register_type::<ProximityFuze>()
+            .register_type::<ContactFuze>()
+            .register_type::<Detonation>();
+
+        let physics_schedule = app
+            .get_schedule_mut(PhysicsSchedule)
+            .expect("add PhysicsSchedule first");
+
+        physics_schedule.add_systems(
+            (trigger_proximity_fuzes, trigger_contact_fuzes)
+                .chain()
+                .after(PhysicsStepSet::SpatialQuery),
+        );
+    }
+}
+
+/// A fuze that detonates when a [collider](Collider) is within the given `radius` of the [`Position`] of the fuze.
+///
+/// The fuze must be added to an entity with a [`Collider`], and its [`ColliderAabb`] is enlarged by the radius
+/// so that the colliders around it are collected by the [broad phase](BroadPhasePlugin). Colliders are filtered
+/// using the [`CollisionLayers`] of the fuze's collider and the `masks` of the fuze. Use a [`Sensor`] collider
+/// if the fuze shouldn't collide with anything, like for mines.
+///
+/// When the fuze detonates, a [`Detonation`] event is sent and the fuze is removed from the entity.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     // A mine that detonates when something comes within 2 units of it
+///     commands.spawn((
+///         RigidBody::Static,
+///         Collider::ball(0.25),
+///         Sensor,
+///         ProximityFuze::new(2.0),
+///     ));
+/// }
+///
+/// fn explode(mut detonations: EventReader<Detonation>, mut commands: Commands) {
+///     for detonation in detonations.iter() {
+///         commands.entity(detonation.fuze).despawn_recursive();
+///     }
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Component, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct ProximityFuze {
+    /// The distance from the fuze that colliders trigger the fuze at.
+    pub radius: Scalar,
+    /// Specifies which [collision groups](CollisionLayers) can trigger the fuze.
+    pub masks: u32,
+}
+
+impl Default for ProximityFuze {
+    fn default() -> Self {
+        Self::new(0.0)
+    }
+}
+
+impl ProximityFuze {
+    /// Creates a new proximity fuze with the given radius that can be triggered by any collider.
+    pub fn new(radius: Scalar) -> Self {
+        Self {
+            radius,
+            masks: 0xffff_ffff,
+        }
+    }
+
+    /// Sets the masks of the fuze using a list of [layers](PhysicsLayer). Only colliders with
+    /// the corresponding [collision groups](CollisionLayers) can trigger the fuze.
+    pub fn with_masks(mut self, masks: impl IntoIterator<Item = impl PhysicsLayer>) -> Self {
+        self.masks = 0;
+        for mask in masks.into_iter().map(|l| l.to_bits()) {
+            self.masks |= mask;
+        }
+        self
+    }
+
+    /// Sets the masks of the fuze using a bitmask. Only colliders with
+    /// the corresponding [collision groups](CollisionLayers) can trigger the fuze.
+    pub fn with_masks_from_bits(mut self, masks: u32) -> Self {
+        self.masks = masks;
+        self
+    }
+}
+
+/// A fuze that is armed when its [collider](Collider) comes into contact with another collider,
+/// and that detonates after the given `delay`.
+///
+/// A delay of zero detonates the fuze on impact. Longer delays can be used for things like grenades
+/// that bounce around before exploding. The contacts are read from [`Collisions`], so they are filtered
+/// like any other collisions, and [`Sensor`] colliders can be used as well.
+///
+/// When the fuze detonates, a [`Detonation`] event is sent and the fuze is removed from the entity.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     // A grenade that detonates 1.5 seconds after it first hits something
+///     commands.spawn((
+///         RigidBody::Dynamic,
+///         Collider::ball(0.1),
+///         ContactFuze::new(1.5),
+///     ));
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Component, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct ContactFuze {
+    /// The time in seconds between the first contact and the detonation.
+    pub delay: Scalar,
+    /// The entity that armed the fuze and the time that has elapsed since it was armed.
+    pub(crate) armed: Option<(Entity, Scalar)>,
+}
+
+impl Default for ContactFuze {
+    fn default() -> Self {
+        Self::impact()
+    }
+}
+
+impl ContactFuze {
+    /// Creates a new contact fuze that detonates the given time in seconds after its first contact.
+    pub fn new(delay: Scalar) -> Self {
+        Self { delay, armed: None }
+    }
+
+    /// Creates a new contact fuze that detonates on impact.
+    pub fn impact() -> Self {
+        Self::new(0.0)
+    }
+
+    /// Returns true if the fuze has come into contact with another collider and is counting down.
+    pub fn is_armed(&self) -> bool {
+        self.armed.is_some()
+    }
+
+    /// Returns the time in seconds until the fuze detonates, or `None` if it isn't armed yet.
+    pub fn remaining_time(&self) -> Option<Scalar> {
+        self.armed
+            .map(|(_, elapsed)| (self.delay - elapsed).max(0.0))
+    }
+}
+
+/// An event that is sent when a [`ProximityFuze`] or a [`ContactFuze`] detonates.
+#[derive(Event, Reflect, Clone, Copy, Debug, PartialEq)]
+pub struct Detonation {
+    /// The entity of the fuze.
+    pub fuze: Entity,
+    /// The collider that triggered the fuze. For contact fuzes, this is the collider that armed the fuze.
+    pub trigger: Entity,
+    /// The [`Position`] of the fuze at the time of the detonation.
+    pub position: Vector,
+}
+
+type FuzeTargetComponents = (
+    &'static Collider,
+    &'static Position,
+    &'static Rotation,
+    Option<&'static ColliderOffset>,
+    Option<&'static CollisionLayers>,
+);
+
+/// Detonates [`ProximityFuze`]s that have a collider within their radius.
+///
+/// Only the [`BroadCollisionPairs`] that contain a fuze are checked.
+fn trigger_proximity_fuzes(
+    mut commands: Commands,
+    fuzes: Query<(&ProximityFuze, &Position)>,
+    targets: Query<FuzeTargetComponents>,
+    broad_collision_pairs: Res<BroadCollisionPairs>,
+    mut detonation_ev_writer: EventWriter<Detonation>,
+) {
+    let mut detonated = HashSet::new();
+
+    for &(entity1, entity2) in broad_collision_pairs.0.iter() {
+        for (fuze_entity, target_entity) in [(entity1, entity2), (entity2, entity1)] {
+            if detonated.contains(&fuze_entity) {
+                continue;
+            }
+            let Ok((fuze, fuze_position)) = fuzes.get(fuze_entity) else {
+                continue;
+            };
+            let Ok((collider, position, rotation, offset, layers)) = targets.get(target_entity)
+            else {
+                continue;
+            };
+
+            let layers = layers.map_or(CollisionLayers::default(), |layers| *layers);
+            if layers.groups_bits() & fuze.masks == 0 {
+                continue;
+            }
+
+            let (position, rotation) = offset.map_or((position.0, *rotation), |offset| {
+                offset.transform_pose(position.0, *rotation)
+            });
+            let distance = collider.get_shape().distance_to_point(
+                &utils::make_isometry(position, rotation),
+                &fuze_position.0.into(),
+                true,
+            );
+
+            if distance <= fuze.radius {
+                detonated.insert(fuze_entity);
+                commands.entity(fuze_entity).remove::<ProximityFuze>();
+                detonation_ev_writer.send(Detonation {
+                    fuze: fuze_entity,
+                    trigger: target_entity,
+                    position: fuze_position.0,
+                });
+            }
+        }
+    }
+}
+
+/// Arms [`ContactFuze`]s that are in contact with other colliders and detonates them after their delay.
+fn trigger_contact_fuzes(
+    mut commands: Commands,
+    mut fuzes: Query<(Entity, &mut ContactFuze, &Position)>,
+    collisions: Res<Collisions>,
+    dt: Res<DeltaTime>,
+    mut detonation_ev_writer: EventWriter<Detonation>,
+) {
+    for (entity, mut fuze, position) in &mut fuzes {
+        if let Some((_, elapsed)) = &mut fuze.armed {
+            *elapsed += dt.0;
+        } else if let Some(contacts) = collisions.collisions_with_entity(entity).next() {
+            let other = if contacts.entity1 == entity {
+                contacts.entity2
+            } else {
+                contacts.entity1
+            };
+            fuze.armed = Some((other, 0.0));
+        }
+
+        if let Some((trigger, elapsed)) = fuze.armed {
+            if elapsed >= fuze.delay {
+                commands.entity(entity).remove::<ContactFuze>();
+                detonation_ev_writer.send(Detonation {
+                    fuze: entity,
+                    trigger,
+                    position: position.0,
+                });
+            }
+        }
+    }
+}
@@ -12,6 +12,10 @@ use bevy::prelude::*;
 ///
 /// The integration scheme used is very closely related to implicit Euler integration.
 ///
+/// The thrust of [`Thruster`]s and the torque of [`AttitudeControl`] are also applied here.
+///
+/// Bodies inside of [`TimeDilationVolume`]s are integrated using a time step scaled by their [`TimeDilation`].
+///
 /// The integration systems run in [`SubstepSet::Integrate`].
 pub struct IntegratorPlugin;
 
@@ -19,11 +23,20 @@ impl Plugin for IntegratorPlugin {
     fn build(&self, app: &mut App) {
         app.get_schedule_mut(SubstepSchedule)
             .expect("add SubstepSchedule first")
-            .add_systems((integrate_pos, integrate_rot).in_set(SubstepSet::Integrate));
+            .add_systems(
+                (
+                    apply_thrusters,
+                    apply_attitude_control,
+                    (integrate_pos, integrate_rot),
+                )
+                    .chain()
+                    .in_set(SubstepSet::Integrate),
+            );
         app.get_schedule_mut(PhysicsSchedule)
             .expect("add PhysicsSchedule first")
             .add_systems(
-                apply_impulses
+                (update_time_dilation, apply_impulses)
+                    .chain()
                     .after(PhysicsStepSet::BroadPhase)
                     .before(PhysicsStepSet::Substeps),
             )
@@ -31,6 +44,179 @@ impl Plugin for IntegratorPlugin {
     }
 }
 
+type ThrusterBodyComponents = (
+    &'static RigidBody,
+    &'static Rotation,
+    &'static mut LinearVelocity,
+    &'static mut AngularVelocity,
+    &'static InverseMass,
+    &'static InverseInertia,
+    &'static CenterOfMass,
+    Option<&'static CenterOfMassOverride>,
+    Option<&'static LockedAxes>,
+    Option<&'static TimeDilation>,
+    Option<&'static Sleeping>,
+    Option<&'static mut TimeSleeping>,
+);
+
+/// Spools [`Thruster`]s towards their throttle and applies their thrust at their mount points
+/// to the rigid bodies that they are attached to.
+fn apply_thrusters(
+    mut commands: Commands,
+    mut thrusters: Query<(Entity, &mut Thruster, Option<&ColliderParent>)>,
+    mut bodies: Query<ThrusterBodyComponents>,
+    parents: Query<&Parent>,
+    sub_dt: Res<SubDeltaTime>,
+) {
+    for (entity, mut thruster, collider_parent) in &mut thrusters {
+        thruster.spool(sub_dt.0);
+
+        if thruster.thrust() == 0.0 {
+            continue;
+        }
+
+        // The thruster can be on the body itself, on one of its colliders,
+        // or on another descendant entity of the body
+        let body_entity = if bodies.contains(entity) {
+            Some(entity)
+        } else if let Some(collider_parent) = collider_parent {
+            Some(collider_parent.get())
+        } else {
+            parents
+                .iter_ancestors(entity)
+                .find(|ancestor| bodies.contains(*ancestor))
+        };
+        let Some(body_entity) = body_entity else {
+            continue;
+        };
+
+        let Ok((
+            rb,
+            rot,
+            mut lin_vel,
+            mut ang_vel,
+            inv_mass,
+            inv_inertia,
+            center_of_mass,
+            com_override,
+            locked_axes,
+            time_dilation,
+            sleeping,
+            time_sleeping,
+        )) = bodies.get_mut(body_entity)
+        else {
+            continue;
+        };
+
+        if !rb.is_dynamic() {
+            continue;
+        }
+
+        // Wake up sleeping bodies, and start pushing them once they are awake
+        if sleeping.is_some() {
+            commands.entity(body_entity).remove::<Sleeping>();
+            if let Some(mut time_sleeping) = time_sleeping {
+                time_sleeping.0 = 0.0;
+            }
+            continue;
+        }
+
+        let delta_secs = sub_dt.0 * time_dilation.map_or(1.0, |dilation| dilation.0);
+
+        let locked_axes = locked_axes.map_or(LockedAxes::default(), |locked_axes| *locked_axes);
+        let center_of_mass =
+            com_override.map_or(center_of_mass.0, |o| o.apply_to(center_of_mass.0));
+
+        let force = rot.rotate(thruster.local_force());
+        let r = rot.rotate(thruster.local_position - center_of_mass);
+
+        let effective_inv_mass = locked_axes.apply_to_vec(Vector::splat(inv_mass.0));
+        lin_vel.0 += delta_secs * force * effective_inv_mass;
+
+        #[cfg(feature = "2d")]
+        {
+            let effective_inv_inertia = locked_axes.apply_to_rotation(inv_inertia.0);
+            ang_vel.0 += delta_secs * effective_inv_inertia * r.perp_dot(force);
+        }
+        #[cfg(feature = "3d")]
+        {
+            let effective_inv_inertia = locked_axes.apply_to_rotation(inv_inertia.rotated(rot).0);
+            ang_vel.0 += delta_secs * effective_inv_inertia * r.cross(force);
+        }
+    }
+}
+
+/// Applies the torque of [`AttitudeControl`] to rotate bodies towards their target orientations.
+fn apply_attitude_control(
+    mut commands: Commands,
+    mut bodies: Query<(
+        Entity,
+        &RigidBody,
+        &Rotation,
+        &mut AngularVelocity,
+        &mut AttitudeControl,
+        &Inertia,
+        &InverseInertia,
+        Option<&LockedAxes>,
+        Option<&TimeDilation>,
+        Option<&Sleeping>,
+        Option<&mut TimeSleeping>,
+    )>,
+    sub_dt: Res<SubDeltaTime>,
+) {
+    for (
+        entity,
+        rb,
+        rot,
+        mut ang_vel,
+        mut controller,
+        inertia,
+        inv_inertia,
+        locked_axes,
+        time_dilation,
+        sleeping,
+        time_sleeping,
+    ) in &mut bodies
+    {
+        if !rb.is_dynamic() {
+            continue;
+        }
+
+        let delta_secs = sub_dt.0 * time_dilation.map_or(1.0, |dilation| dilation.0);
+
+        let locked_axes = locked_axes.map_or(LockedAxes::default(), |locked_axes| *locked_axes);
+
+        #[cfg(feature = "2d")]
+        let (inertia, inv_inertia) = (inertia.0, inv_inertia.0);
+        #[cfg(feature = "3d")]
+        let (inertia, inv_inertia) = (inertia.rotated(rot).0, inv_inertia.rotated(rot).0);
+
+        let torque = controller.compute_torque(rot, ang_vel.0, inertia, delta_secs);
+        let delta_ang_vel =
+            locked_axes.apply_to_angular_velocity(delta_secs * inv_inertia * torque);
+
+        // Avoid triggering change detection unnecessarily
+        #[cfg(feature = "2d")]
+        let is_zero = delta_ang_vel == 0.0;
+        #[cfg(feature = "3d")]
+        let is_zero = delta_ang_vel == Vector::ZERO;
+        if is_zero {
+            continue;
+        }
+
+        // Wake up sleeping bodies, and start rotating them once they are awake
+        if sleeping.is_some() {
+            commands.entity(entity).remove::<Sleeping>();
+            if let Some(mut time_sleeping) = time_sleeping {
+                time_sleeping.0 = 0.0;
+            }
+            continue;
+        }
+
+        ang_vel.0 += delta_ang_vel;
+    }
+}
+
 type PosIntegrationComponents = (
     &'static RigidBody,
     &'static Position,
@@ -43,6 +229,7 @@ type PosIntegrationComponents = (
     &'static Mass,
     &'static InverseMass,
     Option<&'static LockedAxes>,
+    Option<&'static TimeDilation>,
 );
 
 /// Explicitly integrates the positions and linear velocities of bodies taking only external forces
@@ -64,6 +251,7 @@ fn integrate_pos(
         mass,
         inv_mass,
         locked_axes,
+        time_dilation,
     ) in &mut bodies
     {
         prev_pos.0 = pos.0;
@@ -73,12 +261,13 @@ fn integrate_pos(
         }
 
         let locked_axes = locked_axes.map_or(LockedAxes::default(), |locked_axes| *locked_axes);
+        let delta_secs = sub_dt.0 * time_dilation.map_or(1.0, |dilation| dilation.0);
 
         // Apply damping, gravity and other external forces
         if rb.is_dynamic() {
             // Apply damping
             if let Some(damping) = lin_damping {
-                lin_vel.0 *= 1.0 / (1.0 + sub_dt.0 * damping.0);
+                lin_vel.0 *= 1.0 / (1.0 + delta_secs * damping.0);
             }
 
             let effective_mass = locked_axes.apply_to_vec(Vector::splat(mass.0));
@@ -88,14 +277,14 @@ fn integrate_pos(
             let gravitation_force =
                 effective_mass * gravity.0 * gravity_scale.map_or(1.0, |scale| scale.0);
             let external_forces = gravitation_force + external_force.force();
-            let delta_lin_vel = sub_dt.0 * external_forces * effective_inv_mass;
+            let delta_lin_vel = delta_secs * external_forces * effective_inv_mass;
             // avoid triggering bevy's change detection unnecessarily
             if delta_lin_vel != Vector::ZERO {
                 lin_vel.0 += delta_lin_vel;
             }
         }
         if lin_vel.0 != Vector::ZERO {
-            translation.0 += locked_axes.apply_to_vec(sub_dt.0 * lin_vel.0);
+            translation.0 += locked_axes.apply_to_vec(delta_secs * lin_vel.0);
         }
     }
 }
@@ -111,6 +300,7 @@ type RotIntegrationComponents = (
     &'static Inertia,
     &'static InverseInertia,
     Option<&'static LockedAxes>,
+    Option<&'static TimeDilation>,
 );
 
 /// Explicitly integrates the rotations and angular velocities of bodies taking only external torque into account.
@@ -131,6 +321,7 @@ fn integrate_rot(
         _inertia,
         inv_inertia,
         locked_axes,
+        time_dilation,
     ) in &mut bodies
     {
         prev_rot.0 = *rot;
@@ -140,6 +331,7 @@ fn integrate_rot(
         }
 
         let locked_axes = locked_axes.map_or(LockedAxes::default(), |locked_axes| *locked_axes);
+        let delta_secs = sub_dt.0 * time_dilation.map_or(1.0, |dilation| dilation.0);
 
         // Apply damping and external torque
         if rb.is_dynamic() {
@@ -147,14 +339,14 @@ fn integrate_rot(
             if let Some(damping) = ang_damping {
                 // avoid triggering bevy's change detection unnecessarily
                 if ang_vel.0 != 0.0 && damping.0 != 0.0 {
-                    ang_vel.0 *= 1.0 / (1.0 + sub_dt.0 * damping.0);
+                    ang_vel.0 *= 1.0 / (1.0 + delta_secs * damping.0);
                 }
             }
 
             let effective_inv_inertia = locked_axes.apply_to_rotation(inv_inertia.0);
 
             // Apply external torque
-            let delta_ang_vel = sub_dt.0
+            let delta_ang_vel = delta_secs
                 * effective_inv_inertia
                 * (external_torque.torque() + external_force.torque());
             // avoid triggering bevy's change detection unnecessarily
@@ -163,7 +355,7 @@ fn integrate_rot(
             }
         }
         // avoid triggering bevy's change detection unnecessarily
-        let delta = locked_axes.apply_to_angular_velocity(sub_dt.0 * ang_vel.0);
+        let delta = locked_axes.apply_to_angular_velocity(delta_secs * ang_vel.0);
         if delta != 0.0 {
             *rot += Rotation::from_radians(delta);
         }
@@ -188,6 +380,7 @@ fn integrate_rot(
         inertia,
         inv_inertia,
         locked_axes,
+        time_dilation,
     ) in &mut bodies
     {
         prev_rot.0 = *rot;
@@ -197,6 +390,7 @@ fn integrate_rot(
         }
 
         let locked_axes = locked_axes.map_or(LockedAxes::default(), |locked_axes| *locked_axes);
+        let delta_secs = sub_dt.0 * time_dilation.map_or(1.0, |dilation| dilation.0);
 
         // Apply damping and external torque
         if rb.is_dynamic() {
@@ -204,7 +398,7 @@ fn integrate_rot(
             if let Some(damping) = ang_damping {
                 // avoid triggering bevy's change detection unnecessarily
                 if ang_vel.0 != Vector::ZERO && damping.0 != 0.0 {
-                    ang_vel.0 *= 1.0 / (1.0 + sub_dt.0 * damping.0);
+                    ang_vel.0 *= 1.0 / (1.0 + delta_secs * damping.0);
                 }
             }
 
@@ -212,7 +406,7 @@ fn integrate_rot(
             let effective_inv_inertia = locked_axes.apply_to_rotation(inv_inertia.rotated(&rot).0);
 
             // Apply external torque
-            let delta_ang_vel = sub_dt.0
+            let delta_ang_vel = delta_secs
                 * effective_inv_inertia
                 * ((external_torque.torque() + external_force.torque())
                     - ang_vel.0.cross(effective_inertia * ang_vel.0));
@@ -224,8 +418,8 @@ fn integrate_rot(
 
         let q = Quaternion::from_vec4(ang_vel.0.extend(0.0)) * rot.0;
         let effective_dq = locked_axes
-            .apply_to_angular_velocity(sub_dt.0 * 0.5 * q.xyz())
-            .extend(sub_dt.0 * 0.5 * q.w);
+            .apply_to_angular_velocity(delta_secs * 0.5 * q.xyz())
+            .extend(delta_secs * 0.5 * q.w);
         // avoid triggering bevy's change detection unnecessarily
         let delta = Quaternion::from_vec4(effective_dq);
         if delta != Quaternion::IDENTITY {
@@ -244,8 +438,13 @@ type ImpulseQueryComponents = (
     &'static InverseMass,
     &'static InverseInertia,
     Option<&'static LockedAxes>,
+    Option<&'static TimeDilation>,
 );
 
+/// Applies [`ExternalImpulse`]s and [`ExternalAngularImpulse`]s to the velocities of dynamic bodies.
+///
+/// The velocities of time dilated bodies are relative to their own time scale,
+/// so the velocity changes are divided by the time scale.
 fn apply_impulses(mut bodies: Query<ImpulseQueryComponents, Without<Sleeping>>) {
     for (
         rb,
@@ -257,6 +456,7 @@ fn apply_impulses(mut bodies: Query<ImpulseQueryComponents, Without<Sleeping>>)
         inv_mass,
         inv_inertia,
         locked_axes,
+        time_dilation,
     ) in &mut bodies
     {
         if !rb.is_dynamic() {
@@ -264,9 +464,11 @@ fn apply_impulses(mut bodies: Query<ImpulseQueryComponents, Without<Sleeping>>)
         }
 
         let locked_axes = locked_axes.map_or(LockedAxes::default(), |locked_axes| *locked_axes);
+        let time_scale = time_dilation.map_or(1.0, |dilation| dilation.0);
 
-        let effective_inv_mass = locked_axes.apply_to_vec(Vector::splat(inv_mass.0));
-        let effective_inv_inertia = locked_axes.apply_to_rotation(inv_inertia.rotated(rotation).0);
+        let effective_inv_mass = locked_axes.apply_to_vec(Vector::splat(inv_mass.0)) / time_scale;
+        let effective_inv_inertia =
+            locked_axes.apply_to_rotation(inv_inertia.rotated(rotation).0) * (1.0 / time_scale);
 
         // avoid triggering bevy's change detection unnecessarily
         let delta_lin_vel = impulse.impulse() * effective_inv_mass;
@@ -282,6 +484,55 @@ fn apply_impulses(mut bodies: Query<ImpulseQueryComponents, Without<Sleeping>>)
     }
 }
 
+type TimeDilationVolumeComponents = (
+    &'static TimeDilationVolume,
+    &'static Collider,
+    &'static ColliderAabb,
+    &'static Position,
+    &'static Rotation,
+    Option<&'static ColliderOffset>,
+);
+
+/// Sets the [`TimeDilation`] of bodies to the smallest scale of the [`TimeDilationVolume`]s that contain their [`Position`].
+fn update_time_dilation(
+    mut bodies: Query<(&RigidBody, &Position, &mut TimeDilation)>,
+    volumes: Query<TimeDilationVolumeComponents>,
+) {
+    for (rb, position, mut time_dilation) in &mut bodies {
+        if rb.is_static() {
+            continue;
+        }
+
+        let point = position.0;
+        let scale = volumes
+            .iter()
+            .filter(|(_, _, aabb, ..)| {
+                let mins = Vector::from(aabb.mins);
+                let maxs = Vector::from(aabb.maxs);
+                point.cmpge(mins).all() && point.cmple(maxs).all()
+            })
+            .filter(
+                |(_, collider, _, volume_position, volume_rotation, offset)| {
+                    let (volume_position, volume_rotation) = offset
+                        .map_or((volume_position.0, **volume_rotation), |offset| {
+                            offset.transform_pose(volume_position.0, **volume_rotation)
+                        });
+                    collider.get_shape().contains_point(
+                        &utils::make_isometry(volume_position, volume_rotation),
+                        &point.into(),
+                    )
+                },
+            )
+            .map(|(volume, ..)| volume.scale.clamp(MIN_TIME_SCALE, 1.0))
+            .fold(1.0, Scalar::min);
+
+        // Avoid triggering change detection unnecessarily
+        if time_dilation.0 != scale {
+            time_dilation.0 = scale;
+        }
+    }
+}
+
 type ForceComponents = (
     &'static mut ExternalForce,
     &'static mut ExternalTorque,
@@ -0,0 +1,182 @@
+//! Simulation islands, groups of bodies that are connected by contacts and joints.
+//!
+//! See [`SimulationIslands`].
+
+use crate::{prelude::*, utils::entity_sort_key};
+use bevy::{prelude::*, utils::HashMap};
+
+/// Groups of [dynamic](RigidBody::Dynamic) bodies that are connected to each other by contacts and [joints].
+///
+/// Bodies in different islands can't affect each other during a substep, so with the `parallel` feature,
+/// the [solver](SolverPlugin) solves the contacts of different islands on separate threads.
+/// Static and kinematic bodies don't connect islands, since the solver never moves them.
+///
+/// The islands are rebuilt in [`SubstepSet::SolveConstraints`] before the constraints are solved.
+///
+/// ## Ordering
+///
+/// In [`DeterministicMode`], the islands are numbered and the bodies of each island are ordered
+/// by their entities, so the same world always produces the same islands in the same order,
+/// regardless of the order in which the bodies are iterated over. Otherwise, the order is unspecified.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn print_islands(islands: Res<SimulationIslands>) {
+///     for (i, bodies) in islands.iter().enumerate() {
+///         println!("Island {i} has {} bodies", bodies.len());
+///     }
+/// }
+/// ```
+#[derive(Resource, Reflect, Clone, Debug, Default)]
+#[reflect(Resource)]
+pub struct SimulationIslands {
+    /// The bodies in each island.
+    islands: Vec<Vec<Entity>>,
+    /// The index of the island of each dynamic body.
+    body_islands: HashMap<Entity, usize>,
+}
+
+impl SimulationIslands {
+    /// Returns the number of islands.
+    pub fn len(&self) -> usize {
+        self.islands.len()
+    }
+
+    /// Returns true if there are no islands.
+    pub fn is_empty(&self) -> bool {
+        self.islands.is_empty()
+    }
+
+    /// Returns the index of the island that the given body is in, or `None` if it isn't a dynamic body.
+    pub fn island_of(&self, body: Entity) -> Option<usize> {
+        self.body_islands.get(&body).copied()
+    }
+
+    /// Returns the bodies in the island with the given index.
+    pub fn bodies(&self, island: usize) -> Option<&[Entity]> {
+        self.islands.get(island).map(|bodies| bodies.as_slice())
+    }
+
+    /// Returns an iterator over the bodies in each island.
+    pub fn iter(&self) -> impl Iterator<Item = &[Entity]> {
+        self.islands.iter().map(|bodies| bodies.as_slice())
+    }
+}
+
+/// A disjoint-set forest used for finding the connected bodies.
+pub(crate) struct UnionFind {
+    parents: Vec<usize>,
+}
+
+impl UnionFind {
+    pub(crate) fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+        }
+    }
+
+    pub(crate) fn find(&mut self, mut index: usize) -> usize {
+        while self.parents[index] != index {
+            // Path halving keeps the trees shallow
+            self.parents[index] = self.parents[self.parents[index]];
+            index = self.parents[index];
+        }
+        index
+    }
+
+    pub(crate) fn union(&mut self, index1: usize, index2: usize) {
+        let root1 = self.find(index1);
+        let root2 = self.find(index2);
+        if root1 != root2 {
+            self.parents[root1.max(root2)] = root1.min(root2);
+        }
+    }
+}
+
+/// Rebuilds the [`SimulationIslands`] from the current contacts and joints.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn update_simulation_islands(
+    bodies: Query<(Entity, &RigidBody)>,
+    colliders: Query<(&ColliderParent, Option<&Sensor>)>,
+    collisions: Res<Collisions>,
+    fixed_joints: Query<&FixedJoint>,
+    revolute_joints: Query<&RevoluteJoint>,
+    spherical_joints: Query<&SphericalJoint>,
+    prismatic_joints: Query<&PrismaticJoint>,
+    distance_joints: Query<&DistanceJoint>,
+    deterministic_mode: Res<DeterministicMode>,
+    mut islands: ResMut<SimulationIslands>,
+) {
+    let islands = &mut *islands;
+
+    // Index the dynamic bodies
+    islands.body_islands.clear();
+    let mut dynamic_bodies = bodies
+        .iter()
+        .filter(|(_, rb)| rb.is_dynamic())
+        .map(|(entity, _)| entity)
+        .collect::<Vec<_>>();
+
+    // The islands are numbered in the order of their first body, so the bodies are sorted
+    // to make the islands independent of the query order in deterministic mode
+    if deterministic_mode.0 {
+        dynamic_bodies.sort_unstable_by_key(|entity| entity_sort_key(*entity));
+    }
+    for (index, entity) in dynamic_bodies.iter().enumerate() {
+        islands.body_islands.insert(*entity, index);
+    }
+
+    let mut sets = UnionFind::new(dynamic_bodies.len());
+    let mut connect = |entity1: Entity, entity2: Entity| {
+        if let (Some(&index1), Some(&index2)) = (
+            islands.body_islands.get(&entity1),
+            islands.body_islands.get(&entity2),
+        ) {
+            sets.union(index1, index2);
+        }
+    };
+
+    // Connect the bodies of solid colliders that are in contact
+    for (entity1, entity2) in collisions
+        .get_internal()
+        .iter()
+        .filter(|(_, contacts)| contacts.during_current_substep)
+        .map(|(entities, _)| entities)
+    {
+        if let Ok([(parent1, None), (parent2, None)]) = colliders.get_many([*entity1, *entity2]) {
+            connect(parent1.get(), parent2.get());
+        }
+    }
+
+    // Connect the bodies of joints
+    for [entity1, entity2] in fixed_joints
+        .iter()
+        .map(|joint| joint.entities())
+        .chain(revolute_joints.iter().map(|joint| joint.entities()))
+        .chain(spherical_joints.iter().map(|joint| joint.entities()))
+        .chain(prismatic_joints.iter().map(|joint| joint.entities()))
+        .chain(distance_joints.iter().map(|joint| joint.entities()))
+    {
+        connect(entity1, entity2);
+    }
+
+    // Collect the bodies of each set into an island
+    islands.islands.clear();
+    let mut root_islands = vec![None; dynamic_bodies.len()];
+    for (index, entity) in dynamic_bodies.into_iter().enumerate() {
+        let root = sets.find(index);
+        let island = *root_islands[root].get_or_insert_with(|| {
+            islands.islands.push(vec![]);
+            islands.islands.len() - 1
+        });
+        islands.islands[island].push(entity);
+        islands.body_islands.insert(entity, island);
+    }
+}
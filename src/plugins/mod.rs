@@ -20,29 +20,64 @@
 //! - [`PhysicsSchedule`] and [`PhysicsStepSet`]
 //! - [`SubstepSchedule`] and [`SubstepSet`]
 
+pub mod activity_regions;
 pub mod broad_phase;
+pub mod crowd;
 #[cfg(feature = "debug-plugin")]
 pub mod debug;
+#[cfg(feature = "frame-capture")]
+pub mod frame_capture;
+pub mod fuzes;
 pub mod integrator;
+pub mod islands;
 pub mod narrow_phase;
 pub mod prepare;
+pub mod resimulation;
+pub mod rollback;
+pub mod rooms;
+#[cfg(feature = "2d")]
+pub mod screen_wrap;
 pub mod setup;
 pub mod sleeping;
+pub mod snapshot;
 pub mod solver;
+#[cfg(feature = "spatial-query")]
 pub mod spatial_query;
+pub mod sticky_contacts;
 pub mod sync;
+pub mod welds;
+pub mod world_bounds;
 
-pub use broad_phase::BroadPhasePlugin;
+pub use activity_regions::*;
+pub use broad_phase::{BroadPhaseAlgorithm, BroadPhaseConfig, BroadPhasePlugin};
+pub use crowd::*;
 #[cfg(feature = "debug-plugin")]
 pub use debug::*;
+#[cfg(feature = "frame-capture")]
+pub use frame_capture::*;
+pub use fuzes::*;
 pub use integrator::IntegratorPlugin;
+pub use islands::SimulationIslands;
 pub use narrow_phase::*;
 pub use prepare::PreparePlugin;
+pub use resimulation::Resimulation;
+pub use rollback::*;
+pub use rooms::*;
+#[cfg(feature = "2d")]
+pub use screen_wrap::*;
 pub use setup::*;
 pub use sleeping::SleepingPlugin;
-pub use solver::{solve_constraint, SolverPlugin};
+pub use snapshot::*;
+pub use solver::{solve_constraint, FrictionModel, SolverConfig, SolverPlugin};
+#[cfg(feature = "spatial-query")]
 pub use spatial_query::*;
-pub use sync::SyncPlugin;
+pub use sticky_contacts::*;
+pub use sync::{
+    InterpolateAllTransforms, ResetInterpolation, SyncPlugin, TransformInterpolation,
+    TransformInterpolationMode,
+};
+pub use welds::*;
+pub use world_bounds::*;
 
 #[allow(unused_imports)]
 use crate::prelude::*; // For doc comments
@@ -61,9 +96,22 @@ use bevy::prelude::*;
 /// - [`NarrowPhasePlugin`]: Computes contacts between entities and sends collision events.
 /// - [`SolverPlugin`]: Solves positional and angular [constraints], updates velocities and solves velocity constraints
 /// (dynamic [friction](Friction) and [restitution](Restitution)).
+/// - [`CrowdPlugin`]: Pushes overlapping [`CrowdAgent`]s apart with lightweight soft constraints.
 /// - [`SleepingPlugin`]: Controls when bodies should be deactivated and marked as [`Sleeping`] to improve performance.
-/// - [`SpatialQueryPlugin`]: Handles spatial queries like [ray casting](RayCaster) and shape casting.
+/// - `SpatialQueryPlugin`: Handles spatial queries like ray casting and shape casting (only with `spatial-query` feature enabled).
+/// - [`FuzePlugin`]: Triggers [proximity fuzes](ProximityFuze) and [contact fuzes](ContactFuze) and sends [`Detonation`] events.
+/// - [`WorldBoundsPlugin`]: Handles bodies that leave the optional [`WorldBounds`].
+/// - [`ActivityRegionsPlugin`]: Collects bodies that moved into the grid cells and regions of the optional [`ActivityRegions`].
+/// - [`WeldMergingPlugin`]: Merges bodies welded together by rigid [fixed joints](FixedJoint) into single bodies
+/// while the optional [`WeldMerging`] resource exists.
+/// - [`PhysicsRollbackPlugin`]: Restores the internal state of the engine on rollback
+/// while the optional [`PhysicsRollback`] resource exists.
+/// - [`StickyContactsPlugin`]: Converts long-lived, low-slip contacts between bodies with [`StickyContact`]
+/// into temporary soft [fixed joints](FixedJoint) marked with [`StickyWeld`].
+/// - [`PhysicsRoomsPlugin`]: Freezes bodies inside of the inactive rooms of the optional [`PhysicsRooms`] resource.
+/// - `ScreenWrapPlugin`: Wraps bodies with `ScreenWrap` around the edges of the world, like in Asteroids (only in 2D).
 /// - [`SyncPlugin`]: Keeps [`Position`] and [`Rotation`] in sync with `Transform`.
+/// - `FrameCapturePlugin`: Exports physics frames into files (only with `frame-capture` feature enabled).
 /// - `PhysicsDebugPlugin`: Renders physics objects and events like [AABBs](ColliderAabb) and [contacts](Collision)
 /// for debugging purposes (only with `debug-plugin` feature enabled).
 ///
@@ -89,9 +137,11 @@ use bevy::prelude::*;
 /// }
 /// ```
 ///
-/// Note that using `FixedUpdate` with [`PhysicsTimestep::Fixed`] can produce unexpected results due to two separate
-/// fixed timesteps. However, using `FixedUpdate` can be useful for [networking usage](crate#can-the-engine-be-used-on-servers)
-/// when you need to keep the client and server in sync.
+/// When running in `FixedUpdate`, the default timestep is [`PhysicsTimestep::Schedule`], so the simulation is advanced
+/// once per run of `FixedUpdate` by the period of `FixedTime`. This keeps physics in lockstep with game logic
+/// that also runs in `FixedUpdate`, which is useful for [networking usage](crate#can-the-engine-be-used-on-servers)
+/// when you need to keep the client and server in sync. Using `FixedUpdate` with [`PhysicsTimestep::Fixed`] instead
+/// can produce unexpected results due to two separate fixed timesteps.
 ///
 /// ## Custom plugins
 ///
@@ -184,22 +234,43 @@ impl Default for PhysicsPlugins {
 impl PluginGroup for PhysicsPlugins {
     fn build(self) -> PluginGroupBuilder {
         #[allow(unused_mut)]
-        let mut builder = PluginGroupBuilder::start::<Self>();
-
-        #[cfg(feature = "debug-plugin")]
-        {
-            builder = builder.add(PhysicsDebugPlugin::default());
-        }
-
-        builder
+        let mut builder = PluginGroupBuilder::start::<Self>()
             .add(PhysicsSetupPlugin::new(self.schedule.dyn_clone()))
             .add(PreparePlugin::new(self.schedule.dyn_clone()))
             .add(BroadPhasePlugin)
             .add(IntegratorPlugin)
             .add(NarrowPhasePlugin)
             .add(SolverPlugin)
+            .add(CrowdPlugin)
             .add(SleepingPlugin)
-            .add(SpatialQueryPlugin::new(self.schedule.dyn_clone()))
-            .add(SyncPlugin::new(self.schedule))
+            .add(FuzePlugin)
+            .add(WorldBoundsPlugin::new(self.schedule.dyn_clone()))
+            .add(ActivityRegionsPlugin::new(self.schedule.dyn_clone()))
+            .add(WeldMergingPlugin::new(self.schedule.dyn_clone()))
+            .add(PhysicsRollbackPlugin::new(self.schedule.dyn_clone()))
+            .add(StickyContactsPlugin::new(self.schedule.dyn_clone()))
+            .add(PhysicsRoomsPlugin::new(self.schedule.dyn_clone()));
+
+        #[cfg(feature = "2d")]
+        {
+            builder = builder.add(ScreenWrapPlugin::new(self.schedule.dyn_clone()));
+        }
+
+        #[cfg(feature = "spatial-query")]
+        {
+            builder = builder.add(SpatialQueryPlugin::new(self.schedule.dyn_clone()));
+        }
+
+        #[cfg(feature = "frame-capture")]
+        {
+            builder = builder.add(FrameCapturePlugin::new(self.schedule.dyn_clone()));
+        }
+
+        #[cfg(feature = "debug-plugin")]
+        {
+            builder = builder.add(PhysicsDebugPlugin::new(self.schedule.dyn_clone()));
+        }
+
+        builder.add(SyncPlugin::new(self.schedule))
     }
 }
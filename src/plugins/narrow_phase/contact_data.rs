@@ -1,6 +1,7 @@
 use crate::prelude::*;
 use bevy::prelude::*;
 use indexmap::IndexMap;
+use parry::shape::PackedFeatureId;
 
 // Collisions are stored in an `IndexMap` that uses fxhash.
 // It should have faster iteration than a `HashMap` while mostly retaining other performance characteristics.
@@ -54,7 +55,8 @@ use indexmap::IndexMap;
 ///
 /// However, the public methods only use the current frame's collisions. To access the internal data structure,
 /// you can use [`get_internal`](#method.get_internal) or [`get_internal_mut`](#method.get_internal_mut).
-#[derive(Resource, Clone, Debug, Default, PartialEq)]
+#[derive(Resource, Reflect, Clone, Debug, Default, PartialEq)]
+#[reflect_value(Resource, Debug, PartialEq)]
 pub struct Collisions(IndexMap<(Entity, Entity), Contacts, fxhash::FxBuildHasher>);
 
 impl Collisions {
@@ -226,12 +228,18 @@ pub(super) struct PreviousCollisions(Collisions);
 /// The contacts are stored in contact manifolds.
 /// Each manifold contains one or more contact points, and each contact
 /// in a given manifold shares the same contact normal.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Reflect, Clone, Debug, PartialEq)]
 pub struct Contacts {
     /// First entity in the contact.
     pub entity1: Entity,
     /// Second entity in the contact.
     pub entity2: Entity,
+    /// The entity of the [rigid body](RigidBody) that the first collider is attached to.
+    /// This is the same as `entity1` if the collider is on the body entity itself.
+    pub body_entity1: Option<Entity>,
+    /// The entity of the [rigid body](RigidBody) that the second collider is attached to.
+    /// This is the same as `entity2` if the collider is on the body entity itself.
+    pub body_entity2: Option<Entity>,
     /// A list of contact manifolds between two colliders.
     /// Each manifold contains one or more contact points, but each contact
     /// in a given manifold shares the same contact normal.
@@ -244,12 +252,65 @@ pub struct Contacts {
     pub during_previous_frame: bool,
 }
 
+impl Contacts {
+    /// Inflates the first collider by the given collision margin by moving the contact points
+    /// along the contact normals and increasing the penetration depths.
+    pub(crate) fn apply_collision_margin(&mut self, margin: Scalar) {
+        if margin == 0.0 {
+            return;
+        }
+        for manifold in self.manifolds.iter_mut() {
+            for contact in manifold.contacts.iter_mut() {
+                contact.point1 += contact.normal1 * margin;
+                contact.penetration += margin;
+            }
+        }
+    }
+
+    /// Copies the ages and Lagrange multipliers of the contacts that persist from the given previous contacts
+    /// of the same colliders and increments the ages.
+    pub(crate) fn inherit_persistent_data(&mut self, previous: &Contacts) {
+        for manifold in self.manifolds.iter_mut() {
+            // Manifolds are matched by their subshapes, because their order can change between frames
+            let Some(previous_manifold) = previous
+                .manifolds
+                .iter()
+                .find(|m| m.subshape1 == manifold.subshape1 && m.subshape2 == manifold.subshape2)
+            else {
+                continue;
+            };
+            for contact in manifold.contacts.iter_mut() {
+                if let Some(previous_contact) = previous_manifold.contacts.iter().find(|c| {
+                    c.feature_id1 == contact.feature_id1 && c.feature_id2 == contact.feature_id2
+                }) {
+                    contact.age = previous_contact.age.saturating_add(1);
+                    contact.normal_lagrange = previous_contact.normal_lagrange;
+                    contact.tangent_lagrange = previous_contact.tangent_lagrange;
+                    contact.tangent1 = previous_contact.tangent1;
+                }
+            }
+        }
+    }
+}
+
 /// A contact manifold between two colliders, containing a set of contact points.
 /// Each contact in a manifold shares the same contact normal.
-#[derive(Clone, Debug, PartialEq)]
+///
+/// Manifolds between polygonal faces are computed by clipping the faces against each other,
+/// so they can contain several points, like up to four points for two boxes in 3D.
+/// Manifolds persist across frames: they are identified by their [subshapes](#structfield.subshape1),
+/// and their contacts by their [features](ContactData::feature_id1), which keeps the [ages](ContactData::age)
+/// of persisting contacts.
+#[derive(Reflect, Clone, Debug, PartialEq)]
 pub struct ContactManifold {
     /// The contacts in this manifold.
     pub contacts: Vec<ContactData>,
+    /// The index of the subshape of the first collider involved in this manifold, like a triangle
+    /// of a triangle mesh or a shape of a compound. Zero for shapes without subshapes.
+    pub subshape1: u32,
+    /// The index of the subshape of the second collider involved in this manifold, like a triangle
+    /// of a triangle mesh or a shape of a compound. Zero for shapes without subshapes.
+    pub subshape2: u32,
     /// A contact normal shared by all contacts in this manifold,
     /// expressed in the local space of the first entity.
     pub normal1: Vector,
@@ -259,7 +320,8 @@ pub struct ContactManifold {
 }
 
 /// Data related to a contact between two bodies.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Reflect, Clone, Copy, Debug, PartialEq)]
+#[reflect_value(Debug, PartialEq)]
 pub struct ContactData {
     /// Contact point on the first entity in local coordinates.
     pub point1: Vector,
@@ -271,9 +333,58 @@ pub struct ContactData {
     pub normal2: Vector,
     /// Penetration depth.
     pub penetration: Scalar,
+    /// The feature of the first shape involved in the contact, like a vertex, an edge or a face.
+    pub feature_id1: PackedFeatureId,
+    /// The feature of the second shape involved in the contact, like a vertex, an edge or a face.
+    pub feature_id2: PackedFeatureId,
+    /// The number of substeps that the contact has persisted for.
+    ///
+    /// A contact persists while the colliders keep colliding with the same [features](#structfield.feature_id1).
+    /// The age of new contacts is zero. See also [`ContactStatistics`].
+    pub age: u32,
+    /// The Lagrange multiplier of the normal correction that was applied to the contact during the previous substep.
+    ///
+    /// It is internal solver state used as an initial guess when the contact persists.
+    /// See [`SolverConfig::warm_start_coefficient`].
+    pub(crate) normal_lagrange: Scalar,
+    /// The Lagrange multiplier of the static friction correction that was applied to the contact
+    /// during the previous substep, along `tangent1`.
+    ///
+    /// It is internal solver state used as an initial guess when the contact persists.
+    /// See [`SolverConfig::warm_start_coefficient`].
+    pub(crate) tangent_lagrange: Scalar,
+    /// The direction of the static friction correction that was applied to the contact during the previous substep,
+    /// expressed in the local space of the first entity.
+    pub(crate) tangent1: Vector,
 }
 
 impl ContactData {
+    /// Creates a new contact from local contact points and normals and a penetration depth.
+    ///
+    /// The [features](#structfield.feature_id1) of the contact are unknown, its [age](#structfield.age) is zero,
+    /// and it has no warm starting state from previous substeps.
+    pub fn new(
+        point1: Vector,
+        point2: Vector,
+        normal1: Vector,
+        normal2: Vector,
+        penetration: Scalar,
+    ) -> Self {
+        Self {
+            point1,
+            point2,
+            normal1,
+            normal2,
+            penetration,
+            feature_id1: PackedFeatureId::UNKNOWN,
+            feature_id2: PackedFeatureId::UNKNOWN,
+            age: 0,
+            normal_lagrange: 0.0,
+            tangent_lagrange: 0.0,
+            tangent1: Vector::ZERO,
+        }
+    }
+
     /// Returns the global contact point on the first entity,
     /// transforming the local point by the given entity position and rotation.
     pub fn global_point1(&self, position: &Position, rotation: &Rotation) -> Vector {
@@ -100,13 +100,13 @@ pub fn contact(
                 return None;
             }
 
-            Some(ContactData {
+            Some(ContactData::new(
                 point1,
                 point2,
                 normal1,
                 normal2,
-                penetration: -contact.dist,
-            })
+                -contact.dist,
+            ))
         } else {
             None
         }
@@ -161,6 +161,30 @@ pub fn contact_manifolds(
     rotation2: impl Into<Rotation>,
     prediction_distance: Scalar,
 ) -> Vec<ContactManifold> {
+    contact_manifolds_and_back_faces(
+        collider1,
+        position1,
+        rotation1,
+        collider2,
+        position2,
+        rotation2,
+        prediction_distance,
+    )
+    .0
+}
+
+/// Computes all [`ContactManifold`]s between two [`Collider`]s like [`contact_manifolds`],
+/// and also returns true if contacts against the back faces of [one-sided](TriMeshCollisionFlags::one_sided)
+/// triangle meshes were discarded.
+pub(crate) fn contact_manifolds_and_back_faces(
+    collider1: &Collider,
+    position1: impl Into<Position>,
+    rotation1: impl Into<Rotation>,
+    collider2: &Collider,
+    position2: impl Into<Position>,
+    rotation2: impl Into<Rotation>,
+    prediction_distance: Scalar,
+) -> (Vec<ContactManifold>, bool) {
     let isometry1 = utils::make_isometry(position1.into(), rotation1.into());
     let isometry2 = utils::make_isometry(position2.into(), rotation2.into());
     let isometry12 = isometry1.inv_mul(&isometry2);
@@ -175,7 +199,35 @@ pub fn contact_manifolds(
         &mut manifolds,
         &mut None,
     );
-    manifolds
+
+    // Replace contact normals against internal edges of triangle meshes and heightfields with face normals
+    #[cfg(feature = "3d")]
+    for manifold in manifolds.iter_mut() {
+        fix_internal_edge_normal(manifold, collider1, collider2, &isometry12);
+    }
+
+    // Discard contacts against the back faces of one-sided triangle meshes
+    #[cfg(feature = "3d")]
+    let back_faces = {
+        let manifold_count = manifolds.len();
+        manifolds.retain(|manifold| {
+            is_front_face_contact(collider1, manifold.subshape1, &manifold.local_n1)
+                && is_front_face_contact(collider2, manifold.subshape2, &manifold.local_n2)
+        });
+        manifolds.len() < manifold_count
+    };
+    // Discard contacts against the back sides of one-sided segments and polylines
+    #[cfg(feature = "2d")]
+    let back_faces = {
+        let manifold_count = manifolds.len();
+        manifolds.retain(|manifold| {
+            is_front_side_contact(collider1, manifold.subshape1, &manifold.local_n1)
+                && is_front_side_contact(collider2, manifold.subshape2, &manifold.local_n2)
+        });
+        manifolds.len() < manifold_count
+    };
+
+    let manifolds = manifolds
         .iter()
         .filter_map(|manifold| {
             let subpos1 = manifold.subshape_pos1.unwrap_or_default();
@@ -199,20 +251,118 @@ pub fn contact_manifolds(
             Some(ContactManifold {
                 normal1,
                 normal2,
+                subshape1: manifold.subshape1,
+                subshape2: manifold.subshape2,
                 contacts: manifold
                     .contacts()
                     .iter()
                     .map(|contact| ContactData {
-                        point1: subpos1.transform_point(&contact.local_p1).into(),
-                        point2: subpos2.transform_point(&contact.local_p2).into(),
-                        normal1,
-                        normal2,
-                        penetration: -contact.dist,
+                        feature_id1: contact.fid1,
+                        feature_id2: contact.fid2,
+                        ..ContactData::new(
+                            subpos1.transform_point(&contact.local_p1).into(),
+                            subpos2.transform_point(&contact.local_p2).into(),
+                            normal1,
+                            normal2,
+                            -contact.dist,
+                        )
                     })
                     .collect(),
             })
         })
-        .collect()
+        .collect();
+
+    (manifolds, back_faces)
+}
+
+/// Replaces the normal of the given manifold with the face normal of a triangle if the contacts are
+/// against [internal edges](TriMeshCollisionFlags::fix_internal_edges) of a triangle mesh or heightfield collider.
+/// The penetration depths of the contacts are recomputed along the new normal.
+#[cfg(feature = "3d")]
+fn fix_internal_edge_normal(
+    manifold: &mut parry::query::ContactManifold<(), ()>,
+    collider1: &Collider,
+    collider2: &Collider,
+    isometry12: &Isometry<Scalar>,
+) {
+    let subpos1 = manifold.subshape_pos1.unwrap_or_default();
+    let subpos2 = manifold.subshape_pos2.unwrap_or_default();
+
+    // The normals and points in the local space of the colliders
+    let normal1 = subpos1 * manifold.local_n1;
+    let normal2 = subpos2 * manifold.local_n2;
+    let points1 = manifold.points.iter().map(|c| subpos1 * c.local_p1);
+    let points2 = manifold.points.iter().map(|c| subpos2 * c.local_p2);
+
+    let new_normal1 = if let Some(face_normal) =
+        collider1.internal_edge_face_normal(manifold.subshape1, &normal1, points1)
+    {
+        face_normal
+    } else if let Some(face_normal) =
+        collider2.internal_edge_face_normal(manifold.subshape2, &normal2, points2)
+    {
+        isometry12 * -face_normal
+    } else {
+        return;
+    };
+
+    manifold.local_n1 = subpos1.inverse_transform_vector(&new_normal1);
+    manifold.local_n2 =
+        subpos2.inverse_transform_vector(&isometry12.inverse_transform_vector(&-new_normal1));
+
+    for contact in manifold.points.iter_mut() {
+        let point1 = subpos1 * contact.local_p1;
+        let point2 = isometry12 * (subpos2 * contact.local_p2);
+        contact.dist = (point2 - point1).dot(&new_normal1);
+    }
+}
+
+/// Returns false if the collider is a [one-sided](TriMeshCollisionFlags::one_sided) triangle mesh
+/// and the contact normal points away from the front face of the triangle at `triangle_index`.
+///
+/// The normal is expected to be in the local space of the collider.
+#[cfg(feature = "3d")]
+fn is_front_face_contact(
+    collider: &Collider,
+    triangle_index: u32,
+    local_normal: &parry::math::Vector<Scalar>,
+) -> bool {
+    if !collider.trimesh_collision_flags().one_sided {
+        return true;
+    }
+    let Some(trimesh) = collider.get_shape().as_trimesh() else {
+        return true;
+    };
+    trimesh
+        .triangle(triangle_index)
+        .normal()
+        .map_or(true, |normal| normal.dot(local_normal) >= 0.0)
+}
+
+/// Returns false if the collider is a [one-sided](PolylineCollisionFlags::one_sided) segment or polyline
+/// and the contact normal points away from the front side of the segment at `segment_index`.
+///
+/// The normal is expected to be in the local space of the collider.
+#[cfg(feature = "2d")]
+fn is_front_side_contact(
+    collider: &Collider,
+    segment_index: u32,
+    local_normal: &parry::math::Vector<Scalar>,
+) -> bool {
+    if !collider.polyline_collision_flags().one_sided {
+        return true;
+    }
+    let shape = collider.get_shape();
+    let segment = match shape.as_polyline() {
+        Some(polyline) => polyline.segment(segment_index),
+        None => match shape.as_segment() {
+            Some(segment) => *segment,
+            None => return true,
+        },
+    };
+    segment
+        .normal()
+        .map_or(true, |normal| normal.dot(local_normal) >= 0.0)
 }
 
 /// Information about the closest points between two [`Collider`]s.
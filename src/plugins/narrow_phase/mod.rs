@@ -9,7 +9,7 @@ use bevy::utils::HashSet;
 pub use contact_data::*;
 pub use contact_query::*;
 
-use crate::prelude::*;
+use crate::{plugins::resimulation::Resimulating, prelude::*, utils::entity_sort_key};
 #[cfg(feature = "parallel")]
 use bevy::tasks::{ComputeTaskPool, ParallelSlice};
 
@@ -23,6 +23,8 @@ use bevy::tasks::{ComputeTaskPool, ParallelSlice};
 /// - [`Collision`]
 /// - [`CollisionStarted`]
 /// - [`CollisionEnded`]
+///
+/// The lifetimes of contacts are tracked in [`ContactData::age`] and aggregated in [`ContactStatistics`].
 pub struct NarrowPhasePlugin;
 
 impl Plugin for NarrowPhasePlugin {
@@ -32,7 +34,17 @@ impl Plugin for NarrowPhasePlugin {
             .add_event::<CollisionEnded>()
             .init_resource::<NarrowPhaseConfig>()
             .init_resource::<Collisions>()
-            .register_type::<NarrowPhaseConfig>();
+            .init_resource::<ContactStatistics>()
+            .init_resource::<OneSidedPassThroughs>()
+            .register_type::<NarrowPhaseConfig>()
+            .register_type::<ContactStatistics>()
+            .register_type::<Collisions>()
+            .register_type::<Contacts>()
+            .register_type::<ContactManifold>()
+            .register_type::<ContactData>()
+            .register_type::<Collision>()
+            .register_type::<CollisionStarted>()
+            .register_type::<CollisionEnded>();
 
         let physics_schedule = app
             .get_schedule_mut(PhysicsSchedule)
@@ -55,8 +67,14 @@ impl Plugin for NarrowPhasePlugin {
                     .chain()
                     .after(PhysicsStepSet::Substeps)
                     .before(PhysicsStepSet::Sleeping),
-                // Send collision events
-                send_collision_events
+                // Send collision events and update contact statistics.
+                // The events of re-simulated steps have already been sent.
+                (
+                    send_collision_events
+                        .run_if(|resimulating: Option<Res<Resimulating>>| resimulating.is_none()),
+                    update_contact_statistics,
+                )
+                    .chain()
                     .after(PhysicsStepSet::Sleeping)
                     .before(PhysicsStepSet::SpatialQuery),
             )
@@ -68,7 +86,11 @@ impl Plugin for NarrowPhasePlugin {
             .expect("add SubstepSchedule first");
 
         substep_schedule.add_systems(
-            (reset_substep_collision_states, collect_collisions)
+            (
+                reset_substep_collision_states,
+                collect_collisions,
+                sort_collisions.run_if(|mode: Res<DeterministicMode>| mode.0),
+            )
                 .chain()
                 .in_set(SubstepSet::NarrowPhase),
         );
@@ -87,6 +109,7 @@ impl Plugin for NarrowPhasePlugin {
 
 /// A resource for configuring the [narrow phase](NarrowPhasePlugin).
 #[derive(Resource, Reflect, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Resource)]
 pub struct NarrowPhaseConfig {
     /// The maximum separation distance allowed for a collision to be accepted.
@@ -95,6 +118,11 @@ pub struct NarrowPhaseConfig {
     /// include pairs of entities that *might* be in contact after constraint solving or
     /// other positional changes.
     pub prediction_distance: Scalar,
+    /// The collision margin used for colliders without a [`CollisionMargin`] component.
+    ///
+    /// Colliders in contact are kept separated by the margin, which makes resting contacts more persistent.
+    /// Zero by default.
+    pub collision_margin: Scalar,
 }
 
 impl Default for NarrowPhaseConfig {
@@ -104,169 +132,322 @@ impl Default for NarrowPhaseConfig {
             prediction_distance: 5.0,
             #[cfg(feature = "3d")]
             prediction_distance: 0.005,
+            collision_margin: 0.0,
         }
     }
 }
 
+impl NarrowPhaseConfig {
+    /// Returns the collision margin used for a pair of colliders with the given [`CollisionMargin`]s.
+    /// Colliders without a margin use the global [`collision_margin`](#structfield.collision_margin).
+    fn pair_margin(
+        &self,
+        margin1: Option<&CollisionMargin>,
+        margin2: Option<&CollisionMargin>,
+    ) -> Scalar {
+        let margin1 = margin1.map_or(self.collision_margin, |margin| margin.0);
+        let margin2 = margin2.map_or(self.collision_margin, |margin| margin.0);
+        margin1.max(margin2).max(0.0)
+    }
+}
+
+/// The number of buckets in the [contact age histogram](ContactStatistics::age_histogram).
+pub const CONTACT_AGE_HISTOGRAM_BUCKETS: usize = 16;
+
+/// Statistics about the [ages](ContactData::age) of the contacts in [`Collisions`], updated each physics frame.
+///
+/// Contact lifetimes can be used for tuning things like sleeping thresholds empirically.
+/// For example, if most contacts only live for a few substeps, bodies are jittering or bouncing.
+#[derive(Resource, Reflect, Clone, Debug, Default, PartialEq)]
+#[reflect(Resource)]
+pub struct ContactStatistics {
+    /// The number of contact points in the current collisions.
+    pub contact_count: usize,
+    /// The mean age of the contact points in substeps.
+    pub mean_age: Scalar,
+    /// The age of the oldest contact point in substeps.
+    pub max_age: u32,
+    /// A histogram of the contact ages with exponentially growing bucket sizes.
+    ///
+    /// The first bucket contains contacts with an age of zero, and bucket `i` contains contacts with
+    /// an age in the range `2^(i-1)..2^i`. The last bucket also contains all older contacts.
+    /// See [`ContactStatistics::age_bucket`].
+    pub age_histogram: [usize; CONTACT_AGE_HISTOGRAM_BUCKETS],
+}
+
+impl ContactStatistics {
+    /// Returns the index of the [histogram](#structfield.age_histogram) bucket for the given contact age.
+    pub fn age_bucket(age: u32) -> usize {
+        ((u32::BITS - age.leading_zeros()) as usize).min(CONTACT_AGE_HISTOGRAM_BUCKETS - 1)
+    }
+}
+
 /// A [collision event](Collider#collision-events) that is sent for each contact pair during the narrow phase.
-#[derive(Event, Clone, Debug, PartialEq)]
+///
+/// The entities in the contacts are the colliding [collider](Collider) entities.
+/// The [rigid bodies](RigidBody) that they are attached to are stored in
+/// [`body_entity1`](Contacts::body_entity1) and [`body_entity2`](Contacts::body_entity2).
+#[derive(Event, Reflect, Clone, Debug, PartialEq)]
 pub struct Collision(pub Contacts);
 
 /// A [collision event](Collider#collision-events) that is sent when two entities start colliding.
-#[derive(Event, Clone, Debug, PartialEq)]
+#[derive(Event, Reflect, Clone, Debug, PartialEq)]
 pub struct CollisionStarted(pub Entity, pub Entity);
 
 /// A [collision event](Collider#collision-events) that is sent when two entities stop colliding.
-#[derive(Event, Clone, Debug, PartialEq)]
+#[derive(Event, Reflect, Clone, Debug, PartialEq)]
 pub struct CollisionEnded(pub Entity, pub Entity);
 
+/// Collider pairs that are passing through the back face of a one-sided triangle mesh in 3D
+/// or the back side of a one-sided segment or polyline in 2D.
+///
+/// Contacts are discarded for these pairs until the colliders stop touching, so that bodies that pass through
+/// a back face aren't pushed out of the front face once they are halfway through.
+#[derive(Resource, Default)]
+struct OneSidedPassThroughs(HashSet<(Entity, Entity)>);
+
+impl OneSidedPassThroughs {
+    /// Returns true if the given pair of colliders should pass through each other, given the contact manifolds
+    /// computed for them and whether contacts against back faces were discarded.
+    fn is_passing_through(
+        &self,
+        pair: &(Entity, Entity),
+        manifolds: &[ContactManifold],
+        back_faces: bool,
+    ) -> bool {
+        back_faces || (!manifolds.is_empty() && self.0.contains(pair))
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 #[allow(clippy::type_complexity)]
 fn collect_collisions(
-    bodies: Query<(
-        Option<&RigidBody>,
+    colliders: Query<(
+        Option<&ColliderParent>,
         &Position,
         Option<&AccumulatedTranslation>,
         &Rotation,
+        Option<&ColliderOffset>,
         &Collider,
         Option<&CollisionLayers>,
-        Option<&Sleeping>,
+        Option<&CollisionMargin>,
     )>,
+    bodies: Query<(&RigidBody, Option<&Sleeping>)>,
     broad_collision_pairs: Res<BroadCollisionPairs>,
     mut collisions: ResMut<Collisions>,
+    mut pass_throughs: ResMut<OneSidedPassThroughs>,
     narrow_phase_config: Res<NarrowPhaseConfig>,
 ) {
     #[cfg(feature = "parallel")]
     {
         let pool = ComputeTaskPool::get();
         // Todo: Verify if `par_splat_map` is deterministic. If not, sort the collisions.
-        let new_collisions = broad_collision_pairs
-            .0
-            .par_splat_map(pool, None, |chunks| {
-                let mut new_collisions: Vec<Contacts> = vec![];
-                for (entity1, entity2) in chunks {
-                    if let Ok([bundle1, bundle2]) = bodies.get_many([*entity1, *entity2]) {
-                        let (
-                            rb1,
-                            position1,
-                            accumulated_translation1,
-                            rotation1,
-                            collider1,
-                            layers1,
-                            sleeping1,
-                        ) = bundle1;
-                        let (
-                            rb2,
-                            position2,
-                            accumulated_translation2,
-                            rotation2,
-                            collider2,
-                            layers2,
-                            sleeping2,
-                        ) = bundle2;
-
-                        if check_collision_validity(
-                            rb1, rb2, layers1, layers2, sleeping1, sleeping2,
-                        ) {
-                            let position1 = position1.0
-                                + accumulated_translation1.copied().unwrap_or_default().0;
-                            let position2 = position2.0
-                                + accumulated_translation2.copied().unwrap_or_default().0;
-
-                            let during_previous_frame = collisions
-                                .get_internal()
-                                .get(&(*entity1, *entity2))
-                                .map_or(false, |c| c.during_previous_frame);
-
-                            let contacts = Contacts {
-                                entity1: *entity1,
-                                entity2: *entity2,
-                                during_current_frame: true,
-                                during_current_substep: true,
-                                during_previous_frame,
-                                manifolds: contact_query::contact_manifolds(
-                                    collider1,
-                                    position1,
-                                    *rotation1,
-                                    collider2,
-                                    position2,
-                                    *rotation2,
-                                    narrow_phase_config.prediction_distance,
-                                ),
-                            };
-
-                            if !contacts.manifolds.is_empty() {
-                                new_collisions.push(contacts);
+        let results = broad_collision_pairs.0.par_splat_map(pool, None, |chunks| {
+            let mut new_collisions: Vec<Contacts> = vec![];
+            let mut new_pass_throughs: Vec<(Entity, Entity)> = vec![];
+            for (entity1, entity2) in chunks {
+                if let Ok([bundle1, bundle2]) = colliders.get_many([*entity1, *entity2]) {
+                    let (
+                        parent1,
+                        position1,
+                        accumulated_translation1,
+                        rotation1,
+                        offset1,
+                        collider1,
+                        layers1,
+                        margin1,
+                    ) = bundle1;
+                    let (
+                        parent2,
+                        position2,
+                        accumulated_translation2,
+                        rotation2,
+                        offset2,
+                        collider2,
+                        layers2,
+                        margin2,
+                    ) = bundle2;
+
+                    // No collisions between colliders attached to the same body
+                    if parent1.is_some() && parent1 == parent2 {
+                        continue;
+                    }
+
+                    let (rb1, sleeping1) = get_body(&bodies, parent1);
+                    let (rb2, sleeping2) = get_body(&bodies, parent2);
+
+                    if check_collision_validity(rb1, rb2, layers1, layers2, sleeping1, sleeping2) {
+                        let position1 =
+                            position1.0 + accumulated_translation1.copied().unwrap_or_default().0;
+                        let position2 =
+                            position2.0 + accumulated_translation2.copied().unwrap_or_default().0;
+                        let (position1, rotation1) = offset1
+                            .map_or((position1, *rotation1), |offset| {
+                                offset.transform_pose(position1, *rotation1)
+                            });
+                        let (position2, rotation2) = offset2
+                            .map_or((position2, *rotation2), |offset| {
+                                offset.transform_pose(position2, *rotation2)
+                            });
+
+                        let during_previous_frame = collisions
+                            .get_internal()
+                            .get(&(*entity1, *entity2))
+                            .map_or(false, |c| c.during_previous_frame);
+
+                        let margin = narrow_phase_config.pair_margin(margin1, margin2);
+                        let (manifolds, back_faces) =
+                            contact_query::contact_manifolds_and_back_faces(
+                                collider1,
+                                position1,
+                                rotation1,
+                                collider2,
+                                position2,
+                                rotation2,
+                                narrow_phase_config.prediction_distance + margin,
+                            );
+                        let pair = (*entity1, *entity2);
+                        if pass_throughs.is_passing_through(&pair, &manifolds, back_faces) {
+                            new_pass_throughs.push(pair);
+                            continue;
+                        }
+
+                        let mut contacts = Contacts {
+                            entity1: *entity1,
+                            entity2: *entity2,
+                            body_entity1: parent1.map(|p| p.get()),
+                            body_entity2: parent2.map(|p| p.get()),
+                            during_current_frame: true,
+                            during_current_substep: true,
+                            during_previous_frame,
+                            manifolds,
+                        };
+                        contacts.apply_collision_margin(margin);
+
+                        if !contacts.manifolds.is_empty() {
+                            if let Some(previous) =
+                                collisions.get_internal().get(&(*entity1, *entity2))
+                            {
+                                contacts.inherit_persistent_data(previous);
                             }
+                            new_collisions.push(contacts);
                         }
                     }
                 }
-                new_collisions
-            })
-            .into_iter()
-            .flatten();
-        collisions.extend(new_collisions);
+            }
+            (new_collisions, new_pass_throughs)
+        });
+        pass_throughs.0.clear();
+        for (new_collisions, new_pass_throughs) in results {
+            collisions.extend(new_collisions);
+            pass_throughs.0.extend(new_pass_throughs);
+        }
     }
     #[cfg(not(feature = "parallel"))]
     {
+        let mut new_pass_throughs = HashSet::default();
         for (entity1, entity2) in broad_collision_pairs.0.iter() {
-            if let Ok([bundle1, bundle2]) = bodies.get_many([*entity1, *entity2]) {
+            if let Ok([bundle1, bundle2]) = colliders.get_many([*entity1, *entity2]) {
                 let (
-                    rb1,
+                    parent1,
                     position1,
                     accumulated_translation1,
                     rotation1,
+                    offset1,
                     collider1,
                     layers1,
-                    sleeping1,
+                    margin1,
                 ) = bundle1;
                 let (
-                    rb2,
+                    parent2,
                     position2,
                     accumulated_translation2,
                     rotation2,
+                    offset2,
                     collider2,
                     layers2,
-                    sleeping2,
+                    margin2,
                 ) = bundle2;
 
+                // No collisions between colliders attached to the same body
+                if parent1.is_some() && parent1 == parent2 {
+                    continue;
+                }
+
+                let (rb1, sleeping1) = get_body(&bodies, parent1);
+                let (rb2, sleeping2) = get_body(&bodies, parent2);
+
                 if check_collision_validity(rb1, rb2, layers1, layers2, sleeping1, sleeping2) {
                     let position1 =
                         position1.0 + accumulated_translation1.copied().unwrap_or_default().0;
                     let position2 =
                         position2.0 + accumulated_translation2.copied().unwrap_or_default().0;
+                    let (position1, rotation1) = offset1
+                        .map_or((position1, *rotation1), |offset| {
+                            offset.transform_pose(position1, *rotation1)
+                        });
+                    let (position2, rotation2) = offset2
+                        .map_or((position2, *rotation2), |offset| {
+                            offset.transform_pose(position2, *rotation2)
+                        });
 
                     let during_previous_frame = collisions
                         .get_internal()
                         .get(&(*entity1, *entity2))
                         .map_or(false, |c| c.during_previous_frame);
 
-                    let contacts = Contacts {
+                    let margin = narrow_phase_config.pair_margin(margin1, margin2);
+                    let (manifolds, back_faces) = contact_query::contact_manifolds_and_back_faces(
+                        collider1,
+                        position1,
+                        rotation1,
+                        collider2,
+                        position2,
+                        rotation2,
+                        narrow_phase_config.prediction_distance + margin,
+                    );
+                    let pair = (*entity1, *entity2);
+                    if pass_throughs.is_passing_through(&pair, &manifolds, back_faces) {
+                        new_pass_throughs.insert(pair);
+                        continue;
+                    }
+
+                    let mut contacts = Contacts {
                         entity1: *entity1,
                         entity2: *entity2,
+                        body_entity1: parent1.map(|p| p.get()),
+                        body_entity2: parent2.map(|p| p.get()),
                         during_current_frame: true,
                         during_current_substep: true,
                         during_previous_frame,
-                        manifolds: contact_query::contact_manifolds(
-                            collider1,
-                            position1,
-                            *rotation1,
-                            collider2,
-                            position2,
-                            *rotation2,
-                            narrow_phase_config.prediction_distance,
-                        ),
+                        manifolds,
                     };
+                    contacts.apply_collision_margin(margin);
 
                     if !contacts.manifolds.is_empty() {
+                        if let Some(previous) = collisions.get_internal().get(&(*entity1, *entity2))
+                        {
+                            contacts.inherit_persistent_data(previous);
+                        }
                         collisions.insert_collision_pair(contacts);
                     }
                 }
             }
         }
+        pass_throughs.0 = new_pass_throughs;
     }
 }
 
+/// Gets the [`RigidBody`] and [`Sleeping`] components of the body that a collider is attached to.
+fn get_body<'a>(
+    bodies: &'a Query<(&RigidBody, Option<&Sleeping>)>,
+    parent: Option<&ColliderParent>,
+) -> (Option<&'a RigidBody>, Option<&'a Sleeping>) {
+    parent
+        .and_then(|parent| bodies.get(parent.get()).ok())
+        .map_or((None, None), |(rb, sleeping)| (Some(rb), sleeping))
+}
+
 fn check_collision_validity(
     rb1: Option<&RigidBody>,
     rb2: Option<&RigidBody>,
@@ -294,6 +475,17 @@ fn check_collision_validity(
     true
 }
 
+/// Sorts the [`Collisions`] by the entities of the colliders in [`DeterministicMode`],
+/// so that the contacts are solved in the same order regardless of when the collisions started.
+fn sort_collisions(mut collisions: ResMut<Collisions>) {
+    collisions
+        .get_internal_mut()
+        .sort_by(|(a1, a2), _, (b1, b2), _| {
+            (entity_sort_key(*a1), entity_sort_key(*a2))
+                .cmp(&(entity_sort_key(*b1), entity_sort_key(*b2)))
+        });
+}
+
 fn reset_substep_collision_states(mut collisions: ResMut<Collisions>) {
     for contacts in collisions.get_internal_mut().values_mut() {
         contacts.during_current_substep = false;
@@ -370,16 +562,52 @@ fn send_collision_events(
     collisions.retain(|contacts| !ended_collisions.contains(&(contacts.entity1, contacts.entity2)));
 }
 
-fn wake_up_on_collision_ended(
+/// Updates [`ContactStatistics`] based on the current collisions.
+fn update_contact_statistics(
+    collisions: Res<Collisions>,
+    mut statistics: ResMut<ContactStatistics>,
+) {
+    let mut new_statistics = ContactStatistics::default();
+    let mut total_age: u64 = 0;
+
+    for contact in collisions
+        .iter()
+        .flat_map(|contacts| contacts.manifolds.iter())
+        .flat_map(|manifold| manifold.contacts.iter())
+    {
+        new_statistics.contact_count += 1;
+        new_statistics.max_age = new_statistics.max_age.max(contact.age);
+        new_statistics.age_histogram[ContactStatistics::age_bucket(contact.age)] += 1;
+        total_age += contact.age as u64;
+    }
+
+    if new_statistics.contact_count > 0 {
+        new_statistics.mean_age = total_age as Scalar / new_statistics.contact_count as Scalar;
+    }
+
+    *statistics = new_statistics;
+}
+
+pub(crate) fn wake_up_on_collision_ended(
     mut commands: Commands,
     mut colliding: Query<&CollidingEntities, (Changed<Position>, Without<Sleeping>)>,
     mut collision_ended_ev_reader: EventReader<CollisionEnded>,
-    mut sleeping: Query<(Entity, &CollidingEntities, &mut TimeSleeping), With<Sleeping>>,
+    mut sleeping: Query<
+        (Entity, &CollidingEntities, &mut TimeSleeping),
+        (With<Sleeping>, Without<FrozenInRoom>),
+    >,
 ) {
-    // Wake up bodies when a body they're colliding with moves
+    // Wake up bodies when a body they're colliding with moves.
+    // The iteration order of `CollidingEntities` can differ between runs, so the body to wake up
+    // is picked by entity order to keep the simulation deterministic.
     for colliding_entities1 in colliding.iter_mut() {
-        let mut query = sleeping.iter_many_mut(colliding_entities1.iter());
-        if let Some((entity2, _, mut time_sleeping)) = query.fetch_next() {
+        let entity2 = colliding_entities1
+            .iter()
+            .filter(|entity| sleeping.contains(**entity))
+            .min_by_key(|entity| entity_sort_key(**entity));
+        if let Some(Ok((entity2, _, mut time_sleeping))) =
+            entity2.map(|entity| sleeping.get_mut(*entity))
+        {
             commands.entity(entity2).remove::<Sleeping>();
             time_sleeping.0 = 0.0;
         }
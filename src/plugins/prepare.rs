@@ -6,18 +6,38 @@
 #![allow(clippy::type_complexity)]
 
 use crate::prelude::*;
-use bevy::prelude::*;
+#[cfg(all(feature = "3d", feature = "bone-collider"))]
+use bevy::render::mesh::skinning::SkinnedMesh;
+#[cfg(all(feature = "3d", feature = "async-collider"))]
+use bevy::scene::{SceneInstance, SceneSpawner};
+#[cfg(feature = "physics-material")]
+use bevy::{asset::HandleId, utils::HashSet};
+use bevy::{prelude::*, utils::HashMap};
 
 /// Runs systems at the start of each physics frame; initializes [rigid bodies](RigidBody)
 /// and [colliders](Collider) and updates components.
 ///
+/// - Computes the colliders of `AsyncCollider` entities in the background (3D with the `collider-from-mesh` feature)
+/// - Creates colliders for the meshes of scenes with an `AsyncSceneCollider` (3D with the `async-collider` feature)
+/// - Replaces the shapes of colliders with the shapes computed in the background for their [`ColliderShapeUpdates`]
+/// - Moves bodies with a `BoneCollider` to the joints of skinned meshes, applies their `HitReaction` and switches them between animation and physics (3D with the `bone-collider` feature)
 /// - Adds missing rigid body components for entities with a [`RigidBody`] component
 /// - Adds missing collider components for entities with a [`Collider`] component
+/// - Scales [colliders](Collider) based on the scale of their `GlobalTransform`
 /// - Adds missing mass properties for entities with a [`RigidBody`] or [`Collider`] component
-/// - Updates mass properties and adds [`ColliderMassProperties`] on top of the existing mass properties
+/// - Attaches colliders to the closest [rigid body](RigidBody) in the hierarchy and updates [`ColliderTransform`]
+/// - Applies [`PhysicsMaterial`] assets to entities with a `Handle<PhysicsMaterial>` (with the `physics-material` feature)
+/// - Inserts the colliders of entities with a `Handle<BakedCollider>` once the asset has been loaded (with the `baked-colliders` feature)
+/// - Updates mass properties from the [`ColliderDensity`] of colliders and adds [`ColliderMassProperties`] on top of the existing mass properties
+/// - Subtracts the mass properties of colliders from bodies when the colliders are removed, despawned or attached to another body
 /// - Clamps restitution coefficients between 0 and 1
+/// - Matches the velocity of trailers with the towing vehicle when a `TrailerHitch` is added (3D)
 ///
 /// The systems run in [`PhysicsSet::Prepare`].
+///
+/// The positions of colliders attached to the children of rigid bodies are updated
+/// before the [broad phase](PhysicsStepSet::BroadPhase) and before the [narrow phase](SubstepSet::NarrowPhase)
+/// of each substep.
 pub struct PreparePlugin {
     schedule: Box<dyn ScheduleLabel>,
 }
@@ -41,6 +61,9 @@ impl Default for PreparePlugin {
 
 impl Plugin for PreparePlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<ColliderMassContributions>()
+            .register_type::<ColliderShapeUpdates>();
+
         app.add_systems(
             self.schedule.dyn_clone(),
             (
@@ -55,7 +78,14 @@ impl Plugin for PreparePlugin {
                 init_transforms,
                 init_rigid_bodies,
                 init_mass_properties,
+                update_collider_scale,
                 init_colliders,
+                update_collider_parents,
+                apply_deferred,
+                update_collider_transforms,
+                update_collider_density,
+                remove_collider_mass_properties,
+                update_child_collider_mass_properties,
                 update_mass_properties,
                 clamp_restitution,
                 // all the components we added above must exist before we can simulate the bodies
@@ -64,6 +94,86 @@ impl Plugin for PreparePlugin {
                 .chain()
                 .in_set(PhysicsSet::Prepare),
         );
+
+        app.add_systems(
+            self.schedule.dyn_clone(),
+            apply_collider_shape_updates.before(PhysicsSet::Prepare),
+        );
+
+        #[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
+        app.add_systems(
+            self.schedule.dyn_clone(),
+            (init_async_colliders, update_async_colliders)
+                .chain()
+                .before(PhysicsSet::Prepare),
+        );
+
+        #[cfg(all(feature = "3d", feature = "async-collider"))]
+        app.add_systems(
+            self.schedule.dyn_clone(),
+            init_async_scene_colliders.before(init_async_colliders),
+        );
+
+        #[cfg(all(feature = "3d", feature = "bone-collider"))]
+        app.add_systems(
+            self.schedule.dyn_clone(),
+            // The bodies must be initialized first, so that new bone colliders follow their joints immediately
+            update_bone_colliders
+                .after(PhysicsSet::Prepare)
+                .before(PhysicsSet::StepSimulation),
+        );
+
+        #[cfg(feature = "3d")]
+        app.add_systems(
+            self.schedule.dyn_clone(),
+            match_hitch_velocities
+                .after(clamp_restitution)
+                .in_set(PhysicsSet::Prepare),
+        );
+
+        let physics_schedule = app
+            .get_schedule_mut(PhysicsSchedule)
+            .expect("add PhysicsSchedule first");
+
+        physics_schedule
+            .add_systems(update_child_collider_position.before(PhysicsStepSet::BroadPhase));
+
+        let substep_schedule = app
+            .get_schedule_mut(SubstepSchedule)
+            .expect("add SubstepSchedule first");
+
+        substep_schedule.add_systems(
+            update_child_collider_position
+                .after(SubstepSet::Integrate)
+                .before(SubstepSet::NarrowPhase),
+        );
+    }
+
+    #[cfg(any(feature = "physics-material", feature = "baked-colliders"))]
+    fn finish(&self, app: &mut App) {
+        // Physics materials and baked colliders are assets, so they require the `AssetPlugin`
+        if !app.world.contains_resource::<AssetServer>() {
+            return;
+        }
+
+        #[cfg(feature = "baked-colliders")]
+        app.add_asset::<BakedCollider>()
+            .init_asset_loader::<BakedColliderLoader>()
+            .add_systems(
+                self.schedule.dyn_clone(),
+                init_baked_colliders.before(PhysicsSet::Prepare),
+            );
+
+        #[cfg(feature = "physics-material")]
+        app.add_asset::<PhysicsMaterial>()
+            .register_type::<PhysicsMaterial>()
+            .register_type::<Handle<PhysicsMaterial>>()
+            .add_systems(
+                self.schedule.dyn_clone(),
+                update_physics_materials
+                    .after(update_collider_transforms)
+                    .before(update_collider_density),
+            );
     }
 }
 
@@ -73,6 +183,326 @@ fn any_new_physics_entities(query: Query<(), Or<(Added<RigidBody>, Added<Collide
     !query.is_empty()
 }
 
+/// Moves bodies with a [`BoneCollider`] to the `GlobalTransform` of the followed joint of a `SkinnedMesh`.
+///
+/// If the body has a [`HitReaction`], the reaction is advanced and its offset is added to the pose
+/// of the body, and to the `Transform` of the joint if [`HitReaction::blend_into_joint`] is true.
+///
+/// Bodies in [`BoneColliderMode::Simulated`] are dynamic, and their pose is written to the joint instead.
+/// The velocity is handed off when the [`BoneColliderMode`] changes.
+#[cfg(all(feature = "3d", feature = "bone-collider"))]
+#[allow(clippy::type_complexity)]
+fn update_bone_colliders(
+    mut bone_colliders: Query<(
+        &mut BoneCollider,
+        &mut RigidBody,
+        &mut Position,
+        &mut Rotation,
+        Option<&mut LinearVelocity>,
+        Option<&mut AngularVelocity>,
+        Option<&mut HitReaction>,
+    )>,
+    skinned_meshes: Query<&SkinnedMesh>,
+    global_transforms: Query<&GlobalTransform>,
+    mut joint_transforms: Query<(&mut Transform, Option<&Parent>)>,
+    time: Res<Time>,
+    physics_time: Res<PhysicsTime>,
+) {
+    // The animation advances in real time, but blending and hit reactions respect the physics time
+    let animation_delta_seconds = time.delta_seconds_f64().adjust_precision();
+    let delta_seconds = physics_time.scale_delta(animation_delta_seconds);
+
+    for (
+        mut bone_collider,
+        mut rb,
+        mut position,
+        mut rotation,
+        mut lin_vel,
+        mut ang_vel,
+        hit_reaction,
+    ) in &mut bone_colliders
+    {
+        let Some(joint) = skinned_meshes
+            .get(bone_collider.skinned_mesh)
+            .ok()
+            .and_then(|skinned_mesh| skinned_mesh.joints.get(bone_collider.joint).copied())
+        else {
+            continue;
+        };
+
+        // Remove the hit reaction offset that was blended into the joint during the previous frame,
+        // unless the animation has replaced the transform since
+        if let Some((offset_transform, animated_transform)) = bone_collider.joint_offset.take() {
+            if let Ok((mut transform, _)) = joint_transforms.get_mut(joint) {
+                if *transform == offset_transform {
+                    *transform = animated_transform;
+                }
+            }
+        }
+
+        // The animated pose is computed from the local transform of the joint, because the global transform
+        // contains the simulated pose when the joint follows the body
+        let parent_transform = joint_transforms
+            .get(joint)
+            .ok()
+            .and_then(|(_, parent)| parent)
+            .and_then(|parent| global_transforms.get(parent.get()).ok())
+            .copied();
+        let joint_transform = match (joint_transforms.get(joint), parent_transform) {
+            (Ok((transform, _)), Some(parent_transform)) => {
+                parent_transform.mul_transform(*transform)
+            }
+            (Ok((transform, _)), None) => GlobalTransform::from(*transform),
+            (Err(_), _) => {
+                let Ok(joint_transform) = global_transforms.get(joint) else {
+                    continue;
+                };
+                *joint_transform
+            }
+        };
+
+        let (_, joint_rotation, joint_translation) =
+            joint_transform.to_scale_rotation_translation();
+        let joint_position = joint_translation.adjust_precision();
+        let joint_rotation = joint_rotation.adjust_precision();
+
+        // Only animated poses are used for the velocity of the joint, because the joint follows the body when simulated
+        if bone_collider.mode == BoneColliderMode::Animated && !bone_collider.is_blending() {
+            if let Some((previous_position, previous_rotation)) = bone_collider.previous_joint_pose
+            {
+                if animation_delta_seconds > 0.0 {
+                    let mut delta_rotation = joint_rotation * previous_rotation.inverse();
+                    if delta_rotation.w < 0.0 {
+                        delta_rotation = -delta_rotation;
+                    }
+                    bone_collider.joint_velocity = (
+                        (joint_position - previous_position) / animation_delta_seconds,
+                        delta_rotation.to_scaled_axis() / animation_delta_seconds,
+                    );
+                }
+            }
+            bone_collider.previous_joint_pose = Some((joint_position, joint_rotation));
+        } else {
+            bone_collider.previous_joint_pose = None;
+        }
+
+        let (target_position, target_rotation) = match bone_collider.mode {
+            BoneColliderMode::Simulated => {
+                // Hand off the velocity of the animation to physics
+                if !rb.is_dynamic() {
+                    *rb = RigidBody::Dynamic;
+                    bone_collider.blend = None;
+                    if let Some(lin_vel) = lin_vel.as_mut() {
+                        lin_vel.0 = bone_collider.joint_linear_velocity();
+                    }
+                    if let Some(ang_vel) = ang_vel.as_mut() {
+                        ang_vel.0 = bone_collider.joint_angular_velocity();
+                    }
+                }
+                (position.0, rotation.0)
+            }
+            BoneColliderMode::Animated => {
+                // Hand off the velocity of physics to the animation by blending
+                if rb.is_dynamic() {
+                    *rb = RigidBody::Kinematic;
+                    bone_collider.blend = Some(BoneColliderBlend {
+                        position: position.0,
+                        rotation: rotation.0,
+                        linear_velocity: lin_vel.as_ref().map_or(Vector::ZERO, |v| v.0),
+                        angular_velocity: ang_vel.as_ref().map_or(Vector::ZERO, |v| v.0),
+                        elapsed: 0.0,
+                    });
+                    if let Some(lin_vel) = lin_vel.as_mut() {
+                        lin_vel.0 = Vector::ZERO;
+                    }
+                    if let Some(ang_vel) = ang_vel.as_mut() {
+                        ang_vel.0 = Vector::ZERO;
+                    }
+                }
+
+                let blend_duration = bone_collider.blend_duration;
+                let blended_pose = bone_collider.blend.as_mut().and_then(|blend| {
+                    blend.advance(
+                        joint_position,
+                        joint_rotation,
+                        blend_duration,
+                        delta_seconds,
+                    )
+                });
+                if blended_pose.is_none() {
+                    bone_collider.blend = None;
+                }
+                blended_pose.unwrap_or((joint_position, joint_rotation))
+            }
+        };
+
+        position.0 = target_position;
+        rotation.0 = target_rotation;
+
+        let mut joint_offset = None;
+        if let Some(mut hit_reaction) = hit_reaction {
+            if bone_collider.mode == BoneColliderMode::Animated {
+                hit_reaction.advance(delta_seconds);
+                let rotation_offset = hit_reaction.rotation_offset();
+                position.0 += hit_reaction.offset;
+                rotation.0 = rotation_offset * rotation.0;
+
+                if hit_reaction.blend_into_joint {
+                    joint_offset = Some((hit_reaction.offset, rotation_offset));
+                }
+            }
+        }
+
+        let Ok((mut transform, _)) = joint_transforms.get_mut(joint) else {
+            continue;
+        };
+        let parent_rotation = parent_transform.map_or(Quat::IDENTITY, |parent_transform| {
+            parent_transform.compute_transform().rotation
+        });
+
+        if bone_collider.mode == BoneColliderMode::Simulated || bone_collider.is_blending() {
+            // The joint follows the body, so the pose is transformed into the space of the parent of the joint
+            let translation = position.0.as_f32();
+            transform.translation = parent_transform.map_or(translation, |parent_transform| {
+                parent_transform
+                    .affine()
+                    .inverse()
+                    .transform_point3(translation)
+            });
+            transform.rotation = parent_rotation.inverse() * rotation.0.as_f32();
+        } else if let Some((offset, rotation_offset)) = joint_offset {
+            // The offsets are in world space, so they are transformed into the space of the parent of the joint
+            let animated_transform = *transform;
+            transform.translation += parent_rotation.inverse() * offset.as_f32();
+            transform.rotation = parent_rotation.inverse()
+                * rotation_offset.as_f32()
+                * parent_rotation
+                * transform.rotation;
+            bone_collider.joint_offset = Some((*transform, animated_transform));
+        }
+    }
+}
+
+/// Creates colliders for the meshes of scenes with an [`AsyncSceneCollider`] once the scenes are ready.
+#[cfg(all(feature = "3d", feature = "async-collider"))]
+#[allow(clippy::too_many_arguments)]
+fn init_async_scene_colliders(
+    mut commands: Commands,
+    meshes: Option<Res<Assets<Mesh>>>,
+    scene_spawner: Option<Res<SceneSpawner>>,
+    async_scenes: Query<(Entity, &SceneInstance, &AsyncSceneCollider)>,
+    children: Query<&Children>,
+    mesh_handles: Query<&Handle<Mesh>>,
+    names: Query<&Name>,
+    parents: Query<&Parent>,
+) {
+    let (Some(meshes), Some(scene_spawner)) = (meshes, scene_spawner) else {
+        return;
+    };
+
+    for (scene_entity, scene_instance, async_scene_collider) in &async_scenes {
+        if !scene_spawner.instance_is_ready(**scene_instance) {
+            continue;
+        }
+
+        for entity in children.iter_descendants(scene_entity) {
+            let Ok(handle) = mesh_handles.get(entity) else {
+                continue;
+            };
+
+            // Use the name of the mesh entity or its parent, like the node of a glTF mesh primitive
+            let named_shape = names
+                .get(entity)
+                .ok()
+                .and_then(|name| async_scene_collider.named_shapes.get(name.as_str()))
+                .or_else(|| {
+                    let parent = parents.get(entity).ok()?;
+                    let name = names.get(parent.get()).ok()?;
+                    async_scene_collider.named_shapes.get(name.as_str())
+                });
+            let shape = named_shape.map_or(async_scene_collider.default_shape, |shape| *shape);
+
+            let collider = match shape {
+                Some(ComputedCollider::TriMesh) => meshes
+                    .get(handle)
+                    .and_then(Collider::trimesh_from_bevy_mesh),
+                Some(ComputedCollider::ConvexHull) => meshes
+                    .get(handle)
+                    .and_then(Collider::convex_hull_from_bevy_mesh),
+                Some(ComputedCollider::ConvexDecomposition) => {
+                    commands
+                        .entity(entity)
+                        .insert(AsyncCollider::new(handle.clone()));
+                    None
+                }
+                None => None,
+            };
+
+            if let Some(collider) = collider {
+                commands.entity(entity).insert(collider);
+            }
+        }
+
+        commands.entity(scene_entity).remove::<AsyncSceneCollider>();
+    }
+}
+
+/// Inserts the placeholders of [async colliders](AsyncCollider) and starts computing
+/// the convex decompositions of their meshes once the meshes have been loaded.
+#[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
+fn init_async_colliders(
+    mut commands: Commands,
+    meshes: Option<Res<Assets<Mesh>>>,
+    async_colliders: Query<(Entity, &AsyncCollider, Option<&Collider>), Without<AsyncColliderTask>>,
+) {
+    for (entity, async_collider, collider) in &async_colliders {
+        if let (None, Some(placeholder)) = (collider, &async_collider.placeholder) {
+            commands.entity(entity).insert(placeholder.clone());
+        }
+
+        let Some(mesh) = meshes
+            .as_ref()
+            .and_then(|meshes| meshes.get(&async_collider.mesh))
+        else {
+            continue;
+        };
+
+        if let Some(task) = AsyncColliderTask::new(mesh) {
+            commands.entity(entity).insert(task);
+        } else {
+            commands.entity(entity).remove::<AsyncCollider>();
+        }
+    }
+}
+
+/// Inserts the colliders of [async colliders](AsyncCollider) whose convex decompositions have been computed.
+#[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
+fn update_async_colliders(
+    mut commands: Commands,
+    mut tasks: Query<(Entity, &mut AsyncColliderTask)>,
+) {
+    use futures_lite::future;
+
+    for (entity, mut task) in &mut tasks {
+        if let Some(collider) = future::block_on(future::poll_once(&mut task.0)) {
+            commands
+                .entity(entity)
+                .insert(collider)
+                .remove::<(AsyncCollider, AsyncColliderTask)>();
+        }
+    }
+}
+
+/// Replaces the shapes of colliders with the newest shapes computed for their [`ColliderShapeUpdates`].
+fn apply_collider_shape_updates(mut colliders: Query<(&mut Collider, &mut ColliderShapeUpdates)>) {
+    for (mut collider, mut shape_updates) in &mut colliders {
+        // Avoid triggering change detection for the colliders that aren't updated
+        if let Some(new_collider) = shape_updates.bypass_change_detection().take_finished() {
+            collider.replace_shape(new_collider.get_unscaled_shape().clone());
+        }
+    }
+}
+
 /// Initializes [`Transform`] based on [`Position`] and [`Rotation`] or vice versa.
 fn init_transforms(
     mut commands: Commands,
@@ -173,6 +603,9 @@ fn init_transforms(
 }
 
 /// Initializes missing components for [rigid bodies](RigidBody).
+///
+/// Missing [`Restitution`] and [`Friction`] components are initialized from
+/// [`DefaultRestitution`] and [`DefaultFriction`].
 fn init_rigid_bodies(
     mut commands: Commands,
     mut bodies: Query<
@@ -187,9 +620,12 @@ fn init_rigid_bodies(
             Option<&Restitution>,
             Option<&Friction>,
             Option<&TimeSleeping>,
+            Option<&TimeDilation>,
         ),
         Added<RigidBody>,
     >,
+    default_restitution: Res<DefaultRestitution>,
+    default_friction: Res<DefaultFriction>,
 ) {
     for (
         entity,
@@ -202,6 +638,7 @@ fn init_rigid_bodies(
         restitution,
         friction,
         time_sleeping,
+        time_dilation,
     ) in &mut bodies
     {
         commands.entity(entity).insert((
@@ -214,13 +651,80 @@ fn init_rigid_bodies(
             *torque.unwrap_or(&ExternalTorque::default()),
             *impulse.unwrap_or(&ExternalImpulse::default()),
             *angular_impulse.unwrap_or(&ExternalAngularImpulse::default()),
-            *restitution.unwrap_or(&Restitution::default()),
-            *friction.unwrap_or(&Friction::default()),
+            *restitution.unwrap_or(&default_restitution.0),
+            *friction.unwrap_or(&default_friction.0),
             *time_sleeping.unwrap_or(&TimeSleeping::default()),
+            *time_dilation.unwrap_or(&TimeDilation::default()),
         ));
     }
 }
 
+/// Inserts the colliders of entities with a `Handle<BakedCollider>` once the [`BakedCollider`] has been loaded.
+#[cfg(feature = "baked-colliders")]
+fn init_baked_colliders(
+    mut commands: Commands,
+    baked_colliders: Res<Assets<BakedCollider>>,
+    entities: Query<(Entity, &Handle<BakedCollider>), Without<Collider>>,
+) {
+    for (entity, handle) in &entities {
+        if let Some(baked) = baked_colliders.get(handle) {
+            commands.entity(entity).insert(baked.0.clone());
+        }
+    }
+}
+
+/// Applies the [`PhysicsMaterial`] of entities when their `Handle<PhysicsMaterial>` changes
+/// or when the material asset is created or modified.
+#[cfg(feature = "physics-material")]
+fn update_physics_materials(
+    mut commands: Commands,
+    materials: Res<Assets<PhysicsMaterial>>,
+    mut material_events: EventReader<AssetEvent<PhysicsMaterial>>,
+    mut entities: Query<(
+        Entity,
+        Ref<Handle<PhysicsMaterial>>,
+        Option<&mut Friction>,
+        Option<&mut Restitution>,
+        Option<&mut ColliderDensity>,
+    )>,
+) {
+    let modified_materials: HashSet<HandleId> = material_events
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => Some(handle.id()),
+            AssetEvent::Removed { .. } => None,
+        })
+        .collect();
+
+    for (entity, handle, friction, restitution, density) in &mut entities {
+        if !handle.is_changed() && !modified_materials.contains(&handle.id()) {
+            continue;
+        }
+        let Some(material) = materials.get(&handle) else {
+            continue;
+        };
+
+        match friction {
+            Some(mut friction) => *friction = material.friction,
+            None => {
+                commands.entity(entity).insert(material.friction);
+            }
+        }
+        match restitution {
+            Some(mut restitution) => *restitution = material.restitution,
+            None => {
+                commands.entity(entity).insert(material.restitution);
+            }
+        }
+
+        if let Some(mut density) = density {
+            if density.0 != material.density {
+                density.0 = material.density;
+            }
+        }
+    }
+}
+
 /// Initializes missing mass properties for [rigid bodies](RigidBody) and [colliders](Collider).
 fn init_mass_properties(
     mut commands: Commands,
@@ -252,6 +756,65 @@ fn init_mass_properties(
     }
 }
 
+/// Applies the scale of each collider's `GlobalTransform` to the [`Collider`].
+///
+/// The scaled shape is only recomputed when the scale changes by more than a small relative tolerance,
+/// so that the `GlobalTransform` changes caused by movement don't rebuild the shapes because of rounding errors.
+/// Nearly uniform scales are treated as uniform, so that balls stay balls.
+fn update_collider_scale(
+    mut colliders: Query<
+        (&GlobalTransform, &mut Collider),
+        Or<(Changed<GlobalTransform>, Changed<Collider>)>,
+    >,
+) {
+    for (transform, mut collider) in &mut colliders {
+        collider.bypass_change_detection().update_borrowed_shape();
+
+        #[cfg(feature = "2d")]
+        let scale = global_scale(transform).truncate().adjust_precision();
+        #[cfg(feature = "3d")]
+        let scale = global_scale(transform).adjust_precision();
+        let current_scale = collider.scale();
+
+        // Avoid triggering change detection unnecessarily
+        if (scale - current_scale).abs().max_element()
+            > SCALE_TOLERANCE * current_scale.abs().max_element()
+        {
+            collider.set_scale(scale, DEFAULT_SCALE_SUBDIVISIONS);
+        }
+    }
+}
+
+/// The relative tolerance used for detecting changes in the scale of colliders.
+const SCALE_TOLERANCE: Scalar = 1e-4;
+
+/// Returns the scale of the given `GlobalTransform`. Scales whose components differ only by
+/// the [`SCALE_TOLERANCE`] are made uniform.
+fn global_scale(transform: &GlobalTransform) -> Vec3 {
+    // Computing the scale from the columns of the matrix is cheaper than decomposing the transform
+    let matrix = transform.affine().matrix3;
+    let sign = matrix.determinant().signum();
+    let scale = Vec3::new(
+        matrix.x_axis.length() * sign,
+        matrix.y_axis.length(),
+        matrix.z_axis.length(),
+    );
+
+    #[cfg(feature = "2d")]
+    let (max, min) = {
+        let abs = scale.truncate().abs();
+        (abs.max_element(), abs.min_element())
+    };
+    #[cfg(feature = "3d")]
+    let (max, min) = (scale.abs().max_element(), scale.abs().min_element());
+
+    if max - min <= SCALE_TOLERANCE as f32 * max {
+        Vec3::splat(max) * scale.signum()
+    } else {
+        scale
+    }
+}
+
 /// Initializes missing components for [colliders](Collider).
 fn init_colliders(
     mut commands: Commands,
@@ -260,16 +823,26 @@ fn init_colliders(
             Entity,
             &Collider,
             Option<&ColliderAabb>,
+            Option<&ColliderDensity>,
             Option<&ColliderMassProperties>,
             Option<&PreviousColliderMassProperties>,
         ),
         Added<Collider>,
     >,
 ) {
-    for (entity, collider, aabb, mass_properties, previous_mass_properties) in &mut colliders {
+    for (entity, collider, aabb, density, mass_properties, previous_mass_properties) in
+        &mut colliders
+    {
+        // Use the density of user-defined mass properties if there is no density
+        let density = density.copied().unwrap_or_else(|| {
+            mass_properties.map_or(ColliderDensity::default(), |mass_properties| {
+                ColliderDensity(mass_properties.density)
+            })
+        });
         commands.entity(entity).insert((
             *aabb.unwrap_or(&ColliderAabb::from_shape(collider.get_shape())),
-            *mass_properties.unwrap_or(&ColliderMassProperties::new_computed(collider, 1.0)),
+            density,
+            *mass_properties.unwrap_or(&ColliderMassProperties::new_computed(collider, density.0)),
             *previous_mass_properties.unwrap_or(&PreviousColliderMassProperties(
                 ColliderMassProperties::ZERO,
             )),
@@ -278,6 +851,275 @@ fn init_colliders(
     }
 }
 
+/// Recomputes the [`ColliderMassProperties`] of colliders whose [`ColliderDensity`] has changed.
+fn update_collider_density(
+    mut colliders: Query<
+        (&Collider, &ColliderDensity, &mut ColliderMassProperties),
+        Changed<ColliderDensity>,
+    >,
+) {
+    for (collider, density, mut mass_properties) in &mut colliders {
+        // Avoid triggering change detection unnecessarily
+        if mass_properties.density != density.0 {
+            *mass_properties = ColliderMassProperties::new_computed(collider, density.0);
+        }
+    }
+}
+
+/// Attaches colliders to the closest [rigid body](RigidBody) in the entity hierarchy
+/// by adding [`ColliderParent`] and [`ColliderTransform`] components.
+fn update_collider_parents(
+    mut commands: Commands,
+    colliders: Query<
+        (Entity, Option<&RigidBody>, Option<&Parent>),
+        (With<Collider>, Or<(Added<Collider>, Changed<Parent>)>),
+    >,
+    ancestors: Query<(Option<&RigidBody>, Option<&Parent>)>,
+) {
+    for (entity, rb, parent) in &colliders {
+        let body = if rb.is_some() {
+            Some(entity)
+        } else {
+            // Find the closest ancestor that is a rigid body
+            let mut next = parent.map(|parent| parent.get());
+            let mut body = None;
+            while let Some(ancestor) = next {
+                let Ok((ancestor_rb, ancestor_parent)) = ancestors.get(ancestor) else {
+                    break;
+                };
+                if ancestor_rb.is_some() {
+                    body = Some(ancestor);
+                    break;
+                }
+                next = ancestor_parent.map(|parent| parent.get());
+            }
+            body
+        };
+
+        if let Some(body) = body {
+            commands
+                .entity(entity)
+                .insert((ColliderParent(body), ColliderTransform::default()));
+        } else {
+            commands
+                .entity(entity)
+                .remove::<(ColliderParent, ColliderTransform)>();
+        }
+    }
+}
+
+/// Updates the [`ColliderTransform`] of colliders attached to the descendants of rigid bodies
+/// based on the local transforms of the entities between the collider and the body.
+///
+/// Only colliders whose `Transform` or `GlobalTransform` has changed are updated, so colliders
+/// of static and sleeping bodies aren't visited. Changes to the transforms of the ancestors
+/// are detected through the `GlobalTransform` of the collider.
+fn update_collider_transforms(
+    mut colliders: Query<
+        (Entity, &ColliderParent, &mut ColliderTransform),
+        (
+            Without<RigidBody>,
+            // The transforms of welded bodies are relative to the body they are welded to
+            Without<WeldedBody>,
+            Or<(
+                Changed<Transform>,
+                Changed<GlobalTransform>,
+                Changed<ColliderParent>,
+            )>,
+        ),
+    >,
+    transforms: Query<(&Transform, Option<&Parent>)>,
+    body_transforms: Query<&GlobalTransform, With<RigidBody>>,
+) {
+    for (entity, collider_parent, mut collider_transform) in &mut colliders {
+        let Ok((transform, parent)) = transforms.get(entity) else {
+            continue;
+        };
+
+        // Combine the local transforms up to the rigid body
+        let mut relative_transform = *transform;
+        let mut next = parent.map(|parent| parent.get());
+        while let Some(ancestor) = next {
+            if ancestor == collider_parent.get() {
+                break;
+            }
+            let Ok((ancestor_transform, ancestor_parent)) = transforms.get(ancestor) else {
+                break;
+            };
+            relative_transform = ancestor_transform.mul_transform(relative_transform);
+            next = ancestor_parent.map(|parent| parent.get());
+        }
+
+        // The body's scale affects the offset of the collider, but not the body's own collider.
+        // Like the shapes of colliders, the offset uses the scale of the `GlobalTransform`.
+        if let Ok(body_transform) = body_transforms.get(collider_parent.get()) {
+            relative_transform.translation *= global_scale(body_transform);
+        }
+
+        let new_transform = ColliderTransform::from(relative_transform);
+
+        // Avoid triggering change detection unnecessarily
+        if new_transform != *collider_transform {
+            *collider_transform = new_transform;
+        }
+    }
+}
+
+/// Updates the [`Position`] and [`Rotation`] of colliders attached to the descendants of rigid bodies
+/// based on the body's position and rotation and the collider's [`ColliderTransform`].
+pub(crate) fn update_child_collider_position(
+    mut colliders: Query<
+        (
+            &ColliderTransform,
+            &ColliderParent,
+            &mut Position,
+            &mut Rotation,
+        ),
+        Without<RigidBody>,
+    >,
+    bodies: Query<(&Position, Option<&AccumulatedTranslation>, &Rotation), With<RigidBody>>,
+) {
+    for (collider_transform, parent, mut position, mut rotation) in &mut colliders {
+        let Ok((body_pos, body_translation, body_rot)) = bodies.get(parent.get()) else {
+            continue;
+        };
+
+        let body_pos = body_pos.0 + body_translation.map_or(Vector::ZERO, |t| t.0);
+        let new_position = body_pos + body_rot.rotate(collider_transform.translation);
+        let new_rotation = body_rot.mul(collider_transform.rotation);
+
+        // Avoid triggering change detection unnecessarily, so that the AABBs of colliders
+        // attached to static and sleeping bodies aren't recomputed
+        if position.0 != new_position {
+            position.0 = new_position;
+        }
+        if *rotation != new_rotation {
+            *rotation = new_rotation;
+        }
+    }
+}
+
+/// The mass properties that each collider has added to a rigid body, stored by the collider entity.
+///
+/// This is used for subtracting the mass properties from the body when the collider is removed
+/// or attached to another body, since the components of despawned colliders can't be accessed.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct ColliderMassContributions(HashMap<Entity, (Entity, ColliderMassProperties)>);
+
+/// Subtracts the mass properties of colliders from the mass properties of their rigid bodies
+/// when the colliders are removed or despawned, or when they are no longer attached to a body.
+fn remove_collider_mass_properties(
+    mut bodies: Query<MassPropertiesQuery, With<RigidBody>>,
+    attached_colliders: Query<(), (With<Collider>, With<ColliderParent>)>,
+    mut previous_mass_properties: Query<&mut PreviousColliderMassProperties>,
+    mut removed_colliders: RemovedComponents<Collider>,
+    mut removed_parents: RemovedComponents<ColliderParent>,
+    mut contributions: ResMut<ColliderMassContributions>,
+) {
+    for entity in removed_colliders.iter().chain(removed_parents.iter()) {
+        // The collider might have been added back or attached to another body after the removal
+        if attached_colliders.contains(entity) {
+            continue;
+        }
+
+        let Some((body, mass_properties)) = contributions.0.remove(&entity) else {
+            continue;
+        };
+
+        if let Ok(mut body_mass_properties) = bodies.get_mut(body) {
+            body_mass_properties -= mass_properties;
+        }
+
+        // The mass properties were already subtracted, so they shouldn't be subtracted again
+        // if the collider is attached to a body later
+        if let Ok(mut previous) = previous_mass_properties.get_mut(entity) {
+            previous.0 = ColliderMassProperties::ZERO;
+        }
+    }
+}
+
+/// Adds the mass properties of colliders attached to the descendants of rigid bodies
+/// to the mass properties of the bodies.
+fn update_child_collider_mass_properties(
+    mut bodies: Query<MassPropertiesQuery, With<RigidBody>>,
+    mut colliders: Query<
+        (
+            Entity,
+            &ColliderParent,
+            &ColliderTransform,
+            Option<&ColliderOffset>,
+            &Collider,
+            &mut ColliderMassProperties,
+            &mut PreviousColliderMassProperties,
+        ),
+        (
+            Without<RigidBody>,
+            // The mass properties of welded bodies are added to the body they are welded to as a whole
+            Without<WeldedBody>,
+            Or<(
+                Changed<Collider>,
+                Changed<ColliderMassProperties>,
+                Changed<ColliderTransform>,
+                Changed<ColliderOffset>,
+                Changed<ColliderParent>,
+            )>,
+        ),
+    >,
+    mut contributions: ResMut<ColliderMassContributions>,
+) {
+    for (
+        entity,
+        collider_parent,
+        collider_transform,
+        collider_offset,
+        collider,
+        mut collider_mass_properties,
+        mut previous_collider_mass_properties,
+    ) in &mut colliders
+    {
+        // If the collider was attached to another body, remove its mass properties from that body
+        if let Some((previous_body, previous_mass_properties)) = contributions.0.get(&entity) {
+            if *previous_body != collider_parent.get() {
+                if let Ok(mut previous_body_mass_properties) = bodies.get_mut(*previous_body) {
+                    previous_body_mass_properties -= *previous_mass_properties;
+                }
+                previous_collider_mass_properties.0 = ColliderMassProperties::ZERO;
+            }
+        }
+
+        let Ok(mut body_mass_properties) = bodies.get_mut(collider_parent.get()) else {
+            contributions.0.remove(&entity);
+            continue;
+        };
+
+        // Subtract previous collider mass props from the body's mass props
+        body_mass_properties -= previous_collider_mass_properties.0;
+
+        let new_collider_mass_properties =
+            ColliderMassProperties::new_computed(collider, collider_mass_properties.density);
+
+        // Avoid triggering change detection unnecessarily
+        if new_collider_mass_properties != *collider_mass_properties {
+            *collider_mass_properties = new_collider_mass_properties;
+        }
+
+        // Transform the collider mass props into the body's local space,
+        // and store them so that they can be subtracted when the collider changes
+        let collider_transform = collider_offset.map_or(*collider_transform, |offset| {
+            collider_transform.offset_by(offset)
+        });
+        let transformed_mass_properties =
+            collider_mass_properties.transformed_by(&collider_transform);
+        previous_collider_mass_properties.0 = transformed_mass_properties;
+        contributions
+            .0
+            .insert(entity, (collider_parent.get(), transformed_mass_properties));
+
+        // Add new collider mass props to the body's mass props
+        body_mass_properties += transformed_mass_properties;
+    }
+}
+
 type MassPropertiesChanged = Or<(
     Changed<Mass>,
     Changed<InverseMass>,
@@ -285,6 +1127,7 @@ type MassPropertiesChanged = Or<(
     Changed<InverseInertia>,
     Changed<Collider>,
     Changed<ColliderMassProperties>,
+    Changed<ColliderOffset>,
 )>;
 
 /// Updates each body's mass properties whenever their dependant mass properties or the body's [`Collider`] change.
@@ -299,9 +1142,15 @@ fn update_mass_properties(
             Option<&Collider>,
             Option<&mut ColliderMassProperties>,
             Option<&mut PreviousColliderMassProperties>,
+            Option<&ColliderOffset>,
+        ),
+        (
+            MassPropertiesChanged,
+            // Colliders attached to other bodies are handled by `update_child_collider_mass_properties`
+            Or<(With<RigidBody>, Without<ColliderParent>)>,
         ),
-        MassPropertiesChanged,
     >,
+    mut contributions: ResMut<ColliderMassContributions>,
 ) {
     for (
         entity,
@@ -310,6 +1159,7 @@ fn update_mass_properties(
         collider,
         collider_mass_properties,
         previous_collider_mass_properties,
+        collider_offset,
     ) in &mut bodies
     {
         if mass_properties.mass.is_changed() && mass_properties.mass.0 >= Scalar::EPSILON {
@@ -328,13 +1178,22 @@ fn update_mass_properties(
             // Subtract previous collider mass props from the body's mass props
             mass_properties -= previous_collider_mass_properties.0;
 
-            // Update previous and current collider mass props
-            previous_collider_mass_properties.0 = *collider_mass_properties;
             *collider_mass_properties =
                 ColliderMassProperties::new_computed(collider, collider_mass_properties.density);
 
+            // Apply the collider's local offset, and store the mass props
+            // so that they can be subtracted when the collider changes
+            let offset_mass_properties = collider_offset
+                .map_or(*collider_mass_properties, |offset| {
+                    collider_mass_properties.transformed_by(&ColliderTransform::from(*offset))
+                });
+            previous_collider_mass_properties.0 = offset_mass_properties;
+            contributions
+                .0
+                .insert(entity, (entity, offset_mass_properties));
+
             // Add new collider mass props to the body's mass props
-            mass_properties += *collider_mass_properties;
+            mass_properties += offset_mass_properties;
         }
 
         // Warn about dynamic bodies with no mass or inertia
@@ -363,3 +1222,35 @@ fn clamp_restitution(mut query: Query<&mut Restitution, Changed<Restitution>>) {
         restitution.coefficient = restitution.coefficient.clamp(0.0, 1.0);
     }
 }
+
+/// Matches the velocity of trailers with the velocity of the towing vehicle at the hitch
+/// when a [`TrailerHitch`] is added, so that connecting vehicles at runtime doesn't cause jolts.
+#[cfg(feature = "3d")]
+fn match_hitch_velocities(
+    hitches: Query<(&SphericalJoint, &TrailerHitch), Added<TrailerHitch>>,
+    mut bodies: Query<(&RigidBody, &Rotation, &mut LinearVelocity, &AngularVelocity)>,
+) {
+    for (joint, hitch) in &hitches {
+        if !hitch.match_velocity {
+            continue;
+        }
+
+        let Ok([(_, rot1, lin_vel1, ang_vel1), (rb2, rot2, mut lin_vel2, ang_vel2)]) =
+            bodies.get_many_mut(joint.entities())
+        else {
+            continue;
+        };
+
+        if !rb2.is_dynamic() {
+            continue;
+        }
+
+        // Velocities of the attachment points
+        let r1 = rot1.rotate(joint.local_anchor1);
+        let r2 = rot2.rotate(joint.local_anchor2);
+        let anchor_vel1 = lin_vel1.0 + ang_vel1.0.cross(r1);
+        let anchor_vel2 = lin_vel2.0 + ang_vel2.0.cross(r2);
+
+        lin_vel2.0 += anchor_vel1 - anchor_vel2;
+    }
+}
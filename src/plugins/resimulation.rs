@@ -0,0 +1,274 @@
+//! Re-simulates a subset of bodies from a snapshot, for example for client-side prediction and reconciliation.
+//!
+//! See [`Resimulation`].
+
+use crate::prelude::*;
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+
+/// Re-simulates a set of bodies for a number of steps from a snapshot of their [state](BodyState)
+/// without disturbing the rest of the world.
+///
+/// This is the core primitive of client-side prediction with server reconciliation. When an authoritative
+/// state for a past frame arrives, the locally predicted bodies are reset to that state, and the inputs
+/// recorded since then are replayed to get the corrected state of the current frame.
+///
+/// Bodies that the re-simulated bodies come into contact with join the re-simulation from the step
+/// of the contact onwards, so that the locally controlled bodies can push their surroundings.
+/// This can be disabled with [`with_contact_neighborhood`](#method.with_contact_neighborhood).
+/// All other [rigid bodies](RigidBody) are frozen in place: they are marked as [`Sleeping`] so that
+/// they aren't stepped, and if a re-simulated body disturbs them anyway, they are restored after the step.
+///
+/// Each step runs the [`PhysicsSchedule`] once using the current [`DeltaTime`]. Collision events like
+/// [`Collision`] and [`CollisionStarted`] have already been sent for the original steps,
+/// so they are not sent again while re-simulating.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// /// The inputs of the local player for the frames that haven't been confirmed by the server.
+/// #[derive(Resource)]
+/// struct PendingInputs(Vec<LinearVelocity>);
+///
+/// fn reconcile(world: &mut World, player: Entity, server_state: BodyState) {
+///     let inputs = world.resource::<PendingInputs>().0.clone();
+///
+///     Resimulation::new([(player, server_state)]).run(world, inputs.len() as u32, |world, step| {
+///         if let Some(mut velocity) = world.get_mut::<LinearVelocity>(player) {
+///             *velocity = inputs[step as usize];
+///         }
+///     });
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Resimulation {
+    /// The states that the re-simulated bodies are reset to before the first step.
+    states: HashMap<Entity, BodyState>,
+    /// If true, bodies touched by the re-simulated bodies join the re-simulation.
+    contact_neighborhood: bool,
+}
+
+impl Resimulation {
+    /// Creates a new [`Resimulation`] that resets the given bodies to the given states
+    /// and re-simulates them.
+    pub fn new(snapshot: impl IntoIterator<Item = (Entity, BodyState)>) -> Self {
+        Self {
+            states: snapshot.into_iter().collect(),
+            contact_neighborhood: true,
+        }
+    }
+
+    /// Sets whether bodies that the re-simulated bodies come into contact with join the re-simulation.
+    /// This is enabled by default.
+    pub fn with_contact_neighborhood(mut self, contact_neighborhood: bool) -> Self {
+        self.contact_neighborhood = contact_neighborhood;
+        self
+    }
+
+    /// Resets the bodies to the snapshot and runs the given number of physics steps.
+    ///
+    /// `apply_inputs` is called with the world and the index of the step before each step,
+    /// and it should apply the inputs that were recorded for that step, for example by setting
+    /// velocities or [external forces](ExternalForce).
+    ///
+    /// Returns the bodies that were re-simulated, including the ones that joined through contacts.
+    pub fn run(
+        &self,
+        world: &mut World,
+        steps: u32,
+        mut apply_inputs: impl FnMut(&mut World, u32),
+    ) -> HashSet<Entity> {
+        for (entity, state) in self.states.iter() {
+            write_body_state(world, *entity, state);
+        }
+
+        let mut resimulated: HashSet<Entity> = self.states.keys().copied().collect();
+
+        // Store the current state of the other bodies so that they can be restored after each step
+        let mut frozen: HashMap<Entity, FrozenBody> = world
+            .query::<FrozenBodyQueryComponents>()
+            .iter(world)
+            .filter(|(entity, rb, ..)| !rb.is_static() && !resimulated.contains(entity))
+            .map(
+                |(entity, _, pos, rot, lin_vel, ang_vel, prev_pos, translation, sleeping)| {
+                    let body = FrozenBody {
+                        state: BodyState::new(*pos, *rot, *lin_vel, *ang_vel),
+                        previous_position: prev_pos.copied(),
+                        accumulated_translation: translation.copied(),
+                        sleeping: sleeping.is_some(),
+                    };
+                    (entity, body)
+                },
+            )
+            .collect();
+
+        // Frozen bodies are put to sleep so that they aren't stepped
+        for entity in frozen.keys() {
+            world.entity_mut(*entity).insert(Sleeping);
+        }
+
+        world.insert_resource(Resimulating);
+
+        for step in 0..steps {
+            apply_inputs(world, step);
+            world.run_schedule(PhysicsSchedule);
+
+            if self.contact_neighborhood {
+                for entity in touched_bodies(world, &resimulated) {
+                    if frozen.remove(&entity).is_some() {
+                        world.entity_mut(entity).remove::<Sleeping>();
+                        resimulated.insert(entity);
+                    }
+                }
+            }
+
+            // Restore frozen bodies that were woken up and moved by the re-simulated bodies
+            for (entity, body) in frozen.iter() {
+                body.restore(world, *entity);
+            }
+        }
+
+        world.remove_resource::<Resimulating>();
+
+        // Restore the sleeping states of the frozen bodies
+        for (entity, body) in frozen {
+            if let Some(mut entity_mut) = world.get_entity_mut(entity) {
+                if body.sleeping {
+                    entity_mut.insert(Sleeping);
+                } else {
+                    entity_mut.remove::<Sleeping>();
+                }
+            }
+        }
+
+        resimulated
+    }
+}
+
+/// A marker resource that exists while a [`Resimulation`] is running.
+///
+/// Collision events are not sent while re-simulating, since they were already sent for the original steps.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub(crate) struct Resimulating;
+
+type FrozenBodyQueryComponents = (
+    Entity,
+    &'static RigidBody,
+    &'static Position,
+    &'static Rotation,
+    &'static LinearVelocity,
+    &'static AngularVelocity,
+    Option<&'static PreviousPosition>,
+    Option<&'static AccumulatedTranslation>,
+    Option<&'static Sleeping>,
+);
+
+/// The state of a body that doesn't take part in a [`Resimulation`].
+#[derive(Clone, Copy, Debug)]
+struct FrozenBody {
+    state: BodyState,
+    previous_position: Option<PreviousPosition>,
+    accumulated_translation: Option<AccumulatedTranslation>,
+    /// True if the body was sleeping before the re-simulation.
+    sleeping: bool,
+}
+
+impl FrozenBody {
+    /// Restores the body in case it was moved during a step, and puts it back to sleep.
+    ///
+    /// Change detection is bypassed so that the restored body isn't woken up again in the next step.
+    fn restore(&self, world: &mut World, entity: Entity) {
+        let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+            return;
+        };
+        if let Some(mut position) = entity_mut.get_mut::<Position>() {
+            *position.bypass_change_detection() = self.state.position;
+        }
+        if let Some(mut rotation) = entity_mut.get_mut::<Rotation>() {
+            *rotation.bypass_change_detection() = self.state.rotation;
+        }
+        if let Some(mut linear_velocity) = entity_mut.get_mut::<LinearVelocity>() {
+            *linear_velocity.bypass_change_detection() = self.state.linear_velocity;
+        }
+        if let Some(mut angular_velocity) = entity_mut.get_mut::<AngularVelocity>() {
+            *angular_velocity.bypass_change_detection() = self.state.angular_velocity;
+        }
+        if let (Some(mut previous_position), Some(value)) = (
+            entity_mut.get_mut::<PreviousPosition>(),
+            self.previous_position,
+        ) {
+            *previous_position.bypass_change_detection() = value;
+        }
+        if let (Some(mut translation), Some(value)) = (
+            entity_mut.get_mut::<AccumulatedTranslation>(),
+            self.accumulated_translation,
+        ) {
+            *translation.bypass_change_detection() = value;
+        }
+        if !entity_mut.contains::<Sleeping>() {
+            entity_mut.insert(Sleeping);
+        }
+    }
+}
+
+/// Returns the bodies that are in contact with the given bodies during the current frame
+/// but are not contained in them.
+fn touched_bodies(world: &World, bodies: &HashSet<Entity>) -> Vec<Entity> {
+    // Colliders can be attached to a parent body
+    let body_of = |collider: Entity| {
+        world
+            .get::<ColliderParent>(collider)
+            .map_or(collider, |parent| parent.get())
+    };
+
+    world
+        .resource::<Collisions>()
+        .iter()
+        .filter(|contacts| contacts.during_current_frame)
+        .filter_map(|contacts| {
+            let body1 = body_of(contacts.entity1);
+            let body2 = body_of(contacts.entity2);
+            match (bodies.contains(&body1), bodies.contains(&body2)) {
+                (true, false) => Some(body2),
+                (false, true) => Some(body1),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Writes the given [`BodyState`] into the components of a body.
+///
+/// The [`PreviousPosition`] and [`AccumulatedTranslation`] are reset too, so that the first
+/// re-simulated step starts from the given state.
+fn write_body_state(world: &mut World, entity: Entity, state: &BodyState) {
+    let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+    if let Some(mut position) = entity_mut.get_mut::<Position>() {
+        *position = state.position;
+    }
+    if let Some(mut previous_position) = entity_mut.get_mut::<PreviousPosition>() {
+        previous_position.0 = state.position.0;
+    }
+    if let Some(mut translation) = entity_mut.get_mut::<AccumulatedTranslation>() {
+        translation.0 = Vector::ZERO;
+    }
+    if let Some(mut rotation) = entity_mut.get_mut::<Rotation>() {
+        *rotation = state.rotation;
+    }
+    if let Some(mut linear_velocity) = entity_mut.get_mut::<LinearVelocity>() {
+        *linear_velocity = state.linear_velocity;
+    }
+    if let Some(mut angular_velocity) = entity_mut.get_mut::<AngularVelocity>() {
+        *angular_velocity = state.angular_velocity;
+    }
+}
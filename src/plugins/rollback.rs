@@ -0,0 +1,236 @@
+//! Keeps the internal state of the physics engine consistent when the world is rolled back,
+//! for example by rollback netcode like [GGRS](https://github.com/gschup/bevy_ggrs).
+//!
+//! See [`PhysicsRollbackPlugin`].
+
+use std::collections::VecDeque;
+
+use super::snapshot;
+use crate::prelude::*;
+use bevy::prelude::*;
+
+/// Keeps the internal state of the physics engine consistent when the world is rolled back,
+/// for example by rollback netcode.
+///
+/// Rollback libraries restore the components and resources that are registered for rollback,
+/// but the engine also keeps state between frames that isn't stored in components, like
+/// the [`Collisions`] used for warm starting contacts, the time in the [`PhysicsLoop`] accumulator
+/// and the [Lagrange multipliers](Joint::lagrange_multipliers) of [joints]. This plugin stores
+/// that state for each of the latest frames and restores it when it detects a rollback.
+///
+/// Rollbacks are detected using the [`PhysicsRollbackFrame`] resource, which counts the physics frames
+/// and must be registered for rollback together with the components of the bodies.
+/// With the `ggrs` feature, everything can be registered with `register_physics_rollback`.
+///
+/// The plugin is optional, and nothing is done if the [`PhysicsRollback`] resource doesn't exist.
+pub struct PhysicsRollbackPlugin {
+    schedule: Box<dyn ScheduleLabel>,
+}
+
+impl PhysicsRollbackPlugin {
+    /// Creates a [`PhysicsRollbackPlugin`] with the schedule that is used for running the [`PhysicsSchedule`].
+    ///
+    /// The default schedule is `PostUpdate`.
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: Box::new(schedule),
+        }
+    }
+}
+
+impl Default for PhysicsRollbackPlugin {
+    fn default() -> Self {
+        Self::new(PostUpdate)
+    }
+}
+
+impl Plugin for PhysicsRollbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PhysicsRollbackFrame>()
+            .register_type::<PhysicsRollbackFrame>()
+            .register_type::<PhysicsRollback>()
+            .add_systems(
+                self.schedule.dyn_clone(),
+                (
+                    enable_deterministic_mode.run_if(resource_added::<PhysicsRollback>()),
+                    restore_rollback_state,
+                )
+                    .chain()
+                    .before(PhysicsSet::Prepare)
+                    .run_if(resource_exists::<PhysicsRollback>()),
+            )
+            .add_systems(
+                self.schedule.dyn_clone(),
+                store_rollback_state
+                    .after(PhysicsSet::StepSimulation)
+                    .before(PhysicsSet::Sync)
+                    .run_if(resource_exists::<PhysicsRollback>()),
+            );
+    }
+}
+
+/// An optional resource that enables the [`PhysicsRollbackPlugin`] and stores the internal state
+/// of the physics engine for the latest [`max_frames`](#structfield.max_frames) frames.
+///
+/// Rolling back further than that clears the stored state, so contacts lose their warm starting
+/// for one frame. Inserting the resource also enables [`DeterministicMode`], because the order
+/// of contacts found by the broad phase depends on the history of the simulation.
+///
+/// ## Example
+///
+/// With the `ggrs` feature, the physics can be added to the `GgrsSchedule` of `bevy_ggrs`:
+///
+/// ```ignore
+/// use bevy::prelude::*;
+/// use bevy_ggrs::{GgrsPlugin, GgrsSchedule};
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn main() {
+///     let mut app = App::new();
+///
+///     GgrsPlugin::<GgrsConfig>::new()
+///         .with_update_frequency(60)
+///         .with_input_system(read_inputs)
+///         .register_physics_rollback()
+///         .build(&mut app);
+///
+///     app.add_plugins((DefaultPlugins, PhysicsPlugins::new(GgrsSchedule)))
+///         .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0))
+///         .insert_resource(PhysicsRollback::default())
+///         .run();
+/// }
+/// ```
+#[derive(Resource, Reflect, Clone, Debug, PartialEq)]
+#[reflect(Resource)]
+pub struct PhysicsRollback {
+    /// The maximum number of frames that can be rolled back without losing the internal state.
+    ///
+    /// The default is `16`.
+    pub max_frames: usize,
+    /// The stored state of the latest frames in ascending order.
+    #[reflect(ignore)]
+    history: VecDeque<(u32, RollbackState)>,
+    /// The frame at the end of the latest physics frame, used for detecting rollbacks.
+    last_frame: Option<u32>,
+}
+
+impl Default for PhysicsRollback {
+    fn default() -> Self {
+        Self::new(16)
+    }
+}
+
+impl PhysicsRollback {
+    /// Creates a new [`PhysicsRollback`] that stores the state of the given number of frames.
+    pub fn new(max_frames: usize) -> Self {
+        Self {
+            max_frames,
+            history: VecDeque::new(),
+            last_frame: None,
+        }
+    }
+}
+
+/// The number of physics frames that have been run with the [`PhysicsRollback`] resource.
+///
+/// This must be registered for rollback so that the [`PhysicsRollbackPlugin`] can detect rollbacks.
+#[derive(Resource, Reflect, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Resource)]
+pub struct PhysicsRollbackFrame(pub u32);
+
+/// The internal state of the physics engine at the end of a frame.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct RollbackState {
+    collisions: Collisions,
+    accumulator: Scalar,
+    joints: Vec<JointSnapshot>,
+}
+
+fn enable_deterministic_mode(mut deterministic_mode: ResMut<DeterministicMode>) {
+    deterministic_mode.0 = true;
+}
+
+/// Restores the stored internal state if the world has been rolled back to an earlier frame.
+fn restore_rollback_state(world: &mut World) {
+    let frame = world.resource::<PhysicsRollbackFrame>().0;
+    let mut rollback = world.resource_mut::<PhysicsRollback>();
+
+    if rollback
+        .last_frame
+        .map_or(true, |last_frame| last_frame == frame)
+    {
+        return;
+    }
+
+    // The frames after the restored frame will be simulated again
+    rollback
+        .history
+        .retain(|(stored_frame, _)| *stored_frame <= frame);
+    rollback.last_frame = Some(frame);
+    let state = rollback
+        .history
+        .back()
+        .filter(|(stored_frame, _)| *stored_frame == frame)
+        .map(|(_, state)| state.clone())
+        .unwrap_or_default();
+
+    *world.resource_mut::<Collisions>() = state.collisions;
+    world.resource_mut::<PhysicsLoop>().accumulator = state.accumulator;
+    snapshot::restore_joints(world, &state.joints, Some);
+}
+
+/// Advances the [`PhysicsRollbackFrame`] and stores the internal state of the new frame.
+fn store_rollback_state(world: &mut World) {
+    let frame = {
+        let mut frame = world.resource_mut::<PhysicsRollbackFrame>();
+        frame.0 = frame.0.wrapping_add(1);
+        frame.0
+    };
+    let state = RollbackState {
+        collisions: world.resource::<Collisions>().clone(),
+        accumulator: world.resource::<PhysicsLoop>().accumulator,
+        joints: snapshot::take_joints(world),
+    };
+
+    let mut rollback = world.resource_mut::<PhysicsRollback>();
+    rollback.history.push_back((frame, state));
+    while rollback.history.len() > rollback.max_frames {
+        rollback.history.pop_front();
+    }
+    rollback.last_frame = Some(frame);
+}
+
+/// Registers the components and resources of the physics engine for rollback with `bevy_ggrs`.
+///
+/// This requires the `ggrs` feature. See [`PhysicsRollback`] for an example.
+#[cfg(feature = "ggrs")]
+pub trait GgrsPhysicsExt {
+    /// Registers the state of [rigid bodies](RigidBody), the [`PhysicsTime`] and
+    /// the [`PhysicsRollbackFrame`] for rollback.
+    fn register_physics_rollback(self) -> Self;
+}
+
+#[cfg(feature = "ggrs")]
+impl<C: bevy_ggrs::ggrs::Config> GgrsPhysicsExt for bevy_ggrs::GgrsPlugin<C> {
+    fn register_physics_rollback(self) -> Self {
+        self.register_rollback_component::<Position>()
+            .register_rollback_component::<Rotation>()
+            .register_rollback_component::<PreviousPosition>()
+            .register_rollback_component::<PreviousRotation>()
+            .register_rollback_component::<AccumulatedTranslation>()
+            .register_rollback_component::<LinearVelocity>()
+            .register_rollback_component::<AngularVelocity>()
+            .register_rollback_component::<ExternalForce>()
+            .register_rollback_component::<ExternalTorque>()
+            .register_rollback_component::<ExternalImpulse>()
+            .register_rollback_component::<ExternalAngularImpulse>()
+            .register_rollback_component::<Sleeping>()
+            .register_rollback_component::<TimeSleeping>()
+            .register_rollback_resource::<PhysicsTime>()
+            .register_rollback_resource::<PhysicsRollbackFrame>()
+    }
+}
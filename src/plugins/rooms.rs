@@ -0,0 +1,322 @@
+//! Suspends the simulation of bodies inside of inactive rooms, for example rooms that are off-screen.
+//!
+//! See [`PhysicsRoomsPlugin`].
+
+use crate::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
+use parry::bounding_volume::{Aabb, BoundingVolume};
+
+/// Suspends the simulation of [rigid bodies](RigidBody) inside of the inactive rooms of the
+/// [`PhysicsRooms`] resource.
+///
+/// Bodies whose colliders are fully inside of an inactive room are [frozen](FrozenInRoom) until the room
+/// is activated again. Frozen bodies are removed from the [broad phase](BroadPhasePlugin) and aren't simulated,
+/// so they don't cost anything besides memory, and their state is preserved exactly.
+///
+/// The rooms are optional, and nothing is done if the [`PhysicsRooms`] resource doesn't exist.
+pub struct PhysicsRoomsPlugin {
+    schedule: Box<dyn ScheduleLabel>,
+}
+
+impl PhysicsRoomsPlugin {
+    /// Creates a [`PhysicsRoomsPlugin`] with the schedule that is used for running the [`PhysicsSchedule`].
+    ///
+    /// The default schedule is `PostUpdate`.
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: Box::new(schedule),
+        }
+    }
+}
+
+impl Default for PhysicsRoomsPlugin {
+    fn default() -> Self {
+        Self::new(PostUpdate)
+    }
+}
+
+impl Plugin for PhysicsRoomsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PhysicsRooms>()
+            .register_type::<FrozenInRoom>()
+            .register_type::<PhysicsRoom>()
+            .register_type::<RoomId>()
+            .add_systems(
+                self.schedule.dyn_clone(),
+                (update_frozen_bodies, apply_deferred)
+                    .chain()
+                    .after(PhysicsSet::Prepare)
+                    .before(PhysicsSet::StepSimulation)
+                    .run_if(resource_exists::<PhysicsRooms>()),
+            );
+    }
+}
+
+/// An optional resource that divides the world into rectangular rooms that can be activated and deactivated,
+/// like the rooms of a metroidvania.
+///
+/// Bodies whose colliders are fully inside of an inactive room and don't touch any active room are
+/// [frozen](FrozenInRoom): they are removed from the [broad phase](BroadPhasePlugin) and aren't simulated
+/// until the room is activated again, at which point they continue exactly where they left off.
+/// Bodies that move into an inactive room are frozen too. Rooms can overlap, for example at doorways.
+///
+/// Rooms are mainly meant for 2D games, but they work in 3D as axis-aligned boxes too.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// #[derive(Resource)]
+/// struct Rooms {
+///     cave: RoomId,
+///     castle: RoomId,
+/// }
+///
+/// # #[cfg(all(feature = "2d", feature = "f32"))]
+/// fn setup(mut commands: Commands) {
+///     let mut physics_rooms = PhysicsRooms::default();
+///     let cave = physics_rooms.add_room(Vec2::new(0.0, 0.0), Vec2::new(800.0, 600.0));
+///     let castle = physics_rooms.add_room(Vec2::new(800.0, 0.0), Vec2::new(1600.0, 600.0));
+///
+///     // Only simulate the room that the player starts in
+///     physics_rooms.activate_only(cave);
+///
+///     commands.insert_resource(physics_rooms);
+///     commands.insert_resource(Rooms { cave, castle });
+/// }
+///
+/// fn enter_castle(mut physics_rooms: ResMut<PhysicsRooms>, rooms: Res<Rooms>) {
+///     physics_rooms.activate_only(rooms.castle);
+/// }
+/// ```
+#[derive(Resource, Reflect, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Resource)]
+pub struct PhysicsRooms {
+    rooms: Vec<PhysicsRoom>,
+}
+
+/// The identifier of a room in [`PhysicsRooms`].
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoomId(usize);
+
+/// A room in [`PhysicsRooms`].
+#[derive(Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PhysicsRoom {
+    /// The minimum corner of the room.
+    pub min: Vector,
+    /// The maximum corner of the room.
+    pub max: Vector,
+    /// True if the bodies in the room are simulated.
+    pub active: bool,
+}
+
+impl PhysicsRoom {
+    /// Returns true if the given AABB is fully inside of the room.
+    fn contains(&self, aabb: &ColliderAabb) -> bool {
+        Vector::from(aabb.mins).cmpge(self.min).all()
+            && Vector::from(aabb.maxs).cmple(self.max).all()
+    }
+
+    /// Returns true if the given AABB intersects the room.
+    fn intersects(&self, aabb: &ColliderAabb) -> bool {
+        Vector::from(aabb.mins).cmple(self.max).all()
+            && Vector::from(aabb.maxs).cmpge(self.min).all()
+    }
+}
+
+impl PhysicsRooms {
+    /// Adds an active room with the given minimum and maximum corners and returns its identifier.
+    pub fn add_room(&mut self, min: Vector, max: Vector) -> RoomId {
+        self.rooms.push(PhysicsRoom {
+            min,
+            max,
+            active: true,
+        });
+        RoomId(self.rooms.len() - 1)
+    }
+
+    /// Returns the room with the given identifier.
+    pub fn room(&self, id: RoomId) -> Option<&PhysicsRoom> {
+        self.rooms.get(id.0)
+    }
+
+    /// Returns an iterator over the identifiers and rooms.
+    pub fn iter(&self) -> impl Iterator<Item = (RoomId, &PhysicsRoom)> {
+        self.rooms
+            .iter()
+            .enumerate()
+            .map(|(i, room)| (RoomId(i), room))
+    }
+
+    /// Returns the first room that contains the given point.
+    pub fn room_at(&self, point: Vector) -> Option<RoomId> {
+        self.iter()
+            .find(|(_, room)| point.cmpge(room.min).all() && point.cmple(room.max).all())
+            .map(|(id, _)| id)
+    }
+
+    /// Returns true if the room with the given identifier is active.
+    pub fn is_active(&self, id: RoomId) -> bool {
+        self.room(id).map_or(false, |room| room.active)
+    }
+
+    /// Activates or deactivates the room with the given identifier.
+    pub fn set_active(&mut self, id: RoomId, active: bool) {
+        if let Some(room) = self.rooms.get_mut(id.0) {
+            room.active = active;
+        }
+    }
+
+    /// Activates the room with the given identifier.
+    pub fn activate(&mut self, id: RoomId) {
+        self.set_active(id, true);
+    }
+
+    /// Deactivates the room with the given identifier.
+    pub fn deactivate(&mut self, id: RoomId) {
+        self.set_active(id, false);
+    }
+
+    /// Activates the room with the given identifier and deactivates all other rooms.
+    pub fn activate_only(&mut self, id: RoomId) {
+        for (i, room) in self.rooms.iter_mut().enumerate() {
+            room.active = i == id.0;
+        }
+    }
+
+    /// Returns the inactive room that the given AABB should be frozen in, if any.
+    fn freezing_room(&self, aabb: &ColliderAabb) -> Option<RoomId> {
+        if self
+            .rooms
+            .iter()
+            .any(|room| room.active && room.intersects(aabb))
+        {
+            return None;
+        }
+        self.iter()
+            .find(|(_, room)| !room.active && room.contains(aabb))
+            .map(|(id, _)| id)
+    }
+}
+
+/// A component that is added to [rigid bodies](RigidBody) and their colliders while they are
+/// frozen in an inactive room of the [`PhysicsRooms`] resource.
+///
+/// Frozen bodies are also marked as [`Sleeping`], but they can't be woken up until the room is activated.
+#[derive(Reflect, Clone, Copy, Component, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct FrozenInRoom {
+    /// The room that the body is frozen in.
+    room: RoomId,
+    /// True if the body was sleeping before it was frozen.
+    was_sleeping: bool,
+}
+
+impl FrozenInRoom {
+    /// Returns the room that the body is frozen in.
+    pub fn room(&self) -> RoomId {
+        self.room
+    }
+}
+
+type RoomBodyQueryComponents = (
+    Entity,
+    Ref<'static, Position>,
+    Option<&'static FrozenInRoom>,
+    Option<&'static Sleeping>,
+);
+
+/// Freezes the bodies that are inside of inactive rooms and thaws the bodies whose rooms have been activated.
+///
+/// All bodies are checked when the rooms are changed. Otherwise, only bodies that moved are checked.
+fn update_frozen_bodies(
+    mut commands: Commands,
+    bodies: Query<RoomBodyQueryComponents, With<RigidBody>>,
+    colliders: Query<(Entity, &ColliderParent, &ColliderAabb)>,
+    rooms: Res<PhysicsRooms>,
+) {
+    let candidates = bodies
+        .iter()
+        .filter(|(_, position, frozen, _)| {
+            rooms.is_changed() || (frozen.is_none() && position.is_changed())
+        })
+        .map(|(entity, ..)| entity)
+        .collect::<Vec<_>>();
+
+    if candidates.is_empty() {
+        return;
+    }
+
+    // Compute the AABB containing all colliders of each body
+    let mut body_aabbs: HashMap<Entity, ColliderAabb> = HashMap::default();
+    let mut body_colliders: HashMap<Entity, Vec<(Entity, ColliderAabb)>> = HashMap::default();
+    for (collider, parent, aabb) in &colliders {
+        body_aabbs
+            .entry(parent.get())
+            .and_modify(|body_aabb| *body_aabb = ColliderAabb(body_aabb.merged(aabb)))
+            .or_insert(*aabb);
+        body_colliders
+            .entry(parent.get())
+            .or_default()
+            .push((collider, *aabb));
+    }
+
+    for entity in candidates {
+        let Ok((_, position, frozen, sleeping)) = bodies.get(entity) else {
+            continue;
+        };
+
+        // Bodies without colliders are treated as points
+        let aabb = body_aabbs
+            .get(&entity)
+            .copied()
+            .unwrap_or_else(|| ColliderAabb(Aabb::new(position.0.into(), position.0.into())));
+        let room = rooms.freezing_room(&aabb);
+        let colliders = body_colliders
+            .get(&entity)
+            .map_or(&[][..], |c| c.as_slice());
+
+        match (frozen, room) {
+            (None, Some(room)) => {
+                let frozen = FrozenInRoom {
+                    room,
+                    was_sleeping: sleeping.is_some(),
+                };
+                commands.entity(entity).insert((frozen, Sleeping));
+                for (collider, _) in colliders {
+                    commands.entity(*collider).insert(frozen);
+                }
+            }
+            (Some(frozen), None) => {
+                commands.entity(entity).remove::<FrozenInRoom>();
+                if !frozen.was_sleeping {
+                    commands.entity(entity).remove::<Sleeping>();
+                }
+                // Re-adding the AABBs adds the colliders back to the broad phase
+                for (collider, aabb) in colliders {
+                    commands
+                        .entity(*collider)
+                        .remove::<(FrozenInRoom, ColliderAabb)>()
+                        .insert(*aabb);
+                }
+            }
+            (Some(frozen), Some(room)) if frozen.room != room => {
+                let frozen = FrozenInRoom { room, ..*frozen };
+                commands.entity(entity).insert(frozen);
+                for (collider, _) in colliders {
+                    commands.entity(*collider).insert(frozen);
+                }
+            }
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,315 @@
+//! Wraps 2D bodies around the edges of the world, like in Asteroids.
+//!
+//! See [`ScreenWrapPlugin`].
+
+use crate::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
+
+/// Wraps [rigid bodies](RigidBody) with the [`ScreenWrap`] component around the edges of their wrapping area
+/// and keeps their [seam colliders](ScreenWrap::seam_colliders) up to date. Only available in 2D.
+///
+/// The bodies are wrapped at the end of each physics frame, after the simulation has been stepped.
+pub struct ScreenWrapPlugin {
+    schedule: Box<dyn ScheduleLabel>,
+}
+
+impl ScreenWrapPlugin {
+    /// Creates a [`ScreenWrapPlugin`] with the schedule that is used for running the [`PhysicsSchedule`].
+    ///
+    /// The default schedule is `PostUpdate`.
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: Box::new(schedule),
+        }
+    }
+}
+
+impl Default for ScreenWrapPlugin {
+    fn default() -> Self {
+        Self::new(PostUpdate)
+    }
+}
+
+impl Plugin for ScreenWrapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ScreenWrapped>()
+            .register_type::<ScreenWrap>()
+            .register_type::<SeamCollider>()
+            .register_type::<ScreenWrapped>()
+            .add_systems(
+                self.schedule.dyn_clone(),
+                (wrap_bodies, update_seam_colliders)
+                    .chain()
+                    .after(PhysicsSet::StepSimulation)
+                    .before(PhysicsSet::Sync),
+            );
+    }
+}
+
+/// A component that wraps a [rigid body](RigidBody) around the edges of a rectangular area, so that a body
+/// leaving the area on one side enters it on the opposite side, like in Asteroids and other arcade games.
+///
+/// When a body is wrapped, its [`Position`] is moved by the size of the area, the [AABBs](ColliderAabb)
+/// of its colliders are moved along with it, and its [`TransformInterpolation`] is reset so that it isn't
+/// interpolated across the screen. A [`ScreenWrapped`] event is sent for the body.
+///
+/// ## Seam colliders
+///
+/// Bodies are wrapped based on their position, so large bodies can stick out of the area
+/// and overlap the opposite edge without colliding with the bodies there. If
+/// [`seam_colliders`](#structfield.seam_colliders) is true, copies of the body's [`Collider`] are attached
+/// to the body on the opposite sides of the area while it overlaps the edges. The copies are child entities
+/// with the [`SeamCollider`] component and a [`ColliderDensity`] of zero, so contacts across the seam
+/// move the body without changing its mass. Only the collider of the body entity itself is copied.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// # #[cfg(all(feature = "2d", feature = "f32"))]
+/// fn spawn_asteroid(mut commands: Commands) {
+///     commands.spawn((
+///         RigidBody::Dynamic,
+///         Collider::ball(40.0),
+///         LinearVelocity(Vec2::new(100.0, 20.0)),
+///         ScreenWrap::new(Vec2::new(-640.0, -360.0), Vec2::new(640.0, 360.0)).with_seam_colliders(true),
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct ScreenWrap {
+    /// The minimum corner of the wrapping area.
+    pub min: Vector,
+    /// The maximum corner of the wrapping area.
+    pub max: Vector,
+    /// If true, copies of the body's collider are placed on the opposite sides of the area while the body
+    /// overlaps its edges, so that the body collides with bodies across the seam. False by default.
+    pub seam_colliders: bool,
+}
+
+impl Default for ScreenWrap {
+    fn default() -> Self {
+        Self::new(Vector::splat(-500.0), Vector::splat(500.0))
+    }
+}
+
+impl ScreenWrap {
+    /// Creates a [`ScreenWrap`] with the given minimum and maximum corners of the wrapping area.
+    pub fn new(min: Vector, max: Vector) -> Self {
+        Self {
+            min,
+            max,
+            seam_colliders: false,
+        }
+    }
+
+    /// Sets whether [seam colliders](#seam-colliders) are used.
+    pub fn with_seam_colliders(mut self, seam_colliders: bool) -> Self {
+        self.seam_colliders = seam_colliders;
+        self
+    }
+
+    /// Returns the size of the wrapping area.
+    pub fn size(&self) -> Vector {
+        self.max - self.min
+    }
+
+    /// Returns the given point wrapped into the wrapping area.
+    ///
+    /// Axes with a size of zero or less aren't wrapped.
+    pub fn wrap_point(&self, point: Vector) -> Vector {
+        let size = self.size();
+        let wrapped = point - size * ((point - self.min) / size).floor();
+        Vector::select(size.cmpgt(Vector::ZERO), wrapped, point)
+    }
+
+    /// Returns the offsets of the seam colliders needed for an AABB that overlaps the edges of the area.
+    fn seam_offsets(&self, aabb: &ColliderAabb) -> Vec<Vector> {
+        let size = self.size();
+        let mins = Vector::from(aabb.mins);
+        let maxs = Vector::from(aabb.maxs);
+
+        // The offsets that bring the parts sticking out of each edge to the opposite side
+        let axis_offsets = |axis: usize| {
+            let mut offsets = vec![0.0];
+            if size[axis] > 0.0 && maxs[axis] > self.max[axis] {
+                offsets.push(-size[axis]);
+            }
+            if size[axis] > 0.0 && mins[axis] < self.min[axis] {
+                offsets.push(size[axis]);
+            }
+            offsets
+        };
+
+        let x_offsets = axis_offsets(0);
+        let y_offsets = axis_offsets(1);
+        x_offsets
+            .iter()
+            .flat_map(|x| y_offsets.iter().map(|y| Vector::new(*x, *y)))
+            .filter(|offset| *offset != Vector::ZERO)
+            .collect()
+    }
+}
+
+/// A copy of the [`Collider`] of a body with [`ScreenWrap`] that is placed on the opposite side
+/// of the wrapping area. See [seam colliders](ScreenWrap#seam-colliders).
+///
+/// Seam colliders are managed by the [`ScreenWrapPlugin`] and shouldn't be added manually.
+#[derive(Component, Reflect, Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct SeamCollider {
+    /// The offset of the collider from the body in world space.
+    offset: Vector,
+}
+
+impl SeamCollider {
+    /// Returns the offset of the collider from the body in world space.
+    pub fn offset(&self) -> Vector {
+        self.offset
+    }
+}
+
+/// An event that is sent when a body with [`ScreenWrap`] is wrapped around the edges of its wrapping area.
+#[derive(Event, Reflect, Clone, Copy, Debug, PartialEq)]
+pub struct ScreenWrapped {
+    /// The entity of the body.
+    pub entity: Entity,
+    /// The translation that was applied to the body.
+    pub offset: Vector,
+}
+
+type WrappedBodyComponents = (
+    Entity,
+    &'static ScreenWrap,
+    &'static mut Position,
+    Option<&'static mut TransformInterpolation>,
+);
+
+/// Moves bodies that left their wrapping area to the opposite side, along with the AABBs of their colliders.
+fn wrap_bodies(
+    mut bodies: Query<WrappedBodyComponents, (With<RigidBody>, Without<Sleeping>)>,
+    mut colliders: Query<(&ColliderParent, &mut ColliderAabb, &mut Position), Without<RigidBody>>,
+    mut body_aabbs: Query<&mut ColliderAabb, With<RigidBody>>,
+    mut wrapped_ev_writer: EventWriter<ScreenWrapped>,
+) {
+    let mut offsets: HashMap<Entity, Vector> = HashMap::default();
+
+    for (entity, wrap, mut position, interpolation) in &mut bodies {
+        let wrapped = wrap.wrap_point(position.0);
+        if wrapped == position.0 {
+            continue;
+        }
+
+        let offset = wrapped - position.0;
+        position.0 = wrapped;
+        offsets.insert(entity, offset);
+
+        // Teleport the body instead of interpolating it across the screen
+        if let Some(mut interpolation) = interpolation {
+            interpolation.reset();
+        }
+
+        if let Ok(mut aabb) = body_aabbs.get_mut(entity) {
+            translate_aabb(&mut aabb, offset);
+        }
+
+        wrapped_ev_writer.send(ScreenWrapped { entity, offset });
+    }
+
+    if offsets.is_empty() {
+        return;
+    }
+
+    // Move the colliders attached to the children of the wrapped bodies
+    for (parent, mut aabb, mut position) in &mut colliders {
+        if let Some(offset) = offsets.get(&parent.get()) {
+            translate_aabb(&mut aabb, *offset);
+            position.0 += *offset;
+        }
+    }
+}
+
+fn translate_aabb(aabb: &mut ColliderAabb, offset: Vector) {
+    aabb.mins.coords = (Vector::from(aabb.mins) + offset).into();
+    aabb.maxs.coords = (Vector::from(aabb.maxs) + offset).into();
+}
+
+type SeamBodyComponents = (
+    Entity,
+    &'static ScreenWrap,
+    &'static Collider,
+    &'static ColliderAabb,
+    &'static Rotation,
+    Option<&'static CollisionLayers>,
+    Option<&'static Sensor>,
+);
+
+/// Spawns, moves and despawns the [seam colliders](ScreenWrap#seam-colliders) of bodies that overlap the edges
+/// of their wrapping area.
+fn update_seam_colliders(
+    mut commands: Commands,
+    bodies: Query<SeamBodyComponents, With<RigidBody>>,
+    mut seam_colliders: Query<(Entity, &SeamCollider, &Parent, &mut Transform)>,
+) {
+    // The existing seam colliders of each body
+    let mut existing: HashMap<Entity, Vec<Entity>> = HashMap::default();
+    for (entity, _, parent, _) in &seam_colliders {
+        existing.entry(parent.get()).or_default().push(entity);
+    }
+
+    for (entity, wrap, collider, aabb, rotation, layers, sensor) in &bodies {
+        let mut offsets = if wrap.seam_colliders {
+            wrap.seam_offsets(aabb)
+        } else {
+            vec![]
+        };
+        let inverse_rotation = rotation.inverse();
+
+        for seam_entity in existing.remove(&entity).unwrap_or_default() {
+            let Ok((_, seam, _, mut transform)) = seam_colliders.get_mut(seam_entity) else {
+                continue;
+            };
+            if let Some(index) = offsets.iter().position(|offset| *offset == seam.offset) {
+                // Keep the collider in place in world space as the body rotates
+                let offset = offsets.swap_remove(index);
+                let translation = inverse_rotation.rotate(offset).as_f32().extend(0.0);
+                // Avoid triggering change detection unnecessarily
+                if transform.translation != translation {
+                    transform.translation = translation;
+                }
+            } else {
+                commands.entity(seam_entity).despawn_recursive();
+            }
+        }
+
+        for offset in offsets {
+            let translation = inverse_rotation.rotate(offset).as_f32().extend(0.0);
+            let seam_entity = commands
+                .spawn((
+                    SeamCollider { offset },
+                    collider.clone(),
+                    ColliderDensity(0.0),
+                    layers.copied().unwrap_or_default(),
+                    TransformBundle::from_transform(Transform::from_translation(translation)),
+                ))
+                .id();
+            if sensor.is_some() {
+                commands.entity(seam_entity).insert(Sensor);
+            }
+            commands.entity(entity).add_child(seam_entity);
+        }
+    }
+
+    // Remove the seam colliders of bodies that no longer use screen wrapping
+    for seam_entity in existing.into_values().flatten() {
+        commands.entity(seam_entity).despawn_recursive();
+    }
+}
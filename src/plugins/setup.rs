@@ -9,7 +9,7 @@ use bevy::{
 
 use crate::prelude::*;
 
-use super::sync::PreviousGlobalTransform;
+use super::{solver::PenetrationConstraints, sync::PreviousGlobalTransform};
 
 /// Sets up the physics engine by initializing the necessary schedules, sets and resources.
 ///
@@ -55,9 +55,20 @@ impl Default for PhysicsSetupPlugin {
 
 impl Plugin for PhysicsSetupPlugin {
     fn build(&self, app: &mut App) {
+        // Check and store if schedule is configured to run in FixedUpdate.
+        let fixed_update =
+            self.schedule.inner_type_id() == FixedUpdate::inner_type_id(&FixedUpdate);
+
+        // In FixedUpdate, step once per run of the schedule so that physics stays in sync with game logic,
+        // unless another timestep has already been configured. This must happen before the default
+        // timestep is initialized below.
+        if fixed_update && !app.world.contains_resource::<PhysicsTimestep>() {
+            app.insert_resource(PhysicsTimestep::Schedule);
+        }
+
         // Init resources and register component types
         app.init_resource::<PhysicsTimestep>()
-            .init_resource::<PhysicsTimescale>()
+            .init_resource::<PhysicsTime>()
             .init_resource::<DeltaTime>()
             .init_resource::<SubDeltaTime>()
             .init_resource::<SubstepCount>()
@@ -65,8 +76,13 @@ impl Plugin for PhysicsSetupPlugin {
             .init_resource::<SleepingThreshold>()
             .init_resource::<DeactivationTime>()
             .init_resource::<Gravity>()
+            .init_resource::<DefaultFriction>()
+            .init_resource::<DefaultRestitution>()
+            .init_resource::<DeterministicMode>()
+            .init_resource::<BufferShrinkPolicy>()
+            .init_resource::<PhysicsBufferStats>()
             .register_type::<PhysicsTimestep>()
-            .register_type::<PhysicsTimescale>()
+            .register_type::<PhysicsTime>()
             .register_type::<DeltaTime>()
             .register_type::<SubDeltaTime>()
             .register_type::<SubstepCount>()
@@ -75,10 +91,17 @@ impl Plugin for PhysicsSetupPlugin {
             .register_type::<DeactivationTime>()
             .register_type::<PhysicsLoop>()
             .register_type::<Gravity>()
+            .register_type::<DefaultFriction>()
+            .register_type::<DefaultRestitution>()
+            .register_type::<DeterministicMode>()
+            .register_type::<BufferShrinkPolicy>()
+            .register_type::<PhysicsBufferStats>()
+            .register_type::<BufferStats>()
             .register_type::<RigidBody>()
             .register_type::<Sleeping>()
             .register_type::<SleepingDisabled>()
             .register_type::<TimeSleeping>()
+            .register_type::<AssemblySleeping>()
             .register_type::<Position>()
             .register_type::<Rotation>()
             .register_type::<PreviousPosition>()
@@ -91,6 +114,13 @@ impl Plugin for PhysicsSetupPlugin {
             .register_type::<PreSolveAngularVelocity>()
             .register_type::<Restitution>()
             .register_type::<Friction>()
+            .register_type::<AnisotropicFriction>()
+            .register_type::<TireFriction>()
+            .register_type::<Thruster>()
+            .register_type::<AttitudeControl>()
+            .register_type::<TimeDilationVolume>()
+            .register_type::<TimeDilation>()
+            .register_type::<SlipCurve>()
             .register_type::<LinearDamping>()
             .register_type::<AngularDamping>()
             .register_type::<ExternalForce>()
@@ -103,12 +133,50 @@ impl Plugin for PhysicsSetupPlugin {
             .register_type::<Inertia>()
             .register_type::<InverseInertia>()
             .register_type::<CenterOfMass>()
+            .register_type::<CenterOfMassOverride>()
             .register_type::<LockedAxes>()
             .register_type::<CollisionLayers>()
+            .register_type::<CollisionMatrix>()
+            .register_type::<Collider>()
+            .register_type::<ColliderAabb>()
+            .register_type::<ColliderMassProperties>()
             .register_type::<CollidingEntities>()
+            .register_type::<ColliderParent>()
+            .register_type::<ColliderTransform>()
+            .register_type::<ColliderOffset>()
+            .register_type::<ColliderDensity>()
+            .register_type::<CollisionMargin>()
+            .register_type::<ContactNormalOverride>()
+            .register_type::<RollingContact>()
+            .register_type::<RemoteBody>()
+            .register_type::<ConstraintIterations>()
+            .register_type::<JointLimitMonitor>()
             .register_type::<CoefficientCombine>()
             .register_type::<Sensor>();
 
+        #[cfg(feature = "3d")]
+        app.register_type::<TriMeshCollisionFlags>();
+        #[cfg(feature = "2d")]
+        app.register_type::<PolylineCollisionFlags>();
+
+        // Colliders are reflected as opaque values, so they are serialized using their serde representation
+        #[cfg(feature = "serde")]
+        app.register_type_data::<Collider, bevy::reflect::ReflectSerialize>()
+            .register_type_data::<Collider, bevy::reflect::ReflectDeserialize>();
+
+        #[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
+        app.register_type::<AsyncCollider>()
+            .register_type::<ComputedCollider>();
+
+        #[cfg(all(feature = "3d", feature = "async-collider"))]
+        app.register_type::<AsyncSceneCollider>();
+
+        #[cfg(all(feature = "3d", feature = "bone-collider"))]
+        app.register_type::<BoneCollider>()
+            .register_type::<BoneColliderMode>()
+            .register_type::<BoneColliderBlend>()
+            .register_type::<HitReaction>();
+
         // Configure higher level system sets for the given schedule
         let schedule = &self.schedule;
         app.configure_sets(
@@ -122,9 +190,6 @@ impl Plugin for PhysicsSetupPlugin {
                 .before(TransformSystem::TransformPropagate),
         );
 
-        // Check and store if schedule is configured to run in FixedUpdate.
-        let fixed_update = schedule.inner_type_id() == FixedUpdate::inner_type_id(&FixedUpdate);
-
         app.insert_resource(PhysicsLoop {
             fixed_update,
             ..Default::default()
@@ -185,7 +250,13 @@ impl Plugin for PhysicsSetupPlugin {
 
         app.add_systems(
             PhysicsSchedule,
-            run_substep_schedule.in_set(PhysicsStepSet::Substeps),
+            (
+                run_substep_schedule.in_set(PhysicsStepSet::Substeps),
+                update_buffer_stats
+                    .after(PhysicsStepSet::Substeps)
+                    .before(PhysicsStepSet::Sleeping)
+                    .after(super::narrow_phase::wake_up_on_collision_ended),
+            ),
         );
 
         // Create the PostProcessCollisions schedule for user-defined systems
@@ -209,42 +280,74 @@ impl Plugin for PhysicsSetupPlugin {
 }
 
 /// Data related to the physics simulation loop.
+///
+/// Pausing, stepping and the time scale are controlled with the [`PhysicsTime`] resource.
 #[derive(Reflect, Resource, Debug, Default)]
 #[reflect(Resource)]
 pub struct PhysicsLoop {
     /// Time accumulated into the physics loop. This is consumed by the [`PhysicsSchedule`].
     pub accumulator: Scalar,
-    /// Number of steps queued by the user. Time will be added to the accumulator according to the number of queued step.
+    /// Number of steps queued by the user. They are forwarded to [`PhysicsTime::queued_steps`].
+    #[deprecated(since = "0.3.0", note = "use `PhysicsTime::queued_steps`")]
     pub queued_steps: u32,
     /// If [`PhysicsSchedule`] runs in [`FixedUpdate`]. Determines the delta time for the simulation.
     pub(crate) fixed_update: bool,
-    /// Determines if the simulation is paused.
+    /// Determines if the simulation is paused. This is forwarded to and from [`PhysicsTime::paused`].
+    #[deprecated(since = "0.3.0", note = "use `PhysicsTime::paused`")]
     pub paused: bool,
+    /// The value of `paused` that was last forwarded to [`PhysicsTime`].
+    forwarded_paused: bool,
 }
 
+#[allow(deprecated)]
 impl PhysicsLoop {
     /// Add a step to be run on the next run of the [`PhysicsSchedule`].
+    ///
+    /// With an accumulating [`PhysicsTimestep`], all steps queued with this method are run on the next frame,
+    /// while steps queued with [`PhysicsTime::step`] are run one per frame.
+    #[deprecated(since = "0.3.0", note = "use `PhysicsTime::step`")]
     pub fn step(&mut self) {
         self.queued_steps += 1;
     }
+
     /// Pause the simulation.
+    #[deprecated(since = "0.3.0", note = "use `PhysicsTime::pause`")]
     pub fn pause(&mut self) {
         self.paused = true;
     }
+
     /// Resume the simulation.
+    #[deprecated(since = "0.3.0", note = "use `PhysicsTime::resume`")]
     pub fn resume(&mut self) {
         self.paused = false;
     }
+
+    /// Forwards the deprecated pause and step controls to the [`PhysicsTime`].
+    /// Returns the number of steps that were queued using the deprecated controls.
+    fn forward_to(&mut self, physics_time: &mut PhysicsTime) -> u32 {
+        if self.paused != self.forwarded_paused {
+            if self.paused {
+                physics_time.pause();
+            } else {
+                physics_time.resume();
+            }
+        }
+        let queued_steps = std::mem::take(&mut self.queued_steps);
+        physics_time.queued_steps += queued_steps;
+        self.paused = physics_time.paused;
+        self.forwarded_paused = physics_time.paused;
+        queued_steps
+    }
 }
 
 /// Pause the simulation.
-pub fn pause(mut physics_loop: ResMut<PhysicsLoop>) {
-    physics_loop.pause();
+pub fn pause(mut physics_time: ResMut<PhysicsTime>) {
+    physics_time.pause();
 }
 
 /// Resume the simulation.
-pub fn resume(mut physics_loop: ResMut<PhysicsLoop>) {
-    physics_loop.resume();
+pub fn resume(mut physics_time: ResMut<PhysicsTime>) {
+    physics_time.resume();
 }
 
 /// Runs the [`PhysicsSchedule`].
@@ -267,14 +370,30 @@ fn run_physics_schedule(world: &mut World) {
         world.resource::<Time>().delta_seconds_f64()
     };
 
+    // Forward the deprecated time controls
+    #[allow(deprecated)]
+    if world.is_resource_changed::<PhysicsTimescale>() {
+        let time_scale = world.resource::<PhysicsTimescale>().0;
+        world.resource_mut::<PhysicsTime>().time_scale = time_scale;
+    }
+    let deprecated_steps = physics_loop.forward_to(&mut world.resource_mut::<PhysicsTime>());
+
     let time_step = *world.resource::<PhysicsTimestep>();
-    let time_scale = world.resource::<PhysicsTimescale>().0.max(0.0);
+    let physics_time = *world.resource::<PhysicsTime>();
+    let time_scale = physics_time.effective_time_scale();
+
+    // Limit the time that the simulation is advanced by after long frames, so that the simulation
+    // doesn't fall further and further behind trying to catch up
+    if let Some(max_delta) = physics_time.max_delta {
+        delta_seconds = delta_seconds.min(max_delta.max(0.0));
+    }
 
     // Update `DeltaTime` according to the `PhysicsTimestep` configuration
     let (raw_dt, accumulate) = match time_step {
         PhysicsTimestep::Fixed(fixed_delta_seconds) => (fixed_delta_seconds, true),
         PhysicsTimestep::FixedOnce(fixed_delta_seconds) => (fixed_delta_seconds, false),
         PhysicsTimestep::Variable { max_dt } => (delta_seconds.min(max_dt), true),
+        PhysicsTimestep::Schedule => (delta_seconds, false),
     };
 
     // On the first ever call to app.update() delta_seconds would be 0.
@@ -285,36 +404,51 @@ fn run_physics_schedule(world: &mut World) {
     }
 
     let dt = raw_dt * time_scale;
-    world.resource_mut::<DeltaTime>().0 = dt;
 
-    match accumulate {
-        false if physics_loop.paused && physics_loop.queued_steps == 0 => {}
-        false => {
-            if physics_loop.queued_steps > 0 {
-                physics_loop.queued_steps -= 1;
-            }
-            debug!("running PhysicsSchedule");
-            world.run_schedule(PhysicsSchedule);
-        }
-        true => {
-            // Add time to the accumulator
-            if physics_loop.paused {
-                physics_loop.accumulator += dt * physics_loop.queued_steps as Scalar;
-                physics_loop.queued_steps = 0;
-            } else {
-                physics_loop.accumulator += delta_seconds * time_scale;
-            }
+    // Only write the delta time when it changes, so that systems can react to actual changes
+    let mut delta_time = world.resource_mut::<DeltaTime>();
+    if delta_time.0 != dt {
+        delta_time.0 = dt;
+    }
 
-            // Step the simulation until the accumulator has been consumed.
+    // The number of steps to run. While paused, only the queued steps are run, one per run of the
+    // physics schedule, and the accumulator is left untouched so that no time is built up.
+    // Steps queued with the deprecated `PhysicsLoop::step` are all run at once with an accumulating timestep,
+    // like they were before.
+    let steps = if physics_time.paused {
+        let deprecated_steps = if accumulate { deprecated_steps } else { 0 };
+        physics_time.queued_steps.min(1).max(deprecated_steps)
+    } else if accumulate {
+        physics_loop.accumulator += delta_seconds * time_scale;
+        if dt > 0.0 {
             // Note that a small remainder may be passed on to the next run of the physics schedule.
-            while physics_loop.accumulator >= dt && dt > 0.0 {
-                debug!("running PhysicsSchedule");
-                world.run_schedule(PhysicsSchedule);
-                physics_loop.accumulator -= dt;
-            }
+            let steps = (physics_loop.accumulator / dt).floor();
+            physics_loop.accumulator -= steps * dt;
+            steps as u32
+        } else {
+            0
+        }
+    } else {
+        1
+    };
+
+    // Constraints divide by the delta time, so a step with no elapsed time is skipped
+    if dt > 0.0 {
+        for _ in 0..steps {
+            debug!("running PhysicsSchedule");
+            world.run_schedule(PhysicsSchedule);
+            world.resource_mut::<PhysicsTime>().advance(dt);
         }
     }
 
+    // Steps are only queued while paused, so any steps queued while running are discarded
+    let mut time = world.resource_mut::<PhysicsTime>();
+    time.queued_steps = if physics_time.paused {
+        time.queued_steps.saturating_sub(steps)
+    } else {
+        0
+    };
+
     world.insert_resource(physics_loop);
 }
 
@@ -338,3 +472,47 @@ fn run_post_process_collisions_schedule(world: &mut World) {
     debug!("running PostProcessCollisions");
     world.run_schedule(PostProcessCollisions);
 }
+
+/// Records the sizes of the lists that are reused across physics steps in [`PhysicsBufferStats`],
+/// and shrinks the lists according to the [`BufferShrinkPolicy`].
+///
+/// The lists are only accessed mutably when they are shrunk, so that change detection isn't triggered.
+fn update_buffer_stats(
+    policy: Res<BufferShrinkPolicy>,
+    mut stats: ResMut<PhysicsBufferStats>,
+    mut broad_collision_pairs: ResMut<BroadCollisionPairs>,
+    collisions: Option<ResMut<Collisions>>,
+    penetration_constraints: Option<ResMut<PenetrationConstraints>>,
+) {
+    let stats = &mut *stats;
+
+    let pairs = &broad_collision_pairs.0;
+    if let Some(capacity) =
+        stats
+            .broad_collision_pairs
+            .record(pairs.len(), pairs.capacity(), &policy)
+    {
+        broad_collision_pairs.0.shrink_to(capacity);
+        stats.broad_collision_pairs.capacity = broad_collision_pairs.0.capacity();
+    }
+
+    if let Some(mut collisions) = collisions {
+        let map = collisions.get_internal();
+        if let Some(capacity) = stats.collisions.record(map.len(), map.capacity(), &policy) {
+            collisions.get_internal_mut().shrink_to(capacity);
+            stats.collisions.capacity = collisions.get_internal().capacity();
+        }
+    }
+
+    if let Some(mut constraints) = penetration_constraints {
+        let list = &constraints.0;
+        if let Some(capacity) =
+            stats
+                .penetration_constraints
+                .record(list.len(), list.capacity(), &policy)
+        {
+            constraints.0.shrink_to(capacity);
+            stats.penetration_constraints.capacity = constraints.0.capacity();
+        }
+    }
+}
@@ -2,8 +2,16 @@
 //!
 //! See [`SleepingPlugin`].
 
-use crate::prelude::*;
-use bevy::prelude::*;
+use super::islands::UnionFind;
+use crate::{
+    prelude::*,
+    utils::{self, entity_sort_key},
+};
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use parry::bounding_volume::BoundingVolume;
 
 /// Controls when bodies should be deactivated and marked as [`Sleeping`] to improve performance.
 ///
@@ -12,6 +20,10 @@ use bevy::prelude::*;
 ///
 /// Bodies are woken up when an active body or constraint interacts with them, or when gravity changes,
 /// or when the body's position, rotation, velocity, or external forces are changed.
+/// Editing a heightfield collider or replacing the shape of a collider in the background wakes up the bodies near the edited region.
+///
+/// Dynamic bodies that are connected by [joints] form assemblies that sleep and wake up together,
+/// and the sleep state of each assembly is stored in an [`AssemblySleeping`] component on one of its bodies.
 ///
 /// This plugin does *not* handle constraints waking up bodies. That is done by the [solver].
 ///
@@ -20,13 +32,19 @@ pub struct SleepingPlugin;
 
 impl Plugin for SleepingPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<JointAssemblies>();
+
         app.get_schedule_mut(PhysicsSchedule)
             .expect("add PhysicsSchedule first")
             .add_systems(
                 (
+                    update_joint_assemblies,
                     mark_sleeping_bodies,
                     wake_up_bodies,
+                    wake_up_bodies_near_shape_edits,
                     wake_all_sleeping_bodies.run_if(resource_changed::<Gravity>()),
+                    apply_deferred,
+                    wake_up_assemblies,
                 )
                     .chain()
                     .in_set(PhysicsStepSet::Sleeping),
@@ -42,15 +60,116 @@ type SleepingQueryComponents = (
     &'static mut TimeSleeping,
 );
 
+/// The [dynamic](RigidBody::Dynamic) bodies that are connected to each other by [joints].
+/// Only assemblies with more than one body are stored.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct JointAssemblies {
+    /// The bodies in each assembly, ordered by their entities.
+    assemblies: Vec<Vec<Entity>>,
+    /// The index of the assembly of each body.
+    body_assemblies: HashMap<Entity, usize>,
+    /// The bodies connected by each joint when the assemblies were last rebuilt.
+    joints: Vec<[Entity; 2]>,
+}
+
+/// Rebuilds the [`JointAssemblies`] when joints are added, removed or attached to other bodies,
+/// or when the types of bodies change.
+#[allow(clippy::too_many_arguments)]
+fn update_joint_assemblies(
+    bodies: Query<(Entity, &RigidBody)>,
+    changed_bodies: Query<(), Changed<RigidBody>>,
+    mut removed_bodies: RemovedComponents<RigidBody>,
+    fixed_joints: Query<&FixedJoint>,
+    revolute_joints: Query<&RevoluteJoint>,
+    spherical_joints: Query<&SphericalJoint>,
+    prismatic_joints: Query<&PrismaticJoint>,
+    distance_joints: Query<&DistanceJoint>,
+    mut assemblies: ResMut<JointAssemblies>,
+) {
+    // Joints change every step as their Lagrange multipliers and forces are updated,
+    // so only the bodies that they connect are compared
+    let joints = fixed_joints
+        .iter()
+        .map(|joint| joint.entities())
+        .chain(revolute_joints.iter().map(|joint| joint.entities()))
+        .chain(spherical_joints.iter().map(|joint| joint.entities()))
+        .chain(prismatic_joints.iter().map(|joint| joint.entities()))
+        .chain(distance_joints.iter().map(|joint| joint.entities()))
+        .collect::<Vec<_>>();
+
+    let bodies_removed = removed_bodies.iter().count() > 0;
+    if !bodies_removed && changed_bodies.is_empty() && joints == assemblies.joints {
+        return;
+    }
+
+    let assemblies = &mut *assemblies;
+    assemblies.assemblies.clear();
+    assemblies.body_assemblies.clear();
+
+    // The bodies are sorted so that the first body of each assembly is its representative
+    let mut dynamic_bodies = bodies
+        .iter()
+        .filter(|(_, rb)| rb.is_dynamic())
+        .map(|(entity, _)| entity)
+        .collect::<Vec<_>>();
+    dynamic_bodies.sort_unstable_by_key(|entity| entity_sort_key(*entity));
+    let indices: HashMap<Entity, usize> = dynamic_bodies
+        .iter()
+        .enumerate()
+        .map(|(index, entity)| (*entity, index))
+        .collect();
+
+    let mut sets = UnionFind::new(dynamic_bodies.len());
+    for &[entity1, entity2] in joints.iter() {
+        if let (Some(&index1), Some(&index2)) = (indices.get(&entity1), indices.get(&entity2)) {
+            sets.union(index1, index2);
+        }
+    }
+
+    let mut root_bodies: HashMap<usize, Vec<Entity>> = HashMap::default();
+    for (index, entity) in dynamic_bodies.iter().enumerate() {
+        root_bodies
+            .entry(sets.find(index))
+            .or_default()
+            .push(*entity);
+    }
+
+    // Roots are the smallest indices of their sets, so this keeps the assemblies in entity order
+    let mut roots = root_bodies.keys().copied().collect::<Vec<_>>();
+    roots.sort_unstable();
+    for root in roots {
+        let bodies = root_bodies.remove(&root).unwrap_or_default();
+        if bodies.len() < 2 {
+            continue;
+        }
+        for entity in bodies.iter() {
+            assemblies
+                .body_assemblies
+                .insert(*entity, assemblies.assemblies.len());
+        }
+        assemblies.assemblies.push(bodies);
+    }
+
+    assemblies.joints = joints;
+}
+
 /// Adds the [`Sleeping`] component to bodies whose linear and anigular velocities have been
 /// under the [`SleepingThreshold`] for a duration indicated by [`DeactivationTime`].
+///
+/// Bodies in [`JointAssemblies`] are only put to sleep once all bodies in the assembly can sleep.
+#[allow(clippy::too_many_arguments)]
 fn mark_sleeping_bodies(
     mut commands: Commands,
     mut bodies: Query<SleepingQueryComponents, (Without<Sleeping>, Without<SleepingDisabled>)>,
+    sleeping_bodies: Query<(), With<Sleeping>>,
+    assemblies: Res<JointAssemblies>,
     deactivation_time: Res<DeactivationTime>,
     sleep_threshold: Res<SleepingThreshold>,
     dt: Res<DeltaTime>,
 ) {
+    // Bodies in assemblies that could sleep if the rest of their assembly could sleep too
+    let mut ready_bodies = HashSet::new();
+
     for (entity, rb, mut lin_vel, mut ang_vel, mut time_sleeping) in &mut bodies {
         // Only dynamic bodies can sleep.
         if !rb.is_dynamic() {
@@ -78,11 +197,35 @@ fn mark_sleeping_bodies(
 
         // If the body has been still for long enough, set it to sleep and reset velocities.
         if time_sleeping.0 > deactivation_time.0 {
+            if assemblies.body_assemblies.contains_key(&entity) {
+                ready_bodies.insert(entity);
+                continue;
+            }
             commands.entity(entity).insert(Sleeping);
             *lin_vel = LinearVelocity::ZERO;
             *ang_vel = AngularVelocity::ZERO;
         }
     }
+
+    for assembly in assemblies.assemblies.iter() {
+        let can_sleep = assembly
+            .iter()
+            .all(|entity| ready_bodies.contains(entity) || sleeping_bodies.contains(*entity));
+        if !can_sleep {
+            continue;
+        }
+
+        for entity in assembly
+            .iter()
+            .filter(|entity| ready_bodies.contains(*entity))
+        {
+            if let Ok((_, _, mut lin_vel, mut ang_vel, _)) = bodies.get_mut(*entity) {
+                commands.entity(*entity).insert(Sleeping);
+                *lin_vel = LinearVelocity::ZERO;
+                *ang_vel = AngularVelocity::ZERO;
+            }
+        }
+    }
 }
 
 type WokeUpFilter = Or<(
@@ -101,7 +244,10 @@ type WokeUpFilter = Or<(
 /// position, rotation, velocity and external forces are changed.
 fn wake_up_bodies(
     mut commands: Commands,
-    mut bodies: Query<(Entity, &mut TimeSleeping), (With<Sleeping>, WokeUpFilter)>,
+    mut bodies: Query<
+        (Entity, &mut TimeSleeping),
+        (With<Sleeping>, Without<FrozenInRoom>, WokeUpFilter),
+    >,
 ) {
     for (entity, mut time_sleeping) in &mut bodies {
         commands.entity(entity).remove::<Sleeping>();
@@ -109,14 +255,112 @@ fn wake_up_bodies(
     }
 }
 
-/// Removes the [`Sleeping`] component from all sleeping bodies.
+/// Removes the [`Sleeping`] component from sleeping bodies whose colliders overlap a region of a heightfield
+/// that was edited with methods like [`Collider::edit_heightfield_heights`], or the region of a shape
+/// that was replaced in the background. Bodies elsewhere on the shape keep sleeping.
+fn wake_up_bodies_near_shape_edits(
+    mut commands: Commands,
+    mut edited_colliders: Query<
+        (&mut Collider, &Position, &Rotation, Option<&ColliderOffset>),
+        Changed<Collider>,
+    >,
+    colliders: Query<(&ColliderParent, &ColliderAabb)>,
+    mut bodies: Query<&mut TimeSleeping, (With<Sleeping>, Without<FrozenInRoom>)>,
+) {
+    for (mut collider, pos, rot, offset) in &mut edited_colliders {
+        // Taking the edits shouldn't trigger change detection again
+        let Some(region) = collider.bypass_change_detection().take_shape_edits() else {
+            continue;
+        };
+
+        // Transform the edited region to world space
+        let (pos, rot) = offset.map_or((pos.0, *rot), |offset| offset.transform_pose(pos.0, *rot));
+        let region = region.transform_by(&utils::make_isometry(pos, rot));
+
+        for (parent, aabb) in &colliders {
+            if !aabb.intersects(&region) {
+                continue;
+            }
+            if let Ok(mut time_sleeping) = bodies.get_mut(parent.get()) {
+                commands.entity(parent.get()).remove::<Sleeping>();
+                time_sleeping.0 = 0.0;
+            }
+        }
+    }
+}
+
+/// Removes the [`Sleeping`] component from all sleeping bodies except the ones [frozen](FrozenInRoom) in rooms.
 /// Triggered automatically when [`Gravity`] is changed.
 fn wake_all_sleeping_bodies(
     mut commands: Commands,
-    mut bodies: Query<(Entity, &mut TimeSleeping), With<Sleeping>>,
+    mut bodies: Query<(Entity, &mut TimeSleeping), (With<Sleeping>, Without<FrozenInRoom>)>,
 ) {
     for (entity, mut time_sleeping) in &mut bodies {
         commands.entity(entity).remove::<Sleeping>();
         time_sleeping.0 = 0.0;
     }
 }
+
+/// Wakes up the [`JointAssemblies`] that have both sleeping and awake bodies,
+/// and updates the [`AssemblySleeping`] components of their representative bodies.
+///
+/// Bodies [frozen](FrozenInRoom) in rooms are ignored.
+fn wake_up_assemblies(
+    mut commands: Commands,
+    mut bodies: Query<(Option<&Sleeping>, &mut TimeSleeping), Without<FrozenInRoom>>,
+    mut representatives: Query<(Entity, &mut AssemblySleeping)>,
+    assemblies: Res<JointAssemblies>,
+) {
+    for assembly in assemblies.assemblies.iter() {
+        let (body_count, sleeping_count) =
+            assembly
+                .iter()
+                .fold(
+                    (0, 0),
+                    |(body_count, sleeping_count), entity| match bodies.get(*entity) {
+                        Ok((sleeping, _)) => {
+                            (body_count + 1, sleeping_count + sleeping.is_some() as usize)
+                        }
+                        Err(_) => (body_count, sleeping_count),
+                    },
+                );
+        let sleeping = sleeping_count == body_count;
+
+        // Waking up any body wakes up the whole assembly
+        if sleeping_count > 0 && !sleeping {
+            for entity in assembly.iter() {
+                if let Ok((Some(_), mut time_sleeping)) = bodies.get_mut(*entity) {
+                    commands.entity(*entity).remove::<Sleeping>();
+                    time_sleeping.0 = 0.0;
+                }
+            }
+        }
+
+        let representative = assembly[0];
+        match representatives.get_mut(representative) {
+            Ok((_, mut assembly_sleeping)) => {
+                if assembly_sleeping.sleeping != sleeping || assembly_sleeping.bodies != *assembly {
+                    assembly_sleeping.sleeping = sleeping;
+                    assembly_sleeping.bodies = assembly.clone();
+                }
+            }
+            Err(_) => {
+                commands.entity(representative).insert(AssemblySleeping {
+                    sleeping,
+                    bodies: assembly.clone(),
+                });
+            }
+        }
+    }
+
+    // Remove the components of bodies that don't represent an assembly anymore
+    for (entity, _) in &representatives {
+        let is_representative = assemblies
+            .body_assemblies
+            .get(&entity)
+            .is_some_and(|index| assemblies.assemblies[*index][0] == entity);
+        if !is_representative {
+            commands.entity(entity).remove::<AssemblySleeping>();
+        }
+    }
+}
@@ -0,0 +1,382 @@
+//! Saves and restores the dynamic state of the physics world, for example for save games and rollback.
+//!
+//! See [`PhysicsSnapshot`].
+
+use crate::{prelude::*, utils::entity_sort_key};
+use bevy::prelude::*;
+
+/// The number of components in a [`Vector`].
+#[cfg(feature = "2d")]
+const VECTOR_DIM: usize = 2;
+/// The number of components in a [`Vector`].
+#[cfg(feature = "3d")]
+const VECTOR_DIM: usize = 3;
+
+/// The number of scalars used for storing a [`Rotation`].
+#[cfg(feature = "2d")]
+const ROTATION_DIM: usize = 2;
+/// The number of scalars used for storing a [`Rotation`].
+#[cfg(feature = "3d")]
+const ROTATION_DIM: usize = 4;
+
+/// The number of components in an [`AngularVelocity`].
+#[cfg(feature = "2d")]
+const ANGULAR_DIM: usize = 1;
+/// The number of components in an [`AngularVelocity`].
+#[cfg(feature = "3d")]
+const ANGULAR_DIM: usize = 3;
+
+/// A snapshot of the dynamic state of the physics world that can be restored later,
+/// for example for save games or rollback netcode.
+///
+/// A snapshot stores the [state](BodyState) and sleep state of all dynamic and kinematic
+/// [rigid bodies](RigidBody), and the [Lagrange multipliers](Joint::lagrange_multipliers) of [joints].
+/// Static bodies, [colliders](Collider) and the configuration of bodies and joints are not stored:
+/// the snapshot only refers to the existing entities, so collider shapes are never duplicated.
+/// This keeps snapshots small and cheap to take every frame.
+///
+/// Snapshots can be converted into a compact byte payload with [`to_bytes`](#method.to_bytes)
+/// and back with [`from_bytes`](#method.from_bytes). Values are stored with the precision of [`Scalar`],
+/// so payloads created with the `f32` feature can't be read with the `f64` feature and vice versa.
+///
+/// Restoring a snapshot only writes the physics components. [`Transform`]s are updated
+/// by the [`SyncPlugin`] the next time the physics schedules run.
+///
+/// To re-simulate only some of the bodies from a snapshot of their state, see [`Resimulation`].
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// #[derive(Resource)]
+/// struct SaveGame(Vec<u8>);
+///
+/// fn save(world: &mut World) {
+///     let snapshot = PhysicsSnapshot::take(world);
+///     world.insert_resource(SaveGame(snapshot.to_bytes()));
+/// }
+///
+/// fn load(world: &mut World) {
+///     let bytes = &world.resource::<SaveGame>().0;
+///     if let Some(snapshot) = PhysicsSnapshot::from_bytes(bytes) {
+///         snapshot.restore(world);
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PhysicsSnapshot {
+    /// The stored rigid bodies.
+    pub bodies: Vec<BodySnapshot>,
+    /// The stored joints.
+    pub joints: Vec<JointSnapshot>,
+}
+
+/// The stored state of a [rigid body](RigidBody) in a [`PhysicsSnapshot`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BodySnapshot {
+    /// The entity of the body.
+    pub entity: Entity,
+    /// The position, rotation and velocity of the body.
+    pub state: BodyState,
+    /// True if the body is [`Sleeping`].
+    pub sleeping: bool,
+    /// The [`TimeSleeping`] of the body.
+    pub time_sleeping: Scalar,
+}
+
+/// The stored state of a [joint](joints) in a [`PhysicsSnapshot`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct JointSnapshot {
+    /// The entity of the joint.
+    pub entity: Entity,
+    /// The Lagrange multipliers of the joint. See [`Joint::lagrange_multipliers`].
+    pub lagrange_multipliers: Vec<Scalar>,
+}
+
+impl PhysicsSnapshot {
+    /// Takes a snapshot of the current state of the physics world.
+    ///
+    /// Bodies and joints are stored in the order of their entities, so snapshots of identical
+    /// worlds are identical.
+    pub fn take(world: &mut World) -> Self {
+        let mut snapshot = Self::default();
+
+        let mut bodies = world.query::<(
+            Entity,
+            &RigidBody,
+            &Position,
+            &Rotation,
+            &LinearVelocity,
+            &AngularVelocity,
+            Option<&Sleeping>,
+            Option<&TimeSleeping>,
+        )>();
+        for (entity, rb, pos, rot, lin_vel, ang_vel, sleeping, time_sleeping) in bodies.iter(world)
+        {
+            if rb.is_static() {
+                continue;
+            }
+            snapshot.bodies.push(BodySnapshot {
+                entity,
+                state: BodyState::new(*pos, *rot, *lin_vel, *ang_vel),
+                sleeping: sleeping.is_some(),
+                time_sleeping: time_sleeping.map_or(0.0, |time| time.0),
+            });
+        }
+
+        snapshot
+            .bodies
+            .sort_by_key(|body| entity_sort_key(body.entity));
+        snapshot.joints = take_joints(world);
+
+        snapshot
+    }
+
+    /// Restores the stored state into the given world.
+    ///
+    /// Bodies and joints that don't exist anymore are skipped.
+    pub fn restore(&self, world: &mut World) {
+        self.restore_mapped(world, Some);
+    }
+
+    /// Restores the stored state into the given world, using `map_entity` to find the entity
+    /// that corresponds to each stored entity.
+    ///
+    /// This is useful when the snapshot is restored into a different world than the one it was
+    /// taken from, for example after the entities of a save game have been spawned again.
+    /// Stored entities that `map_entity` returns `None` for are skipped.
+    pub fn restore_mapped(&self, world: &mut World, map_entity: impl Fn(Entity) -> Option<Entity>) {
+        for body in self.bodies.iter() {
+            let Some(mut entity_mut) =
+                map_entity(body.entity).and_then(|e| world.get_entity_mut(e))
+            else {
+                continue;
+            };
+            if let Some(mut position) = entity_mut.get_mut::<Position>() {
+                *position = body.state.position;
+            }
+            if let Some(mut rotation) = entity_mut.get_mut::<Rotation>() {
+                *rotation = body.state.rotation;
+            }
+            if let Some(mut linear_velocity) = entity_mut.get_mut::<LinearVelocity>() {
+                *linear_velocity = body.state.linear_velocity;
+            }
+            if let Some(mut angular_velocity) = entity_mut.get_mut::<AngularVelocity>() {
+                *angular_velocity = body.state.angular_velocity;
+            }
+            if let Some(mut time_sleeping) = entity_mut.get_mut::<TimeSleeping>() {
+                time_sleeping.0 = body.time_sleeping;
+            }
+            if body.sleeping {
+                entity_mut.insert(Sleeping);
+            } else {
+                entity_mut.remove::<Sleeping>();
+            }
+        }
+
+        restore_joints(world, &self.joints, map_entity);
+    }
+
+    /// Encodes the snapshot into a compact little-endian byte payload.
+    ///
+    /// Entities are stored using [`Entity::to_bits`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        bytes.extend_from_slice(&(self.bodies.len() as u32).to_le_bytes());
+        for body in self.bodies.iter() {
+            bytes.extend_from_slice(&body.entity.to_bits().to_le_bytes());
+            bytes.push(body.sleeping as u8);
+            for value in body_values(body) {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+
+        bytes.extend_from_slice(&(self.joints.len() as u32).to_le_bytes());
+        for joint in self.joints.iter() {
+            bytes.extend_from_slice(&joint.entity.to_bits().to_le_bytes());
+            bytes.push(joint.lagrange_multipliers.len() as u8);
+            for value in joint.lagrange_multipliers.iter() {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+
+        bytes
+    }
+
+    /// Decodes a snapshot from a byte payload created by [`to_bytes`](#method.to_bytes).
+    ///
+    /// Returns `None` if the payload is invalid.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut reader = ByteReader { bytes };
+        let mut snapshot = Self::default();
+
+        let body_count = u32::from_le_bytes(reader.take()?);
+        for _ in 0..body_count {
+            let entity = Entity::from_bits(u64::from_le_bytes(reader.take()?));
+            let sleeping = match u8::from_le_bytes(reader.take()?) {
+                0 => false,
+                1 => true,
+                _ => return None,
+            };
+            let mut values = [0.0; BODY_VALUE_COUNT];
+            for value in values.iter_mut() {
+                *value = Scalar::from_le_bytes(reader.take()?);
+            }
+            snapshot
+                .bodies
+                .push(body_from_values(entity, sleeping, values));
+        }
+
+        let joint_count = u32::from_le_bytes(reader.take()?);
+        for _ in 0..joint_count {
+            let entity = Entity::from_bits(u64::from_le_bytes(reader.take()?));
+            let multiplier_count = u8::from_le_bytes(reader.take()?);
+            let lagrange_multipliers = (0..multiplier_count)
+                .map(|_| Some(Scalar::from_le_bytes(reader.take()?)))
+                .collect::<Option<Vec<_>>>()?;
+            snapshot.joints.push(JointSnapshot {
+                entity,
+                lagrange_multipliers,
+            });
+        }
+
+        reader.bytes.is_empty().then_some(snapshot)
+    }
+}
+
+/// The number of scalars stored for each body.
+const BODY_VALUE_COUNT: usize = VECTOR_DIM * 2 + ROTATION_DIM + ANGULAR_DIM + 1;
+
+/// Returns the scalars stored for the given body.
+fn body_values(body: &BodySnapshot) -> [Scalar; BODY_VALUE_COUNT] {
+    let state = &body.state;
+    let mut values = [0.0; BODY_VALUE_COUNT];
+
+    #[cfg(feature = "2d")]
+    let (rotation, angular_velocity) = (
+        [state.rotation.sin(), state.rotation.cos()],
+        [state.angular_velocity.0],
+    );
+    #[cfg(feature = "3d")]
+    let (rotation, angular_velocity) =
+        (state.rotation.to_array(), state.angular_velocity.to_array());
+
+    for (value, component) in values.iter_mut().zip(
+        state
+            .position
+            .to_array()
+            .into_iter()
+            .chain(rotation)
+            .chain(state.linear_velocity.to_array())
+            .chain(angular_velocity)
+            .chain([body.time_sleeping]),
+    ) {
+        *value = component;
+    }
+
+    values
+}
+
+/// Creates a [`BodySnapshot`] from the scalars created by [`body_values`].
+fn body_from_values(
+    entity: Entity,
+    sleeping: bool,
+    values: [Scalar; BODY_VALUE_COUNT],
+) -> BodySnapshot {
+    let (position, rest) = values.split_at(VECTOR_DIM);
+    let (rotation, rest) = rest.split_at(ROTATION_DIM);
+    let (linear_velocity, rest) = rest.split_at(VECTOR_DIM);
+    let (angular_velocity, rest) = rest.split_at(ANGULAR_DIM);
+
+    #[cfg(feature = "2d")]
+    let (rotation, angular_velocity) = (
+        Rotation::from_sin_cos(rotation[0], rotation[1]),
+        AngularVelocity(angular_velocity[0]),
+    );
+    #[cfg(feature = "3d")]
+    let (rotation, angular_velocity) = (
+        Rotation(Quaternion::from_slice(rotation)),
+        AngularVelocity(Vector::from_slice(angular_velocity)),
+    );
+
+    BodySnapshot {
+        entity,
+        state: BodyState::new(
+            Position(Vector::from_slice(position)),
+            rotation,
+            LinearVelocity(Vector::from_slice(linear_velocity)),
+            angular_velocity,
+        ),
+        sleeping,
+        time_sleeping: rest[0],
+    }
+}
+
+/// Reads fixed-size chunks from the start of a byte slice.
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ByteReader<'a> {
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        if self.bytes.len() < N {
+            return None;
+        }
+        let (chunk, rest) = self.bytes.split_at(N);
+        self.bytes = rest;
+        chunk.try_into().ok()
+    }
+}
+
+/// Stores the Lagrange multipliers of all [joints] in the order of their entities.
+pub(crate) fn take_joints(world: &mut World) -> Vec<JointSnapshot> {
+    let mut joints = vec![];
+    take_joints_of_type::<FixedJoint>(world, &mut joints);
+    take_joints_of_type::<PrismaticJoint>(world, &mut joints);
+    take_joints_of_type::<DistanceJoint>(world, &mut joints);
+    take_joints_of_type::<RevoluteJoint>(world, &mut joints);
+    take_joints_of_type::<SphericalJoint>(world, &mut joints);
+    joints.sort_by_key(|joint| entity_sort_key(joint.entity));
+    joints
+}
+
+/// Restores the Lagrange multipliers of the given [joints], using `map_entity` to find the entity
+/// that corresponds to each stored entity.
+pub(crate) fn restore_joints(
+    world: &mut World,
+    joints: &[JointSnapshot],
+    map_entity: impl Fn(Entity) -> Option<Entity>,
+) {
+    for joint in joints.iter() {
+        let Some(entity) = map_entity(joint.entity) else {
+            continue;
+        };
+        restore_joint::<FixedJoint>(world, entity, &joint.lagrange_multipliers);
+        restore_joint::<PrismaticJoint>(world, entity, &joint.lagrange_multipliers);
+        restore_joint::<DistanceJoint>(world, entity, &joint.lagrange_multipliers);
+        restore_joint::<RevoluteJoint>(world, entity, &joint.lagrange_multipliers);
+        restore_joint::<SphericalJoint>(world, entity, &joint.lagrange_multipliers);
+    }
+}
+
+fn take_joints_of_type<T: Joint>(world: &mut World, joints: &mut Vec<JointSnapshot>) {
+    let mut query = world.query::<(Entity, &T)>();
+    for (entity, joint) in query.iter(world) {
+        joints.push(JointSnapshot {
+            entity,
+            lagrange_multipliers: joint.lagrange_multipliers(),
+        });
+    }
+}
+
+fn restore_joint<T: Joint>(world: &mut World, entity: Entity, lagrange_multipliers: &[Scalar]) {
+    if let Some(mut joint) = world.get_mut::<T>(entity) {
+        joint.set_lagrange_multipliers(lagrange_multipliers);
+    }
+}
@@ -5,10 +5,16 @@
 
 use crate::{
     prelude::*,
-    utils::{compute_dynamic_friction, compute_restitution},
+    utils::{
+        compute_dynamic_friction, compute_restitution, compute_rolling_friction, entity_sort_key,
+    },
 };
-use bevy::prelude::*;
-use constraints::penetration::PenetrationConstraint;
+#[cfg(feature = "parallel")]
+use bevy::tasks::{ComputeTaskPool, ParallelSliceMut};
+use bevy::{prelude::*, utils::HashMap};
+use constraints::penetration::{PenetrationBatch, PenetrationConstraint};
+#[cfg(feature = "parallel")]
+use std::sync::{Mutex, PoisonError};
 
 /// Solves positional and angular [constraints], updates velocities and solves velocity constraints
 /// (dynamic [friction](Friction) and [restitution](Restitution) and [joint damping](joints#damping)).
@@ -23,17 +29,67 @@ use constraints::penetration::PenetrationConstraint;
 /// 2. **Velocity update**: The velocities of bodies are updated based on positional and rotational changes from the last step.
 /// Runs in [`SubstepSet::UpdateVelocities`].
 ///
-/// 3. **Velocity solve**: Velocity corrections caused by dynamic friction, restitution and joint damping are applied.
+/// 3. **Velocity solve**: Velocity corrections caused by dynamic friction, rolling friction, restitution and joint damping are applied.
 /// Runs in [`SubstepSet::SolveVelocities`].
 ///
 /// In the case of collisions, [`PenetrationConstraint`]s are created for each contact pair.
 /// The constraints are resolved by moving the bodies so that they no longer penetrate.
 /// Then, the velocities are updated, and velocity corrections caused by dynamic friction and restitution are applied.
+///
+/// Contacts that persist across substeps are warm started using the Lagrange multipliers from the previous substep.
+/// This can be configured using the [`SolverConfig`] resource.
+///
+/// ## Simulation islands
+///
+/// Before the constraints are solved, the dynamic bodies are grouped into [`SimulationIslands`] that are connected
+/// by contacts and joints. With the `parallel` feature, the contacts of different islands are solved on separate threads,
+/// which speeds up scenes with many separate piles of bodies. The contacts of large islands are also split into
+/// colors using graph coloring, so that contacts that don't share any dynamic bodies are solved in parallel batches.
+/// Within a batch, the non-penetration constraints of several contacts are solved at once using SIMD-friendly
+/// wide math, even without the `parallel` feature. Joints are solved afterwards on a single thread.
+///
+/// ## Solving order
+///
+/// In [`DeterministicMode`], the order in which constraints are solved only depends on the entities
+/// involved, never on the order in which entities are stored or iterated over, or on how work is split between threads:
+///
+/// - The contacts of each island are solved in the order of the [`Collisions`], which are sorted by their entities.
+/// Pairs of large islands are colored greedily in the same order, the colors are solved one after another,
+/// and pairs that don't fit into any color are solved afterwards in the same order.
+/// - Islands don't share any dynamic bodies, and static and kinematic bodies aren't moved by contacts,
+/// so the order in which different islands are solved, and whether they are solved in parallel, doesn't affect the results.
+/// - Joints of each type are solved in the order of their entities.
+///
+/// This means that the same world produces bit-identical results on the same platform
+/// with and without the `parallel` feature, which is relied on by replays and lockstep multiplayer.
+///
+/// ## Time dilation
+///
+/// The velocities of bodies with a [`TimeDilation`] are relative to their own time scale, so velocities are
+/// computed using the scaled time step, and velocity corrections are computed using the velocities
+/// as seen from outside of the [`TimeDilationVolume`]s. Positional corrections don't depend on time,
+/// so bodies with different time scales can interact through contacts and joints consistently.
+/// The compliance of joints uses the time step of the slowest dynamic body in the joint.
 pub struct SolverPlugin;
 
 impl Plugin for SolverPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<PenetrationConstraints>();
+        app.init_resource::<PenetrationConstraints>()
+            .init_resource::<SimulationIslands>()
+            .init_resource::<SolverConfig>()
+            .register_type::<SimulationIslands>()
+            .register_type::<SolverConfig>()
+            .register_type::<FrictionModel>()
+            .register_type::<FixedJoint>()
+            .register_type::<RevoluteJoint>()
+            .register_type::<SphericalJoint>()
+            .register_type::<PrismaticJoint>()
+            .register_type::<DistanceJoint>()
+            .register_type::<TrailerHitch>()
+            .register_type::<DistanceLimit>()
+            .register_type::<AngleLimit>()
+            .register_type::<JointLimitViolated>()
+            .add_event::<JointLimitViolated>();
 
         let substeps = app
             .get_schedule_mut(SubstepSchedule)
@@ -41,17 +97,29 @@ impl Plugin for SolverPlugin {
 
         substeps.add_systems(
             (
+                islands::update_simulation_islands,
                 penetration_constraints,
                 solve_constraint::<FixedJoint, 2>,
                 solve_constraint::<RevoluteJoint, 2>,
                 solve_constraint::<SphericalJoint, 2>,
                 solve_constraint::<PrismaticJoint, 2>,
                 solve_constraint::<DistanceJoint, 2>,
+                solve_remote_bodies,
             )
                 .chain()
                 .in_set(SubstepSet::SolveConstraints),
         );
 
+        substeps.add_systems(
+            (
+                monitor_joint_limits::<RevoluteJoint>,
+                monitor_joint_limits::<SphericalJoint>,
+            )
+                .chain()
+                .after(SubstepSet::SolveUserConstraints)
+                .before(SubstepSet::UpdateVelocities),
+        );
+
         substeps.add_systems((update_lin_vel, update_ang_vel).in_set(SubstepSet::UpdateVelocities));
 
         substeps.add_systems(
@@ -67,77 +135,728 @@ impl Plugin for SolverPlugin {
                 .in_set(SubstepSet::SolveVelocities),
         );
 
+        #[cfg(feature = "3d")]
+        substeps.add_systems(
+            stabilize_hitches
+                .after(joint_damping::<DistanceJoint>)
+                .in_set(SubstepSet::SolveVelocities),
+        );
+
         substeps.add_systems(apply_translation.in_set(SubstepSet::ApplyTranslation));
     }
 }
 
+/// A resource for configuring the [solver](SolverPlugin).
+#[derive(Resource, Reflect, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Resource)]
+pub struct SolverConfig {
+    /// The coefficient in the `[0, 1]` range that the Lagrange multipliers of persisting contacts
+    /// from the previous substep are scaled by when warm starting [`PenetrationConstraint`]s.
+    ///
+    /// Warm starting applies the previous corrections as an initial guess instead of starting from zero.
+    /// This makes resting contacts and stacks more stable, especially with a low [`SubstepCount`].
+    /// Zero disables warm starting. One by default.
+    pub warm_start_coefficient: Scalar,
+    /// The relative normal speed below which [restitution](Restitution) is ignored for contacts.
+    ///
+    /// This makes slightly bouncy bodies come to rest instead of bouncing on the ground indefinitely.
+    /// Zero by default, so all contacts bounce.
+    pub restitution_threshold: Scalar,
+    /// How dynamic [friction](Friction) is applied to sliding contacts. See [`FrictionModel`].
+    pub friction_model: FrictionModel,
+    /// The number of times that constraints solved with [`solve_constraint`], like [joints], are solved per substep.
+    ///
+    /// More iterations make the constraints stiffer at the cost of performance. This can be overridden
+    /// for specific constraints using the [`ConstraintIterations`] component. One by default.
+    ///
+    /// The number of substeps is configured using the [`SubstepCount`] resource.
+    /// Increasing it improves the quality of the whole simulation, including contacts, but it is more expensive.
+    ///
+    /// Both can be changed at runtime.
+    pub constraint_iterations: u32,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self {
+            warm_start_coefficient: 1.0,
+            restitution_threshold: 0.0,
+            friction_model: FrictionModel::default(),
+            constraint_iterations: 1,
+        }
+    }
+}
+
+/// How dynamic [friction](Friction) is applied to sliding contacts in the velocity solve.
+///
+/// In 2D, contacts only have one tangent direction, so all of the models behave the same.
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FrictionModel {
+    /// Friction is applied along the single direction that the contact is sliding in.
+    ///
+    /// This is the cheapest model, but the impulse doesn't account for bodies resisting motion
+    /// differently in different directions, which can bend the path of bodies sliding diagonally.
+    #[default]
+    SingleTangent,
+    /// Friction is applied separately along two perpendicular tangent directions,
+    /// and the impulse along each direction is limited by the friction coefficient.
+    ///
+    /// The friction limit forms a pyramid, so bodies sliding diagonally to the tangent directions
+    /// can get up to `√2` times more friction.
+    Pyramid,
+    /// Friction is computed along two perpendicular tangent directions, and the combined impulse
+    /// is projected onto the friction cone, so the friction limit is the same in all directions.
+    Cone,
+}
+
 /// Stores penetration constraints for colliding entity pairs.
 #[derive(Resource, Debug, Default)]
 pub struct PenetrationConstraints(pub Vec<PenetrationConstraint>);
 
+/// A pair of colliding colliders whose contacts are solved by [`penetration_constraints`].
+///
+/// The contact manifolds are moved out of the [`Collisions`] while the pair is solved,
+/// and moved back afterwards with [`ContactPair::return_contacts`].
+struct ContactPair {
+    /// The index of the [`Contacts`] of the pair in the [`Collisions`].
+    index: usize,
+    /// The contact manifolds of the pair.
+    manifolds: Vec<ContactManifold>,
+    /// True if any of the contacts is penetrating.
+    penetrating: bool,
+    body1: Entity,
+    body2: Entity,
+    /// The transform of the first collider relative to its body, including its local offset.
+    collider_transform1: ColliderTransform,
+    /// The transform of the second collider relative to its body, including its local offset.
+    collider_transform2: ColliderTransform,
+    normal_override1: Option<ContactNormalOverride>,
+    normal_override2: Option<ContactNormalOverride>,
+    /// True if either collider or body is a [`RollingContact`].
+    rolling: bool,
+    /// The surface properties of the first body.
+    material1: ContactMaterial,
+    /// The surface properties of the second body.
+    material2: ContactMaterial,
+}
+
+/// The lists that [`penetration_constraints`] collects [`ContactPair`]s into.
+///
+/// The lists are kept empty between substeps, so that their memory can be reused instead of
+/// allocating new lists every substep.
+#[derive(Default)]
+struct ContactPairBuffers {
+    island_pairs: Vec<Vec<ContactPair>>,
+    other_pairs: Vec<ContactPair>,
+    colors: Vec<Vec<ContactPair>>,
+    body_colors: HashMap<Entity, u64>,
+    #[cfg(feature = "parallel")]
+    locks: HashMap<Entity, Mutex<()>>,
+}
+
 /// Iterates through broad phase collision pairs, checks which ones are actually colliding, and uses [`PenetrationConstraint`]s to resolve the collisions.
+///
+/// The constraints are created between the [rigid bodies](RigidBody) that the colliding colliders are attached to,
+/// so contacts are first transformed from the local space of the colliders to the local space of the bodies
+/// using [`ColliderTransform`].
+///
+/// The contacts are grouped by the [`SimulationIslands`] of the bodies. With the `parallel` feature,
+/// the islands are solved on separate threads.
 #[allow(clippy::too_many_arguments)]
 #[allow(clippy::type_complexity)]
 fn penetration_constraints(
     mut commands: Commands,
-    mut bodies: Query<(RigidBodyQuery, Option<&Sensor>, Option<&Sleeping>)>,
+    mut bodies: ContactBodyQuery,
+    colliders: Query<(
+        &ColliderParent,
+        Option<&ColliderTransform>,
+        Option<&ColliderOffset>,
+        Option<&Sensor>,
+        Option<&AnisotropicFriction>,
+    )>,
+    normal_overrides: Query<&ContactNormalOverride>,
+    rolling_contacts: Query<(), With<RollingContact>>,
+    materials: Query<ContactMaterialQuery>,
+    islands: Res<SimulationIslands>,
     mut penetration_constraints: ResMut<PenetrationConstraints>,
     mut collisions: ResMut<Collisions>,
+    solver_config: Res<SolverConfig>,
     sub_dt: Res<SubDeltaTime>,
+    mut buffers: Local<ContactPairBuffers>,
 ) {
     penetration_constraints.0.clear();
 
-    for ((entity1, entity2), contacts) in collisions
+    // The contact pairs of each island. Pairs that don't belong to a single island,
+    // like contacts between kinematic bodies, are solved afterwards.
+    // Large islands are split into colors of pairs that don't share any dynamic bodies.
+    let ContactPairBuffers {
+        island_pairs,
+        other_pairs,
+        colors,
+        body_colors,
+        #[cfg(feature = "parallel")]
+        locks,
+    } = &mut *buffers;
+    island_pairs.resize_with(islands.len(), Vec::new);
+
+    for (index, ((entity1, entity2), contacts)) in collisions
         .get_internal_mut()
         .iter_mut()
-        .filter(|(_, contacts)| contacts.during_current_substep)
+        .enumerate()
+        .filter(|(_, (_, contacts))| contacts.during_current_substep)
     {
         // Reset penetration state for this substep.
         // This is set to true if any of the contacts is penetrating.
         contacts.during_current_substep = false;
 
-        if let Ok([bundle1, bundle2]) = bodies.get_many_mut([*entity1, *entity2]) {
-            let (mut body1, sensor1, sleeping1) = bundle1;
-            let (mut body2, sensor2, sleeping2) = bundle2;
+        let Ok(
+            [(parent1, collider_transform1, offset1, sensor1, anisotropic_friction1), (parent2, collider_transform2, offset2, sensor2, anisotropic_friction2)],
+        ) = colliders.get_many([*entity1, *entity2])
+        else {
+            continue;
+        };
 
-            let inactive1 = body1.rb.is_static() || sleeping1.is_some();
-            let inactive2 = body2.rb.is_static() || sleeping2.is_some();
+        // Create and solve constraint only if both colliders are solid
+        if parent1 == parent2 || sensor1.is_some() || sensor2.is_some() {
+            continue;
+        }
 
-            // No collision if one of the bodies is static and the other one is sleeping.
-            if inactive1 && inactive2 {
-                continue;
-            }
+        let Ok([(body1, sleeping1), (body2, sleeping2)]) =
+            bodies.get_many([parent1.get(), parent2.get()])
+        else {
+            continue;
+        };
+
+        let inactive1 = body1.rb.is_static() || sleeping1.is_some();
+        let inactive2 = body2.rb.is_static() || sleeping2.is_some();
+
+        // No collision if one of the bodies is static and the other one is sleeping.
+        if inactive1 && inactive2 {
+            continue;
+        }
+
+        // When an active body collides with a sleeping body, wake up the sleeping body
+        if sleeping1.is_some() {
+            commands.entity(body1.entity).remove::<Sleeping>();
+        } else if sleeping2.is_some() {
+            commands.entity(body2.entity).remove::<Sleeping>();
+        }
+
+        // Get the transforms of the colliders relative to the bodies, including local offsets
+        let collider_transform1 = collider_transform1.copied().unwrap_or_default();
+        let collider_transform1 =
+            offset1.map_or(collider_transform1, |o| collider_transform1.offset_by(o));
+        let collider_transform2 = collider_transform2.copied().unwrap_or_default();
+        let collider_transform2 =
+            offset2.map_or(collider_transform2, |o| collider_transform2.offset_by(o));
+
+        // Normal overrides can be on the colliders or on the bodies
+        let normal_override1 = normal_overrides
+            .get(*entity1)
+            .or_else(|_| normal_overrides.get(parent1.get()))
+            .ok()
+            .copied();
+        let normal_override2 = normal_overrides
+            .get(*entity2)
+            .or_else(|_| normal_overrides.get(parent2.get()))
+            .ok()
+            .copied();
+
+        let pair = ContactPair {
+            index,
+            manifolds: std::mem::take(&mut contacts.manifolds),
+            penetrating: false,
+            body1: body1.entity,
+            body2: body2.entity,
+            collider_transform1,
+            collider_transform2,
+            normal_override1,
+            normal_override2,
+            rolling: [*entity1, parent1.get(), *entity2, parent2.get()]
+                .into_iter()
+                .any(|entity| rolling_contacts.contains(entity)),
+            // Gather the surface properties once for all contacts of the pair,
+            // so that the bodies only need to be accessed for their poses and velocities
+            material1: materials
+                .get(body1.entity)
+                .map_or(ContactMaterial::default(), |material| material.material())
+                .with_collider_anisotropic_friction(anisotropic_friction1, &collider_transform1),
+            material2: materials
+                .get(body2.entity)
+                .map_or(ContactMaterial::default(), |material| material.material())
+                .with_collider_anisotropic_friction(anisotropic_friction2, &collider_transform2),
+        };
+
+        let island = match (body1.rb.is_dynamic(), body2.rb.is_dynamic()) {
+            (true, true) => islands
+                .island_of(body1.entity)
+                .filter(|island| islands.island_of(body2.entity) == Some(*island)),
+            (true, false) => islands.island_of(body1.entity),
+            (false, true) => islands.island_of(body2.entity),
+            (false, false) => None,
+        };
+
+        match island {
+            Some(island) => island_pairs[island].push(pair),
+            None => other_pairs.push(pair),
+        }
+    }
 
-            // Create and solve constraint if both colliders are solid
-            if sensor1.is_none() && sensor2.is_none() {
-                // When an active body collides with a sleeping body, wake up the sleeping body
-                if sleeping1.is_some() {
-                    commands.entity(*entity1).remove::<Sleeping>();
-                } else if sleeping2.is_some() {
-                    commands.entity(*entity2).remove::<Sleeping>();
+    // Large islands are split into colors of pairs that don't share any dynamic bodies, so that the contacts
+    // of several pairs can be solved at once with wide math, and with the `parallel` feature, on several threads.
+    // The pairs of large islands are moved into the colors, leaving only the pairs of small islands behind.
+    for pairs in island_pairs
+        .iter_mut()
+        .filter(|pairs| pairs.len() >= MIN_COLORED_ISLAND_PAIRS)
+    {
+        color_contact_pairs(pairs.drain(..), &islands, body_colors, colors, other_pairs);
+    }
+
+    #[cfg(feature = "parallel")]
+    {
+        // Static and kinematic bodies can be in contact with bodies in several islands and colors,
+        // so they are locked while a contact with them is being solved
+        locks.clear();
+        locks.extend(
+            island_pairs
+                .iter()
+                .chain(colors.iter())
+                .flatten()
+                .flat_map(|pair| [pair.body1, pair.body2])
+                .filter(|entity| islands.island_of(*entity).is_none())
+                .map(|entity| (entity, Mutex::new(()))),
+        );
+        let locks = &*locks;
+
+        let pool = ComputeTaskPool::get();
+        let bodies = &bodies;
+
+        // Solve the small islands, each on a single thread
+        let new_constraints = island_pairs
+            .par_splat_map_mut(pool, None, |small_islands| {
+                // SAFETY: Each dynamic body is in exactly one island, and the pairs of an island
+                // are only solved by one task, so no other task accesses the dynamic bodies of the pairs.
+                let mut bodies = unsafe { LockedContactBodies::new(bodies, locks) };
+                let mut constraints = vec![];
+                for pairs in small_islands {
+                    solve_contact_pairs(
+                        pairs,
+                        &mut bodies,
+                        &solver_config,
+                        sub_dt.0,
+                        &mut constraints,
+                    );
                 }
+                constraints
+            })
+            .into_iter()
+            .flatten();
+        penetration_constraints.0.extend(new_constraints);
 
-                for contact_manifold in contacts.manifolds.iter() {
-                    for contact in contact_manifold.contacts.iter() {
-                        let mut constraint = PenetrationConstraint::new(&body1, &body2, *contact);
-                        constraint.solve([&mut body1, &mut body2], sub_dt.0);
-                        penetration_constraints.0.push(constraint);
-
-                        // Set collision as penetrating for this frame and substep.
-                        // This is used for detecting when the collision has started or ended.
-                        if contact.penetration > Scalar::EPSILON {
-                            contacts.during_current_frame = true;
-                            contacts.during_current_substep = true;
-                        }
+        // Solve the colors one after the other, splitting the pairs of each color between threads
+        for pairs in colors.iter_mut() {
+            let new_constraints = pairs
+                .par_splat_map_mut(pool, None, |pairs| {
+                    // SAFETY: The pairs of a color don't share any dynamic bodies, and the colors
+                    // of other islands are solved at a different time, so no other task accesses
+                    // the dynamic bodies of the pairs.
+                    let mut bodies = unsafe { LockedContactBodies::new(bodies, locks) };
+                    let mut constraints = vec![];
+                    for batch in pairs.chunks_mut(LANES) {
+                        solve_contact_pair_batch(
+                            batch,
+                            &mut bodies,
+                            &solver_config,
+                            sub_dt.0,
+                            &mut constraints,
+                        );
                     }
+                    constraints
+                })
+                .into_iter()
+                .flatten();
+            penetration_constraints.0.extend(new_constraints);
+        }
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        for batch in colors.iter_mut().flat_map(|pairs| pairs.chunks_mut(LANES)) {
+            solve_contact_pair_batch(
+                batch,
+                &mut bodies,
+                &solver_config,
+                sub_dt.0,
+                &mut penetration_constraints.0,
+            );
+        }
+        other_pairs.extend(island_pairs.iter_mut().flat_map(|pairs| pairs.drain(..)));
+    }
+
+    solve_contact_pairs(
+        other_pairs,
+        &mut bodies,
+        &solver_config,
+        sub_dt.0,
+        &mut penetration_constraints.0,
+    );
+
+    // Move the contacts back into the collisions, keeping the memory of the lists for the next substep
+    for pair in island_pairs
+        .iter_mut()
+        .chain(colors.iter_mut())
+        .chain(std::iter::once(&mut *other_pairs))
+        .flat_map(|pairs| pairs.drain(..))
+    {
+        pair.return_contacts(&mut collisions);
+    }
+}
+
+/// The number of contact pairs at which the pairs of an island are split into colors,
+/// whose contacts are solved in batches with wide math, and in parallel with the `parallel` feature.
+const MIN_COLORED_ISLAND_PAIRS: usize = 128;
+
+/// Splits the contact pairs of an island into colors where no two pairs share a dynamic body,
+/// so that the pairs of each color can be solved at once.
+///
+/// The colors are assigned greedily, and color `i` of every island is added to `colors[i]`.
+/// Bodies can have at most 64 colors, so pairs that don't fit into any color are added to `overflow`.
+///
+/// `body_colors` is used to store the colors of the pairs of each dynamic body as a bit mask.
+fn color_contact_pairs(
+    pairs: impl IntoIterator<Item = ContactPair>,
+    islands: &SimulationIslands,
+    body_colors: &mut HashMap<Entity, u64>,
+    colors: &mut Vec<Vec<ContactPair>>,
+    overflow: &mut Vec<ContactPair>,
+) {
+    body_colors.clear();
+
+    for pair in pairs {
+        let dynamic_bodies = [pair.body1, pair.body2]
+            .into_iter()
+            .filter(|entity| islands.island_of(*entity).is_some());
+        let used_colors = dynamic_bodies.clone().fold(0, |used_colors, entity| {
+            used_colors | body_colors.get(&entity).copied().unwrap_or(0)
+        });
+
+        // Use the first color that neither body is using yet
+        let color = (!used_colors).trailing_zeros() as usize;
+        if color >= 64 {
+            overflow.push(pair);
+            continue;
+        }
+
+        for entity in dynamic_bodies {
+            *body_colors.entry(entity).or_default() |= 1 << color;
+        }
+        if colors.len() <= color {
+            colors.resize_with(color + 1, Vec::new);
+        }
+        colors[color].push(pair);
+    }
+}
+
+/// The bodies of contact pairs and whether they are sleeping.
+type ContactBodyQuery<'w, 's> = Query<'w, 's, (RigidBodyQuery, Option<&'static Sleeping>)>;
+
+/// Mutable access to the bodies of contact pairs while their contacts are being solved.
+trait ContactBodies {
+    /// Calls `solve` with the bodies with the given entities, if both of them exist.
+    fn with_bodies(
+        &mut self,
+        entity1: Entity,
+        entity2: Entity,
+        solve: impl for<'w> FnOnce(&mut RigidBodyQueryItem<'w>, &mut RigidBodyQueryItem<'w>),
+    );
+}
+
+impl ContactBodies for ContactBodyQuery<'_, '_> {
+    fn with_bodies(
+        &mut self,
+        entity1: Entity,
+        entity2: Entity,
+        solve: impl for<'w> FnOnce(&mut RigidBodyQueryItem<'w>, &mut RigidBodyQueryItem<'w>),
+    ) {
+        if let Ok([(mut body1, _), (mut body2, _)]) = self.get_many_mut([entity1, entity2]) {
+            solve(&mut body1, &mut body2);
+        }
+    }
+}
+
+/// Accesses the bodies of contact pairs using [`Query::get_unchecked`] so that the contacts
+/// can be solved on several threads. Bodies that aren't dynamic are locked while they are accessed.
+#[cfg(feature = "parallel")]
+struct LockedContactBodies<'a, 'w, 's> {
+    bodies: &'a ContactBodyQuery<'w, 's>,
+    locks: &'a HashMap<Entity, Mutex<()>>,
+}
+
+#[cfg(feature = "parallel")]
+impl<'a, 'w, 's> LockedContactBodies<'a, 'w, 's> {
+    /// Creates a new [`LockedContactBodies`] that locks the bodies that have a lock in `locks`.
+    ///
+    /// # Safety
+    ///
+    /// While the returned value is in use, no other thread may access the dynamic bodies that it accesses.
+    unsafe fn new(
+        bodies: &'a ContactBodyQuery<'w, 's>,
+        locks: &'a HashMap<Entity, Mutex<()>>,
+    ) -> Self {
+        Self { bodies, locks }
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl ContactBodies for LockedContactBodies<'_, '_, '_> {
+    fn with_bodies(
+        &mut self,
+        entity1: Entity,
+        entity2: Entity,
+        solve: impl for<'w> FnOnce(&mut RigidBodyQueryItem<'w>, &mut RigidBodyQueryItem<'w>),
+    ) {
+        // A pair has at most one body that isn't dynamic, since pairs of two
+        // non-dynamic bodies don't belong to any island
+        let _lock = [entity1, entity2]
+            .iter()
+            .find_map(|entity| self.locks.get(entity))
+            .map(|lock| lock.lock().unwrap_or_else(PoisonError::into_inner));
+
+        // SAFETY: The creator of `self` guarantees that no other thread accesses the dynamic bodies,
+        // the other body is locked above, and the bodies of a pair are different.
+        let (Ok((mut body1, _)), Ok((mut body2, _))) = (unsafe {
+            (
+                self.bodies.get_unchecked(entity1),
+                self.bodies.get_unchecked(entity2),
+            )
+        }) else {
+            return;
+        };
+
+        solve(&mut body1, &mut body2);
+    }
+}
+
+/// Solves the contacts of the given contact pairs one pair at a time.
+fn solve_contact_pairs(
+    pairs: &mut [ContactPair],
+    bodies: &mut impl ContactBodies,
+    solver_config: &SolverConfig,
+    sub_dt: Scalar,
+    penetration_constraints: &mut Vec<PenetrationConstraint>,
+) {
+    for pair in pairs {
+        bodies.with_bodies(pair.body1, pair.body2, |body1, body2| {
+            solve_contact_pair(
+                pair,
+                body1,
+                body2,
+                solver_config,
+                sub_dt,
+                penetration_constraints,
+            );
+        });
+    }
+}
+
+/// Solves the contacts of up to [`LANES`] contact pairs that don't share any dynamic bodies.
+///
+/// The contacts of each pair are solved one after the other, but the non-penetration constraints
+/// of one contact from each pair are solved at once with wide math using a [`PenetrationBatch`].
+fn solve_contact_pair_batch(
+    pairs: &mut [ContactPair],
+    bodies: &mut impl ContactBodies,
+    solver_config: &SolverConfig,
+    sub_dt: Scalar,
+    penetration_constraints: &mut Vec<PenetrationConstraint>,
+) {
+    debug_assert!(pairs.len() <= LANES);
+
+    let contact_indices = pairs
+        .iter()
+        .map(|pair| pair.contact_indices())
+        .collect::<Vec<_>>();
+    let max_contacts = contact_indices.iter().map(Vec::len).max().unwrap_or(0);
+
+    for step in 0..max_contacts {
+        let mut batch = PenetrationBatch::default();
+        let mut constraints = [None; LANES];
+
+        // Create the constraints and gather their contacts into the batch
+        for (lane, pair) in pairs.iter_mut().enumerate() {
+            let Some(&index) = contact_indices[lane].get(step) else {
+                continue;
+            };
+            bodies.with_bodies(pair.body1, pair.body2, |body1, body2| {
+                let mut constraint =
+                    pair.prepare_constraint(index, body1, body2, solver_config, sub_dt);
+                batch.set_lane(lane, &mut constraint, body1, body2);
+                constraints[lane] = Some(constraint);
+            });
+        }
+
+        let delta_lagrange = batch.compute_lagrange_updates(sub_dt);
+
+        // Apply the corrections and solve friction for each contact
+        for (lane, pair) in pairs.iter_mut().enumerate() {
+            let Some(mut constraint) = constraints[lane] else {
+                continue;
+            };
+            bodies.with_bodies(pair.body1, pair.body2, |body1, body2| {
+                if batch.is_active(lane) {
+                    constraint.apply_batch_update(body1, body2, delta_lagrange[lane], sub_dt);
                 }
-            }
+                pair.finish_constraint(contact_indices[lane][step], &constraint, body1, sub_dt);
+            });
+            penetration_constraints.push(constraint);
+        }
+    }
+}
+
+/// Creates and solves [`PenetrationConstraint`]s for the contacts of a pair of colliders
+/// and stores the Lagrange multipliers for warm starting.
+fn solve_contact_pair<'w>(
+    pair: &mut ContactPair,
+    body1: &mut RigidBodyQueryItem<'w>,
+    body2: &mut RigidBodyQueryItem<'w>,
+    solver_config: &SolverConfig,
+    sub_dt: Scalar,
+    penetration_constraints: &mut Vec<PenetrationConstraint>,
+) {
+    for index in pair.contact_indices() {
+        let mut constraint = pair.prepare_constraint(index, body1, body2, solver_config, sub_dt);
+        constraint.solve([&mut *body1, &mut *body2], sub_dt);
+        pair.finish_constraint(index, &constraint, body1, sub_dt);
+        penetration_constraints.push(constraint);
+    }
+}
+
+impl ContactPair {
+    /// Returns the indices of the manifolds and contacts of all contacts in the pair.
+    fn contact_indices(&self) -> Vec<(usize, usize)> {
+        self.manifolds
+            .iter()
+            .enumerate()
+            .flat_map(|(manifold_index, manifold)| {
+                (0..manifold.contacts.len())
+                    .map(move |contact_index| (manifold_index, contact_index))
+            })
+            .collect()
+    }
+
+    /// Creates a [`PenetrationConstraint`] for the contact with the given indices and warm starts it.
+    fn prepare_constraint(
+        &self,
+        (manifold_index, contact_index): (usize, usize),
+        body1: &mut RigidBodyQueryItem,
+        body2: &mut RigidBodyQueryItem,
+        solver_config: &SolverConfig,
+        sub_dt: Scalar,
+    ) -> PenetrationConstraint {
+        let contact = &self.manifolds[manifold_index].contacts[contact_index];
+
+        // Transform the contact from the local space of the colliders
+        // to the local space of the bodies
+        let mut body_contact = ContactData {
+            point1: self.collider_transform1.transform_point(contact.point1),
+            point2: self.collider_transform2.transform_point(contact.point2),
+            normal1: self
+                .collider_transform1
+                .transform_direction(contact.normal1),
+            normal2: self
+                .collider_transform2
+                .transform_direction(contact.normal2),
+            tangent1: self
+                .collider_transform1
+                .transform_direction(contact.tangent1),
+            ..*contact
+        };
+
+        if self.normal_override1.is_some() || self.normal_override2.is_some() {
+            override_contact_normal(
+                &mut body_contact,
+                (&*body1.rotation, self.normal_override1.as_ref()),
+                (&*body2.rotation, self.normal_override2.as_ref()),
+            );
+        }
+
+        let mut constraint = PenetrationConstraint::new(body1, body2, body_contact);
+        constraint.rolling = self.rolling;
+        constraint.material1 = self.material1;
+        constraint.material2 = self.material2;
+        constraint.warm_start(body1, body2, solver_config.warm_start_coefficient, sub_dt);
+        constraint
+    }
+
+    /// Stores the Lagrange multipliers of the solved constraint of the contact with the given indices
+    /// for warm starting the contact on the next substep.
+    fn finish_constraint(
+        &mut self,
+        (manifold_index, contact_index): (usize, usize),
+        constraint: &PenetrationConstraint,
+        body1: &RigidBodyQueryItem,
+        sub_dt: Scalar,
+    ) {
+        let contact = &mut self.manifolds[manifold_index].contacts[contact_index];
+
+        let (tangent_lagrange, tangent1) =
+            constraint.static_friction_lagrange(&body1.rotation, sub_dt);
+        contact.normal_lagrange = constraint.normal_lagrange;
+        contact.tangent_lagrange = tangent_lagrange;
+        contact.tangent1 = self.collider_transform1.rotation.inverse().rotate(tangent1);
+
+        // Set collision as penetrating for this frame and substep.
+        // This is used for detecting when the collision has started or ended.
+        if contact.penetration > Scalar::EPSILON {
+            self.penetrating = true;
+        }
+    }
+
+    /// Moves the contact manifolds of the pair back into the [`Collisions`] and marks the collision
+    /// as penetrating if any of the contacts is penetrating.
+    fn return_contacts(self, collisions: &mut Collisions) {
+        let Some((_, contacts)) = collisions.get_internal_mut().get_index_mut(self.index) else {
+            return;
+        };
+        contacts.manifolds = self.manifolds;
+        if self.penetrating {
+            contacts.during_current_frame = true;
+            contacts.during_current_substep = true;
         }
     }
 }
 
+/// Changes the normals of a contact in the local space of the bodies using their [`ContactNormalOverride`]s.
+///
+/// The first body is pushed along the second normal and the second body along the first normal,
+/// so the overrides are applied to the normals in those directions.
+fn override_contact_normal(
+    contact: &mut ContactData,
+    (rotation1, normal_override1): (&Rotation, Option<&ContactNormalOverride>),
+    (rotation2, normal_override2): (&Rotation, Option<&ContactNormalOverride>),
+) {
+    let mut normal = rotation1.rotate(contact.normal1);
+
+    if let Some(normal_override) = normal_override1 {
+        normal = -normal_override.apply_to(-normal);
+    }
+    if let Some(normal_override) = normal_override2 {
+        normal = normal_override.apply_to(normal);
+    }
+
+    // Keep the friction direction perpendicular to the new normal
+    let tangent = rotation1.rotate(contact.tangent1);
+    let tangent = (tangent - normal * tangent.dot(normal)).normalize_or_zero();
+
+    contact.normal1 = rotation1.inverse().rotate(normal);
+    contact.normal2 = rotation2.inverse().rotate(-normal);
+    contact.tangent1 = rotation1.inverse().rotate(tangent);
+}
+
 /// Iterates through the constraints of a given type and solves them. Sleeping bodies are woken up when
-/// active bodies interact with them in a constraint.
+/// active bodies interact with them in a constraint. Constraints attached to bodies that are
+/// [frozen](FrozenInRoom) in inactive rooms are skipped.
 ///
 /// Note that this system only works for constraints that are modeled as entities.
 /// If you store constraints in a resource, you must create your own system for solving them.
@@ -163,43 +882,157 @@ fn penetration_constraints(
 pub fn solve_constraint<C: XpbdConstraint<ENTITY_COUNT> + Component, const ENTITY_COUNT: usize>(
     mut commands: Commands,
     mut bodies: Query<(RigidBodyQuery, Option<&Sleeping>)>,
-    mut constraints: Query<&mut C, Without<RigidBody>>,
+    frozen_bodies: Query<(), With<FrozenInRoom>>,
+    mut constraints: Query<(Entity, &mut C, Option<&ConstraintIterations>), Without<RigidBody>>,
+    deterministic_mode: Res<DeterministicMode>,
+    solver_config: Res<SolverConfig>,
     sub_dt: Res<SubDeltaTime>,
 ) {
+    let mut constraints = constraints
+        .iter_mut()
+        .map(|(entity, constraint, iterations)| {
+            let iterations = iterations.map_or(solver_config.constraint_iterations, |i| i.0);
+            (entity, constraint, iterations.max(1))
+        })
+        .collect::<Vec<_>>();
+
+    // Solve the constraints in a stable order in deterministic mode
+    if deterministic_mode.0 {
+        constraints.sort_unstable_by_key(|(entity, ..)| entity_sort_key(*entity));
+    }
+
     // Clear Lagrange multipliers
     constraints
         .iter_mut()
-        .for_each(|mut c| c.clear_lagrange_multipliers());
+        .for_each(|(_, c, _)| c.clear_lagrange_multipliers());
 
-    for mut constraint in &mut constraints {
-        // Get components for entities
-        if let Ok(mut bodies) = bodies.get_many_mut(constraint.entities()) {
-            let none_dynamic = bodies.iter().all(|(body, _)| !body.rb.is_dynamic());
-            let all_inactive = bodies
-                .iter()
-                .all(|(body, sleeping)| body.rb.is_static() || sleeping.is_some());
+    // Constraints that need more iterations are solved again after all constraints have been solved once,
+    // so that corrections propagate along chains of constraints
+    let max_iterations = constraints.iter().map(|(.., i)| *i).max().unwrap_or(0);
 
-            // No constraint solving if none of the bodies is dynamic,
-            // or if all of the bodies are either static or sleeping
-            if none_dynamic || all_inactive {
+    for iteration in 0..max_iterations {
+        for (_, constraint, _) in constraints
+            .iter_mut()
+            .filter(|(.., iterations)| iteration < *iterations)
+        {
+            // Bodies frozen in inactive rooms can't be moved or woken up
+            if constraint
+                .entities()
+                .iter()
+                .any(|entity| frozen_bodies.contains(*entity))
+            {
                 continue;
             }
 
-            // At least one of the participating bodies is active, so wake up any sleeping bodies
-            for (body, sleeping) in &bodies {
-                if sleeping.is_some() {
-                    commands.entity(body.entity).remove::<Sleeping>();
+            // Get components for entities
+            if let Ok(mut bodies) = bodies.get_many_mut(constraint.entities()) {
+                let none_dynamic = bodies.iter().all(|(body, _)| !body.rb.is_dynamic());
+                let all_inactive = bodies
+                    .iter()
+                    .all(|(body, sleeping)| body.rb.is_static() || sleeping.is_some());
+
+                // No constraint solving if none of the bodies is dynamic,
+                // or if all of the bodies are either static or sleeping
+                if none_dynamic || all_inactive {
+                    continue;
+                }
+
+                // At least one of the participating bodies is active, so wake up any sleeping bodies
+                if iteration == 0 {
+                    for (body, sleeping) in &bodies {
+                        if sleeping.is_some() {
+                            commands.entity(body.entity).remove::<Sleeping>();
+                        }
+                    }
+                }
+
+                // Joints in time dilated areas are solved using the time step of the slowest dynamic body
+                let time_scale = bodies
+                    .iter()
+                    .filter(|(body, _)| body.rb.is_dynamic())
+                    .map(|(body, _)| body.time_scale())
+                    .fold(1.0, Scalar::min);
+
+                // Get the bodies as an array and solve the constraint
+                if let Ok(bodies) = bodies
+                    .iter_mut()
+                    .map(|(ref mut body, _)| body)
+                    .collect::<Vec<&mut RigidBodyQueryItem>>()
+                    .try_into()
+                {
+                    constraint.solve(bodies, sub_dt.0 * time_scale);
                 }
             }
+        }
+    }
+}
 
-            // Get the bodies as an array and solve the constraint
-            if let Ok(bodies) = bodies
-                .iter_mut()
-                .map(|(ref mut body, _)| body)
-                .collect::<Vec<&mut RigidBodyQueryItem>>()
-                .try_into()
-            {
-                constraint.solve(bodies, sub_dt.0);
+/// Pulls the poses of [remote bodies](RemoteBody) towards their authoritative target poses
+/// using compliant positional and angular corrections.
+fn solve_remote_bodies(
+    mut bodies: Query<(RigidBodyQuery, &RemoteBody), Without<Sleeping>>,
+    sub_dt: Res<SubDeltaTime>,
+) {
+    for (mut body, remote_body) in &mut bodies {
+        if !body.rb.is_dynamic() {
+            continue;
+        }
+
+        let dt_squared = (sub_dt.0 * body.time_scale()).powi(2);
+
+        // Positional correction towards the target position
+        let delta = remote_body.target_position - body.current_position();
+        let distance = delta.length();
+        if distance > Scalar::EPSILON && body.inverse_mass.0 > 0.0 {
+            let direction = delta / distance;
+            let w = body.inverse_mass.0;
+            let delta_lagrange = distance / (w + remote_body.compliance / dt_squared);
+            let correction = delta_lagrange * direction * body.effective_inv_mass();
+            body.accumulated_translation.0 += correction;
+            // Move the previous position as well so that the correction doesn't add velocity
+            body.previous_position.0 += correction;
+        }
+
+        // Angular correction towards the target rotation
+        #[cfg(feature = "2d")]
+        {
+            let angle = remote_body
+                .target_rotation
+                .mul(body.rotation.inverse())
+                .as_radians();
+            let inv_inertia = body.effective_world_inv_inertia();
+            if angle.abs() > Scalar::EPSILON && inv_inertia > 0.0 {
+                let delta_lagrange =
+                    angle / (inv_inertia + remote_body.angular_compliance / dt_squared);
+                let correction = Rotation::from_radians(inv_inertia * delta_lagrange);
+                *body.rotation += correction;
+                body.previous_rotation.0 += correction;
+            }
+        }
+        #[cfg(feature = "3d")]
+        {
+            let mut difference = remote_body.target_rotation.0 * body.rotation.inverse().0;
+            // Take the shortest path
+            if difference.w < 0.0 {
+                difference = -difference;
+            }
+            let rotation_vector = 2.0 * difference.xyz();
+            let angle = rotation_vector.length();
+            if angle > Scalar::EPSILON {
+                let axis = rotation_vector / angle;
+                let inv_inertia = body.effective_world_inv_inertia();
+                let w = axis.dot(inv_inertia * axis);
+                if w > 0.0 {
+                    let delta_lagrange = angle / (w + remote_body.angular_compliance / dt_squared);
+                    let delta_rotation = Quaternion::from_vec4(
+                        0.5 * (inv_inertia * (delta_lagrange * axis)).extend(0.0),
+                    );
+                    let rotation = body.rotation.0;
+                    let previous_rotation = body.previous_rotation.0 .0;
+                    body.rotation.0 = (rotation + delta_rotation * rotation).normalize();
+                    body.previous_rotation.0 .0 =
+                        (previous_rotation + delta_rotation * previous_rotation).normalize();
+                }
             }
         }
     }
@@ -216,12 +1049,15 @@ fn update_lin_vel(
             &AccumulatedTranslation,
             &mut LinearVelocity,
             &mut PreSolveLinearVelocity,
+            Option<&TimeDilation>,
         ),
         Without<Sleeping>,
     >,
     sub_dt: Res<SubDeltaTime>,
 ) {
-    for (rb, pos, prev_pos, translation, mut lin_vel, mut pre_solve_lin_vel) in &mut bodies {
+    for (rb, pos, prev_pos, translation, mut lin_vel, mut pre_solve_lin_vel, time_dilation) in
+        &mut bodies
+    {
         // Static bodies have no velocity
         if rb.is_static() && lin_vel.0 != Vector::ZERO {
             lin_vel.0 = Vector::ZERO;
@@ -231,7 +1067,8 @@ fn update_lin_vel(
 
         if rb.is_dynamic() {
             // v = (x - x_prev) / h
-            let new_lin_vel = (pos.0 - prev_pos.0 + translation.0) / sub_dt.0;
+            let delta_secs = sub_dt.0 * time_dilation.map_or(1.0, |dilation| dilation.0);
+            let new_lin_vel = (pos.0 - prev_pos.0 + translation.0) / delta_secs;
             // avoid triggering bevy's change detection unnecessarily
             if new_lin_vel != lin_vel.0 {
                 lin_vel.0 = new_lin_vel;
@@ -250,12 +1087,13 @@ fn update_ang_vel(
             &PreviousRotation,
             &mut AngularVelocity,
             &mut PreSolveAngularVelocity,
+            Option<&TimeDilation>,
         ),
         Without<Sleeping>,
     >,
     sub_dt: Res<SubDeltaTime>,
 ) {
-    for (rb, rot, prev_rot, mut ang_vel, mut pre_solve_ang_vel) in &mut bodies {
+    for (rb, rot, prev_rot, mut ang_vel, mut pre_solve_ang_vel, time_dilation) in &mut bodies {
         // Static bodies have no velocity
         if rb.is_static() && ang_vel.0 != 0.0 {
             ang_vel.0 = 0.0;
@@ -264,7 +1102,8 @@ fn update_ang_vel(
         pre_solve_ang_vel.0 = ang_vel.0;
 
         if rb.is_dynamic() {
-            let new_ang_vel = (rot.mul(prev_rot.inverse())).as_radians() / sub_dt.0;
+            let delta_secs = sub_dt.0 * time_dilation.map_or(1.0, |dilation| dilation.0);
+            let new_ang_vel = (rot.mul(prev_rot.inverse())).as_radians() / delta_secs;
             // avoid triggering bevy's change detection unnecessarily
             if new_ang_vel != ang_vel.0 {
                 ang_vel.0 = new_ang_vel;
@@ -283,12 +1122,13 @@ fn update_ang_vel(
             &PreviousRotation,
             &mut AngularVelocity,
             &mut PreSolveAngularVelocity,
+            Option<&TimeDilation>,
         ),
         Without<Sleeping>,
     >,
     sub_dt: Res<SubDeltaTime>,
 ) {
-    for (rb, rot, prev_rot, mut ang_vel, mut pre_solve_ang_vel) in &mut bodies {
+    for (rb, rot, prev_rot, mut ang_vel, mut pre_solve_ang_vel, time_dilation) in &mut bodies {
         // Static bodies have no velocity
         if rb.is_static() && ang_vel.0 != Vector::ZERO {
             ang_vel.0 = Vector::ZERO;
@@ -297,8 +1137,9 @@ fn update_ang_vel(
         pre_solve_ang_vel.0 = ang_vel.0;
 
         if rb.is_dynamic() {
+            let delta_secs = sub_dt.0 * time_dilation.map_or(1.0, |dilation| dilation.0);
             let delta_rot = rot.mul_quat(prev_rot.inverse().0);
-            let mut new_ang_vel = 2.0 * delta_rot.xyz() / sub_dt.0;
+            let mut new_ang_vel = 2.0 * delta_rot.xyz() / delta_secs;
             if delta_rot.w < 0.0 {
                 new_ang_vel = -new_ang_vel;
             }
@@ -310,12 +1151,12 @@ fn update_ang_vel(
     }
 }
 
-/// Applies velocity corrections caused by dynamic friction and restitution.
+/// Applies velocity corrections caused by dynamic friction, rolling friction and restitution.
 #[allow(clippy::type_complexity)]
 fn solve_vel(
     mut bodies: Query<RigidBodyQuery, Without<Sleeping>>,
     penetration_constraints: Res<PenetrationConstraints>,
-    gravity: Res<Gravity>,
+    solver_config: Res<SolverConfig>,
     sub_dt: Res<SubDeltaTime>,
 ) {
     for constraint in penetration_constraints.0.iter() {
@@ -333,25 +1174,35 @@ fn solve_vel(
             let r1 = body1.rotation.rotate(constraint.r1);
             let r2 = body2.rotation.rotate(constraint.r2);
 
+            // The velocities of time dilated bodies are scaled to the velocities seen from outside
+            let time_scale1 = body1.time_scale();
+            let time_scale2 = body2.time_scale();
+
             // Compute pre-solve relative normal velocities at the contact point (used for restitution)
             let pre_solve_contact_vel1 = compute_contact_vel(
-                body1.pre_solve_linear_velocity.0,
-                body1.pre_solve_angular_velocity.0,
+                body1.pre_solve_linear_velocity.0 * time_scale1,
+                body1.pre_solve_angular_velocity.0 * time_scale1,
                 r1,
             );
             let pre_solve_contact_vel2 = compute_contact_vel(
-                body2.pre_solve_linear_velocity.0,
-                body2.pre_solve_angular_velocity.0,
+                body2.pre_solve_linear_velocity.0 * time_scale2,
+                body2.pre_solve_angular_velocity.0 * time_scale2,
                 r2,
             );
             let pre_solve_relative_vel = pre_solve_contact_vel1 - pre_solve_contact_vel2;
             let pre_solve_normal_speed = normal.dot(pre_solve_relative_vel);
 
             // Compute relative normal and tangential velocities at the contact point (equation 29)
-            let contact_vel1 =
-                compute_contact_vel(body1.linear_velocity.0, body1.angular_velocity.0, r1);
-            let contact_vel2 =
-                compute_contact_vel(body2.linear_velocity.0, body2.angular_velocity.0, r2);
+            let contact_vel1 = compute_contact_vel(
+                body1.dilated_linear_velocity(),
+                body1.dilated_angular_velocity(),
+                r1,
+            );
+            let contact_vel2 = compute_contact_vel(
+                body2.dilated_linear_velocity(),
+                body2.dilated_angular_velocity(),
+                r2,
+            );
             let relative_vel = contact_vel1 - contact_vel2;
 
             let normal_speed = normal.dot(relative_vel);
@@ -369,9 +1220,12 @@ fn solve_vel(
             let restitution_speed = compute_restitution(
                 normal_speed,
                 pre_solve_normal_speed,
-                body1.restitution.combine(*body2.restitution).coefficient,
-                gravity.0,
-                sub_dt.0,
+                constraint
+                    .material1
+                    .restitution
+                    .combine(constraint.material2.restitution)
+                    .coefficient,
+                solver_config.restitution_threshold,
             );
             if restitution_speed.abs() > Scalar::EPSILON {
                 let w1 = constraint.compute_generalized_inverse_mass(&body1, r1, normal);
@@ -379,15 +1233,60 @@ fn solve_vel(
                 p += restitution_speed / (w1 + w2) * normal;
             }
 
-            // Compute dynamic friction
-            if tangent_speed > Scalar::EPSILON {
+            // Compute tire friction if either body is a tire, rolling friction that matches the velocities
+            // of the contact points for rolling contacts, and dynamic friction otherwise
+            if constraint.material1.tire_friction.is_some()
+                || constraint.material2.tire_friction.is_some()
+            {
+                p += compute_tire_friction(
+                    constraint,
+                    &body1,
+                    &body2,
+                    normal,
+                    relative_vel,
+                    r1,
+                    r2,
+                    sub_dt.0,
+                );
+            } else if constraint.rolling && tangent_speed > Scalar::EPSILON {
+                // Remove the relative tangential velocity, limited by static friction
+                let tangent_dir = tangent_vel / tangent_speed;
+                let w1 = constraint.compute_generalized_inverse_mass(&body1, r1, tangent_dir);
+                let w2 = constraint.compute_generalized_inverse_mass(&body2, r2, tangent_dir);
+                let friction_impulse = compute_dynamic_friction(
+                    tangent_speed,
+                    w1 + w2,
+                    constraint
+                        .friction_along(&body1.rotation, &body2.rotation, tangent_dir)
+                        .static_coefficient,
+                    constraint.normal_lagrange,
+                    sub_dt.0,
+                );
+                p += friction_impulse * tangent_dir;
+            } else if tangent_speed > Scalar::EPSILON
+                && solver_config.friction_model != FrictionModel::SingleTangent
+            {
+                p += compute_tangent_friction(
+                    constraint,
+                    &body1,
+                    &body2,
+                    normal,
+                    tangent_vel,
+                    r1,
+                    r2,
+                    solver_config.friction_model,
+                    sub_dt.0,
+                );
+            } else if tangent_speed > Scalar::EPSILON {
                 let tangent_dir = tangent_vel / tangent_speed;
                 let w1 = constraint.compute_generalized_inverse_mass(&body1, r1, tangent_dir);
                 let w2 = constraint.compute_generalized_inverse_mass(&body2, r2, tangent_dir);
                 let friction_impulse = compute_dynamic_friction(
                     tangent_speed,
                     w1 + w2,
-                    body1.friction.combine(*body2.friction).dynamic_coefficient,
+                    constraint
+                        .friction_along(&body1.rotation, &body2.rotation, tangent_dir)
+                        .dynamic_coefficient,
                     constraint.normal_lagrange,
                     sub_dt.0,
                 );
@@ -395,8 +1294,8 @@ fn solve_vel(
             }
 
             if body1.rb.is_dynamic() {
-                let delta_lin_vel = p * inv_mass1;
-                let delta_ang_vel = compute_delta_ang_vel(inv_inertia1, r1, p);
+                let delta_lin_vel = p * inv_mass1 / time_scale1;
+                let delta_ang_vel = compute_delta_ang_vel(inv_inertia1, r1, p) / time_scale1;
 
                 if delta_lin_vel != Vector::ZERO {
                     body1.linear_velocity.0 += delta_lin_vel;
@@ -406,8 +1305,8 @@ fn solve_vel(
                 }
             }
             if body2.rb.is_dynamic() {
-                let delta_lin_vel = p * inv_mass2;
-                let delta_ang_vel = compute_delta_ang_vel(inv_inertia2, r2, p);
+                let delta_lin_vel = p * inv_mass2 / time_scale2;
+                let delta_ang_vel = compute_delta_ang_vel(inv_inertia2, r2, p) / time_scale2;
 
                 if delta_lin_vel != Vector::ZERO {
                     body2.linear_velocity.0 -= delta_lin_vel;
@@ -416,11 +1315,151 @@ fn solve_vel(
                     body2.angular_velocity.0 -= delta_ang_vel;
                 }
             }
+
+            // Compute rolling friction
+            let rolling_coefficient = constraint
+                .material1
+                .friction
+                .combine(constraint.material2.friction)
+                .rolling_coefficient;
+            if rolling_coefficient > 0.0 {
+                let inv_inertia1 = if body1.rb.is_dynamic() {
+                    inv_inertia1
+                } else {
+                    InverseInertia::ZERO.0
+                };
+                let inv_inertia2 = if body2.rb.is_dynamic() {
+                    inv_inertia2
+                } else {
+                    InverseInertia::ZERO.0
+                };
+                let angular_impulse = compute_rolling_friction(
+                    body1.dilated_angular_velocity() - body2.dilated_angular_velocity(),
+                    normal,
+                    inv_inertia1,
+                    inv_inertia2,
+                    rolling_coefficient,
+                    constraint.normal_lagrange,
+                    sub_dt.0,
+                );
+
+                if body1.rb.is_dynamic() {
+                    body1.angular_velocity.0 += inv_inertia1 * angular_impulse / time_scale1;
+                }
+                if body2.rb.is_dynamic() {
+                    body2.angular_velocity.0 -= inv_inertia2 * angular_impulse / time_scale2;
+                }
+            }
+        }
+    }
+}
+
+/// Computes the impulse caused by [`TireFriction`] that is applied to the first body.
+///
+/// The slip is computed for the body with the tire, so the impulse is flipped if the tire is the second body.
+#[allow(clippy::too_many_arguments)]
+fn compute_tire_friction<'w>(
+    constraint: &PenetrationConstraint,
+    body1: &RigidBodyQueryItem<'w>,
+    body2: &RigidBodyQueryItem<'w>,
+    normal: Vector,
+    relative_vel: Vector,
+    r1: Vector,
+    r2: Vector,
+    sub_dt: Scalar,
+) -> Vector {
+    let (tire, tire_body, other_body, r_other, sign) = match (
+        constraint.material1.tire_friction,
+        constraint.material2.tire_friction,
+    ) {
+        (Some(tire), _) => (tire, body1, body2, r2, 1.0),
+        (None, Some(tire)) => (tire, body2, body1, r1, -1.0),
+        (None, None) => return Vector::ZERO,
+    };
+
+    // The slip velocity at the contact point and the velocity of the wheel hub relative to the ground
+    let slip_vel = sign * relative_vel;
+    let hub_vel = tire_body.dilated_linear_velocity()
+        - compute_contact_vel(
+            other_body.dilated_linear_velocity(),
+            other_body.dilated_angular_velocity(),
+            r_other,
+        );
+
+    #[cfg(feature = "2d")]
+    let forward = normal.perp();
+    #[cfg(feature = "3d")]
+    let (forward, lateral) = {
+        let axle = tire_body.rotation.rotate(tire.axle);
+        let lateral = (axle - normal * normal.dot(axle)).normalize_or_zero();
+        (normal.cross(lateral), lateral)
+    };
+
+    let speed = hub_vel.dot(forward).abs().max(tire.min_speed);
+    let normal_impulse = (constraint.normal_lagrange / sub_dt).abs();
+
+    // Clamp the impulses so that they never reverse the slip
+    let clamp_impulse = |coefficient: Scalar, slip_speed: Scalar, direction: Vector| {
+        let w1 = constraint.compute_generalized_inverse_mass(body1, r1, direction);
+        let w2 = constraint.compute_generalized_inverse_mass(body2, r2, direction);
+        if w1 + w2 <= Scalar::EPSILON {
+            return 0.0;
+        }
+        let max_impulse = slip_speed.abs() / (w1 + w2);
+        (coefficient * normal_impulse).clamp(-max_impulse, max_impulse)
+    };
+
+    // The slip ratio is positive when the wheel spins faster than the ground moves under it
+    let longitudinal_slip_speed = slip_vel.dot(forward);
+    let longitudinal = tire.longitudinal.evaluate(-longitudinal_slip_speed / speed);
+
+    #[cfg(feature = "2d")]
+    {
+        sign * clamp_impulse(longitudinal, longitudinal_slip_speed, forward) * forward
+    }
+    #[cfg(feature = "3d")]
+    {
+        let lateral_slip_speed = slip_vel.dot(lateral);
+        let lateral_coefficient = -tire
+            .lateral
+            .evaluate(math::ops::atan(lateral_slip_speed / speed));
+
+        // Limit the combined friction to the friction ellipse
+        let ellipse = (longitudinal / tire.longitudinal.peak.max(Scalar::EPSILON)).powi(2)
+            + (lateral_coefficient / tire.lateral.peak.max(Scalar::EPSILON)).powi(2);
+        let scale = if ellipse > 1.0 {
+            1.0 / ellipse.sqrt()
+        } else {
+            1.0
+        };
+
+        let impulse = clamp_impulse(longitudinal * scale, longitudinal_slip_speed, forward)
+            * forward
+            + clamp_impulse(lateral_coefficient * scale, lateral_slip_speed, lateral) * lateral;
+        sign * impulse
+    }
+}
+
+/// Updates the [`JointLimitMonitor`]s of joints and sends a [`JointLimitViolated`] event
+/// when a joint starts violating its angle limits.
+fn monitor_joint_limits<T: Joint>(
+    mut joints: Query<(Entity, &T, &mut JointLimitMonitor)>,
+    mut violation_events: EventWriter<JointLimitViolated>,
+) {
+    for (entity, joint, mut monitor) in &mut joints {
+        if monitor.update(joint.angle_limit_violation()) {
+            violation_events.send(JointLimitViolated {
+                joint: entity,
+                bodies: joint.entities(),
+                violation: monitor.max_violation,
+            });
         }
     }
 }
 
 /// Applies velocity corrections caused by joint damping.
+///
+/// The relative velocities are computed using the velocities seen from outside of any [`TimeDilationVolume`].
 pub fn joint_damping<T: Joint>(
     mut bodies: Query<
         (
@@ -428,28 +1467,41 @@ pub fn joint_damping<T: Joint>(
             &mut LinearVelocity,
             &mut AngularVelocity,
             &InverseMass,
+            Option<&TimeDilation>,
         ),
         Without<Sleeping>,
     >,
-    joints: Query<&T, Without<RigidBody>>,
+    joints: Query<(Entity, &T), Without<RigidBody>>,
+    deterministic_mode: Res<DeterministicMode>,
     sub_dt: Res<SubDeltaTime>,
 ) {
-    for joint in &joints {
+    let mut joints = joints.iter().collect::<Vec<_>>();
+
+    // Apply the damping in a stable order in deterministic mode
+    if deterministic_mode.0 {
+        joints.sort_unstable_by_key(|(entity, _)| entity_sort_key(*entity));
+    }
+
+    for (_, joint) in joints {
         if let Ok(
-            [(rb1, mut lin_vel1, mut ang_vel1, inv_mass1), (rb2, mut lin_vel2, mut ang_vel2, inv_mass2)],
+            [(rb1, mut lin_vel1, mut ang_vel1, inv_mass1, time_dilation1), (rb2, mut lin_vel2, mut ang_vel2, inv_mass2, time_dilation2)],
         ) = bodies.get_many_mut(joint.entities())
         {
-            let delta_omega =
-                (ang_vel2.0 - ang_vel1.0) * (joint.damping_angular() * sub_dt.0).min(1.0);
+            let time_scale1 = time_dilation1.map_or(1.0, |dilation| dilation.0);
+            let time_scale2 = time_dilation2.map_or(1.0, |dilation| dilation.0);
+
+            let delta_omega = (ang_vel2.0 * time_scale2 - ang_vel1.0 * time_scale1)
+                * (joint.damping_angular() * sub_dt.0).min(1.0);
 
             if rb1.is_dynamic() {
-                ang_vel1.0 += delta_omega;
+                ang_vel1.0 += delta_omega / time_scale1;
             }
             if rb2.is_dynamic() {
-                ang_vel2.0 -= delta_omega;
+                ang_vel2.0 -= delta_omega / time_scale2;
             }
 
-            let delta_v = (lin_vel2.0 - lin_vel1.0) * (joint.damping_linear() * sub_dt.0).min(1.0);
+            let delta_v = (lin_vel2.0 * time_scale2 - lin_vel1.0 * time_scale1)
+                * (joint.damping_linear() * sub_dt.0).min(1.0);
 
             let w1 = if rb1.is_dynamic() { inv_mass1.0 } else { 0.0 };
             let w2 = if rb2.is_dynamic() { inv_mass2.0 } else { 0.0 };
@@ -461,15 +1513,80 @@ pub fn joint_damping<T: Joint>(
             let p = delta_v / (w1 + w2);
 
             if rb1.is_dynamic() {
-                lin_vel1.0 += p * inv_mass1.0;
+                lin_vel1.0 += p * inv_mass1.0 / time_scale1;
             }
             if rb2.is_dynamic() {
-                lin_vel2.0 -= p * inv_mass2.0;
+                lin_vel2.0 -= p * inv_mass2.0 / time_scale2;
             }
         }
     }
 }
 
+/// Damps the relative yaw rotation of bodies connected by a [`TrailerHitch`] and applies
+/// a restoring torque when the yaw angle exceeds the hitch's jackknife angle.
+#[cfg(feature = "3d")]
+fn stabilize_hitches(
+    mut bodies: Query<
+        (
+            &RigidBody,
+            &Rotation,
+            &mut AngularVelocity,
+            &InverseInertia,
+            Option<&TimeDilation>,
+        ),
+        Without<Sleeping>,
+    >,
+    hitches: Query<(&SphericalJoint, &TrailerHitch), Without<RigidBody>>,
+    sub_dt: Res<SubDeltaTime>,
+) {
+    for (joint, hitch) in &hitches {
+        let Ok(
+            [(rb1, rot1, mut ang_vel1, inv_inertia1, time_dilation1), (rb2, rot2, mut ang_vel2, inv_inertia2, time_dilation2)],
+        ) = bodies.get_many_mut(joint.entities())
+        else {
+            continue;
+        };
+
+        let time_scale1 = time_dilation1.map_or(1.0, |dilation| dilation.0);
+        let time_scale2 = time_dilation2.map_or(1.0, |dilation| dilation.0);
+
+        let axis = rot1.rotate(hitch.up_axis);
+        let angle = hitch.yaw_angle(rot1, rot2);
+        let excess = angle.signum() * (angle.abs() - hitch.jackknife_angle).max(0.0);
+        let yaw_rate = (ang_vel2.0 * time_scale2 - ang_vel1.0 * time_scale1).dot(axis);
+
+        // Desired change in the relative yaw rate
+        let delta_rate = -hitch.stiffness * excess * sub_dt.0
+            - yaw_rate * (hitch.yaw_damping * sub_dt.0).min(1.0);
+
+        let inv_inertia1 = inv_inertia1.rotated(rot1).0;
+        let inv_inertia2 = inv_inertia2.rotated(rot2).0;
+        let w1 = if rb1.is_dynamic() {
+            axis.dot(inv_inertia1 * axis)
+        } else {
+            0.0
+        };
+        let w2 = if rb2.is_dynamic() {
+            axis.dot(inv_inertia2 * axis)
+        } else {
+            0.0
+        };
+
+        if w1 + w2 <= Scalar::EPSILON {
+            continue;
+        }
+
+        let impulse = axis * delta_rate / (w1 + w2);
+
+        if rb1.is_dynamic() {
+            ang_vel1.0 -= inv_inertia1 * impulse / time_scale1;
+        }
+        if rb2.is_dynamic() {
+            ang_vel2.0 += inv_inertia2 * impulse / time_scale2;
+        }
+    }
+}
+
 fn apply_translation(
     mut bodies: Query<
         (&RigidBody, &mut Position, &mut AccumulatedTranslation),
@@ -486,6 +1603,68 @@ fn apply_translation(
     }
 }
 
+/// Computes the dynamic friction impulse of a sliding contact along perpendicular tangent directions
+/// for the [`FrictionModel::Pyramid`] and [`FrictionModel::Cone`] models.
+#[allow(clippy::too_many_arguments)]
+fn compute_tangent_friction(
+    constraint: &PenetrationConstraint,
+    body1: &RigidBodyQueryItem,
+    body2: &RigidBodyQueryItem,
+    normal: Vector,
+    tangent_vel: Vector,
+    r1: Vector,
+    r2: Vector,
+    model: FrictionModel,
+    sub_dt: Scalar,
+) -> Vector {
+    let normal_impulse = (constraint.normal_lagrange / sub_dt).abs();
+    #[cfg(feature = "2d")]
+    let tangents = [normal.perp()];
+    #[cfg(feature = "3d")]
+    let tangents = {
+        let (tangent1, tangent2) = normal.any_orthonormal_pair();
+        [tangent1, tangent2]
+    };
+    let mut impulse = Vector::ZERO;
+
+    for tangent in tangents {
+        let w1 = constraint.compute_generalized_inverse_mass(body1, r1, tangent);
+        let w2 = constraint.compute_generalized_inverse_mass(body2, r2, tangent);
+        if w1 + w2 <= Scalar::EPSILON {
+            continue;
+        }
+
+        // The impulse that stops the sliding along the tangent
+        let stopping_impulse = -tangent_vel.dot(tangent) / (w1 + w2);
+
+        let tangent_impulse = if model == FrictionModel::Pyramid {
+            let max_impulse = constraint
+                .friction_along(&body1.rotation, &body2.rotation, tangent)
+                .dynamic_coefficient
+                * normal_impulse;
+            stopping_impulse.clamp(-max_impulse, max_impulse)
+        } else {
+            stopping_impulse
+        };
+        impulse += tangent_impulse * tangent;
+    }
+
+    if model == FrictionModel::Cone {
+        // Project the impulse onto the friction cone
+        let sliding_dir = tangent_vel.normalize_or_zero();
+        let max_impulse = constraint
+            .friction_along(&body1.rotation, &body2.rotation, sliding_dir)
+            .dynamic_coefficient
+            * normal_impulse;
+        let impulse_magnitude = impulse.length();
+        if impulse_magnitude > max_impulse {
+            impulse *= max_impulse / impulse_magnitude;
+        }
+    }
+
+    impulse
+}
+
 #[cfg(feature = "2d")]
 fn compute_contact_vel(lin_vel: Vector, ang_vel: Scalar, r: Vector) -> Vector {
     lin_vel + ang_vel * r.perp()
@@ -83,6 +83,9 @@
 //! [`SpatialQuery`], like [`cast_shape`](SpatialQuery#method.cast_shape), [`shape_hits`](SpatialQuery#method.shape_hits) or
 //! [`shape_hits_callback`](SpatialQuery#method.shape_hits_callback).
 //!
+//! For keeping a camera or another entity at an offset from a target without clipping through colliders,
+//! use the [`SpringArm`] component, which performs a shape cast along the arm every frame.
+//!
 //! See the documentation of the components and methods for more information.
 //!
 //! A simple example using the component-based method looks like this:
@@ -150,12 +153,14 @@ mod pipeline;
 mod query_filter;
 mod ray_caster;
 mod shape_caster;
+mod spring_arm;
 mod system_param;
 
 pub use pipeline::*;
 pub use query_filter::*;
 pub use ray_caster::*;
 pub use shape_caster::*;
+pub use spring_arm::*;
 pub use system_param::*;
 
 use crate::prelude::*;
@@ -163,7 +168,7 @@ use bevy::prelude::*;
 
 /// Initializes the [`SpatialQueryPipeline`] resource and handles component-based [spatial queries](spatial_query)
 /// like [ray casting](spatial_query#ray-casting) and [shape casting](spatial_query#shape-casting) with
-/// [`RayCaster`] and [`ShapeCaster`].
+/// [`RayCaster`] and [`ShapeCaster`]. Also updates [`SpringArm`]s.
 pub struct SpatialQueryPlugin {
     schedule: Box<dyn ScheduleLabel>,
 }
@@ -187,10 +192,27 @@ impl Default for SpatialQueryPlugin {
 
 impl Plugin for SpatialQueryPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<SpatialQueryPipeline>().add_systems(
-            self.schedule.dyn_clone(),
-            (init_ray_hits, init_shape_hit).in_set(PhysicsSet::Prepare),
-        );
+        app.init_resource::<SpatialQueryPipeline>()
+            .register_type::<SpatialQueryLayers>()
+            .register_type::<SpatialQueryFilter>()
+            .register_type::<RayCaster>()
+            .register_type::<RayHits>()
+            .register_type::<RayHitData>()
+            .register_type::<ShapeCaster>()
+            .register_type::<ShapeHits>()
+            .register_type::<ShapeHitData>()
+            .register_type::<SpringArm>()
+            .add_systems(
+                self.schedule.dyn_clone(),
+                (init_ray_hits, init_shape_hit).in_set(PhysicsSet::Prepare),
+            )
+            .add_systems(
+                self.schedule.dyn_clone(),
+                spring_arm::update_spring_arms
+                    .after(PhysicsSet::Sync)
+                    .after(crate::plugins::sync::interpolate_transforms)
+                    .before(bevy::transform::TransformSystem::TransformPropagate),
+            );
 
         let physics_schedule = app
             .get_schedule_mut(PhysicsSchedule)
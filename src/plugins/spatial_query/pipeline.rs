@@ -27,6 +27,8 @@ pub struct SpatialQueryPipeline {
     pub(crate) qbvh: Qbvh<u32>,
     pub(crate) dispatcher: Arc<dyn QueryDispatcher>,
     pub(crate) colliders: HashMap<Entity, (Isometry<Scalar>, Collider, CollisionLayers)>,
+    /// The rigid bodies that the colliders are attached to.
+    pub(crate) collider_parents: HashMap<Entity, Entity>,
     pub(crate) entity_generations: HashMap<u32, u32>,
 }
 
@@ -36,6 +38,7 @@ impl Default for SpatialQueryPipeline {
             qbvh: Qbvh::new(),
             dispatcher: Arc::new(DefaultQueryDispatcher),
             colliders: HashMap::default(),
+            collider_parents: HashMap::default(),
             entity_generations: HashMap::default(),
         }
     }
@@ -47,6 +50,11 @@ impl SpatialQueryPipeline {
         SpatialQueryPipeline::default()
     }
 
+    /// Returns the entity of the rigid body that the given collider is attached to.
+    pub(crate) fn body_entity(&self, collider: Entity) -> Option<Entity> {
+        self.collider_parents.get(&collider).copied()
+    }
+
     pub(crate) fn as_composite_shape(
         &self,
         query_filter: SpatialQueryFilter,
@@ -66,23 +74,38 @@ impl SpatialQueryPipeline {
                 Entity,
                 &'a Position,
                 &'a Rotation,
+                Option<&'a ColliderOffset>,
                 &'a Collider,
                 Option<&'a CollisionLayers>,
+                Option<&'a SpatialQueryLayers>,
+                Option<&'a ColliderParent>,
             ),
         >,
         added_colliders: impl Iterator<Item = Entity>,
     ) {
+        self.collider_parents.clear();
         let colliders = colliders
-            .map(|(entity, position, rotation, collider, layers)| {
-                (
-                    entity,
+            .map(
+                |(entity, position, rotation, offset, collider, layers, query_layers, parent)| {
+                    if let Some(parent) = parent {
+                        self.collider_parents.insert(entity, parent.get());
+                    }
+                    let layers = layers.map_or(CollisionLayers::default(), |layers| *layers);
+                    let (position, rotation) = offset.map_or((position.0, *rotation), |offset| {
+                        offset.transform_pose(position.0, *rotation)
+                    });
                     (
-                        utils::make_isometry(position.0, *rotation),
-                        collider.clone(),
-                        layers.map_or(CollisionLayers::default(), |layers| *layers),
-                    ),
-                )
-            })
+                        entity,
+                        (
+                            utils::make_isometry(position, rotation),
+                            collider.clone(),
+                            // Spatial queries use the query layers instead of the collision groups if they exist
+                            query_layers
+                                .map_or(layers, |query_layers| query_layers.apply_to(layers)),
+                        ),
+                    )
+                },
+            )
             .collect();
 
         self.update_internal(colliders, added_colliders)
@@ -154,22 +177,135 @@ impl SpatialQueryPipeline {
         solid: bool,
         query_filter: SpatialQueryFilter,
     ) -> Option<RayHitData> {
-        let pipeline_shape = self.as_composite_shape(query_filter);
         let ray = parry::query::Ray::new(origin.into(), direction.into());
-        let mut visitor = RayCompositeShapeToiAndNormalBestFirstVisitor::new(
-            &pipeline_shape,
-            &ray,
-            max_time_of_impact,
-            solid,
-        );
+        let mut query_filter = query_filter;
+        let mut closest_front_face_hit: Option<RayHitData> = None;
 
-        self.qbvh
-            .traverse_best_first(&mut visitor)
-            .map(|(_, (entity_index, hit))| RayHitData {
-                entity: self.entity_from_index(entity_index),
-                time_of_impact: hit.toi,
-                normal: hit.normal.into(),
-            })
+        loop {
+            let max_time_of_impact = closest_front_face_hit
+                .as_ref()
+                .map_or(max_time_of_impact, |hit| hit.time_of_impact);
+            let pipeline_shape = self.as_composite_shape(query_filter.clone());
+            let mut visitor = RayCompositeShapeToiAndNormalBestFirstVisitor::new(
+                &pipeline_shape,
+                &ray,
+                max_time_of_impact,
+                solid,
+            );
+
+            let Some(hit) =
+                self.qbvh
+                    .traverse_best_first(&mut visitor)
+                    .map(|(_, (entity_index, hit))| {
+                        let entity = self.entity_from_index(entity_index);
+                        RayHitData {
+                            entity,
+                            body_entity: self.body_entity(entity),
+                            time_of_impact: hit.toi,
+                            normal: hit.normal.into(),
+                        }
+                    })
+            else {
+                return closest_front_face_hit;
+            };
+
+            // The closest hit can be against the back face of a triangle mesh that culls back faces.
+            // In that case, the mesh is cast against separately using only its front faces,
+            // and the query is repeated without the mesh to find out if there are closer hits.
+            let Some(front_face_hit) =
+                self.cast_ray_against_front_faces(hit.entity, &ray, max_time_of_impact, solid)
+            else {
+                return Some(hit);
+            };
+
+            if let Some(front_face_hit) = front_face_hit {
+                closest_front_face_hit = Some(RayHitData {
+                    entity: hit.entity,
+                    body_entity: hit.body_entity,
+                    time_of_impact: front_face_hit.toi,
+                    normal: front_face_hit.normal.into(),
+                });
+            }
+            query_filter.excluded_entities.insert(hit.entity);
+        }
+    }
+
+    /// Casts a ray against a single collider in the pipeline. Back faces of triangle meshes
+    /// that [cull back faces](TriMeshCollisionFlags::cull_backfaces) are ignored.
+    pub(crate) fn cast_ray_against_collider(
+        &self,
+        entity: Entity,
+        ray: &parry::query::Ray,
+        max_time_of_impact: Scalar,
+        solid: bool,
+    ) -> Option<parry::query::RayIntersection> {
+        if let Some(front_face_hit) =
+            self.cast_ray_against_front_faces(entity, ray, max_time_of_impact, solid)
+        {
+            return front_face_hit;
+        }
+        let (iso, shape, _) = self.colliders.get(&entity)?;
+        shape.cast_ray_and_get_normal(iso, ray, max_time_of_impact, solid)
+    }
+
+    /// Casts a ray against the front faces of a triangle mesh collider that
+    /// [culls back faces](TriMeshCollisionFlags::cull_backfaces).
+    ///
+    /// Returns `None` if the collider doesn't cull back faces, and `Some(None)` if the ray
+    /// doesn't hit any of the front faces.
+    #[cfg(feature = "3d")]
+    fn cast_ray_against_front_faces(
+        &self,
+        entity: Entity,
+        ray: &parry::query::Ray,
+        max_time_of_impact: Scalar,
+        solid: bool,
+    ) -> Option<Option<parry::query::RayIntersection>> {
+        use parry::query::RayCast;
+
+        let (iso, collider, _) = self.colliders.get(&entity)?;
+        if !collider.trimesh_collision_flags().cull_backfaces {
+            return None;
+        }
+        let trimesh = collider.get_shape().as_trimesh()?;
+
+        let local_ray = ray.inverse_transform_by(iso);
+        let mut closest_hit: Option<parry::query::RayIntersection> = None;
+
+        let mut leaf_callback = &mut |triangle_index: &u32| {
+            let triangle = trimesh.triangle(*triangle_index);
+            let is_front_face = triangle
+                .normal()
+                .map_or(false, |normal| normal.dot(&local_ray.dir) < 0.0);
+            if is_front_face {
+                let max_time_of_impact = closest_hit.map_or(max_time_of_impact, |hit| hit.toi);
+                if let Some(mut hit) =
+                    triangle.cast_local_ray_and_get_normal(&local_ray, max_time_of_impact, solid)
+                {
+                    hit.feature = parry::shape::FeatureId::Face(*triangle_index);
+                    closest_hit = Some(hit);
+                }
+            }
+            true
+        };
+
+        let mut visitor =
+            RayIntersectionsVisitor::new(&local_ray, max_time_of_impact, &mut leaf_callback);
+        trimesh.qbvh().traverse_depth_first(&mut visitor);
+
+        Some(closest_hit.map(|hit| hit.transform_by(iso)))
+    }
+
+    /// Back face culling for triangle meshes is only supported in 3D.
+    #[cfg(feature = "2d")]
+    fn cast_ray_against_front_faces(
+        &self,
+        _entity: Entity,
+        _ray: &parry::query::Ray,
+        _max_time_of_impact: Scalar,
+        _solid: bool,
+    ) -> Option<Option<parry::query::RayIntersection>> {
+        None
     }
 
     /// Casts a [ray](spatial_query#ray-casting) and computes all [hits](RayHitData) until `max_hits` is reached.
@@ -243,13 +379,14 @@ impl SpatialQueryPipeline {
 
         let mut leaf_callback = &mut |entity_index: &u32| {
             let entity = self.entity_from_index(*entity_index);
-            if let Some((iso, shape, layers)) = colliders.get(&entity) {
+            if let Some((_, _, layers)) = colliders.get(&entity) {
                 if query_filter.test(entity, *layers) {
                     if let Some(hit) =
-                        shape.cast_ray_and_get_normal(iso, &ray, max_time_of_impact, solid)
+                        self.cast_ray_against_collider(entity, &ray, max_time_of_impact, solid)
                     {
                         let hit = RayHitData {
                             entity,
+                            body_entity: self.body_entity(entity),
                             time_of_impact: hit.toi,
                             normal: hit.normal.into(),
                         };
@@ -320,13 +457,17 @@ impl SpatialQueryPipeline {
 
         self.qbvh
             .traverse_best_first(&mut visitor)
-            .map(|(_, (entity_index, hit))| ShapeHitData {
-                entity: self.entity_from_index(entity_index),
-                time_of_impact: hit.toi,
-                point1: hit.witness1.into(),
-                point2: hit.witness2.into(),
-                normal1: hit.normal1.into(),
-                normal2: hit.normal2.into(),
+            .map(|(_, (entity_index, hit))| {
+                let entity = self.entity_from_index(entity_index);
+                ShapeHitData {
+                    entity,
+                    body_entity: self.body_entity(entity),
+                    time_of_impact: hit.toi,
+                    point1: hit.witness1.into(),
+                    point2: hit.witness2.into(),
+                    normal1: hit.normal1.into(),
+                    normal2: hit.normal2.into(),
+                }
             })
     }
 
@@ -435,13 +576,17 @@ impl SpatialQueryPipeline {
             if let Some(hit) =
                 self.qbvh
                     .traverse_best_first(&mut visitor)
-                    .map(|(_, (entity_index, hit))| ShapeHitData {
-                        entity: self.entity_from_index(entity_index),
-                        time_of_impact: hit.toi,
-                        point1: hit.witness1.into(),
-                        point2: hit.witness2.into(),
-                        normal1: hit.normal1.into(),
-                        normal2: hit.normal2.into(),
+                    .map(|(_, (entity_index, hit))| {
+                        let entity = self.entity_from_index(entity_index);
+                        ShapeHitData {
+                            entity,
+                            body_entity: self.body_entity(entity),
+                            time_of_impact: hit.toi,
+                            point1: hit.witness1.into(),
+                            point2: hit.witness2.into(),
+                            normal1: hit.normal1.into(),
+                            normal2: hit.normal2.into(),
+                        }
                     })
             {
                 query_filter.excluded_entities.insert(hit.entity);
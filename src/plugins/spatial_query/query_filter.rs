@@ -25,7 +25,8 @@ use crate::prelude::*;
 ///     commands.spawn(RayCaster::default().with_query_filter(query_filter));
 /// }
 /// ```
-#[derive(Clone)]
+#[derive(Reflect, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpatialQueryFilter {
     /// Specifies which [collision groups](CollisionLayers) will be included in a [spatial query](crate::spatial_query).
     pub masks: u32,
@@ -75,6 +76,9 @@ impl SpatialQueryFilter {
 
     /// Tests if an entity should be included in [spatial queries](crate::spatial_query) based on the
     /// filter configuration.
+    ///
+    /// Only the groups of the given `layers` are taken into account. For colliders with [`SpatialQueryLayers`],
+    /// the pipeline passes those groups here instead of the groups of the collider's [`CollisionLayers`].
     pub fn test(&self, entity: Entity, layers: CollisionLayers) -> bool {
         !self.excluded_entities.contains(&entity)
             && CollisionLayers::from_bits(0xffff_ffff, self.masks).interacts_with(
@@ -82,3 +86,90 @@ impl SpatialQueryFilter {
             )
     }
 }
+
+/// Defines the layers that a collider belongs to in [spatial queries](crate::spatial_query),
+/// separately from the groups of its [`CollisionLayers`].
+///
+/// By default, spatial queries use the groups of a collider's [`CollisionLayers`]. When this component
+/// is added, its groups are used for spatial queries instead, while [`CollisionLayers`] only determine
+/// which colliders generate contacts. This way a collider can, for example, be hit by ray casts
+/// without colliding with anything.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// #[derive(PhysicsLayer)]
+/// enum Layer {
+///     Ground,
+///     Foliage,
+/// }
+///
+/// fn spawn(mut commands: Commands) {
+///     commands.spawn((
+///         Collider::ball(0.5),
+///         // Foliage doesn't collide with anything...
+///         CollisionLayers::none(),
+///         // ...but it can be found with spatial queries that include the foliage layer
+///         SpatialQueryLayers::new([Layer::Foliage]),
+///     ));
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Component, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct SpatialQueryLayers {
+    groups: u32,
+}
+
+impl SpatialQueryLayers {
+    /// Creates a new [`SpatialQueryLayers`] configuration with the given groups.
+    pub fn new(groups: impl IntoIterator<Item = impl PhysicsLayer>) -> Self {
+        let mut bits = 0;
+        for group in groups.into_iter().map(|l| l.to_bits()) {
+            bits |= group;
+        }
+        Self::from_bits(bits)
+    }
+
+    /// Contains all groups.
+    pub fn all<L: PhysicsLayer>() -> Self {
+        Self::from_bits(L::all_bits())
+    }
+
+    /// Contains no groups. Colliders with this configuration are ignored by all spatial queries.
+    pub const fn none() -> Self {
+        Self::from_bits(0)
+    }
+
+    /// Creates a new [`SpatialQueryLayers`] configuration using a bitmask of groups.
+    pub const fn from_bits(groups: u32) -> Self {
+        Self { groups }
+    }
+
+    /// Returns true if the given layer is contained in `groups`.
+    pub fn contains_group(self, layer: impl PhysicsLayer) -> bool {
+        (self.groups & layer.to_bits()) != 0
+    }
+
+    /// Returns the `groups` bitmask.
+    pub fn groups_bits(self) -> u32 {
+        self.groups
+    }
+
+    /// Returns the given [`CollisionLayers`] with their groups replaced by these groups.
+    pub(crate) fn apply_to(self, layers: CollisionLayers) -> CollisionLayers {
+        CollisionLayers::from_bits(self.groups, layers.masks_bits())
+    }
+}
+
+impl Default for SpatialQueryLayers {
+    fn default() -> Self {
+        Self::from_bits(0xffff_ffff)
+    }
+}
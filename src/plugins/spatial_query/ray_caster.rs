@@ -1,8 +1,6 @@
 use crate::prelude::*;
 use bevy::prelude::*;
-use parry::query::{
-    details::RayCompositeShapeToiAndNormalBestFirstVisitor, visitors::RayIntersectionsVisitor,
-};
+use parry::query::visitors::RayIntersectionsVisitor;
 
 /// A component used for [ray casting](spatial_query#ray-casting).
 ///
@@ -59,7 +57,9 @@ use parry::query::{
 ///     }
 /// }
 /// ```
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
 pub struct RayCaster {
     /// Controls if the ray caster is enabled.
     pub enabled: bool,
@@ -68,12 +68,14 @@ pub struct RayCaster {
     /// To get the global origin, use the `global_origin` method.
     pub origin: Vector,
     /// The global origin of the ray.
+    #[cfg_attr(feature = "serde", serde(skip))]
     global_origin: Vector,
     /// The local direction of the ray relative to the [`Rotation`] of the ray entity or its parent.
     ///
     /// To get the global direction, use the `global_direction` method.
     pub direction: Vector,
     /// The global direction of the ray.
+    #[cfg_attr(feature = "serde", serde(skip))]
     global_direction: Vector,
     /// The maximum distance the ray can travel. By default this is infinite, so the ray will travel
     /// until all hits up to `max_hits` have been checked.
@@ -194,22 +196,12 @@ impl RayCaster {
     pub(crate) fn cast(&self, hits: &mut RayHits, query_pipeline: &SpatialQueryPipeline) {
         hits.count = 0;
         if self.max_hits == 1 {
-            let pipeline_shape = query_pipeline.as_composite_shape(self.query_filter.clone());
-            let ray =
-                parry::query::Ray::new(self.global_origin().into(), self.global_direction().into());
-            let mut visitor = RayCompositeShapeToiAndNormalBestFirstVisitor::new(
-                &pipeline_shape,
-                &ray,
+            if let Some(hit) = query_pipeline.cast_ray(
+                self.global_origin(),
+                self.global_direction(),
                 self.max_time_of_impact,
                 self.solid,
-            );
-
-            if let Some(hit) = query_pipeline.qbvh.traverse_best_first(&mut visitor).map(
-                |(_, (entity_index, hit))| RayHitData {
-                    entity: query_pipeline.entity_from_index(entity_index),
-                    time_of_impact: hit.toi,
-                    normal: hit.normal.into(),
-                },
+                self.query_filter.clone(),
             ) {
                 if (hits.vector.len() as u32) < hits.count + 1 {
                     hits.vector.push(hit);
@@ -224,10 +216,10 @@ impl RayCaster {
 
             let mut leaf_callback = &mut |entity_index: &u32| {
                 let entity = query_pipeline.entity_from_index(*entity_index);
-                if let Some((iso, shape, layers)) = query_pipeline.colliders.get(&entity) {
+                if let Some((_, _, layers)) = query_pipeline.colliders.get(&entity) {
                     if self.query_filter.test(entity, *layers) {
-                        if let Some(hit) = shape.cast_ray_and_get_normal(
-                            iso,
+                        if let Some(hit) = query_pipeline.cast_ray_against_collider(
+                            entity,
                             &ray,
                             self.max_time_of_impact,
                             self.solid,
@@ -235,12 +227,14 @@ impl RayCaster {
                             if (hits.vector.len() as u32) < hits.count + 1 {
                                 hits.vector.push(RayHitData {
                                     entity,
+                                    body_entity: query_pipeline.body_entity(entity),
                                     time_of_impact: hit.toi,
                                     normal: hit.normal.into(),
                                 });
                             } else {
                                 hits.vector[hits.count as usize] = RayHitData {
                                     entity,
+                                    body_entity: query_pipeline.body_entity(entity),
                                     time_of_impact: hit.toi,
                                     normal: hit.normal.into(),
                                 };
@@ -298,7 +292,9 @@ impl RayCaster {
 ///     }
 /// }
 /// ```
-#[derive(Component, Clone, Default)]
+#[derive(Component, Reflect, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
 pub struct RayHits {
     pub(crate) vector: Vec<RayHitData>,
     /// The number of hits.
@@ -346,10 +342,13 @@ impl RayHits {
 }
 
 /// Data related to a hit during a [ray cast](spatial_query#ray-casting).
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RayHitData {
     /// The entity of the collider that was hit by the ray.
     pub entity: Entity,
+    /// The entity of the rigid body that the collider is attached to, if any.
+    pub body_entity: Option<Entity>,
     /// How long the ray travelled, i.e. the distance between the ray origin and the point of intersection.
     pub time_of_impact: Scalar,
     /// The normal at the point of intersection.
@@ -46,7 +46,9 @@ use parry::query::details::TOICompositeShapeShapeBestFirstVisitor;
 ///     }
 /// }
 /// ```
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
 pub struct ShapeCaster {
     /// Controls if the shape caster is enabled.
     pub enabled: bool,
@@ -58,6 +60,7 @@ pub struct ShapeCaster {
     /// To get the global origin, use the `global_origin` method.
     pub origin: Vector,
     /// The global origin of the shape.
+    #[cfg_attr(feature = "serde", serde(skip))]
     global_origin: Vector,
     /// The local rotation of the shape being cast relative to the [`Rotation`]
     /// of the shape caster entity or its parent. Expressed in radians.
@@ -73,15 +76,18 @@ pub struct ShapeCaster {
     pub shape_rotation: Quaternion,
     /// The global rotation of the shape.
     #[cfg(feature = "2d")]
+    #[cfg_attr(feature = "serde", serde(skip))]
     global_shape_rotation: Scalar,
     /// The global rotation of the shape.
     #[cfg(feature = "3d")]
+    #[cfg_attr(feature = "serde", serde(skip))]
     global_shape_rotation: Quaternion,
     /// The local direction of the shape cast relative to the [`Rotation`] of the shape caster entity or its parent.
     ///
     /// To get the global direction, use the `global_direction` method.
     pub direction: Vector,
     /// The global direction of the shape cast.
+    #[cfg_attr(feature = "serde", serde(skip))]
     global_direction: Vector,
     /// The maximum distance the shape can travel. By default this is infinite, so the shape will travel
     /// until a hit is found.
@@ -278,13 +284,17 @@ impl ShapeCaster {
             );
 
             if let Some(hit) = query_pipeline.qbvh.traverse_best_first(&mut visitor).map(
-                |(_, (entity_index, hit))| ShapeHitData {
-                    entity: query_pipeline.entity_from_index(entity_index),
-                    time_of_impact: hit.toi,
-                    point1: hit.witness1.into(),
-                    point2: hit.witness2.into(),
-                    normal1: hit.normal1.into(),
-                    normal2: hit.normal2.into(),
+                |(_, (entity_index, hit))| {
+                    let entity = query_pipeline.entity_from_index(entity_index);
+                    ShapeHitData {
+                        entity,
+                        body_entity: query_pipeline.body_entity(entity),
+                        time_of_impact: hit.toi,
+                        point1: hit.witness1.into(),
+                        point2: hit.witness2.into(),
+                        normal1: hit.normal1.into(),
+                        normal2: hit.normal2.into(),
+                    }
                 },
             ) {
                 if (hits.vector.len() as u32) < hits.count + 1 {
@@ -328,7 +338,9 @@ impl ShapeCaster {
 ///     }
 /// }
 /// ```
-#[derive(Component, Clone, Debug)]
+#[derive(Component, Reflect, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
 pub struct ShapeHits {
     pub(crate) vector: Vec<ShapeHitData>,
     pub(crate) count: u32,
@@ -364,10 +376,13 @@ impl ShapeHits {
 }
 
 /// Data related to a hit during a [shape cast](spatial_query#shape-casting).
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShapeHitData {
     /// The entity of the collider that was hit by the shape.
     pub entity: Entity,
+    /// The entity of the rigid body that the collider is attached to, if any.
+    pub body_entity: Option<Entity>,
     /// How long the shape travelled before the initial hit,
     /// i.e. the distance between the origin and the point of intersection.
     pub time_of_impact: Scalar,
@@ -0,0 +1,250 @@
+use crate::prelude::*;
+use bevy::prelude::*;
+
+/// A component that keeps an entity, typically a third-person camera, at an offset from a target
+/// without clipping through colliders.
+///
+/// Each frame, the [`shape`](#structfield.shape) of the spring arm is [cast](spatial_query#shape-casting)
+/// from the pivot, which is at [`pivot_offset`](#structfield.pivot_offset) from the [`Position`] of the
+/// [`target`](#structfield.target), toward the desired [`offset`](#structfield.offset). If the shape hits
+/// something, the arm is shortened so that the shape stops [`margin`](#structfield.margin) before the hit.
+/// The collision-free position of the end of the arm can be read with [`SpringArm::position`], and by default,
+/// it is also written to the translation of the entity's `Transform`.
+///
+/// The arm shortens immediately to avoid clipping, but it extends back smoothly according to
+/// [`smoothing`](#structfield.smoothing) once the obstacle is gone.
+///
+/// Colliders attached to the target body are ignored automatically. Other colliders can be excluded using the
+/// [`query_filter`](#structfield.query_filter).
+///
+/// The spring arm is updated after the physics simulation and [`PhysicsSet::Sync`], so the target and
+/// the offset can be changed freely in `Update`, for example to orbit the camera around a character.
+/// If the target has [`TransformInterpolation`], the arm follows its interpolated `Transform` instead of
+/// its [`Position`], so that the camera doesn't stutter when physics runs at a different rate than rendering.
+/// The entity with the spring arm shouldn't have a parent if the `Transform` is updated, as the position
+/// is written in global space.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// # #[cfg(all(feature = "3d", feature = "f32"))]
+/// fn setup(mut commands: Commands) {
+///     let player = commands
+///         .spawn((RigidBody::Dynamic, Collider::capsule(1.0, 0.5)))
+///         .id();
+///
+///     // Follow the player from behind and above, pulling the camera closer if a wall is in the way
+///     commands.spawn((
+///         Camera3dBundle::default(),
+///         SpringArm::new(player, Vec3::new(0.0, 2.0, 8.0), Collider::ball(0.2))
+///             .with_pivot_offset(Vec3::Y)
+///             .with_smoothing(8.0),
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct SpringArm {
+    /// Controls if the spring arm is enabled. Disabled spring arms are not updated.
+    pub enabled: bool,
+    /// The entity that the spring arm is attached to. It should have a [`Position`].
+    pub target: Entity,
+    /// The offset of the pivot of the arm from the [`Position`] of the target in world space.
+    pub pivot_offset: Vector,
+    /// The desired offset of the end of the arm from the pivot in world space.
+    /// The arm is never longer than this offset.
+    pub offset: Vector,
+    /// The shape that is cast along the arm, represented as a [`Collider`].
+    /// It should roughly cover the near plane of the camera.
+    pub shape: Collider,
+    /// The distance that is kept between the shape and the hit colliders.
+    pub margin: Scalar,
+    /// How fast the arm extends back to its full length after being shortened, as the rate of
+    /// exponential decay per second. Zero extends it immediately.
+    pub smoothing: Scalar,
+    /// Rules that determine which colliders are taken into account when casting the shape.
+    pub query_filter: SpatialQueryFilter,
+    /// If true, the resulting position is written to the translation of the entity's `Transform`.
+    /// True by default.
+    pub update_transform: bool,
+    /// The current length of the arm, or `None` if it hasn't been computed yet.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    length: Option<Scalar>,
+    /// The current collision-free position of the end of the arm in world space.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    position: Vector,
+}
+
+impl Default for SpringArm {
+    fn default() -> Self {
+        #[cfg(feature = "2d")]
+        let shape = Collider::ball(1.0);
+        #[cfg(feature = "3d")]
+        let shape = Collider::ball(0.1);
+
+        Self::new(Entity::PLACEHOLDER, Vector::ZERO, shape)
+    }
+}
+
+impl SpringArm {
+    /// Creates a [`SpringArm`] attached to the given `target` entity, with the desired `offset` of the end
+    /// of the arm from the pivot and the `shape` that is cast along the arm.
+    pub fn new(target: Entity, offset: Vector, shape: Collider) -> Self {
+        Self {
+            enabled: true,
+            target,
+            pivot_offset: Vector::ZERO,
+            offset,
+            shape,
+            margin: 0.0,
+            smoothing: 0.0,
+            query_filter: SpatialQueryFilter::default(),
+            update_transform: true,
+            length: None,
+            position: Vector::ZERO,
+        }
+    }
+
+    /// Sets the offset of the pivot of the arm from the [`Position`] of the target in world space.
+    pub fn with_pivot_offset(mut self, pivot_offset: Vector) -> Self {
+        self.pivot_offset = pivot_offset;
+        self
+    }
+
+    /// Sets the distance that is kept between the cast shape and the hit colliders.
+    pub fn with_margin(mut self, margin: Scalar) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Sets how fast the arm extends back to its full length after being shortened, as the rate of
+    /// exponential decay per second. Zero extends it immediately.
+    pub fn with_smoothing(mut self, smoothing: Scalar) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    /// Sets the spring arm's [query filter](SpatialQueryFilter) that controls which colliders
+    /// are taken into account when casting the shape.
+    pub fn with_query_filter(mut self, query_filter: SpatialQueryFilter) -> Self {
+        self.query_filter = query_filter;
+        self
+    }
+
+    /// Sets whether the resulting position is written to the translation of the entity's `Transform`.
+    pub fn with_transform_updates(mut self, update_transform: bool) -> Self {
+        self.update_transform = update_transform;
+        self
+    }
+
+    /// Returns the current length of the arm. Before the first update, this is the length of the desired offset.
+    pub fn length(&self) -> Scalar {
+        self.length.unwrap_or(self.offset.length())
+    }
+
+    /// Returns the current collision-free position of the end of the arm in world space.
+    pub fn position(&self) -> Vector {
+        self.position
+    }
+
+    /// Returns true if the arm is currently shorter than the desired offset.
+    pub fn is_retracted(&self) -> bool {
+        self.length() < self.offset.length()
+    }
+
+    /// Casts the shape along the arm and updates the length and position of the arm.
+    fn update(
+        &mut self,
+        target_position: Vector,
+        delta_seconds: Scalar,
+        spatial_query: &SpatialQuery,
+    ) {
+        let pivot = target_position + self.pivot_offset;
+        let max_length = self.offset.length();
+        let direction = self.offset.normalize_or_zero();
+
+        // Find the first hit that doesn't belong to the target body
+        let mut hit_length = max_length;
+        if direction != Vector::ZERO {
+            let mut query_filter = self.query_filter.clone();
+            query_filter.excluded_entities.insert(self.target);
+            spatial_query.shape_hits_callback(
+                &self.shape,
+                pivot,
+                RotationValue::default(),
+                direction,
+                max_length,
+                false,
+                query_filter,
+                |hit| {
+                    if hit.body_entity == Some(self.target) {
+                        return true;
+                    }
+                    hit_length = (hit.time_of_impact - self.margin).max(0.0);
+                    false
+                },
+            );
+        }
+
+        // Retract immediately to avoid clipping, but extend smoothly
+        let length = match self.length {
+            Some(length) if hit_length > length && self.smoothing > 0.0 => {
+                let factor = 1.0 - math::ops::exp(-self.smoothing * delta_seconds);
+                length + (hit_length - length) * factor
+            }
+            _ => hit_length,
+        };
+
+        self.length = Some(length);
+        self.position = pivot + direction * length;
+    }
+}
+
+pub(super) fn update_spring_arms(
+    mut spring_arms: Query<(&mut SpringArm, Option<&mut Transform>)>,
+    targets: Query<&Position>,
+    interpolated_targets: Query<&Transform, (With<TransformInterpolation>, Without<SpringArm>)>,
+    spatial_query: SpatialQuery,
+    time: Res<Time>,
+) {
+    let delta_seconds = time.delta_seconds_f64().adjust_precision();
+
+    for (mut spring_arm, transform) in &mut spring_arms {
+        if !spring_arm.enabled {
+            continue;
+        }
+        let Ok(position) = targets.get(spring_arm.target) else {
+            continue;
+        };
+
+        // Follow the rendered pose of interpolated targets
+        let target_position = match interpolated_targets.get(spring_arm.target) {
+            #[cfg(feature = "2d")]
+            Ok(transform) => transform.translation.truncate().adjust_precision(),
+            #[cfg(feature = "3d")]
+            Ok(transform) => transform.translation.adjust_precision(),
+            Err(_) => position.0,
+        };
+
+        spring_arm.update(target_position, delta_seconds, &spatial_query);
+
+        if let (true, Some(mut transform)) = (spring_arm.update_transform, transform) {
+            #[cfg(feature = "2d")]
+            {
+                transform.translation =
+                    spring_arm.position.as_f32().extend(transform.translation.z);
+            }
+            #[cfg(feature = "3d")]
+            {
+                transform.translation = spring_arm.position.as_f32();
+            }
+        }
+    }
+}
@@ -1,6 +1,17 @@
 use crate::prelude::*;
 use bevy::{ecs::system::SystemParam, prelude::*};
 
+type SpatialQueryColliderComponents = (
+    Entity,
+    &'static Position,
+    &'static Rotation,
+    Option<&'static ColliderOffset>,
+    &'static Collider,
+    Option<&'static CollisionLayers>,
+    Option<&'static SpatialQueryLayers>,
+    Option<&'static ColliderParent>,
+);
+
 /// A system parameter for performing [spatial queries](spatial_query).
 ///
 /// ## Methods
@@ -61,17 +72,7 @@ use bevy::{ecs::system::SystemParam, prelude::*};
 /// ```
 #[derive(SystemParam)]
 pub struct SpatialQuery<'w, 's> {
-    pub(crate) colliders: Query<
-        'w,
-        's,
-        (
-            Entity,
-            &'static Position,
-            &'static Rotation,
-            &'static Collider,
-            Option<&'static CollisionLayers>,
-        ),
-    >,
+    pub(crate) colliders: Query<'w, 's, SpatialQueryColliderComponents>,
     pub(crate) added_colliders: Query<'w, 's, Entity, Added<Collider>>,
     /// The [`SpatialQueryPipeline`].
     pub query_pipeline: ResMut<'w, SpatialQueryPipeline>,
@@ -0,0 +1,348 @@
+//! Converts long-lived, low-slip contacts between bodies with [`StickyContact`] into temporary soft welds.
+//!
+//! See [`StickyContactsPlugin`].
+
+use crate::prelude::*;
+use bevy::{prelude::*, utils::HashSet};
+
+/// Converts long-lived, low-slip contacts between [rigid bodies](RigidBody) that both have a [`StickyContact`]
+/// component into temporary soft welds, and breaks the welds when they are overloaded.
+///
+/// Contacts alone let stacked bodies creep and slide a little at every bump, for example cargo on a moving
+/// truck, as friction is only an approximation. Once a pair of bodies has been resting on each other for long
+/// enough without slipping, a compliant [`FixedJoint`] marked with [`StickyWeld`] holds them together in their
+/// current relative pose until the force needed to do so exceeds the [break force](StickyContact::break_force).
+/// When a weld breaks, a [`StickyWeldBroken`]
+/// event is sent, and the contacts between the bodies have to persist again before a new weld can be created.
+///
+/// The welds are created and broken after the simulation has been stepped.
+pub struct StickyContactsPlugin {
+    schedule: Box<dyn ScheduleLabel>,
+}
+
+impl StickyContactsPlugin {
+    /// Creates a [`StickyContactsPlugin`] with the schedule that is used for running the [`PhysicsSchedule`].
+    ///
+    /// The default schedule is `PostUpdate`.
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: Box::new(schedule),
+        }
+    }
+}
+
+impl Default for StickyContactsPlugin {
+    fn default() -> Self {
+        Self::new(PostUpdate)
+    }
+}
+
+impl Plugin for StickyContactsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<StickyWeldBroken>()
+            .register_type::<StickyContact>()
+            .register_type::<StickyWeld>()
+            .register_type::<StickyWeldBroken>()
+            .add_systems(
+                self.schedule.dyn_clone(),
+                (break_sticky_welds, create_sticky_welds)
+                    .chain()
+                    .after(PhysicsSet::StepSimulation)
+                    .before(PhysicsSet::Sync),
+            );
+    }
+}
+
+/// A component that lets the contacts between this body and other bodies with [`StickyContact`]
+/// be converted into temporary soft welds. See [`StickyContactsPlugin`].
+///
+/// When the configurations of the two bodies differ, the stricter value of each property is used.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// # #[cfg(all(feature = "3d", feature = "f32"))]
+/// fn setup(mut commands: Commands) {
+///     // Crates that stick to each other and to the truck bed once they have settled
+///     let sticky = StickyContact::default().with_break_force(5000.0);
+///     commands.spawn((RigidBody::Dynamic, Collider::cuboid(4.0, 0.2, 8.0), sticky));
+///     for i in 0..3 {
+///         commands.spawn((
+///             RigidBody::Dynamic,
+///             Collider::cuboid(1.0, 1.0, 1.0),
+///             Position(Vec3::Y * (0.6 + i as f32)),
+///             sticky,
+///         ));
+///     }
+/// }
+/// ```
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct StickyContact {
+    /// The number of substeps that every contact between the bodies must have persisted for
+    /// before the bodies are welded. See [`ContactData::age`].
+    pub min_age: u32,
+    /// The maximum relative tangential speed of the bodies at each contact point for the bodies to be welded.
+    pub max_slip_speed: Scalar,
+    /// The compliance of the weld, the inverse of its stiffness.
+    pub compliance: Scalar,
+    /// The force at which the weld breaks.
+    pub break_force: Scalar,
+}
+
+impl Default for StickyContact {
+    fn default() -> Self {
+        Self {
+            min_age: 60,
+            #[cfg(feature = "2d")]
+            max_slip_speed: 1.0,
+            #[cfg(feature = "3d")]
+            max_slip_speed: 0.01,
+            compliance: 0.000_01,
+            break_force: Scalar::INFINITY,
+        }
+    }
+}
+
+impl StickyContact {
+    /// Sets the number of substeps that every contact must have persisted for before the bodies are welded.
+    pub fn with_min_age(mut self, min_age: u32) -> Self {
+        self.min_age = min_age;
+        self
+    }
+
+    /// Sets the maximum relative tangential speed at each contact point for the bodies to be welded.
+    pub fn with_max_slip_speed(mut self, max_slip_speed: Scalar) -> Self {
+        self.max_slip_speed = max_slip_speed;
+        self
+    }
+
+    /// Sets the compliance of the weld.
+    pub fn with_compliance(mut self, compliance: Scalar) -> Self {
+        self.compliance = compliance;
+        self
+    }
+
+    /// Sets the force at which the weld breaks.
+    pub fn with_break_force(mut self, break_force: Scalar) -> Self {
+        self.break_force = break_force;
+        self
+    }
+
+    /// Combines the configurations of two bodies, using the stricter value of each property.
+    fn combine(&self, other: &Self) -> Self {
+        Self {
+            min_age: self.min_age.max(other.min_age),
+            max_slip_speed: self.max_slip_speed.min(other.max_slip_speed),
+            compliance: self.compliance.max(other.compliance),
+            break_force: self.break_force.min(other.break_force),
+        }
+    }
+}
+
+/// Marks a [`FixedJoint`] as a temporary soft weld between two bodies with [`StickyContact`], created from
+/// their contacts by the [`StickyContactsPlugin`].
+///
+/// The joint keeps the [relative rotation](FixedJoint::rest_rotation) that the bodies had when it was created.
+/// The joint entity is despawned when the [force](FixedJoint::force) of the joint exceeds the
+/// [`break_force`](#structfield.break_force), or when either body is despawned. It can also be
+/// despawned manually to release the bodies.
+///
+/// Note that if the [`WeldMerging`] resource is inserted, welds with zero compliance between dynamic bodies
+/// merge the bodies, and the welds no longer exert a force that could break them.
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct StickyWeld {
+    /// The force at which the weld breaks.
+    pub break_force: Scalar,
+}
+
+impl Default for StickyWeld {
+    fn default() -> Self {
+        Self::new(Scalar::INFINITY)
+    }
+}
+
+impl StickyWeld {
+    /// Creates a new [`StickyWeld`] that breaks at the given force.
+    pub fn new(break_force: Scalar) -> Self {
+        Self { break_force }
+    }
+}
+
+/// An event that is sent when a [`StickyWeld`] breaks because its force exceeded the break force.
+#[derive(Event, Reflect, Clone, Copy, Debug, PartialEq)]
+pub struct StickyWeldBroken {
+    /// The first body of the weld.
+    pub entity1: Entity,
+    /// The second body of the weld.
+    pub entity2: Entity,
+    /// The force of the weld when it broke.
+    pub force: Vector,
+}
+
+/// Removes welds that are overloaded or whose bodies no longer exist.
+fn break_sticky_welds(
+    mut commands: Commands,
+    welds: Query<(Entity, &FixedJoint, &StickyWeld)>,
+    bodies: Query<(), With<RigidBody>>,
+    mut collisions: ResMut<Collisions>,
+    mut broken_ev_writer: EventWriter<StickyWeldBroken>,
+) {
+    for (entity, weld, sticky_weld) in &welds {
+        if !bodies.contains(weld.entity1) || !bodies.contains(weld.entity2) {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        if weld.force.length() <= sticky_weld.break_force {
+            continue;
+        }
+
+        commands.entity(entity).despawn_recursive();
+
+        // The contacts have to persist again before the bodies can be welded again
+        for contacts in collisions.iter_mut().filter(|contacts| {
+            let bodies = (contacts.body_entity1, contacts.body_entity2);
+            bodies == (Some(weld.entity1), Some(weld.entity2))
+                || bodies == (Some(weld.entity2), Some(weld.entity1))
+        }) {
+            for manifold in contacts.manifolds.iter_mut() {
+                for contact in manifold.contacts.iter_mut() {
+                    contact.age = 0;
+                }
+            }
+        }
+
+        broken_ev_writer.send(StickyWeldBroken {
+            entity1: weld.entity1,
+            entity2: weld.entity2,
+            force: weld.force,
+        });
+    }
+}
+
+type StickyBodyComponents = (
+    &'static RigidBody,
+    &'static StickyContact,
+    &'static Position,
+    &'static Rotation,
+    &'static CenterOfMass,
+    &'static LinearVelocity,
+    &'static AngularVelocity,
+);
+
+/// Welds pairs of bodies with [`StickyContact`] whose contacts are old enough and aren't slipping.
+fn create_sticky_welds(
+    mut commands: Commands,
+    bodies: Query<StickyBodyComponents>,
+    colliders: Query<(&Position, &Rotation, Option<&ColliderOffset>), With<Collider>>,
+    welds: Query<&FixedJoint, With<StickyWeld>>,
+    collisions: Res<Collisions>,
+) {
+    // The pairs of bodies that are already welded
+    let mut welded: HashSet<(Entity, Entity)> = welds
+        .iter()
+        .flat_map(|weld| [(weld.entity1, weld.entity2), (weld.entity2, weld.entity1)])
+        .collect();
+
+    for contacts in collisions.iter() {
+        let (Some(body_entity1), Some(body_entity2)) =
+            (contacts.body_entity1, contacts.body_entity2)
+        else {
+            continue;
+        };
+        if body_entity1 == body_entity2 || welded.contains(&(body_entity1, body_entity2)) {
+            continue;
+        }
+        let Ok([body1, body2]) = bodies.get_many([body_entity1, body_entity2]) else {
+            continue;
+        };
+        let (rb1, sticky1, pos1, rot1, com1, lin_vel1, ang_vel1) = body1;
+        let (rb2, sticky2, pos2, rot2, com2, lin_vel2, ang_vel2) = body2;
+        if !rb1.is_dynamic() && !rb2.is_dynamic() {
+            continue;
+        }
+        let Ok([(collider_pos1, collider_rot1, offset1), (collider_pos2, collider_rot2, offset2)]) =
+            colliders.get_many([contacts.entity1, contacts.entity2])
+        else {
+            continue;
+        };
+
+        // Contacts are expressed relative to the offset colliders
+        let (collider_pos1, collider_rot1) =
+            offset1.map_or((*collider_pos1, *collider_rot1), |o| {
+                let (pos, rot) = o.transform_pose(collider_pos1.0, *collider_rot1);
+                (Position(pos), rot)
+            });
+        let (collider_pos2, collider_rot2) =
+            offset2.map_or((*collider_pos2, *collider_rot2), |o| {
+                let (pos, rot) = o.transform_pose(collider_pos2.0, *collider_rot2);
+                (Position(pos), rot)
+            });
+
+        let sticky = sticky1.combine(sticky2);
+        let global_com1 = pos1.0 + rot1.rotate(com1.0);
+        let global_com2 = pos2.0 + rot2.rotate(com2.0);
+
+        // All contacts must be old enough and the bodies must not be slipping at any of them
+        let mut contact_count = 0;
+        let mut contact_point_sum = Vector::ZERO;
+        let is_sticking = contacts
+            .manifolds
+            .iter()
+            .flat_map(|manifold| manifold.contacts.iter())
+            .all(|contact| {
+                let point1 = contact.global_point1(&collider_pos1, &collider_rot1);
+                let point2 = contact.global_point2(&collider_pos2, &collider_rot2);
+                let normal = contact.global_normal1(&collider_rot1);
+
+                let vel1 = lin_vel1.0 + point_velocity(ang_vel1, point1 - global_com1);
+                let vel2 = lin_vel2.0 + point_velocity(ang_vel2, point2 - global_com2);
+                let relative_vel = vel1 - vel2;
+                let tangent_vel = relative_vel - normal * relative_vel.dot(normal);
+
+                contact_count += 1;
+                contact_point_sum += (point1 + point2) * 0.5;
+
+                contact.age >= sticky.min_age && tangent_vel.length() <= sticky.max_slip_speed
+            });
+
+        if !is_sticking || contact_count == 0 {
+            continue;
+        }
+
+        // Weld the bodies at the center of the contacts, keeping their current relative pose
+        let anchor = contact_point_sum / contact_count as Scalar;
+        commands.spawn((
+            FixedJoint::new(body_entity1, body_entity2)
+                .with_local_anchor_1(rot1.inverse().rotate(anchor - pos1.0))
+                .with_local_anchor_2(rot2.inverse().rotate(anchor - pos2.0))
+                .with_rest_rotation(rot1.inverse().mul(*rot2))
+                .with_compliance(sticky.compliance),
+            StickyWeld::new(sticky.break_force),
+        ));
+        welded.insert((body_entity1, body_entity2));
+        welded.insert((body_entity2, body_entity1));
+    }
+}
+
+/// Computes the velocity of a point at the offset `r` from the center of mass caused by the angular velocity.
+#[cfg(feature = "2d")]
+fn point_velocity(ang_vel: &AngularVelocity, r: Vector) -> Vector {
+    ang_vel.0 * r.perp()
+}
+
+/// Computes the velocity of a point at the offset `r` from the center of mass caused by the angular velocity.
+#[cfg(feature = "3d")]
+fn point_velocity(ang_vel: &AngularVelocity, r: Vector) -> Vector {
+    ang_vel.0.cross(r)
+}
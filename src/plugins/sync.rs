@@ -4,7 +4,7 @@
 //! See [`SyncPlugin`].
 
 use crate::prelude::*;
-use bevy::prelude::*;
+use bevy::{ecs::system::Command, prelude::*, transform::TransformSystem};
 
 /// Responsible for synchronizing physics components with other data, like keeping [`Position`]
 /// and [`Rotation`] in sync with `Transform`.
@@ -26,6 +26,28 @@ use bevy::prelude::*;
 ///
 /// If you would like a child entity to be rigidly attached to its parent, you could use a [`FixedJoint`]
 /// or write your own system to handle hierarchies differently.
+///
+/// Colliders on child entities without a [`RigidBody`] are an exception: they are [attached](ColliderParent)
+/// to the closest rigid body ancestor, and their `Transform`s are left to the transform hierarchy.
+///
+/// ## Transform interpolation
+///
+/// When physics runs at a fixed timestep, the number of physics steps per frame varies, which can make
+/// bodies stutter, especially when physics runs at a lower rate than the frame rate, like 50 Hz physics
+/// rendered at 144 Hz. To smooth out the motion, the `Transform` of a body can be interpolated between its
+/// poses at the start and end of the latest physics step by adding the [`TransformInterpolation`] component,
+/// or for all rigid bodies by setting [`InterpolateAllTransforms`] to true.
+///
+/// Interpolated bodies are rendered up to one physics step late. Latency-sensitive bodies like the player character
+/// can use [`TransformInterpolation::extrapolate`] to predict the `Transform` from the latest pose and the velocity
+/// of the body instead.
+///
+/// The interpolated `Transform` is written in `PostUpdate` before transform propagation, even when physics runs
+/// in `FixedUpdate`, and is replaced by the actual pose of the body again in `PreUpdate`.
+/// This means that systems in `Update` and the physics engine only ever see the actual poses of bodies,
+/// and that setting the `Transform` of a body still teleports it.
+///
+/// Only rigid bodies without a `Parent` are interpolated.
 pub struct SyncPlugin {
     schedule: Box<dyn ScheduleLabel>,
 }
@@ -50,7 +72,22 @@ impl Default for SyncPlugin {
 impl Plugin for SyncPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<SyncConfig>()
-            .register_type::<SyncConfig>();
+            .init_resource::<InterpolateAllTransforms>()
+            .register_type::<SyncConfig>()
+            .register_type::<InterpolateAllTransforms>()
+            .register_type::<TransformInterpolation>()
+            .register_type::<TransformInterpolationMode>();
+
+        // Replace interpolated transforms with the actual poses of the bodies before any game logic runs
+        app.add_systems(
+            PreUpdate,
+            (
+                insert_transform_interpolation
+                    .run_if(|interpolate_all: Res<InterpolateAllTransforms>| interpolate_all.0),
+                restore_interpolated_transforms,
+            )
+                .chain(),
+        );
 
         // Initialize `PreviousGlobalTransform` and apply `Transform` changes that happened
         // between the end of the previous physics frame and the start of this physics frame.
@@ -60,6 +97,8 @@ impl Plugin for SyncPlugin {
                 bevy::transform::systems::sync_simple_transforms,
                 bevy::transform::systems::propagate_transforms,
                 init_previous_global_transform,
+                // Make sure new entities have `PreviousGlobalTransform` before it's updated
+                apply_deferred,
                 transform_to_position,
                 // Update `PreviousGlobalTransform` for the physics step's `GlobalTransform` change detection
                 update_previous_global_transforms,
@@ -98,11 +137,31 @@ impl Plugin for SyncPlugin {
                 .in_set(PhysicsSet::Sync)
                 .run_if(|config: Res<SyncConfig>| config.position_to_transform),
         );
+
+        // Store the poses of interpolated bodies before each physics step
+        app.get_schedule_mut(PhysicsSchedule)
+            .expect("add PhysicsSchedule first")
+            .add_systems(
+                store_interpolation_start
+                    .before(PhysicsStepSet::BroadPhase)
+                    // Only rigid bodies are interpolated, and only the positions of child colliders are updated
+                    .ambiguous_with(super::prepare::update_child_collider_position),
+            );
+
+        // Interpolate transforms once per frame, after physics has been run
+        app.add_systems(
+            PostUpdate,
+            interpolate_transforms
+                .after(PhysicsSet::Sync)
+                .before(TransformSystem::TransformPropagate)
+                .run_if(|config: Res<SyncConfig>| config.position_to_transform),
+        );
     }
 }
 
 /// Configures what physics data is synchronized by the [`SyncPlugin`] and how.
 #[derive(Resource, Reflect, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Resource)]
 pub struct SyncConfig {
     /// Updates transforms based on [`Position`] and [`Rotation`] changes. Defaults to true.
@@ -121,6 +180,181 @@ impl Default for SyncConfig {
     }
 }
 
+/// Interpolates the `Transform` of a [rigid body](RigidBody) between its poses at the start and end
+/// of the latest physics step, so that the body moves smoothly even if physics runs at a lower rate than the frame rate.
+///
+/// Interpolation renders bodies up to one physics step behind the simulation. For latency-sensitive bodies
+/// like the player character, the `Transform` can be [extrapolated](TransformInterpolationMode::Extrapolate)
+/// from the latest pose using the velocity of the body instead.
+///
+/// Bodies that are teleported during a physics step, for example by changing their [`Position`] in the
+/// [`PhysicsSchedule`], would sweep across the world for one step. This can be avoided by resetting the interpolation
+/// using [`TransformInterpolation::reset`] or the [`ResetInterpolation`] command, or automatically by setting
+/// a [teleport threshold](TransformInterpolation::with_teleport_threshold).
+///
+/// See the [`SyncPlugin`](SyncPlugin#transform-interpolation) for more information.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     // A crate that is interpolated smoothly
+///     commands.spawn((
+///         RigidBody::Dynamic,
+///         Collider::ball(0.5),
+///         TransformInterpolation::default(),
+///     ));
+///
+///     // A player whose rendered pose is predicted ahead of the latest physics step
+///     commands.spawn((
+///         RigidBody::Dynamic,
+///         Collider::ball(0.5),
+///         TransformInterpolation::extrapolate(),
+///     ));
+///
+///     // A body that is teleported instead of interpolated when it moves more than 5 units in one step
+///     commands.spawn((
+///         RigidBody::Dynamic,
+///         Collider::ball(0.5),
+///         TransformInterpolation::default().with_teleport_threshold(5.0),
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct TransformInterpolation {
+    /// How the `Transform` of the body is computed between physics steps.
+    pub mode: TransformInterpolationMode,
+    /// The distance that the body must move during one physics step for the movement to be treated as a teleport.
+    /// Teleported bodies are moved directly to their new pose instead of being interpolated. Defaults to `None`,
+    /// which means that bodies are always interpolated unless the interpolation is [reset](Self::reset).
+    pub teleport_threshold: Option<Scalar>,
+    /// The translation and rotation of the body at the start of the latest physics step.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[reflect(ignore)]
+    start: Option<(Vec3, Quat)>,
+    /// The translation and rotation of the body at the end of the latest physics step.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[reflect(ignore)]
+    end: Option<(Vec3, Quat)>,
+    /// The interpolated translation and rotation that were written to `Transform`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[reflect(ignore)]
+    interpolated: Option<(Vec3, Quat)>,
+}
+
+impl TransformInterpolation {
+    /// Creates a [`TransformInterpolation`] that interpolates between the poses at the start and end
+    /// of the latest physics step. This is the default.
+    pub fn interpolate() -> Self {
+        Self::default()
+    }
+
+    /// Creates a [`TransformInterpolation`] that extrapolates from the pose at the end of the latest physics step
+    /// using the velocity of the body.
+    pub fn extrapolate() -> Self {
+        Self {
+            mode: TransformInterpolationMode::Extrapolate,
+            ..default()
+        }
+    }
+
+    /// Sets the distance that the body must move during one physics step for the movement to be treated
+    /// as a teleport. Teleported bodies are moved directly to their new pose instead of being interpolated.
+    pub fn with_teleport_threshold(self, threshold: Scalar) -> Self {
+        Self {
+            teleport_threshold: Some(threshold),
+            ..self
+        }
+    }
+
+    /// Resets the interpolation so that the body is teleported to its pose at the end of the latest physics step
+    /// instead of being interpolated from its previous pose. Use this when teleporting bodies by changing
+    /// their [`Position`] during the physics frame.
+    pub fn reset(&mut self) {
+        self.start = None;
+    }
+}
+
+/// A [`Command`] that [resets](TransformInterpolation::reset) the [`TransformInterpolation`] of an `entity`
+/// so that it is teleported to its pose at the end of the latest physics step instead of being interpolated
+/// from its previous pose.
+///
+/// Commands added in the [`PhysicsSchedule`] are applied at the end of the physics step, so this can be used
+/// by systems that teleport bodies during the step.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ResetInterpolation {
+    /// The entity whose interpolation is reset.
+    pub entity: Entity,
+}
+
+impl ResetInterpolation {
+    /// Creates a command that resets the interpolation of the given `entity`.
+    pub fn new(entity: Entity) -> Self {
+        Self { entity }
+    }
+}
+
+impl Command for ResetInterpolation {
+    fn apply(self, world: &mut World) {
+        if let Some(mut interpolation) = world.get_mut::<TransformInterpolation>(self.entity) {
+            interpolation.reset();
+        }
+    }
+}
+
+/// How the `Transform` of a body with [`TransformInterpolation`] is computed between physics steps.
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TransformInterpolationMode {
+    /// Interpolates between the poses at the start and end of the latest physics step.
+    ///
+    /// The motion is always smooth and matches the simulation, but it is rendered up to one step late.
+    #[default]
+    Interpolate,
+    /// Predicts the pose from the pose at the end of the latest physics step using the [`LinearVelocity`]
+    /// and [`AngularVelocity`] of the body.
+    ///
+    /// There is no added latency, but the prediction overshoots when the body hits something or changes direction,
+    /// which can cause small visible corrections.
+    Extrapolate,
+}
+
+/// Enables [transform interpolation](SyncPlugin#transform-interpolation) for all [rigid bodies](RigidBody)
+/// by adding the [`TransformInterpolation`] component to them. Defaults to false.
+///
+/// Setting this back to false doesn't remove [`TransformInterpolation`] from the bodies that it was added to.
+///
+/// ## Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn main() {
+///     App::new()
+///         .add_plugins((DefaultPlugins, PhysicsPlugins::default()))
+///         // Run physics at 50 Hz and interpolate the transforms of all bodies in between
+///         .insert_resource(PhysicsTimestep::Fixed(1.0 / 50.0))
+///         .insert_resource(InterpolateAllTransforms(true))
+///         .run();
+/// }
+/// ```
+#[derive(Resource, Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Resource)]
+pub struct InterpolateAllTransforms(pub bool);
+
 /// The global transform of a body at the end of the previous frame.
 /// Used for detecting if the transform was modified before the start of the physics schedule.
 #[derive(Component, Reflect, Clone, Copy, Debug, Default, Deref, DerefMut, PartialEq)]
@@ -144,14 +378,21 @@ fn init_previous_global_transform(
 /// This allows users to use transforms for moving and positioning bodies and colliders.
 ///
 /// To account for hierarchies, transform propagation should be run before this system.
+///
+/// Colliders attached to the children of rigid bodies are skipped, as their positions
+/// are determined by the body and their [`ColliderTransform`]. Entities whose `GlobalTransform`
+/// hasn't changed since the system last ran are skipped too, so static and sleeping bodies aren't visited.
 fn transform_to_position(
-    mut query: Query<(
-        &GlobalTransform,
-        &PreviousGlobalTransform,
-        &mut Position,
-        Option<&AccumulatedTranslation>,
-        &mut Rotation,
-    )>,
+    mut query: Query<
+        (
+            &GlobalTransform,
+            &PreviousGlobalTransform,
+            &mut Position,
+            Option<&AccumulatedTranslation>,
+            &mut Rotation,
+        ),
+        (NotChildCollider, Changed<GlobalTransform>),
+    >,
 ) {
     for (
         global_transform,
@@ -209,7 +450,11 @@ type PosToTransformComponents = (
     Option<&'static Parent>,
 );
 
-type PosToTransformFilter = Or<(Changed<Position>, Changed<Rotation>)>;
+type PosToTransformFilter = (Or<(Changed<Position>, Changed<Rotation>)>, NotChildCollider);
+
+/// Filters out colliders that are attached to a [rigid body](RigidBody) on another entity.
+/// Bodies that are [welded](WeldedBody) to another body aren't its children, so they are kept.
+type NotChildCollider = Or<(With<RigidBody>, With<WeldedBody>, Without<ColliderParent>)>;
 
 type ParentComponents = (
     &'static GlobalTransform,
@@ -305,10 +550,159 @@ fn position_to_transform(
 }
 
 /// Updates [`PreviousGlobalTransform`] by setting it to `GlobalTransform` at the very end or start of a frame.
+///
+/// Only entities whose `GlobalTransform` has changed since the system last ran are updated.
 fn update_previous_global_transforms(
-    mut bodies: Query<(&GlobalTransform, &mut PreviousGlobalTransform)>,
+    mut bodies: Query<(&GlobalTransform, &mut PreviousGlobalTransform), Changed<GlobalTransform>>,
 ) {
     for (transform, mut previous_transform) in &mut bodies {
-        previous_transform.0 = *transform;
+        // Avoid triggering change detection unnecessarily
+        if previous_transform.0 != *transform {
+            previous_transform.0 = *transform;
+        }
+    }
+}
+
+/// Adds [`TransformInterpolation`] to all [rigid bodies](RigidBody) when [`InterpolateAllTransforms`] is enabled.
+fn insert_transform_interpolation(
+    mut commands: Commands,
+    bodies: Query<Entity, (With<RigidBody>, Without<TransformInterpolation>)>,
+) {
+    for entity in &bodies {
+        commands
+            .entity(entity)
+            .insert(TransformInterpolation::default());
+    }
+}
+
+/// Replaces interpolated transforms with the poses of the bodies at the end of the latest physics step.
+///
+/// If a `Transform` was changed after it was interpolated, the change is kept, and the body
+/// isn't interpolated until the next physics step so that it is teleported.
+fn restore_interpolated_transforms(
+    mut bodies: Query<(&mut Transform, &mut TransformInterpolation), Without<Parent>>,
+) {
+    for (mut transform, mut interpolation) in &mut bodies {
+        let Some((translation, rotation)) = interpolation.interpolated else {
+            continue;
+        };
+        interpolation.interpolated = None;
+
+        if transform.translation == translation && transform.rotation == rotation {
+            if let Some((translation, rotation)) = interpolation.end {
+                // Avoid triggering change detection unnecessarily
+                if transform.translation != translation || transform.rotation != rotation {
+                    transform.translation = translation;
+                    transform.rotation = rotation;
+                }
+            }
+        } else {
+            interpolation.start = None;
+        }
+    }
+}
+
+/// Stores the poses of interpolated bodies at the start of a physics step.
+fn store_interpolation_start(
+    mut bodies: Query<(&Position, &Rotation, &mut TransformInterpolation), Without<Parent>>,
+) {
+    for (pos, rot, mut interpolation) in &mut bodies {
+        #[cfg(feature = "2d")]
+        let start = Some((pos.as_f32().extend(0.0), Quaternion::from(*rot).as_f32()));
+        #[cfg(feature = "3d")]
+        let start = Some((pos.as_f32(), rot.as_f32()));
+
+        // Avoid triggering change detection unnecessarily, for example for sleeping bodies
+        if interpolation.start != start {
+            interpolation.start = start;
+        }
+    }
+}
+
+/// Interpolates the `Transform`s of bodies with [`TransformInterpolation`] between their poses at the start
+/// and end of the latest physics step, or extrapolates them from the end of the step using their velocities,
+/// based on how much time has been accumulated for the next step.
+pub(crate) fn interpolate_transforms(
+    mut bodies: Query<
+        (
+            &mut Transform,
+            &mut TransformInterpolation,
+            Option<&LinearVelocity>,
+            Option<&AngularVelocity>,
+        ),
+        Without<Parent>,
+    >,
+    time_step: Res<PhysicsTimestep>,
+    physics_loop: Res<PhysicsLoop>,
+    dt: Res<DeltaTime>,
+    fixed_time: Option<Res<FixedTime>>,
+) {
+    // The fraction of the next step that has been accumulated, and the corresponding simulated time.
+    // If a step is run for every frame, there is nothing to interpolate or extrapolate.
+    let alpha = match *time_step {
+        PhysicsTimestep::Fixed(_) | PhysicsTimestep::Variable { .. } if dt.0 > 0.0 => {
+            Some((physics_loop.accumulator / dt.0) as f32)
+        }
+        PhysicsTimestep::Schedule => fixed_time.map(|fixed_time| {
+            fixed_time.accumulated().as_secs_f32() / fixed_time.period.as_secs_f32()
+        }),
+        _ => None,
+    }
+    .map(|alpha| alpha.clamp(0.0, 1.0));
+    let elapsed = alpha.map_or(0.0, |alpha| alpha * dt.0 as f32);
+    let alpha = alpha.unwrap_or(1.0);
+
+    for (mut transform, mut interpolation, lin_vel, ang_vel) in &mut bodies {
+        let end = (transform.translation, transform.rotation);
+
+        let (translation, rotation) = match interpolation.mode {
+            TransformInterpolationMode::Interpolate => {
+                let Some((start_translation, start_rotation)) = interpolation.start else {
+                    continue;
+                };
+
+                // Only the translation along the plane is simulated in 2D
+                #[cfg(feature = "2d")]
+                let start_translation = start_translation.truncate().extend(end.0.z);
+
+                // Teleport bodies that moved too far during the step instead of sweeping them across the world
+                if interpolation.teleport_threshold.is_some_and(|threshold| {
+                    start_translation.distance_squared(end.0) as Scalar > threshold * threshold
+                }) {
+                    interpolation.start = None;
+                    continue;
+                }
+
+                (
+                    start_translation.lerp(end.0, alpha),
+                    start_rotation.slerp(end.1, alpha),
+                )
+            }
+            TransformInterpolationMode::Extrapolate => {
+                let lin_vel = lin_vel.map_or(Vector::ZERO, |v| v.0).as_f32();
+                let ang_vel = ang_vel.map_or(AngularVelocity::ZERO.0, |v| v.0);
+
+                #[cfg(feature = "2d")]
+                let (lin_vel, ang_rot) = (
+                    lin_vel.extend(0.0),
+                    Quat::from_rotation_z(ang_vel as f32 * elapsed),
+                );
+                #[cfg(feature = "3d")]
+                let ang_rot = Quat::from_scaled_axis(ang_vel.as_f32() * elapsed);
+
+                (end.0 + lin_vel * elapsed, ang_rot * end.1)
+            }
+        };
+
+        // Bodies that aren't moving, like sleeping bodies, are left untouched
+        if (translation, rotation) == end {
+            continue;
+        }
+
+        interpolation.end = Some(end);
+
+        transform.translation = translation;
+        transform.rotation = rotation;
+        interpolation.interpolated = Some((translation, rotation));
     }
 }
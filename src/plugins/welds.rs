@@ -0,0 +1,461 @@
+//! Merges bodies that are welded together by rigid [fixed joints](FixedJoint) into single bodies.
+//!
+//! See [`WeldMergingPlugin`].
+
+use super::islands::UnionFind;
+use crate::{prelude::*, utils::entity_sort_key};
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+
+/// Merges [dynamic](RigidBody::Dynamic) bodies that are welded together by [`FixedJoint`]s with zero compliance
+/// into a single body while the optional [`WeldMerging`] resource exists.
+///
+/// Solving hundreds of rigid welds between the pieces of a structure every substep is expensive, and the welds
+/// can never be perfectly rigid. Instead, each group of welded bodies is simulated as one body with the combined
+/// mass properties and a compound collider made of the colliders of the pieces.
+///
+/// One body of each group, the *root*, is simulated normally. The other bodies get a [`WeldedBody`] component
+/// and their [`RigidBody`] component is removed, so that the joints between them aren't solved. Their colliders
+/// are attached to the root like the colliders of its children, and their [`Position`], [`Rotation`] and velocities
+/// follow the root.
+///
+/// The groups are updated whenever fixed joints or rigid bodies are added, changed or removed. When a joint is
+/// removed or made compliant, for example to break a structure apart, the bodies are split again and continue
+/// with the velocities that they had as part of the structure.
+///
+/// The merging runs after [`PhysicsSet::Prepare`], before the simulation is stepped.
+pub struct WeldMergingPlugin {
+    schedule: Box<dyn ScheduleLabel>,
+}
+
+impl WeldMergingPlugin {
+    /// Creates a [`WeldMergingPlugin`] with the schedule that is used for running the [`PhysicsSchedule`].
+    ///
+    /// The default schedule is `PostUpdate`.
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: Box::new(schedule),
+        }
+    }
+}
+
+impl Default for WeldMergingPlugin {
+    fn default() -> Self {
+        Self::new(PostUpdate)
+    }
+}
+
+impl Plugin for WeldMergingPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<WeldMerging>()
+            .register_type::<WeldedBody>()
+            .add_systems(
+                self.schedule.dyn_clone(),
+                // The bodies are merged after their components have been initialized,
+                // and the changes are applied before the simulation is stepped
+                (update_welds, apply_deferred)
+                    .chain()
+                    .after(PhysicsSet::Prepare)
+                    .before(PhysicsSet::StepSimulation),
+            )
+            .add_systems(
+                self.schedule.dyn_clone(),
+                update_welded_bodies
+                    .after(PhysicsSet::StepSimulation)
+                    .before(PhysicsSet::Sync),
+            );
+    }
+}
+
+/// An optional resource that enables merging bodies that are welded together by rigid [`FixedJoint`]s
+/// into single bodies. See [`WeldMergingPlugin`].
+///
+/// Bodies are only merged if
+///
+/// - they are [dynamic](RigidBody::Dynamic),
+/// - the [`FixedJoint`] between them has zero compliance,
+/// - they aren't attached to any other joints, and
+/// - they don't have colliders on their children.
+///
+/// While merged, the forces, impulses and velocities of the bodies other than the root are ignored,
+/// so forces should be applied to the root, which can be found using [`WeldedBody::root`].
+/// Custom constraints that use merged bodies are skipped, like the welds between the bodies.
+///
+/// Removing the resource splits all of the merged bodies.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// # #[cfg(feature = "f32")]
+/// fn setup(mut commands: Commands) {
+///     commands.insert_resource(WeldMerging);
+///
+///     // Build a wall of bricks that are welded together
+///     let mut previous_brick = None;
+///     for i in 0..10 {
+///         # #[cfg(feature = "2d")]
+///         # let (position, collider) = (Vec2::new(i as f32, 0.5), Collider::cuboid(1.0, 1.0));
+///         # #[cfg(feature = "3d")]
+///         let (position, collider) = (Vec3::new(i as f32, 0.5, 0.0), Collider::cuboid(1.0, 1.0, 1.0));
+///         let brick = commands
+///             .spawn((RigidBody::Dynamic, Position(position), collider))
+///             .id();
+///
+///         if let Some(previous_brick) = previous_brick {
+///             # #[cfg(feature = "2d")]
+///             # let anchor = Vec2::X * 0.5;
+///             # #[cfg(feature = "3d")]
+///             let anchor = Vec3::X * 0.5;
+///             commands.spawn(
+///                 FixedJoint::new(previous_brick, brick)
+///                     .with_local_anchor_1(anchor)
+///                     .with_local_anchor_2(-anchor),
+///             );
+///         }
+///         previous_brick = Some(brick);
+///     }
+/// }
+/// ```
+#[derive(Resource, Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Resource)]
+pub struct WeldMerging;
+
+/// A body that has been merged into another body by the [`WeldMergingPlugin`].
+///
+/// The body doesn't have a [`RigidBody`] component while it is merged,
+/// and its pose and velocity follow the [`root`](#structfield.root) body.
+#[derive(Component, Reflect, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct WeldedBody {
+    /// The body that this body has been merged into.
+    pub root: Entity,
+    /// The transform of this body relative to the root.
+    transform: ColliderTransform,
+    /// The mass properties that this body added to the root, in the local space of the root.
+    mass_properties: ColliderMassProperties,
+}
+
+impl Default for WeldedBody {
+    fn default() -> Self {
+        Self {
+            root: Entity::PLACEHOLDER,
+            transform: ColliderTransform::default(),
+            mass_properties: ColliderMassProperties::default(),
+        }
+    }
+}
+
+impl WeldedBody {
+    /// Returns the transform of this body relative to the root.
+    pub fn transform(&self) -> ColliderTransform {
+        self.transform
+    }
+}
+
+type WeldPoseComponents = (
+    &'static Position,
+    &'static Rotation,
+    Option<&'static LinearVelocity>,
+    Option<&'static AngularVelocity>,
+    Option<&'static Collider>,
+);
+
+/// Merges the groups of welded bodies and splits the bodies whose welds have been removed.
+///
+/// The groups are only recomputed when joints or bodies have changed.
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)]
+fn update_welds(
+    mut commands: Commands,
+    weld_merging: Option<Res<WeldMerging>>,
+    fixed_joints: Query<Ref<FixedJoint>>,
+    revolute_joints: Query<&RevoluteJoint>,
+    spherical_joints: Query<&SphericalJoint>,
+    prismatic_joints: Query<&PrismaticJoint>,
+    distance_joints: Query<&DistanceJoint>,
+    bodies: Query<(Entity, Ref<RigidBody>)>,
+    colliders: Query<(Entity, &ColliderParent)>,
+    welded_bodies: Query<(Entity, &WeldedBody, Option<&RigidBody>)>,
+    poses: Query<WeldPoseComponents>,
+    mut mass_properties: Query<MassPropertiesQuery>,
+    mut removed_joints: RemovedComponents<FixedJoint>,
+    mut removed_bodies: RemovedComponents<RigidBody>,
+) {
+    let joints_removed = removed_joints.iter().count() > 0;
+    let bodies_removed = removed_bodies.iter().count() > 0;
+
+    // Nothing to do if merging is disabled and no bodies are merged
+    if weld_merging.is_none() && welded_bodies.is_empty() {
+        return;
+    }
+
+    let changed = weld_merging.as_ref().is_some_and(|res| res.is_changed())
+        || weld_merging.is_none()
+        || joints_removed
+        || bodies_removed
+        || fixed_joints.iter().any(|joint| joint.is_changed())
+        || bodies.iter().any(|(_, rb)| rb.is_changed());
+    if !changed {
+        return;
+    }
+
+    // Find the root that each welded body should be merged into
+    let mut roots = HashMap::<Entity, Entity>::default();
+    if weld_merging.is_some() {
+        // Bodies that are already merged don't have a `RigidBody`, but they are dynamic
+        let welded = welded_bodies
+            .iter()
+            .filter(|(.., rb)| rb.is_none())
+            .map(|(entity, ..)| entity)
+            .collect::<HashSet<_>>();
+
+        // Bodies attached to other joints or with colliders on their children can't be merged
+        let mut ineligible = HashSet::<Entity>::default();
+        ineligible.extend(
+            revolute_joints
+                .iter()
+                .map(|joint| joint.entities())
+                .chain(spherical_joints.iter().map(|joint| joint.entities()))
+                .chain(prismatic_joints.iter().map(|joint| joint.entities()))
+                .chain(distance_joints.iter().map(|joint| joint.entities()))
+                .chain(
+                    fixed_joints
+                        .iter()
+                        .filter(|joint| joint.compliance != 0.0)
+                        .map(|joint| joint.entities()),
+                )
+                .flatten(),
+        );
+        ineligible.extend(
+            colliders
+                .iter()
+                .filter(|(entity, parent)| *entity != parent.get() && !welded.contains(entity))
+                .map(|(_, parent)| parent.get()),
+        );
+
+        let mut candidates = bodies
+            .iter()
+            .filter(|(_, rb)| rb.is_dynamic())
+            .map(|(entity, _)| entity)
+            .chain(welded.iter().copied())
+            .filter(|entity| !ineligible.contains(entity))
+            .collect::<Vec<_>>();
+        // The root of each group is the body with the smallest entity, so that the groups are stable
+        candidates.sort_unstable_by_key(|entity| entity_sort_key(*entity));
+        let indices = candidates
+            .iter()
+            .enumerate()
+            .map(|(index, entity)| (*entity, index))
+            .collect::<HashMap<_, _>>();
+
+        let mut sets = UnionFind::new(candidates.len());
+        for joint in fixed_joints.iter().filter(|joint| joint.compliance == 0.0) {
+            if let (Some(&index1), Some(&index2)) =
+                (indices.get(&joint.entity1), indices.get(&joint.entity2))
+            {
+                sets.union(index1, index2);
+            }
+        }
+
+        for (index, entity) in candidates.iter().enumerate() {
+            let root = sets.find(index);
+            if root != index {
+                roots.insert(*entity, candidates[root]);
+            }
+        }
+    }
+
+    // Split the bodies that should no longer be merged or that should be merged into another root.
+    // This is done before merging so that the mass properties of the roots only contain their current bodies.
+    let mut current_roots = HashMap::<Entity, Entity>::default();
+    for (entity, welded_body, rb) in &welded_bodies {
+        if rb.is_none() && roots.get(&entity) == Some(&welded_body.root) {
+            current_roots.insert(entity, welded_body.root);
+            continue;
+        }
+
+        split_body(
+            &mut commands,
+            entity,
+            welded_body,
+            rb.is_some(),
+            &poses,
+            &mut mass_properties,
+        );
+    }
+
+    for (&entity, &root) in roots.iter() {
+        if current_roots.get(&entity) == Some(&root) {
+            continue;
+        }
+
+        let (Ok((position, rotation, ..)), Ok((root_position, root_rotation, ..))) =
+            (poses.get(entity), poses.get(root))
+        else {
+            continue;
+        };
+        let Ok(body) = mass_properties.get(entity) else {
+            continue;
+        };
+
+        // The transform of the body relative to the root
+        let inverse_root_rotation = root_rotation.inverse();
+        let transform = ColliderTransform {
+            translation: inverse_root_rotation.rotate(position.0 - root_position.0),
+            rotation: inverse_root_rotation.mul(*rotation),
+        };
+
+        // Add the mass properties of the body to the root
+        let body_mass_properties = ColliderMassProperties {
+            mass: *body.mass,
+            inverse_mass: *body.inverse_mass,
+            inertia: *body.inertia,
+            inverse_inertia: *body.inverse_inertia,
+            center_of_mass: *body.center_of_mass,
+            density: 0.0,
+        }
+        .transformed_by(&transform);
+        if let Ok(mut root_mass_properties) = mass_properties.get_mut(root) {
+            root_mass_properties += body_mass_properties;
+        }
+
+        commands.entity(root).remove::<Sleeping>();
+        commands
+            .entity(entity)
+            .remove::<(RigidBody, Sleeping)>()
+            .insert((
+                WeldedBody {
+                    root,
+                    transform,
+                    mass_properties: body_mass_properties,
+                },
+                // Attach the body to the root like a collider on a child of the root,
+                // so that its collider is part of the root's compound collider
+                ColliderParent(root),
+                transform,
+            ));
+    }
+}
+
+/// Splits a welded body from its root, removing its mass properties from the root
+/// and giving it the velocity of the root at the position of the body.
+fn split_body(
+    commands: &mut Commands,
+    entity: Entity,
+    welded_body: &WeldedBody,
+    has_rb: bool,
+    poses: &Query<WeldPoseComponents>,
+    mass_properties: &mut Query<MassPropertiesQuery>,
+) {
+    let root = welded_body.root;
+
+    if let Ok(mut root_mass_properties) = mass_properties.get_mut(root) {
+        root_mass_properties -= welded_body.mass_properties;
+    }
+
+    let mut entity_commands = commands.entity(entity);
+    entity_commands.remove::<WeldedBody>();
+
+    // The body might have been turned into a rigid body again by the user
+    if !has_rb {
+        entity_commands.insert(RigidBody::Dynamic);
+    }
+
+    if let (Ok((position, ..)), Ok((root_position, root_rotation, root_lin_vel, root_ang_vel, _))) =
+        (poses.get(entity), poses.get(root))
+    {
+        let center_of_mass = mass_properties
+            .get(root)
+            .map_or(Vector::ZERO, |root| root.center_of_mass.0);
+        let lin_vel = root_lin_vel.map_or(Vector::ZERO, |v| v.0);
+        let ang_vel = root_ang_vel.copied().unwrap_or_default();
+
+        // The velocity of the root at the position of the body
+        let r = position.0 - (root_position.0 + root_rotation.rotate(center_of_mass));
+        #[cfg(feature = "2d")]
+        let lin_vel = lin_vel + ang_vel.0 * r.perp();
+        #[cfg(feature = "3d")]
+        let lin_vel = lin_vel + ang_vel.0.cross(r);
+
+        entity_commands.insert((LinearVelocity(lin_vel), ang_vel));
+    }
+
+    if poses
+        .get(entity)
+        .is_ok_and(|(.., collider)| collider.is_some())
+    {
+        entity_commands.insert((ColliderParent(entity), ColliderTransform::default()));
+    } else {
+        entity_commands.remove::<(ColliderParent, ColliderTransform)>();
+    }
+
+    commands.entity(root).remove::<Sleeping>();
+}
+
+/// Updates the poses and velocities of welded bodies to follow their roots after the simulation has been stepped.
+#[allow(clippy::type_complexity)]
+fn update_welded_bodies(
+    mut welded_bodies: Query<
+        (
+            &WeldedBody,
+            &mut Position,
+            &mut Rotation,
+            &mut LinearVelocity,
+            &mut AngularVelocity,
+        ),
+        Without<RigidBody>,
+    >,
+    roots: Query<
+        (
+            &Position,
+            &Rotation,
+            &CenterOfMass,
+            &LinearVelocity,
+            &AngularVelocity,
+        ),
+        With<RigidBody>,
+    >,
+) {
+    for (welded_body, mut position, mut rotation, mut lin_vel, mut ang_vel) in &mut welded_bodies {
+        let Ok((root_position, root_rotation, center_of_mass, root_lin_vel, root_ang_vel)) =
+            roots.get(welded_body.root)
+        else {
+            continue;
+        };
+
+        let new_position =
+            root_position.0 + root_rotation.rotate(welded_body.transform.translation);
+        let new_rotation = root_rotation.mul(welded_body.transform.rotation);
+
+        // The velocity of the root at the position of the body
+        let r = new_position - (root_position.0 + root_rotation.rotate(center_of_mass.0));
+        #[cfg(feature = "2d")]
+        let new_lin_vel = root_lin_vel.0 + root_ang_vel.0 * r.perp();
+        #[cfg(feature = "3d")]
+        let new_lin_vel = root_lin_vel.0 + root_ang_vel.0.cross(r);
+
+        // Avoid triggering change detection unnecessarily
+        if position.0 != new_position {
+            position.0 = new_position;
+        }
+        if *rotation != new_rotation {
+            *rotation = new_rotation;
+        }
+        if lin_vel.0 != new_lin_vel {
+            lin_vel.0 = new_lin_vel;
+        }
+        if *ang_vel != *root_ang_vel {
+            *ang_vel = *root_ang_vel;
+        }
+    }
+}
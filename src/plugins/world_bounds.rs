@@ -0,0 +1,174 @@
+//! Handles [rigid bodies](RigidBody) that leave the simulation area defined by [`WorldBounds`].
+//!
+//! See [`WorldBoundsPlugin`].
+
+use crate::prelude::*;
+use bevy::prelude::*;
+
+/// Handles [rigid bodies](RigidBody) that leave the simulation area defined by the [`WorldBounds`] resource.
+///
+/// When a body's [`Position`] is outside of the bounds at the end of a physics frame, an [`OutOfBounds`] event
+/// is sent and the body is handled according to the [`OutOfBoundsPolicy`] of the bounds.
+///
+/// The bounds are optional, and nothing is done if the [`WorldBounds`] resource doesn't exist.
+pub struct WorldBoundsPlugin {
+    schedule: Box<dyn ScheduleLabel>,
+}
+
+impl WorldBoundsPlugin {
+    /// Creates a [`WorldBoundsPlugin`] with the schedule that is used for running the [`PhysicsSchedule`].
+    ///
+    /// The default schedule is `PostUpdate`.
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: Box::new(schedule),
+        }
+    }
+}
+
+impl Default for WorldBoundsPlugin {
+    fn default() -> Self {
+        Self::new(PostUpdate)
+    }
+}
+
+impl Plugin for WorldBoundsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<OutOfBounds>()
+            .register_type::<WorldBounds>()
+            .register_type::<OutOfBoundsPolicy>()
+            .register_type::<OutOfBounds>()
+            .add_systems(
+                self.schedule.dyn_clone(),
+                handle_out_of_bounds_bodies
+                    .after(PhysicsSet::StepSimulation)
+                    .before(PhysicsSet::Sync)
+                    .run_if(resource_exists::<WorldBounds>()),
+            );
+    }
+}
+
+/// An optional resource that defines the simulation area as an axis-aligned box.
+///
+/// [Rigid bodies](RigidBody) whose [`Position`] leaves the bounds are handled according to the [`policy`](#structfield.policy),
+/// and an [`OutOfBounds`] event is sent for them. Static bodies are ignored.
+///
+/// ## Example
+///
+/// ```
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// # #[cfg(all(feature = "3d", feature = "f32"))]
+/// fn setup(mut commands: Commands) {
+///     // Despawn bodies that fall below the level
+///     commands.insert_resource(WorldBounds::new(
+///         Vec3::new(-1000.0, -50.0, -1000.0),
+///         Vec3::splat(1000.0),
+///         OutOfBoundsPolicy::Despawn,
+///     ));
+/// }
+/// ```
+#[derive(Resource, Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Resource)]
+pub struct WorldBounds {
+    /// The minimum corner of the bounds.
+    pub min: Vector,
+    /// The maximum corner of the bounds.
+    pub max: Vector,
+    /// Determines what happens to bodies that leave the bounds.
+    pub policy: OutOfBoundsPolicy,
+}
+
+impl Default for WorldBounds {
+    /// Unlimited bounds that contain every point.
+    fn default() -> Self {
+        Self::new(
+            Vector::splat(Scalar::NEG_INFINITY),
+            Vector::splat(Scalar::INFINITY),
+            OutOfBoundsPolicy::default(),
+        )
+    }
+}
+
+impl WorldBounds {
+    /// Creates new [`WorldBounds`] with the given minimum and maximum corners and [`OutOfBoundsPolicy`].
+    pub fn new(min: Vector, max: Vector, policy: OutOfBoundsPolicy) -> Self {
+        Self { min, max, policy }
+    }
+
+    /// Returns true if the given point is inside of the bounds.
+    pub fn contains(&self, point: Vector) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+}
+
+/// Determines what happens to [rigid bodies](RigidBody) that leave the [`WorldBounds`].
+///
+/// An [`OutOfBounds`] event is sent in all cases.
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OutOfBoundsPolicy {
+    /// The body is despawned along with its children.
+    #[default]
+    Despawn,
+    /// The body is stopped and marked as [`Sleeping`] at its current position.
+    /// It stays asleep until it is woken up, for example by moving it back inside of the bounds.
+    Park,
+    /// The body is moved back to the closest point inside of the bounds,
+    /// and its velocity is removed along the axes that it left the bounds on.
+    Clamp,
+    /// The body is left as is. [`OutOfBounds`] events are sent for the body
+    /// on each frame that it is outside of the bounds.
+    Event,
+}
+
+/// An event that is sent when a [rigid body](RigidBody) is outside of the [`WorldBounds`]
+/// at the end of a physics frame. Contains the entity of the body.
+#[derive(Event, Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutOfBounds(pub Entity);
+
+type OutOfBoundsQueryComponents = (
+    Entity,
+    &'static RigidBody,
+    &'static mut Position,
+    &'static mut LinearVelocity,
+    &'static mut AngularVelocity,
+);
+
+/// Sends [`OutOfBounds`] events for bodies outside of the [`WorldBounds`] and applies the [`OutOfBoundsPolicy`].
+fn handle_out_of_bounds_bodies(
+    mut commands: Commands,
+    mut bodies: Query<OutOfBoundsQueryComponents, Without<Sleeping>>,
+    bounds: Res<WorldBounds>,
+    mut out_of_bounds_ev_writer: EventWriter<OutOfBounds>,
+) {
+    for (entity, rb, mut position, mut lin_vel, mut ang_vel) in &mut bodies {
+        if rb.is_static() || bounds.contains(position.0) {
+            continue;
+        }
+
+        out_of_bounds_ev_writer.send(OutOfBounds(entity));
+
+        match bounds.policy {
+            OutOfBoundsPolicy::Despawn => commands.entity(entity).despawn_recursive(),
+            OutOfBoundsPolicy::Park => {
+                // Bypass change detection so that the velocity change doesn't wake the body up again
+                *lin_vel.bypass_change_detection() = LinearVelocity::ZERO;
+                *ang_vel.bypass_change_detection() = AngularVelocity::ZERO;
+                commands.entity(entity).insert(Sleeping);
+            }
+            OutOfBoundsPolicy::Clamp => {
+                let clamped = position.0.clamp(bounds.min, bounds.max);
+                let outside = position.0.cmpne(clamped);
+                lin_vel.0 = Vector::select(outside, Vector::ZERO, lin_vel.0);
+                position.0 = clamped;
+            }
+            OutOfBoundsPolicy::Event => (),
+        }
+    }
+}
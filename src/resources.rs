@@ -26,9 +26,12 @@ use crate::prelude::*;
 /// }
 /// ```
 #[derive(Reflect, Resource, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Resource)]
 pub enum PhysicsTimestep {
     /// **Fixed timestep**: the physics simulation will be advanced by a fixed value `dt` for every `dt` seconds passed since the previous physics frame. This allows consistent behavior across different machines and framerates.
+    ///
+    /// The time passed in a single frame is limited by [`PhysicsTime::max_delta`], so that the simulation doesn't try to catch up after long frames.
     Fixed(Scalar),
     /// **Fixed delta, once per frame**: the physics simulation will be advanced by a fixed value `dt` once every frame. This should only be used in cases where you can guarantee a fixed number of executions, like in FixedUpdate or on a server.
     FixedOnce(Scalar),
@@ -39,6 +42,13 @@ pub enum PhysicsTimestep {
         /// A good default is `1.0 / 60.0` (60 Hz)
         max_dt: Scalar,
     },
+    /// **Once per schedule run**: the physics simulation will be advanced once every time the schedule
+    /// that runs physics is run, by the schedule's own delta time. In `FixedUpdate`, this is the period
+    /// of `FixedTime`, so physics steps in lockstep with the game logic in `FixedUpdate` and doesn't drift
+    /// relative to it. In other schedules, this is `Time::delta_seconds()`.
+    ///
+    /// This is the default when the physics plugins are added to `FixedUpdate`.
+    Schedule,
 }
 
 impl Default for PhysicsTimestep {
@@ -47,7 +57,15 @@ impl Default for PhysicsTimestep {
     }
 }
 
-/// Configures the ratio of physics seconds per real second.
+/// Controls the passage of time in the physics simulation, and stores information about the simulated time.
+///
+/// The simulation can be paused and resumed, advanced by exactly one step at a time while paused,
+/// and slowed down or sped up with a time scale. These are respected by all of the physics systems,
+/// including the ones that run outside of the [`PhysicsSchedule`].
+///
+/// ## Time scale
+///
+/// The time scale is the ratio of physics seconds per real second.
 ///
 /// The default time scale is 1.0, meaning the simulation runs in real time.
 /// Reduce this for slow motion, increase for fast forward.
@@ -61,9 +79,22 @@ impl Default for PhysicsTimestep {
 /// behaviour by adjusting your [timestep](`PhysicsTimestep::Fixed`)
 /// at the cost of performance.
 ///
-/// ## Example
+/// ## Maximum delta
+///
+/// After a long frame hitch, or while the window is being dragged, a lot of time can pass between frames.
+/// With an accumulating [timestep](PhysicsTimestep), simulating all of it at once would take even longer,
+/// causing the next frame to be even slower and the simulation to fall further and further behind.
 ///
-/// You can change the time scale by inserting the [`PhysicsTimescale`] resource:
+/// To prevent this "spiral of death", the real time that the simulation is advanced by in a single
+/// frame is clamped to [`PhysicsTime::max_delta`]. The simulation slows down instead of catching up.
+///
+/// ## Stepping
+///
+/// While the simulation is paused, [`PhysicsTime::step`] queues a single step that is run
+/// on the next run of the physics schedule, using the current [`DeltaTime`]. This is useful for
+/// inspecting the simulation one step at a time.
+///
+/// ## Example
 ///
 /// ```no_run
 /// use bevy::prelude::*;
@@ -76,17 +107,164 @@ impl Default for PhysicsTimestep {
 ///     App::new()
 ///         .add_plugins((DefaultPlugins, PhysicsPlugins::default()))
 ///         // Run the simulation at half speed, in slow motion
-///         .insert_resource(PhysicsTimescale(0.5))
+///         .insert_resource(PhysicsTime::default().with_time_scale(0.5))
+///         .add_systems(Update, control_time)
 ///         .run();
 /// }
+///
+/// fn control_time(keys: Res<Input<KeyCode>>, mut physics_time: ResMut<PhysicsTime>) {
+///     if keys.just_pressed(KeyCode::P) {
+///         physics_time.toggle_pause();
+///     }
+///     if keys.just_pressed(KeyCode::Return) {
+///         // Advance the paused simulation by one step
+///         physics_time.step();
+///     }
+///     if keys.pressed(KeyCode::ShiftLeft) {
+///         // Bullet time
+///         physics_time.time_scale = 0.1;
+///     } else {
+///         physics_time.time_scale = 1.0;
+///     }
+/// }
 /// ```
 #[derive(Reflect, Resource, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Resource)]
-pub struct PhysicsTimescale(pub Scalar);
+pub struct PhysicsTime {
+    /// The ratio of physics seconds per real second. Negative values are treated as zero.
+    pub time_scale: Scalar,
+    /// Determines if the simulation is paused.
+    pub paused: bool,
+    /// Number of steps queued with [`PhysicsTime::step`] that are run while the simulation is paused.
+    pub queued_steps: u32,
+    /// The maximum amount of real time in seconds that the simulation can be advanced by in a single frame,
+    /// before the time scale is applied. `None` means that there is no limit.
+    ///
+    /// The default is 0.25 seconds. Earlier versions had no limit, so after long frames like hitches, the simulation
+    /// ran all of the steps needed to catch up with the real time. Now the time beyond the limit is dropped,
+    /// and the simulation falls behind the real time instead. Set this to `None` to keep the old behavior.
+    pub max_delta: Option<Scalar>,
+    /// The simulated time of the latest step.
+    delta: Scalar,
+    /// The total simulated time.
+    elapsed: Scalar,
+    /// The number of steps that have been run.
+    step_count: u64,
+}
 
-impl Default for PhysicsTimescale {
+impl Default for PhysicsTime {
     fn default() -> Self {
-        Self(1.0)
+        Self {
+            time_scale: 1.0,
+            paused: false,
+            queued_steps: 0,
+            max_delta: Some(0.25),
+            delta: 0.0,
+            elapsed: 0.0,
+            step_count: 0,
+        }
+    }
+}
+
+impl PhysicsTime {
+    /// Sets the time scale, the ratio of physics seconds per real second.
+    pub fn with_time_scale(self, time_scale: Scalar) -> Self {
+        Self { time_scale, ..self }
+    }
+
+    /// Sets the maximum amount of real time that the simulation can be advanced by in a single frame.
+    /// `None` means that there is no limit.
+    pub fn with_max_delta(self, max_delta: Option<Scalar>) -> Self {
+        Self { max_delta, ..self }
+    }
+
+    /// Pauses the simulation.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes the simulation. Steps that were queued while paused are discarded.
+    pub fn resume(&mut self) {
+        self.paused = false;
+        self.queued_steps = 0;
+    }
+
+    /// Pauses the simulation if it is running, and resumes it if it is paused.
+    pub fn toggle_pause(&mut self) {
+        if self.paused {
+            self.resume();
+        } else {
+            self.pause();
+        }
+    }
+
+    /// Queues exactly one step to be run on the next run of the physics schedule while the simulation is paused.
+    pub fn step(&mut self) {
+        self.queued_steps += 1;
+    }
+
+    /// Returns the time scale clamped to be non-negative.
+    pub fn effective_time_scale(&self) -> Scalar {
+        self.time_scale.max(0.0)
+    }
+
+    /// Scales a real time delta according to the time scale, returning zero while the simulation is paused.
+    ///
+    /// This is used by systems that advance physics-related state outside of the [`PhysicsSchedule`].
+    pub fn scale_delta(&self, delta_seconds: Scalar) -> Scalar {
+        if self.paused {
+            0.0
+        } else {
+            delta_seconds * self.effective_time_scale()
+        }
+    }
+
+    /// Returns the simulated time of the latest step in seconds.
+    pub fn delta_seconds(&self) -> Scalar {
+        self.delta
+    }
+
+    /// Returns the total simulated time in seconds.
+    pub fn elapsed_seconds(&self) -> Scalar {
+        self.elapsed
+    }
+
+    /// Returns the number of steps that have been run.
+    pub fn step_count(&self) -> u64 {
+        self.step_count
+    }
+
+    /// Records a step that advanced the simulation by `delta_seconds`.
+    pub(crate) fn advance(&mut self, delta_seconds: Scalar) {
+        self.delta = delta_seconds;
+        self.elapsed += delta_seconds;
+        self.step_count += 1;
+    }
+}
+
+#[allow(deprecated)]
+pub use deprecated::PhysicsTimescale;
+
+mod deprecated {
+    // The derived impls use the deprecated type
+    #![allow(deprecated)]
+
+    use crate::prelude::*;
+    use bevy::prelude::*;
+
+    /// Configures the ratio of physics seconds per real second.
+    ///
+    /// When this resource is inserted or changed, its value is forwarded to [`PhysicsTime::time_scale`].
+    #[deprecated(since = "0.3.0", note = "use `PhysicsTime::time_scale`")]
+    #[derive(Reflect, Resource, Clone, Copy, Debug, PartialEq)]
+    #[reflect(Resource)]
+    pub struct PhysicsTimescale(pub Scalar);
+
+    impl Default for PhysicsTimescale {
+        fn default() -> Self {
+            Self(1.0)
+        }
     }
 }
 
@@ -129,6 +307,7 @@ pub struct SubDeltaTime(pub Scalar);
 /// }
 /// ```
 #[derive(Reflect, Resource, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Resource)]
 pub struct SubstepCount(pub u32);
 
@@ -149,6 +328,7 @@ pub struct BroadCollisionPairs(pub Vec<(Entity, Entity)>);
 ///
 /// See [`Sleeping`] for further information about sleeping.
 #[derive(Reflect, Resource, Clone, Copy, PartialEq, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Resource)]
 pub struct SleepingThreshold {
     /// The maximum linear velocity allowed for a body to be marked as sleeping.
@@ -171,6 +351,7 @@ impl Default for SleepingThreshold {
 ///
 /// See [`Sleeping`] for further information about sleeping.
 #[derive(Reflect, Resource, Clone, Copy, PartialEq, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Resource)]
 pub struct DeactivationTime(pub Scalar);
 
@@ -213,6 +394,7 @@ impl Default for DeactivationTime {
 ///
 /// You can also modify gravity while the app is running.
 #[derive(Reflect, Resource, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[reflect(Resource)]
 pub struct Gravity(pub Vector);
 
@@ -226,3 +408,188 @@ impl Gravity {
     /// Zero gravity.
     pub const ZERO: Gravity = Gravity(Vector::ZERO);
 }
+
+/// Enables a deterministic mode where the physics simulation doesn't depend on the order
+/// in which entities are stored or iterated over. Disabled by default.
+///
+/// Normally, the order of collision pairs, contacts and joints depends on the order of entities in queries,
+/// which can differ between apps that spawn the same entities in a different order or at different times.
+/// In deterministic mode, the [broad phase pairs](BroadCollisionPairs), [`Collisions`] and joints are sorted
+/// by their entities, including the entity generations, before they are used, so the same inputs always produce
+/// bit-identical results on the same platform. This is required for things like lockstep multiplayer.
+///
+/// Sorting has a small performance cost every substep.
+///
+/// The [`SimulationIslands`] are also numbered in a stable order, and constraints are solved in a stable order
+/// within each island, even when islands are solved in parallel. See the [solving order](SolverPlugin#solving-order)
+/// of the solver for the exact guarantees.
+///
+/// ## Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn main() {
+///     App::new()
+///         .add_plugins((DefaultPlugins, PhysicsPlugins::default()))
+///         .insert_resource(DeterministicMode(true))
+///         .run();
+/// }
+/// ```
+#[derive(Reflect, Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Resource)]
+pub struct DeterministicMode(pub bool);
+
+/// The [`Friction`] that is used for [rigid bodies](RigidBody) that don't have a [`Friction`] component
+/// when they are added, including the [combine rule](CoefficientCombine) of the friction coefficients.
+///
+/// ## Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn main() {
+///     App::new()
+///         .add_plugins((DefaultPlugins, PhysicsPlugins::default()))
+///         // Use the smaller friction coefficient in contacts unless a body overrides it
+///         .insert_resource(DefaultFriction(
+///             Friction::new(0.5).with_combine_rule(CoefficientCombine::Min),
+///         ))
+///         .run();
+/// }
+/// ```
+#[derive(Reflect, Resource, Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Resource)]
+pub struct DefaultFriction(pub Friction);
+
+/// The [`Restitution`] that is used for [rigid bodies](RigidBody) that don't have a [`Restitution`] component
+/// when they are added, including the [combine rule](CoefficientCombine) of the restitution coefficients.
+///
+/// See [`DefaultFriction`] for an example.
+#[derive(Reflect, Resource, Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Resource)]
+pub struct DefaultRestitution(pub Restitution);
+
+/// Controls when the lists that are reused across physics steps give back memory that they no longer need.
+///
+/// Lists like the [`BroadCollisionPairs`], the [`Collisions`] and the penetration constraints keep their memory
+/// between steps so that memory doesn't need to be allocated again every step. After a spike, like an explosion
+/// that creates a lot of contacts for a moment, a list is shrunk if its capacity has stayed more than `ratio`
+/// times larger than its largest length for `steps` steps.
+///
+/// The sizes of the lists can be inspected using [`PhysicsBufferStats`].
+///
+/// ## Example
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// # #[cfg(feature = "2d")]
+/// # use bevy_xpbd_2d::prelude::*;
+/// # #[cfg(feature = "3d")]
+/// use bevy_xpbd_3d::prelude::*;
+///
+/// fn main() {
+///     App::new()
+///         .add_plugins((DefaultPlugins, PhysicsPlugins::default()))
+///         // Keep the memory for up to 10 000 items in each list
+///         .insert_resource(BufferShrinkPolicy {
+///             min_capacity: 10_000,
+///             ..default()
+///         })
+///         .run();
+/// }
+/// ```
+#[derive(Reflect, Resource, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Resource)]
+pub struct BufferShrinkPolicy {
+    /// The capacity that lists are never shrunk below.
+    pub min_capacity: usize,
+    /// How many times larger than its largest length the capacity of a list must be for the list to be shrunk.
+    pub ratio: usize,
+    /// The number of steps that the lengths of a list are tracked for before the list can be shrunk.
+    pub steps: u32,
+}
+
+impl Default for BufferShrinkPolicy {
+    fn default() -> Self {
+        Self {
+            min_capacity: 256,
+            ratio: 4,
+            steps: 300,
+        }
+    }
+}
+
+/// The sizes of the lists that are reused across physics steps, updated after the substeps of every step.
+///
+/// This can be used to find out how much memory the physics engine keeps for collision data,
+/// and how often the lists are shrunk according to the [`BufferShrinkPolicy`].
+#[derive(Reflect, Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[reflect(Resource)]
+pub struct PhysicsBufferStats {
+    /// The sizes of the [`BroadCollisionPairs`].
+    pub broad_collision_pairs: BufferStats,
+    /// The sizes of the [`Collisions`].
+    pub collisions: BufferStats,
+    /// The sizes of the list of penetration constraints created by the solver.
+    pub penetration_constraints: BufferStats,
+}
+
+/// The sizes of a list that is reused across physics steps. See [`PhysicsBufferStats`].
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BufferStats {
+    /// The number of items in the list at the end of the last step.
+    pub len: usize,
+    /// The number of items that the list can hold without allocating more memory.
+    pub capacity: usize,
+    /// The largest number of items in the list since it was last checked for shrinking.
+    pub peak_len: usize,
+    /// The number of times that the list has been shrunk.
+    pub shrink_count: u32,
+    /// The number of steps since the list was last checked for shrinking.
+    steps: u32,
+}
+
+impl BufferStats {
+    /// Records the length and capacity of the list at the end of a step.
+    ///
+    /// Returns the capacity that the list should be shrunk to if the [`BufferShrinkPolicy`] says so.
+    pub(crate) fn record(
+        &mut self,
+        len: usize,
+        capacity: usize,
+        policy: &BufferShrinkPolicy,
+    ) -> Option<usize> {
+        self.len = len;
+        self.capacity = capacity;
+        self.peak_len = self.peak_len.max(len);
+        self.steps += 1;
+
+        if self.steps < policy.steps {
+            return None;
+        }
+
+        let target = self.peak_len.max(policy.min_capacity);
+        self.steps = 0;
+        self.peak_len = len;
+
+        if capacity > target.saturating_mul(policy.ratio.max(1)) {
+            self.shrink_count += 1;
+            Some(target)
+        } else {
+            None
+        }
+    }
+}
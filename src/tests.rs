@@ -1,6 +1,8 @@
 use crate::prelude::*;
 use approx::assert_relative_eq;
-use bevy::{log::LogPlugin, prelude::*, time::TimeUpdateStrategy, utils::Instant};
+use bevy::{
+    ecs::system::SystemState, log::LogPlugin, prelude::*, time::TimeUpdateStrategy, utils::Instant,
+};
 #[cfg(feature = "enhanced-determinism")]
 use insta::assert_debug_snapshot;
 use std::time::Duration;
@@ -30,6 +32,12 @@ fn create_app() -> App {
         PhysicsPlugins::default(),
     ));
     app.insert_resource(TimeUpdateStrategy::ManualInstant(Instant::now()));
+    // Rendering the debug gizmos needs Bevy's GizmoPlugin, which isn't added in the tests
+    #[cfg(feature = "debug-plugin")]
+    app.insert_resource(PhysicsDebugConfig {
+        enabled: false,
+        ..default()
+    });
     app
 }
 
@@ -43,6 +51,17 @@ fn tick_60_fps(app: &mut App) {
     app.update();
 }
 
+/// Spawns a large static ground whose top is at y = 0.
+fn spawn_ground(app: &mut App) -> Entity {
+    #[cfg(feature = "2d")]
+    let collider = Collider::cuboid(20.0, 1.0);
+    #[cfg(feature = "3d")]
+    let collider = Collider::cuboid(20.0, 1.0, 20.0);
+    app.world
+        .spawn((RigidBody::Static, Position(Vector::NEG_Y * 0.5), collider))
+        .id()
+}
+
 #[cfg(feature = "3d")]
 fn setup_cubes_simulation(mut commands: Commands) {
     let mut next_id = 0;
@@ -159,61 +178,4573 @@ fn body_with_velocity_moves() {
     }
 }
 
-#[derive(Component, Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord)]
-struct Id(usize);
-
-#[cfg(all(feature = "3d", feature = "enhanced-determinism"))]
 #[test]
-fn cubes_simulation_is_deterministic_across_machines() {
-    setup_insta!();
+fn child_collider_is_attached_to_parent_body() {
     let mut app = create_app();
 
-    app.add_systems(Startup, setup_cubes_simulation);
-
-    const SECONDS: usize = 10;
-    const UPDATES: usize = 60 * SECONDS;
+    app.add_systems(Startup, |mut commands: Commands| {
+        commands
+            .spawn((
+                SpatialBundle::default(),
+                RigidBody::Dynamic,
+                Collider::ball(0.5),
+            ))
+            .with_children(|children| {
+                children.spawn((
+                    Collider::ball(0.5),
+                    TransformBundle::from_transform(Transform::from_xyz(2.0, 0.0, 0.0)),
+                ));
+            });
+    });
 
-    for _ in 0..UPDATES {
+    for _ in 0..30 {
         tick_60_fps(&mut app);
     }
 
-    let mut app_query = app.world.query::<(&Id, &Transform)>();
+    let mut body_query = app
+        .world
+        .query_filtered::<(Entity, &Position, &Mass, &CenterOfMass), With<RigidBody>>();
+    let (body, body_pos, mass, center_of_mass) = body_query.single(&app.world);
+    let (body_pos, mass, center_of_mass) = (body_pos.0, mass.0, center_of_mass.0);
 
-    let mut bodies: Vec<(&Id, &Transform)> = app_query.iter(&app.world).collect();
-    bodies.sort_by_key(|b| b.0);
+    let mut collider_query = app
+        .world
+        .query_filtered::<(&ColliderParent, &Position), Without<RigidBody>>();
+    let (collider_parent, collider_pos) = collider_query.single(&app.world);
 
-    assert_debug_snapshot!(bodies);
+    // The child collider should be attached to the body and contribute to its mass properties
+    assert_eq!(collider_parent.get(), body);
+    let ball_mass = ColliderMassProperties::new_computed(&Collider::ball(0.5), 1.0)
+        .mass
+        .0;
+    assert_relative_eq!(mass, 2.0 * ball_mass, epsilon = 0.0001);
+    assert_relative_eq!(center_of_mass, Vector::X, epsilon = 0.0001);
+
+    // The child collider should move with the body
+    assert!(body_pos.y < 0.0);
+    assert_relative_eq!(collider_pos.0, body_pos + 2.0 * Vector::X, epsilon = 0.0001);
 }
 
-#[cfg(feature = "3d")]
 #[test]
-fn cubes_simulation_is_locally_deterministic() {
-    use itertools::Itertools;
+fn removing_and_moving_colliders_updates_mass_properties() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO);
 
-    fn run_cubes() -> Vec<(Id, Transform)> {
-        let mut app = create_app();
+    let ball_mass = ColliderMassProperties::new_computed(&Collider::ball(0.5), 1.0)
+        .mass
+        .0;
+    let child_collider = || {
+        (
+            Collider::ball(0.5),
+            TransformBundle::from_transform(Transform::from_xyz(2.0, 0.0, 0.0)),
+        )
+    };
 
-        app.add_systems(Startup, setup_cubes_simulation);
+    let body1 = app
+        .world
+        .spawn((
+            SpatialBundle::default(),
+            RigidBody::Dynamic,
+            Collider::ball(0.5),
+        ))
+        .id();
+    let body2 = app
+        .world
+        .spawn((
+            SpatialBundle::default(),
+            RigidBody::Dynamic,
+            Collider::ball(0.5),
+        ))
+        .id();
+    let child1 = app.world.spawn(child_collider()).set_parent(body1).id();
+    let child2 = app.world.spawn(child_collider()).set_parent(body1).id();
 
-        const SECONDS: usize = 5;
-        const UPDATES: usize = 60 * SECONDS;
+    app.update();
+    assert_relative_eq!(
+        app.world.get::<Mass>(body1).unwrap().0,
+        3.0 * ball_mass,
+        epsilon = 0.0001
+    );
 
-        for _ in 0..UPDATES {
+    // Breaking off a part removes its mass from the body
+    app.world.entity_mut(child1).despawn_recursive();
+    app.update();
+    assert_relative_eq!(
+        app.world.get::<Mass>(body1).unwrap().0,
+        2.0 * ball_mass,
+        epsilon = 0.0001
+    );
+
+    // Moving a collider to another body moves its mass as well
+    app.world.entity_mut(child2).set_parent(body2);
+    app.update();
+    assert_relative_eq!(
+        app.world.get::<Mass>(body1).unwrap().0,
+        ball_mass,
+        epsilon = 0.0001
+    );
+    assert_relative_eq!(
+        app.world.get::<CenterOfMass>(body1).unwrap().0,
+        Vector::ZERO,
+        epsilon = 0.0001
+    );
+    assert_relative_eq!(
+        app.world.get::<Mass>(body2).unwrap().0,
+        2.0 * ball_mass,
+        epsilon = 0.0001
+    );
+    assert_relative_eq!(
+        app.world.get::<CenterOfMass>(body2).unwrap().0,
+        Vector::X,
+        epsilon = 0.0001
+    );
+}
+
+#[test]
+fn collider_density_updates_mass_properties() {
+    let mut app = create_app();
+
+    let body = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Collider::ball(0.5),
+            ColliderDensity(2.0),
+        ))
+        .id();
+
+    app.update();
+
+    let expected = ColliderMassProperties::new_computed(&Collider::ball(0.5), 2.0);
+    assert_relative_eq!(app.world.get::<Mass>(body).unwrap().0, expected.mass.0);
+
+    // Changing the density recomputes the mass properties
+    app.world.get_mut::<ColliderDensity>(body).unwrap().0 = 4.0;
+    app.update();
+
+    let expected = ColliderMassProperties::new_computed(&Collider::ball(0.5), 4.0);
+    assert_relative_eq!(app.world.get::<Mass>(body).unwrap().0, expected.mass.0);
+    assert_relative_eq!(
+        app.world.get::<Inertia>(body).unwrap().0,
+        expected.inertia.0,
+        epsilon = 0.0001
+    );
+}
+
+#[test]
+fn center_of_mass_override_tips_resting_body() {
+    let uprightness = |com_override: Option<CenterOfMassOverride>| {
+        let mut app = create_app();
+        app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+        spawn_ground(&mut app);
+        #[cfg(feature = "2d")]
+        let box_collider = Collider::cuboid(1.0, 1.0);
+        #[cfg(feature = "3d")]
+        let box_collider = Collider::cuboid(1.0, 1.0, 1.0);
+        let mut body =
+            app.world
+                .spawn((RigidBody::Dynamic, box_collider, Position(Vector::Y * 0.5)));
+        if let Some(com_override) = com_override {
+            body.insert(com_override);
+        }
+        let body = body.id();
+
+        for _ in 0..60 {
             tick_60_fps(&mut app);
         }
 
-        let mut app_query = app.world.query::<(&Id, &Transform)>();
+        // How much the up axis of the body still points up
+        let rotation = app.world.get::<Rotation>(body).unwrap();
+        rotation.rotate(Vector::Y).dot(Vector::Y)
+    };
 
-        let mut bodies: Vec<(Id, Transform)> = app_query
-            .iter(&app.world)
-            .map(|(id, transform)| (*id, *transform))
-            .collect();
-        bodies.sort_by_key(|b| b.0);
-        bodies
+    // A box resting on the ground stays upright
+    assert!(uprightness(None) > 0.999);
+
+    // Moving the center of mass outside of the contact area makes it tip over
+    assert!(uprightness(Some(CenterOfMassOverride::Local(Vector::X * 2.0))) < 0.98);
+    assert!(uprightness(Some(CenterOfMassOverride::Offset(Vector::NEG_X * 2.0))) < 0.98);
+}
+
+#[test]
+fn box_contact_manifold_has_persistent_points() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    let ground = spawn_ground(&mut app);
+    #[cfg(feature = "2d")]
+    let box_collider = Collider::cuboid(1.0, 1.0);
+    #[cfg(feature = "3d")]
+    let box_collider = Collider::cuboid(1.0, 1.0, 1.0);
+    let body = app
+        .world
+        .spawn((RigidBody::Dynamic, Position(Vector::Y * 0.5), box_collider))
+        .id();
+
+    for _ in 0..30 {
+        app.update();
     }
 
-    // run simulation and check that results are equal each time
-    for (a, b) in (0..4).map(|_| run_cubes()).tuple_windows() {
-        assert_eq!(a, b);
+    let collisions = app.world.resource::<Collisions>();
+    let contacts = collisions.get(ground, body).unwrap();
+    assert_eq!(contacts.manifolds.len(), 1);
+
+    // The faces are clipped against each other, so all corners of the box touch the ground
+    let manifold = &contacts.manifolds[0];
+    #[cfg(feature = "2d")]
+    assert_eq!(manifold.contacts.len(), 2);
+    #[cfg(feature = "3d")]
+    assert_eq!(manifold.contacts.len(), 4);
+
+    // The contacts have persisted since the box landed
+    assert!(manifold.contacts.iter().all(|contact| contact.age > 0));
+}
+
+#[test]
+fn box_stack_is_stable_with_warm_starting() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0))
+        .insert_resource(SubstepCount(4));
+
+    let ground = spawn_ground(&mut app);
+    #[cfg(feature = "2d")]
+    let box_collider = Collider::cuboid(1.0, 1.0);
+    #[cfg(feature = "3d")]
+    let box_collider = Collider::cuboid(1.0, 1.0, 1.0);
+    let boxes: Vec<Entity> = (0..3)
+        .map(|i| {
+            app.world
+                .spawn((
+                    RigidBody::Dynamic,
+                    Position(Vector::Y * (0.5 + i as Scalar)),
+                    box_collider.clone(),
+                    SleepingDisabled,
+                ))
+                .id()
+        })
+        .collect();
+
+    for _ in 0..120 {
+        app.update();
+    }
+
+    // The boxes stay stacked on top of each other
+    for (i, entity) in boxes.iter().enumerate() {
+        let position = app.world.get::<Position>(*entity).unwrap().0;
+        assert_relative_eq!(position.y, 0.5 + i as Scalar, epsilon = 0.05);
+        assert_relative_eq!(position.x, 0.0, epsilon = 0.05);
+    }
+
+    // The resting contacts carry their Lagrange multipliers over to the next substep
+    let collisions = app.world.resource::<Collisions>();
+    let contacts = collisions.get(ground, boxes[0]).unwrap();
+    assert!(contacts
+        .manifolds
+        .iter()
+        .flat_map(|manifold| manifold.contacts.iter())
+        .any(|contact| contact.normal_lagrange < 0.0));
+}
+
+#[test]
+fn restitution_is_ignored_below_threshold() {
+    let bounce_speed = |impact_speed: Scalar| {
+        let mut app = create_app();
+        app.insert_resource(Gravity::ZERO)
+            .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0))
+            .insert_resource(SolverConfig {
+                restitution_threshold: 1.0,
+                ..default()
+            });
+
+        let ground = spawn_ground(&mut app);
+        app.world.entity_mut(ground).insert(Restitution::new(0.8));
+        let ball = app
+            .world
+            .spawn((
+                RigidBody::Dynamic,
+                Position(Vector::Y * 0.6),
+                LinearVelocity(Vector::NEG_Y * impact_speed),
+                Collider::ball(0.5),
+                Restitution::new(0.8),
+            ))
+            .id();
+
+        for _ in 0..30 {
+            app.update();
+        }
+
+        app.world.get::<LinearVelocity>(ball).unwrap().y
+    };
+
+    // Fast impacts bounce, but slow impacts come to rest
+    assert!(bounce_speed(5.0) > 2.0);
+    assert_relative_eq!(bounce_speed(0.5), 0.0, epsilon = 0.01);
+}
+
+#[test]
+fn bodies_use_default_friction_and_restitution() {
+    let mut app = create_app();
+    let default_friction = Friction::new(0.1).with_combine_rule(CoefficientCombine::Min);
+    let default_restitution = Restitution::new(0.9).with_combine_rule(CoefficientCombine::Max);
+    app.insert_resource(DefaultFriction(default_friction))
+        .insert_resource(DefaultRestitution(default_restitution))
+        .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    let default_body = app.world.spawn(RigidBody::Dynamic).id();
+    let custom_body = app
+        .world
+        .spawn((RigidBody::Dynamic, Friction::new(0.8)))
+        .id();
+
+    app.update();
+
+    let entity = app.world.entity(default_body);
+    assert_eq!(*entity.get::<Friction>().unwrap(), default_friction);
+    assert_eq!(*entity.get::<Restitution>().unwrap(), default_restitution);
+
+    // Components on the body override the defaults, but the combine rule with the highest priority is used
+    let custom_friction = *app.world.get::<Friction>(custom_body).unwrap();
+    assert_eq!(custom_friction, Friction::new(0.8));
+    let combined = custom_friction.combine(default_friction);
+    assert_eq!(combined.combine_rule, CoefficientCombine::Min);
+    assert_relative_eq!(combined.dynamic_coefficient, 0.1);
+}
+
+#[test]
+fn rolling_friction_stops_rolling_balls() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    let friction = Friction::new(0.5).with_rolling_coefficient(0.2);
+
+    let ground = spawn_ground(&mut app);
+    app.world.entity_mut(ground).insert(friction);
+
+    let mut spawn_ball = |x: Scalar, friction: Friction| {
+        app.world
+            .spawn((
+                RigidBody::Dynamic,
+                Position(Vector::Y * 0.5 + Vector::X * x),
+                LinearVelocity(Vector::X * 3.0),
+                Collider::ball(0.5),
+                friction,
+            ))
+            .id()
+    };
+    let rolling_ball = spawn_ball(-8.0, friction);
+    let free_ball = spawn_ball(
+        -4.0,
+        Friction::new(0.5).with_combine_rule(CoefficientCombine::Multiply),
+    );
+
+    for _ in 0..240 {
+        app.update();
+    }
+
+    // The rolling friction of the ground is multiplied by zero for the free ball, so it keeps rolling
+    let rolling_speed = app
+        .world
+        .get::<LinearVelocity>(rolling_ball)
+        .unwrap()
+        .length();
+    let free_speed = app.world.get::<LinearVelocity>(free_ball).unwrap().length();
+    assert!(rolling_speed < 0.05, "rolling speed {rolling_speed}");
+    assert!(free_speed > rolling_speed + 1.0, "free speed {free_speed}");
+}
+
+#[test]
+fn anisotropic_friction_depends_on_sliding_direction() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    let ground = spawn_ground(&mut app);
+    app.world
+        .entity_mut(ground)
+        .insert(Friction::new(1.0).with_combine_rule(CoefficientCombine::Multiply));
+
+    let mut spawn_box = |x: Scalar, direction: Vector| {
+        #[cfg(feature = "2d")]
+        let collider = Collider::cuboid(1.0, 1.0);
+        #[cfg(feature = "3d")]
+        let collider = Collider::cuboid(1.0, 1.0, 1.0);
+        app.world
+            .spawn((
+                RigidBody::Dynamic,
+                Position(Vector::Y * 0.5 + Vector::X * x),
+                LinearVelocity(Vector::X * 3.0),
+                collider,
+                AnisotropicFriction::new(direction, 0.05, 0.8),
+            ))
+            .id()
+    };
+    let sliding_along = spawn_box(-5.0, Vector::X);
+    let sliding_across = spawn_box(5.0, Vector::Y);
+
+    // The anisotropic friction of a collider is used instead of that of its body
+    #[cfg(feature = "2d")]
+    let collider = Collider::cuboid(1.0, 1.0);
+    #[cfg(feature = "3d")]
+    let collider = Collider::cuboid(1.0, 1.0, 1.0);
+    let collider_sliding_along = app
+        .world
+        .spawn((
+            SpatialBundle::from_transform(Transform::from_xyz(-8.0, 0.5, 0.0)),
+            RigidBody::Dynamic,
+            LinearVelocity(Vector::X * 3.0),
+            AnisotropicFriction::new(Vector::Y, 0.05, 0.8),
+        ))
+        .with_children(|children| {
+            children.spawn((
+                TransformBundle::default(),
+                collider,
+                AnisotropicFriction::new(Vector::X, 0.05, 0.8),
+            ));
+        })
+        .id();
+
+    for _ in 0..60 {
+        app.update();
+    }
+
+    let along_speed = app.world.get::<LinearVelocity>(sliding_along).unwrap().x;
+    let across_speed = app.world.get::<LinearVelocity>(sliding_across).unwrap().x;
+    let collider_along_speed = app
+        .world
+        .get::<LinearVelocity>(collider_sliding_along)
+        .unwrap()
+        .x;
+    assert!(along_speed > 2.0, "along speed {along_speed}");
+    assert!(across_speed < 0.1, "across speed {across_speed}");
+    assert!(
+        collider_along_speed > 2.0,
+        "collider along speed {collider_along_speed}"
+    );
+}
+
+#[test]
+fn tire_friction_drives_spinning_wheel() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    spawn_ground(&mut app);
+
+    // A wheel spinning in place, rolling towards -X in 2D and +Z in 3D
+    #[cfg(feature = "2d")]
+    let (angular_velocity, forward) = (10.0, Vector::NEG_X);
+    #[cfg(feature = "3d")]
+    let (angular_velocity, forward) = (Vector::X * 10.0, Vector::Z);
+    let wheel = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::Y * 0.5),
+            AngularVelocity(angular_velocity),
+            Collider::ball(0.5),
+            TireFriction::default(),
+        ))
+        .id();
+
+    for _ in 0..30 {
+        app.update();
+    }
+
+    let velocity = app.world.get::<LinearVelocity>(wheel).unwrap().0;
+    assert!(velocity.dot(forward) > 1.0, "velocity {velocity}");
+}
+
+#[test]
+fn thrusters_push_bodies_from_their_mount_points() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO)
+        .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    let mass = MassPropertiesBundle::new_computed(&Collider::ball(0.5), 1.0);
+    let inv_mass = mass.inverse_mass.0;
+
+    // A thruster at the center of mass that spools up over a second
+    let body = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            mass.clone(),
+            Thruster::new(Vector::ZERO, Vector::Y, 10.0)
+                .with_throttle(1.0)
+                .with_spool_up_time(1.0),
+        ))
+        .id();
+
+    // A body with two offset thrusters on child entities pushing in opposite directions
+    let spinner = app.world.spawn((RigidBody::Dynamic, mass)).id();
+    app.world
+        .spawn(Thruster::new(Vector::X, Vector::Y, 10.0).with_throttle(1.0))
+        .set_parent(spinner);
+    app.world
+        .spawn(Thruster::new(Vector::NEG_X, Vector::NEG_Y, 10.0).with_throttle(1.0))
+        .set_parent(spinner);
+
+    // A sleeping body with a thruster on a child collider
+    let sleeper = app
+        .world
+        .spawn((SpatialBundle::default(), RigidBody::Dynamic, Sleeping))
+        .with_children(|children| {
+            children.spawn((
+                TransformBundle::default(),
+                Collider::ball(0.5),
+                Thruster::new(Vector::ZERO, Vector::Y, 10.0).with_throttle(1.0),
+            ));
+        })
+        .id();
+
+    for _ in 0..60 {
+        app.update();
+    }
+
+    // The thrust ramps up linearly, so the average force is half of the maximum thrust
+    let thruster = app.world.get::<Thruster>(body).unwrap();
+    assert_relative_eq!(thruster.thrust(), 10.0, epsilon = 0.0001);
+    let lin_vel = app.world.get::<LinearVelocity>(body).unwrap().0;
+    assert_relative_eq!(lin_vel.y, 5.0 * inv_mass, epsilon = 0.1 * inv_mass);
+    assert_relative_eq!(
+        app.world.get::<AngularVelocity>(body).unwrap().0,
+        AngularVelocity::ZERO.0
+    );
+
+    // The opposing thrusters cancel out, but cause torque
+    let lin_vel = app.world.get::<LinearVelocity>(spinner).unwrap().0;
+    assert!(lin_vel.length() < 0.01);
+    let ang_vel = app.world.get::<AngularVelocity>(spinner).unwrap().0;
+    #[cfg(feature = "2d")]
+    assert!(ang_vel > 1.0);
+    #[cfg(feature = "3d")]
+    assert!(ang_vel.z > 1.0);
+
+    // The thrust wakes up the sleeping body and pushes it through its collider
+    assert!(!app.world.entity(sleeper).contains::<Sleeping>());
+    let lin_vel = app.world.get::<LinearVelocity>(sleeper).unwrap().0;
+    assert!(lin_vel.y > 1.0, "sleeper velocity {lin_vel}");
+}
+
+#[test]
+fn attitude_control_rotates_body_to_target() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO)
+        .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    #[cfg(feature = "2d")]
+    let target = Rotation::from_degrees(90.0);
+    #[cfg(feature = "3d")]
+    let target = Rotation(Quaternion::from_rotation_y(PI / 2.0));
+
+    let controller = AttitudeControl::new(100.0)
+        .with_gains(20.0, 9.0)
+        .with_target_rotation(target);
+    let body = app
+        .world
+        .spawn((RigidBody::Dynamic, Collider::ball(0.5), controller))
+        .id();
+
+    // Reaction wheels that saturate almost immediately
+    let saturated_body = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::X * 5.0),
+            Collider::ball(0.5),
+            controller.with_max_momentum(0.001),
+        ))
+        .id();
+
+    for _ in 0..180 {
+        app.update();
+    }
+
+    // How well the x-axis of the body is aligned with the target orientation
+    let alignment = |entity: Entity| {
+        let rotation = app.world.get::<Rotation>(entity).unwrap();
+        rotation.rotate(Vector::X).dot(target.rotate(Vector::X))
+    };
+
+    assert!(alignment(body) > 0.9999);
+    assert!(!app
+        .world
+        .get::<AttitudeControl>(body)
+        .unwrap()
+        .is_saturated());
+
+    assert!(alignment(saturated_body) < 0.5);
+    assert!(app
+        .world
+        .get::<AttitudeControl>(saturated_body)
+        .unwrap()
+        .is_saturated());
+}
+
+#[test]
+fn time_dilation_volume_slows_bodies_down() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    // A slow-motion bubble around one of two falling bodies
+    app.world.spawn((
+        RigidBody::Static,
+        Collider::ball(20.0),
+        Sensor,
+        TimeDilationVolume::new(0.5),
+    ));
+    let slowed = app
+        .world
+        .spawn((RigidBody::Dynamic, Collider::ball(0.5)))
+        .id();
+    let normal = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::X * 100.0),
+            Collider::ball(0.5),
+        ))
+        .id();
+
+    for _ in 0..60 {
+        tick_60_fps(&mut app);
+    }
+
+    assert_eq!(app.world.get::<TimeDilation>(slowed).unwrap().0, 0.5);
+    assert_eq!(app.world.get::<TimeDilation>(normal).unwrap().0, 1.0);
+
+    // Half of the time passes for the slowed body, so it falls a quarter of the distance
+    let fallen_slowed = -app.world.get::<Position>(slowed).unwrap().y;
+    let fallen_normal = -app.world.get::<Position>(normal).unwrap().y;
+    assert_relative_eq!(fallen_slowed / fallen_normal, 0.25, epsilon = 0.02);
+
+    // The velocity of the slowed body is relative to its own time
+    let speed_slowed = app.world.get::<LinearVelocity>(slowed).unwrap().length();
+    let speed_normal = app.world.get::<LinearVelocity>(normal).unwrap().length();
+    assert_relative_eq!(speed_slowed / speed_normal, 0.5, epsilon = 0.02);
+}
+
+#[cfg(feature = "physics-material")]
+#[test]
+fn physics_materials_are_applied_and_hot_reloaded() {
+    let mut app = create_app();
+    app.add_plugins(AssetPlugin::default());
+    app.finish();
+
+    let material = app
+        .world
+        .resource_mut::<Assets<PhysicsMaterial>>()
+        .add(PhysicsMaterial::new(
+            Friction::new(0.1),
+            Restitution::new(0.8),
+            2.0,
+        ));
+    let body = app
+        .world
+        .spawn((RigidBody::Dynamic, Collider::ball(0.5), material.clone()))
+        .id();
+
+    app.update();
+
+    let friction = *app.world.get::<Friction>(body).unwrap();
+    let restitution = *app.world.get::<Restitution>(body).unwrap();
+    assert_eq!(friction, Friction::new(0.1));
+    assert_eq!(restitution, Restitution::new(0.8));
+    let mass = app.world.get::<Mass>(body).unwrap().0;
+    assert_relative_eq!(
+        mass,
+        ColliderMassProperties::new_computed(&Collider::ball(0.5), 2.0)
+            .mass
+            .0,
+        epsilon = 0.0001
+    );
+
+    // Modifying the asset updates the entities that use it
+    app.world
+        .resource_mut::<Assets<PhysicsMaterial>>()
+        .get_mut(&material)
+        .unwrap()
+        .friction = Friction::new(0.6);
+
+    // Asset events are sent at the end of the frame
+    app.update();
+    app.update();
+
+    assert_eq!(
+        *app.world.get::<Friction>(body).unwrap(),
+        Friction::new(0.6)
+    );
+}
+
+#[test]
+fn collision_margin_keeps_bodies_separated() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    spawn_ground(&mut app);
+
+    let ball = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::Y),
+            Collider::ball(0.5),
+            CollisionMargin(0.1),
+        ))
+        .id();
+
+    for _ in 0..120 {
+        app.update();
+    }
+
+    // The ball rests on the ground with a gap of the collision margin
+    let position = app.world.entity(ball).get::<Position>().unwrap();
+    assert_relative_eq!(position.y, 0.6, epsilon = 0.02);
+}
+
+#[test]
+fn aabbs_are_updated_when_the_default_collision_margin_changes() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    let ball = app
+        .world
+        .spawn((RigidBody::Static, Position::default(), Collider::ball(0.5)))
+        .id();
+
+    app.update();
+    let maxs_x = app.world.get::<ColliderAabb>(ball).unwrap().maxs.x;
+
+    // The static ball isn't changed, but its AABB depends on the default collision margin
+    app.world
+        .resource_mut::<NarrowPhaseConfig>()
+        .collision_margin += 0.25;
+    app.update();
+
+    let aabb = app.world.get::<ColliderAabb>(ball).unwrap();
+    assert_relative_eq!(aabb.maxs.x, maxs_x + 0.25, epsilon = 0.0001);
+}
+
+#[test]
+fn collider_offset_is_applied() {
+    let mut app = create_app();
+
+    spawn_ground(&mut app);
+    app.add_systems(Startup, |mut commands: Commands| {
+        // Dynamic body with a ball collider one unit above the body's origin
+        commands.spawn((
+            SpatialBundle::default(),
+            RigidBody::Dynamic,
+            Position(Vector::Y * 2.0),
+            Collider::ball(0.5),
+            ColliderOffset::from_translation(Vector::Y),
+        ));
+    });
+
+    for _ in 0..120 {
+        tick_60_fps(&mut app);
     }
+
+    let mut query = app.world.query::<(&RigidBody, &Position, &CenterOfMass)>();
+    let (_, position, center_of_mass) = query
+        .iter(&app.world)
+        .find(|(rb, _, _)| rb.is_dynamic())
+        .unwrap();
+
+    assert_relative_eq!(center_of_mass.0, Vector::Y, epsilon = 0.0001);
+
+    // The ball should rest on the ground, so the body's origin is half a unit below the ground
+    assert_relative_eq!(position.y, -0.5, epsilon = 0.05);
+}
+
+#[test]
+fn collider_is_scaled_by_transform() {
+    let mut app = create_app();
+
+    spawn_ground(&mut app);
+    app.add_systems(Startup, |mut commands: Commands| {
+        // Dynamic body with a ball collider scaled to a radius of 1.0
+        commands.spawn((
+            SpatialBundle::from_transform(Transform::from_scale(Vec3::splat(2.0))),
+            RigidBody::Dynamic,
+            Position(Vector::Y * 3.0),
+            Collider::ball(0.5),
+        ));
+    });
+
+    for _ in 0..120 {
+        tick_60_fps(&mut app);
+    }
+
+    let mut query = app.world.query::<(&RigidBody, &Position, &Collider)>();
+    let (_, position, collider) = query
+        .iter(&app.world)
+        .find(|(rb, _, _)| rb.is_dynamic())
+        .unwrap();
+
+    assert_eq!(collider.scale(), Vector::splat(2.0));
+    assert_relative_eq!(
+        collider.get_shape().as_ball().unwrap().radius,
+        1.0,
+        epsilon = 0.0001
+    );
+
+    // The scaled ball should rest on the ground
+    assert_relative_eq!(position.y, 1.0, epsilon = 0.05);
+}
+
+#[test]
+fn convex_hull_is_none_for_degenerate_points() {
+    let points = vec![Vector::ZERO, Vector::X, Vector::X * 2.0, Vector::X * 3.0];
+    assert!(Collider::convex_hull(points).is_none());
+
+    #[cfg(feature = "2d")]
+    let points = vec![
+        Vector::ZERO,
+        Vector::X,
+        Vector::Y,
+        Vector::ONE,
+        Vector::ONE * 0.5,
+    ];
+    #[cfg(feature = "3d")]
+    let points = vec![
+        Vector::ZERO,
+        Vector::X,
+        Vector::Y,
+        Vector::Z,
+        Vector::ONE,
+        Vector::ONE * 0.5,
+    ];
+    let collider = Collider::convex_hull(points).unwrap();
+
+    // The interior point is not part of the hull
+    #[cfg(feature = "2d")]
+    assert_eq!(
+        collider
+            .get_shape()
+            .as_convex_polygon()
+            .unwrap()
+            .points()
+            .len(),
+        4
+    );
+    #[cfg(feature = "3d")]
+    assert_eq!(
+        collider
+            .get_shape()
+            .as_convex_polyhedron()
+            .unwrap()
+            .points()
+            .len(),
+        5
+    );
+}
+
+#[test]
+fn resting_contacts_age() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    spawn_ground(&mut app);
+    app.add_systems(Startup, |mut commands: Commands| {
+        #[cfg(feature = "2d")]
+        let box_collider = Collider::cuboid(1.0, 1.0);
+        #[cfg(feature = "3d")]
+        let box_collider = Collider::cuboid(1.0, 1.0, 1.0);
+        commands.spawn((RigidBody::Dynamic, Position(Vector::Y * 0.5), box_collider));
+    });
+
+    for _ in 0..30 {
+        app.update();
+    }
+
+    let statistics = app.world.resource::<ContactStatistics>().clone();
+    let substeps = app.world.resource::<SubstepCount>().0;
+
+    assert!(statistics.contact_count > 0);
+    assert_eq!(
+        statistics.age_histogram.iter().sum::<usize>(),
+        statistics.contact_count
+    );
+    // The box has been resting on the ground for most of the simulation
+    assert!(statistics.max_age >= 20 * substeps);
+
+    let collisions = app.world.resource::<Collisions>();
+    for contacts in collisions.iter() {
+        for contact in contacts.manifolds.iter().flat_map(|m| m.contacts.iter()) {
+            assert!(contact.age > 0);
+        }
+    }
+
+    assert_eq!(ContactStatistics::age_bucket(0), 0);
+    assert_eq!(ContactStatistics::age_bucket(1), 1);
+    assert_eq!(ContactStatistics::age_bucket(3), 2);
+    assert_eq!(ContactStatistics::age_bucket(4), 3);
+    assert_eq!(
+        ContactStatistics::age_bucket(u32::MAX),
+        CONTACT_AGE_HISTOGRAM_BUCKETS - 1
+    );
+}
+
+#[derive(Clone, Copy)]
+enum TestLayer {
+    A,
+    B,
+    C,
+}
+
+impl PhysicsLayer for TestLayer {
+    fn to_bits(&self) -> u32 {
+        1 << *self as u32
+    }
+
+    fn all_bits() -> u32 {
+        0b111
+    }
+}
+
+#[test]
+fn collision_matrix_filters_layers() {
+    let matrix = CollisionMatrix::none().with_interaction(TestLayer::A, TestLayer::B, true);
+    assert!(matrix.interacts(TestLayer::A, TestLayer::B));
+    assert!(matrix.interacts(TestLayer::B, TestLayer::A));
+    assert!(!matrix.interacts(TestLayer::A, TestLayer::A));
+    assert!(!matrix.interacts(TestLayer::B, TestLayer::C));
+
+    let mut app = create_app();
+    app.insert_resource(matrix)
+        .insert_resource(Gravity::ZERO)
+        .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    // Overlapping pairs of A-B and A-C colliders
+    let spawn_pair = |world: &mut World, layer: TestLayer, offset: Vector| {
+        let entity1 = world
+            .spawn((
+                RigidBody::Dynamic,
+                Position(offset),
+                Collider::ball(0.5),
+                CollisionLayers::new([TestLayer::A], [TestLayer::A, TestLayer::B, TestLayer::C]),
+            ))
+            .id();
+        let entity2 = world
+            .spawn((
+                RigidBody::Dynamic,
+                Position(offset + Vector::X * 0.5),
+                Collider::ball(0.5),
+                CollisionLayers::new([layer], [TestLayer::A]),
+            ))
+            .id();
+        (entity1, entity2)
+    };
+    let (a1, b) = spawn_pair(&mut app.world, TestLayer::B, Vector::ZERO);
+    let (a2, c) = spawn_pair(&mut app.world, TestLayer::C, Vector::Y * 10.0);
+
+    app.update();
+
+    let collisions = app.world.resource::<Collisions>();
+    assert!(collisions.contains(a1, b));
+    assert!(!collisions.contains(a2, c));
+}
+
+#[test]
+fn dynamic_bvh_and_spatial_hash_broad_phases_find_collisions() {
+    for algorithm in [
+        BroadPhaseAlgorithm::DynamicBvh,
+        BroadPhaseAlgorithm::SpatialHash,
+    ] {
+        broad_phase_finds_collisions(algorithm);
+    }
+}
+
+#[test]
+fn broad_phase_algorithms_find_same_pairs() {
+    // Enough colliders for the pairs to be collected in several batches with the `parallel` feature
+    let collect_pairs = |algorithm| {
+        let mut app = create_app();
+        app.insert_resource(BroadPhaseConfig {
+            algorithm,
+            cell_size: 1.0,
+            ..default()
+        })
+        .insert_resource(Gravity::ZERO)
+        .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+        for i in 0..500 {
+            let position =
+                Vector::X * (i % 25) as Scalar * 0.9 + Vector::Y * (i / 25) as Scalar * 0.9;
+            app.world
+                .spawn((RigidBody::Dynamic, Position(position), Collider::ball(0.5)));
+        }
+
+        app.update();
+
+        let mut pairs = app
+            .world
+            .resource::<BroadCollisionPairs>()
+            .0
+            .iter()
+            .map(|&(entity1, entity2)| (entity1.min(entity2), entity1.max(entity2)))
+            .collect::<Vec<_>>();
+        pairs.sort();
+        pairs
+    };
+
+    let pairs = collect_pairs(BroadPhaseAlgorithm::SweepAndPrune);
+    assert!(!pairs.is_empty());
+    assert_eq!(collect_pairs(BroadPhaseAlgorithm::DynamicBvh), pairs);
+    assert_eq!(collect_pairs(BroadPhaseAlgorithm::SpatialHash), pairs);
+}
+
+fn broad_phase_finds_collisions(algorithm: BroadPhaseAlgorithm) {
+    let mut app = create_app();
+    app.insert_resource(BroadPhaseConfig {
+        algorithm,
+        cell_size: 1.0,
+        ..default()
+    })
+    .insert_resource(Gravity::ZERO)
+    .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    let ball1 = app
+        .world
+        .spawn((RigidBody::Dynamic, Collider::ball(0.5)))
+        .id();
+    let ball2 = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::X * 0.75),
+            Collider::ball(0.5),
+        ))
+        .id();
+    let far_ball = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::Y * 10.0),
+            Collider::ball(0.5),
+        ))
+        .id();
+
+    app.update();
+
+    let collisions = app.world.resource::<Collisions>();
+    assert!(collisions.contains(ball1, ball2));
+    assert!(!collisions.contains(ball1, far_ball));
+
+    // Teleport the far ball into the first ball
+    app.world
+        .entity_mut(far_ball)
+        .insert(Position(Vector::NEG_X * 0.75));
+    app.update();
+
+    let collisions = app.world.resource::<Collisions>();
+    assert!(collisions.contains(ball1, far_ball));
+}
+
+#[cfg(feature = "spatial-query")]
+#[test]
+fn spatial_query_layers_are_separate_from_collision_layers() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO)
+        .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    // A collider that doesn't collide with anything but can be found in spatial queries on layer B
+    let queryable = app
+        .world
+        .spawn((
+            RigidBody::Static,
+            Position(Vector::X * 5.0),
+            Collider::ball(0.5),
+            CollisionLayers::none(),
+            SpatialQueryLayers::new([TestLayer::B]),
+        ))
+        .id();
+    let body = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::X * 5.5),
+            Collider::ball(0.5),
+        ))
+        .id();
+
+    app.update();
+
+    assert!(!app.world.resource::<Collisions>().contains(queryable, body));
+
+    let pipeline = app.world.resource::<SpatialQueryPipeline>();
+    let cast = |layer: TestLayer| {
+        pipeline
+            .cast_ray(
+                Vector::ZERO,
+                Vector::X,
+                100.0,
+                true,
+                SpatialQueryFilter::new()
+                    .with_masks([layer])
+                    .without_entities([body]),
+            )
+            .map(|hit| hit.entity)
+    };
+    assert_eq!(cast(TestLayer::B), Some(queryable));
+    assert_eq!(cast(TestLayer::A), None);
+}
+
+#[test]
+fn contacts_and_hits_contain_body_entities() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO)
+        .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    let sensor = app
+        .world
+        .spawn((
+            RigidBody::Static,
+            Position(Vector::X * 5.0),
+            Collider::ball(0.5),
+            Sensor,
+        ))
+        .id();
+    let mut child = Entity::PLACEHOLDER;
+    let body = app
+        .world
+        .spawn((
+            SpatialBundle::from_transform(Transform::from_xyz(4.0, 0.0, 0.0)),
+            RigidBody::Dynamic,
+        ))
+        .with_children(|children| {
+            child = children
+                .spawn((
+                    Collider::ball(0.5),
+                    TransformBundle::from_transform(Transform::from_xyz(0.75, 0.0, 0.0)),
+                ))
+                .id();
+        })
+        .id();
+
+    for _ in 0..2 {
+        app.update();
+    }
+
+    let collisions = app.world.resource::<Collisions>();
+    let contacts = collisions
+        .get(sensor, child)
+        .expect("child collider should be touching the sensor");
+    let (sensor_body, child_body) = if contacts.entity1 == sensor {
+        (contacts.body_entity1, contacts.body_entity2)
+    } else {
+        (contacts.body_entity2, contacts.body_entity1)
+    };
+    assert_eq!(sensor_body, Some(sensor));
+    assert_eq!(child_body, Some(body));
+
+    #[cfg(feature = "spatial-query")]
+    {
+        let hit = app
+            .world
+            .resource::<SpatialQueryPipeline>()
+            .cast_ray(
+                Vector::ZERO,
+                Vector::X,
+                100.0,
+                true,
+                SpatialQueryFilter::default(),
+            )
+            .expect("ray should hit the child collider");
+        assert_eq!(hit.entity, child);
+        assert_eq!(hit.body_entity, Some(body));
+    }
+}
+
+#[test]
+fn activity_regions_collect_moving_bodies() {
+    let mut app = create_app();
+    let mut activity_regions = ActivityRegions::new(Some(10.0));
+    let region = activity_regions.add_region(ActivityRegion::new(
+        Vector::splat(-100.0),
+        Vector::splat(0.0),
+    ));
+    app.insert_resource(Gravity::ZERO)
+        .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0))
+        .insert_resource(activity_regions);
+
+    let moving = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::splat(-5.0)),
+            LinearVelocity(Vector::X),
+        ))
+        .id();
+    app.world
+        .spawn((RigidBody::Static, Position(Vector::splat(-5.0))));
+
+    // An awake body at rest is only collected when it is first added
+    app.world.spawn((
+        RigidBody::Dynamic,
+        Position(Vector::splat(-4.0)),
+        SleepingDisabled,
+    ));
+
+    app.update();
+    app.update();
+
+    let activity_regions = app.world.resource::<ActivityRegions>();
+    let cell = activity_regions.cell_at(Vector::splat(-5.0)).unwrap();
+    assert_eq!(cell, GridCell::splat(-1));
+    assert_eq!(activity_regions.bodies_in_cell(cell), &[moving]);
+    assert_eq!(activity_regions.bodies_in_region(region), &[moving]);
+    assert_eq!(activity_regions.cells().count(), 1);
+}
+
+#[test]
+fn joint_limit_violations_are_reported() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO)
+        .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    // The second body starts rotated far outside of the angle limits of the joint
+    #[cfg(feature = "2d")]
+    let rotation = Rotation::from_radians(1.0);
+    #[cfg(feature = "3d")]
+    let rotation = Rotation(Quaternion::from_rotation_z(1.0));
+    let body1 = app.world.spawn(RigidBody::Static).id();
+    let body2 = app
+        .world
+        .spawn((RigidBody::Dynamic, rotation, Collider::ball(0.5)))
+        .id();
+    let joint = app
+        .world
+        .spawn((
+            RevoluteJoint::new(body1, body2).with_angle_limits(-0.1, 0.1),
+            JointLimitMonitor::new(0.05, 1),
+        ))
+        .id();
+
+    app.update();
+
+    let events = app.world.resource::<Events<JointLimitViolated>>();
+    let violations = events
+        .get_reader()
+        .iter(events)
+        .copied()
+        .collect::<Vec<_>>();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].joint, joint);
+    assert_eq!(violations[0].bodies, [body1, body2]);
+    assert_relative_eq!(violations[0].violation, 0.9, epsilon = 0.05);
+
+    // The limits were applied, so the joint is no longer violating them
+    let monitor = app.world.get::<JointLimitMonitor>(joint).unwrap();
+    assert!(!monitor.is_violating());
+}
+
+#[cfg(feature = "3d")]
+#[test]
+fn trailer_hitch_matches_velocity_and_damps_yaw() {
+    use bevy::ecs::system::Command;
+
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO)
+        .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    let truck = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Collider::cuboid(2.0, 1.0, 4.0),
+            LinearVelocity(Vector::NEG_Z * 5.0),
+        ))
+        .id();
+    let trailer = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::Z * 6.0),
+            Collider::cuboid(2.0, 1.0, 6.0),
+            AngularVelocity(Vector::Y * 2.0),
+        ))
+        .id();
+
+    // Connect the vehicles at runtime
+    let joint = app.world.spawn_empty().id();
+    ConnectHitch::new(joint, truck, trailer)
+        .with_local_anchors(Vector::Z * 2.5, Vector::NEG_Z * 3.5)
+        .apply(&mut app.world);
+
+    app.update();
+
+    // The trailer moves with the truck instead of being yanked by the joint
+    let trailer_vel = app.world.get::<LinearVelocity>(trailer).unwrap().0;
+    assert!(trailer_vel.z < -4.0);
+
+    for _ in 0..60 {
+        app.update();
+    }
+
+    // The relative yaw rotation is damped and stays within the jackknife limits
+    let hitch = *app.world.get::<TrailerHitch>(joint).unwrap();
+    let rot1 = app.world.get::<Rotation>(truck).unwrap();
+    let rot2 = app.world.get::<Rotation>(trailer).unwrap();
+    assert!(hitch.yaw_angle(rot1, rot2).abs() < hitch.jackknife_angle);
+
+    let ang_vel1 = app.world.get::<AngularVelocity>(truck).unwrap().0;
+    let ang_vel2 = app.world.get::<AngularVelocity>(trailer).unwrap().0;
+    assert!((ang_vel2 - ang_vel1).y.abs() < 0.1);
+
+    // Disconnecting removes the joint
+    DisconnectHitch::new(joint).apply(&mut app.world);
+    assert!(app.world.get::<SphericalJoint>(joint).is_none());
+    assert!(app.world.get::<TrailerHitch>(joint).is_none());
+}
+
+#[test]
+fn fuzes_detonate_near_and_after_contacts() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO)
+        .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    let detonations = |world: &World| {
+        let events = world.resource::<Events<Detonation>>();
+        events
+            .get_reader()
+            .iter(events)
+            .map(|event| (event.fuze, event.trigger))
+            .collect::<Vec<_>>()
+    };
+
+    // A mine and a body outside of its radius
+    let mine = app
+        .world
+        .spawn((
+            RigidBody::Static,
+            Collider::ball(0.25),
+            Sensor,
+            ProximityFuze::new(2.0),
+        ))
+        .id();
+    let body = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::X * 3.0),
+            Collider::ball(0.5),
+        ))
+        .id();
+
+    app.update();
+    assert!(detonations(&app.world).is_empty());
+
+    // Moving the body within the radius detonates the mine once
+    app.world.entity_mut(body).insert(Position(Vector::X * 2.0));
+    app.update();
+    assert_eq!(detonations(&app.world), vec![(mine, body)]);
+    assert!(app.world.get::<ProximityFuze>(mine).is_none());
+
+    // A grenade is armed on contact and detonates after its delay
+    let grenade = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::X * 2.0 + Vector::Y * 0.75),
+            Collider::ball(0.5),
+            ContactFuze::new(0.01),
+        ))
+        .id();
+
+    app.update();
+    assert!(app.world.get::<ContactFuze>(grenade).unwrap().is_armed());
+
+    app.update();
+    assert!(detonations(&app.world).contains(&(grenade, body)));
+    assert!(app.world.get::<ContactFuze>(grenade).is_none());
+}
+
+#[test]
+fn world_bounds_handle_out_of_bounds_bodies() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO)
+        .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0))
+        .insert_resource(WorldBounds::new(
+            Vector::splat(-10.0),
+            Vector::splat(10.0),
+            OutOfBoundsPolicy::Clamp,
+        ));
+
+    let spawn_body = |world: &mut World, position: Vector| {
+        world
+            .spawn((
+                RigidBody::Dynamic,
+                Position(position),
+                LinearVelocity(Vector::X * 5.0),
+            ))
+            .id()
+    };
+    let inside = spawn_body(&mut app.world, Vector::ZERO);
+    let outside = spawn_body(&mut app.world, Vector::X * 20.0);
+
+    app.update();
+
+    let events = app.world.resource::<Events<OutOfBounds>>();
+    let out_of_bounds = events
+        .get_reader()
+        .iter(events)
+        .map(|event| event.0)
+        .collect::<Vec<_>>();
+    assert_eq!(out_of_bounds, vec![outside]);
+
+    let entity = app.world.entity(outside);
+    assert_relative_eq!(entity.get::<Position>().unwrap().x, 10.0);
+    assert_relative_eq!(entity.get::<LinearVelocity>().unwrap().x, 0.0);
+    assert_relative_eq!(
+        app.world.entity(inside).get::<LinearVelocity>().unwrap().x,
+        5.0
+    );
+
+    // Parked bodies are put to sleep
+    app.world.resource_mut::<WorldBounds>().policy = OutOfBoundsPolicy::Park;
+    app.world
+        .entity_mut(inside)
+        .insert(Position(Vector::Y * 20.0));
+
+    app.update();
+    app.update();
+
+    let entity = app.world.entity(inside);
+    assert!(entity.contains::<Sleeping>());
+    assert_eq!(entity.get::<LinearVelocity>().unwrap().0, Vector::ZERO);
+
+    // Despawned bodies are removed
+    app.world.resource_mut::<WorldBounds>().policy = OutOfBoundsPolicy::Despawn;
+    app.world
+        .entity_mut(outside)
+        .insert(Position(Vector::NEG_Y * 20.0));
+
+    app.update();
+
+    assert!(app.world.get_entity(outside).is_none());
+}
+
+#[cfg(feature = "2d")]
+#[test]
+fn polyline_supports_falling_body() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    // A V-shaped level boundary
+    app.world.spawn((
+        RigidBody::Static,
+        Collider::polyline(
+            vec![
+                Vector::new(-10.0, 10.0),
+                Vector::new(0.0, 0.0),
+                Vector::new(10.0, 10.0),
+            ],
+            None,
+        ),
+    ));
+    let ball = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::new(0.0, 5.0)),
+            Collider::ball(1.0),
+        ))
+        .id();
+
+    for _ in 0..240 {
+        app.update();
+    }
+
+    // The ball rests at the bottom of the V without passing through
+    let position = app.world.entity(ball).get::<Position>().unwrap();
+    assert!(position.y > 1.0);
+    assert!(position.y < 2.0);
+    assert_relative_eq!(position.x, 0.0, epsilon = 0.1);
+}
+
+#[cfg(feature = "2d")]
+#[test]
+fn one_sided_polyline_ignores_back_sides() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO)
+        .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    // A floor going from right to left, so its front sides point up
+    app.world.spawn((
+        RigidBody::Static,
+        Collider::polyline(
+            vec![
+                Vector::new(10.0, 0.0),
+                Vector::new(0.0, 0.0),
+                Vector::new(-10.0, 0.0),
+            ],
+            None,
+        )
+        .with_polyline_collision_flags(PolylineCollisionFlags { one_sided: true }),
+    ));
+    let above = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::new(-5.0, 2.0)),
+            LinearVelocity(Vector::NEG_Y * 5.0),
+            Collider::ball(0.5),
+        ))
+        .id();
+    let below = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::new(5.0, -2.0)),
+            LinearVelocity(Vector::Y * 5.0),
+            Collider::ball(0.5),
+        ))
+        .id();
+
+    for _ in 0..60 {
+        app.update();
+    }
+
+    // The body above lands on the front side, and the body below passes through the back side
+    let above_position = app.world.entity(above).get::<Position>().unwrap();
+    let below_position = app.world.entity(below).get::<Position>().unwrap();
+    assert!(above_position.y > 0.4);
+    assert!(below_position.y > 2.0);
+}
+
+#[cfg(all(feature = "3d", feature = "spatial-query"))]
+#[test]
+fn one_sided_trimesh_ignores_back_faces() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO)
+        .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    // A single upward-facing triangle
+    let floor = app
+        .world
+        .spawn((
+            RigidBody::Static,
+            Collider::trimesh(
+                vec![
+                    Vector::new(-10.0, 0.0, -10.0),
+                    Vector::new(-10.0, 0.0, 10.0),
+                    Vector::new(10.0, 0.0, 0.0),
+                ],
+                vec![[0, 1, 2]],
+            )
+            .with_trimesh_collision_flags(TriMeshCollisionFlags {
+                one_sided: true,
+                cull_backfaces: true,
+                ..default()
+            }),
+        ))
+        .id();
+    let above = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::new(-5.0, 2.0, 2.0)),
+            LinearVelocity(Vector::NEG_Y * 5.0),
+            Collider::ball(0.5),
+        ))
+        .id();
+    let below = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::new(-5.0, -2.0, -2.0)),
+            LinearVelocity(Vector::Y * 5.0),
+            Collider::ball(0.5),
+        ))
+        .id();
+
+    for _ in 0..60 {
+        app.update();
+    }
+
+    // The body above lands on the front face, and the body below passes through the back face
+    let above_position = app.world.entity(above).get::<Position>().unwrap();
+    let below_position = app.world.entity(below).get::<Position>().unwrap();
+    assert!(above_position.y > 0.4);
+    assert!(below_position.y > 2.0);
+
+    let pipeline = app.world.resource::<SpatialQueryPipeline>();
+    let cast = |origin: Vector, direction: Vector| {
+        pipeline
+            .cast_ray(
+                origin,
+                direction,
+                100.0,
+                true,
+                SpatialQueryFilter::new().without_entities([above, below]),
+            )
+            .map(|hit| hit.entity)
+    };
+    assert_eq!(
+        cast(Vector::new(-5.0, 5.0, 0.0), Vector::NEG_Y),
+        Some(floor)
+    );
+    assert_eq!(cast(Vector::new(-5.0, -5.0, 0.0), Vector::Y), None);
+}
+
+#[cfg(feature = "3d")]
+#[test]
+fn trimesh_internal_edges_dont_catch_sliding_bodies() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    // A flat grid of small triangles
+    let size = 40;
+    let mut vertices = vec![];
+    let mut indices = vec![];
+    for z in 0..=size {
+        for x in 0..=size {
+            vertices.push(Vector::new(
+                x as Scalar - 5.0,
+                0.0,
+                z as Scalar * 0.5 - 10.0,
+            ));
+        }
+    }
+    for z in 0..size {
+        for x in 0..size {
+            let i = z * (size + 1) + x;
+            indices.push([i, i + size + 1, i + 1]);
+            indices.push([i + 1, i + size + 1, i + size + 2]);
+        }
+    }
+    app.world.spawn((
+        RigidBody::Static,
+        Collider::trimesh(vertices, indices).with_trimesh_collision_flags(TriMeshCollisionFlags {
+            fix_internal_edges: true,
+            ..default()
+        }),
+    ));
+
+    let body = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::Y * 0.5),
+            LinearVelocity(Vector::X * 5.0),
+            Collider::cuboid(1.0, 1.0, 1.0),
+            Friction::ZERO,
+        ))
+        .id();
+
+    for _ in 0..60 {
+        app.update();
+
+        // The body slides across the triangle edges without being kicked upward
+        let position = app.world.entity(body).get::<Position>().unwrap();
+        let lin_vel = app.world.entity(body).get::<LinearVelocity>().unwrap();
+        assert!(position.y < 0.55);
+        assert!(lin_vel.y < 0.5);
+    }
+
+    let position = app.world.entity(body).get::<Position>().unwrap();
+    assert!(position.x > 3.0);
+}
+
+#[cfg(feature = "3d")]
+#[test]
+fn heightfield_internal_edges_dont_catch_sliding_bodies() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    // A flat 20x20 terrain with cells of half a unit
+    let heightfield = Collider::heightfield(vec![vec![0.0; 41]; 41], Vector::new(20.0, 1.0, 20.0))
+        .with_trimesh_collision_flags(TriMeshCollisionFlags {
+            fix_internal_edges: true,
+            ..default()
+        });
+
+    // A contact against an edge shared by two triangles of the first cell gets the face normal
+    let tilted_normal = Vector::new(1.0, 1.0, 0.0).normalize().into();
+    let edge_point = parry::math::Point::new(-9.75, 0.0, -9.75);
+    assert_eq!(
+        heightfield.internal_edge_face_normal(0, &tilted_normal, [edge_point].into_iter()),
+        Some(Vector::Y.into())
+    );
+
+    app.world.spawn((RigidBody::Static, heightfield));
+
+    let body = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::Y * 0.5),
+            LinearVelocity(Vector::new(5.0, 0.0, 2.0)),
+            Collider::cuboid(1.0, 1.0, 1.0),
+            Friction::ZERO,
+        ))
+        .id();
+
+    for _ in 0..60 {
+        app.update();
+
+        // The body slides across the cell edges without being kicked upward
+        let position = app.world.entity(body).get::<Position>().unwrap();
+        let lin_vel = app.world.entity(body).get::<LinearVelocity>().unwrap();
+        assert!(position.y < 0.55);
+        assert!(lin_vel.y < 0.5);
+    }
+
+    let position = app.world.entity(body).get::<Position>().unwrap();
+    assert!(position.x > 3.0);
+}
+
+#[test]
+fn remote_body_is_pulled_towards_target() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO)
+        .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    #[cfg(feature = "2d")]
+    let target_rotation = Rotation::from_radians(1.0);
+    #[cfg(feature = "3d")]
+    let target_rotation = Rotation(Quaternion::from_rotation_y(1.0));
+
+    let body = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position::default(),
+            Collider::ball(0.5),
+            RemoteBody::new(Vector::X * 5.0, target_rotation),
+        ))
+        .id();
+
+    app.update();
+
+    // The correction is soft, so the body doesn't snap to the target
+    let position = app.world.entity(body).get::<Position>().unwrap();
+    assert!(position.x > 0.0);
+    assert!(position.x < 5.0);
+
+    for _ in 0..120 {
+        app.update();
+    }
+
+    let entity = app.world.entity(body);
+    assert_relative_eq!(
+        entity.get::<Position>().unwrap().0,
+        Vector::X * 5.0,
+        epsilon = 0.05
+    );
+    #[cfg(feature = "2d")]
+    assert_relative_eq!(
+        entity.get::<Rotation>().unwrap().as_radians(),
+        1.0,
+        epsilon = 0.05
+    );
+    #[cfg(feature = "3d")]
+    assert!(
+        entity
+            .get::<Rotation>()
+            .unwrap()
+            .angle_between(target_rotation.0)
+            < 0.05
+    );
+}
+
+#[test]
+fn resimulation_steps_bodies_and_their_contacts() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO)
+        .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    let player = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::X * 100.0),
+            Collider::ball(0.5),
+        ))
+        .id();
+    let obstacle = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::X * 3.0),
+            Collider::ball(0.5),
+        ))
+        .id();
+    let unrelated = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::Y * 50.0),
+            LinearVelocity(Vector::X),
+            Collider::ball(0.5),
+        ))
+        .id();
+
+    app.update();
+
+    let unrelated_position = *app.world.entity(unrelated).get::<Position>().unwrap();
+    let snapshot = BodyState {
+        position: Position(Vector::ZERO),
+        ..default()
+    };
+
+    // Replay an input that moves the player into the obstacle
+    let resimulated =
+        Resimulation::new([(player, snapshot)]).run(&mut app.world, 60, |world, _| {
+            world.get_mut::<LinearVelocity>(player).unwrap().0 = Vector::X * 5.0;
+        });
+
+    assert!(resimulated.contains(&player));
+    assert!(resimulated.contains(&obstacle));
+    assert!(!resimulated.contains(&unrelated));
+
+    // The player moved from the snapshot and pushed the obstacle
+    let player_position = app.world.entity(player).get::<Position>().unwrap();
+    let obstacle_position = app.world.entity(obstacle).get::<Position>().unwrap();
+    assert!(player_position.x > 2.0);
+    assert!(obstacle_position.x > 3.0);
+
+    // Bodies outside of the re-simulation are not disturbed
+    assert_eq!(
+        *app.world.entity(unrelated).get::<Position>().unwrap(),
+        unrelated_position
+    );
+}
+
+#[test]
+fn resimulation_freezes_other_bodies_and_sends_no_collision_events() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO)
+        .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    let player = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::X * 100.0),
+            Collider::ball(0.5),
+        ))
+        .id();
+    app.world.spawn((
+        RigidBody::Dynamic,
+        Position(Vector::X * 3.0),
+        Collider::ball(0.5),
+    ));
+    let unrelated = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::Y * 50.0),
+            LinearVelocity(Vector::X),
+            Collider::ball(0.5),
+        ))
+        .id();
+
+    app.update();
+
+    let unrelated_previous_position = app.world.get::<PreviousPosition>(unrelated).copied();
+    let collision_count = |app: &App| app.world.resource::<Events<CollisionStarted>>().len();
+    let collisions_before = collision_count(&app);
+    let snapshot = BodyState {
+        position: Position(Vector::ZERO),
+        ..default()
+    };
+
+    Resimulation::new([(player, snapshot)]).run(&mut app.world, 60, |world, step| {
+        if step == 0 {
+            // The player starts from the snapshot without any translation left from the original step
+            assert_eq!(
+                world.get::<PreviousPosition>(player).unwrap().0,
+                Vector::ZERO
+            );
+            assert_eq!(
+                world.get::<AccumulatedTranslation>(player).unwrap().0,
+                Vector::ZERO
+            );
+        }
+        // Bodies outside of the re-simulation are not stepped
+        assert!(world.entity(unrelated).contains::<Sleeping>());
+        world.get_mut::<LinearVelocity>(player).unwrap().0 = Vector::X * 5.0;
+    });
+
+    // The player hit the obstacle, but the collision events were already sent for the original steps
+    assert!(app.world.get::<Position>(player).unwrap().x > 2.0);
+    assert_eq!(collision_count(&app), collisions_before);
+
+    // The frozen body is restored and woken up again
+    assert_eq!(
+        app.world.get::<PreviousPosition>(unrelated).copied(),
+        unrelated_previous_position
+    );
+    assert!(!app.world.entity(unrelated).contains::<Sleeping>());
+}
+
+#[test]
+fn body_state_quantization_round_trips() {
+    let quantizer = StateQuantizer::default();
+
+    #[cfg(feature = "2d")]
+    let state = BodyState::new(
+        Position(Vector::new(123.456, -7.89)),
+        Rotation::from_radians(2.5),
+        LinearVelocity(Vector::new(12.0, -3.5)),
+        AngularVelocity(-4.0),
+    );
+    #[cfg(feature = "3d")]
+    let state = BodyState::new(
+        Position(Vector::new(123.456, -7.89, 0.5)),
+        Rotation(Quaternion::from_euler(EulerRot::XYZ, 0.3, -1.2, 2.5)),
+        LinearVelocity(Vector::new(12.0, -3.5, 0.25)),
+        AngularVelocity(Vector::new(-4.0, 1.0, 0.0)),
+    );
+
+    let bytes = quantizer.quantize(&state).to_bytes();
+    assert_eq!(bytes.len(), QuantizedBodyState::BYTE_LEN);
+    assert!(QuantizedBodyState::from_bytes(&bytes[1..]).is_none());
+
+    let quantized = QuantizedBodyState::from_bytes(&bytes).unwrap();
+    let error = state.error(&quantizer.dequantize(&quantized));
+    assert!(error.position <= quantizer.position_precision);
+    assert!(error.rotation < 0.005);
+    assert!(error.linear_velocity < 0.01);
+    assert!(error.angular_velocity < 0.01);
+
+    // Velocities are clamped to the maximum speeds
+    let fast_state = BodyState {
+        linear_velocity: LinearVelocity(Vector::X * 1000.0),
+        ..state
+    };
+    let dequantized = quantizer.dequantize(&quantizer.quantize(&fast_state));
+    assert_relative_eq!(dequantized.linear_velocity.x, quantizer.max_linear_speed);
+}
+
+#[test]
+fn round_shapes_are_inflated_by_border_radius() {
+    #[cfg(feature = "2d")]
+    let (collider, aabb_rotation) = (Collider::round_cuboid(2.0, 1.0, 0.25), 0.0);
+    #[cfg(feature = "3d")]
+    let (collider, aabb_rotation) = (
+        Collider::round_cuboid(2.0, 1.0, 1.0, 0.25),
+        Quaternion::IDENTITY,
+    );
+    assert!(collider.get_shape().as_round_cuboid().is_some());
+
+    let aabb = collider.compute_aabb(Vector::ZERO, aabb_rotation);
+    assert_relative_eq!(aabb.maxs.x - aabb.mins.x, 2.5, epsilon = 0.0001);
+    assert_relative_eq!(aabb.maxs.y - aabb.mins.y, 1.5, epsilon = 0.0001);
+
+    #[cfg(feature = "3d")]
+    {
+        let collider = Collider::round_cylinder(2.0, 0.5, 0.1);
+        assert!(collider.get_shape().as_round_cylinder().is_some());
+
+        let aabb = collider.compute_aabb(Vector::ZERO, Quaternion::IDENTITY);
+        assert_relative_eq!(aabb.maxs.y - aabb.mins.y, 2.2, epsilon = 0.0001);
+        assert_relative_eq!(aabb.maxs.x - aabb.mins.x, 1.2, epsilon = 0.0001);
+    }
+}
+
+#[cfg(feature = "3d")]
+#[test]
+fn cone_mass_properties() {
+    let cone = Collider::cone(2.0, 1.0);
+    let props = ColliderMassProperties::new_computed(&cone, 1.0);
+
+    // The volume of a cone is `PI * r^2 * h / 3`
+    assert_relative_eq!(props.mass.0, PI * 2.0 / 3.0, epsilon = 0.0001);
+    assert_relative_eq!(
+        props.center_of_mass.0,
+        Vector::NEG_Y * 0.5,
+        epsilon = 0.0001
+    );
+
+    let aabb = cone.compute_aabb(Vector::ZERO, Quaternion::IDENTITY);
+    assert_relative_eq!(aabb.maxs.y, 1.0, epsilon = 0.0001);
+    assert_relative_eq!(aabb.mins.y, -1.0, epsilon = 0.0001);
+}
+
+#[cfg(all(feature = "3d", feature = "collider-from-image"))]
+#[test]
+fn heightfield_from_image_uses_pixel_brightness() {
+    use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+    // A 4x4 heightmap with a single white pixel
+    let mut data = vec![0; 16];
+    data[5] = u8::MAX;
+    let image = Image::new(
+        Extent3d {
+            width: 4,
+            height: 4,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::R8Unorm,
+    );
+
+    let collider = Collider::heightfield_from_image(&image, Vector::new(8.0, 2.0, 8.0), 1).unwrap();
+    let heightfield = collider.get_shape().as_heightfield().unwrap();
+    assert_eq!(heightfield.nrows(), 3);
+    assert_eq!(heightfield.ncols(), 3);
+
+    let aabb = collider.compute_aabb(Vector::ZERO, Quaternion::IDENTITY);
+    assert_relative_eq!(aabb.maxs.y, 2.0, epsilon = 0.0001);
+    assert_relative_eq!(aabb.maxs.x - aabb.mins.x, 8.0, epsilon = 0.0001);
+
+    // Sub-sampling every other pixel leaves a 2x2 grid of points
+    let collider = Collider::heightfield_from_image(&image, Vector::new(8.0, 2.0, 8.0), 2).unwrap();
+    let heightfield = collider.get_shape().as_heightfield().unwrap();
+    assert_eq!(heightfield.nrows(), 1);
+    assert_eq!(heightfield.ncols(), 1);
+}
+
+#[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
+#[test]
+fn convex_hull_from_bevy_mesh() {
+    let cube = Mesh::from(shape::Box::new(1.0, 2.0, 3.0));
+    let collider = Collider::convex_hull_from_bevy_mesh(&cube).unwrap();
+    assert!(collider.get_shape().as_convex_polyhedron().is_some());
+
+    let aabb = collider.compute_aabb(Vector::ZERO, Quaternion::IDENTITY);
+    assert_relative_eq!(aabb.maxs.x - aabb.mins.x, 1.0, epsilon = 0.0001);
+    assert_relative_eq!(aabb.maxs.y - aabb.mins.y, 2.0, epsilon = 0.0001);
+    assert_relative_eq!(aabb.maxs.z - aabb.mins.z, 3.0, epsilon = 0.0001);
+
+    // Meshes without vertex positions don't have a hull
+    let empty = Mesh::new(bevy::render::render_resource::PrimitiveTopology::TriangleList);
+    assert!(Collider::convex_hull_from_bevy_mesh(&empty).is_none());
+}
+
+#[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
+#[test]
+fn mesh_mass_properties_match_primitive_shapes() {
+    let cube = Mesh::from(shape::Box::new(1.0, 2.0, 3.0));
+    let expected = ColliderMassProperties::new_computed(&Collider::cuboid(1.0, 2.0, 3.0), 2.0);
+
+    let props = MeshMassProperties::from_bevy_mesh(&cube).unwrap();
+    assert_relative_eq!(props.volume, 6.0, epsilon = 0.0001);
+    assert_relative_eq!(props.center_of_mass, Vector::ZERO, epsilon = 0.0001);
+
+    // Trimesh and convex colliders get the same mass properties as the primitive
+    for collider in [
+        Collider::trimesh_from_bevy_mesh(&cube).unwrap(),
+        Collider::convex_hull_from_bevy_mesh(&cube).unwrap(),
+    ] {
+        let computed = ColliderMassProperties::new_computed(&collider, 2.0);
+        assert_relative_eq!(computed.mass.0, expected.mass.0, epsilon = 0.0001);
+        assert_relative_eq!(computed.inertia.0, expected.inertia.0, epsilon = 0.0001);
+    }
+
+    // Flat meshes have no volume
+    let plane = Mesh::from(shape::Plane::from_size(1.0));
+    assert!(MeshMassProperties::from_bevy_mesh(&plane).is_none());
+}
+
+#[cfg(all(feature = "3d", feature = "collider-from-mesh"))]
+#[test]
+fn async_collider_replaces_placeholder() {
+    let mut app = create_app();
+    app.add_plugins(AssetPlugin::default()).add_asset::<Mesh>();
+
+    let mesh = app
+        .world
+        .resource_mut::<Assets<Mesh>>()
+        .add(Mesh::from(shape::Cube { size: 1.0 }));
+    let entity = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            AsyncCollider::new(mesh).with_placeholder(Collider::ball(0.5)),
+        ))
+        .id();
+
+    app.update();
+
+    let placeholder = app.world.entity(entity).get::<Collider>().unwrap();
+    assert!(placeholder.get_shape().as_ball().is_some());
+
+    // Wait for the decomposition to finish
+    for _ in 0..1000 {
+        if app.world.entity(entity).get::<AsyncCollider>().is_none() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+        app.update();
+    }
+
+    assert!(app.world.entity(entity).get::<AsyncCollider>().is_none());
+    let collider = app.world.entity(entity).get::<Collider>().unwrap();
+    assert!(collider.get_shape().as_compound().is_some());
+    assert_relative_eq!(
+        app.world.entity(entity).get::<Mass>().unwrap().0,
+        1.0,
+        epsilon = 0.01
+    );
+}
+
+#[cfg(all(feature = "3d", feature = "async-collider"))]
+#[test]
+fn async_scene_collider_creates_colliders_for_meshes() {
+    use bevy::scene::ScenePlugin;
+
+    let mut app = create_app();
+    app.add_plugins((AssetPlugin::default(), HierarchyPlugin, ScenePlugin))
+        .add_asset::<Mesh>()
+        .register_type::<Handle<Mesh>>()
+        .register_type::<Name>();
+
+    let mut meshes = app.world.resource_mut::<Assets<Mesh>>();
+    let cube = meshes.add(Mesh::from(shape::Cube { size: 1.0 }));
+    let ball = meshes.add(Mesh::from(shape::UVSphere::default()));
+
+    // A scene with a level mesh, a named prop and a named mesh without a collider
+    let mut scene_world = World::new();
+    scene_world.spawn((TransformBundle::default(), cube.clone()));
+    scene_world
+        .spawn((TransformBundle::default(), Name::new("Prop")))
+        .with_children(|parent| {
+            parent.spawn((TransformBundle::default(), ball));
+        });
+    scene_world.spawn((TransformBundle::default(), Name::new("Decal"), cube));
+    let scene = app
+        .world
+        .resource_mut::<Assets<Scene>>()
+        .add(Scene::new(scene_world));
+
+    let scene_entity = app
+        .world
+        .spawn((
+            SceneBundle { scene, ..default() },
+            AsyncSceneCollider::new(Some(ComputedCollider::TriMesh))
+                .with_shape_for_name("Prop", Some(ComputedCollider::ConvexHull))
+                .with_shape_for_name("Decal", None),
+        ))
+        .id();
+
+    for _ in 0..3 {
+        app.update();
+    }
+
+    assert!(app
+        .world
+        .entity(scene_entity)
+        .get::<AsyncSceneCollider>()
+        .is_none());
+
+    let mut colliders = app.world.query::<&Collider>();
+    let shapes = colliders
+        .iter(&app.world)
+        .map(|collider| collider.get_shape().shape_type())
+        .collect::<Vec<_>>();
+    assert_eq!(shapes.len(), 2);
+    assert!(shapes.contains(&parry::shape::ShapeType::TriMesh));
+    assert!(shapes.contains(&parry::shape::ShapeType::ConvexPolyhedron));
+}
+
+#[cfg(all(feature = "3d", feature = "bone-collider"))]
+#[test]
+fn bone_collider_follows_joint() {
+    use bevy::render::mesh::skinning::SkinnedMesh;
+
+    let mut app = create_app();
+
+    let joint_transform =
+        Transform::from_xyz(1.0, 2.0, 3.0).with_rotation(Quat::from_rotation_y(0.5));
+    let joint = app.world.spawn(GlobalTransform::from(joint_transform)).id();
+    let skinned_mesh = app
+        .world
+        .spawn(SkinnedMesh {
+            joints: vec![joint],
+            ..default()
+        })
+        .id();
+    let body = app
+        .world
+        .spawn((
+            RigidBody::Kinematic,
+            Collider::capsule(0.5, 0.2),
+            BoneCollider::new(skinned_mesh, 0),
+        ))
+        .id();
+
+    app.update();
+
+    let position = app.world.entity(body).get::<Position>().unwrap();
+    let rotation = app.world.entity(body).get::<Rotation>().unwrap();
+    assert_relative_eq!(position.0, Vector::new(1.0, 2.0, 3.0), epsilon = 0.0001);
+    assert_relative_eq!(
+        rotation.0,
+        Quaternion::from_rotation_y(0.5),
+        epsilon = 0.0001
+    );
+}
+
+#[cfg(all(feature = "3d", feature = "bone-collider"))]
+#[test]
+fn bone_collider_hands_off_between_animation_and_physics() {
+    use bevy::render::mesh::skinning::SkinnedMesh;
+
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO);
+
+    let joint = app
+        .world
+        .spawn(TransformBundle::from_transform(Transform::from_xyz(
+            0.0, 2.0, 0.0,
+        )))
+        .id();
+    let skinned_mesh = app
+        .world
+        .spawn(SkinnedMesh {
+            joints: vec![joint],
+            ..default()
+        })
+        .id();
+    let body = app
+        .world
+        .spawn((
+            RigidBody::Kinematic,
+            Collider::ball(0.5),
+            BoneCollider::new(skinned_mesh, 0).with_blend_duration(0.0),
+        ))
+        .id();
+
+    // Animate the joint along the X axis
+    for i in 0..3 {
+        app.world.get_mut::<Transform>(joint).unwrap().translation.x = i as f32 * 0.1;
+        tick_60_fps(&mut app);
+    }
+
+    // Take the body over with physics, handing off the velocity of the animation
+    app.world.get_mut::<BoneCollider>(body).unwrap().mode = BoneColliderMode::Simulated;
+    tick_60_fps(&mut app);
+
+    assert_eq!(
+        *app.world.get::<RigidBody>(body).unwrap(),
+        RigidBody::Dynamic
+    );
+    let velocity = app.world.get::<LinearVelocity>(body).unwrap().0;
+    assert_relative_eq!(velocity, Vector::X * 6.0, epsilon = 0.01);
+
+    // The joint follows the simulated body before each physics frame
+    let position = app.world.get::<Position>(body).unwrap().0;
+    tick_60_fps(&mut app);
+    let joint_translation = app.world.get::<Transform>(joint).unwrap().translation;
+    assert_relative_eq!(joint_translation.x, position.x as f32, epsilon = 0.0001);
+
+    // Give the body back to the animation
+    app.world.get_mut::<Transform>(joint).unwrap().translation = Vec3::new(1.0, 2.0, 0.0);
+    app.world.get_mut::<BoneCollider>(body).unwrap().mode = BoneColliderMode::Animated;
+    tick_60_fps(&mut app);
+
+    assert_eq!(
+        *app.world.get::<RigidBody>(body).unwrap(),
+        RigidBody::Kinematic
+    );
+    assert_eq!(
+        app.world.get::<LinearVelocity>(body).unwrap().0,
+        Vector::ZERO
+    );
+    let position = app.world.get::<Position>(body).unwrap().0;
+    assert_relative_eq!(position, Vector::new(1.0, 2.0, 0.0), epsilon = 0.0001);
+}
+
+#[cfg(all(feature = "3d", feature = "bone-collider"))]
+#[test]
+fn hit_reaction_decays_back_to_joint() {
+    let mut reaction = HitReaction::new(2.0, 0.1);
+    reaction.apply_impulse_at_point(Vector::X * 4.0, Vector::Y, Vector::ZERO);
+    assert_relative_eq!(reaction.velocity, Vector::X * 2.0);
+    assert!(reaction.angular_velocity.z < 0.0);
+
+    for _ in 0..10 {
+        reaction.advance(1.0 / 60.0);
+    }
+    assert!(reaction.offset.x > 0.0);
+    assert!(reaction.angular_offset.z < 0.0);
+
+    for _ in 0..300 {
+        reaction.advance(1.0 / 60.0);
+    }
+    assert_relative_eq!(reaction.offset, Vector::ZERO, epsilon = 0.001);
+    assert_relative_eq!(reaction.angular_offset, Vector::ZERO, epsilon = 0.001);
+}
+
+#[cfg(all(feature = "3d", feature = "bone-collider"))]
+#[test]
+fn hit_reaction_offset_is_applied_once() {
+    use bevy::render::mesh::skinning::SkinnedMesh;
+
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO);
+
+    let joint = app
+        .world
+        .spawn(TransformBundle::from_transform(Transform::from_xyz(
+            0.0, 2.0, 0.0,
+        )))
+        .id();
+    let skinned_mesh = app
+        .world
+        .spawn(SkinnedMesh {
+            joints: vec![joint],
+            ..default()
+        })
+        .id();
+
+    // A constant offset that is blended into the joint
+    let hit_reaction = HitReaction {
+        offset: Vector::X * 0.5,
+        stiffness: 0.0,
+        damping: 0.0,
+        blend_into_joint: true,
+        ..default()
+    };
+    let body = app
+        .world
+        .spawn((
+            RigidBody::Kinematic,
+            Collider::ball(0.5),
+            BoneCollider::new(skinned_mesh, 0),
+            hit_reaction,
+        ))
+        .id();
+
+    for _ in 0..5 {
+        tick_60_fps(&mut app);
+    }
+
+    // The offset doesn't accumulate in the joint or the body
+    let joint_translation = app.world.get::<Transform>(joint).unwrap().translation;
+    assert_relative_eq!(
+        joint_translation,
+        Vec3::new(0.5, 2.0, 0.0),
+        epsilon = 0.0001
+    );
+    let position = app.world.get::<Position>(body).unwrap().0;
+    assert_relative_eq!(position, Vector::new(0.5, 2.0, 0.0), epsilon = 0.0001);
+
+    // Animating the joint replaces the offset transform, and the offset is applied to the new pose
+    app.world.get_mut::<Transform>(joint).unwrap().translation = Vec3::new(1.0, 2.0, 0.0);
+    tick_60_fps(&mut app);
+
+    let joint_translation = app.world.get::<Transform>(joint).unwrap().translation;
+    assert_relative_eq!(
+        joint_translation,
+        Vec3::new(1.5, 2.0, 0.0),
+        epsilon = 0.0001
+    );
+    let position = app.world.get::<Position>(body).unwrap().0;
+    assert_relative_eq!(position, Vector::new(1.5, 2.0, 0.0), epsilon = 0.0001);
+}
+
+#[test]
+fn physics_types_are_registered_for_reflection() {
+    use bevy::ecs::reflect::ReflectMapEntities;
+
+    let mut app = create_app();
+    let body1 = app.world.spawn(RigidBody::Dynamic).id();
+    let body2 = app.world.spawn(RigidBody::Dynamic).id();
+    let joint = app
+        .world
+        .spawn((FixedJoint::new(body1, body2), Collider::ball(0.5)))
+        .id();
+
+    // Initializes the components and resources accessed by the physics systems
+    app.update();
+
+    let registry = app.world.resource::<AppTypeRegistry>().read();
+
+    // Internal caches and acceleration structures that are rebuilt by the engine
+    let internal = [
+        "AabbIntervals",
+        "AsyncColliderTask",
+        "ColliderMassContributions",
+        "DynamicBvh",
+        "JointAssemblies",
+        "OneSidedPassThroughs",
+        "PenetrationConstraints",
+        "PreviousColliderMassProperties",
+        "Resimulating",
+        "SpatialHash",
+        "SpatialQueryPipeline",
+    ];
+
+    // Every other physics component and resource known to the world can be reflected
+    let components = app.world.components();
+    let unreflected = components
+        .iter()
+        .filter(|info| info.name().starts_with("bevy_xpbd"))
+        .filter(|info| !internal.iter().any(|name| info.name().ends_with(name)))
+        .filter(|info| {
+            let Some(type_id) = info.type_id() else {
+                return false;
+            };
+            if components.get_resource_id(type_id) == Some(info.id()) {
+                registry.get_type_data::<ReflectResource>(type_id).is_none()
+            } else {
+                registry
+                    .get_type_data::<ReflectComponent>(type_id)
+                    .is_none()
+            }
+        })
+        .map(|info| info.name())
+        .collect::<Vec<_>>();
+    assert!(
+        unreflected.is_empty(),
+        "types without reflection: {unreflected:#?}"
+    );
+
+    for type_id in [
+        std::any::TypeId::of::<Collision>(),
+        std::any::TypeId::of::<CollisionStarted>(),
+    ] {
+        assert!(registry.get(type_id).is_some());
+    }
+    assert!(registry
+        .get_type_data::<ReflectMapEntities>(std::any::TypeId::of::<FixedJoint>())
+        .is_some());
+
+    let reflect_joint = registry
+        .get_type_data::<ReflectComponent>(std::any::TypeId::of::<FixedJoint>())
+        .unwrap();
+    let reflected = reflect_joint.reflect(app.world.entity(joint)).unwrap();
+    let reflected = reflected.downcast_ref::<FixedJoint>().unwrap();
+    assert_eq!(reflected.entity1, body1);
+    assert_eq!(reflected.entity2, body2);
+
+    let reflect_collider = registry
+        .get_type_data::<ReflectComponent>(std::any::TypeId::of::<Collider>())
+        .unwrap();
+    assert!(reflect_collider.reflect(app.world.entity(joint)).is_some());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn components_and_colliders_serde_round_trip() {
+    let friction = Friction::new(0.3).with_combine_rule(CoefficientCombine::Max);
+    let json = serde_json::to_string(&friction).unwrap();
+    assert_eq!(serde_json::from_str::<Friction>(&json).unwrap(), friction);
+
+    let velocity = LinearVelocity(Vector::X * 2.0);
+    let json = serde_json::to_string(&velocity).unwrap();
+    assert_eq!(
+        serde_json::from_str::<LinearVelocity>(&json).unwrap(),
+        velocity
+    );
+
+    let gravity = Gravity(Vector::NEG_Y * 3.0);
+    let json = serde_json::to_string(&gravity).unwrap();
+    assert_eq!(serde_json::from_str::<Gravity>(&json).unwrap().0, gravity.0);
+
+    #[cfg(feature = "2d")]
+    let collider = Collider::cuboid(1.0, 2.0);
+    #[cfg(feature = "3d")]
+    let collider = Collider::cuboid(1.0, 2.0, 3.0);
+    let json = serde_json::to_string(&collider).unwrap();
+    let loaded = serde_json::from_str::<Collider>(&json).unwrap();
+    assert_eq!(
+        loaded.as_cuboid().unwrap().half_extents,
+        collider.as_cuboid().unwrap().half_extents
+    );
+}
+
+#[cfg(feature = "frame-capture")]
+#[test]
+fn physics_frame_capture_round_trips() {
+    let mut app = create_app();
+
+    spawn_ground(&mut app);
+    app.add_systems(Startup, |mut commands: Commands| {
+        commands.spawn((
+            RigidBody::Dynamic,
+            Position(Vector::Y * 0.4),
+            Collider::ball(0.5),
+        ));
+    });
+
+    for _ in 0..5 {
+        tick_60_fps(&mut app);
+    }
+
+    let capture = PhysicsCapture::capture(&mut app.world);
+
+    assert_eq!(capture.bodies.len(), 2);
+    assert_eq!(capture.colliders.len(), 2);
+    assert!(!capture.contacts.is_empty());
+    assert!(capture
+        .colliders
+        .iter()
+        .any(|c| c.shape == CapturedShape::Ball { radius: 0.5 }));
+
+    let loaded = PhysicsCapture::from_json(&capture.to_json().unwrap()).unwrap();
+
+    assert_eq!(loaded.bodies.len(), capture.bodies.len());
+    assert_eq!(loaded.contacts.len(), capture.contacts.len());
+    for (loaded, body) in loaded.bodies.iter().zip(capture.bodies.iter()) {
+        assert_eq!(loaded.entity, body.entity);
+        assert_eq!(loaded.rigid_body, body.rigid_body);
+        assert_relative_eq!(loaded.position, body.position);
+    }
+}
+
+#[cfg(feature = "frame-capture")]
+#[test]
+fn physics_frame_capture_replays_simulation() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    spawn_ground(&mut app);
+    app.add_systems(Startup, |mut commands: Commands| {
+        commands.spawn((
+            RigidBody::Dynamic,
+            Position(Vector::Y * 2.0),
+            LinearVelocity(Vector::X),
+            Collider::ball(0.5),
+        ));
+    });
+
+    for _ in 0..5 {
+        app.update();
+    }
+
+    let capture = PhysicsCapture::capture(&mut app.world);
+    let mut replay = capture.create_app();
+
+    assert_eq!(
+        replay
+            .world
+            .query::<&Collider>()
+            .iter(&replay.world)
+            .count(),
+        2
+    );
+
+    for _ in 0..60 {
+        app.update();
+        replay.update();
+    }
+
+    let mut positions = |world: &mut World| {
+        world
+            .query::<(&RigidBody, &Position)>()
+            .iter(world)
+            .filter(|(rb, _)| rb.is_dynamic())
+            .map(|(_, pos)| pos.0)
+            .collect::<Vec<_>>()
+    };
+    let original = positions(&mut app.world);
+    let replayed = positions(&mut replay.world);
+
+    assert_eq!(original.len(), 1);
+    assert_eq!(replayed.len(), 1);
+    assert_relative_eq!(original[0], replayed[0], epsilon = 0.001);
+}
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord)]
+struct Id(usize);
+
+#[cfg(all(feature = "3d", feature = "enhanced-determinism"))]
+#[test]
+fn cubes_simulation_is_deterministic_across_machines() {
+    setup_insta!();
+    let mut app = create_app();
+
+    app.add_systems(Startup, setup_cubes_simulation);
+
+    const SECONDS: usize = 10;
+    const UPDATES: usize = 60 * SECONDS;
+
+    for _ in 0..UPDATES {
+        tick_60_fps(&mut app);
+    }
+
+    let mut app_query = app.world.query::<(&Id, &Transform)>();
+
+    let mut bodies: Vec<(&Id, &Transform)> = app_query.iter(&app.world).collect();
+    bodies.sort_by_key(|b| b.0);
+
+    assert_debug_snapshot!(bodies);
+}
+
+#[cfg(feature = "3d")]
+#[test]
+fn cubes_simulation_is_locally_deterministic() {
+    use itertools::Itertools;
+
+    fn run_cubes() -> Vec<(Id, Transform)> {
+        let mut app = create_app();
+
+        app.add_systems(Startup, setup_cubes_simulation);
+
+        const SECONDS: usize = 5;
+        const UPDATES: usize = 60 * SECONDS;
+
+        for _ in 0..UPDATES {
+            tick_60_fps(&mut app);
+        }
+
+        let mut app_query = app.world.query::<(&Id, &Transform)>();
+
+        let mut bodies: Vec<(Id, Transform)> = app_query
+            .iter(&app.world)
+            .map(|(id, transform)| (*id, *transform))
+            .collect();
+        bodies.sort_by_key(|b| b.0);
+        bodies
+    }
+
+    // run simulation and check that results are equal each time
+    for (a, b) in (0..4).map(|_| run_cubes()).tuple_windows() {
+        assert_eq!(a, b);
+    }
+}
+
+#[test]
+fn deterministic_mode_ignores_query_order() {
+    #[derive(Component)]
+    struct Marker;
+
+    // Simulates the same boxes, but with a marker on some of them,
+    // which changes the order in which the boxes are iterated over
+    let run_boxes = |marked: bool| {
+        let mut app = create_app();
+        app.insert_resource(DeterministicMode(true))
+            .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+        spawn_ground(&mut app);
+        #[cfg(feature = "2d")]
+        let box_collider = Collider::cuboid(1.0, 1.0);
+        #[cfg(feature = "3d")]
+        let box_collider = Collider::cuboid(1.0, 1.0, 1.0);
+
+        let mut boxes = vec![];
+        for i in 0..12 {
+            let position =
+                Vector::X * (i % 3) as Scalar * 0.9 + Vector::Y * (1.0 + i as Scalar * 1.1);
+            let mut entity =
+                app.world
+                    .spawn((RigidBody::Dynamic, Position(position), box_collider.clone()));
+            if marked && i % 2 == 0 {
+                entity.insert(Marker);
+            }
+            boxes.push(entity.id());
+        }
+
+        for _ in 0..120 {
+            app.update();
+        }
+
+        boxes
+            .into_iter()
+            .map(|entity| {
+                let position = app.world.get::<Position>(entity).unwrap().0;
+                let rotation = *app.world.get::<Rotation>(entity).unwrap();
+                (entity, position, rotation)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(run_boxes(false), run_boxes(true));
+}
+
+#[test]
+fn velocity_provider_includes_rotation() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO)
+        .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    #[cfg(feature = "2d")]
+    let angular_velocity = AngularVelocity(2.0);
+    #[cfg(feature = "3d")]
+    let angular_velocity = AngularVelocity(Vector::Z * 2.0);
+
+    let body = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Collider::ball(0.5),
+            LinearVelocity(Vector::X),
+            angular_velocity,
+        ))
+        .id();
+    let emitter = app.world.spawn(TransformBundle::default()).id();
+    app.world.entity_mut(body).add_child(emitter);
+    let other = app.world.spawn(TransformBundle::default()).id();
+
+    app.update();
+
+    let mut system_state: SystemState<(VelocityProvider, Query<&Position>)> =
+        SystemState::new(&mut app.world);
+    let (velocities, positions) = system_state.get(&app.world);
+    let position = positions.get(body).unwrap().0;
+
+    // A point above the center rotates backwards
+    let velocity = velocities
+        .velocity_at_point(emitter, position + Vector::Y)
+        .unwrap();
+    assert!((velocity - Vector::NEG_X).length() < 0.001);
+
+    // The center only moves with the linear velocity
+    let velocity = velocities.velocity_at_point(body, position).unwrap();
+    assert!((velocity - Vector::X).length() < 0.001);
+    assert_eq!(velocities.linear_velocity(emitter), Some(Vector::X));
+
+    // Entities that aren't attached to a body have no velocity
+    assert_eq!(velocities.velocity_at_point(other, Vector::ZERO), None);
+}
+
+#[test]
+fn contact_normal_override_pushes_along_direction() {
+    let slide_distance = |normal_override: Option<ContactNormalOverride>| {
+        let mut app = create_app();
+        app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+        // A slope without friction
+        #[cfg(feature = "2d")]
+        let rotation = Rotation::from_radians(0.3);
+        #[cfg(feature = "3d")]
+        let rotation = Rotation(Quaternion::from_rotation_z(0.3));
+        app.world.spawn((
+            RigidBody::Static,
+            #[cfg(feature = "2d")]
+            Collider::cuboid(20.0, 1.0),
+            #[cfg(feature = "3d")]
+            Collider::cuboid(20.0, 1.0, 20.0),
+            rotation,
+            Friction::ZERO,
+        ));
+
+        let ball = app
+            .world
+            .spawn((
+                RigidBody::Dynamic,
+                Position(Vector::Y * 1.5),
+                Collider::ball(0.5),
+                Friction::ZERO,
+            ))
+            .id();
+        if let Some(normal_override) = normal_override {
+            app.world.entity_mut(ball).insert(normal_override);
+        }
+
+        for _ in 0..120 {
+            app.update();
+        }
+
+        app.world.get::<Position>(ball).unwrap().x.abs()
+    };
+
+    // Without the override, the ball slides down the slope
+    assert!(slide_distance(None) > 0.5);
+
+    // The ground only pushes the ball straight up, so it stays in place
+    let normal_override = ContactNormalOverride::new(Vector::Y).with_max_angle(0.5);
+    assert!(slide_distance(Some(normal_override)) < 0.05);
+
+    // The override doesn't apply to normals outside of the angle
+    let normal_override = ContactNormalOverride::new(Vector::Y).with_max_angle(0.1);
+    assert!(slide_distance(Some(normal_override)) > 0.5);
+}
+
+#[test]
+fn rolling_contact_keeps_rolling_speed() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    app.world.spawn((
+        RigidBody::Static,
+        Position(Vector::NEG_Y * 0.5),
+        #[cfg(feature = "2d")]
+        Collider::cuboid(100.0, 1.0),
+        #[cfg(feature = "3d")]
+        Collider::cuboid(100.0, 1.0, 100.0),
+    ));
+
+    // A ball that is already rolling without slipping
+    let ball = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::Y * 0.5),
+            Collider::ball(0.5),
+            LinearVelocity(Vector::X * 2.0),
+            #[cfg(feature = "2d")]
+            AngularVelocity(-4.0),
+            #[cfg(feature = "3d")]
+            AngularVelocity(Vector::Z * -4.0),
+            RollingContact,
+        ))
+        .id();
+
+    for _ in 0..60 {
+        app.update();
+    }
+
+    let lin_vel = app.world.get::<LinearVelocity>(ball).unwrap();
+    assert!(
+        lin_vel.x > 1.95,
+        "rolling ball slowed down to {}",
+        lin_vel.x
+    );
+}
+
+#[test]
+fn simulation_islands_group_connected_bodies() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    #[cfg(feature = "2d")]
+    let (ground_collider, box_collider) =
+        (Collider::cuboid(100.0, 1.0), Collider::cuboid(1.0, 1.0));
+    #[cfg(feature = "3d")]
+    let (ground_collider, box_collider) = (
+        Collider::cuboid(100.0, 1.0, 100.0),
+        Collider::cuboid(1.0, 1.0, 1.0),
+    );
+
+    let ground = app
+        .world
+        .spawn((
+            RigidBody::Static,
+            Position(Vector::NEG_Y * 0.5),
+            ground_collider,
+        ))
+        .id();
+    let mut spawn_box = |x: Scalar, y: Scalar| {
+        app.world
+            .spawn((
+                RigidBody::Dynamic,
+                Position(Vector::X * x + Vector::Y * y),
+                box_collider.clone(),
+            ))
+            .id()
+    };
+
+    // Two piles of two boxes, two boxes connected by a joint and a single box
+    let pile1 = [spawn_box(-5.0, 0.5), spawn_box(-5.0, 1.5)];
+    let pile2 = [spawn_box(5.0, 0.5), spawn_box(5.0, 1.5)];
+    let jointed = [spawn_box(20.0, 0.5), spawn_box(22.0, 0.5)];
+    let single = spawn_box(-20.0, 0.5);
+    app.world
+        .spawn(DistanceJoint::new(jointed[0], jointed[1]).with_rest_length(2.0));
+
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let islands = app.world.resource::<SimulationIslands>();
+    assert_eq!(islands.len(), 4);
+
+    // The ground is static, so it doesn't connect the piles
+    assert_eq!(islands.island_of(ground), None);
+    assert_eq!(islands.island_of(pile1[0]), islands.island_of(pile1[1]));
+    assert_eq!(islands.island_of(pile2[0]), islands.island_of(pile2[1]));
+    assert_ne!(islands.island_of(pile1[0]), islands.island_of(pile2[0]));
+    assert_eq!(islands.island_of(jointed[0]), islands.island_of(jointed[1]));
+
+    let single_island = islands.island_of(single).unwrap();
+    assert_eq!(islands.bodies(single_island), Some([single].as_slice()));
+}
+
+#[test]
+fn large_island_stays_at_rest() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    #[cfg(feature = "2d")]
+    let (ground_collider, box_collider) =
+        (Collider::cuboid(100.0, 1.0), Collider::cuboid(1.0, 1.0));
+    #[cfg(feature = "3d")]
+    let (ground_collider, box_collider) = (
+        Collider::cuboid(100.0, 1.0, 100.0),
+        Collider::cuboid(1.0, 1.0, 1.0),
+    );
+
+    app.world.spawn((
+        RigidBody::Static,
+        Position(Vector::NEG_Y * 0.5),
+        ground_collider,
+    ));
+
+    // A wall of touching boxes that forms a single island with enough contacts
+    // to be solved in parallel batches with the `parallel` feature
+    let mut boxes = vec![];
+    for x in 0..20 {
+        for y in 0..10 {
+            let position = Vector::X * x as Scalar + Vector::Y * (y as Scalar + 0.5);
+            let entity = app
+                .world
+                .spawn((RigidBody::Dynamic, Position(position), box_collider.clone()))
+                .id();
+            boxes.push((entity, position));
+        }
+    }
+
+    for _ in 0..60 {
+        app.update();
+    }
+
+    assert_eq!(app.world.resource::<SimulationIslands>().len(), 1);
+
+    for (entity, start_position) in boxes {
+        let position = app.world.get::<Position>(entity).unwrap();
+        assert!(
+            position.distance(start_position) < 0.1,
+            "box moved from {start_position} to {}",
+            position.0
+        );
+    }
+}
+
+#[test]
+fn batched_contact_solve_matches_scalar_solve() {
+    use crate::constraints::PenetrationBatch;
+
+    // Spawns pairs of overlapping bodies with different masses, inertias and rotations.
+    // Every third pair is against a static body, and the last lane is left empty.
+    let spawn_pairs = |world: &mut World| {
+        (0..LANES - 1)
+            .map(|i| {
+                let t = i as Scalar;
+                #[cfg(feature = "2d")]
+                let (rotation, inverse_inertia) = (
+                    Rotation::from_radians(0.2 * t),
+                    InverseInertia(1.0 + 0.5 * t),
+                );
+                #[cfg(feature = "3d")]
+                let (rotation, inverse_inertia) = (
+                    Rotation(Quaternion::from_rotation_z(0.2 * t)),
+                    InverseInertia(Matrix3::from_diagonal(
+                        Vector::new(1.0, 2.0, 0.5) * (1.0 + 0.1 * t),
+                    )),
+                );
+                let mut spawn_body = |rb: RigidBody, position: Vector, inverse_mass: Scalar| {
+                    world
+                        .spawn((
+                            rb,
+                            Position(position),
+                            rotation,
+                            PreviousPosition(position),
+                            PreviousRotation(rotation),
+                            AccumulatedTranslation::default(),
+                            LinearVelocity::default(),
+                            PreSolveLinearVelocity::default(),
+                            AngularVelocity::default(),
+                            PreSolveAngularVelocity::default(),
+                            InverseMass(inverse_mass),
+                            inverse_inertia,
+                            CenterOfMass::default(),
+                        ))
+                        .id()
+                };
+
+                let depth = 0.01 * (t + 1.0);
+                let position1 = Vector::X * 10.0 * t;
+                let position2 = position1 + Vector::X * 0.3 + Vector::Y * (1.0 - depth);
+                let body1 = spawn_body(RigidBody::Dynamic, position1, 1.0 + t);
+                let body2 = if i % 3 == 0 {
+                    spawn_body(RigidBody::Static, position2, 0.0)
+                } else {
+                    spawn_body(RigidBody::Dynamic, position2, 1.0 / (1.0 + t))
+                };
+
+                let normal = Vector::Y;
+                let point = position1 + Vector::X * 0.2 + Vector::Y * 0.5;
+                let contact = ContactData::new(
+                    rotation.inverse().rotate(point - position1),
+                    rotation
+                        .inverse()
+                        .rotate(point - Vector::Y * depth - position2),
+                    rotation.inverse().rotate(normal),
+                    rotation.inverse().rotate(-normal),
+                    depth,
+                );
+                (body1, body2, contact)
+            })
+            .collect::<Vec<_>>()
+    };
+    let dt = 1.0 / 240.0;
+
+    // Solve the contacts one by one
+    let mut scalar_world = World::new();
+    let pairs = spawn_pairs(&mut scalar_world);
+    let mut query = scalar_world.query::<RigidBodyQuery>();
+    for &(entity1, entity2, contact) in &pairs {
+        let [mut body1, mut body2] = query
+            .get_many_mut(&mut scalar_world, [entity1, entity2])
+            .unwrap();
+        let mut constraint = PenetrationConstraint::new(&body1, &body2, contact);
+        constraint.solve([&mut body1, &mut body2], dt);
+    }
+
+    // Solve the contacts at once in a batch
+    let mut batch_world = World::new();
+    assert_eq!(spawn_pairs(&mut batch_world), pairs);
+    let mut query = batch_world.query::<RigidBodyQuery>();
+    let mut batch = PenetrationBatch::default();
+    let mut constraints = vec![];
+    for (lane, &(entity1, entity2, contact)) in pairs.iter().enumerate() {
+        let [body1, body2] = query
+            .get_many_mut(&mut batch_world, [entity1, entity2])
+            .unwrap();
+        let mut constraint = PenetrationConstraint::new(&body1, &body2, contact);
+        batch.set_lane(lane, &mut constraint, &body1, &body2);
+        assert!(batch.is_active(lane));
+        constraints.push(constraint);
+    }
+    assert!(!batch.is_active(LANES - 1));
+    let delta_lagrange = batch.compute_lagrange_updates(dt);
+    for (lane, constraint) in constraints.iter_mut().enumerate() {
+        let [mut body1, mut body2] = query
+            .get_many_mut(&mut batch_world, [constraint.entity1, constraint.entity2])
+            .unwrap();
+        constraint.apply_batch_update(&mut body1, &mut body2, delta_lagrange[lane], dt);
+    }
+
+    for &(entity1, entity2, _) in &pairs {
+        for entity in [entity1, entity2] {
+            let scalar = scalar_world.get::<AccumulatedTranslation>(entity).unwrap();
+            let batched = batch_world.get::<AccumulatedTranslation>(entity).unwrap();
+            assert_relative_eq!(scalar.0, batched.0, epsilon = 1e-5);
+
+            let scalar = scalar_world.get::<Rotation>(entity).unwrap();
+            let batched = batch_world.get::<Rotation>(entity).unwrap();
+            assert_relative_eq!(
+                scalar.rotate(Vector::X),
+                batched.rotate(Vector::X),
+                epsilon = 1e-5
+            );
+        }
+        // The bodies were actually pushed apart
+        let translation = scalar_world.get::<AccumulatedTranslation>(entity1).unwrap();
+        assert!(translation.0.y < 0.0);
+    }
+}
+
+#[test]
+fn friction_models_slow_down_sliding_bodies() {
+    for friction_model in [
+        FrictionModel::SingleTangent,
+        FrictionModel::Pyramid,
+        FrictionModel::Cone,
+    ] {
+        let mut app = create_app();
+        app.insert_resource(SolverConfig {
+            friction_model,
+            ..default()
+        })
+        .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+        #[cfg(feature = "2d")]
+        let (ground_collider, box_collider, velocity) = (
+            Collider::cuboid(100.0, 1.0),
+            Collider::cuboid(1.0, 1.0),
+            Vector::X * 3.0,
+        );
+        #[cfg(feature = "3d")]
+        let (ground_collider, box_collider, velocity) = (
+            Collider::cuboid(100.0, 1.0, 100.0),
+            Collider::cuboid(1.0, 1.0, 1.0),
+            Vector::new(3.0, 0.0, 3.0),
+        );
+
+        app.world.spawn((
+            RigidBody::Static,
+            Position(Vector::NEG_Y * 0.5),
+            ground_collider,
+        ));
+        let body = app
+            .world
+            .spawn((
+                RigidBody::Dynamic,
+                Position(Vector::Y * 0.5),
+                LinearVelocity(velocity),
+                box_collider,
+                Friction::new(0.5),
+            ))
+            .id();
+
+        for _ in 0..30 {
+            app.update();
+        }
+
+        // The body slows down but keeps sliding in the same direction
+        let new_velocity = app.world.get::<LinearVelocity>(body).unwrap().0;
+        let new_velocity = new_velocity - Vector::Y * new_velocity.y;
+        assert!(new_velocity.length() < velocity.length() * 0.8);
+        assert!(new_velocity.length() > 0.1);
+        assert!(new_velocity.normalize().dot(velocity.normalize()) > 0.99);
+    }
+}
+
+#[test]
+fn heightfield_hole_wakes_up_and_drops_bodies_above_it() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    // A flat heightfield with 3 cells along each axis
+    let ground = app
+        .world
+        .spawn((
+            RigidBody::Static,
+            #[cfg(feature = "2d")]
+            Collider::heightfield(vec![0.0; 4], 4.0),
+            #[cfg(feature = "3d")]
+            Collider::heightfield(vec![vec![0.0; 4]; 4], Vector::new(4.0, 1.0, 4.0)),
+        ))
+        .id();
+
+    // One ball above the middle cell and another one above a corner cell
+    let center = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::Y * 0.25),
+            Collider::ball(0.25),
+        ))
+        .id();
+    #[cfg(feature = "2d")]
+    let corner_position = Vector::new(-1.33, 0.25);
+    #[cfg(feature = "3d")]
+    let corner_position = Vector::new(-1.33, 0.25, -1.33);
+    let corner = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(corner_position),
+            Collider::ball(0.25),
+        ))
+        .id();
+
+    for _ in 0..30 {
+        app.update();
+    }
+
+    app.world.entity_mut(center).insert(Sleeping);
+    app.world.entity_mut(corner).insert(Sleeping);
+
+    let mut collider = app.world.get_mut::<Collider>(ground).unwrap();
+    #[cfg(feature = "2d")]
+    assert!(collider.set_heightfield_hole(1, true));
+    #[cfg(feature = "3d")]
+    assert!(collider.set_heightfield_hole(1, 1, true));
+
+    for _ in 0..30 {
+        app.update();
+    }
+
+    // Only the ball above the hole is woken up, and it falls through
+    assert!(app.world.get::<Sleeping>(corner).is_some());
+    assert!(app.world.get::<Sleeping>(center).is_none());
+    assert!(app.world.get::<Position>(center).unwrap().y < -0.5);
+}
+
+#[test]
+fn math_ops_match_standard_library() {
+    for i in -20..=20 {
+        let x = i as Scalar * 0.1;
+        assert_relative_eq!(math::ops::sin(x), x.sin(), epsilon = 1e-6);
+        assert_relative_eq!(math::ops::cos(x), x.cos(), epsilon = 1e-6);
+        assert_relative_eq!(math::ops::atan(x), x.atan(), epsilon = 1e-6);
+        assert_relative_eq!(math::ops::atan2(x, 0.5), x.atan2(0.5), epsilon = 1e-6);
+        assert_relative_eq!(math::ops::exp(x), x.exp(), epsilon = 1e-5);
+        assert_relative_eq!(math::ops::asin(x / 2.0), (x / 2.0).asin(), epsilon = 1e-6);
+    }
+}
+
+#[test]
+fn collider_shape_updates_replace_shape_in_background() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+    app.insert_resource(Gravity::ZERO);
+
+    let body = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Collider::ball(0.5),
+            ColliderShapeUpdates::default(),
+        ))
+        .id();
+
+    app.update();
+    let small_mass = app.world.get::<Mass>(body).unwrap().0;
+
+    // Replace the ball with a much larger box
+    #[cfg(feature = "2d")]
+    let points = vec![
+        Vector::new(-2.0, -2.0),
+        Vector::new(2.0, -2.0),
+        Vector::new(2.0, 2.0),
+        Vector::new(-2.0, 2.0),
+    ];
+    #[cfg(feature = "3d")]
+    let points = (0..8)
+        .map(|i| {
+            let corner = Vector::new(
+                (i & 1) as Scalar,
+                ((i >> 1) & 1) as Scalar,
+                ((i >> 2) & 1) as Scalar,
+            );
+            corner * 4.0 - Vector::splat(2.0)
+        })
+        .collect::<Vec<_>>();
+    app.world
+        .get_mut::<ColliderShapeUpdates>(body)
+        .unwrap()
+        .request_convex_hull(points);
+
+    for _ in 0..1000 {
+        app.update();
+        if !app
+            .world
+            .get::<ColliderShapeUpdates>(body)
+            .unwrap()
+            .is_pending()
+        {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    app.update();
+
+    let collider = app.world.get::<Collider>(body).unwrap();
+    assert!(collider.get_shape().as_ball().is_none());
+    let large_mass = app.world.get::<Mass>(body).unwrap().0;
+    assert!(large_mass > small_mass);
+
+    // Requests that fail to compute a collider keep the shape and stop being pending
+    app.world
+        .get_mut::<ColliderShapeUpdates>(body)
+        .unwrap()
+        .request(|| None);
+
+    for _ in 0..1000 {
+        app.update();
+        if !app
+            .world
+            .get::<ColliderShapeUpdates>(body)
+            .unwrap()
+            .is_pending()
+        {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    assert!(!app
+        .world
+        .get::<ColliderShapeUpdates>(body)
+        .unwrap()
+        .is_pending());
+    assert_eq!(app.world.get::<Mass>(body).unwrap().0, large_mass);
+}
+
+#[cfg(feature = "baked-colliders")]
+#[test]
+fn baked_colliders_round_trip() {
+    #[cfg(feature = "2d")]
+    let vertices = vec![
+        Vector::new(-1.0, 0.0),
+        Vector::new(1.0, 0.0),
+        Vector::new(1.0, 2.0),
+        Vector::new(-1.0, 2.0),
+    ];
+    #[cfg(feature = "3d")]
+    let vertices = vec![
+        Vector::new(-1.0, 0.0, -1.0),
+        Vector::new(1.0, 0.0, -1.0),
+        Vector::new(1.0, 2.0, 1.0),
+        Vector::new(-1.0, 2.0, 1.0),
+    ];
+    let indices = vec![[0, 1, 2], [0, 2, 3]];
+
+    let baked = Collider::bake_trimesh(vertices.clone(), indices.clone(), TriMeshFlags::empty());
+    let collider = Collider::from_baked(&baked).unwrap();
+
+    let trimesh = collider.get_shape().as_trimesh().unwrap();
+    assert_eq!(trimesh.indices(), indices.as_slice());
+    let expected = Collider::trimesh(vertices, indices);
+    assert_eq!(
+        collider.get_shape().compute_local_aabb(),
+        expected.get_shape().compute_local_aabb()
+    );
+
+    assert!(matches!(
+        Collider::from_baked(b"not a collider"),
+        Err(BakedColliderError::InvalidHeader)
+    ));
+
+    // Other shapes can be baked too
+    let ball = Collider::from_baked(&Collider::ball(0.5).bake()).unwrap();
+    assert_eq!(ball.get_shape().as_ball().unwrap().radius, 0.5);
+}
+
+#[test]
+fn physics_in_fixed_update_steps_once_per_run() {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        PhysicsPlugins::new(FixedUpdate),
+    ));
+    #[cfg(feature = "debug-plugin")]
+    app.insert_resource(PhysicsDebugConfig {
+        enabled: false,
+        ..default()
+    });
+    app.insert_resource(FixedTime::new_from_secs(1.0 / 50.0));
+    app.insert_resource(Gravity::ZERO);
+
+    assert_eq!(
+        *app.world.resource::<PhysicsTimestep>(),
+        PhysicsTimestep::Schedule
+    );
+
+    let body = app
+        .world
+        .spawn((RigidBody::Dynamic, LinearVelocity(Vector::X)))
+        .id();
+
+    // Run the schedule once to initialize the body
+    app.world.run_schedule(FixedUpdate);
+    let start = app.world.get::<Position>(body).unwrap().x;
+
+    for _ in 0..50 {
+        app.world.run_schedule(FixedUpdate);
+    }
+
+    // Each run advances physics by the period of `FixedTime`, regardless of how much time has passed
+    assert_relative_eq!(app.world.resource::<DeltaTime>().0, 1.0 / 50.0);
+    let end = app.world.get::<Position>(body).unwrap().x;
+    assert_relative_eq!(end - start, 1.0, epsilon = 0.0001);
+}
+
+#[test]
+fn buffer_stats_track_and_shrink_collision_buffers() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO);
+    app.insert_resource(BufferShrinkPolicy {
+        min_capacity: 0,
+        ratio: 2,
+        steps: 5,
+    });
+    app.finish();
+
+    // Overlapping balls that all collide with each other
+    let balls = (0..20)
+        .map(|i| {
+            app.world
+                .spawn((
+                    RigidBody::Dynamic,
+                    Position(Vector::X * i as Scalar * 0.01),
+                    Collider::ball(1.0),
+                ))
+                .id()
+        })
+        .collect::<Vec<_>>();
+
+    tick_60_fps(&mut app);
+    tick_60_fps(&mut app);
+
+    let stats = *app.world.resource::<PhysicsBufferStats>();
+    assert!(stats.broad_collision_pairs.len > 0);
+    assert!(stats.collisions.len > 0);
+    assert!(stats.penetration_constraints.len > 0);
+    assert!(stats.broad_collision_pairs.capacity >= stats.broad_collision_pairs.len);
+
+    for ball in balls {
+        app.world.despawn(ball);
+    }
+    for _ in 0..20 {
+        tick_60_fps(&mut app);
+    }
+
+    // The lists give back their memory once they have been empty for long enough
+    let stats = *app.world.resource::<PhysicsBufferStats>();
+    assert_eq!(stats.broad_collision_pairs.len, 0);
+    assert!(stats.broad_collision_pairs.shrink_count > 0);
+    assert_eq!(
+        app.world.resource::<BroadCollisionPairs>().0.capacity(),
+        stats.broad_collision_pairs.capacity
+    );
+    assert_eq!(stats.broad_collision_pairs.capacity, 0);
+}
+
+#[test]
+fn transform_interpolation_smooths_fixed_timestep_motion() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO);
+    app.insert_resource(PhysicsTimestep::Fixed(1.0 / 50.0));
+
+    let interpolated = app
+        .world
+        .spawn((
+            TransformBundle::default(),
+            RigidBody::Dynamic,
+            LinearVelocity(Vector::X),
+            TransformInterpolation::default(),
+        ))
+        .id();
+    let not_interpolated = app
+        .world
+        .spawn((
+            TransformBundle::default(),
+            RigidBody::Dynamic,
+            LinearVelocity(Vector::X),
+        ))
+        .id();
+
+    for _ in 0..5 {
+        tick_60_fps(&mut app);
+    }
+
+    for _ in 0..20 {
+        tick_60_fps(&mut app);
+
+        // Interpolation doesn't affect the simulation
+        let position = app.world.get::<Position>(interpolated).unwrap().0;
+        assert_eq!(
+            position,
+            app.world.get::<Position>(not_interpolated).unwrap().0
+        );
+        assert_eq!(
+            app.world
+                .get::<Transform>(not_interpolated)
+                .unwrap()
+                .translation
+                .x,
+            position.x as f32
+        );
+
+        // The body is rendered between its poses at the start and end of the latest step
+        let dt = app.world.resource::<DeltaTime>().0;
+        let alpha = app.world.resource::<PhysicsLoop>().accumulator / dt;
+        let translation = app
+            .world
+            .get::<Transform>(interpolated)
+            .unwrap()
+            .translation;
+        assert_relative_eq!(
+            translation.x,
+            (position.x - (1.0 - alpha) * dt) as f32,
+            epsilon = 0.0001
+        );
+    }
+}
+
+#[test]
+fn static_bodies_are_not_written_every_frame() {
+    use crate::plugins::sync::PreviousGlobalTransform;
+
+    let mut app = create_app();
+
+    let body = app
+        .world
+        .spawn((
+            TransformBundle::default(),
+            RigidBody::Static,
+            Collider::ball(1.0),
+        ))
+        .id();
+    let child_collider = app
+        .world
+        .spawn((
+            TransformBundle::from_transform(Transform::from_xyz(2.0, 0.0, 0.0)),
+            Collider::ball(1.0),
+        ))
+        .set_parent(body)
+        .id();
+
+    for _ in 0..3 {
+        tick_60_fps(&mut app);
+    }
+
+    let last_changed = |app: &App| {
+        let body = app.world.entity(body);
+        let child_collider = app.world.entity(child_collider);
+        [
+            body.get_ref::<PreviousGlobalTransform>()
+                .unwrap()
+                .last_changed(),
+            body.get_ref::<Position>().unwrap().last_changed(),
+            child_collider.get_ref::<Position>().unwrap().last_changed(),
+            child_collider
+                .get_ref::<ColliderTransform>()
+                .unwrap()
+                .last_changed(),
+            child_collider
+                .get_ref::<ColliderAabb>()
+                .unwrap()
+                .last_changed(),
+        ]
+    };
+    let before = last_changed(&app);
+
+    for _ in 0..10 {
+        tick_60_fps(&mut app);
+    }
+
+    assert_eq!(last_changed(&app), before);
+}
+
+#[test]
+fn transform_extrapolation_predicts_from_velocity() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO);
+    app.insert_resource(PhysicsTimestep::Fixed(1.0 / 50.0));
+
+    let body = app
+        .world
+        .spawn((
+            TransformBundle::default(),
+            RigidBody::Dynamic,
+            LinearVelocity(Vector::X),
+            TransformInterpolation::extrapolate(),
+        ))
+        .id();
+
+    for _ in 0..5 {
+        tick_60_fps(&mut app);
+    }
+
+    for _ in 0..20 {
+        tick_60_fps(&mut app);
+
+        // The body is rendered ahead of its latest position by the time accumulated for the next step
+        let position = app.world.get::<Position>(body).unwrap().0;
+        let accumulator = app.world.resource::<PhysicsLoop>().accumulator;
+        let translation = app.world.get::<Transform>(body).unwrap().translation;
+        assert_relative_eq!(
+            translation.x,
+            (position.x + accumulator) as f32,
+            epsilon = 0.0001
+        );
+    }
+}
+
+#[test]
+fn transform_interpolation_is_reset_for_teleported_bodies() {
+    #[derive(Component)]
+    struct Teleported;
+
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO);
+    app.insert_resource(PhysicsTimestep::Fixed(1.0 / 50.0));
+    app.add_systems(
+        PhysicsSchedule,
+        (|mut commands: Commands, bodies: Query<Entity, With<Teleported>>| {
+            for entity in &bodies {
+                commands.add(ResetInterpolation::new(entity));
+            }
+        })
+        .in_set(PhysicsStepSet::SpatialQuery),
+    );
+
+    // Moves 2 units per step, which exceeds the teleport threshold
+    let fast = app
+        .world
+        .spawn((
+            TransformBundle::default(),
+            RigidBody::Dynamic,
+            LinearVelocity(Vector::X * 100.0),
+            TransformInterpolation::default().with_teleport_threshold(1.0),
+        ))
+        .id();
+    let slow = app
+        .world
+        .spawn((
+            TransformBundle::default(),
+            RigidBody::Dynamic,
+            LinearVelocity(Vector::X),
+            TransformInterpolation::default().with_teleport_threshold(1.0),
+        ))
+        .id();
+    let reset = app
+        .world
+        .spawn((
+            TransformBundle::default(),
+            RigidBody::Dynamic,
+            LinearVelocity(Vector::X),
+            TransformInterpolation::default(),
+            Teleported,
+        ))
+        .id();
+
+    let mut slow_interpolated = false;
+    for _ in 0..20 {
+        tick_60_fps(&mut app);
+
+        let rendered = |entity| {
+            let x = app.world.get::<Transform>(entity).unwrap().translation.x;
+            let position = app.world.get::<Position>(entity).unwrap().x as f32;
+            x == position
+        };
+        assert!(rendered(fast));
+        assert!(rendered(reset));
+        slow_interpolated |= !rendered(slow);
+    }
+    assert!(slow_interpolated);
+}
+
+#[test]
+fn physics_time_pauses_steps_and_scales_time() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO);
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    let body = app
+        .world
+        .spawn((RigidBody::Dynamic, LinearVelocity(Vector::X)))
+        .id();
+
+    tick_60_fps(&mut app);
+    let position = |app: &App| app.world.get::<Position>(body).unwrap().x;
+    let start = position(&app);
+
+    // Nothing is simulated while paused
+    app.world.resource_mut::<PhysicsTime>().pause();
+    let step_count = app.world.resource::<PhysicsTime>().step_count();
+    for _ in 0..10 {
+        tick_60_fps(&mut app);
+    }
+    assert_eq!(position(&app), start);
+    assert_eq!(app.world.resource::<PhysicsTime>().step_count(), step_count);
+
+    // Stepping runs exactly one step, also with a fixed timestep that accumulates time
+    for time_step in [
+        PhysicsTimestep::FixedOnce(1.0 / 60.0),
+        PhysicsTimestep::Fixed(1.0 / 60.0),
+    ] {
+        app.insert_resource(time_step);
+        let start = position(&app);
+        app.world.resource_mut::<PhysicsTime>().step();
+        for _ in 0..10 {
+            tick_60_fps(&mut app);
+        }
+        assert_relative_eq!(position(&app) - start, 1.0 / 60.0, epsilon = 0.00001);
+        assert_eq!(app.world.resource::<PhysicsLoop>().accumulator, 0.0);
+    }
+    assert_eq!(
+        app.world.resource::<PhysicsTime>().step_count(),
+        step_count + 2
+    );
+
+    // Half of the time is simulated in slow motion
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+    let mut physics_time = app.world.resource_mut::<PhysicsTime>();
+    physics_time.resume();
+    physics_time.time_scale = 0.5;
+    let start = position(&app);
+    let elapsed = app.world.resource::<PhysicsTime>().elapsed_seconds();
+    for _ in 0..60 {
+        tick_60_fps(&mut app);
+    }
+    assert_relative_eq!(position(&app) - start, 0.5, epsilon = 0.0001);
+    assert_relative_eq!(
+        app.world.resource::<PhysicsTime>().elapsed_seconds() - elapsed,
+        0.5,
+        epsilon = 0.0001
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_time_controls_are_forwarded_to_physics_time() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+    tick_60_fps(&mut app);
+
+    app.world.resource_mut::<PhysicsLoop>().pause();
+    tick_60_fps(&mut app);
+    assert!(app.world.resource::<PhysicsTime>().paused);
+
+    // Steps queued through the physics loop are run while paused
+    let step_count = app.world.resource::<PhysicsTime>().step_count();
+    app.world.resource_mut::<PhysicsLoop>().step();
+    tick_60_fps(&mut app);
+    tick_60_fps(&mut app);
+    assert_eq!(
+        app.world.resource::<PhysicsTime>().step_count(),
+        step_count + 1
+    );
+
+    // With an accumulating timestep, all steps queued through the physics loop are run at once
+    app.insert_resource(PhysicsTimestep::Fixed(1.0 / 60.0));
+    for _ in 0..3 {
+        app.world.resource_mut::<PhysicsLoop>().step();
+    }
+    tick_60_fps(&mut app);
+    assert_eq!(
+        app.world.resource::<PhysicsTime>().step_count(),
+        step_count + 4
+    );
+
+    // Resuming through the physics time is reflected in the physics loop
+    app.world.resource_mut::<PhysicsTime>().resume();
+    tick_60_fps(&mut app);
+    assert!(!app.world.resource::<PhysicsLoop>().paused);
+
+    app.insert_resource(PhysicsTimescale(0.5));
+    tick_60_fps(&mut app);
+    assert_eq!(app.world.resource::<PhysicsTime>().time_scale, 0.5);
+}
+
+#[test]
+fn long_frames_are_clamped_to_max_delta() {
+    fn step_count_after_long_frame(max_delta: Option<Scalar>) -> u64 {
+        let mut app = create_app();
+        app.insert_resource(PhysicsTimestep::Fixed(1.0 / 60.0));
+        app.insert_resource(PhysicsTime::default().with_max_delta(max_delta));
+        tick_60_fps(&mut app);
+
+        let step_count = app.world.resource::<PhysicsTime>().step_count();
+        let mut update_strategy = app.world.resource_mut::<TimeUpdateStrategy>();
+        let TimeUpdateStrategy::ManualInstant(prev_time) = *update_strategy else {
+            unimplemented!()
+        };
+        *update_strategy = TimeUpdateStrategy::ManualInstant(prev_time + Duration::from_secs(5));
+        app.update();
+
+        app.world.resource::<PhysicsTime>().step_count() - step_count
+    }
+
+    // A 5 second hitch only advances the simulation by the maximum delta
+    let clamped = step_count_after_long_frame(Some(0.25));
+    assert!((14..=16).contains(&clamped), "ran {clamped} steps");
+
+    // Without a limit, the simulation catches up with all of the time
+    let unclamped = step_count_after_long_frame(None);
+    assert!((299..=301).contains(&unclamped), "ran {unclamped} steps");
+}
+
+#[test]
+fn constraint_iterations_stiffen_joint_chains() {
+    fn chain_stretch(iterations: Option<ConstraintIterations>) -> Scalar {
+        let mut app = create_app();
+        app.insert_resource(SubstepCount(1));
+
+        let anchor = app.world.spawn(RigidBody::Static).id();
+        let mut previous_link = anchor;
+        for i in 1..=10 {
+            let link = app
+                .world
+                .spawn((
+                    RigidBody::Dynamic,
+                    Position(Vector::NEG_Y * i as Scalar),
+                    Collider::ball(0.25),
+                ))
+                .id();
+            let mut joint = app.world.spawn(
+                DistanceJoint::new(previous_link, link)
+                    .with_rest_length(1.0)
+                    .with_limits(1.0, 1.0),
+            );
+            if let Some(iterations) = iterations {
+                joint.insert(iterations);
+            }
+            previous_link = link;
+        }
+
+        for _ in 0..60 {
+            tick_60_fps(&mut app);
+        }
+
+        let end = app.world.get::<Position>(previous_link).unwrap().0;
+        let anchor = app.world.get::<Position>(anchor).unwrap().0;
+        end.distance(anchor) - 10.0
+    }
+
+    let stretch = chain_stretch(None);
+    let stiff_stretch = chain_stretch(Some(ConstraintIterations(8)));
+    assert!(
+        stiff_stretch < stretch,
+        "chain stretched by {stiff_stretch} with 8 iterations and {stretch} with 1"
+    );
+}
+
+#[test]
+fn deterministic_mode_orders_islands_and_contacts() {
+    use crate::{plugins::solver::PenetrationConstraints, utils::entity_sort_key};
+
+    #[derive(Component)]
+    struct Marker;
+
+    /// The pairs of bodies of the penetration constraints of each island, in the order they were solved.
+    #[derive(Resource, Default)]
+    struct SolveOrder(Vec<Vec<Vec<(Entity, Entity)>>>);
+
+    fn record_solve_order(
+        constraints: Res<PenetrationConstraints>,
+        islands: Res<SimulationIslands>,
+        mut order: ResMut<SolveOrder>,
+    ) {
+        let mut island_order = vec![vec![]; islands.len()];
+        for constraint in constraints.0.iter() {
+            let [entity1, entity2] = constraint.entities();
+            if let Some(island) = islands
+                .island_of(entity1)
+                .or_else(|| islands.island_of(entity2))
+            {
+                island_order[island].push((entity1, entity2));
+            }
+        }
+        order.0.push(island_order);
+    }
+
+    // Simulates the same piles of boxes, but with a marker on some of them,
+    // which changes the order in which the boxes are iterated over
+    let run_piles = |marked: bool| {
+        let mut app = create_app();
+        app.insert_resource(DeterministicMode(true))
+            .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0))
+            .init_resource::<SolveOrder>();
+        app.get_schedule_mut(SubstepSchedule).unwrap().add_systems(
+            record_solve_order
+                .after(SubstepSet::SolveUserConstraints)
+                .before(SubstepSet::UpdateVelocities),
+        );
+
+        #[cfg(feature = "2d")]
+        let (ground_collider, box_collider) =
+            (Collider::cuboid(40.0, 1.0), Collider::cuboid(1.0, 1.0));
+        #[cfg(feature = "3d")]
+        let (ground_collider, box_collider) = (
+            Collider::cuboid(40.0, 1.0, 40.0),
+            Collider::cuboid(1.0, 1.0, 1.0),
+        );
+
+        app.world.spawn((
+            RigidBody::Static,
+            Position(Vector::NEG_Y * 0.5),
+            ground_collider,
+        ));
+
+        // Three separate piles, each of which is its own island
+        let mut boxes = vec![];
+        for i in 0..18 {
+            let pile = (i % 3) as Scalar * 10.0 - 10.0;
+            let position = Vector::X * (pile + (i % 2) as Scalar * 0.4)
+                + Vector::Y * (1.0 + (i / 3) as Scalar * 1.1);
+            // Sleeping bodies would split the islands
+            let mut entity = app.world.spawn((
+                RigidBody::Dynamic,
+                Position(position),
+                box_collider.clone(),
+                SleepingDisabled,
+            ));
+            if marked && i % 4 < 2 {
+                entity.insert(Marker);
+            }
+            boxes.push(entity.id());
+        }
+
+        for _ in 0..60 {
+            app.update();
+        }
+
+        let islands = app
+            .world
+            .resource::<SimulationIslands>()
+            .iter()
+            .map(|bodies| bodies.to_vec())
+            .collect::<Vec<_>>();
+        let poses = boxes
+            .into_iter()
+            .map(|entity| {
+                let position = app.world.get::<Position>(entity).unwrap().0;
+                let rotation = *app.world.get::<Rotation>(entity).unwrap();
+                (entity, position, rotation)
+            })
+            .collect::<Vec<_>>();
+        let order = std::mem::take(&mut app.world.resource_mut::<SolveOrder>().0);
+        (islands, order, poses)
+    };
+
+    let (islands, order, poses) = run_piles(false);
+    let (marked_islands, marked_order, marked_poses) = run_piles(true);
+
+    // The piles are separate islands whose bodies are ordered by their entities
+    assert_eq!(islands.len(), 3);
+    for bodies in &islands {
+        assert!(bodies
+            .windows(2)
+            .all(|pair| entity_sort_key(pair[0]) < entity_sort_key(pair[1])));
+    }
+    assert_eq!(islands, marked_islands);
+
+    // The contacts of each island are solved in the same order, producing identical results
+    assert!(order.iter().flatten().any(|pairs| !pairs.is_empty()));
+    assert_eq!(order, marked_order);
+    assert_eq!(poses, marked_poses);
+}
+
+#[test]
+fn weld_merging_merges_and_splits_welded_bodies() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO)
+        .insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0))
+        .insert_resource(WeldMerging);
+
+    #[cfg(feature = "2d")]
+    let collider = Collider::cuboid(1.0, 1.0);
+    #[cfg(feature = "3d")]
+    let collider = Collider::cuboid(1.0, 1.0, 1.0);
+
+    let body1 = app
+        .world
+        .spawn((RigidBody::Dynamic, Position(Vector::ZERO), collider.clone()))
+        .id();
+    let body2 = app
+        .world
+        .spawn((RigidBody::Dynamic, Position(Vector::X), collider))
+        .id();
+    let joint = app
+        .world
+        .spawn(
+            FixedJoint::new(body1, body2)
+                .with_local_anchor_1(Vector::X * 0.5)
+                .with_local_anchor_2(Vector::NEG_X * 0.5),
+        )
+        .id();
+
+    tick_60_fps(&mut app);
+    let single_mass = app.world.get::<Mass>(body2).unwrap().0;
+    tick_60_fps(&mut app);
+
+    // The second body is merged into the first one, which has the combined mass
+    assert!(app.world.get::<RigidBody>(body2).is_none());
+    assert_eq!(app.world.get::<WeldedBody>(body2).unwrap().root, body1);
+    assert_relative_eq!(app.world.get::<Mass>(body1).unwrap().0, 2.0 * single_mass);
+    assert_relative_eq!(
+        app.world.get::<CenterOfMass>(body1).unwrap().0,
+        Vector::X * 0.5,
+        epsilon = 0.0001
+    );
+
+    // The merged body follows the root
+    app.world.get_mut::<LinearVelocity>(body1).unwrap().0 = Vector::Y;
+    for _ in 0..60 {
+        tick_60_fps(&mut app);
+    }
+    let position1 = app.world.get::<Position>(body1).unwrap().0;
+    let position2 = app.world.get::<Position>(body2).unwrap().0;
+    assert_relative_eq!(position1, Vector::Y, epsilon = 0.001);
+    assert_relative_eq!(position2 - position1, Vector::X, epsilon = 0.0001);
+
+    // Removing the joint splits the bodies again
+    app.world.despawn(joint);
+    tick_60_fps(&mut app);
+    tick_60_fps(&mut app);
+    assert!(app.world.get::<WeldedBody>(body2).is_none());
+    assert_eq!(app.world.get::<RigidBody>(body2), Some(&RigidBody::Dynamic));
+    assert_relative_eq!(app.world.get::<Mass>(body1).unwrap().0, single_mass);
+    assert_relative_eq!(
+        app.world.get::<LinearVelocity>(body2).unwrap().0,
+        Vector::Y,
+        epsilon = 0.0001
+    );
+}
+
+#[test]
+fn physics_snapshot_restores_dynamic_state() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    let body1 = app
+        .world
+        .spawn((RigidBody::Dynamic, Position::default(), Collider::ball(0.5)))
+        .id();
+    let body2 = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::X * 2.0),
+            LinearVelocity(Vector::Y),
+            Collider::ball(0.5),
+        ))
+        .id();
+    let joint = app
+        .world
+        .spawn(DistanceJoint::new(body1, body2).with_rest_length(2.0))
+        .id();
+
+    tick_60_fps(&mut app);
+
+    let snapshot = PhysicsSnapshot::take(&mut app.world);
+    assert_eq!(snapshot.bodies.len(), 2);
+    assert_eq!(snapshot.joints.len(), 1);
+    assert_eq!(snapshot.joints[0].entity, joint);
+
+    // The snapshot round-trips through bytes
+    let bytes = snapshot.to_bytes();
+    assert_eq!(PhysicsSnapshot::from_bytes(&bytes), Some(snapshot.clone()));
+    assert_eq!(PhysicsSnapshot::from_bytes(&bytes[..bytes.len() - 1]), None);
+
+    let positions =
+        |app: &App| [body1, body2].map(|entity| app.world.get::<Position>(entity).unwrap().0);
+
+    for _ in 0..30 {
+        tick_60_fps(&mut app);
+    }
+    let expected = positions(&app);
+
+    // Restoring the snapshot and simulating again gives the same result
+    PhysicsSnapshot::from_bytes(&bytes)
+        .unwrap()
+        .restore(&mut app.world);
+    assert_eq!(
+        app.world.get::<Position>(body2).unwrap().0,
+        snapshot.bodies[1].state.position.0
+    );
+    for _ in 0..30 {
+        tick_60_fps(&mut app);
+    }
+    for (position, expected) in positions(&app).into_iter().zip(expected) {
+        assert_relative_eq!(position, expected, epsilon = 0.0001);
+    }
+}
+
+#[test]
+fn physics_rollback_restores_internal_state() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0))
+        .insert_resource(PhysicsRollback::default());
+
+    app.world.spawn((
+        RigidBody::Static,
+        Position(Vector::NEG_Y),
+        Collider::ball(0.5),
+    ));
+    let ball = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::X * 0.2),
+            Collider::ball(0.5),
+        ))
+        .id();
+
+    for _ in 0..10 {
+        tick_60_fps(&mut app);
+    }
+    assert!(app.world.resource::<DeterministicMode>().0);
+
+    // Save the state like a rollback library would
+    let snapshot = PhysicsSnapshot::take(&mut app.world);
+    let frame = *app.world.resource::<PhysicsRollbackFrame>();
+    assert_eq!(frame.0, 10);
+
+    for _ in 0..5 {
+        tick_60_fps(&mut app);
+    }
+    let expected_position = app.world.get::<Position>(ball).unwrap().0;
+    let expected_collisions = app.world.resource::<Collisions>().clone();
+    assert!(!expected_collisions.get_internal().is_empty());
+
+    // Roll back and simulate the same frames again
+    snapshot.restore(&mut app.world);
+    *app.world.resource_mut::<PhysicsRollbackFrame>() = frame;
+    app.world
+        .resource_mut::<Collisions>()
+        .get_internal_mut()
+        .clear();
+    for _ in 0..5 {
+        tick_60_fps(&mut app);
+    }
+
+    assert_eq!(
+        app.world.get::<Position>(ball).unwrap().0,
+        expected_position
+    );
+    assert_eq!(*app.world.resource::<Collisions>(), expected_collisions);
+}
+
+#[test]
+fn joint_assemblies_sleep_and_wake_together() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO);
+
+    let chassis = app
+        .world
+        .spawn((RigidBody::Dynamic, Position::default(), Collider::ball(0.5)))
+        .id();
+    let wheel = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::X * 2.0),
+            AngularVelocity::ZERO,
+            Collider::ball(0.5),
+        ))
+        .id();
+    let joint = app
+        .world
+        .spawn(DistanceJoint::new(chassis, wheel).with_rest_length(2.0))
+        .id();
+
+    // A spinning wheel keeps the whole assembly awake
+    #[cfg(feature = "2d")]
+    let spin = AngularVelocity(5.0);
+    #[cfg(feature = "3d")]
+    let spin = AngularVelocity(Vector::Z * 5.0);
+    for _ in 0..120 {
+        *app.world.get_mut::<AngularVelocity>(wheel).unwrap() = spin;
+        tick_60_fps(&mut app);
+    }
+    assert!(app.world.get::<Sleeping>(chassis).is_none());
+    let assembly = app.world.get::<AssemblySleeping>(chassis).unwrap();
+    assert!(!assembly.is_sleeping());
+    assert_eq!(assembly.bodies(), &[chassis, wheel]);
+    assert!(app.world.get::<AssemblySleeping>(wheel).is_none());
+
+    // Once the wheel stops, the assembly falls asleep as a whole
+    *app.world.get_mut::<AngularVelocity>(wheel).unwrap() = AngularVelocity::ZERO;
+    for _ in 0..120 {
+        tick_60_fps(&mut app);
+    }
+    assert!(app.world.get::<Sleeping>(chassis).is_some());
+    assert!(app.world.get::<Sleeping>(wheel).is_some());
+    assert!(app
+        .world
+        .get::<AssemblySleeping>(chassis)
+        .unwrap()
+        .is_sleeping());
+
+    // Touching the wheel wakes up the chassis
+    *app.world.get_mut::<AngularVelocity>(wheel).unwrap() = spin;
+    tick_60_fps(&mut app);
+    tick_60_fps(&mut app);
+    assert!(app.world.get::<Sleeping>(chassis).is_none());
+    assert!(!app
+        .world
+        .get::<AssemblySleeping>(chassis)
+        .unwrap()
+        .is_sleeping());
+
+    // Removing the joint breaks up the assembly
+    app.world.despawn(joint);
+    tick_60_fps(&mut app);
+    assert!(app.world.get::<AssemblySleeping>(chassis).is_none());
+}
+
+#[test]
+fn bodies_in_inactive_rooms_are_frozen() {
+    let mut app = create_app();
+
+    let mut rooms = PhysicsRooms::default();
+    let room = rooms.add_room(Vector::splat(-10.0), Vector::splat(10.0));
+    let other_room = rooms.add_room(Vector::splat(20.0), Vector::splat(30.0));
+    app.insert_resource(rooms);
+
+    // A falling ball that overlaps a static body
+    app.world.spawn((
+        RigidBody::Static,
+        Position(Vector::Y * 5.0 + Vector::X),
+        Collider::ball(1.0),
+    ));
+    let ball = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::Y * 5.0),
+            LinearVelocity(Vector::NEG_Y),
+            Collider::ball(0.5),
+        ))
+        .id();
+
+    tick_60_fps(&mut app);
+    assert!(app.world.get::<FrozenInRoom>(ball).is_none());
+    assert!(!app.world.resource::<BroadCollisionPairs>().0.is_empty());
+
+    app.world
+        .resource_mut::<PhysicsRooms>()
+        .activate_only(other_room);
+    tick_60_fps(&mut app);
+
+    let frozen = *app.world.get::<FrozenInRoom>(ball).unwrap();
+    assert_eq!(frozen.room(), room);
+    let position = *app.world.get::<Position>(ball).unwrap();
+    let velocity = *app.world.get::<LinearVelocity>(ball).unwrap();
+
+    // Frozen bodies are not simulated and are removed from the broad phase
+    for _ in 0..30 {
+        tick_60_fps(&mut app);
+    }
+    assert_eq!(*app.world.get::<Position>(ball).unwrap(), position);
+    assert_eq!(*app.world.get::<LinearVelocity>(ball).unwrap(), velocity);
+    assert!(app.world.resource::<BroadCollisionPairs>().0.is_empty());
+
+    // Changing gravity doesn't wake up frozen bodies
+    app.insert_resource(Gravity(Vector::NEG_Y * 20.0));
+    tick_60_fps(&mut app);
+    assert_eq!(*app.world.get::<Position>(ball).unwrap(), position);
+
+    // Activating the room continues the simulation where it left off
+    app.world.resource_mut::<PhysicsRooms>().activate(room);
+    tick_60_fps(&mut app);
+    assert!(app.world.get::<FrozenInRoom>(ball).is_none());
+    assert!(app.world.get::<Sleeping>(ball).is_none());
+    assert!(app.world.get::<Position>(ball).unwrap().y < position.y);
+}
+
+#[cfg(feature = "2d")]
+#[test]
+fn screen_wrap_moves_bodies_across_edges() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO);
+
+    let wrap = ScreenWrap::new(Vector::splat(-10.0), Vector::splat(10.0)).with_seam_colliders(true);
+    let ball = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::X * 9.5),
+            Collider::ball(1.0),
+            TransformInterpolation::default(),
+            wrap,
+        ))
+        .id();
+
+    tick_60_fps(&mut app);
+
+    // The ball sticks out of the right edge, so a seam collider is placed at the left edge
+    let mut seams = app.world.query::<(&SeamCollider, &Parent)>();
+    let offsets = seams
+        .iter(&app.world)
+        .map(|(seam, parent)| (seam.offset(), parent.get()))
+        .collect::<Vec<_>>();
+    assert_eq!(offsets, vec![(Vector::NEG_X * 20.0, ball)]);
+
+    app.world.get_mut::<LinearVelocity>(ball).unwrap().0 = Vector::X * 60.0;
+    tick_60_fps(&mut app);
+    let position = app.world.get::<Position>(ball).unwrap().0;
+    assert_relative_eq!(position.x, -9.5, epsilon = 0.001);
+
+    let events = app.world.resource::<Events<ScreenWrapped>>();
+    let wrapped = events.iter_current_update_events().next().unwrap();
+    assert_eq!(wrapped.entity, ball);
+    assert_relative_eq!(wrapped.offset, Vector::NEG_X * 20.0);
+
+    // The AABB is moved with the body
+    let aabb = app.world.get::<ColliderAabb>(ball).unwrap();
+    assert!(aabb.mins.x < -10.0 && aabb.maxs.x < 0.0);
+
+    // The seam collider is now needed on the other side
+    let offsets = seams
+        .iter(&app.world)
+        .map(|(seam, _)| seam.offset())
+        .collect::<Vec<_>>();
+    assert_eq!(offsets, vec![Vector::X * 20.0]);
+}
+
+#[cfg(feature = "spatial-query")]
+#[test]
+fn spring_arm_retracts_in_front_of_obstacles() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO);
+
+    let target = app
+        .world
+        .spawn((RigidBody::Static, Position::default(), Collider::ball(0.5)))
+        .id();
+    let wall = app
+        .world
+        .spawn((
+            RigidBody::Static,
+            Position(Vector::X * 5.0),
+            Collider::ball(1.0),
+        ))
+        .id();
+    let camera = app
+        .world
+        .spawn((
+            TransformBundle::default(),
+            SpringArm::new(target, Vector::X * 10.0, Collider::ball(0.25))
+                .with_margin(0.25)
+                .with_smoothing(10.0),
+        ))
+        .id();
+
+    tick_60_fps(&mut app);
+
+    // The target's own collider is ignored, and the arm stops in front of the wall
+    let spring_arm = app.world.get::<SpringArm>(camera).unwrap();
+    assert_relative_eq!(spring_arm.length(), 3.5, epsilon = 0.01);
+    assert!(spring_arm.is_retracted());
+    let translation = app.world.get::<Transform>(camera).unwrap().translation;
+    assert_relative_eq!(translation.x, 3.5, epsilon = 0.01);
+
+    // Without the wall, the arm extends back smoothly
+    app.world.despawn(wall);
+    tick_60_fps(&mut app);
+
+    let length = app.world.get::<SpringArm>(camera).unwrap().length();
+    assert!(length > 3.5 && length < 10.0);
+
+    app.world.get_mut::<SpringArm>(camera).unwrap().smoothing = 0.0;
+    tick_60_fps(&mut app);
+
+    let spring_arm = app.world.get::<SpringArm>(camera).unwrap();
+    assert_relative_eq!(spring_arm.position(), Vector::X * 10.0, epsilon = 0.01);
+    assert!(!spring_arm.is_retracted());
+}
+
+#[cfg(feature = "spatial-query")]
+#[test]
+fn spring_arm_follows_interpolated_target() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO);
+    app.insert_resource(PhysicsTimestep::Fixed(1.0 / 50.0));
+
+    let target = app
+        .world
+        .spawn((
+            TransformBundle::default(),
+            RigidBody::Dynamic,
+            Collider::ball(0.5),
+            LinearVelocity(Vector::X),
+            TransformInterpolation::default(),
+        ))
+        .id();
+    let camera = app
+        .world
+        .spawn((
+            TransformBundle::default(),
+            SpringArm::new(target, Vector::Y * 10.0, Collider::ball(0.25)),
+        ))
+        .id();
+
+    for _ in 0..20 {
+        tick_60_fps(&mut app);
+
+        // The arm follows the rendered pose of the target, which lags behind its position
+        let target_translation = app.world.get::<Transform>(target).unwrap().translation;
+        let camera_translation = app.world.get::<Transform>(camera).unwrap().translation;
+        assert_relative_eq!(camera_translation.x, target_translation.x, epsilon = 0.0001);
+    }
+
+    let position = app.world.get::<Position>(target).unwrap().0;
+    let camera_translation = app.world.get::<Transform>(camera).unwrap().translation;
+    assert!((camera_translation.x as Scalar) < position.x);
+}
+
+#[test]
+fn crowd_agents_push_each_other_apart() {
+    let mut app = create_app();
+    app.insert_resource(Gravity::ZERO);
+
+    let agent1 = app
+        .world
+        .spawn((
+            RigidBody::Kinematic,
+            Position::default(),
+            CrowdAgent::new(0.5),
+        ))
+        .id();
+    let agent2 = app
+        .world
+        .spawn((
+            RigidBody::Kinematic,
+            Position(Vector::X * 0.5),
+            CrowdAgent::new(0.5),
+        ))
+        .id();
+    // A heavy agent that is barely moved by the others
+    let heavy = app
+        .world
+        .spawn((
+            RigidBody::Kinematic,
+            Position(Vector::X * 10.0),
+            CrowdAgent::new(0.5).with_weight(100.0),
+        ))
+        .id();
+    let light = app
+        .world
+        .spawn((
+            RigidBody::Kinematic,
+            Position(Vector::X * 10.5),
+            CrowdAgent::new(0.5),
+        ))
+        .id();
+
+    for _ in 0..30 {
+        tick_60_fps(&mut app);
+    }
+
+    let position = |entity: Entity| app.world.get::<Position>(entity).unwrap().0;
+    assert_relative_eq!(
+        position(agent1).distance(position(agent2)),
+        1.0,
+        epsilon = 0.01
+    );
+    // The agents are pushed apart symmetrically
+    assert_relative_eq!(
+        position(agent1).x + position(agent2).x,
+        0.5,
+        epsilon = 0.001
+    );
+    assert_relative_eq!(
+        position(light).distance(position(heavy)),
+        1.0,
+        epsilon = 0.01
+    );
+    assert!(position(heavy).x > 9.99);
+
+    // The pushback doesn't change the velocities of kinematic agents
+    assert_eq!(
+        app.world.get::<LinearVelocity>(agent1).unwrap().0,
+        Vector::ZERO
+    );
+}
+
+#[test]
+fn resting_sticky_contacts_are_welded_until_overloaded() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0));
+
+    #[cfg(feature = "2d")]
+    let box_collider = Collider::cuboid(1.0, 1.0);
+    #[cfg(feature = "3d")]
+    let box_collider = Collider::cuboid(1.0, 1.0, 1.0);
+
+    let sticky = StickyContact::default()
+        .with_min_age(30)
+        .with_max_slip_speed(0.1);
+    let ground = spawn_ground(&mut app);
+    app.world.entity_mut(ground).insert(sticky);
+    let body = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            box_collider,
+            Position(Vector::Y * 0.5),
+            sticky,
+        ))
+        .id();
+
+    for _ in 0..30 {
+        app.update();
+    }
+
+    let mut welds = app
+        .world
+        .query_filtered::<(Entity, &FixedJoint), With<StickyWeld>>();
+    let (weld_entity, weld) = welds.single(&app.world);
+    assert_eq!(
+        bevy::utils::HashSet::from([weld.entity1, weld.entity2]),
+        bevy::utils::HashSet::from([ground, body])
+    );
+
+    // Yanking the box overloads the weld
+    app.world
+        .get_mut::<StickyWeld>(weld_entity)
+        .unwrap()
+        .break_force = 1.0;
+    app.world.get_mut::<LinearVelocity>(body).unwrap().0 = Vector::Y * 10.0;
+    app.update();
+
+    assert!(welds.iter(&app.world).next().is_none());
+    let events = app.world.resource::<Events<StickyWeldBroken>>();
+    assert_eq!(events.iter_current_update_events().count(), 1);
+}
+
+#[test]
+fn broad_phase_dominant_axis_has_largest_spread() {
+    use crate::plugins::broad_phase::dominant_axis;
+
+    let aabb_at = |center: Vector| {
+        let mut aabb = ColliderAabb::default();
+        aabb.mins.coords = (center - Vector::splat(0.5)).into();
+        aabb.maxs.coords = (center + Vector::splat(0.5)).into();
+        aabb
+    };
+
+    // A column of AABBs along the y-axis
+    let column = (0..10)
+        .map(|i| aabb_at(Vector::Y * i as Scalar + Vector::X * (i % 2) as Scalar))
+        .collect::<Vec<_>>();
+    assert_eq!(dominant_axis(column.iter(), 0), 1);
+
+    // Equal variances in a grid don't change the current axis
+    let grid = (0..9)
+        .map(|i| aabb_at(Vector::X * (i % 3) as Scalar + Vector::Y * (i / 3) as Scalar))
+        .collect::<Vec<_>>();
+    assert_eq!(dominant_axis(grid.iter(), 0), 0);
+    assert_eq!(dominant_axis(grid.iter(), 1), 1);
+
+    // A single AABB keeps the current axis
+    assert_eq!(dominant_axis([aabb_at(Vector::ZERO)].iter(), 1), 1);
+}
+
+#[cfg(feature = "debug-plugin")]
+#[test]
+fn constraint_debugger_steps_through_records() {
+    let mut app = create_app();
+    app.insert_resource(PhysicsTimestep::FixedOnce(1.0 / 60.0))
+        .insert_resource(SubstepCount(4));
+
+    let anchor = app.world.spawn(RigidBody::Static).id();
+    let body = app
+        .world
+        .spawn((
+            RigidBody::Dynamic,
+            Position(Vector::X * 2.0),
+            MassPropertiesBundle::new_computed(&Collider::ball(0.5), 1.0),
+        ))
+        .id();
+    let joint = app
+        .world
+        .spawn((
+            DistanceJoint::new(anchor, body).with_rest_length(1.0),
+            DebugConstraint,
+        ))
+        .id();
+    // A joint that isn't marked for debugging isn't recorded
+    app.world
+        .spawn(DistanceJoint::new(anchor, body).with_rest_length(1.0));
+
+    app.world.resource_mut::<ConstraintDebugger>().recording = true;
+    app.update();
+    app.update();
+
+    let debugger = app.world.resource::<ConstraintDebugger>();
+    let records = debugger.records();
+    assert_eq!(records.len(), 8);
+    for (i, record) in records.iter().enumerate() {
+        assert_eq!(record.constraint, joint);
+        assert_eq!(record.bodies, [anchor, body]);
+        assert_eq!(record.substep, i + 1);
+    }
+    // The joint pulls the body towards the anchor
+    assert!(records[0].corrections()[1].x < 0.0);
+
+    // Stop recording and step through the records
+    let mut debugger = app.world.resource_mut::<ConstraintDebugger>();
+    debugger.recording = false;
+    assert!(debugger.current().is_none());
+
+    debugger.step_backward();
+    assert_eq!(debugger.replay_index, Some(7));
+    debugger.step_backward();
+    debugger.step_backward();
+    assert_eq!(debugger.current().unwrap().substep, 6);
+
+    debugger.step_forward();
+    assert_eq!(debugger.current().unwrap().substep, 7);
+    for _ in 0..3 {
+        debugger.step_forward();
+    }
+    assert_eq!(debugger.replay_index, Some(7));
+
+    // No new records are added while not recording
+    app.update();
+    let mut debugger = app.world.resource_mut::<ConstraintDebugger>();
+    assert_eq!(debugger.records().len(), 8);
+
+    debugger.clear();
+    assert!(debugger.records().is_empty());
+    assert!(debugger.current().is_none());
 }
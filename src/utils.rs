@@ -47,23 +47,80 @@ pub(crate) fn compute_dynamic_friction(
     -(coefficient * normal_impulse.abs()).min(tangent_speed / generalized_inv_mass_sum)
 }
 
+/// Computes the angular impulse caused by rolling friction, clamped to never exceed
+/// the relative angular speed of the bodies.
+#[cfg(feature = "2d")]
+pub(crate) fn compute_rolling_friction(
+    relative_ang_vel: Scalar,
+    _normal: Vector,
+    inv_inertia1: Scalar,
+    inv_inertia2: Scalar,
+    coefficient: Scalar,
+    normal_lagrange: Scalar,
+    sub_dt: Scalar,
+) -> Scalar {
+    let inv_inertia_sum = inv_inertia1 + inv_inertia2;
+    if relative_ang_vel.abs() <= Scalar::EPSILON || inv_inertia_sum <= Scalar::EPSILON {
+        return 0.0;
+    }
+
+    let normal_impulse = normal_lagrange / sub_dt;
+    let magnitude =
+        (coefficient * normal_impulse.abs()).min(relative_ang_vel.abs() / inv_inertia_sum);
+    -relative_ang_vel.signum() * magnitude
+}
+
+/// Computes the angular impulse caused by rolling friction, clamped to never exceed
+/// the relative angular speed of the bodies.
+///
+/// Spinning around the contact normal is not affected.
+#[cfg(feature = "3d")]
+pub(crate) fn compute_rolling_friction(
+    relative_ang_vel: Vector,
+    normal: Vector,
+    inv_inertia1: Matrix3,
+    inv_inertia2: Matrix3,
+    coefficient: Scalar,
+    normal_lagrange: Scalar,
+    sub_dt: Scalar,
+) -> Vector {
+    let rolling_vel = relative_ang_vel - normal * normal.dot(relative_ang_vel);
+    let rolling_speed = rolling_vel.length();
+    if rolling_speed <= Scalar::EPSILON {
+        return Vector::ZERO;
+    }
+
+    let axis = rolling_vel / rolling_speed;
+    let inv_inertia_sum = axis.dot(inv_inertia1 * axis) + axis.dot(inv_inertia2 * axis);
+    if inv_inertia_sum <= Scalar::EPSILON {
+        return Vector::ZERO;
+    }
+
+    let normal_impulse = normal_lagrange / sub_dt;
+    let magnitude = (coefficient * normal_impulse.abs()).min(rolling_speed / inv_inertia_sum);
+    -axis * magnitude
+}
+
 /// Computes the speed correction caused by restitution.
+///
+/// Restitution is ignored if the bodies approach each other slower than the given threshold,
+/// so that slightly bouncy bodies come to rest instead of bouncing indefinitely.
 pub(crate) fn compute_restitution(
     normal_speed: Scalar,
     pre_solve_normal_speed: Scalar,
-    coefficient: Scalar,
-    _gravity: Vector,
-    _sub_dt: Scalar,
+    mut coefficient: Scalar,
+    threshold: Scalar,
 ) -> Scalar {
-    // TODO: The XPBD paper has this, but it seems to be prevent bounces in cases
-    // where bodies should clearly be bouncing.
-    // Maybe change the threshold to be even lower? Or is this even needed at all?
-    /*
-    // If normal velocity is small enough, use restitution of 0 to avoid jittering
-    if normal_speed.abs() <= 2.0 * gravity.length() * sub_dt {
+    if pre_solve_normal_speed.abs() <= threshold {
         coefficient = 0.0;
     }
-    */
 
     -normal_speed + (-coefficient * pre_solve_normal_speed).min(0.0)
 }
+
+/// Returns a key for sorting entities in a stable order, consisting of the index and generation of the entity.
+///
+/// See [`DeterministicMode`].
+pub(crate) fn entity_sort_key(entity: bevy::prelude::Entity) -> (u32, u32) {
+    (entity.index(), entity.generation())
+}